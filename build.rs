@@ -1,6 +1,14 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT license.
 
+use std::path::PathBuf;
+
 fn main() {
     built::write_built_file().expect("Failed to acquire build-time information");
+
+    // Assemble the component bundled for `wassette selftest`
+    println!("cargo:rerun-if-changed=src/selftest.wat");
+    let wasm = wat::parse_file("src/selftest.wat").expect("Failed to assemble src/selftest.wat");
+    let out_dir = PathBuf::from(std::env::var_os("OUT_DIR").expect("OUT_DIR is not set"));
+    std::fs::write(out_dir.join("selftest.wasm"), wasm).expect("Failed to write selftest.wasm");
 }
@@ -3,6 +3,8 @@
 
 #![doc = include_str!("../README.md")]
 
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Map, Value};
 use thiserror::Error;
@@ -45,6 +47,11 @@ pub enum ValError {
     #[error("cannot interpret number as i64 or f64: {0}")]
     NumberError(String),
 
+    /// A number given for an integer type was written with a fraction or exponent. Such a
+    /// number is a float whose value may already have been rounded.
+    #[error("expected an integer, got {0}; write integers without a fraction or exponent")]
+    NotAnInteger(String),
+
     /// A character field was invalid, for example an empty or multi-character string
     /// when you expected a single char.
     #[error("invalid char: {0}")]
@@ -251,19 +258,20 @@ pub fn component_exports_to_json_schema_with_docs(
     json!({ "tools": tools.into_iter().map(|t| t.schema).collect::<Vec<_>>() })
 }
 
-/// Converts a slice of component model [`Val`] objects into a JSON representation.
-pub fn vals_to_json(vals: &[Val]) -> Value {
+/// Converts a slice of component model [`Val`] objects, the results of a function returning
+/// `types`, into a JSON representation.
+pub fn vals_to_json(vals: &[Val], types: &[Type]) -> Value {
     match vals.len() {
         0 => Value::Null,
         1 => {
             let mut wrapper = Map::new();
-            wrapper.insert("result".to_string(), val_to_json(&vals[0]));
+            wrapper.insert("result".to_string(), val_to_json(&vals[0], types.first()));
             Value::Object(wrapper)
         }
         _ => {
             let mut tuple_map = Map::new();
             for (i, v) in vals.iter().enumerate() {
-                tuple_map.insert(format!("val{i}"), val_to_json(v));
+                tuple_map.insert(format!("val{i}"), val_to_json(v, types.get(i)));
             }
 
             let mut wrapper = Map::new();
@@ -300,6 +308,20 @@ pub fn create_placeholder_results(results: &[Type]) -> Vec<Val> {
     results.iter().map(default_val_for_type).collect()
 }
 
+/// Schema of a `list<u8>` value: a base64 string. See [`is_binary_schema`].
+fn binary_schema() -> Value {
+    json!({
+        "type": "string",
+        "contentEncoding": "base64",
+        "description": "Binary data, base64-encoded"
+    })
+}
+
+/// Whether `schema` describes binary data (a `list<u8>`) carried as a base64 string
+pub fn is_binary_schema(schema: &Value) -> bool {
+    schema.get("contentEncoding").and_then(Value::as_str) == Some("base64")
+}
+
 fn type_to_json_schema(t: &Type) -> Value {
     match t {
         Type::Bool => json!({ "type": "boolean" }),
@@ -319,6 +341,9 @@ fn type_to_json_schema(t: &Type) -> Value {
         }),
         Type::String => json!({ "type": "string" }),
 
+        // `list<u8>` is binary data, exchanged as a base64 string
+        Type::List(list_handle) if matches!(list_handle.ty(), Type::U8) => binary_schema(),
+
         // represent a `list<T>` as an array with items = schema-of-T
         Type::List(list_handle) => {
             let elem_schema = type_to_json_schema(&list_handle.ty());
@@ -650,7 +675,9 @@ fn gather_exported_functions_with_metadata_internal(
     }
 }
 
-fn val_to_json(val: &Val) -> Value {
+/// Converts `val` to JSON. With its WIT type `ty` known, a `list<u8>` is encoded as a base64
+/// string, empty or not; the value alone cannot tell it apart from other lists.
+fn val_to_json(val: &Val, ty: Option<&Type>) -> Value {
     match val {
         Val::Bool(b) => Value::Bool(*b),
         Val::S8(n) => Value::Number((*n as i64).into()),
@@ -670,46 +697,107 @@ fn val_to_json(val: &Val) -> Value {
         Val::Char(c) => Value::String(c.to_string()),
         Val::String(s) => Value::String(s.clone()),
 
-        Val::List(list) => Value::Array(list.iter().map(val_to_json).collect()),
+        Val::List(list) => {
+            let item_ty = match ty {
+                Some(Type::List(list_handle)) => Some(list_handle.ty()),
+                _ => None,
+            };
+            if matches!(item_ty, Some(Type::U8)) {
+                let bytes: Vec<u8> = list
+                    .iter()
+                    .filter_map(|v| match v {
+                        Val::U8(b) => Some(*b),
+                        _ => None,
+                    })
+                    .collect();
+                return Value::String(BASE64.encode(bytes));
+            }
+            Value::Array(
+                list.iter()
+                    .map(|v| val_to_json(v, item_ty.as_ref()))
+                    .collect(),
+            )
+        }
         Val::Record(fields) => {
+            let field_types: Vec<_> = match ty {
+                Some(Type::Record(r)) => r.fields().collect(),
+                _ => Vec::new(),
+            };
             let mut map = Map::new();
             for (k, v) in fields {
-                map.insert(k.clone(), val_to_json(v));
+                let field_ty = field_types
+                    .iter()
+                    .find(|field| field.name == k.as_str())
+                    .map(|field| &field.ty);
+                map.insert(k.clone(), val_to_json(v, field_ty));
             }
             Value::Object(map)
         }
-        Val::Tuple(items) => Value::Array(items.iter().map(val_to_json).collect()),
+        Val::Tuple(items) => {
+            let item_types: Vec<_> = match ty {
+                Some(Type::Tuple(tup)) => tup.types().collect(),
+                _ => Vec::new(),
+            };
+            Value::Array(
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, v)| val_to_json(v, item_types.get(i)))
+                    .collect(),
+            )
+        }
 
         Val::Variant(tag, payload) => {
+            let payload_ty = match ty {
+                Some(Type::Variant(variant_handle)) => variant_handle
+                    .cases()
+                    .find(|case| case.name == tag.as_str())
+                    .and_then(|case| case.ty),
+                _ => None,
+            };
             let mut obj = Map::new();
             obj.insert("tag".to_string(), Value::String(tag.clone()));
             if let Some(val_box) = payload {
-                obj.insert("val".to_string(), val_to_json(val_box));
+                obj.insert("val".to_string(), val_to_json(val_box, payload_ty.as_ref()));
             }
             Value::Object(obj)
         }
         Val::Enum(s) => Value::String(s.clone()),
 
         Val::Option(None) => Value::Null,
-        Val::Option(Some(val_box)) => val_to_json(val_box),
+        Val::Option(Some(val_box)) => {
+            let inner_ty = match ty {
+                Some(Type::Option(opt_handle)) => Some(opt_handle.ty()),
+                _ => None,
+            };
+            val_to_json(val_box, inner_ty.as_ref())
+        }
 
         Val::Result(Ok(opt_box)) => {
+            let ok_ty = match ty {
+                Some(Type::Result(res_handle)) => res_handle.ok(),
+                _ => None,
+            };
             let mut obj = Map::new();
             obj.insert(
                 "ok".to_string(),
                 match opt_box {
-                    Some(v) => val_to_json(v),
+                    Some(v) => val_to_json(v, ok_ty.as_ref()),
                     None => Value::Null,
                 },
             );
             Value::Object(obj)
         }
         Val::Result(Err(opt_box)) => {
+            let err_ty = match ty {
+                Some(Type::Result(res_handle)) => res_handle.err(),
+                _ => None,
+            };
             let mut obj = Map::new();
             obj.insert(
                 "err".to_string(),
                 match opt_box {
-                    Some(v) => val_to_json(v),
+                    Some(v) => val_to_json(v, err_ty.as_ref()),
                     None => Value::Null,
                 },
             );
@@ -724,6 +812,18 @@ fn val_to_json(val: &Val) -> Value {
     }
 }
 
+/// The exact integer value of a JSON number.
+///
+/// Integer literals are kept exactly across the full i64 and u64 ranges; they never pass through
+/// f64. Numbers written with a fraction or exponent (`42.0`, `1e3`) are rejected, even when
+/// whole, since they are floats and large ones may already have been rounded.
+fn json_to_integer(n: &serde_json::Number) -> Result<i128, ValError> {
+    n.as_i64()
+        .map(i128::from)
+        .or_else(|| n.as_u64().map(i128::from))
+        .ok_or_else(|| ValError::NotAnInteger(n.to_string()))
+}
+
 fn json_to_val(value: &Value, ty: &Type) -> Result<Val, ValError> {
     match ty {
         Type::Bool => match value {
@@ -731,65 +831,71 @@ fn json_to_val(value: &Value, ty: &Type) -> Result<Val, ValError> {
             _ => Err(ValError::ShapeError("bool", format!("{value:?}"))),
         },
         Type::S8 => match value {
-            Value::Number(n) => n
-                .as_i64()
-                .and_then(|i| i8::try_from(i).ok())
+            Value::Number(n) => json_to_integer(n)?
+                .try_into()
                 .map(Val::S8)
-                .ok_or_else(|| ValError::NumberError(format!("{n:?}"))),
+                .map_err(|_| ValError::NumberError(format!("{n:?}"))),
             _ => Err(ValError::ShapeError("s8", format!("{value:?}"))),
         },
         Type::S16 => match value {
-            Value::Number(n) => n
-                .as_i64()
-                .and_then(|i| i16::try_from(i).ok())
+            Value::Number(n) => json_to_integer(n)?
+                .try_into()
                 .map(Val::S16)
-                .ok_or_else(|| ValError::NumberError(format!("{n:?}"))),
+                .map_err(|_| ValError::NumberError(format!("{n:?}"))),
             _ => Err(ValError::ShapeError("s16", format!("{value:?}"))),
         },
         Type::S32 => match value {
-            Value::Number(n) => n
-                .as_i64()
-                .and_then(|i| i32::try_from(i).ok())
+            Value::Number(n) => json_to_integer(n)?
+                .try_into()
                 .map(Val::S32)
-                .ok_or_else(|| ValError::NumberError(format!("{n:?}"))),
+                .map_err(|_| ValError::NumberError(format!("{n:?}"))),
             _ => Err(ValError::ShapeError("s32", format!("{value:?}"))),
         },
         Type::S64 => match value {
-            Value::Number(n) => n
-                .as_i64()
+            Value::Number(n) => json_to_integer(n)?
+                .try_into()
                 .map(Val::S64)
-                .ok_or_else(|| ValError::NumberError(format!("{n:?}"))),
+                .map_err(|_| ValError::NumberError(format!("{n:?}"))),
+            // Clients whose JSON numbers are doubles cannot send integers above 2^53 exactly,
+            // so 64-bit integers may also be written as decimal strings
+            Value::String(s) => s
+                .parse()
+                .map(Val::S64)
+                .map_err(|_| ValError::NumberError(s.clone())),
             _ => Err(ValError::ShapeError("s64", format!("{value:?}"))),
         },
         Type::U8 => match value {
-            Value::Number(n) => n
-                .as_u64()
-                .and_then(|i| u8::try_from(i).ok())
+            Value::Number(n) => json_to_integer(n)?
+                .try_into()
                 .map(Val::U8)
-                .ok_or_else(|| ValError::NumberError(format!("{n:?}"))),
+                .map_err(|_| ValError::NumberError(format!("{n:?}"))),
             _ => Err(ValError::ShapeError("u8", format!("{value:?}"))),
         },
         Type::U16 => match value {
-            Value::Number(n) => n
-                .as_u64()
-                .and_then(|i| u16::try_from(i).ok())
+            Value::Number(n) => json_to_integer(n)?
+                .try_into()
                 .map(Val::U16)
-                .ok_or_else(|| ValError::NumberError(format!("{n:?}"))),
+                .map_err(|_| ValError::NumberError(format!("{n:?}"))),
             _ => Err(ValError::ShapeError("u16", format!("{value:?}"))),
         },
         Type::U32 => match value {
-            Value::Number(n) => n
-                .as_u64()
-                .and_then(|i| u32::try_from(i).ok())
+            Value::Number(n) => json_to_integer(n)?
+                .try_into()
                 .map(Val::U32)
-                .ok_or_else(|| ValError::NumberError(format!("{n:?}"))),
+                .map_err(|_| ValError::NumberError(format!("{n:?}"))),
             _ => Err(ValError::ShapeError("u32", format!("{value:?}"))),
         },
         Type::U64 => match value {
-            Value::Number(n) => n
-                .as_u64()
+            Value::Number(n) => json_to_integer(n)?
+                .try_into()
                 .map(Val::U64)
-                .ok_or_else(|| ValError::NumberError(format!("{n:?}"))),
+                .map_err(|_| ValError::NumberError(format!("{n:?}"))),
+            // Clients whose JSON numbers are doubles cannot send integers above 2^53 exactly,
+            // so 64-bit integers may also be written as decimal strings
+            Value::String(s) => s
+                .parse()
+                .map(Val::U64)
+                .map_err(|_| ValError::NumberError(s.clone())),
             _ => Err(ValError::ShapeError("u64", format!("{value:?}"))),
         },
         Type::Float32 => match value {
@@ -821,6 +927,10 @@ fn json_to_val(value: &Value, ty: &Type) -> Result<Val, ValError> {
             _ => Err(ValError::ShapeError("string", format!("{value:?}"))),
         },
         Type::List(list_handle) => match value {
+            Value::String(encoded) if matches!(list_handle.ty(), Type::U8) => BASE64
+                .decode(encoded)
+                .map(|bytes| Val::List(bytes.into_iter().map(Val::U8).collect()))
+                .map_err(|e| ValError::ShapeError("base64 bytes", e.to_string())),
             Value::Array(arr) => {
                 let mut vals = Vec::new();
                 for item in arr {
@@ -1027,14 +1137,14 @@ mod tests {
 
     #[test]
     fn test_vals_to_json_empty() {
-        let json_val = vals_to_json(&[]);
+        let json_val = vals_to_json(&[], &[]);
         assert_eq!(json_val, json!(null));
     }
 
     #[test]
     fn test_vals_to_json_single() {
         let val = Val::Bool(true);
-        let json_val = vals_to_json(std::slice::from_ref(&val));
+        let json_val = vals_to_json(std::slice::from_ref(&val), &[Type::Bool]);
         assert_eq!(json_val, json!({"result": true}));
     }
 
@@ -1078,7 +1188,7 @@ mod tests {
     #[test]
     fn test_vals_to_json_multiple_values() {
         let wit_vals = vec![Val::String("example".to_string()), Val::S64(42)];
-        let json_result = vals_to_json(&wit_vals);
+        let json_result = vals_to_json(&wit_vals, &[Type::String, Type::S64]);
         assert_eq!(
             json_result,
             json!({"result": {"val0": "example", "val1": 42}})
@@ -1088,48 +1198,48 @@ mod tests {
     #[test]
     fn test_val_to_json_bool() {
         let val = Val::Bool(false);
-        assert_eq!(val_to_json(&val), json!(false));
+        assert_eq!(val_to_json(&val, None), json!(false));
     }
 
     #[test]
     fn test_val_to_json_numbers() {
         let s8 = Val::S8(-5);
-        assert_eq!(val_to_json(&s8), json!(-5));
+        assert_eq!(val_to_json(&s8, None), json!(-5));
 
         let u8 = Val::U8(200);
-        assert_eq!(val_to_json(&u8), json!(200));
+        assert_eq!(val_to_json(&u8, None), json!(200));
 
         let s16 = Val::S16(-123);
-        assert_eq!(val_to_json(&s16), json!(-123));
+        assert_eq!(val_to_json(&s16, None), json!(-123));
 
         let u16 = Val::U16(123);
-        assert_eq!(val_to_json(&u16), json!(123));
+        assert_eq!(val_to_json(&u16, None), json!(123));
 
         let s32 = Val::S32(-1000);
-        assert_eq!(val_to_json(&s32), json!(-1000));
+        assert_eq!(val_to_json(&s32, None), json!(-1000));
 
         let u32 = Val::U32(1000);
-        assert_eq!(val_to_json(&u32), json!(1000));
+        assert_eq!(val_to_json(&u32, None), json!(1000));
 
         let s64 = Val::S64(-9999);
-        assert_eq!(val_to_json(&s64), json!(-9999));
+        assert_eq!(val_to_json(&s64, None), json!(-9999));
 
         let u64 = Val::U64(9999);
-        assert_eq!(val_to_json(&u64), json!(9999));
+        assert_eq!(val_to_json(&u64, None), json!(9999));
     }
 
     #[allow(clippy::approx_constant)]
     #[test]
     fn test_val_to_json_floats() {
         let float32 = Val::Float32(3.14);
-        if let Value::Number(n) = val_to_json(&float32) {
+        if let Value::Number(n) = val_to_json(&float32, None) {
             assert!((n.as_f64().unwrap() - 3.14).abs() < 1e-6);
         } else {
             panic!("Expected a JSON number for Float32");
         }
 
         let float64 = Val::Float64(2.718281828);
-        if let Value::Number(n) = val_to_json(&float64) {
+        if let Value::Number(n) = val_to_json(&float64, None) {
             assert!((n.as_f64().unwrap() - 2.718281828).abs() < 1e-9);
         } else {
             panic!("Expected a JSON number for Float64");
@@ -1139,19 +1249,19 @@ mod tests {
     #[test]
     fn test_val_to_json_char() {
         let val = Val::Char('A');
-        assert_eq!(val_to_json(&val), json!("A"));
+        assert_eq!(val_to_json(&val, None), json!("A"));
     }
 
     #[test]
     fn test_val_to_json_string() {
         let val = Val::String("hello".to_string());
-        assert_eq!(val_to_json(&val), json!("hello"));
+        assert_eq!(val_to_json(&val, None), json!("hello"));
     }
 
     #[test]
     fn test_val_to_json_list() {
         let val = Val::List(vec![Val::S64(1), Val::S64(2)]);
-        assert_eq!(val_to_json(&val), json!([1, 2]));
+        assert_eq!(val_to_json(&val, None), json!([1, 2]));
     }
 
     #[test]
@@ -1160,7 +1270,7 @@ mod tests {
             ("key1".to_string(), Val::Bool(true)),
             ("key2".to_string(), Val::String("value".to_string())),
         ]);
-        let json_val = val_to_json(&val);
+        let json_val = val_to_json(&val, None);
         let obj = json_val.as_object().unwrap();
         assert_eq!(obj.get("key1").unwrap(), &json!(true));
         assert_eq!(obj.get("key2").unwrap(), &json!("value"));
@@ -1169,19 +1279,19 @@ mod tests {
     #[test]
     fn test_val_to_json_tuple() {
         let val = Val::Tuple(vec![Val::S64(42), Val::String("tuple".to_string())]);
-        assert_eq!(val_to_json(&val), json!([42, "tuple"]));
+        assert_eq!(val_to_json(&val, None), json!([42, "tuple"]));
     }
 
     #[test]
     fn test_val_to_json_variant() {
         let variant_with = Val::Variant("tag1".to_string(), Some(Box::new(Val::S64(99))));
-        let json_with = val_to_json(&variant_with);
+        let json_with = val_to_json(&variant_with, None);
         let obj_with = json_with.as_object().unwrap();
         assert_eq!(obj_with.get("tag").unwrap(), &json!("tag1"));
         assert_eq!(obj_with.get("val").unwrap(), &json!(99));
 
         let variant_without = Val::Variant("tag2".to_string(), None);
-        let json_without = val_to_json(&variant_without);
+        let json_without = val_to_json(&variant_without, None);
         let obj_without = json_without.as_object().unwrap();
         assert_eq!(obj_without.get("tag").unwrap(), &json!("tag2"));
         assert!(obj_without.get("val").is_none());
@@ -1190,37 +1300,37 @@ mod tests {
     #[test]
     fn test_val_to_json_enum() {
         let val = Val::Enum("green".to_string());
-        assert_eq!(val_to_json(&val), json!("green"));
+        assert_eq!(val_to_json(&val, None), json!("green"));
     }
 
     #[test]
     fn test_val_to_json_option() {
         let none_option = Val::Option(None);
-        assert_eq!(val_to_json(&none_option), json!(null));
+        assert_eq!(val_to_json(&none_option, None), json!(null));
 
         let some_option = Val::Option(Some(Box::new(Val::String("some".to_string()))));
-        assert_eq!(val_to_json(&some_option), json!("some"));
+        assert_eq!(val_to_json(&some_option, None), json!("some"));
     }
 
     #[test]
     fn test_val_to_json_result() {
         let ok_result = Val::Result(Ok(Some(Box::new(Val::String("ok".to_string())))));
-        let json_ok = val_to_json(&ok_result);
+        let json_ok = val_to_json(&ok_result, None);
         let obj_ok = json_ok.as_object().unwrap();
         assert_eq!(obj_ok.get("ok").unwrap(), &json!("ok"));
 
         let err_result = Val::Result(Err(Some(Box::new(Val::String("err".to_string())))));
-        let json_err = val_to_json(&err_result);
+        let json_err = val_to_json(&err_result, None);
         let obj_err = json_err.as_object().unwrap();
         assert_eq!(obj_err.get("err").unwrap(), &json!("err"));
 
         let ok_none = Val::Result(Ok(None));
-        let json_ok_none = val_to_json(&ok_none);
+        let json_ok_none = val_to_json(&ok_none, None);
         let obj_ok_none = json_ok_none.as_object().unwrap();
         assert_eq!(obj_ok_none.get("ok").unwrap(), &json!(null));
 
         let err_none = Val::Result(Err(None));
-        let json_err_none = val_to_json(&err_none);
+        let json_err_none = val_to_json(&err_none, None);
         let obj_err_none = json_err_none.as_object().unwrap();
         assert_eq!(obj_err_none.get("err").unwrap(), &json!(null));
     }
@@ -1228,7 +1338,7 @@ mod tests {
     #[test]
     fn test_val_to_json_flags() {
         let val = Val::Flags(vec!["f1".to_string(), "f2".to_string()]);
-        assert_eq!(val_to_json(&val), json!(["f1", "f2"]));
+        assert_eq!(val_to_json(&val, None), json!(["f1", "f2"]));
     }
 
     #[test]
@@ -1639,20 +1749,20 @@ mod tests {
     #[test]
     fn test_wit_to_json_conversions() {
         let wit_bool = Val::Bool(false);
-        assert_eq!(val_to_json(&wit_bool), json!(false));
+        assert_eq!(val_to_json(&wit_bool, None), json!(false));
 
         let wit_string = Val::String("test".to_string());
-        assert_eq!(val_to_json(&wit_string), json!("test"));
+        assert_eq!(val_to_json(&wit_string, None), json!("test"));
 
         let wit_list = Val::List(vec![Val::S64(1), Val::S64(2)]);
-        assert_eq!(val_to_json(&wit_list), json!([1, 2]));
+        assert_eq!(val_to_json(&wit_list, None), json!([1, 2]));
 
         let wit_record = Val::Record(vec![
             ("key1".to_string(), Val::Bool(true)),
             ("key2".to_string(), Val::String("value".to_string())),
         ]);
         assert_eq!(
-            val_to_json(&wit_record),
+            val_to_json(&wit_record, None),
             json!({
                 "key1": true,
                 "key2": "value"
@@ -1660,20 +1770,20 @@ mod tests {
         );
 
         let wit_option_none = Val::Option(None);
-        assert_eq!(val_to_json(&wit_option_none), json!(null));
+        assert_eq!(val_to_json(&wit_option_none, None), json!(null));
         let wit_option_some = Val::Option(Some(Box::new(Val::String("some".to_string()))));
-        assert_eq!(val_to_json(&wit_option_some), json!("some"));
+        assert_eq!(val_to_json(&wit_option_some, None), json!("some"));
 
         let wit_result_ok = Val::Result(Ok(Some(Box::new(Val::String("success".to_string())))));
-        assert_eq!(val_to_json(&wit_result_ok), json!({"ok": "success"}));
+        assert_eq!(val_to_json(&wit_result_ok, None), json!({"ok": "success"}));
         let wit_result_err = Val::Result(Err(Some(Box::new(Val::String("error".to_string())))));
-        assert_eq!(val_to_json(&wit_result_err), json!({"err": "error"}));
+        assert_eq!(val_to_json(&wit_result_err, None), json!({"err": "error"}));
     }
 
     #[test]
     fn test_vals_to_json_multiple() {
         let wit_vals = vec![Val::String("example".to_string()), Val::S64(42)];
-        let json_result = vals_to_json(&wit_vals);
+        let json_result = vals_to_json(&wit_vals, &[Type::String, Type::S64]);
         assert_eq!(
             json_result,
             json!({"result": {"val0": "example", "val1": 42}})
@@ -1701,6 +1811,61 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_binary_list_round_trip() {
+        let engine = Engine::default();
+        let wat = r#"(component
+            (type (component
+                (type (component
+                    (type (list u8))
+                    (type (func (param "name" string) (param "wit" 0) (result 0)))
+                    (export "generate" (func (type 1)))
+                ))
+                (export "foo:foo/foo" (component (type 0)))
+            ))
+            (export "foo" (type 0))
+        )"#;
+        let component = Component::new(&engine, wat).unwrap();
+
+        let schema = component_exports_to_json_schema(&component, &engine, true);
+        let tool = &schema["tools"][0];
+        assert!(is_binary_schema(&tool["inputSchema"]["properties"]["wit"]));
+        assert!(is_binary_schema(
+            &tool["outputSchema"]["properties"]["result"]
+        ));
+
+        let export = |item: ComponentItem, name: &str| match item {
+            ComponentItem::Component(c) => c.get_export(&engine, name).unwrap(),
+            _ => panic!("Expected a component export"),
+        };
+        let outer = component
+            .component_type()
+            .get_export(&engine, "foo")
+            .unwrap();
+        let func = match export(export(outer, "foo:foo/foo"), "generate") {
+            ComponentItem::ComponentFunc(f) => f,
+            _ => panic!("Expected a function export"),
+        };
+        let (_, bytes_ty) = func.params().find(|(name, _)| *name == "wit").unwrap();
+
+        let val = json_to_val(&json!("iVBORw=="), &bytes_ty).unwrap();
+        assert!(matches!(&val, Val::List(bytes) if bytes.len() == 4));
+        assert_eq!(val_to_json(&val, Some(&bytes_ty)), json!("iVBORw=="));
+
+        // The type decides the encoding, so an empty `list<u8>` is still a string
+        let empty = Val::List(Vec::new());
+        assert_eq!(val_to_json(&empty, Some(&bytes_ty)), json!(""));
+        assert_eq!(
+            vals_to_json(&[empty.clone()], &[bytes_ty.clone()]),
+            json!({"result": ""})
+        );
+        assert_eq!(val_to_json(&empty, None), json!([]));
+
+        // Byte arrays are still accepted
+        assert!(json_to_val(&json!([1, 2]), &bytes_ty).is_ok());
+        assert!(json_to_val(&json!("not base64!"), &bytes_ty).is_err());
+    }
+
     #[test]
     fn test_json_to_vals() {
         let types = vec![
@@ -1728,6 +1893,49 @@ mod tests {
         assert!(json_to_val(&overflow_val, &s8_ty).is_err());
     }
 
+    #[test]
+    fn test_json_to_val_keeps_64_bit_integers_exact() {
+        // 2^53 + 1 is the first integer an f64 cannot represent
+        let value: Value = serde_json::from_str("9007199254740993").unwrap();
+        assert!(matches!(
+            json_to_val(&value, &Type::U64),
+            Ok(Val::U64(9_007_199_254_740_993))
+        ));
+        assert!(matches!(
+            json_to_val(&json!(u64::MAX), &Type::U64),
+            Ok(Val::U64(u64::MAX))
+        ));
+        assert!(matches!(
+            json_to_val(&json!(i64::MIN), &Type::S64),
+            Ok(Val::S64(i64::MIN))
+        ));
+
+        // A client that cannot write 2^53 + 1 as a number can send it as a string
+        assert!(matches!(
+            json_to_val(&json!("9007199254740993"), &Type::U64),
+            Ok(Val::U64(9_007_199_254_740_993))
+        ));
+        assert!(matches!(
+            json_to_val(&json!("-9007199254740993"), &Type::S64),
+            Ok(Val::S64(-9_007_199_254_740_993))
+        ));
+        assert!(json_to_val(&json!("12abc"), &Type::U64).is_err());
+        assert!(json_to_val(&json!("42"), &Type::U8).is_err());
+
+        // Floats are not integers, whole or not
+        for float in ["42.0", "1e3", "1.5", "9007199254740993.0"] {
+            let value: Value = serde_json::from_str(float).unwrap();
+            assert!(
+                matches!(
+                    json_to_val(&value, &Type::U64),
+                    Err(ValError::NotAnInteger(_))
+                ),
+                "{float} was accepted"
+            );
+        }
+        assert!(json_to_val(&json!(-1), &Type::U64).is_err());
+    }
+
     #[test]
     fn test_json_to_vals_errors() {
         let types = vec![
@@ -1862,7 +2070,7 @@ mod tests {
             ("name".to_string(), Val::String("alpha".to_string())),
             ("value".to_string(), Val::U32(101)),
         ]);
-        let json_record = val_to_json(&original_record);
+        let json_record = val_to_json(&original_record, None);
         let roundtrip_record = json_to_val(&json_record, &record_type).unwrap();
         assert_eq!(original_record, roundtrip_record);
 
@@ -1871,19 +2079,19 @@ mod tests {
             "s".to_string(),
             Some(Box::new(Val::String("beta".to_string()))),
         );
-        let json_variant = val_to_json(&original_variant);
+        let json_variant = val_to_json(&original_variant, None);
         let roundtrip_variant = json_to_val(&json_variant, &variant_type).unwrap();
         assert_eq!(original_variant, roundtrip_variant);
 
         let tuple_type = get_exported_type("t");
         let original_tuple = Val::Tuple(vec![Val::S32(-42), Val::Bool(true)]);
-        let json_tuple = val_to_json(&original_tuple);
+        let json_tuple = val_to_json(&original_tuple, None);
         let roundtrip_tuple = json_to_val(&json_tuple, &tuple_type).unwrap();
         assert_eq!(original_tuple, roundtrip_tuple);
 
         let enum_type = get_exported_type("e");
         let original_enum = Val::Enum("dog".to_string());
-        let json_enum = val_to_json(&original_enum);
+        let json_enum = val_to_json(&original_enum, None);
         let roundtrip_enum = json_to_val(&json_enum, &enum_type).unwrap();
         assert_eq!(original_enum, roundtrip_enum);
 
@@ -1893,20 +2101,20 @@ mod tests {
             ("value".to_string(), Val::U32(202)),
         ]);
         let original_some = Val::Option(Some(Box::new(inner_val.clone())));
-        let json_inner = val_to_json(&inner_val);
+        let json_inner = val_to_json(&inner_val, None);
         let roundtrip_some = json_to_val(&json_inner, &option_type).unwrap();
         assert_eq!(original_some, roundtrip_some);
 
         let result_type = get_exported_type("res");
         let ok_inner = Val::Variant("u".to_string(), Some(Box::new(Val::U64(303))));
         let original_ok = Val::Result(Ok(Some(Box::new(ok_inner))));
-        let json_ok = val_to_json(&original_ok);
+        let json_ok = val_to_json(&original_ok, None);
         let roundtrip_ok = json_to_val(&json_ok, &result_type).unwrap();
         assert_eq!(original_ok, roundtrip_ok);
 
         let flags_type = get_exported_type("f");
         let original_flags = Val::Flags(vec!["read".to_string(), "write".to_string()]);
-        let json_flags = val_to_json(&original_flags);
+        let json_flags = val_to_json(&original_flags, None);
         let roundtrip_flags = json_to_val(&json_flags, &flags_type).unwrap();
         assert_eq!(original_flags, roundtrip_flags);
 
@@ -1915,7 +2123,7 @@ mod tests {
             Val::Tuple(vec![Val::S32(1), Val::Bool(true)]),
             Val::Tuple(vec![Val::S32(2), Val::Bool(false)]),
         ]);
-        let json_list = val_to_json(&original_list);
+        let json_list = val_to_json(&original_list, None);
         let roundtrip_list = json_to_val(&json_list, &list_type).unwrap();
         assert_eq!(original_list, roundtrip_list);
     }
@@ -0,0 +1,81 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Nesting limit for tool calls that trigger further tool calls.
+//!
+//! A hook or meta-tool that calls back into the server runs the nested call on the same
+//! task, one level deeper than the call that triggered it. Calls nested deeper than the
+//! configured maximum are rejected, so an accidental loop fails with an error instead of
+//! overflowing the stack.
+
+use std::future::Future;
+
+use rmcp::model::ErrorData;
+
+/// Maximum nesting depth of tool calls unless configured otherwise
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 8;
+
+tokio::task_local! {
+    static CALL_DEPTH: usize;
+}
+
+/// Nesting depth of the tool call running on the current task: 1 for a call made directly
+/// by a client, 0 outside of any tool call.
+pub fn current_call_depth() -> usize {
+    CALL_DEPTH.try_with(|depth| *depth).unwrap_or(0)
+}
+
+/// Run `call` one level deeper than the current tool call, or reject it without running it
+/// when that would exceed `max_depth`.
+pub(crate) async fn nested<T, F>(max_depth: usize, call: F) -> Result<T, ErrorData>
+where
+    F: Future<Output = Result<T, ErrorData>>,
+{
+    let depth = current_call_depth() + 1;
+    if depth > max_depth {
+        return Err(ErrorData::invalid_request(
+            format!(
+                "Tool call nested {depth} levels deep exceeds the maximum of {max_depth}; \
+                 a tool or hook is likely calling tools in a loop"
+            ),
+            None,
+        ));
+    }
+    CALL_DEPTH.scope(depth, call).await
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::future::BoxFuture;
+
+    use super::*;
+
+    /// A tool whose hook calls the same tool again, forever
+    fn call_tool_recursively(
+        max_depth: usize,
+        depths: &mut Vec<usize>,
+    ) -> BoxFuture<'_, Result<(), ErrorData>> {
+        Box::pin(nested(max_depth, async move {
+            depths.push(current_call_depth());
+            call_tool_recursively(max_depth, depths).await
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_calls_nested_beyond_max_depth_are_rejected() {
+        assert_eq!(current_call_depth(), 0);
+
+        let mut depths = Vec::new();
+        let err = call_tool_recursively(3, &mut depths).await.unwrap_err();
+        assert_eq!(depths, vec![1, 2, 3]);
+        assert!(err.message.contains("nested 4 levels deep"));
+        assert!(err.message.contains("maximum of 3"));
+
+        // The limit only applies within a call chain
+        assert_eq!(current_call_depth(), 0);
+        assert_eq!(
+            nested(3, async { Ok(current_call_depth()) }).await.unwrap(),
+            1
+        );
+    }
+}
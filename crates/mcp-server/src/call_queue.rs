@@ -0,0 +1,213 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Bounded admission of tool calls.
+//!
+//! Every tool call takes a slot from a [`CallQueue`] before it runs and releases it when it
+//! finishes. Once all slots are taken, further calls either wait for one to free up or are
+//! rejected with a "server busy" error, so a burst of requests cannot spawn an unbounded
+//! number of component instances. A call that waits longer than the queue's maximum wait is
+//! rejected the same way.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use rmcp::model::{ErrorCode, ErrorData};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// JSON-RPC error code of the "server busy" error returned when a full queue rejects a call
+pub const SERVER_BUSY_ERROR_CODE: ErrorCode = ErrorCode(-32000);
+
+/// How long a call waits for a free slot before it is rejected, unless configured
+pub const DEFAULT_QUEUE_MAX_WAIT: Duration = Duration::from_secs(60);
+
+/// Limits how many tool calls run at once. Clones share the same slots.
+#[derive(Clone)]
+pub struct CallQueue {
+    slots: Arc<Semaphore>,
+    capacity: Option<usize>,
+    reject_when_full: bool,
+    max_wait: Duration,
+    waiting: Arc<AtomicUsize>,
+}
+
+/// Snapshot of a [`CallQueue`]'s occupancy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallQueueStats {
+    /// Calls currently running
+    pub depth: usize,
+    /// Calls waiting for a free slot
+    pub waiting: usize,
+    /// Maximum number of calls that run at once, if limited
+    pub capacity: Option<usize>,
+}
+
+impl Default for CallQueue {
+    fn default() -> Self {
+        Self::new(None, false)
+    }
+}
+
+impl CallQueue {
+    /// Create a queue running at most `capacity` calls at once, or any number when `None`.
+    /// With `reject_when_full`, calls arriving while every slot is taken fail immediately
+    /// instead of waiting.
+    pub fn new(capacity: Option<usize>, reject_when_full: bool) -> Self {
+        let capacity = capacity.map(|n| n.max(1));
+        Self {
+            slots: Arc::new(Semaphore::new(capacity.unwrap_or(Semaphore::MAX_PERMITS))),
+            capacity,
+            reject_when_full,
+            max_wait: DEFAULT_QUEUE_MAX_WAIT,
+            waiting: Arc::default(),
+        }
+    }
+
+    /// Reject a call with a "server busy" error once it has waited `max_wait` for a slot.
+    /// Defaults to [`DEFAULT_QUEUE_MAX_WAIT`].
+    pub fn with_max_wait(mut self, max_wait: Duration) -> Self {
+        self.max_wait = max_wait;
+        self
+    }
+
+    /// Take a slot for one call, which is held until the returned permit is dropped.
+    pub async fn acquire(&self) -> Result<OwnedSemaphorePermit, ErrorData> {
+        if self.reject_when_full {
+            return Arc::clone(&self.slots)
+                .try_acquire_owned()
+                .map_err(|_| self.server_busy());
+        }
+
+        let _waiting = WaitingGuard::new(&self.waiting);
+        match tokio::time::timeout(self.max_wait, Arc::clone(&self.slots).acquire_owned()).await {
+            Ok(Ok(permit)) => Ok(permit),
+            Ok(Err(_)) | Err(_) => Err(self.server_busy()),
+        }
+    }
+
+    /// Current occupancy of the queue
+    pub fn stats(&self) -> CallQueueStats {
+        let total = self.capacity.unwrap_or(Semaphore::MAX_PERMITS);
+        CallQueueStats {
+            depth: total - self.slots.available_permits(),
+            waiting: self.waiting.load(Ordering::Relaxed),
+            capacity: self.capacity,
+        }
+    }
+
+    fn server_busy(&self) -> ErrorData {
+        let message = match self.capacity {
+            Some(capacity) => {
+                format!("Server busy: {capacity} tool calls are already running, retry later")
+            }
+            None => "Server busy, retry later".to_string(),
+        };
+        ErrorData::new(SERVER_BUSY_ERROR_CODE, message, None)
+    }
+}
+
+/// Counts a caller as waiting until it gets a slot or gives up
+struct WaitingGuard<'a>(&'a AtomicUsize);
+
+impl<'a> WaitingGuard<'a> {
+    fn new(waiting: &'a AtomicUsize) -> Self {
+        waiting.fetch_add(1, Ordering::Relaxed);
+        Self(waiting)
+    }
+}
+
+impl Drop for WaitingGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_full_queue_rejects_calls() {
+        let queue = CallQueue::new(Some(2), true);
+        let _slow_calls = [
+            queue.acquire().await.unwrap(),
+            queue.acquire().await.unwrap(),
+        ];
+
+        let err = queue.acquire().await.unwrap_err();
+        assert_eq!(err.code, SERVER_BUSY_ERROR_CODE);
+        assert!(err.message.contains("Server busy"));
+        assert_eq!(
+            queue.stats(),
+            CallQueueStats {
+                depth: 2,
+                waiting: 0,
+                capacity: Some(2),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_full_queue_makes_calls_wait() {
+        let queue = CallQueue::new(Some(2), false);
+        let first = queue.acquire().await.unwrap();
+        let _second = queue.acquire().await.unwrap();
+
+        let waiter = tokio::spawn({
+            let queue = queue.clone();
+            async move { queue.acquire().await.map(|_| ()) }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiter.is_finished());
+        assert_eq!(queue.stats().waiting, 1);
+
+        // Finishing a slow call lets the waiting one through
+        drop(first);
+        tokio::time::timeout(Duration::from_secs(5), waiter)
+            .await
+            .expect("waiting call should get a slot")
+            .unwrap()
+            .unwrap();
+        assert_eq!(queue.stats().waiting, 0);
+        assert_eq!(queue.stats().depth, 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_waiting_call_rejected_after_max_wait() {
+        let queue = CallQueue::new(Some(1), false).with_max_wait(Duration::from_secs(10));
+        let running = queue.acquire().await.unwrap();
+
+        let waiter = tokio::spawn({
+            let queue = queue.clone();
+            async move { queue.acquire().await.map(|_| ()) }
+        });
+        tokio::time::sleep(Duration::from_secs(9)).await;
+        assert!(!waiter.is_finished());
+        assert_eq!(queue.stats().waiting, 1);
+
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        let err = waiter.await.unwrap().unwrap_err();
+        assert_eq!(err.code, SERVER_BUSY_ERROR_CODE);
+        assert!(err.message.contains("Server busy"));
+        assert_eq!(queue.stats().waiting, 0);
+        assert_eq!(queue.stats().depth, 1);
+        drop(running);
+    }
+
+    #[tokio::test]
+    async fn test_unbounded_queue_tracks_depth() {
+        let queue = CallQueue::default();
+        let permits: Vec<_> = futures::future::join_all((0..5).map(|_| queue.acquire()))
+            .await
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(queue.stats().depth, 5);
+        assert_eq!(queue.stats().capacity, None);
+        drop(permits);
+        assert_eq!(queue.stats().depth, 0);
+    }
+}
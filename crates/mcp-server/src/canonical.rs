@@ -0,0 +1,122 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Canonical JSON text for tool arguments, so hooks that cache results or record calls can
+//! derive keys that do not depend on how the client happened to order or format them.
+//!
+//! Object keys are sorted, insignificant whitespace is dropped and numbers are written in
+//! one form: whole numbers that a float represents exactly are written as integers, so
+//! `1`, `1.0` and `1e0` all become `1`, and `-0.0` becomes `0`.
+
+use std::fmt::Write;
+
+use serde_json::{Number, Value};
+
+/// Largest magnitude below which every whole float is an exact integer (2^53)
+const MAX_EXACT_FLOAT_INTEGER: f64 = 9_007_199_254_740_992.0;
+
+/// Canonical text of `value`. Logically equal values produce identical strings.
+pub fn canonical_json(value: &Value) -> String {
+    let mut out = String::new();
+    write_value(&mut out, value);
+    out
+}
+
+fn write_value(out: &mut String, value: &Value) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => write_number(out, n),
+        Value::String(s) => write_string(out, s),
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_value(out, item);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+            out.push('{');
+            for (i, (key, item)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_string(out, key);
+                out.push(':');
+                write_value(out, item);
+            }
+            out.push('}');
+        }
+    }
+}
+
+fn write_number(out: &mut String, n: &Number) {
+    if let Some(i) = n.as_i64() {
+        let _ = write!(out, "{i}");
+    } else if let Some(u) = n.as_u64() {
+        let _ = write!(out, "{u}");
+    } else if let Some(f) = n.as_f64() {
+        if f.fract() == 0.0 && f.abs() < MAX_EXACT_FLOAT_INTEGER {
+            let _ = write!(out, "{}", f as i64);
+        } else {
+            out.push_str(&n.to_string());
+        }
+    }
+}
+
+fn write_string(out: &mut String, s: &str) {
+    // Serializing a string cannot fail
+    out.push_str(&serde_json::to_string(s).unwrap_or_default());
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_key_order_does_not_change_canonical_json() {
+        let a: Value = serde_json::from_str(
+            r#"{"url": "https://example.com",
+                "options": {"retries": 3, "headers": {"b": "2", "a": "1"}}}"#,
+        )
+        .unwrap();
+        let b: Value = serde_json::from_str(
+            r#"{"options": {"headers": {"a": "1", "b": "2"}, "retries": 3},
+                "url": "https://example.com"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(canonical_json(&a), canonical_json(&b));
+        assert_eq!(
+            canonical_json(&a),
+            r#"{"options":{"headers":{"a":"1","b":"2"},"retries":3},"url":"https://example.com"}"#
+        );
+    }
+
+    #[test]
+    fn test_numbers_are_normalized() {
+        let a: Value =
+            serde_json::from_str(r#"{"n": 1.0, "z": -0.0, "big": 1e3, "x": 0.5}"#).unwrap();
+        let b = json!({ "n": 1, "z": 0, "big": 1000, "x": 0.5 });
+        assert_eq!(canonical_json(&a), canonical_json(&b));
+        assert_eq!(canonical_json(&a), r#"{"big":1000,"n":1,"x":0.5,"z":0}"#);
+
+        // Integers beyond the exact float range keep every digit
+        assert_eq!(
+            canonical_json(&json!([u64::MAX, i64::MIN])),
+            "[18446744073709551615,-9223372036854775808]"
+        );
+        // Array order is significant
+        assert_ne!(
+            canonical_json(&json!([1, 2])),
+            canonical_json(&json!([2, 1]))
+        );
+    }
+}
@@ -2,19 +2,67 @@
 // Licensed under the MIT license.
 
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
 use futures::stream::{self, StreamExt};
-use rmcp::model::{CallToolRequestParam, CallToolResult, Content, Tool};
+use rmcp::model::{CallToolRequestParam, CallToolResult, Content, ResourceContents, Tool};
 use rmcp::{Peer, RoleServer};
 use serde_json::{json, Value};
 use tracing::{debug, error, info, instrument};
-use wassette::schema::{canonicalize_output_schema, ensure_structured_result};
-use wassette::{ComponentLoadOutcome, LifecycleManager, LoadResult};
+use wassette::schema::{canonicalize_output_schema, ensure_structured_result, is_binary_schema};
+use wassette::{
+    ComponentLoadOutcome, ComponentTrap, DuplicateToolPolicy, LifecycleManager, LoadOptions,
+    LoadResult, PolicySource, UnloadResult,
+};
+
+use crate::tools::COMPONENT_LIST;
+
+/// Separator between the component id and the tool name when component tools are
+/// advertised with component prefixes, e.g. `weather__get-forecast`
+pub const TOOL_PREFIX_SEPARATOR: &str = "__";
+
+/// Key of the tool `_meta` entry naming the component a tool belongs to, so clients can
+/// group component tools when tool groups are enabled
+pub const TOOL_GROUP_META_KEY: &str = "group";
+
+/// Name a component tool is advertised under when tools are grouped by component
+pub fn prefixed_tool_name(component_id: &str, tool_name: &str) -> String {
+    format!("{component_id}{TOOL_PREFIX_SEPARATOR}{tool_name}")
+}
+
+/// Find the component and tool a prefixed tool name refers to
+pub(crate) async fn resolve_prefixed_tool(
+    lifecycle_manager: &LifecycleManager,
+    name: &str,
+) -> Result<(String, String)> {
+    for id in lifecycle_manager.list_components_known().await {
+        let Some(tool_name) = name
+            .strip_prefix(id.as_str())
+            .and_then(|rest| rest.strip_prefix(TOOL_PREFIX_SEPARATOR))
+        else {
+            continue;
+        };
+        if lifecycle_manager
+            .get_tool_schema_for_component(&id, tool_name)
+            .await
+            .is_some()
+        {
+            return Ok((id, tool_name.to_string()));
+        }
+    }
+    bail!("No component provides the tool '{name}'")
+}
 
 #[instrument(skip(lifecycle_manager))]
-pub(crate) async fn get_component_tools(lifecycle_manager: &LifecycleManager) -> Result<Vec<Tool>> {
+pub(crate) async fn get_component_tools(
+    lifecycle_manager: &LifecycleManager,
+    prefix_component_tools: bool,
+    group_component_tools: bool,
+) -> Result<Vec<Tool>> {
     debug!("Listing components");
     // Use known components (loaded or present on disk) for fast listing
     let component_ids = lifecycle_manager.list_components_known().await;
@@ -30,12 +78,34 @@ pub(crate) async fn get_component_tools(lifecycle_manager: &LifecycleManager) ->
                 debug!(component_id = %id, tool_count, "Found tools in component");
                 for tool_json in arr {
                     if let Some(tool) = parse_tool_schema(tool_json) {
-                        tools.push(tool);
+                        tools.push((id.clone(), tool));
                     }
                 }
             }
         }
     }
+
+    // Tools exported by several components can only be called by their prefixed name
+    let require_namespace =
+        lifecycle_manager.duplicate_tool_policy() == DuplicateToolPolicy::RequireNamespace;
+    let mut exporters: HashMap<String, usize> = HashMap::new();
+    for (_, tool) in &tools {
+        *exporters.entry(tool.name.to_string()).or_default() += 1;
+    }
+    let tools: Vec<Tool> = tools
+        .into_iter()
+        .map(|(id, mut tool)| {
+            if prefix_component_tools || (require_namespace && exporters[tool.name.as_ref()] > 1) {
+                tool.name = Cow::Owned(prefixed_tool_name(&id, &tool.name));
+            }
+            if group_component_tools {
+                let mut meta = tool.meta.take().unwrap_or_default();
+                meta.insert(TOOL_GROUP_META_KEY.to_string(), Value::from(id));
+                tool.meta = Some(meta);
+            }
+            tool
+        })
+        .collect();
     info!(total_tools = tools.len(), "Total tools collected");
     Ok(tools)
 }
@@ -46,19 +116,18 @@ pub(crate) async fn handle_load_component(
     lifecycle_manager: &LifecycleManager,
     server_peer: Peer<RoleServer>,
 ) -> Result<CallToolResult> {
-    let args = extract_args_from_request(req)?;
-    let path = args
-        .get("path")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| anyhow::anyhow!("Missing required argument: 'path'"))?;
+    let (path, options) = extract_load_args(req)?;
+    let path = path.as_str();
+    let uri = resolve_component_reference(path)?;
 
     debug!(
         path = %path,
+        uri = %uri,
         operation = "load-component",
         "Component load operation started"
     );
 
-    match lifecycle_manager.load_component(path).await {
+    match lifecycle_manager.load_component_with(&uri, options).await {
         Ok(outcome) => {
             info!(
                 path = %path,
@@ -85,17 +154,185 @@ pub(crate) async fn handle_load_component(
     }
 }
 
+/// Parse the `path`, optional `id`, `force` and `force_recompile` arguments of a
+/// `load-component` request
+fn extract_load_args(req: &CallToolRequestParam) -> Result<(String, LoadOptions)> {
+    let args = extract_args_from_request(req)?;
+    let path = args
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing required argument: 'path'"))?;
+    let options = LoadOptions {
+        component_id: args.get("id").and_then(|v| v.as_str()).map(str::to_string),
+        force: args.get("force").and_then(|v| v.as_bool()).unwrap_or(false),
+        force_recompile: args
+            .get("force_recompile")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+    };
+    Ok((path.to_string(), options))
+}
+
+/// A single entry of a `load-components` request
+#[derive(Debug, PartialEq)]
+struct BatchLoadEntry {
+    path: String,
+    policy: Option<String>,
+}
+
+/// Parse the `components` argument, which lists URIs either as plain strings or as
+/// `{"path": ..., "policy": ...}` objects.
+fn extract_batch_load_entries(req: &CallToolRequestParam) -> Result<Vec<BatchLoadEntry>> {
+    let args = extract_args_from_request(req)?;
+    let components = args
+        .get("components")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| anyhow::anyhow!("Missing required argument: 'components'"))?;
+
+    components
+        .iter()
+        .map(|entry| match entry {
+            Value::String(path) => Ok(BatchLoadEntry {
+                path: path.clone(),
+                policy: None,
+            }),
+            Value::Object(map) => {
+                let path = map
+                    .get("path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("Each component entry needs a 'path'"))?;
+                Ok(BatchLoadEntry {
+                    path: path.to_string(),
+                    policy: map
+                        .get("policy")
+                        .and_then(|v| v.as_str())
+                        .map(str::to_string),
+                })
+            }
+            other => anyhow::bail!("Invalid component entry: {other}"),
+        })
+        .collect()
+}
+
+/// Load one batch entry, returning its report and whether the tool list changed
+async fn load_batch_entry(
+    lifecycle_manager: &LifecycleManager,
+    entry: &BatchLoadEntry,
+) -> (Value, bool) {
+    let outcome = match async {
+        let uri = resolve_component_reference(&entry.path)?;
+        lifecycle_manager.load_component(&uri).await
+    }
+    .await
+    {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            error!(path = %entry.path, error = %e, "Batch component load failed");
+            return (
+                json!({ "path": entry.path, "status": "error", "error": e.to_string() }),
+                false,
+            );
+        }
+    };
+
+    if let Some(policy) = &entry.policy {
+        if let Err(e) = lifecycle_manager
+            .attach_policy(&outcome.component_id, policy)
+            .await
+        {
+            error!(
+                component_id = %outcome.component_id,
+                error = %e,
+                "Failed to attach policy to batch-loaded component"
+            );
+            return (
+                json!({
+                    "path": entry.path,
+                    "id": outcome.component_id,
+                    "status": "error",
+                    "error": format!("Component loaded but policy could not be attached: {e}"),
+                }),
+                true,
+            );
+        }
+    }
+
+    let status = match outcome.status {
+        LoadResult::New => "loaded",
+        LoadResult::Replaced => "reloaded",
+        LoadResult::Unchanged => "unchanged",
+    };
+    (
+        json!({
+            "path": entry.path,
+            "id": outcome.component_id,
+            "status": status,
+            "tools": outcome.tool_names,
+        }),
+        true,
+    )
+}
+
+/// Load several components concurrently. Downloads are still bounded by the lifecycle
+/// manager's download limit, and clients receive a single tool list notification once
+/// every entry has been processed.
+#[instrument(skip_all)]
+pub(crate) async fn handle_load_components(
+    req: &CallToolRequestParam,
+    lifecycle_manager: &LifecycleManager,
+    server_peer: Peer<RoleServer>,
+) -> Result<CallToolResult> {
+    let entries = extract_batch_load_entries(req)?;
+
+    debug!(
+        count = entries.len(),
+        operation = "load-components",
+        "Batch component load started"
+    );
+
+    let reports = futures::future::join_all(
+        entries
+            .iter()
+            .map(|entry| load_batch_entry(lifecycle_manager, entry)),
+    )
+    .await;
+
+    let changed = reports.iter().any(|(_, changed)| *changed);
+    let results: Vec<Value> = reports.into_iter().map(|(report, _)| report).collect();
+    let failed = results.iter().filter(|r| r["status"] == "error").count();
+
+    info!(
+        loaded = results.len() - failed,
+        failed,
+        operation = "load-components",
+        "Batch component load completed"
+    );
+
+    if changed {
+        handle_tool_list_notification(Some(server_peer), "batch", "load").await;
+    }
+
+    let status_text = serde_json::to_string(&json!({
+        "loaded": results.len() - failed,
+        "failed": failed,
+        "results": results,
+    }))?;
+
+    Ok(CallToolResult {
+        content: vec![Content::text(status_text)],
+        structured_content: None,
+        is_error: (!results.is_empty() && failed == results.len()).then_some(true),
+        meta: None,
+    })
+}
+
 #[instrument(skip(lifecycle_manager))]
 pub(crate) async fn handle_unload_component(
     req: &CallToolRequestParam,
     lifecycle_manager: &LifecycleManager,
     server_peer: Peer<RoleServer>,
 ) -> Result<CallToolResult> {
-    let args = extract_args_from_request(req)?;
-    let id = args
-        .get("id")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| anyhow::anyhow!("Missing 'id' in arguments"))?;
+    let (id, ignore_missing) = extract_unload_args(req)?;
 
     debug!(
         component_id = %id,
@@ -103,15 +340,18 @@ pub(crate) async fn handle_unload_component(
         "Component unload operation started"
     );
 
-    match lifecycle_manager.unload_component(id).await {
-        Ok(()) => {
+    match lifecycle_manager.unload_component(&id).await {
+        Ok(outcome) => {
             info!(
                 component_id = %id,
                 operation = "unload-component",
-                "Component unloaded successfully"
+                outcome = ?outcome,
+                "Component unload operation completed"
             );
-            handle_tool_list_notification(Some(server_peer), id, "unload").await;
-            create_component_success_result("unload", id)
+            if outcome == UnloadResult::Unloaded {
+                handle_tool_list_notification(Some(server_peer), &id, "unload").await;
+            }
+            create_unload_component_result(outcome, &id, ignore_missing)
         }
         Err(e) => {
             error!(
@@ -120,11 +360,25 @@ pub(crate) async fn handle_unload_component(
                 error = %e,
                 "Component unload operation failed"
             );
-            Ok(create_component_error_result("unload", id, &e))
+            Ok(create_component_error_result("unload", &id, &e))
         }
     }
 }
 
+/// Extract the component id and the `ignore_missing` flag from an unload request
+fn extract_unload_args(req: &CallToolRequestParam) -> Result<(String, bool)> {
+    let args = extract_args_from_request(req)?;
+    let id = args
+        .get("id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing 'id' in arguments"))?;
+    let ignore_missing = args
+        .get("ignore_missing")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    Ok((id.to_string(), ignore_missing))
+}
+
 #[instrument(skip(lifecycle_manager))]
 pub async fn handle_component_call(
     req: &CallToolRequestParam,
@@ -132,11 +386,40 @@ pub async fn handle_component_call(
 ) -> Result<CallToolResult> {
     let args = extract_args_from_request(req)?;
 
-    let component_id = lifecycle_manager
-        .get_component_id_for_tool(&req.name)
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to find component for tool '{}': {}", req.name, e))?;
+    let component_id = match lifecycle_manager.get_component_id_for_tool(&req.name).await {
+        Ok(component_id) => component_id,
+        // Any component tool can also be called by its prefixed name
+        Err(_) if req.name.contains(TOOL_PREFIX_SEPARATOR) => {
+            return handle_prefixed_component_call(req, lifecycle_manager).await;
+        }
+        Err(e) => bail!("Failed to find component for tool '{}': {}", req.name, e),
+    };
+
+    call_component_tool(req, &component_id, args, lifecycle_manager).await
+}
+
+/// Handle a call to a tool advertised with its component prefix by calling the unprefixed
+/// tool of that component
+#[instrument(skip(lifecycle_manager))]
+pub async fn handle_prefixed_component_call(
+    req: &CallToolRequestParam,
+    lifecycle_manager: &LifecycleManager,
+) -> Result<CallToolResult> {
+    let args = extract_args_from_request(req)?;
+    let (component_id, tool_name) = resolve_prefixed_tool(lifecycle_manager, &req.name).await?;
+    let req = CallToolRequestParam {
+        name: tool_name.into(),
+        arguments: req.arguments.clone(),
+    };
+    call_component_tool(&req, &component_id, args, lifecycle_manager).await
+}
 
+async fn call_component_tool(
+    req: &CallToolRequestParam,
+    component_id: &str,
+    args: serde_json::Map<String, Value>,
+    lifecycle_manager: &LifecycleManager,
+) -> Result<CallToolResult> {
     debug!(
         function_name = %req.name,
         component_id = %component_id,
@@ -144,11 +427,11 @@ pub async fn handle_component_call(
     );
 
     let tool_schema = lifecycle_manager
-        .get_tool_schema_for_component(&component_id, &req.name)
+        .get_tool_schema_for_component(component_id, &req.name)
         .await;
 
     let result = lifecycle_manager
-        .execute_component_call(&component_id, &req.name, &serde_json::to_string(&args)?)
+        .execute_component_call(component_id, &req.name, &serde_json::to_string(&args)?)
         .await;
 
     match result {
@@ -172,7 +455,12 @@ pub async fn handle_component_call(
                 align_structured_result_with_schema(Some(schema), parsed_value.clone())
             });
 
-            let contents = vec![Content::text(response_text)];
+            let uri = format!("wassette://{component_id}/{}", req.name);
+            let contents =
+                vec![
+                    binary_result_content(normalized_schema.as_ref(), &display_value, &uri)
+                        .unwrap_or_else(|| Content::text(response_text)),
+                ];
 
             Ok(CallToolResult {
                 content: contents,
@@ -188,11 +476,48 @@ pub async fn handle_component_call(
                 error = %e,
                 "Component function invocation failed"
             );
+            if let Some(trap) = e.downcast_ref::<ComponentTrap>() {
+                return Ok(create_trap_result(trap));
+            }
             Err(anyhow::anyhow!(e.to_string()))
         }
     }
 }
 
+/// Report a trapped call as a tool error classified as `trap`, so clients can tell a crashed
+/// component from one that returned an error value
+fn create_trap_result(trap: &ComponentTrap) -> CallToolResult {
+    let mut details = serde_json::to_value(trap).unwrap_or_else(|_| json!({}));
+    details["kind"] = json!("trap");
+
+    CallToolResult {
+        content: vec![Content::text(trap.to_string())],
+        structured_content: Some(json!({ "error": details })),
+        is_error: Some(true),
+        meta: None,
+    }
+}
+
+/// Insert the schema `default` of every property missing from `args`, descending into
+/// object arguments that were provided.
+pub(crate) fn apply_schema_defaults(args: &mut serde_json::Map<String, Value>, schema: &Value) {
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return;
+    };
+
+    for (name, property) in properties {
+        match args.get_mut(name) {
+            Some(Value::Object(nested)) => apply_schema_defaults(nested, property),
+            Some(_) => {}
+            None => {
+                if let Some(default) = property.get("default") {
+                    args.insert(name.clone(), default.clone());
+                }
+            }
+        }
+    }
+}
+
 fn parse_structured_result(result: &str) -> Value {
     serde_json::from_str(result).unwrap_or_else(|_| Value::String(result.to_string()))
 }
@@ -226,6 +551,60 @@ fn unwrap_result_wrapper(value: &Value) -> Value {
     value.clone()
 }
 
+/// MCP content for a result declared binary (`list<u8>`) by the tool's output schema: images
+/// become image content and other data an embedded blob. `value` is the unwrapped result.
+fn binary_result_content(
+    output_schema: Option<&Value>,
+    value: &Value,
+    uri: &str,
+) -> Option<Content> {
+    let result_schema = output_schema?.get("properties")?.get("result")?;
+    let encoded = binary_payload(result_schema, value)?;
+    let bytes = BASE64.decode(encoded).ok()?;
+
+    Some(match image_mime_type(&bytes) {
+        Some(mime_type) => Content::image(encoded, mime_type),
+        None => Content::resource(ResourceContents::BlobResourceContents {
+            uri: uri.to_string(),
+            mime_type: Some("application/octet-stream".to_string()),
+            blob: encoded.to_string(),
+            meta: None,
+        }),
+    })
+}
+
+/// Base64 payload of a binary result, looking through the `ok` case of a `result<list<u8>, _>`
+fn binary_payload<'a>(schema: &Value, value: &'a Value) -> Option<&'a str> {
+    if is_binary_schema(schema) {
+        return value.as_str();
+    }
+    let ok_is_binary = schema
+        .get("oneOf")?
+        .as_array()?
+        .iter()
+        .filter_map(|case| case.get("properties")?.get("ok"))
+        .any(is_binary_schema);
+    if ok_is_binary {
+        value.get("ok")?.as_str()
+    } else {
+        None
+    }
+}
+
+/// Recognize common image formats by their magic bytes
+fn image_mime_type(bytes: &[u8]) -> Option<&'static str> {
+    const SIGNATURES: [(&[u8], &str); 3] = [
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"GIF8", "image/gif"),
+    ];
+    if let Some((_, mime_type)) = SIGNATURES.iter().find(|(sig, _)| bytes.starts_with(sig)) {
+        return Some(mime_type);
+    }
+    (bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP")
+        .then_some("image/webp")
+}
+
 fn value_to_text(value: &Value) -> Result<String> {
     match value {
         Value::String(text) => Ok(text.clone()),
@@ -301,14 +680,29 @@ pub(crate) fn extract_args_from_request(
     }
 }
 
-/// Create successful result for component operations
-fn create_component_success_result(
-    operation_name: &str,
+/// Report the outcome of an unload. A missing component is an error unless
+/// `ignore_missing` is set, in which case it is reported as a successful no-op.
+fn create_unload_component_result(
+    outcome: UnloadResult,
     component_id: &str,
+    ignore_missing: bool,
 ) -> Result<CallToolResult> {
+    let status = match outcome {
+        UnloadResult::Unloaded => "component unloaded successfully",
+        UnloadResult::NotFound if ignore_missing => "component not found, nothing to unload",
+        UnloadResult::NotFound => {
+            return Ok(create_component_error_result(
+                "unload",
+                component_id,
+                &anyhow::anyhow!("Component not found: {component_id}"),
+            ));
+        }
+    };
+
     let status_text = serde_json::to_string(&json!({
-        "status": format!("component {}ed successfully", operation_name),
-        "id": component_id
+        "status": status,
+        "id": component_id,
+        "unloaded": outcome == UnloadResult::Unloaded,
     }))?;
 
     let contents = vec![Content::text(status_text)];
@@ -322,16 +716,40 @@ fn create_component_success_result(
 }
 
 fn create_load_component_success_result(outcome: &ComponentLoadOutcome) -> Result<CallToolResult> {
-    let status = match outcome.status {
-        LoadResult::New => "component loaded successfully",
-        LoadResult::Replaced => "component reloaded successfully",
+    let (status, load_result) = match outcome.status {
+        LoadResult::New => ("component loaded successfully", "new"),
+        LoadResult::Replaced => ("component reloaded successfully", "replaced"),
+        LoadResult::Unchanged => ("component already loaded and unchanged", "unchanged"),
+    };
+    let policy = match &outcome.policy {
+        Some(info) => json!({
+            "attached": true,
+            "source": info.source.as_str(),
+            "source_uri": info.source_uri,
+        }),
+        None => json!({
+            "attached": false,
+            "source": PolicySource::Default.as_str(),
+        }),
     };
 
-    let status_text = serde_json::to_string(&json!({
+    let mut result = json!({
         "status": status,
         "id": &outcome.component_id,
+        "load_result": load_result,
         "tools": &outcome.tool_names,
-    }))?;
+        "tool_count": outcome.tool_count(),
+        "new_tools": &outcome.new_tools,
+        "policy": policy,
+    });
+    if let Some(warning) = outcome.warning() {
+        result["warning"] = json!(warning);
+    }
+    if !outcome.declared_permissions.is_empty() {
+        result["declared_permissions"] = json!(&outcome.declared_permissions);
+        result["auto_granted"] = json!(&outcome.auto_granted);
+    }
+    let status_text = serde_json::to_string(&result)?;
 
     let contents = vec![Content::text(status_text)];
 
@@ -343,6 +761,84 @@ fn create_load_component_success_result(outcome: &ComponentLoadOutcome) -> Resul
     })
 }
 
+/// Resolve the `path` argument of a load request to a component URI. References with a
+/// scheme are returned unchanged; anything else is treated as the name of a component in
+/// the bundled registry.
+pub fn resolve_component_reference(path: &str) -> Result<String> {
+    resolve_component_reference_in(COMPONENT_LIST, path)
+}
+
+/// [`resolve_component_reference`] against the registry given as JSON
+fn resolve_component_reference_in(registry: &str, path: &str) -> Result<String> {
+    if path.contains("://") {
+        return Ok(path.to_string());
+    }
+
+    let registry: Value = serde_json::from_str(registry)?;
+    let entries = registry
+        .as_array()
+        .ok_or_else(|| anyhow::anyhow!("Component registry is not an array"))?;
+    let uri = resolve_registry_name(entries, path)?;
+    debug!(name = %path, uri = %uri, "Resolved registry name to component URI");
+    Ok(uri)
+}
+
+/// Find the registry entry for `name`, matching the display name or the image name in the
+/// URI case-insensitively. Exact matches win; otherwise a partial match is accepted only
+/// when it is unique.
+fn resolve_registry_name(entries: &[Value], name: &str) -> Result<String> {
+    let candidates: Vec<(&str, &str)> = entries
+        .iter()
+        .filter_map(|entry| Some((entry["name"].as_str()?, entry["uri"].as_str()?)))
+        .collect();
+
+    let exact: Vec<(&str, &str)> = candidates
+        .iter()
+        .copied()
+        .filter(|(entry_name, uri)| {
+            entry_name.eq_ignore_ascii_case(name)
+                || registry_image_name(uri).eq_ignore_ascii_case(name)
+        })
+        .collect();
+    let matches = if exact.is_empty() {
+        let needle = name.to_lowercase();
+        candidates
+            .into_iter()
+            .filter(|(entry_name, uri)| {
+                entry_name.to_lowercase().contains(&needle)
+                    || registry_image_name(uri).to_lowercase().contains(&needle)
+            })
+            .collect()
+    } else {
+        exact
+    };
+
+    match matches.as_slice() {
+        [] => bail!(
+            "'{name}' is not a component URI and does not match any component in the registry. Use search-components to list available components."
+        ),
+        [(_, uri)] => Ok(uri.to_string()),
+        _ => {
+            let listed: Vec<String> = matches
+                .iter()
+                .map(|(entry_name, uri)| format!("{entry_name} ({uri})"))
+                .collect();
+            bail!(
+                "Registry name '{name}' is ambiguous, it matches: {}",
+                listed.join(", ")
+            )
+        }
+    }
+}
+
+/// Repository name of a component URI without registry, namespace or tag, e.g. `fetch-rs`
+/// for `oci://ghcr.io/microsoft/fetch-rs:latest`
+fn registry_image_name(uri: &str) -> &str {
+    let reference = uri.split_once("://").map_or(uri, |(_, rest)| rest);
+    let repository = reference.rsplit('/').next().unwrap_or(reference);
+    repository.split([':', '@']).next().unwrap_or(repository)
+}
+
 /// Create error result for component operations
 fn create_component_error_result(
     operation_name: &str,
@@ -394,15 +890,23 @@ pub async fn handle_load_component_cli(
     req: &CallToolRequestParam,
     lifecycle_manager: &LifecycleManager,
 ) -> Result<CallToolResult> {
-    let args = extract_args_from_request(req)?;
-    let path = args
-        .get("path")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| anyhow::anyhow!("Missing required argument: 'path'"))?;
+    load_component_cli(req, lifecycle_manager, COMPONENT_LIST).await
+}
 
-    info!(path, "Loading component (CLI mode)");
+/// [`handle_load_component_cli`] resolving registry names against the registry given as JSON
+async fn load_component_cli(
+    req: &CallToolRequestParam,
+    lifecycle_manager: &LifecycleManager,
+    registry: &str,
+) -> Result<CallToolResult> {
+    let (path, options) = extract_load_args(req)?;
+    let path = path.as_str();
+
+    let uri = resolve_component_reference_in(registry, path)?;
 
-    match lifecycle_manager.load_component(path).await {
+    info!(path, uri, "Loading component (CLI mode)");
+
+    match lifecycle_manager.load_component_with(&uri, options).await {
         Ok(outcome) => {
             handle_tool_list_notification(None, &outcome.component_id, "load").await;
             create_load_component_success_result(&outcome)
@@ -418,28 +922,60 @@ pub async fn handle_load_component_cli(
     }
 }
 
+/// Give component `id` the id `new_id`, telling the connected client, if any, that the tool
+/// list changed. Failures are reported as an error result rather than an `Err`.
+#[instrument(skip(lifecycle_manager, server_peer))]
+pub async fn handle_rename_component(
+    lifecycle_manager: &LifecycleManager,
+    id: &str,
+    new_id: &str,
+    server_peer: Option<Peer<RoleServer>>,
+) -> Result<CallToolResult> {
+    if let Err(e) = lifecycle_manager.rename_component(id, new_id).await {
+        error!(component_id = %id, new_id, error = %e, "Failed to rename component");
+        return Ok(create_component_error_result("rename", id, &e));
+    }
+
+    info!(component_id = %new_id, previous_id = %id, "Component renamed");
+    if let Some(peer) = server_peer {
+        if let Err(e) = peer.notify_tool_list_changed().await {
+            error!(error = %e, "Failed to send tool list change notification");
+        }
+    }
+
+    let status_text = serde_json::to_string(&json!({
+        "status": "component renamed successfully",
+        "id": new_id,
+        "previous_id": id,
+    }))?;
+    Ok(CallToolResult {
+        content: vec![Content::text(status_text)],
+        structured_content: None,
+        is_error: None,
+        meta: None,
+    })
+}
+
 /// CLI-specific version of handle_unload_component that doesn't require server peer notifications
 #[instrument(skip(lifecycle_manager))]
 pub async fn handle_unload_component_cli(
     req: &CallToolRequestParam,
     lifecycle_manager: &LifecycleManager,
 ) -> Result<CallToolResult> {
-    let args = extract_args_from_request(req)?;
-    let id = args
-        .get("id")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| anyhow::anyhow!("Missing 'id' in arguments"))?;
+    let (id, ignore_missing) = extract_unload_args(req)?;
 
     info!(component_id = %id, "Unloading component (CLI mode)");
 
-    match lifecycle_manager.unload_component(id).await {
-        Ok(()) => {
-            handle_tool_list_notification(None, id, "unload").await;
-            create_component_success_result("unload", id)
+    match lifecycle_manager.unload_component(&id).await {
+        Ok(outcome) => {
+            if outcome == UnloadResult::Unloaded {
+                handle_tool_list_notification(None, &id, "unload").await;
+            }
+            create_unload_component_result(outcome, &id, ignore_missing)
         }
         Err(e) => {
             error!(error = %e, "Failed to unload component");
-            Ok(create_component_error_result("unload", id, &e))
+            Ok(create_component_error_result("unload", &id, &e))
         }
     }
 }
@@ -493,6 +1029,311 @@ mod tests {
     use serde_json::json;
 
     use super::*;
+    use crate::test_support::answer_component;
+
+    #[test]
+    fn test_apply_schema_defaults() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "url": { "type": "string" },
+                "method": { "type": "string", "default": "GET" },
+                "retries": { "type": "integer", "default": 3 },
+                "options": {
+                    "type": "object",
+                    "properties": {
+                        "follow_redirects": { "type": "boolean", "default": true },
+                        "timeout_ms": { "type": "integer" }
+                    }
+                }
+            },
+            "required": ["url"]
+        });
+
+        let mut args = json!({
+            "url": "https://example.com",
+            "retries": 0,
+            "options": { "timeout_ms": 500 }
+        })
+        .as_object()
+        .unwrap()
+        .clone();
+        apply_schema_defaults(&mut args, &schema);
+
+        assert_eq!(
+            Value::Object(args),
+            json!({
+                "url": "https://example.com",
+                "method": "GET",
+                "retries": 0,
+                "options": { "timeout_ms": 500, "follow_redirects": true }
+            })
+        );
+    }
+
+    #[test]
+    fn test_load_result_includes_declared_permissions() {
+        let declared = wassette::DeclaredPermission {
+            permission_type: "network".to_string(),
+            details: json!({ "host": "api.example.com" }),
+        };
+        let outcome = ComponentLoadOutcome {
+            component_id: "weather".to_string(),
+            status: LoadResult::New,
+            tool_names: vec!["get-weather".to_string()],
+            declared_permissions: vec![declared],
+            auto_granted: Vec::new(),
+            new_tools: vec!["get-weather".to_string()],
+            policy: None,
+        };
+
+        let result = create_load_component_success_result(&outcome).unwrap();
+        let text = result.content[0].as_text().unwrap().text.clone();
+        let body: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(
+            body["declared_permissions"],
+            json!([{ "permission_type": "network", "details": { "host": "api.example.com" } }])
+        );
+        assert_eq!(body["auto_granted"], json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_load_component_reports_tools_and_policy_source() -> Result<()> {
+        let component_dir = tempfile::tempdir()?;
+        let source_dir = tempfile::tempdir()?;
+        let lifecycle_manager = LifecycleManager::new(component_dir.path()).await?;
+
+        let wasm = answer_component(source_dir.path(), "answer", 42, None);
+        let req = CallToolRequestParam {
+            name: "load-component".into(),
+            arguments: Some(
+                json!({"path": format!("file://{}", wasm.display())})
+                    .as_object()
+                    .unwrap()
+                    .clone(),
+            ),
+        };
+        let load = |result: CallToolResult| -> Result<Value> {
+            let text = result.content[0].as_text().unwrap().text.clone();
+            Ok(serde_json::from_str(&text)?)
+        };
+
+        let body = load(handle_load_component_cli(&req, &lifecycle_manager).await?)?;
+        assert_eq!(body["id"], "answer");
+        assert_eq!(body["load_result"], "new");
+        assert_eq!(body["tools"], json!(["answer"]));
+        assert_eq!(body["tool_count"], 1);
+        assert_eq!(body["new_tools"], json!(["answer"]));
+        assert!(body.get("warning").is_none());
+        assert_eq!(
+            body["policy"],
+            json!({"attached": false, "source": "default"})
+        );
+
+        let policy = source_dir.path().join("answer.policy.yaml");
+        std::fs::write(&policy, "version: \"1.0\"\npermissions: {}\n")?;
+        let policy_uri = format!("file://{}", policy.display());
+        lifecycle_manager
+            .attach_policy("answer", &policy_uri)
+            .await?;
+
+        // Loading the same bytes again changes nothing and keeps the attached policy
+        let body = load(handle_load_component_cli(&req, &lifecycle_manager).await?)?;
+        assert_eq!(body["load_result"], "unchanged");
+        assert_eq!(body["tools"], json!(["answer"]));
+        assert_eq!(body["new_tools"], json!([]));
+        assert_eq!(
+            body["policy"],
+            json!({"attached": true, "source": "attached", "source_uri": policy_uri})
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_load_component_by_registry_name() -> Result<()> {
+        let component_dir = tempfile::tempdir()?;
+        let source_dir = tempfile::tempdir()?;
+        let lifecycle_manager = LifecycleManager::new(component_dir.path()).await?;
+
+        let wasm = answer_component(source_dir.path(), "answer", 42, None);
+        let registry = json!([
+            {"name": "Answer Server", "uri": format!("file://{}", wasm.display())},
+            {"name": "Fetch", "uri": "oci://ghcr.io/microsoft/fetch-rs:latest"},
+        ])
+        .to_string();
+        let req = CallToolRequestParam {
+            name: "load-component".into(),
+            arguments: Some(
+                json!({"path": "answer server"})
+                    .as_object()
+                    .unwrap()
+                    .clone(),
+            ),
+        };
+
+        let result = load_component_cli(&req, &lifecycle_manager, &registry).await?;
+        assert_ne!(result.is_error, Some(true));
+        let body: Value = serde_json::from_str(&result.content[0].as_text().unwrap().text)?;
+        assert_eq!(body["id"], "answer");
+        assert_eq!(body["load_result"], "new");
+        assert_eq!(body["tools"], json!(["answer"]));
+        assert_eq!(lifecycle_manager.list_components().await, ["answer"]);
+
+        let result = lifecycle_manager
+            .execute_component_call("answer", "answer", "{}")
+            .await?;
+        assert!(result.contains("42"), "{result}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_load_component_without_tools_reports_zero_tools() -> Result<()> {
+        let component_dir = tempfile::tempdir()?;
+        let source_dir = tempfile::tempdir()?;
+        let lifecycle_manager = LifecycleManager::new(component_dir.path()).await?;
+
+        let wasm = source_dir.path().join("empty.wasm");
+        std::fs::write(&wasm, wat::parse_str("(component)")?)?;
+        let req = CallToolRequestParam {
+            name: "load-component".into(),
+            arguments: Some(
+                json!({"path": format!("file://{}", wasm.display())})
+                    .as_object()
+                    .unwrap()
+                    .clone(),
+            ),
+        };
+
+        let result = handle_load_component_cli(&req, &lifecycle_manager).await?;
+        assert_ne!(result.is_error, Some(true));
+        let body: Value = serde_json::from_str(&result.content[0].as_text().unwrap().text)?;
+        assert_eq!(body["load_result"], "new");
+        assert_eq!(body["tools"], json!([]));
+        assert_eq!(body["tool_count"], 0);
+        assert_eq!(
+            body["warning"],
+            "Component 'empty' loaded but exports no functions that can be called as tools"
+        );
+
+        let result = handle_list_components(&lifecycle_manager).await?;
+        let body: Value = serde_json::from_str(&result.content[0].as_text().unwrap().text)?;
+        assert_eq!(body["components"][0]["id"], "empty");
+        assert_eq!(body["components"][0]["tools_count"], 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_binary_result_content_returns_image() {
+        // generate-qr style signature: result<list<u8>, string>
+        let output_schema = json!({
+            "type": "object",
+            "properties": {
+                "result": {
+                    "oneOf": [
+                        {
+                            "type": "object",
+                            "properties": {
+                                "ok": { "type": "string", "contentEncoding": "base64" }
+                            },
+                            "required": ["ok"]
+                        },
+                        {
+                            "type": "object",
+                            "properties": { "err": { "type": "string" } },
+                            "required": ["err"]
+                        }
+                    ]
+                }
+            },
+            "required": ["result"]
+        });
+        let png = BASE64.encode(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR");
+        let uri = "wassette://qr-generator/generate-qr";
+
+        let content =
+            binary_result_content(Some(&output_schema), &json!({ "ok": png }), uri).unwrap();
+        let image = content
+            .as_image()
+            .expect("PNG results should be image content");
+        assert_eq!(image.mime_type, "image/png");
+        assert_eq!(image.data, png);
+
+        let content = binary_result_content(
+            Some(&json!({ "properties": { "result": { "contentEncoding": "base64" } } })),
+            &json!(BASE64.encode(b"plain bytes")),
+            uri,
+        )
+        .unwrap();
+        assert!(content.as_resource().is_some());
+
+        // Errors and non-binary results stay text
+        assert!(
+            binary_result_content(Some(&output_schema), &json!({ "err": "bad" }), uri).is_none()
+        );
+        assert!(binary_result_content(None, &json!(png), uri).is_none());
+    }
+
+    #[test]
+    fn test_create_trap_result() {
+        let trap = ComponentTrap {
+            component_id: "crashy".to_string(),
+            tool: "boom".to_string(),
+            message: "wasm 'unreachable' instruction executed".to_string(),
+            backtrace: vec!["m!inner".to_string()],
+            truncated_frames: 0,
+        };
+        let result = create_trap_result(&trap);
+
+        assert_eq!(result.is_error, Some(true));
+        let error = &result.structured_content.unwrap()["error"];
+        assert_eq!(error["kind"], "trap");
+        assert_eq!(error["backtrace"], json!(["m!inner"]));
+        assert!(error.get("truncated_frames").is_none());
+    }
+
+    #[test]
+    fn test_resolve_component_reference_keeps_uris() {
+        for uri in [
+            "file:///tmp/component.wasm",
+            "oci://ghcr.io/microsoft/fetch-rs:latest",
+            "https://example.com/component.wasm",
+        ] {
+            assert_eq!(resolve_component_reference(uri).unwrap(), uri);
+        }
+    }
+
+    #[test]
+    fn test_resolve_component_reference_by_registry_name() {
+        let expected = "oci://ghcr.io/microsoft/fetch-rs:latest";
+        assert_eq!(resolve_component_reference("Fetch").unwrap(), expected);
+        assert_eq!(resolve_component_reference("fetch-rs").unwrap(), expected);
+        assert_eq!(
+            resolve_component_reference("time server").unwrap(),
+            "oci://ghcr.io/microsoft/time-server-js:latest"
+        );
+        assert_eq!(
+            resolve_component_reference("context7").unwrap(),
+            "oci://ghcr.io/microsoft/context7-rs:latest"
+        );
+    }
+
+    #[test]
+    fn test_resolve_component_reference_ambiguous_name_lists_matches() {
+        let err = resolve_component_reference("weather")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("ambiguous"), "{err}");
+        assert!(err.contains("oci://ghcr.io/microsoft/get-weather-js:latest"));
+        assert!(err.contains("oci://ghcr.io/microsoft/get-open-meteo-weather-js:latest"));
+    }
+
+    #[test]
+    fn test_resolve_component_reference_unknown_name() {
+        let err = resolve_component_reference("no-such-component")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("does not match any component"), "{err}");
+    }
 
     #[test]
     fn test_parse_tool_schema() {
@@ -550,6 +1391,130 @@ mod tests {
         assert!(args.is_empty());
     }
 
+    fn status_of(result: &CallToolResult) -> Value {
+        let text = serde_json::to_value(&result.content).unwrap()[0]["text"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        serde_json::from_str(&text).unwrap()
+    }
+
+    #[test]
+    fn test_unload_result_reports_unloaded() {
+        let result =
+            create_unload_component_result(UnloadResult::Unloaded, "fetch_rs", false).unwrap();
+        assert_eq!(result.is_error, None);
+        let status = status_of(&result);
+        assert_eq!(status["status"], "component unloaded successfully");
+        assert_eq!(status["unloaded"], true);
+    }
+
+    #[test]
+    fn test_unload_result_for_missing_component() {
+        let strict =
+            create_unload_component_result(UnloadResult::NotFound, "fetch_rs", false).unwrap();
+        assert_eq!(strict.is_error, Some(true));
+        let status = status_of(&strict);
+        assert_eq!(status["status"], "error");
+        assert!(status["message"]
+            .as_str()
+            .unwrap()
+            .contains("Component not found: fetch_rs"));
+
+        let lenient =
+            create_unload_component_result(UnloadResult::NotFound, "fetch_rs", true).unwrap();
+        assert_eq!(lenient.is_error, None);
+        let status = status_of(&lenient);
+        assert_eq!(status["status"], "component not found, nothing to unload");
+        assert_eq!(status["unloaded"], false);
+    }
+
+    #[test]
+    fn test_extract_load_args() {
+        let req = CallToolRequestParam {
+            name: "load-component".into(),
+            arguments: Some(
+                json!({ "path": "./fetch.wasm", "id": "myfetch", "force": true, "force_recompile": true })
+                    .as_object()
+                    .unwrap()
+                    .clone(),
+            ),
+        };
+        assert_eq!(
+            extract_load_args(&req).unwrap(),
+            (
+                "./fetch.wasm".to_string(),
+                LoadOptions {
+                    component_id: Some("myfetch".to_string()),
+                    force: true,
+                    force_recompile: true,
+                }
+            )
+        );
+
+        let req = CallToolRequestParam {
+            name: "load-component".into(),
+            arguments: Some(
+                json!({ "path": "./fetch.wasm" })
+                    .as_object()
+                    .unwrap()
+                    .clone(),
+            ),
+        };
+        assert_eq!(
+            extract_load_args(&req).unwrap(),
+            ("./fetch.wasm".to_string(), LoadOptions::default())
+        );
+    }
+
+    #[test]
+    fn test_extract_batch_load_entries() {
+        let req = CallToolRequestParam {
+            name: "load-components".into(),
+            arguments: Some(
+                json!({
+                    "components": [
+                        "oci://ghcr.io/microsoft/time-server-js:latest",
+                        { "path": "file:///tmp/fetch.wasm", "policy": "file:///tmp/fetch.yaml" }
+                    ]
+                })
+                .as_object()
+                .unwrap()
+                .clone(),
+            ),
+        };
+
+        let entries = extract_batch_load_entries(&req).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                BatchLoadEntry {
+                    path: "oci://ghcr.io/microsoft/time-server-js:latest".to_string(),
+                    policy: None,
+                },
+                BatchLoadEntry {
+                    path: "file:///tmp/fetch.wasm".to_string(),
+                    policy: Some("file:///tmp/fetch.yaml".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_batch_load_entries_rejects_invalid_entries() {
+        let req = CallToolRequestParam {
+            name: "load-components".into(),
+            arguments: Some(json!({ "components": [42] }).as_object().unwrap().clone()),
+        };
+        assert!(extract_batch_load_entries(&req).is_err());
+
+        let req = CallToolRequestParam {
+            name: "load-components".into(),
+            arguments: Some(json!({}).as_object().unwrap().clone()),
+        };
+        assert!(extract_batch_load_entries(&req).is_err());
+    }
+
     #[test]
     fn test_parse_tool_schema_minimal() {
         let tool_json = json!({
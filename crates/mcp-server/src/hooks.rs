@@ -6,11 +6,13 @@
 //! This module provides the [`ServerHooks`] trait for customizing server behavior
 //! and [`MiddlewareStack`] for chaining multiple hooks together.
 
-use rmcp::model::{CallToolRequestParam, CallToolResult, ErrorData, Tool};
+use rmcp::model::{CallToolRequestParam, CallToolResult, ErrorData, Meta, Tool};
 use serde_json::Value;
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use async_trait::async_trait;
+use tokio::time::Instant;
 
 /// Context passed to hooks before a tool call.
 #[derive(Debug)]
@@ -26,10 +28,20 @@ pub struct ToolCallContext<'a> {
     arguments_modified: bool,
     /// Request metadata for sharing data between hooks
     pub metadata: HashMap<String, Value>,
+    /// Trusted values, such as a tenant id derived from authentication, exposed to the
+    /// component as environment variables for this call only. Unlike the arguments these
+    /// never come from the client, so components may rely on them.
+    pub component_context: BTreeMap<String, String>,
     /// Set to true to block execution
     pub blocked: bool,
     /// Reason for blocking (returned to client)
     pub block_reason: Option<String>,
+    /// Name of the hook that blocked the call, recorded by [`MiddlewareStack`]
+    pub blocked_by: Option<String>,
+    /// How long the client should wait before trying again, set by throttling hooks
+    pub retry_after: Option<Duration>,
+    /// How deeply this call is nested in other tool calls, 1 for a call made by a client
+    pub depth: usize,
 }
 
 impl<'a> ToolCallContext<'a> {
@@ -41,8 +53,12 @@ impl<'a> ToolCallContext<'a> {
             original_arguments: &params.arguments,
             arguments_modified: false,
             metadata: HashMap::new(),
+            component_context: BTreeMap::new(),
             blocked: false,
             block_reason: None,
+            blocked_by: None,
+            retry_after: None,
+            depth: crate::call_depth::current_call_depth(),
         }
     }
 
@@ -55,6 +71,15 @@ impl<'a> ToolCallContext<'a> {
         }
     }
 
+    /// Canonical text of the current arguments (see [`canonical_json`](crate::canonical_json)),
+    /// for use as a stable cache or audit key
+    pub fn canonical_arguments(&self) -> String {
+        match self.arguments() {
+            Some(arguments) => crate::canonical_json(&Value::Object(arguments.clone())),
+            None => "{}".to_string(),
+        }
+    }
+
     /// Get mutable reference to arguments, cloning on first access
     pub fn arguments_mut(&mut self) -> &mut Option<serde_json::Map<String, Value>> {
         if !self.arguments_modified {
@@ -75,6 +100,12 @@ impl<'a> ToolCallContext<'a> {
         self.block_reason = Some(reason.into());
     }
 
+    /// Block this tool call and tell the client when it may try again
+    pub fn block_with_retry_after(&mut self, reason: impl Into<String>, retry_after: Duration) {
+        self.block(reason);
+        self.retry_after = Some(retry_after);
+    }
+
     /// Rebuild params with potentially modified arguments.
     /// Only clones if arguments were actually modified.
     pub fn into_params(self, original_params: CallToolRequestParam) -> CallToolRequestParam {
@@ -241,6 +272,8 @@ impl ServerHooks for MiddlewareStack {
             tracing::trace!(hook = middleware.name(), tool = %ctx.tool_name, "before_tool_call");
             middleware.before_tool_call(ctx).await?;
             if ctx.blocked {
+                ctx.blocked_by
+                    .get_or_insert_with(|| middleware.name().to_string());
                 tracing::debug!(
                     hook = middleware.name(),
                     tool = %ctx.tool_name,
@@ -274,16 +307,113 @@ impl ServerHooks for MiddlewareStack {
     }
 }
 
+/// Token-bucket rate limiter for tool calls.
+///
+/// The bucket holds up to `calls_per_minute` tokens and refills continuously. A call made
+/// while it is empty is blocked with the time until the next token as its retry hint.
+#[derive(Debug)]
+pub struct RateLimitMiddleware {
+    capacity: f64,
+    refill_per_sec: f64,
+    bucket: Mutex<TokenBucket>,
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimitMiddleware {
+    /// Allow bursts of up to `calls_per_minute` calls, refilled evenly over a minute.
+    pub fn new(calls_per_minute: u32) -> Self {
+        let capacity = f64::from(calls_per_minute.max(1));
+        Self {
+            capacity,
+            refill_per_sec: capacity / 60.0,
+            bucket: Mutex::new(TokenBucket {
+                tokens: capacity,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Take a token, or return how long until one is available.
+    fn try_acquire(&self) -> Result<(), Duration> {
+        let mut bucket = self.bucket.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            let wait = (1.0 - bucket.tokens) / self.refill_per_sec;
+            // Round up so a client retrying after exactly this long finds a token
+            Err(Duration::from_millis((wait * 1000.0).ceil() as u64))
+        }
+    }
+}
+
+#[async_trait]
+impl ServerHooks for RateLimitMiddleware {
+    async fn before_tool_call(&self, ctx: &mut ToolCallContext<'_>) -> Result<(), ErrorData> {
+        if let Err(retry_after) = self.try_acquire() {
+            ctx.block_with_retry_after(
+                format!(
+                    "Rate limit exceeded, retry in {} ms",
+                    retry_after.as_millis()
+                ),
+                retry_after,
+            );
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "rate_limit"
+    }
+}
+
 /// Create a blocked tool result.
 pub fn blocked_result(reason: &str) -> CallToolResult {
+    blocked_result_with_hook(reason, None)
+}
+
+/// Create a blocked tool result whose structured content also names the hook that blocked
+/// the call, as `{"blocked": true, "reason": ..., "hook": ...}`.
+pub fn blocked_result_with_hook(reason: &str, hook: Option<&str>) -> CallToolResult {
+    blocked_result_with_retry_after(reason, hook, None)
+}
+
+/// Like [`blocked_result_with_hook`], adding `retry_after_ms` to both the structured content
+/// and the result's `_meta` when the blocking hook said when to try again.
+pub fn blocked_result_with_retry_after(
+    reason: &str,
+    hook: Option<&str>,
+    retry_after: Option<Duration>,
+) -> CallToolResult {
+    let mut structured = serde_json::json!({ "blocked": true, "reason": reason });
+    if let Some(hook) = hook {
+        structured["hook"] = Value::from(hook);
+    }
+    let meta = retry_after.map(|retry_after| {
+        let retry_after_ms = Value::from(retry_after.as_millis() as u64);
+        structured["retry_after_ms"] = retry_after_ms.clone();
+        let mut meta = Meta::new();
+        meta.insert("retry_after_ms".to_string(), retry_after_ms);
+        meta
+    });
     CallToolResult {
         content: vec![rmcp::model::Content::text(format!(
             "Tool call blocked: {}",
             reason
         ))],
-        structured_content: None,
+        structured_content: Some(structured),
         is_error: Some(true),
-        meta: None,
+        meta,
     }
 }
 
@@ -573,6 +703,17 @@ mod tests {
         // Should be blocked
         assert!(ctx.blocked);
         assert_eq!(ctx.block_reason, Some("Blocked by policy".to_string()));
+        assert_eq!(ctx.blocked_by.as_deref(), Some("blocking_hook"));
+
+        let result = blocked_result_with_hook("Blocked by policy", ctx.blocked_by.as_deref());
+        assert_eq!(
+            result.structured_content,
+            Some(serde_json::json!({
+                "blocked": true,
+                "reason": "Blocked by policy",
+                "hook": "blocking_hook",
+            }))
+        );
 
         // Hook after blocking hook should NOT be called
         assert!(!*after_hook.called.lock().unwrap());
@@ -711,6 +852,48 @@ mod tests {
         assert!(text.contains("blocked"));
     }
 
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limit_reports_retry_after() {
+        async fn retry_after_ms(
+            stack: &MiddlewareStack,
+            params: &CallToolRequestParam,
+        ) -> Option<u64> {
+            let mut ctx = ToolCallContext::from_params(params);
+            stack.before_tool_call(&mut ctx).await.unwrap();
+            if !ctx.blocked {
+                return None;
+            }
+            let result = blocked_result_with_retry_after(
+                ctx.block_reason.as_deref().unwrap(),
+                ctx.blocked_by.as_deref(),
+                ctx.retry_after,
+            );
+            let structured = result.structured_content.unwrap();
+            assert_eq!(structured["hook"], "rate_limit");
+            let meta = result.meta.unwrap();
+            assert_eq!(meta.get("retry_after_ms"), structured.get("retry_after_ms"));
+            structured["retry_after_ms"].as_u64()
+        }
+
+        let limiter = MiddlewareStack::new().push(RateLimitMiddleware::new(2));
+        let params = make_test_params("test_tool");
+
+        // Two calls fit in the bucket
+        assert_eq!(retry_after_ms(&limiter, &params).await, None);
+        assert_eq!(retry_after_ms(&limiter, &params).await, None);
+
+        // One token refills every 30 seconds
+        let first = retry_after_ms(&limiter, &params).await.unwrap();
+        assert!(first > 0 && first <= 30_000, "{first}");
+
+        tokio::time::advance(Duration::from_secs(10)).await;
+        let second = retry_after_ms(&limiter, &params).await.unwrap();
+        assert!(second > 0 && second < first, "{second} should be below {first}");
+
+        tokio::time::advance(Duration::from_millis(second)).await;
+        assert_eq!(retry_after_ms(&limiter, &params).await, None);
+    }
+
     #[test]
     fn test_on_list_tools_filtering() {
         struct ToolFilter;
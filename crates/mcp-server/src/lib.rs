@@ -56,8 +56,15 @@
 
 pub use wassette::LifecycleManager;
 
+mod call_depth;
+mod call_queue;
+mod canonical;
 mod hooks;
+mod log_level;
 mod server;
+#[cfg(test)]
+#[path = "../../../tests/common/answer.rs"]
+mod test_support;
 
 pub mod components;
 pub mod prompts;
@@ -66,13 +73,28 @@ pub mod tools;
 
 // Re-export hooks
 pub use hooks::{
-    blocked_result, MiddlewareStack, NoOpHooks, ServerHooks, ToolCallContext, ToolResultContext,
+    blocked_result, blocked_result_with_hook, blocked_result_with_retry_after, MiddlewareStack,
+    NoOpHooks, RateLimitMiddleware, ServerHooks, ToolCallContext, ToolResultContext,
 };
 
 // Re-export server
-pub use server::{McpServer, McpServerBuilder};
+pub use server::{McpServer, McpServerBuilder, Transport};
+
+// Re-export runtime log level control
+pub use log_level::{level_filter, LogLevelControl};
+
+// Re-export call depth limit
+pub use call_depth::{current_call_depth, DEFAULT_MAX_CALL_DEPTH};
+
+// Re-export argument canonicalization for cache and audit keys
+pub use canonical::canonical_json;
+
+// Re-export call queue
+pub use call_queue::{CallQueue, CallQueueStats, DEFAULT_QUEUE_MAX_WAIT, SERVER_BUSY_ERROR_CODE};
 
 // Re-export handlers (for advanced use cases)
 pub use prompts::{handle_prompts_get, handle_prompts_list};
 pub use resources::handle_resources_list;
-pub use tools::{handle_tools_call, handle_tools_list};
+pub use tools::{
+    handle_tools_call, handle_tools_list, ToolCallOptions, ToolListOptions, ToolTimeouts,
+};
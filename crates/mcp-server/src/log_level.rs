@@ -0,0 +1,52 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Changing the server's log verbosity at runtime through the MCP `logging/setLevel` request.
+//!
+//! The server does not own the `tracing` subscriber, so the embedder hands it a
+//! [`LogLevelControl`] that applies a new level, typically by reloading the filter layer
+//! through a `tracing_subscriber::reload::Handle`.
+
+use std::fmt;
+use std::sync::Arc;
+
+use anyhow::Result;
+use rmcp::model::LoggingLevel;
+use tracing::level_filters::LevelFilter;
+
+type ApplyLevel = dyn Fn(LevelFilter) -> Result<()> + Send + Sync;
+
+/// Applies the log level requested by a client
+#[derive(Clone)]
+pub struct LogLevelControl(Arc<ApplyLevel>);
+
+impl LogLevelControl {
+    /// Control that calls `apply` with the level each `logging/setLevel` request asks for
+    pub fn new(apply: impl Fn(LevelFilter) -> Result<()> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(apply))
+    }
+
+    pub(crate) fn set(&self, level: LoggingLevel) -> Result<()> {
+        (self.0)(level_filter(level))
+    }
+}
+
+impl fmt::Debug for LogLevelControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LogLevelControl").finish_non_exhaustive()
+    }
+}
+
+/// The `tracing` level closest to an MCP (syslog) level. `tracing` has no levels above
+/// error, so the more severe MCP levels all map to it, and `notice` maps to info.
+pub fn level_filter(level: LoggingLevel) -> LevelFilter {
+    match level {
+        LoggingLevel::Debug => LevelFilter::DEBUG,
+        LoggingLevel::Info | LoggingLevel::Notice => LevelFilter::INFO,
+        LoggingLevel::Warning => LevelFilter::WARN,
+        LoggingLevel::Error
+        | LoggingLevel::Critical
+        | LoggingLevel::Alert
+        | LoggingLevel::Emergency => LevelFilter::ERROR,
+    }
+}
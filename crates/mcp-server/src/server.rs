@@ -6,21 +6,46 @@
 //! This module provides [`McpServer`] which implements the MCP protocol
 //! and can be customized via [`ServerHooks`].
 
+use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, RwLock};
+use std::time::Duration;
 
+use opentelemetry::propagation::TextMapPropagator;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
 use rmcp::model::{
-    CallToolRequestParam, CallToolResult, ErrorData, ListPromptsResult, ListResourcesResult,
-    ListToolsResult, PaginatedRequestParam, ServerCapabilities, ServerInfo, ToolsCapability,
+    CallToolRequestParam, CallToolResult, ErrorData, JsonObject, ListPromptsResult,
+    ListResourcesResult, ListToolsResult, PaginatedRequestParam, ServerCapabilities, ServerInfo,
+    SetLevelRequestParam, Tool, ToolsCapability,
 };
 use rmcp::service::{RequestContext, RoleServer};
 use rmcp::ServerHandler;
-
-use crate::hooks::{blocked_result, NoOpHooks, ServerHooks, ToolCallContext, ToolResultContext};
-use crate::{handle_prompts_list, handle_resources_list, handle_tools_call, handle_tools_list};
+use tracing::Instrument;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::call_depth::{self, DEFAULT_MAX_CALL_DEPTH};
+use crate::call_queue::{CallQueue, CallQueueStats, DEFAULT_QUEUE_MAX_WAIT};
+use crate::components::apply_schema_defaults;
+use crate::hooks::{
+    blocked_result_with_retry_after, NoOpHooks, ServerHooks, ToolCallContext, ToolResultContext,
+};
+use crate::log_level::LogLevelControl;
+use crate::{
+    handle_prompts_list, handle_resources_list, handle_tools_call, handle_tools_list,
+    ToolCallOptions, ToolListOptions, ToolTimeouts,
+};
 use wassette::LifecycleManager;
 
+/// Transport an MCP server is reached over, which can select the instructions it sends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Transport {
+    /// A single local client over stdin and stdout
+    Stdio,
+    /// Clients connecting over SSE or streamable HTTP, possibly shared between users
+    Http,
+}
+
 /// MCP server for running WebAssembly components.
 ///
 /// # Example
@@ -39,10 +64,23 @@ use wassette::LifecycleManager;
 #[derive(Clone)]
 pub struct McpServer {
     lifecycle_manager: LifecycleManager,
+    /// Peer used for background notifications, shared by every clone of the server. The
+    /// mutex is never held across an await, so clones serving different transports can race
+    /// to store their peer; the first one wins.
     peer: Arc<Mutex<Option<rmcp::Peer<rmcp::RoleServer>>>>,
-    disable_builtin_tools: bool,
+    /// Shared by every clone, so limits changed with [`set_call_limits`](Self::set_call_limits)
+    /// reach sessions that are already open
+    call_options: Arc<RwLock<Arc<ToolCallOptions>>>,
     hooks: Arc<dyn ServerHooks>,
     instructions: Option<String>,
+    transport_instructions: Arc<HashMap<Transport, String>>,
+    transport: Option<Transport>,
+    instructions_appended: Option<String>,
+    group_component_tools: bool,
+    schema_defaults: bool,
+    call_queue: CallQueue,
+    max_call_depth: usize,
+    log_level: Option<LogLevelControl>,
 }
 
 impl McpServer {
@@ -55,9 +93,20 @@ impl McpServer {
         Self {
             lifecycle_manager,
             peer: Arc::new(Mutex::new(None)),
-            disable_builtin_tools,
+            call_options: Arc::new(RwLock::new(Arc::new(ToolCallOptions {
+                disable_builtin_tools,
+                ..Default::default()
+            }))),
             hooks: Arc::new(NoOpHooks),
             instructions: None,
+            transport_instructions: Arc::default(),
+            transport: None,
+            instructions_appended: None,
+            group_component_tools: false,
+            schema_defaults: false,
+            call_queue: CallQueue::default(),
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            log_level: None,
         }
     }
 
@@ -68,7 +117,7 @@ impl McpServer {
 
     /// Store the peer for background notifications (called on first request).
     fn store_peer_if_empty(&self, peer: rmcp::Peer<rmcp::RoleServer>) {
-        let mut peer_guard = self.peer.lock().unwrap();
+        let mut peer_guard = self.peer_slot();
         if peer_guard.is_none() {
             *peer_guard = Some(peer);
         }
@@ -76,7 +125,13 @@ impl McpServer {
 
     /// Get a clone of the stored peer if available.
     pub fn get_peer(&self) -> Option<rmcp::Peer<rmcp::RoleServer>> {
-        self.peer.lock().unwrap().clone()
+        self.peer_slot().clone()
+    }
+
+    /// Lock the stored peer. A panic while the lock was held cannot leave the `Option` half
+    /// written, so a poisoned lock is recovered rather than taking every other request down.
+    fn peer_slot(&self) -> MutexGuard<'_, Option<rmcp::Peer<rmcp::RoleServer>>> {
+        self.peer.lock().unwrap_or_else(PoisonError::into_inner)
     }
 
     /// Get the lifecycle manager.
@@ -84,6 +139,88 @@ impl McpServer {
         &self.lifecycle_manager
     }
 
+    /// Number of tool calls running and waiting, shared by every clone of this server.
+    pub fn call_queue_stats(&self) -> CallQueueStats {
+        self.call_queue.stats()
+    }
+
+    /// Instructions sent to clients: the body set for the active transport, else the custom or
+    /// default body, followed by any appended text.
+    fn instructions(&self) -> String {
+        let body = self
+            .transport
+            .and_then(|transport| self.transport_instructions.get(&transport))
+            .or(self.instructions.as_ref())
+            .cloned()
+            .unwrap_or_else(Self::default_instructions);
+        match &self.instructions_appended {
+            Some(extra) => format!("{body}\n\n{extra}"),
+            None => body,
+        }
+    }
+
+    /// The options tool calls are handled with
+    pub fn call_options(&self) -> Arc<ToolCallOptions> {
+        self.call_options
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Apply `timeouts` and `max_output_bytes` to the tool calls made from now on, in every
+    /// clone of this server. Calls already running keep the limits they started with.
+    pub fn set_call_limits(&self, timeouts: ToolTimeouts, max_output_bytes: Option<usize>) {
+        let mut call_options = self
+            .call_options
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        *call_options = Arc::new(ToolCallOptions {
+            timeouts,
+            max_output_bytes,
+            ..(**call_options).clone()
+        });
+    }
+
+    /// The tools as clients see them: component and built-in tools, as adjusted by the hooks
+    async fn advertised_tools(&self) -> Result<Vec<Tool>, ErrorData> {
+        let call_options = self.call_options();
+        let value = handle_tools_list(
+            &self.lifecycle_manager,
+            ToolListOptions {
+                disable_builtin_tools: call_options.disable_builtin_tools,
+                prefix_component_tools: call_options.prefix_component_tools,
+                group_component_tools: self.group_component_tools,
+            },
+        )
+        .await
+        .map_err(|err| ErrorData::parse_error(err.to_string(), None))?;
+        let mut list_result: ListToolsResult = serde_json::from_value(value)
+            .map_err(|e| ErrorData::parse_error(format!("Failed to parse result: {e}"), None))?;
+        self.hooks.on_list_tools(&mut list_result.tools);
+        Ok(list_result.tools)
+    }
+
+    /// Fill in the arguments `params` leaves out with the `default` values of the input
+    /// schema its tool is advertised with. Calls to tools that are not advertised are
+    /// returned unchanged so they fail the usual way.
+    async fn fill_schema_defaults(&self, mut params: CallToolRequestParam) -> CallToolRequestParam {
+        let tools = match self.advertised_tools().await {
+            Ok(tools) => tools,
+            Err(e) => {
+                tracing::warn!(error = ?e, "Could not list tools to fill schema defaults");
+                return params;
+            }
+        };
+        if let Some(tool) = tools.iter().find(|tool| tool.name == params.name) {
+            let input_schema = serde_json::Value::Object(tool.input_schema.as_ref().clone());
+            apply_schema_defaults(
+                params.arguments.get_or_insert_with(Default::default),
+                &input_schema,
+            );
+        }
+        params
+    }
+
     fn default_instructions() -> String {
         r#"This server runs tools in sandboxed WebAssembly environments with no default access to host resources.
 
@@ -106,13 +243,10 @@ impl ServerHandler for McpServer {
                 tools: Some(ToolsCapability {
                     list_changed: Some(true),
                 }),
+                logging: self.log_level.as_ref().map(|_| JsonObject::new()),
                 ..Default::default()
             },
-            instructions: Some(
-                self.instructions
-                    .clone()
-                    .unwrap_or_else(Self::default_instructions),
-            ),
+            instructions: Some(self.instructions()),
             ..Default::default()
         }
     }
@@ -125,70 +259,105 @@ impl ServerHandler for McpServer {
         let peer_clone = ctx.peer.clone();
         self.store_peer_if_empty(peer_clone.clone());
 
-        let disable_builtin_tools = self.disable_builtin_tools;
         let hooks = self.hooks.clone();
 
-        Box::pin(async move {
-            let start_time = std::time::Instant::now();
-
-            // Create hook context (no cloning yet - arguments borrowed)
-            let mut tool_ctx = ToolCallContext::from_params(&params);
-            let tool_name = tool_ctx.tool_name.clone();
-
-            // Run before hooks
-            if let Err(e) = hooks.before_tool_call(&mut tool_ctx).await {
-                tracing::error!(error = ?e, "Hook before_tool_call failed");
-                return Err(e);
-            }
+        let span = tracing::info_span!("tools/call", tool = %params.name, otel.kind = "server");
+        if let Some(parent) = remote_trace_context(&ctx.meta) {
+            let _ = span.set_parent(parent);
+        }
 
-            // Check if blocked
-            if tool_ctx.blocked {
-                let reason = tool_ctx
-                    .block_reason
-                    .unwrap_or_else(|| "Blocked by hook".to_string());
-                tracing::info!(tool = %tool_name, reason = %reason, "Tool call blocked");
-                return Ok(blocked_result(&reason));
-            }
+        Box::pin(call_depth::nested(
+            self.max_call_depth,
+            async move {
+                let start_time = std::time::Instant::now();
 
-            // Get params - only clones arguments if they were modified by hooks
-            let metadata = tool_ctx.metadata.clone();
-            let final_params = tool_ctx.into_params(params.clone());
+                // Create hook context (no cloning yet - arguments borrowed)
+                let mut tool_ctx = ToolCallContext::from_params(&params);
+                let tool_name = tool_ctx.tool_name.clone();
 
-            // Execute the tool
-            let result = handle_tools_call(
-                final_params,
-                &self.lifecycle_manager,
-                peer_clone,
-                disable_builtin_tools,
-            )
-            .await;
+                // Run before hooks
+                if let Err(e) = hooks.before_tool_call(&mut tool_ctx).await {
+                    tracing::error!(error = ?e, "Hook before_tool_call failed");
+                    return Err(e);
+                }
 
-            let duration = start_time.elapsed();
+                // Check if blocked
+                if tool_ctx.blocked {
+                    let reason = tool_ctx
+                        .block_reason
+                        .unwrap_or_else(|| "Blocked by hook".to_string());
+                    let hook = tool_ctx
+                        .blocked_by
+                        .unwrap_or_else(|| hooks.name().to_string());
+                    tracing::info!(
+                        tool = %tool_name,
+                        reason = %reason,
+                        hook = %hook,
+                        "Tool call blocked"
+                    );
+                    return Ok(blocked_result_with_retry_after(
+                        &reason,
+                        Some(&hook),
+                        tool_ctx.retry_after,
+                    ));
+                }
 
-            match result {
-                Ok(value) => {
-                    let call_result: CallToolResult = serde_json::from_value(value).map_err(|e| {
-                        ErrorData::parse_error(format!("Failed to parse result: {e}"), None)
-                    })?;
-
-                    // Run after hooks
-                    let mut result_ctx = ToolResultContext {
-                        tool_name,
-                        result: call_result,
-                        metadata,
-                        duration,
-                    };
-
-                    if let Err(e) = hooks.after_tool_call(&mut result_ctx).await {
-                        tracing::error!(error = ?e, "Hook after_tool_call failed");
-                        return Err(e);
+                // Held until the call completes; waits or fails when the queue is full
+                let _slot = self.call_queue.acquire().await.inspect_err(|_| {
+                    tracing::warn!(tool = %tool_name, "Tool call rejected, call queue is full");
+                })?;
+
+                // Get params - only clones arguments if they were modified by hooks
+                let metadata = tool_ctx.metadata.clone();
+                let component_context = std::mem::take(&mut tool_ctx.component_context);
+                let final_params = tool_ctx.into_params(params.clone());
+                let final_params = if self.schema_defaults {
+                    self.fill_schema_defaults(final_params).await
+                } else {
+                    final_params
+                };
+
+                // Execute the tool
+                let result = wassette::with_call_context(
+                    component_context,
+                    handle_tools_call(
+                        final_params,
+                        &self.lifecycle_manager,
+                        peer_clone,
+                        &self.call_options(),
+                    ),
+                )
+                .await;
+
+                let duration = start_time.elapsed();
+
+                match result {
+                    Ok(value) => {
+                        let call_result: CallToolResult =
+                            serde_json::from_value(value).map_err(|e| {
+                                ErrorData::parse_error(format!("Failed to parse result: {e}"), None)
+                            })?;
+
+                        // Run after hooks
+                        let mut result_ctx = ToolResultContext {
+                            tool_name,
+                            result: call_result,
+                            metadata,
+                            duration,
+                        };
+
+                        if let Err(e) = hooks.after_tool_call(&mut result_ctx).await {
+                            tracing::error!(error = ?e, "Hook after_tool_call failed");
+                            return Err(e);
+                        }
+
+                        Ok(result_ctx.result)
                     }
-
-                    Ok(result_ctx.result)
+                    Err(err) => Err(ErrorData::parse_error(err.to_string(), None)),
                 }
-                Err(err) => Err(ErrorData::parse_error(err.to_string(), None)),
             }
-        })
+            .instrument(span),
+        ))
     }
 
     fn list_tools<'a>(
@@ -198,26 +367,32 @@ impl ServerHandler for McpServer {
     ) -> Pin<Box<dyn Future<Output = Result<ListToolsResult, ErrorData>> + Send + 'a>> {
         self.store_peer_if_empty(ctx.peer.clone());
 
-        let disable_builtin_tools = self.disable_builtin_tools;
-        let hooks = self.hooks.clone();
-
         Box::pin(async move {
-            let result = handle_tools_list(&self.lifecycle_manager, disable_builtin_tools).await;
-
-            match result {
-                Ok(value) => {
-                    let mut list_result: ListToolsResult =
-                        serde_json::from_value(value).map_err(|e| {
-                            ErrorData::parse_error(format!("Failed to parse result: {e}"), None)
-                        })?;
+            Ok(ListToolsResult::with_all_items(
+                self.advertised_tools().await?,
+            ))
+        })
+    }
 
-                    // Run hook
-                    hooks.on_list_tools(&mut list_result.tools);
+    fn set_level<'a>(
+        &'a self,
+        params: SetLevelRequestParam,
+        ctx: RequestContext<RoleServer>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), ErrorData>> + Send + 'a>> {
+        self.store_peer_if_empty(ctx.peer.clone());
 
-                    Ok(list_result)
-                }
-                Err(err) => Err(ErrorData::parse_error(err.to_string(), None)),
-            }
+        Box::pin(async move {
+            let Some(control) = &self.log_level else {
+                return Err(ErrorData::invalid_request(
+                    "This server does not support changing its log level",
+                    None,
+                ));
+            };
+            control
+                .set(params.level)
+                .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+            tracing::info!(level = ?params.level, "Log level changed by client");
+            Ok(())
         })
     }
 
@@ -280,6 +455,19 @@ pub struct McpServerBuilder {
     disable_builtin_tools: bool,
     hooks: Option<Arc<dyn ServerHooks>>,
     instructions: Option<String>,
+    transport_instructions: HashMap<Transport, String>,
+    transport: Option<Transport>,
+    instructions_appended: Option<String>,
+    tool_timeouts: ToolTimeouts,
+    max_tool_output_bytes: Option<usize>,
+    schema_defaults: bool,
+    prefix_component_tools: bool,
+    group_component_tools: bool,
+    call_queue_depth: Option<usize>,
+    reject_when_full: bool,
+    call_queue_max_wait: Duration,
+    max_call_depth: usize,
+    log_level: Option<LogLevelControl>,
 }
 
 impl McpServerBuilder {
@@ -290,6 +478,19 @@ impl McpServerBuilder {
             disable_builtin_tools: false,
             hooks: None,
             instructions: None,
+            transport_instructions: HashMap::new(),
+            transport: None,
+            instructions_appended: None,
+            tool_timeouts: ToolTimeouts::default(),
+            max_tool_output_bytes: None,
+            schema_defaults: false,
+            prefix_component_tools: false,
+            group_component_tools: false,
+            call_queue_depth: None,
+            reject_when_full: false,
+            call_queue_max_wait: DEFAULT_QUEUE_MAX_WAIT,
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            log_level: None,
         }
     }
 
@@ -317,21 +518,146 @@ impl McpServerBuilder {
         self
     }
 
+    /// Set the instructions sent to clients connected over `transport`, in place of those set
+    /// with [`with_instructions`](Self::with_instructions) or the default ones. Applies once
+    /// the server is told its transport with [`with_transport`](Self::with_transport).
+    pub fn with_instructions_for(
+        mut self,
+        transport: Transport,
+        instructions: impl Into<String>,
+    ) -> Self {
+        self.transport_instructions
+            .insert(transport, instructions.into());
+        self
+    }
+
+    /// Declare the transport the server is served over, selecting the instructions set for it
+    /// with [`with_instructions_for`](Self::with_instructions_for).
+    pub fn with_transport(mut self, transport: Transport) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// Add text after the server instructions instead of replacing them, keeping the default
+    /// sandboxing guidance (or the text set with [`with_instructions`](Self::with_instructions)).
+    pub fn with_instructions_appended(mut self, extra: impl Into<String>) -> Self {
+        self.instructions_appended = Some(extra.into());
+        self
+    }
+
+    /// Set server-side time limits for tool calls.
+    pub fn with_tool_timeouts(mut self, timeouts: ToolTimeouts) -> Self {
+        self.tool_timeouts = timeouts;
+        self
+    }
+
+    /// Limit the size of text returned by a tool call; longer output is truncated.
+    pub fn with_max_tool_output_bytes(mut self, max_bytes: Option<usize>) -> Self {
+        self.max_tool_output_bytes = max_bytes;
+        self
+    }
+
+    /// Fill in arguments omitted from tool calls with the `default` values of the input schema
+    /// the tool is advertised with, including defaults added by
+    /// [`ServerHooks::on_list_tools`], before the tool runs.
+    pub fn with_schema_defaults(mut self, enabled: bool) -> Self {
+        self.schema_defaults = enabled;
+        self
+    }
+
+    /// Advertise every component tool as `<component id>__<tool name>` and route calls by
+    /// that prefix, so that each component looks like its own namespace to clients. Built-in
+    /// tools keep their names.
+    pub fn with_component_tool_prefixes(mut self, enabled: bool) -> Self {
+        self.prefix_component_tools = enabled;
+        self
+    }
+
+    /// Tag every component tool in `tools/list` with the id of its component as the `group`
+    /// entry of the tool's `_meta`, so clients can group tools by component.
+    pub fn with_component_tool_groups(mut self, enabled: bool) -> Self {
+        self.group_component_tools = enabled;
+        self
+    }
+
+    /// Run at most `depth` tool calls at once; further calls wait for a running one to finish.
+    /// Unlimited when `None`, the default.
+    pub fn with_call_queue_depth(mut self, depth: Option<usize>) -> Self {
+        self.call_queue_depth = depth;
+        self
+    }
+
+    /// Fail calls with a "server busy" error instead of waiting when the call queue set by
+    /// [`with_call_queue_depth`](Self::with_call_queue_depth) is full.
+    pub fn with_reject_when_full(mut self, reject: bool) -> Self {
+        self.reject_when_full = reject;
+        self
+    }
+
+    /// Fail calls with a "server busy" error once they have waited `max_wait` for the call
+    /// queue set by [`with_call_queue_depth`](Self::with_call_queue_depth). Defaults to
+    /// [`DEFAULT_QUEUE_MAX_WAIT`].
+    pub fn with_call_queue_max_wait(mut self, max_wait: Duration) -> Self {
+        self.call_queue_max_wait = max_wait;
+        self
+    }
+
+    /// Reject tool calls nested more than `depth` levels deep, such as a hook or meta-tool
+    /// that keeps calling tools. Defaults to [`DEFAULT_MAX_CALL_DEPTH`].
+    pub fn with_max_call_depth(mut self, depth: usize) -> Self {
+        self.max_call_depth = depth;
+        self
+    }
+
+    /// Advertise the MCP logging capability and apply the levels clients send with
+    /// `logging/setLevel` through `control`. Without it such requests are refused.
+    pub fn with_log_level_control(mut self, control: LogLevelControl) -> Self {
+        self.log_level = Some(control);
+        self
+    }
+
     /// Build the server.
     pub fn build(self) -> McpServer {
         McpServer {
             lifecycle_manager: self.lifecycle_manager,
             peer: Arc::new(Mutex::new(None)),
-            disable_builtin_tools: self.disable_builtin_tools,
+            call_options: Arc::new(RwLock::new(Arc::new(ToolCallOptions {
+                disable_builtin_tools: self.disable_builtin_tools,
+                timeouts: self.tool_timeouts,
+                max_output_bytes: self.max_tool_output_bytes,
+                prefix_component_tools: self.prefix_component_tools,
+            }))),
             hooks: self.hooks.unwrap_or_else(|| Arc::new(NoOpHooks)),
             instructions: self.instructions,
+            transport_instructions: Arc::new(self.transport_instructions),
+            transport: self.transport,
+            instructions_appended: self.instructions_appended,
+            group_component_tools: self.group_component_tools,
+            schema_defaults: self.schema_defaults,
+            call_queue: CallQueue::new(self.call_queue_depth, self.reject_when_full)
+                .with_max_wait(self.call_queue_max_wait),
+            max_call_depth: self.max_call_depth,
+            log_level: self.log_level,
         }
     }
 }
 
+/// Trace context sent by the client as W3C `traceparent`/`tracestate` entries in the request `_meta`
+fn remote_trace_context(meta: &JsonObject) -> Option<opentelemetry::Context> {
+    if !meta.contains_key("traceparent") {
+        return None;
+    }
+    let carrier: HashMap<String, String> = meta
+        .iter()
+        .filter_map(|(key, value)| Some((key.to_lowercase(), value.as_str()?.to_string())))
+        .collect();
+    Some(TraceContextPropagator::new().extract(&carrier))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::answer_component;
     use crate::MiddlewareStack;
     use rmcp::model::Tool;
     use serde_json::json;
@@ -354,7 +680,7 @@ mod tests {
         let server = McpServer::new(lifecycle_manager, false);
 
         // Verify default state
-        assert!(!server.disable_builtin_tools);
+        assert!(!server.call_options().disable_builtin_tools);
         assert!(server.instructions.is_none());
         assert!(server.get_peer().is_none());
     }
@@ -364,7 +690,7 @@ mod tests {
         let lifecycle_manager = create_test_lifecycle_manager().await;
         let server = McpServer::new(lifecycle_manager, true);
 
-        assert!(server.disable_builtin_tools);
+        assert!(server.call_options().disable_builtin_tools);
     }
 
     // ==================== McpServerBuilder Tests ====================
@@ -375,7 +701,7 @@ mod tests {
         let server = McpServer::builder(lifecycle_manager).build();
 
         // Default: builtin tools enabled, no custom instructions
-        assert!(!server.disable_builtin_tools);
+        assert!(!server.call_options().disable_builtin_tools);
         assert!(server.instructions.is_none());
     }
 
@@ -386,7 +712,7 @@ mod tests {
             .with_builtin_tools_disabled(true)
             .build();
 
-        assert!(server.disable_builtin_tools);
+        assert!(server.call_options().disable_builtin_tools);
     }
 
     #[tokio::test]
@@ -396,7 +722,37 @@ mod tests {
             .with_builtin_tools_disabled(false)
             .build();
 
-        assert!(!server.disable_builtin_tools);
+        assert!(!server.call_options().disable_builtin_tools);
+    }
+
+    #[tokio::test]
+    async fn test_builder_with_schema_defaults() {
+        let lifecycle_manager = create_test_lifecycle_manager().await;
+        let server = McpServer::builder(lifecycle_manager.clone()).build();
+        assert!(!server.schema_defaults);
+
+        let server = McpServer::builder(lifecycle_manager)
+            .with_schema_defaults(true)
+            .build();
+        assert!(server.schema_defaults);
+    }
+
+    #[tokio::test]
+    async fn test_builder_with_call_queue() {
+        let lifecycle_manager = create_test_lifecycle_manager().await;
+        let server = McpServer::builder(lifecycle_manager.clone()).build();
+        assert_eq!(server.call_queue_stats().capacity, None);
+
+        let server = McpServer::builder(lifecycle_manager)
+            .with_call_queue_depth(Some(1))
+            .with_reject_when_full(true)
+            .build();
+        let clone = server.clone();
+        let _running = server.call_queue.acquire().await.unwrap();
+
+        // Clones share the queue, as servers are cloned per session
+        assert_eq!(clone.call_queue_stats().depth, 1);
+        assert!(clone.call_queue.acquire().await.is_err());
     }
 
     #[tokio::test]
@@ -432,7 +788,7 @@ mod tests {
             .with_instructions("Chained instructions")
             .build();
 
-        assert!(server.disable_builtin_tools);
+        assert!(server.call_options().disable_builtin_tools);
         assert_eq!(
             server.instructions,
             Some("Chained instructions".to_string())
@@ -679,6 +1035,58 @@ mod tests {
         assert_eq!(info.instructions.unwrap(), custom);
     }
 
+    #[tokio::test]
+    async fn test_get_info_appends_instructions_to_default() {
+        let lifecycle_manager = create_test_lifecycle_manager().await;
+        let extra = "Components in this deployment may only reach *.corp.example.com.";
+
+        let server = McpServer::builder(lifecycle_manager)
+            .with_instructions_appended(extra)
+            .build();
+
+        let instructions = server.get_info().instructions.unwrap();
+        assert_eq!(
+            instructions,
+            format!("{}\n\n{extra}", McpServer::default_instructions())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_info_returns_instructions_for_the_active_transport() {
+        let lifecycle_manager = create_test_lifecycle_manager().await;
+        let builder = || {
+            McpServer::builder(lifecycle_manager.clone())
+                .with_instructions("General instructions")
+                .with_instructions_for(Transport::Stdio, "Local development server")
+                .with_instructions_for(Transport::Http, "Shared server, ask before loading")
+        };
+
+        let http = builder().with_transport(Transport::Http).build();
+        assert_eq!(
+            http.get_info().instructions.unwrap(),
+            "Shared server, ask before loading"
+        );
+        let stdio = builder().with_transport(Transport::Stdio).build();
+        assert_eq!(
+            stdio.get_info().instructions.unwrap(),
+            "Local development server"
+        );
+
+        // Without a transport, or without instructions for it, the general ones apply
+        assert_eq!(
+            builder().build().get_info().instructions.unwrap(),
+            "General instructions"
+        );
+        let stdio_only = McpServer::builder(lifecycle_manager)
+            .with_instructions_for(Transport::Stdio, "Local development server")
+            .with_transport(Transport::Http)
+            .build();
+        assert_eq!(
+            stdio_only.get_info().instructions.unwrap(),
+            McpServer::default_instructions()
+        );
+    }
+
     #[tokio::test]
     async fn test_get_info_capabilities() {
         let lifecycle_manager = create_test_lifecycle_manager().await;
@@ -715,10 +1123,438 @@ mod tests {
         let cloned = server.clone();
 
         // Verify cloned server has same configuration
-        assert!(cloned.disable_builtin_tools);
+        assert!(cloned.call_options().disable_builtin_tools);
         assert_eq!(cloned.instructions, Some("Test instructions".to_string()));
     }
 
+    /// Hook standing in for authentication: it resolves the caller's tenant and hands it to
+    /// the component
+    struct TenantHook;
+
+    #[async_trait]
+    impl ServerHooks for TenantHook {
+        async fn before_tool_call(&self, ctx: &mut ToolCallContext<'_>) -> Result<(), ErrorData> {
+            ctx.metadata.insert("tenant".to_string(), json!("acme"));
+            ctx.component_context
+                .insert("TENANT_ID".to_string(), "acme".to_string());
+            Ok(())
+        }
+
+        fn name(&self) -> &'static str {
+            "tenant_hook"
+        }
+    }
+
+    /// Load a component whose `first-env` tool returns the value of the first variable in its
+    /// WASI environment
+    async fn env_echo_manager(component_dir: &std::path::Path) -> LifecycleManager {
+        let source_dir = tempfile::tempdir().unwrap();
+        let wasm = source_dir.path().join("env-echo.wasm");
+        let wat = r#"(component
+                (import "wasi:cli/environment@0.2.0" (instance $env
+                    (export "get-environment" (func (result (list (tuple string string)))))
+                ))
+                (core module $alloc
+                    (memory (export "memory") 1)
+                    (global $next (mut i32) (i32.const 1024))
+                    (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+                        (local $ptr i32)
+                        (local.set $ptr
+                            (i32.and
+                                (i32.add (global.get $next) (i32.sub (local.get 2) (i32.const 1)))
+                                (i32.sub (i32.const 0) (local.get 2))))
+                        (global.set $next (i32.add (local.get $ptr) (local.get 3)))
+                        (local.get $ptr))
+                )
+                (core instance $a (instantiate $alloc))
+                (alias core export $a "memory" (core memory $memory))
+                (alias core export $a "realloc" (core func $realloc))
+                (alias export $env "get-environment" (func $get_environment))
+                (core func $get_environment_lowered
+                    (canon lower (func $get_environment) (memory $memory) (realloc $realloc)))
+                (core module $m
+                    (import "host" "get-environment" (func $get_environment (param i32)))
+                    (import "host" "memory" (memory 0))
+                    (func (export "first-env") (result i32)
+                        (call $get_environment (i32.const 0))
+                        (i32.store (i32.const 8) (i32.load offset=8 (i32.load (i32.const 0))))
+                        (i32.store (i32.const 12) (i32.load offset=12 (i32.load (i32.const 0))))
+                        (i32.const 8))
+                )
+                (core instance $i (instantiate $m (with "host" (instance
+                    (export "get-environment" (func $get_environment_lowered))
+                    (export "memory" (memory $memory))
+                ))))
+                (func $first_env (result string) (canon lift (core func $i "first-env") (memory $memory)))
+                (export "first-env" (func $first_env))
+            )"#;
+        std::fs::write(&wasm, wat::parse_str(wat).unwrap()).unwrap();
+
+        let lifecycle_manager = LifecycleManager::new(component_dir).await.unwrap();
+        lifecycle_manager
+            .load_component(&format!("file://{}", wasm.display()))
+            .await
+            .unwrap();
+        lifecycle_manager
+    }
+
+    /// Serve `server` to an in-memory client and return the client's text result of calling
+    /// `first-env`
+    async fn call_first_env(server: McpServer) -> String {
+        use rmcp::ServiceExt;
+
+        let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+        tokio::spawn(async move {
+            if let Ok(running) = server.serve(tokio::io::split(server_io)).await {
+                let _ = running.waiting().await;
+            }
+        });
+        let client = ().serve(tokio::io::split(client_io)).await.unwrap();
+
+        let result = client
+            .call_tool(CallToolRequestParam {
+                name: "first-env".into(),
+                arguments: None,
+            })
+            .await
+            .unwrap();
+        assert_ne!(result.is_error, Some(true), "{result:?}");
+        client.cancel().await.unwrap();
+        result.content[0].as_text().unwrap().text.clone()
+    }
+
+    #[tokio::test]
+    async fn test_hook_context_reaches_component_environment() {
+        let component_dir = tempfile::tempdir().unwrap();
+        let server = McpServer::builder(env_echo_manager(component_dir.path()).await)
+            .with_hooks(TenantHook)
+            .build();
+
+        assert_eq!(call_first_env(server).await, "acme");
+    }
+
+    #[tokio::test]
+    async fn test_tool_timeout_is_exposed_as_deadline() {
+        let component_dir = tempfile::tempdir().unwrap();
+        let server = McpServer::builder(env_echo_manager(component_dir.path()).await)
+            .with_tool_timeouts(ToolTimeouts::from_millis(Some(5_000), &HashMap::new()))
+            .build();
+
+        // The only variable is the time left until the 5s timeout, in milliseconds
+        let remaining: u64 = call_first_env(server).await.parse().unwrap();
+        assert!((1_000..=5_000).contains(&remaining), "{remaining}");
+    }
+
+    /// Hook standing in for a meta-tool: every call it sees triggers another call of the same
+    /// tool through the server, on the same task, so the chain never ends on its own
+    struct ReentrantHook {
+        server: Arc<std::sync::OnceLock<McpServer>>,
+        request: Arc<Mutex<Option<RequestContext<RoleServer>>>>,
+        depths: Arc<Mutex<Vec<usize>>>,
+    }
+
+    #[async_trait]
+    impl ServerHooks for ReentrantHook {
+        async fn before_tool_call(&self, ctx: &mut ToolCallContext<'_>) -> Result<(), ErrorData> {
+            self.depths.lock().unwrap().push(ctx.depth);
+            let request = self.request.lock().unwrap().clone().unwrap();
+            let params = CallToolRequestParam {
+                name: ctx.tool_name.clone().into(),
+                arguments: ctx.arguments().cloned(),
+            };
+            self.server
+                .get()
+                .unwrap()
+                .call_tool(params, request)
+                .await?;
+            Ok(())
+        }
+    }
+
+    /// Serves an [`McpServer`], keeping the request context of the client's call so
+    /// [`ReentrantHook`] can make nested calls with it
+    struct Reentrant {
+        server: McpServer,
+        request: Arc<Mutex<Option<RequestContext<RoleServer>>>>,
+    }
+
+    impl ServerHandler for Reentrant {
+        fn get_info(&self) -> ServerInfo {
+            self.server.get_info()
+        }
+
+        fn call_tool(
+            &self,
+            params: CallToolRequestParam,
+            ctx: RequestContext<RoleServer>,
+        ) -> impl Future<Output = Result<CallToolResult, ErrorData>> + Send + '_ {
+            *self.request.lock().unwrap() = Some(ctx.clone());
+            self.server.call_tool(params, ctx)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_nested_tool_calls_beyond_max_depth_are_rejected() {
+        use rmcp::ServiceExt;
+
+        let component_dir = tempfile::tempdir().unwrap();
+        let server_slot = Arc::new(std::sync::OnceLock::new());
+        let request = Arc::new(Mutex::new(None));
+        let depths = Arc::new(Mutex::new(Vec::new()));
+        let server = McpServer::builder(env_echo_manager(component_dir.path()).await)
+            .with_hooks(ReentrantHook {
+                server: server_slot.clone(),
+                request: request.clone(),
+                depths: depths.clone(),
+            })
+            .with_max_call_depth(3)
+            .build();
+        assert!(server_slot.set(server.clone()).is_ok());
+
+        let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+        tokio::spawn(async move {
+            let handler = Reentrant { server, request };
+            if let Ok(running) = handler.serve(tokio::io::split(server_io)).await {
+                let _ = running.waiting().await;
+            }
+        });
+        let client = ().serve(tokio::io::split(client_io)).await.unwrap();
+
+        let err = client
+            .call_tool(CallToolRequestParam {
+                name: "first-env".into(),
+                arguments: None,
+            })
+            .await
+            .unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("nested 4 levels deep"), "{message}");
+        assert!(message.contains("maximum of 3"), "{message}");
+        assert_eq!(*depths.lock().unwrap(), vec![1, 2, 3]);
+
+        // The limit applies per call chain: a fresh call starts at depth 1 again
+        depths.lock().unwrap().clear();
+        client
+            .call_tool(CallToolRequestParam {
+                name: "first-env".into(),
+                arguments: None,
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(*depths.lock().unwrap(), vec![1, 2, 3]);
+        client.cancel().await.unwrap();
+    }
+
+    /// Declares a default for the `n` argument of `echo` in the advertised schema, as WIT
+    /// cannot express defaults
+    struct EchoDefaultHook;
+
+    #[async_trait]
+    impl ServerHooks for EchoDefaultHook {
+        fn on_list_tools(&self, tools: &mut Vec<Tool>) {
+            for tool in tools.iter_mut().filter(|tool| tool.name == "echo") {
+                let mut input_schema = tool.input_schema.as_ref().clone();
+                input_schema["properties"]["n"]["default"] = json!(7);
+                tool.input_schema = Arc::new(input_schema);
+            }
+        }
+
+        fn name(&self) -> &'static str {
+            "echo_default_hook"
+        }
+    }
+
+    /// Serve `server` to an in-memory client and return the text `echo` returns when called
+    /// without arguments, or `None` if the call fails
+    async fn call_echo_without_arguments(server: McpServer) -> Option<String> {
+        use rmcp::ServiceExt;
+
+        let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+        tokio::spawn(async move {
+            if let Ok(running) = server.serve(tokio::io::split(server_io)).await {
+                let _ = running.waiting().await;
+            }
+        });
+        let client = ().serve(tokio::io::split(client_io)).await.unwrap();
+
+        let result = client
+            .call_tool(CallToolRequestParam {
+                name: "echo".into(),
+                arguments: Some(JsonObject::new()),
+            })
+            .await;
+        client.cancel().await.unwrap();
+        let result = result.ok().filter(|result| result.is_error != Some(true))?;
+        Some(result.content[0].as_text()?.text.clone())
+    }
+
+    #[tokio::test]
+    async fn test_schema_defaults_reach_the_component() {
+        let component_dir = tempfile::tempdir().unwrap();
+        let source_dir = tempfile::tempdir().unwrap();
+        let wasm = source_dir.path().join("echo.wasm");
+        let wat = r#"(component
+                (core module $m (func (export "echo") (param i32) (result i32) (local.get 0)))
+                (core instance $i (instantiate $m))
+                (func $echo (param "n" u32) (result u32) (canon lift (core func $i "echo")))
+                (export "echo" (func $echo))
+            )"#;
+        std::fs::write(&wasm, wat::parse_str(wat).unwrap()).unwrap();
+        let lifecycle_manager = LifecycleManager::new(component_dir.path()).await.unwrap();
+        lifecycle_manager
+            .load_component(&format!("file://{}", wasm.display()))
+            .await
+            .unwrap();
+
+        let server = McpServer::builder(lifecycle_manager.clone())
+            .with_hooks(EchoDefaultHook)
+            .with_schema_defaults(true)
+            .build();
+        assert_eq!(
+            call_echo_without_arguments(server).await.as_deref(),
+            Some("7")
+        );
+
+        // Without defaulting the required argument is missing
+        let server = McpServer::builder(lifecycle_manager)
+            .with_hooks(EchoDefaultHook)
+            .build();
+        assert_eq!(call_echo_without_arguments(server).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_component_output_is_truncated() {
+        use rmcp::ServiceExt;
+
+        let component_dir = tempfile::tempdir().unwrap();
+        let source_dir = tempfile::tempdir().unwrap();
+        let wasm = source_dir.path().join("shout.wasm");
+        // `shout` returns a 36 byte string
+        let wat = r#"(component
+                (core module $m
+                    (memory (export "memory") 1)
+                    (data (i32.const 0) "\10\00\00\00\24\00\00\00")
+                    (data (i32.const 16) "abcdefghijklmnopqrstuvwxyz0123456789")
+                    (func (export "shout") (result i32) (i32.const 0))
+                )
+                (core instance $i (instantiate $m))
+                (alias core export $i "memory" (core memory $mem))
+                (func $shout (result string) (canon lift (core func $i "shout") (memory $mem)))
+                (export "shout" (func $shout))
+            )"#;
+        std::fs::write(&wasm, wat::parse_str(wat).unwrap()).unwrap();
+        let lifecycle_manager = LifecycleManager::new(component_dir.path()).await.unwrap();
+        lifecycle_manager
+            .load_component(&format!("file://{}", wasm.display()))
+            .await
+            .unwrap();
+
+        let server = McpServer::builder(lifecycle_manager)
+            .with_max_tool_output_bytes(Some(10))
+            .build();
+        let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+        tokio::spawn(async move {
+            if let Ok(running) = server.serve(tokio::io::split(server_io)).await {
+                let _ = running.waiting().await;
+            }
+        });
+        let client = ().serve(tokio::io::split(client_io)).await.unwrap();
+        let result = client
+            .call_tool(CallToolRequestParam {
+                name: "shout".into(),
+                arguments: None,
+            })
+            .await
+            .unwrap();
+        client.cancel().await.unwrap();
+
+        assert_ne!(result.is_error, Some(true), "{result:?}");
+        let text = &result.content[0].as_text().unwrap().text;
+        let meta = result.meta.as_ref().unwrap();
+        assert_eq!(meta.get("truncated"), Some(&json!(true)));
+        let dropped = meta.get("truncatedBytes").unwrap().as_u64().unwrap();
+        assert!(dropped >= 26, "{dropped}");
+        // Only the first 10 bytes are kept
+        assert_eq!(&text[10..], format!("...[truncated {dropped} bytes]"));
+        // The structured content would carry the full output
+        assert_eq!(result.structured_content, None);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_clients_share_server_safely() {
+        use rmcp::ServiceExt;
+
+        let component_dir = tempfile::tempdir().unwrap();
+        let source_dir = tempfile::tempdir().unwrap();
+        let wasm = answer_component(source_dir.path(), "answer", 42, None);
+        let uri = format!("file://{}", wasm.display());
+
+        let lifecycle_manager = LifecycleManager::new(component_dir.path()).await.unwrap();
+        let server = McpServer::new(lifecycle_manager, false);
+
+        // Each client talks to its own clone of the server, as stdio and HTTP sessions do
+        let mut clients = Vec::new();
+        for _ in 0..4 {
+            let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+            let server = server.clone();
+            tokio::spawn(async move {
+                if let Ok(running) = server.serve(tokio::io::split(server_io)).await {
+                    let _ = running.waiting().await;
+                }
+            });
+            clients.push(Arc::new(
+                ().serve(tokio::io::split(client_io)).await.unwrap(),
+            ));
+        }
+
+        let call =
+            |name: &'static str, arguments: Option<serde_json::Value>| CallToolRequestParam {
+                name: name.into(),
+                arguments: arguments.and_then(|args| args.as_object().cloned()),
+            };
+        let mut tasks = Vec::new();
+        for client in &clients {
+            for round in 0..8 {
+                let client = Arc::clone(client);
+                let load = call("load-component", Some(json!({ "path": uri })));
+                let list = call("list-components", None);
+                let answer = call("answer", None);
+                tasks.push(tokio::spawn(async move {
+                    match round % 3 {
+                        0 => client.call_tool(load).await.map(|_| ()),
+                        1 => client.call_tool(list).await.map(|_| ()),
+                        // The component may not be loaded yet; only the absence of panics and
+                        // deadlocks matters here
+                        _ => client.call_tool(answer).await.map(|_| ()),
+                    }
+                    .ok();
+                    client.list_tools(None).await.unwrap();
+                }));
+            }
+        }
+
+        tokio::time::timeout(
+            std::time::Duration::from_secs(60),
+            futures::future::join_all(tasks),
+        )
+        .await
+        .expect("concurrent requests deadlocked")
+        .into_iter()
+        .for_each(|task| task.expect("request task panicked"));
+
+        assert!(server.get_peer().is_some());
+        assert_eq!(
+            server.lifecycle_manager().list_components().await,
+            vec!["answer"]
+        );
+        let result = clients[0].call_tool(call("answer", None)).await.unwrap();
+        assert_ne!(result.is_error, Some(true));
+
+        for client in clients {
+            Arc::into_inner(client).unwrap().cancel().await.unwrap();
+        }
+    }
+
     // ==================== Complex Configuration Tests ====================
 
     #[tokio::test]
@@ -738,7 +1574,7 @@ mod tests {
             .build();
 
         // Verify all configurations applied
-        assert!(server.disable_builtin_tools);
+        assert!(server.call_options().disable_builtin_tools);
         assert_eq!(
             server.instructions,
             Some("Production server with security hooks enabled".to_string())
@@ -769,7 +1605,10 @@ mod tests {
             .build();
 
         // Both should have same configuration
-        assert_eq!(server1.disable_builtin_tools, server2.disable_builtin_tools);
+        assert_eq!(
+            server1.call_options().disable_builtin_tools,
+            server2.call_options().disable_builtin_tools
+        );
         assert_eq!(server1.instructions, server2.instructions);
     }
 
@@ -933,4 +1772,108 @@ mod tests {
         // Cannot reuse builder (this is enforced by Rust's ownership system)
         // The test verifies the builder pattern works correctly
     }
+
+    // ==================== Trace Context Tests ====================
+
+    #[test]
+    fn test_remote_trace_context_from_meta() {
+        use opentelemetry::trace::TraceContextExt;
+
+        let meta: JsonObject = serde_json::from_value(json!({
+            "traceparent": "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "progressToken": 7
+        }))
+        .unwrap();
+        let cx = remote_trace_context(&meta).expect("traceparent should be extracted");
+        let span = cx.span();
+        let span_context = span.span_context();
+        assert!(span_context.is_remote());
+        assert_eq!(
+            span_context.trace_id().to_string(),
+            "4bf92f3577b34da6a3ce929d0e0e4736"
+        );
+
+        assert!(remote_trace_context(&JsonObject::new()).is_none());
+    }
+
+    // ==================== Log Level Tests ====================
+
+    /// Collects formatted tracing output in memory
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl CapturedLogs {
+        fn take(&self) -> String {
+            String::from_utf8(std::mem::take(&mut *self.0.lock().unwrap())).unwrap()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_set_level_enables_debug_logs() {
+        use rmcp::model::LoggingLevel;
+        use rmcp::ServiceExt;
+        use tracing::level_filters::LevelFilter;
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let captured = CapturedLogs::default();
+        let writer = captured.clone();
+        let (filter, handle) = tracing_subscriber::reload::Layer::new(LevelFilter::INFO);
+        let subscriber = tracing_subscriber::registry().with(filter).with(
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(move || writer.clone()),
+        );
+        // The test runtime is single threaded, so the server task logs through this subscriber
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let server = McpServer::builder(create_test_lifecycle_manager().await)
+            .with_log_level_control(LogLevelControl::new(move |level| Ok(handle.reload(level)?)))
+            .build();
+        assert!(server.get_info().capabilities.logging.is_some());
+
+        let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+        tokio::spawn(async move {
+            if let Ok(running) = server.serve(tokio::io::split(server_io)).await {
+                let _ = running.waiting().await;
+            }
+        });
+        let client = ().serve(tokio::io::split(client_io)).await.unwrap();
+
+        client.list_tools(None).await.unwrap();
+        let before = captured.take();
+        assert!(!before.contains("DEBUG"), "{before}");
+
+        client
+            .set_level(SetLevelRequestParam {
+                level: LoggingLevel::Debug,
+            })
+            .await
+            .unwrap();
+        client.list_tools(None).await.unwrap();
+        let after = captured.take();
+        assert!(
+            after
+                .lines()
+                .any(|line| line.contains("DEBUG") && line.contains("Handling tools list request")),
+            "{after}"
+        );
+        client.cancel().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_logging_capability_requires_level_control() {
+        let server = McpServer::new(create_test_lifecycle_manager().await, false);
+        assert!(server.get_info().capabilities.logging.is_none());
+    }
 }
@@ -2,36 +2,68 @@
 // Licensed under the MIT license.
 
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use rmcp::model::{CallToolRequestParam, CallToolResult, Content, Tool};
 use rmcp::{Peer, RoleServer};
 use serde_json::{json, Value};
 use tracing::{debug, error, info, instrument, warn};
-use wassette::LifecycleManager;
+use wassette::{LifecycleManager, PolicySource};
 
 use crate::components::{
     extract_args_from_request, get_component_tools, handle_component_call, handle_list_components,
-    handle_load_component, handle_unload_component,
+    handle_load_component, handle_load_components, handle_prefixed_component_call,
+    handle_unload_component,
 };
 
 /// The list of components that Wassette knows about
-const COMPONENT_LIST: &str = include_str!("../../../component-registry.json");
+pub(crate) const COMPONENT_LIST: &str = include_str!("../../../component-registry.json");
+
+/// What [`handle_tools_list`] lists and how it names component tools.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ToolListOptions {
+    /// Leave the built-in tools out of the list
+    pub disable_builtin_tools: bool,
+    /// List every component tool as `<component id>__<tool name>`, so each component
+    /// appears as its own namespace
+    pub prefix_component_tools: bool,
+    /// Give every component tool the id of its component as the `group` entry of its
+    /// `_meta`, for clients that group tools in their UI
+    pub group_component_tools: bool,
+}
 
 /// Handles a request to list available tools.
 #[instrument(skip(lifecycle_manager))]
 pub async fn handle_tools_list(
     lifecycle_manager: &LifecycleManager,
-    disable_builtin_tools: bool,
+    options: ToolListOptions,
 ) -> Result<Value> {
     debug!("Handling tools list request");
-
-    let mut tools = get_component_tools(lifecycle_manager).await?;
-    if !disable_builtin_tools {
-        tools.extend(get_builtin_tools());
-    }
+    let ToolListOptions {
+        disable_builtin_tools,
+        prefix_component_tools,
+        group_component_tools,
+    } = options;
+
+    // Built-ins come first in their fixed order, then component tools by name, so clients
+    // see the same list on every call. The sort is stable, so tools sharing a name stay in
+    // component id order.
+    let mut tools = if disable_builtin_tools {
+        Vec::new()
+    } else {
+        get_builtin_tools()
+    };
+    let mut component_tools = get_component_tools(
+        lifecycle_manager,
+        prefix_component_tools,
+        group_component_tools,
+    )
+    .await?;
+    component_tools.sort_by(|a, b| a.name.cmp(&b.name));
+    tools.extend(component_tools);
     debug!(num_tools = %tools.len(), "Retrieved tools");
 
     let response = rmcp::model::ListToolsResult {
@@ -43,13 +75,15 @@ pub async fn handle_tools_list(
 }
 
 /// Check if a tool name is a builtin tool
-fn is_builtin_tool(name: &str) -> bool {
+pub fn is_builtin_tool(name: &str) -> bool {
     matches!(
         name,
         "load-component"
+            | "load-components"
             | "unload-component"
             | "list-components"
             | "get-policy"
+            | "check-component-health"
             | "grant-storage-permission"
             | "grant-network-permission"
             | "grant-environment-variable-permission"
@@ -107,15 +141,137 @@ fn sanitize_args_for_logging(args: &Option<serde_json::Map<String, Value>>) -> S
     }
 }
 
+/// Server-side time limits applied to tool calls.
+///
+/// A per-tool entry takes precedence over the default; with neither set, calls run
+/// without a deadline.
+#[derive(Debug, Clone, Default)]
+pub struct ToolTimeouts {
+    default: Option<Duration>,
+    per_tool: HashMap<String, Duration>,
+}
+
+impl ToolTimeouts {
+    /// Create timeouts from a default and per-tool overrides, both in milliseconds
+    pub fn from_millis(default_ms: Option<u64>, per_tool_ms: &HashMap<String, u64>) -> Self {
+        Self {
+            default: default_ms.map(Duration::from_millis),
+            per_tool: per_tool_ms
+                .iter()
+                .map(|(name, ms)| (name.clone(), Duration::from_millis(*ms)))
+                .collect(),
+        }
+    }
+
+    /// The timeout that applies to calls of `tool_name`, if any
+    pub fn for_tool(&self, tool_name: &str) -> Option<Duration> {
+        self.per_tool.get(tool_name).copied().or(self.default)
+    }
+}
+
+/// Server settings applied to every call handled by [`handle_tools_call`].
+#[derive(Debug, Clone, Default)]
+pub struct ToolCallOptions {
+    /// Reject calls to the built-in tools
+    pub disable_builtin_tools: bool,
+    /// Time limits for tool calls
+    pub timeouts: ToolTimeouts,
+    /// Truncate the text returned by a call to this many bytes
+    pub max_output_bytes: Option<usize>,
+    /// Route component tool calls by their `<component id>__<tool name>` prefix
+    pub prefix_component_tools: bool,
+}
+
+/// Await a tool call, failing it once `timeout` elapses.
+///
+/// Components called within are interrupted by the runtime at the deadline, since a
+/// CPU-bound call never yields for the timer to fire. A call that fails once the deadline
+/// has passed is reported as timed out either way.
+async fn run_with_timeout<T>(
+    tool_name: &str,
+    timeout: Option<Duration>,
+    call: impl std::future::Future<Output = Result<T>>,
+) -> Result<T> {
+    let Some(limit) = timeout else {
+        return call.await;
+    };
+
+    // Components called within can read the time left from their environment
+    let deadline = tokio::time::Instant::now() + limit;
+    match tokio::time::timeout(limit, wassette::with_call_deadline(deadline, call)).await {
+        Ok(Err(_)) if tokio::time::Instant::now() >= deadline => {}
+        Ok(result) => return result,
+        Err(_) => {}
+    }
+    warn!(
+        tool_name = %tool_name,
+        timeout_ms = %limit.as_millis(),
+        "Tool invocation timed out"
+    );
+    Err(anyhow::anyhow!(
+        "Tool '{tool_name}' timed out after {}ms",
+        limit.as_millis()
+    ))
+}
+
+/// Shorten the text content of a serialized tool result so it fits in `max_bytes`.
+///
+/// Text items are kept in order until the budget runs out; the item that crosses the
+/// limit is cut on a character boundary and every cut item ends with a
+/// `...[truncated N bytes]` marker. Truncated results drop their structured content,
+/// which would otherwise carry the full payload, and report `truncated` and
+/// `truncatedBytes` in `_meta`. Returns the number of bytes removed.
+fn truncate_output(result: &mut Value, max_bytes: usize) -> usize {
+    let Some(content) = result.get_mut("content").and_then(Value::as_array_mut) else {
+        return 0;
+    };
+
+    let mut remaining = max_bytes;
+    let mut truncated_bytes = 0;
+    for item in content.iter_mut() {
+        let Some(Value::String(text)) = item.get_mut("text") else {
+            continue;
+        };
+        if text.len() <= remaining {
+            remaining -= text.len();
+            continue;
+        }
+
+        let mut cut = remaining;
+        while !text.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        let dropped = text.len() - cut;
+        text.truncate(cut);
+        text.push_str(&format!("...[truncated {dropped} bytes]"));
+        truncated_bytes += dropped;
+        remaining = 0;
+    }
+
+    if truncated_bytes > 0 {
+        let Some(object) = result.as_object_mut() else {
+            return truncated_bytes;
+        };
+        object.remove("structuredContent");
+        let meta = object.entry("_meta").or_insert_with(|| json!({}));
+        if let Some(meta) = meta.as_object_mut() {
+            meta.insert("truncated".to_string(), json!(true));
+            meta.insert("truncatedBytes".to_string(), json!(truncated_bytes));
+        }
+    }
+    truncated_bytes
+}
+
 /// Handles a tool call request.
 #[instrument(skip_all, fields(method_name = %req.name))]
 pub async fn handle_tools_call(
     req: CallToolRequestParam,
     lifecycle_manager: &LifecycleManager,
     server_peer: Peer<RoleServer>,
-    disable_builtin_tools: bool,
+    options: &ToolCallOptions,
 ) -> Result<Value> {
     let start_time = Instant::now();
+    let disable_builtin_tools = options.disable_builtin_tools;
     let tool_name = req.name.to_string();
     let sanitized_args = sanitize_args_for_logging(&req.arguments);
 
@@ -134,47 +290,100 @@ pub async fn handle_tools_call(
         Err(anyhow::anyhow!("Built-in tools are disabled"))
     } else {
         // Handle builtin tools (if enabled) or component calls
-        match req.name.as_ref() {
-            "load-component" if !disable_builtin_tools => {
-                handle_load_component(&req, lifecycle_manager, server_peer).await
-            }
-            "unload-component" if !disable_builtin_tools => {
-                handle_unload_component(&req, lifecycle_manager, server_peer).await
-            }
-            "list-components" if !disable_builtin_tools => {
-                handle_list_components(lifecycle_manager).await
-            }
-            "get-policy" if !disable_builtin_tools => {
-                handle_get_policy(&req, lifecycle_manager).await
-            }
-            "grant-storage-permission" if !disable_builtin_tools => {
-                handle_grant_storage_permission(&req, lifecycle_manager).await
-            }
-            "grant-network-permission" if !disable_builtin_tools => {
-                handle_grant_network_permission(&req, lifecycle_manager).await
-            }
-            "grant-environment-variable-permission" if !disable_builtin_tools => {
-                handle_grant_environment_variable_permission(&req, lifecycle_manager).await
-            }
-            "revoke-storage-permission" if !disable_builtin_tools => {
-                handle_revoke_storage_permission(&req, lifecycle_manager).await
-            }
-            "revoke-network-permission" if !disable_builtin_tools => {
-                handle_revoke_network_permission(&req, lifecycle_manager).await
-            }
-            "revoke-environment-variable-permission" if !disable_builtin_tools => {
-                handle_revoke_environment_variable_permission(&req, lifecycle_manager).await
-            }
-            "search-components" if !disable_builtin_tools => {
-                handle_search_component(&req, lifecycle_manager).await
-            }
-            "reset-permission" if !disable_builtin_tools => {
-                handle_reset_permission(&req, lifecycle_manager).await
+        let call = async {
+            match req.name.as_ref() {
+                "load-component" if !disable_builtin_tools => {
+                    handle_load_component(&req, lifecycle_manager, server_peer).await
+                }
+                "load-components" if !disable_builtin_tools => {
+                    handle_load_components(&req, lifecycle_manager, server_peer).await
+                }
+                "unload-component" if !disable_builtin_tools => {
+                    handle_unload_component(&req, lifecycle_manager, server_peer).await
+                }
+                "list-components" if !disable_builtin_tools => {
+                    handle_list_components(lifecycle_manager).await
+                }
+                "get-policy" if !disable_builtin_tools => {
+                    handle_get_policy(&req, lifecycle_manager).await
+                }
+                "check-component-health" if !disable_builtin_tools => {
+                    handle_check_component_health(&req, lifecycle_manager).await
+                }
+                "grant-storage-permission" if !disable_builtin_tools => {
+                    handle_grant_storage_permission(&req, lifecycle_manager).await
+                }
+                "grant-network-permission" if !disable_builtin_tools => {
+                    handle_grant_network_permission(&req, lifecycle_manager).await
+                }
+                "grant-environment-variable-permission" if !disable_builtin_tools => {
+                    handle_grant_environment_variable_permission(&req, lifecycle_manager).await
+                }
+                "revoke-storage-permission" if !disable_builtin_tools => {
+                    handle_revoke_storage_permission(&req, lifecycle_manager).await
+                }
+                "revoke-network-permission" if !disable_builtin_tools => {
+                    handle_revoke_network_permission(&req, lifecycle_manager).await
+                }
+                "revoke-environment-variable-permission" if !disable_builtin_tools => {
+                    handle_revoke_environment_variable_permission(&req, lifecycle_manager).await
+                }
+                "search-components" if !disable_builtin_tools => {
+                    handle_search_component(&req, lifecycle_manager).await
+                }
+                "reset-permission" if !disable_builtin_tools => {
+                    handle_reset_permission(&req, lifecycle_manager).await
+                }
+                _ => route_component_call(&req, lifecycle_manager, options).await,
             }
-            _ => handle_component_call(&req, lifecycle_manager).await,
-        }
+        };
+        run_with_timeout(&tool_name, options.timeouts.for_tool(&tool_name), call).await
     };
 
+    finish_tools_call(&tool_name, start_time, result, options)
+}
+
+/// Handles a call to a component tool the way [`handle_tools_call`] does, with the same
+/// routing, time limit and output truncation, for callers without a client connection.
+#[instrument(skip_all, fields(method_name = %req.name))]
+pub async fn handle_component_tools_call(
+    req: CallToolRequestParam,
+    lifecycle_manager: &LifecycleManager,
+    options: &ToolCallOptions,
+) -> Result<Value> {
+    let start_time = Instant::now();
+    let tool_name = req.name.to_string();
+
+    debug!(
+        tool_name = %tool_name,
+        arguments = %sanitize_args_for_logging(&req.arguments),
+        "Tool invocation started"
+    );
+
+    let call = route_component_call(&req, lifecycle_manager, options);
+    let result = run_with_timeout(&tool_name, options.timeouts.for_tool(&tool_name), call).await;
+    finish_tools_call(&tool_name, start_time, result, options)
+}
+
+async fn route_component_call(
+    req: &CallToolRequestParam,
+    lifecycle_manager: &LifecycleManager,
+    options: &ToolCallOptions,
+) -> Result<CallToolResult> {
+    if options.prefix_component_tools {
+        handle_prefixed_component_call(req, lifecycle_manager).await
+    } else {
+        handle_component_call(req, lifecycle_manager).await
+    }
+}
+
+/// Log the outcome of a tool call and turn it into the result sent to the client
+fn finish_tools_call(
+    tool_name: &str,
+    start_time: Instant,
+    result: Result<CallToolResult>,
+    options: &ToolCallOptions,
+) -> Result<Value> {
     let duration = start_time.elapsed();
 
     match &result {
@@ -198,7 +407,21 @@ pub async fn handle_tools_call(
     }
 
     match result {
-        Ok(result) => Ok(serde_json::to_value(result)?),
+        Ok(result) => {
+            let mut value = serde_json::to_value(result)?;
+            if let Some(max_bytes) = options.max_output_bytes {
+                let truncated_bytes = truncate_output(&mut value, max_bytes);
+                if truncated_bytes > 0 {
+                    warn!(
+                        tool_name = %tool_name,
+                        max_bytes = %max_bytes,
+                        truncated_bytes = %truncated_bytes,
+                        "Tool output exceeded size limit and was truncated"
+                    );
+                }
+            }
+            Ok(value)
+        }
         Err(e) => {
             let error_text = format!("Error: {e}");
             let contents = vec![Content::text(error_text)];
@@ -221,13 +444,25 @@ fn get_builtin_tools() -> Vec<Tool> {
             name: Cow::Borrowed("load-component"),
             title: None,
             description: Some(Cow::Borrowed(
-                "Dynamically loads a new tool or component from either the filesystem or OCI registries.",
+                "Dynamically loads a new tool or component from either the filesystem or OCI registries, or by its name in the component registry.",
             )),
             input_schema: Arc::new(
                 serde_json::from_value(json!({
                     "type": "object",
                     "properties": {
-                        "path": {"type": "string"}
+                        "path": {"type": "string"},
+                        "id": {
+                            "type": "string",
+                            "description": "Load the component under this id instead of one derived from its file name"
+                        },
+                        "force": {
+                            "type": "boolean",
+                            "description": "Replace a component with the same id that was loaded from a different source"
+                        },
+                        "force_recompile": {
+                            "type": "boolean",
+                            "description": "Compile the component from its .wasm instead of using the precompiled cache, and rewrite the cache"
+                        }
                     },
                     "required": ["path"]
                 }))
@@ -238,6 +473,42 @@ fn get_builtin_tools() -> Vec<Tool> {
             icons: None,
             meta: None,
         },
+        Tool {
+            name: Cow::Borrowed("load-components"),
+            title: None,
+            description: Some(Cow::Borrowed(
+                "Loads several components concurrently. Each entry is a URI or an object with a 'path' and an optional 'policy' URI to attach. Returns a result for every entry.",
+            )),
+            input_schema: Arc::new(
+                serde_json::from_value(json!({
+                    "type": "object",
+                    "properties": {
+                        "components": {
+                            "type": "array",
+                            "items": {
+                                "oneOf": [
+                                    {"type": "string"},
+                                    {
+                                        "type": "object",
+                                        "properties": {
+                                            "path": {"type": "string"},
+                                            "policy": {"type": "string"}
+                                        },
+                                        "required": ["path"]
+                                    }
+                                ]
+                            }
+                        }
+                    },
+                    "required": ["components"]
+                }))
+                .unwrap_or_default(),
+            ),
+            output_schema: None,
+            annotations: None,
+            icons: None,
+            meta: None,
+        },
         Tool {
             name: Cow::Borrowed("unload-component"),
             title: None,
@@ -248,7 +519,11 @@ fn get_builtin_tools() -> Vec<Tool> {
                 serde_json::from_value(json!({
                     "type": "object",
                     "properties": {
-                        "id": {"type": "string"}
+                        "id": {"type": "string"},
+                        "ignore_missing": {
+                            "type": "boolean",
+                            "description": "Succeed without changes when the component is not loaded"
+                        }
                     },
                     "required": ["id"]
                 }))
@@ -291,6 +566,34 @@ fn get_builtin_tools() -> Vec<Tool> {
                         "component_id": {
                             "type": "string",
                             "description": "ID of the component to get policy for"
+                        },
+                        "effective": {
+                            "type": "boolean",
+                            "description": "Also return the effective policy: the component's policy merged with the server's default policy"
+                        }
+                    },
+                    "required": ["component_id"]
+                }))
+                .unwrap_or_default(),
+            ),
+            output_schema: None,
+            annotations: None,
+            icons: None,
+            meta: None,
+        },
+        Tool {
+            name: Cow::Borrowed("check-component-health"),
+            title: None,
+            description: Some(Cow::Borrowed(
+                "Runs a component's health check and reports whether it is healthy, unhealthy, or unknown (no health check exported)",
+            )),
+            input_schema: Arc::new(
+                serde_json::from_value(json!({
+                    "type": "object",
+                    "properties": {
+                        "component_id": {
+                            "type": "string",
+                            "description": "ID of the component to check"
                         }
                     },
                     "required": ["component_id"]
@@ -399,7 +702,7 @@ fn get_builtin_tools() -> Vec<Tool> {
                         "properties": {
                           "key": { 
                             "type": "string",
-                            "description": "Environment variable key to grant access to"
+                            "description": "Environment variable key to grant access to, or a prefix ending in '*' (e.g. AWS_*) to grant every matching variable"
                           }
                         },
                         "required": ["key"],
@@ -685,6 +988,10 @@ pub async fn handle_get_policy(
         .get("component_id")
         .and_then(|v| v.as_str())
         .ok_or_else(|| anyhow::anyhow!("Missing required argument: 'component_id'"))?;
+    let effective = args
+        .get("effective")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
 
     info!("Getting policy for component {}", component_id);
 
@@ -696,26 +1003,63 @@ pub async fn handle_get_policy(
 
     let policy_info = lifecycle_manager.get_policy_info(component_id).await;
 
-    let status_text = if let Some(info) = policy_info {
-        serde_json::to_string(&json!({
+    // A policy that cannot be parsed is still shown, so that it can be inspected and fixed
+    let mut warnings = Vec::new();
+    let mut status = if let Some(info) = policy_info {
+        let policy_content = tokio::fs::read_to_string(&info.local_path).await?;
+        let (policy_key, policy) = match serde_yaml::from_str::<Value>(&policy_content) {
+            Ok(policy) => ("policy", policy),
+            Err(e) => {
+                let warning = format!(
+                    "Failed to parse policy file {}: {}",
+                    info.local_path.display(),
+                    e
+                );
+                warn!(%component_id, %warning, "Returning unparsable policy as raw text");
+                warnings.push(warning);
+                ("policy_raw", Value::String(policy_content))
+            }
+        };
+        let epoch_secs = |time: std::time::SystemTime| {
+            time.duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs()
+        };
+
+        let mut status = json!({
             "status": "policy found",
             "component_id": component_id,
             "policy_info": {
                 "policy_id": info.policy_id,
+                "source": info.source.as_str(),
                 "source_uri": info.source_uri,
                 "local_path": info.local_path,
-                "created_at": info.created_at.duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default().as_secs()
-            }
-        }))?
+                "created_at": epoch_secs(info.created_at),
+                "modified_at": epoch_secs(info.modified_at),
+            },
+        });
+        status[policy_key] = policy;
+        status
     } else {
-        serde_json::to_string(&json!({
+        json!({
             "status": "no policy found",
-            "component_id": component_id
-        }))?
+            "component_id": component_id,
+            "policy_info": {
+                "source": PolicySource::Default.as_str(),
+            }
+        })
     };
+    if effective {
+        match lifecycle_manager.get_effective_policy(component_id).await {
+            Ok(policy) => status["effective_policy"] = serde_json::to_value(policy)?,
+            Err(e) => warnings.push(format!("Failed to compute the effective policy: {e:#}")),
+        }
+    }
+    if !warnings.is_empty() {
+        status["warnings"] = json!(warnings);
+    }
 
-    let contents = vec![Content::text(status_text)];
+    let contents = vec![Content::text(serde_json::to_string(&status)?)];
 
     Ok(CallToolResult {
         content: contents,
@@ -725,6 +1069,32 @@ pub async fn handle_get_policy(
     })
 }
 
+#[instrument(skip(lifecycle_manager))]
+pub async fn handle_check_component_health(
+    req: &CallToolRequestParam,
+    lifecycle_manager: &LifecycleManager,
+) -> Result<CallToolResult> {
+    let args = extract_args_from_request(req)?;
+
+    let component_id = args
+        .get("component_id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing required argument: 'component_id'"))?;
+
+    let health = lifecycle_manager
+        .check_component_health(component_id)
+        .await?;
+    let mut report = serde_json::to_value(&health)?;
+    report["component_id"] = json!(component_id);
+
+    Ok(CallToolResult {
+        content: vec![Content::text(serde_json::to_string(&report)?)],
+        structured_content: None,
+        is_error: None,
+        meta: None,
+    })
+}
+
 /// Generic helper for handling grant permission requests
 async fn handle_grant_permission_generic(
     req: &CallToolRequestParam,
@@ -732,6 +1102,9 @@ async fn handle_grant_permission_generic(
     permission_type: &str,
     permission_display_name: &str,
 ) -> Result<CallToolResult> {
+    if lifecycle_manager.is_safe_mode() {
+        return Err(anyhow::anyhow!("Grants disabled in safe mode"));
+    }
     let args = extract_args_from_request(req)?;
 
     let component_id = args
@@ -1031,6 +1404,8 @@ pub async fn handle_reset_permission(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::components::TOOL_GROUP_META_KEY;
+    use crate::test_support::answer_component;
 
     fn first_text_content(result: &CallToolResult) -> Result<String> {
         let content_json = serde_json::to_value(&result.content)?;
@@ -1043,11 +1418,13 @@ mod tests {
     #[test]
     fn test_get_builtin_tools() {
         let tools = get_builtin_tools();
-        assert_eq!(tools.len(), 12);
+        assert_eq!(tools.len(), 14);
         assert!(tools.iter().any(|t| t.name == "load-component"));
+        assert!(tools.iter().any(|t| t.name == "load-components"));
         assert!(tools.iter().any(|t| t.name == "unload-component"));
         assert!(tools.iter().any(|t| t.name == "list-components"));
         assert!(tools.iter().any(|t| t.name == "get-policy"));
+        assert!(tools.iter().any(|t| t.name == "check-component-health"));
         assert!(tools.iter().any(|t| t.name == "grant-storage-permission"));
         assert!(tools.iter().any(|t| t.name == "grant-network-permission"));
         assert!(tools
@@ -1062,6 +1439,320 @@ mod tests {
         assert!(tools.iter().any(|t| t.name == "search-components"));
     }
 
+    #[tokio::test]
+    async fn test_tools_list_order_is_deterministic() -> Result<()> {
+        let component_dir = tempfile::tempdir()?;
+        let source_dir = tempfile::tempdir()?;
+        let lifecycle_manager = LifecycleManager::new(component_dir.path()).await?;
+        for (id, exports) in [("second", ["zeta", "beta"]), ("first", ["mu", "alpha"])] {
+            let funcs: String = exports
+                .iter()
+                .map(|name| {
+                    format!(
+                        r#"(func ${name} (result u32) (canon lift (core func $i "answer")))
+                        (export "{name}" (func ${name}))"#
+                    )
+                })
+                .collect();
+            let wat = format!(
+                r#"(component
+                    (core module $m (func (export "answer") (result i32) (i32.const 42)))
+                    (core instance $i (instantiate $m))
+                    {funcs}
+                )"#
+            );
+            let wasm = source_dir.path().join(format!("{id}.wasm"));
+            std::fs::write(&wasm, wat::parse_str(wat)?)?;
+            lifecycle_manager
+                .load_component(&format!("file://{}", wasm.display()))
+                .await?;
+        }
+
+        let tool_names = |list: Value| -> Vec<String> {
+            list["tools"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|tool| tool["name"].as_str().unwrap().to_string())
+                .collect()
+        };
+        let expected: Vec<String> = get_builtin_tools()
+            .into_iter()
+            .map(|tool| tool.name.to_string())
+            .chain(["alpha", "beta", "mu", "zeta"].map(String::from))
+            .collect();
+        for _ in 0..5 {
+            let list = handle_tools_list(&lifecycle_manager, ToolListOptions::default()).await?;
+            assert_eq!(tool_names(list), expected);
+        }
+
+        let list = handle_tools_list(
+            &lifecycle_manager,
+            ToolListOptions {
+                disable_builtin_tools: true,
+                ..Default::default()
+            },
+        )
+        .await?;
+        assert_eq!(tool_names(list), vec!["alpha", "beta", "mu", "zeta"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_component_tool_prefixes_group_and_route_tools() -> Result<()> {
+        let component_dir = tempfile::tempdir()?;
+        let source_dir = tempfile::tempdir()?;
+        let lifecycle_manager = LifecycleManager::new(component_dir.path()).await?;
+        // Both components export a tool named `answer`, returning different values
+        for (id, value) in [("first", 41), ("second", 42)] {
+            let wasm = answer_component(source_dir.path(), id, value, Some(value));
+            lifecycle_manager
+                .load_component(&format!("file://{}", wasm.display()))
+                .await?;
+        }
+
+        let list = handle_tools_list(
+            &lifecycle_manager,
+            ToolListOptions {
+                disable_builtin_tools: true,
+                prefix_component_tools: true,
+                ..Default::default()
+            },
+        )
+        .await?;
+        let names: Vec<&str> = list["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tool| tool["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "first__answer",
+                "first__version",
+                "second__answer",
+                "second__version"
+            ]
+        );
+        // Built-in tools keep their names
+        let list = handle_tools_list(
+            &lifecycle_manager,
+            ToolListOptions {
+                prefix_component_tools: true,
+                ..Default::default()
+            },
+        )
+        .await?;
+        assert_eq!(list["tools"][0]["name"], "load-component");
+
+        for (name, expected) in [("first__answer", "41"), ("second__answer", "42")] {
+            let req = CallToolRequestParam {
+                name: name.into(),
+                arguments: None,
+            };
+            let result = handle_prefixed_component_call(&req, &lifecycle_manager).await?;
+            assert!(first_text_content(&result)?.contains(expected));
+        }
+
+        let req = CallToolRequestParam {
+            name: "answer".into(),
+            arguments: None,
+        };
+        let err = handle_prefixed_component_call(&req, &lifecycle_manager)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("No component provides the tool"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_component_tool_groups_name_the_component_of_each_tool() -> Result<()> {
+        let component_dir = tempfile::tempdir()?;
+        let source_dir = tempfile::tempdir()?;
+        let lifecycle_manager = LifecycleManager::new(component_dir.path()).await?;
+        for (id, exports) in [("clock", ["now", "today"]), ("dice", ["roll", "flip"])] {
+            let lifted: String = exports
+                .iter()
+                .map(|name| {
+                    format!(
+                        r#"(func ${name} (result u32) (canon lift (core func $i "value")))
+                        (export "{name}" (func ${name}))"#
+                    )
+                })
+                .collect();
+            let wat = format!(
+                r#"(component
+                    (core module $m (func (export "value") (result i32) (i32.const 1)))
+                    (core instance $i (instantiate $m))
+                    {lifted}
+                )"#
+            );
+            let wasm = source_dir.path().join(format!("{id}.wasm"));
+            std::fs::write(&wasm, wat::parse_str(wat)?)?;
+            lifecycle_manager
+                .load_component(&format!("file://{}", wasm.display()))
+                .await?;
+        }
+
+        let list = handle_tools_list(
+            &lifecycle_manager,
+            ToolListOptions {
+                group_component_tools: true,
+                ..Default::default()
+            },
+        )
+        .await?;
+        let groups: Vec<(&str, Option<&str>)> = list["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tool| {
+                (
+                    tool["name"].as_str().unwrap(),
+                    tool["_meta"][TOOL_GROUP_META_KEY].as_str(),
+                )
+            })
+            .filter(|(name, _)| !is_builtin_tool(name))
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                ("flip", Some("dice")),
+                ("now", Some("clock")),
+                ("roll", Some("dice")),
+                ("today", Some("clock")),
+            ]
+        );
+        // Built-in tools belong to no component
+        assert!(list["tools"][0].get("_meta").is_none());
+
+        // Without groups component tools carry no `_meta`
+        let list = handle_tools_list(
+            &lifecycle_manager,
+            ToolListOptions {
+                disable_builtin_tools: true,
+                ..Default::default()
+            },
+        )
+        .await?;
+        assert!(list["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|tool| tool.get("_meta").is_none()));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_require_namespace_lists_shared_tools_with_prefixes() -> Result<()> {
+        let component_dir = tempfile::tempdir()?;
+        let source_dir = tempfile::tempdir()?;
+        let lifecycle_manager = LifecycleManager::builder(component_dir.path())
+            .with_duplicate_tool_policy(wassette::DuplicateToolPolicy::RequireNamespace)
+            .build()
+            .await?;
+        // Both export `answer`, only `second` exports `version`
+        for (id, value, version) in [("first", 41, None), ("second", 42, Some(1))] {
+            let wasm = answer_component(source_dir.path(), id, value, version);
+            lifecycle_manager
+                .load_component(&format!("file://{}", wasm.display()))
+                .await?;
+        }
+
+        let list = handle_tools_list(
+            &lifecycle_manager,
+            ToolListOptions {
+                disable_builtin_tools: true,
+                ..Default::default()
+            },
+        )
+        .await?;
+        let mut names: Vec<&str> = list["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tool| tool["name"].as_str().unwrap())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["first__answer", "second__answer", "version"]);
+
+        let call = |name: &'static str| CallToolRequestParam {
+            name: name.into(),
+            arguments: None,
+        };
+        let result = handle_component_call(&call("second__answer"), &lifecycle_manager).await?;
+        assert!(first_text_content(&result)?.contains("42"));
+        let err = handle_component_call(&call("answer"), &lifecycle_manager)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("first__answer"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_policy_effective_merges_default_policy() -> Result<()> {
+        let component_dir = tempfile::tempdir()?;
+        let source_dir = tempfile::tempdir()?;
+        let default_policy = policy::PolicyParser::parse_str(
+            r#"
+version: "1.2"
+permissions:
+  network:
+    allow:
+      - host: "dns.google"
+"#,
+        )?;
+        let lifecycle_manager = LifecycleManager::builder(component_dir.path())
+            .with_default_policy(Some(default_policy))
+            .build()
+            .await?;
+
+        let wasm = answer_component(source_dir.path(), "answer", 42, None);
+        let id = lifecycle_manager
+            .load_component(&format!("file://{}", wasm.display()))
+            .await?
+            .component_id;
+        lifecycle_manager
+            .grant_permission(&id, "network", &json!({"host": "api.example.com"}))
+            .await?;
+
+        let hosts = |rules: &Value| -> Vec<String> {
+            rules["permissions"]["network"]["allow"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|rule| rule["host"].as_str().unwrap().to_string())
+                .collect()
+        };
+        let req = CallToolRequestParam {
+            name: "get-policy".into(),
+            arguments: Some(
+                json!({"component_id": id, "effective": true})
+                    .as_object()
+                    .unwrap()
+                    .clone(),
+            ),
+        };
+        let result = handle_get_policy(&req, &lifecycle_manager).await?;
+        let response: Value = serde_json::from_str(&first_text_content(&result)?)?;
+        assert_eq!(hosts(&response["policy"]), vec!["api.example.com"]);
+        assert_eq!(
+            hosts(&response["effective_policy"]),
+            vec!["dns.google", "api.example.com"]
+        );
+
+        // Without the flag only the component's own policy is returned
+        let req = CallToolRequestParam {
+            name: "get-policy".into(),
+            arguments: Some(json!({"component_id": id}).as_object().unwrap().clone()),
+        };
+        let result = handle_get_policy(&req, &lifecycle_manager).await?;
+        let response: Value = serde_json::from_str(&first_text_content(&result)?)?;
+        assert!(response.get("effective_policy").is_none());
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_grant_network_permission_integration() -> Result<()> {
         // Create a test lifecycle manager
@@ -1696,4 +2387,136 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_tool_timeout_overrides_default_for_named_tool() {
+        let per_tool =
+            HashMap::from([("fetch".to_string(), 2_000), ("render".to_string(), 60_000)]);
+        let timeouts = ToolTimeouts::from_millis(Some(10_000), &per_tool);
+
+        assert_eq!(
+            timeouts.for_tool("fetch"),
+            Some(Duration::from_millis(2_000))
+        );
+        assert_eq!(
+            timeouts.for_tool("render"),
+            Some(Duration::from_millis(60_000))
+        );
+        assert_eq!(
+            timeouts.for_tool("get-weather"),
+            Some(Duration::from_millis(10_000))
+        );
+    }
+
+    #[test]
+    fn test_tool_timeouts_without_default() {
+        let per_tool = HashMap::from([("fetch".to_string(), 2_000)]);
+        let timeouts = ToolTimeouts::from_millis(None, &per_tool);
+
+        assert_eq!(
+            timeouts.for_tool("fetch"),
+            Some(Duration::from_millis(2_000))
+        );
+        assert_eq!(timeouts.for_tool("render"), None);
+        assert_eq!(ToolTimeouts::default().for_tool("fetch"), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_run_with_timeout_fails_slow_call() {
+        let err = run_with_timeout(
+            "render",
+            Some(Duration::from_millis(50)),
+            std::future::pending::<Result<()>>(),
+        )
+        .await
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Tool 'render' timed out after 50ms"));
+
+        let value = run_with_timeout("fetch", Some(Duration::from_secs(1)), async { Ok(7) })
+            .await
+            .unwrap();
+        assert_eq!(value, 7);
+    }
+
+    #[tokio::test]
+    async fn test_run_with_timeout_interrupts_busy_loop() -> Result<()> {
+        let component_dir = tempfile::tempdir()?;
+        let source_dir = tempfile::tempdir()?;
+        let lifecycle_manager = LifecycleManager::new(component_dir.path()).await?;
+        let wasm = source_dir.path().join("spin.wasm");
+        let wat = r#"(component
+                (core module $m
+                    (func (export "spin") (loop $forever (br $forever))))
+                (core instance $i (instantiate $m))
+                (func $spin (canon lift (core func $i "spin")))
+                (export "spin" (func $spin))
+            )"#;
+        std::fs::write(&wasm, wat::parse_str(wat)?)?;
+        lifecycle_manager
+            .load_component(&format!("file://{}", wasm.display()))
+            .await?;
+
+        let started = std::time::Instant::now();
+        let err = run_with_timeout(
+            "spin",
+            Some(Duration::from_millis(100)),
+            lifecycle_manager.execute_component_call("spin", "spin", "{}"),
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Tool 'spin' timed out after 100ms"),
+            "{err}"
+        );
+        assert!(started.elapsed() < Duration::from_secs(5));
+        Ok(())
+    }
+
+    #[test]
+    fn test_truncate_output_appends_marker_and_flags_meta() {
+        let mut result = serde_json::to_value(CallToolResult {
+            content: vec![Content::text("a".repeat(1_000))],
+            structured_content: Some(json!({ "result": "a".repeat(1_000) })),
+            is_error: Some(false),
+            meta: None,
+        })
+        .unwrap();
+
+        assert_eq!(truncate_output(&mut result, 100), 900);
+        let text = result["content"][0]["text"].as_str().unwrap();
+        assert_eq!(text, format!("{}...[truncated 900 bytes]", "a".repeat(100)));
+        assert_eq!(result["_meta"]["truncated"], true);
+        assert_eq!(result["_meta"]["truncatedBytes"], 900);
+        assert!(result.get("structuredContent").is_none());
+    }
+
+    #[test]
+    fn test_truncate_output_shares_budget_and_respects_char_boundaries() {
+        let mut result = json!({
+            "content": [
+                { "type": "text", "text": "héllo" },
+                { "type": "text", "text": "world" },
+            ]
+        });
+
+        // "h" fits, the two-byte "é" does not
+        assert_eq!(truncate_output(&mut result, 2), 10);
+        assert_eq!(result["content"][0]["text"], "h...[truncated 5 bytes]");
+        assert_eq!(result["content"][1]["text"], "...[truncated 5 bytes]");
+    }
+
+    #[test]
+    fn test_truncate_output_leaves_small_results_untouched() {
+        let original = json!({
+            "content": [{ "type": "text", "text": "ok" }],
+            "structuredContent": { "result": "ok" }
+        });
+        let mut result = original.clone();
+
+        assert_eq!(truncate_output(&mut result, 2), 0);
+        assert_eq!(result, original);
+    }
 }
@@ -9,10 +9,14 @@
 use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
 
+pub mod merge;
+pub mod migration;
 pub mod parser;
 pub mod types;
 
-pub use parser::PolicyParser;
+pub use merge::merge;
+pub use migration::CURRENT_POLICY_VERSION;
+pub use parser::{ParsedPolicy, PolicyParser};
 pub use types::*;
 
 /// Policy document structure
@@ -99,7 +103,7 @@ mod tests {
         let policy = PolicyParser::parse_file("testdata/docker.yaml").unwrap();
         policy.validate().unwrap();
 
-        assert_eq!(policy.version, "1.0");
+        assert_eq!(policy.version, CURRENT_POLICY_VERSION);
         assert_eq!(
             policy.description,
             Some("Permission policy for docker container".to_string())
@@ -0,0 +1,147 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Combining a default policy shared by every component with a component's own policy.
+//!
+//! Precedence:
+//!
+//! - `allow` and `deny` rules of every permission type are the union of both policies,
+//!   default rules first, with duplicates dropped. Neither policy can remove a rule the other
+//!   grants.
+//! - `runtime` and `resources` are taken from the component policy when it sets them and
+//!   from the default policy otherwise.
+//! - `version` and `description` come from the component policy.
+
+use crate::{EnvironmentPermissions, PermissionList, Permissions, PolicyDocument};
+
+/// The policy a component runs with when `default` applies in addition to its own `policy`
+pub fn merge(default: &PolicyDocument, policy: &PolicyDocument) -> PolicyDocument {
+    let (base, own) = (&default.permissions, &policy.permissions);
+    PolicyDocument {
+        version: policy.version.clone(),
+        description: policy.description.clone(),
+        permissions: Permissions {
+            storage: merge_lists(&base.storage, &own.storage),
+            network: merge_lists(&base.network, &own.network),
+            environment: match (&base.environment, &own.environment) {
+                (Some(base), Some(own)) => Some(EnvironmentPermissions {
+                    allow: union(&base.allow, &own.allow),
+                }),
+                (base, own) => own.clone().or_else(|| base.clone()),
+            },
+            runtime: own.runtime.clone().or_else(|| base.runtime.clone()),
+            resources: own.resources.clone().or_else(|| base.resources.clone()),
+            ipc: merge_lists(&base.ipc, &own.ipc),
+        },
+    }
+}
+
+fn merge_lists<T: Clone + PartialEq>(
+    base: &Option<PermissionList<T>>,
+    own: &Option<PermissionList<T>>,
+) -> Option<PermissionList<T>> {
+    match (base, own) {
+        (Some(base), Some(own)) => Some(PermissionList {
+            allow: union(&base.allow, &own.allow),
+            deny: union(&base.deny, &own.deny),
+        }),
+        (base, own) => own.clone().or_else(|| base.clone()),
+    }
+}
+
+fn union<T: Clone + PartialEq>(base: &Option<Vec<T>>, own: &Option<Vec<T>>) -> Option<Vec<T>> {
+    match (base, own) {
+        (Some(base), Some(own)) => {
+            let mut rules = base.clone();
+            for rule in own {
+                if !rules.contains(rule) {
+                    rules.push(rule.clone());
+                }
+            }
+            Some(rules)
+        }
+        (base, own) => own.clone().or_else(|| base.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NetworkPermission, PolicyParser};
+
+    #[test]
+    fn test_merge_unions_rules_and_prefers_component_settings() {
+        let default = PolicyParser::parse_str(
+            r#"
+version: "1.0"
+description: "Shared by every component"
+permissions:
+  network:
+    allow:
+      - host: "dns.google"
+      - host: "api.example.com"
+  environment:
+    allow:
+      - key: "LANG"
+  resources:
+    limits:
+      memory: "256Mi"
+"#,
+        )
+        .unwrap();
+        let policy = PolicyParser::parse_str(
+            r#"
+version: "1.2"
+permissions:
+  network:
+    allow:
+      - host: "api.example.com"
+      - host: "weather.example.com"
+  storage:
+    allow:
+      - uri: "fs:///data"
+        access: ["read"]
+  resources:
+    limits:
+      memory: "1Gi"
+"#,
+        )
+        .unwrap();
+
+        let effective = merge(&default, &policy);
+        assert_eq!(effective.version, "1.2");
+        assert_eq!(effective.description, None);
+
+        let hosts: Vec<_> = effective
+            .permissions
+            .network
+            .unwrap()
+            .allow
+            .unwrap()
+            .into_iter()
+            .map(|rule| match rule {
+                NetworkPermission::Host(host) => host.host,
+                NetworkPermission::Cidr(cidr) => cidr.cidr,
+            })
+            .collect();
+        assert_eq!(
+            hosts,
+            vec!["dns.google", "api.example.com", "weather.example.com"]
+        );
+        assert_eq!(
+            effective.permissions.storage.unwrap().allow.unwrap()[0].uri,
+            "fs:///data"
+        );
+        assert_eq!(
+            effective.permissions.environment.unwrap().allow.unwrap()[0].key,
+            "LANG"
+        );
+        assert_eq!(
+            effective.permissions.resources,
+            policy.permissions.resources
+        );
+
+        // Merging with an empty default changes nothing
+        assert_eq!(merge(&PolicyDocument::default(), &policy), policy);
+    }
+}
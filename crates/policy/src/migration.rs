@@ -0,0 +1,220 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Upgrades of policy documents written for older versions of the policy format.
+//!
+//! Each registered migration rewrites a raw YAML document from one version to the next.
+//! Loading a policy applies the chain starting at its declared `version` until it reaches
+//! [`CURRENT_POLICY_VERSION`], so old policies keep loading as the format evolves.
+
+use anyhow::{bail, Context};
+use serde_yaml::{Mapping, Value};
+
+use crate::PolicyResult;
+
+/// Version of the policy format written by this crate
+pub const CURRENT_POLICY_VERSION: &str = "1.2";
+
+/// Upgrade of a raw policy document from one format version to the next
+struct Migration {
+    from: &'static str,
+    to: &'static str,
+    apply: fn(&mut Mapping) -> PolicyResult<()>,
+}
+
+/// Registered migrations, each starting where an earlier one ends
+const MIGRATIONS: &[Migration] = &[
+    // 1.1 only added optional sections, so 1.0 documents are already valid 1.1
+    Migration {
+        from: "1.0",
+        to: "1.1",
+        apply: unchanged,
+    },
+    Migration {
+        from: "1.1",
+        to: "1.2",
+        apply: move_legacy_resource_limits,
+    },
+];
+
+/// Upgrade `document` in place to [`CURRENT_POLICY_VERSION`], returning the version it
+/// declared if any migration ran.
+///
+/// Documents declaring a version newer than this crate understands are rejected rather
+/// than guessed at.
+pub fn migrate(document: &mut Value) -> PolicyResult<Option<String>> {
+    let root = document
+        .as_mapping_mut()
+        .context("Policy document must be a YAML mapping")?;
+    let original = match root.get("version") {
+        Some(Value::String(version)) => version.clone(),
+        Some(other) => bail!(
+            "Policy version must be a string such as \"{CURRENT_POLICY_VERSION}\", found {}",
+            serde_yaml::to_string(other)?.trim()
+        ),
+        None => bail!("Policy document has no version"),
+    };
+
+    let mut version = original.clone();
+    while version != CURRENT_POLICY_VERSION {
+        let Some(migration) = MIGRATIONS.iter().find(|m| m.from == version) else {
+            if is_newer_than_current(&version) {
+                bail!(
+                    "Policy version {version} is newer than the newest supported version \
+                     {CURRENT_POLICY_VERSION}; upgrade wassette to load it"
+                );
+            }
+            bail!("Unsupported version: {version}");
+        };
+        (migration.apply)(root).with_context(|| {
+            format!(
+                "Failed to migrate policy from version {} to {}",
+                migration.from, migration.to
+            )
+        })?;
+        version = migration.to.to_string();
+    }
+
+    if version == original {
+        return Ok(None);
+    }
+    root.insert("version".into(), Value::String(version));
+    Ok(Some(original))
+}
+
+fn unchanged(_: &mut Mapping) -> PolicyResult<()> {
+    Ok(())
+}
+
+fn is_newer_than_current(version: &str) -> bool {
+    let parse = |version: &str| -> Option<(u32, u32)> {
+        let (major, minor) = version.split_once('.')?;
+        Some((major.parse().ok()?, minor.parse().ok()?))
+    };
+    match (parse(version), parse(CURRENT_POLICY_VERSION)) {
+        (Some(version), Some(current)) => version > current,
+        _ => false,
+    }
+}
+
+/// 1.1 → 1.2: the top-level `resources.cpu` (cores) and `resources.memory` (MiB) fields move
+/// under `resources.limits`, unless `limits` already sets them.
+fn move_legacy_resource_limits(root: &mut Mapping) -> PolicyResult<()> {
+    let Some(resources) = root
+        .get_mut("permissions")
+        .and_then(|permissions| permissions.get_mut("resources"))
+        .and_then(Value::as_mapping_mut)
+    else {
+        return Ok(());
+    };
+
+    let mut limits = match resources.remove("limits") {
+        Some(Value::Mapping(limits)) => limits,
+        Some(Value::Null) | None => Mapping::new(),
+        Some(_) => bail!("permissions.resources.limits must be a mapping"),
+    };
+    if let Some(cpu) = resources.remove("cpu") {
+        limits.entry("cpu".into()).or_insert(cpu);
+    }
+    if let Some(memory) = resources.remove("memory") {
+        let mebibytes = memory
+            .as_u64()
+            .context("permissions.resources.memory must be a whole number of MiB")?;
+        limits
+            .entry("memory".into())
+            .or_insert_with(|| Value::String(format!("{mebibytes}Mi")));
+    }
+    if !limits.is_empty() {
+        resources.insert("limits".into(), Value::Mapping(limits));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CpuLimit, MemoryLimit, NetworkPermission, PolicyParser};
+
+    #[test]
+    fn test_v1_policy_migrates_to_current_version() {
+        let parsed = PolicyParser::parse_str_checked(
+            r#"
+version: "1.0"
+description: "Written before resource limits moved"
+permissions:
+  network:
+    allow:
+      - host: "api.example.com"
+  storage:
+    allow:
+      - uri: "fs:///data"
+        access: ["read"]
+  resources:
+    cpu: 2
+    memory: 512
+    io: 1000
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(parsed.migrated_from.as_deref(), Some("1.0"));
+        let policy = parsed.document;
+        assert_eq!(policy.version, CURRENT_POLICY_VERSION);
+
+        // Existing rules survive
+        let network = policy.permissions.network.unwrap().allow.unwrap();
+        assert!(
+            matches!(&network[..], [NetworkPermission::Host(h)] if h.host == "api.example.com")
+        );
+        assert_eq!(
+            policy.permissions.storage.unwrap().allow.unwrap()[0].uri,
+            "fs:///data"
+        );
+
+        // Legacy resource fields now live under `limits`
+        let resources = policy.permissions.resources.unwrap();
+        let limits = resources.limits.unwrap();
+        assert_eq!(limits.cpu, Some(CpuLimit::Number(2.0)));
+        assert_eq!(
+            limits.memory,
+            Some(MemoryLimit::String("512Mi".to_string()))
+        );
+        assert_eq!(resources.cpu, None);
+        assert_eq!(resources.memory, None);
+        assert_eq!(resources.io, Some(1000));
+    }
+
+    #[test]
+    fn test_migration_keeps_explicit_limits_and_current_documents() {
+        let mut document: Value = serde_yaml::from_str(
+            r#"
+version: "1.1"
+permissions:
+  resources:
+    limits:
+      memory: "1Gi"
+    memory: 256
+"#,
+        )
+        .unwrap();
+        assert_eq!(migrate(&mut document).unwrap().as_deref(), Some("1.1"));
+        assert_eq!(
+            document["permissions"]["resources"]["limits"]["memory"],
+            Value::from("1Gi")
+        );
+
+        assert_eq!(migrate(&mut document).unwrap(), None);
+    }
+
+    #[test]
+    fn test_future_policy_version_is_rejected() {
+        let mut document: Value =
+            serde_yaml::from_str("version: \"1.9\"\npermissions: {}").unwrap();
+        let err = migrate(&mut document).unwrap_err().to_string();
+        assert!(err.contains("newer than the newest supported version"));
+
+        let mut document: Value =
+            serde_yaml::from_str("version: \"2.0\"\npermissions: {}").unwrap();
+        assert!(migrate(&mut document).is_err());
+    }
+}
@@ -4,14 +4,28 @@
 use std::fs;
 use std::path::Path;
 
-use anyhow::Context;
+use anyhow::{bail, Context};
 
-use crate::{PolicyDocument, PolicyResult};
+use crate::{migration, Permissions, PolicyDocument, PolicyResult};
 
 pub struct PolicyParser;
 
+/// A parsed policy document together with the permission types it declared but this
+/// version of the parser does not recognize
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedPolicy {
+    /// The policy with all recognized permissions applied
+    pub document: PolicyDocument,
+    /// Unrecognized keys under `permissions`, in document order
+    pub unknown_permissions: Vec<String>,
+    /// Version the document declared, if it was migrated to
+    /// [`CURRENT_POLICY_VERSION`](crate::CURRENT_POLICY_VERSION)
+    pub migrated_from: Option<String>,
+}
+
 impl PolicyParser {
-    /// Parse a policy document from a YAML string
+    /// Parse a policy document from a YAML string. Documents written for an older version of
+    /// the policy format are [migrated](crate::migration) to the current one.
     ///
     /// # Example
     ///
@@ -29,12 +43,75 @@ impl PolicyParser {
     /// "#;
     ///
     /// let policy = PolicyParser::parse_str(yaml_content).unwrap();
-    /// assert_eq!(policy.version, "1.0");
+    /// assert_eq!(policy.version, policy::CURRENT_POLICY_VERSION);
     /// ```
     pub fn parse_str(content: impl AsRef<str>) -> PolicyResult<PolicyDocument> {
-        let document: PolicyDocument = serde_yaml::from_str(content.as_ref())?;
+        Ok(Self::parse_str_checked(content)?.document)
+    }
+
+    /// Parse a policy document, reporting unrecognized permission types instead of
+    /// silently dropping them. Documents written for an older version of the policy format
+    /// are [migrated](crate::migration) to the current one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use policy::PolicyParser;
+    ///
+    /// let yaml_content = r#"
+    /// version: "1.0"
+    /// permissions:
+    ///   gpu:
+    ///     allow: ["cuda"]
+    ///   network:
+    ///     allow:
+    ///     - host: "api.example.com"
+    /// "#;
+    ///
+    /// let parsed = PolicyParser::parse_str_checked(yaml_content).unwrap();
+    /// assert_eq!(parsed.unknown_permissions, vec!["gpu".to_string()]);
+    /// assert!(parsed.document.permissions.network.is_some());
+    /// ```
+    pub fn parse_str_checked(content: impl AsRef<str>) -> PolicyResult<ParsedPolicy> {
+        let mut raw: serde_yaml::Value = serde_yaml::from_str(content.as_ref())?;
+        let migrated_from = migration::migrate(&mut raw)?;
+
+        let unknown_permissions = raw
+            .get("permissions")
+            .and_then(serde_yaml::Value::as_mapping)
+            .map(|permissions| {
+                permissions
+                    .keys()
+                    .map(|key| match key.as_str() {
+                        Some(name) => name.to_string(),
+                        None => serde_yaml::to_string(key)
+                            .map(|name| name.trim().to_string())
+                            .unwrap_or_default(),
+                    })
+                    .filter(|name| !Permissions::KNOWN_TYPES.contains(&name.as_str()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let document: PolicyDocument = serde_yaml::from_value(raw)?;
         document.validate()?;
-        Ok(document)
+        Ok(ParsedPolicy {
+            document,
+            unknown_permissions,
+            migrated_from,
+        })
+    }
+
+    /// Parse a policy document, failing if it declares any unrecognized permission type
+    pub fn parse_str_strict(content: impl AsRef<str>) -> PolicyResult<PolicyDocument> {
+        let parsed = Self::parse_str_checked(content)?;
+        if !parsed.unknown_permissions.is_empty() {
+            bail!(
+                "Unknown permission type(s) in policy: {}",
+                parsed.unknown_permissions.join(", ")
+            );
+        }
+        Ok(parsed.document)
     }
 
     /// Parse a policy document from a file path
@@ -60,7 +137,7 @@ impl PolicyParser {
     /// use policy::PolicyParser;
     ///
     /// let policy = PolicyParser::parse_bytes(b"version: '1.0'\npermissions: {}").unwrap();
-    /// assert_eq!(policy.version, "1.0");
+    /// assert_eq!(policy.version, policy::CURRENT_POLICY_VERSION);
     /// ```
     pub fn parse_bytes(bytes: &[u8]) -> PolicyResult<PolicyDocument> {
         let content = std::str::from_utf8(bytes).context("Not valid UTF-8")?;
@@ -115,7 +192,7 @@ mod tests {
     use super::*;
     use crate::{
         AccessType, CapabilityAction, CpuLimit, MemoryLimit, PermissionList, Permissions,
-        StoragePermission,
+        StoragePermission, CURRENT_POLICY_VERSION,
     };
 
     #[test]
@@ -131,7 +208,7 @@ permissions:
 "#;
 
         let policy = PolicyParser::parse_str(yaml_content).unwrap();
-        assert_eq!(policy.version, "1.0");
+        assert_eq!(policy.version, CURRENT_POLICY_VERSION);
         assert_eq!(policy.description, Some("Test policy".to_string()));
 
         let storage = policy.permissions.storage.unwrap();
@@ -144,6 +221,32 @@ permissions:
         );
     }
 
+    #[test]
+    fn test_every_entry_point_migrates_old_policies() {
+        let yaml_content = r#"
+version: "1.1"
+permissions:
+  resources:
+    memory: 256
+"#;
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(yaml_content.as_bytes()).unwrap();
+
+        for policy in [
+            PolicyParser::parse_str(yaml_content).unwrap(),
+            PolicyParser::parse_bytes(yaml_content.as_bytes()).unwrap(),
+            PolicyParser::parse_file(temp_file.path()).unwrap(),
+        ] {
+            assert_eq!(policy.version, CURRENT_POLICY_VERSION);
+            let resources = policy.permissions.resources.unwrap();
+            assert_eq!(
+                resources.limits.unwrap().memory,
+                Some(MemoryLimit::String("256Mi".to_string()))
+            );
+            assert_eq!(resources.memory, None);
+        }
+    }
+
     #[test]
     fn test_parse_str_invalid_version() {
         let yaml_content = r#"
@@ -210,7 +313,7 @@ permissions:
         temp_file.flush().unwrap();
 
         let policy = PolicyParser::parse_file(temp_file.path()).unwrap();
-        assert_eq!(policy.version, "1.0");
+        assert_eq!(policy.version, CURRENT_POLICY_VERSION);
         assert_eq!(policy.description, Some("File test policy".to_string()));
 
         let env = policy.permissions.environment.unwrap();
@@ -245,7 +348,7 @@ permissions: {}
 "#;
 
         let policy = PolicyParser::parse_bytes(yaml_content.as_bytes()).unwrap();
-        assert_eq!(policy.version, "1.0");
+        assert_eq!(policy.version, CURRENT_POLICY_VERSION);
         assert_eq!(policy.description, Some("Bytes test".to_string()));
     }
 
@@ -259,7 +362,7 @@ permissions: {}
     #[test]
     fn test_parse_testdata_minimal() {
         let policy = PolicyParser::parse_file("testdata/minimal.yaml").unwrap();
-        assert_eq!(policy.version, "1.0");
+        assert_eq!(policy.version, CURRENT_POLICY_VERSION);
         assert_eq!(policy.description, Some("Minimal valid policy".to_string()));
         assert!(policy.permissions.storage.is_none());
         assert!(policy.permissions.network.is_none());
@@ -270,7 +373,7 @@ permissions: {}
     #[test]
     fn test_parse_testdata_storage_only() {
         let policy = PolicyParser::parse_file("testdata/storage-only.yaml").unwrap();
-        assert_eq!(policy.version, "1.1");
+        assert_eq!(policy.version, CURRENT_POLICY_VERSION);
         assert_eq!(
             policy.description,
             Some("Storage-only policy for file system access".to_string())
@@ -292,7 +395,7 @@ permissions: {}
     #[test]
     fn test_parse_testdata_network_only() {
         let policy = PolicyParser::parse_file("testdata/network-only.yaml").unwrap();
-        assert_eq!(policy.version, "1.0");
+        assert_eq!(policy.version, CURRENT_POLICY_VERSION);
         assert_eq!(
             policy.description,
             Some("Network-only policy for API access".to_string())
@@ -309,7 +412,7 @@ permissions: {}
     #[test]
     fn test_parse_testdata_environment_only() {
         let policy = PolicyParser::parse_file("testdata/environment-only.yaml").unwrap();
-        assert_eq!(policy.version, "1.0");
+        assert_eq!(policy.version, CURRENT_POLICY_VERSION);
         assert_eq!(
             policy.description,
             Some("Environment-only policy for basic shell access".to_string())
@@ -327,7 +430,7 @@ permissions: {}
     #[test]
     fn test_parse_testdata_comprehensive() {
         let policy = PolicyParser::parse_file("testdata/comprehensive.yaml").unwrap();
-        assert_eq!(policy.version, "1.0");
+        assert_eq!(policy.version, CURRENT_POLICY_VERSION);
         assert_eq!(
             policy.description,
             Some("Comprehensive policy with all permission types".to_string())
@@ -354,7 +457,7 @@ permissions: {}
     #[test]
     fn test_parse_testdata_docker_privileged() {
         let policy = PolicyParser::parse_file("testdata/docker-privileged.yaml").unwrap();
-        assert_eq!(policy.version, "1.0");
+        assert_eq!(policy.version, CURRENT_POLICY_VERSION);
         assert_eq!(
             policy.description,
             Some("Policy with privileged Docker runtime for system administration".to_string())
@@ -375,7 +478,7 @@ permissions: {}
     #[test]
     fn test_parse_testdata_restricted() {
         let policy = PolicyParser::parse_file("testdata/restricted.yaml").unwrap();
-        assert_eq!(policy.version, "1.0");
+        assert_eq!(policy.version, CURRENT_POLICY_VERSION);
         assert_eq!(
             policy.description,
             Some("Highly restricted policy for untrusted code".to_string())
@@ -387,16 +490,21 @@ permissions: {}
         assert_eq!(allow_list[0].access, vec![AccessType::Read]);
         assert_eq!(allow_list[1].access, vec![AccessType::Write]);
 
+        // Legacy resource fields are migrated under `limits`
         let resources = policy.permissions.resources.unwrap();
-        assert_eq!(resources.cpu, Some(10.0));
-        assert_eq!(resources.memory, Some(128));
+        let limits = resources.limits.unwrap();
+        assert_eq!(limits.cpu, Some(CpuLimit::Number(10.0)));
+        assert_eq!(
+            limits.memory,
+            Some(MemoryLimit::String("128Mi".to_string()))
+        );
         assert_eq!(resources.io, Some(100));
     }
 
     #[test]
     fn test_parse_testdata_development() {
         let policy = PolicyParser::parse_file("testdata/development.yaml").unwrap();
-        assert_eq!(policy.version, "1.0");
+        assert_eq!(policy.version, CURRENT_POLICY_VERSION);
         assert_eq!(
             policy.description,
             Some("Development environment policy with broad permissions".to_string())
@@ -418,7 +526,7 @@ permissions: {}
     #[test]
     fn test_parse_testdata_web_service() {
         let policy = PolicyParser::parse_file("testdata/web-service.yaml").unwrap();
-        assert_eq!(policy.version, "1.0");
+        assert_eq!(policy.version, CURRENT_POLICY_VERSION);
         assert_eq!(
             policy.description,
             Some("Web service policy for HTTP server deployment".to_string())
@@ -437,15 +545,19 @@ permissions: {}
         assert!(allow_list.iter().any(|e| e.key == "STRIPE_API_KEY"));
 
         let resources = policy.permissions.resources.unwrap();
-        assert_eq!(resources.cpu, Some(75.0));
-        assert_eq!(resources.memory, Some(512));
+        let limits = resources.limits.unwrap();
+        assert_eq!(limits.cpu, Some(CpuLimit::Number(75.0)));
+        assert_eq!(
+            limits.memory,
+            Some(MemoryLimit::String("512Mi".to_string()))
+        );
         assert_eq!(resources.io, Some(500));
     }
 
     #[test]
     fn test_parse_testdata_docker() {
         let policy = PolicyParser::parse_file("testdata/docker.yaml").unwrap();
-        assert_eq!(policy.version, "1.0");
+        assert_eq!(policy.version, CURRENT_POLICY_VERSION);
         assert_eq!(
             policy.description,
             Some("Permission policy for docker container".to_string())
@@ -484,7 +596,7 @@ permissions: {}
     #[test]
     fn test_parse_testdata_resource_limits() {
         let policy = PolicyParser::parse_file("testdata/resource-limits.yaml").unwrap();
-        assert_eq!(policy.version, "1.0");
+        assert_eq!(policy.version, CURRENT_POLICY_VERSION);
         assert_eq!(
             policy.description,
             Some("Policy with k8s-style resource limits".to_string())
@@ -563,4 +675,55 @@ permissions: {}
             });
         }
     }
+
+    #[test]
+    fn test_parse_str_checked_collects_unknown_permissions() {
+        let yaml_content = r#"
+version: "1.0"
+permissions:
+  gpu:
+    allow: ["cuda"]
+  storage:
+    allow:
+    - uri: "fs://work/**"
+      access: ["read"]
+  quantum: true
+"#;
+
+        let parsed = PolicyParser::parse_str_checked(yaml_content).unwrap();
+        assert_eq!(
+            parsed.unknown_permissions,
+            vec!["gpu".to_string(), "quantum".to_string()]
+        );
+
+        let storage = parsed.document.permissions.storage.unwrap();
+        assert_eq!(storage.allow.unwrap()[0].uri, "fs://work/**");
+
+        // The lenient default parser keeps accepting the same document
+        assert!(PolicyParser::parse_str(yaml_content).is_ok());
+    }
+
+    #[test]
+    fn test_parse_str_strict_rejects_unknown_permissions() {
+        let yaml_content = r#"
+version: "1.0"
+permissions:
+  gpu:
+    allow: ["cuda"]
+"#;
+
+        let err = PolicyParser::parse_str_strict(yaml_content).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Unknown permission type(s) in policy: gpu"));
+
+        let known_only = r#"
+version: "1.0"
+permissions:
+  network:
+    allow:
+    - host: "api.example.com"
+"#;
+        assert!(PolicyParser::parse_str_strict(known_only).is_ok());
+    }
 }
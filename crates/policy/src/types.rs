@@ -58,11 +58,28 @@ pub enum NetworkPermission {
 }
 
 /// Environment variable permission
+///
+/// key: Exact variable name, or a prefix followed by a single trailing `*` (e.g. `AWS_*`)
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EnvironmentPermission {
     pub key: String,
 }
 
+impl EnvironmentPermission {
+    /// The prefix matched by a trailing-wildcard key, or `None` for an exact key
+    pub fn prefix(&self) -> Option<&str> {
+        self.key.strip_suffix('*')
+    }
+
+    /// Whether this permission exposes the environment variable `name`
+    pub fn matches(&self, name: &str) -> bool {
+        match self.prefix() {
+            Some(prefix) => name.starts_with(prefix),
+            None => self.key == name,
+        }
+    }
+}
+
 /// Docker capability action
 ///
 /// TODO: Add more capabilities
@@ -390,6 +407,16 @@ impl ResourceLimits {
 }
 
 impl Permissions {
+    /// Permission types understood by this version of the policy format
+    pub const KNOWN_TYPES: &'static [&'static str] = &[
+        "storage",
+        "network",
+        "environment",
+        "runtime",
+        "resources",
+        "ipc",
+    ];
+
     fn validate_storage_uri(uri: &str) -> PolicyResult<()> {
         if uri.is_empty() {
             bail!("Storage URI can't be empty");
@@ -440,14 +467,25 @@ impl Permissions {
         Ok(())
     }
 
-    fn validate_environment_key(key: &str) -> PolicyResult<()> {
+    /// Validate an environment permission key. Only a single trailing wildcard after a
+    /// non-empty prefix is accepted; a bare `*` would expose the whole host environment.
+    pub fn validate_environment_key(key: &str) -> PolicyResult<()> {
         if key.is_empty() {
             bail!("Environment key can't be empty");
         }
 
-        // No wildcards in env vars - too risky
-        if key.contains('*') {
-            bail!("No wildcards allowed in environment keys: {}", key);
+        let name = key.strip_suffix('*').unwrap_or(key);
+        if name.is_empty() {
+            bail!(
+                "Environment key wildcard needs a prefix (e.g. MYAPP_*): {}",
+                key
+            );
+        }
+        if name.contains('*') {
+            bail!(
+                "Only a single trailing wildcard is allowed in environment keys: {}",
+                key
+            );
         }
 
         Ok(())
@@ -613,9 +651,10 @@ mod tests {
         assert!(Permissions::validate_environment_key("PATH").is_ok());
         assert!(Permissions::validate_environment_key("MY_VAR").is_ok());
         assert!(Permissions::validate_environment_key("HOME").is_ok());
+        assert!(Permissions::validate_environment_key("PATH_*").is_ok());
+        assert!(Permissions::validate_environment_key("AWS*").is_ok());
 
         assert!(Permissions::validate_environment_key("").is_err());
-        assert!(Permissions::validate_environment_key("PATH_*").is_err());
         assert!(Permissions::validate_environment_key("*_DEBUG").is_err());
         assert!(Permissions::validate_environment_key("*").is_err());
         assert!(Permissions::validate_environment_key("PA*TH").is_err());
@@ -908,9 +947,28 @@ mod tests {
         permissions = Permissions::default();
         permissions.environment = Some(EnvironmentPermissions {
             allow: Some(vec![EnvironmentPermission {
-                key: "PATH_WITH_WILDCARD_*".to_string(),
+                key: "PATH_*_WILDCARD".to_string(), // Invalid: * in middle
             }]),
         });
         assert!(permissions.validate().is_err());
     }
+
+    #[test]
+    fn test_environment_permission_matching() {
+        let exact = EnvironmentPermission {
+            key: "AWS_REGION".to_string(),
+        };
+        assert!(exact.prefix().is_none());
+        assert!(exact.matches("AWS_REGION"));
+        assert!(!exact.matches("AWS_REGION_OVERRIDE"));
+
+        let family = EnvironmentPermission {
+            key: "AWS_*".to_string(),
+        };
+        assert_eq!(family.prefix(), Some("AWS_"));
+        assert!(family.matches("AWS_REGION"));
+        assert!(family.matches("AWS_SECRET_ACCESS_KEY"));
+        assert!(!family.matches("AWSOME"));
+        assert!(!family.matches("MY_AWS_KEY"));
+    }
 }
@@ -0,0 +1,161 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Reconciliation of the capabilities a component imports with the permissions its policy grants
+
+use std::collections::BTreeSet;
+
+use policy::PolicyDocument;
+use serde::{Deserialize, Serialize};
+
+use crate::DeclaredPermission;
+
+/// Capabilities a component needs compared with the permission categories it has been granted
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PermissionAudit {
+    /// Identifier of the audited component
+    pub component_id: String,
+    /// Capabilities the component imports
+    pub required: Vec<String>,
+    /// Permission categories the component's effective policy allows
+    pub granted: Vec<String>,
+    /// Imported capabilities that no grant covers
+    pub gaps: Vec<String>,
+    /// Granted categories the component never imports
+    pub over_grants: Vec<String>,
+    /// Rules the component declares for its gaps, which granting would close them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suggested_grants: Vec<DeclaredPermission>,
+}
+
+impl PermissionAudit {
+    /// Cross-reference required capabilities with granted permission categories
+    pub fn between(
+        component_id: impl Into<String>,
+        required: &BTreeSet<String>,
+        granted: &BTreeSet<String>,
+    ) -> Self {
+        Self {
+            component_id: component_id.into(),
+            required: required.iter().cloned().collect(),
+            granted: granted.iter().cloned().collect(),
+            gaps: required.difference(granted).cloned().collect(),
+            over_grants: granted.difference(required).cloned().collect(),
+            suggested_grants: Vec::new(),
+        }
+    }
+
+    /// Suggest the `declared` permissions whose type is one of the gaps
+    pub fn with_suggestions(mut self, declared: Vec<DeclaredPermission>) -> Self {
+        self.suggested_grants = declared
+            .into_iter()
+            .filter(|permission| self.gaps.contains(&permission.permission_type))
+            .collect();
+        self
+    }
+
+    /// Whether every required capability is granted and nothing extra is
+    pub fn is_reconciled(&self) -> bool {
+        self.gaps.is_empty() && self.over_grants.is_empty()
+    }
+}
+
+/// Permission categories a policy allows at least one rule for
+pub(crate) fn granted_capabilities(policy: &PolicyDocument) -> BTreeSet<String> {
+    let permissions = &policy.permissions;
+    [
+        (
+            "network",
+            has_rules(permissions.network.as_ref().and_then(|n| n.allow.as_ref())),
+        ),
+        (
+            "storage",
+            has_rules(permissions.storage.as_ref().and_then(|s| s.allow.as_ref())),
+        ),
+        (
+            "environment",
+            has_rules(
+                permissions
+                    .environment
+                    .as_ref()
+                    .and_then(|e| e.allow.as_ref()),
+            ),
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, granted)| *granted)
+    .map(|(category, _)| category.to_string())
+    .collect()
+}
+
+fn has_rules<T>(allow: Option<&Vec<T>>) -> bool {
+    allow.is_some_and(|rules| !rules.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(items: &[&str]) -> BTreeSet<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_audit_reports_gaps_and_over_grants() {
+        let audit = PermissionAudit::between(
+            "fetch_rs",
+            &set(&["environment", "network"]),
+            &set(&["network", "storage"]),
+        );
+
+        assert_eq!(audit.gaps, vec!["environment"]);
+        assert_eq!(audit.over_grants, vec!["storage"]);
+        assert!(!audit.is_reconciled());
+
+        let audit = PermissionAudit::between("fetch_rs", &set(&["network"]), &set(&["network"]));
+        assert!(audit.is_reconciled());
+    }
+
+    #[test]
+    fn test_suggestions_only_cover_gaps() {
+        let declared = |permission_type: &str, details: serde_json::Value| DeclaredPermission {
+            permission_type: permission_type.to_string(),
+            details,
+        };
+        let audit = PermissionAudit::between(
+            "fetch_rs",
+            &set(&["environment", "network"]),
+            &set(&["environment"]),
+        )
+        .with_suggestions(vec![
+            declared("network", serde_json::json!({ "host": "api.example.com" })),
+            declared("environment", serde_json::json!({ "key": "API_KEY" })),
+        ]);
+
+        assert_eq!(
+            audit.suggested_grants,
+            vec![declared(
+                "network",
+                serde_json::json!({ "host": "api.example.com" })
+            )]
+        );
+    }
+
+    #[test]
+    fn test_granted_capabilities_ignores_empty_allow_lists() {
+        let policy: PolicyDocument = serde_yaml::from_str(
+            r#"
+version: "1.0"
+permissions:
+  network:
+    allow:
+      - host: "api.example.com"
+  storage:
+    allow: []
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(granted_capabilities(&policy), set(&["network"]));
+    }
+}
@@ -0,0 +1,166 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Gzipped tarballs holding a component and its policy, as written by
+//! [`LifecycleManager::export_component`](crate::LifecycleManager::export_component).
+//!
+//! A bundle contains `<id>.wasm` and, when the component has one, `<id>.policy.yaml` at its
+//! top level. Loading a bundle installs both, like a multi-layer OCI artifact.
+
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use crate::loader::DownloadedResource;
+
+/// File name suffixes recognized as component bundles
+const BUNDLE_SUFFIXES: [&str; 2] = [".tar.gz", ".tgz"];
+
+/// Whether `path` names a component bundle rather than a bare `.wasm` file
+pub(crate) fn is_bundle(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| BUNDLE_SUFFIXES.iter().any(|suffix| name.ends_with(suffix)))
+}
+
+/// Write a bundle for `component_id` to `out`
+pub(crate) fn write_bundle(
+    out: &Path,
+    component_id: &str,
+    wasm: &Path,
+    policy: Option<&Path>,
+) -> Result<()> {
+    let file = File::create(out)
+        .with_context(|| format!("Failed to create bundle at {}", out.display()))?;
+    let mut archive = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+    archive
+        .append_path_with_name(wasm, format!("{component_id}.wasm"))
+        .context("Failed to add component to bundle")?;
+    if let Some(policy) = policy {
+        archive
+            .append_path_with_name(policy, format!("{component_id}.policy.yaml"))
+            .context("Failed to add policy to bundle")?;
+    }
+
+    archive
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .with_context(|| format!("Failed to write bundle at {}", out.display()))?;
+    Ok(())
+}
+
+/// Unpack a bundle into a temporary directory and return the component it contains
+pub(crate) async fn open_bundle(path: &Path) -> Result<DownloadedResource> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let tempdir = tempfile::tempdir()?;
+        let wasm = extract_bundle(&path, tempdir.path())
+            .with_context(|| format!("Failed to unpack component bundle {}", path.display()))?;
+        Ok(DownloadedResource::Temp((tempdir, wasm)))
+    })
+    .await?
+}
+
+/// Extract the top-level `.wasm` and `.policy.yaml` entries of a bundle into `dest`
+fn extract_bundle(bundle: &Path, dest: &Path) -> Result<PathBuf> {
+    let mut archive = tar::Archive::new(GzDecoder::new(File::open(bundle)?));
+    let mut wasm = None;
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let entry_path = entry.path()?.into_owned();
+        let name = match (entry_path.parent(), entry_path.file_name()) {
+            (Some(parent), Some(name)) if parent.as_os_str().is_empty() => {
+                name.to_string_lossy().into_owned()
+            }
+            _ => bail!(
+                "Unexpected entry '{}'; bundles may only contain files at the top level",
+                entry_path.display()
+            ),
+        };
+        if !entry.header().entry_type().is_file() {
+            bail!("Unexpected entry '{name}'; bundles may only contain regular files");
+        }
+
+        if name.ends_with(".wasm") {
+            if wasm.is_some() {
+                bail!("Bundle contains more than one component");
+            }
+            wasm = Some(dest.join(&name));
+        } else if !name.ends_with(".policy.yaml") {
+            continue;
+        }
+        entry.unpack(dest.join(&name))?;
+    }
+
+    let wasm = wasm.context("Bundle does not contain a .wasm component")?;
+    let stem = wasm
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+    if !dest.join(format!("{stem}.policy.yaml")).exists() {
+        // A policy for a different component name would never be installed; reject it
+        // rather than silently dropping permissions.
+        let stray_policy = std::fs::read_dir(dest)?
+            .flatten()
+            .any(|e| e.file_name().to_string_lossy().ends_with(".policy.yaml"));
+        if stray_policy {
+            bail!("Bundle policy does not match component '{stem}'");
+        }
+    }
+    Ok(wasm)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundle_round_trip() -> Result<()> {
+        let source = tempfile::tempdir()?;
+        let wasm = source.path().join("fetch.wasm");
+        let policy = source.path().join("fetch.policy.yaml");
+        std::fs::write(&wasm, b"\0asm")?;
+        std::fs::write(&policy, "version: \"1.0\"\n")?;
+
+        let bundle = source.path().join("fetch.tar.gz");
+        write_bundle(&bundle, "myfetch", &wasm, Some(&policy))?;
+        assert!(is_bundle(&bundle));
+        assert!(!is_bundle(&wasm));
+
+        let dest = tempfile::tempdir()?;
+        let extracted = extract_bundle(&bundle, dest.path())?;
+        assert_eq!(extracted, dest.path().join("myfetch.wasm"));
+        assert_eq!(std::fs::read(&extracted)?, b"\0asm");
+        assert_eq!(
+            std::fs::read_to_string(dest.path().join("myfetch.policy.yaml"))?,
+            "version: \"1.0\"\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_bundle_rejects_nested_entries() -> Result<()> {
+        let source = tempfile::tempdir()?;
+        let wasm = source.path().join("fetch.wasm");
+        std::fs::write(&wasm, b"\0asm")?;
+
+        let bundle = source.path().join("escape.tgz");
+        let mut archive = tar::Builder::new(GzEncoder::new(
+            File::create(&bundle)?,
+            Compression::default(),
+        ));
+        archive.append_path_with_name(&wasm, "nested/fetch.wasm")?;
+        archive.into_inner()?.finish()?;
+
+        let dest = tempfile::tempdir()?;
+        let err = extract_bundle(&bundle, dest.path()).unwrap_err();
+        assert!(err.to_string().contains("top level"));
+        Ok(())
+    }
+}
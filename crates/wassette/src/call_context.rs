@@ -0,0 +1,138 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Trusted values, such as the tenant an authenticated client belongs to, that the host hands
+//! to a component for the duration of one call.
+//!
+//! The values are added to the environment (and `wasi:config`) of the instance created for
+//! each call made inside [`with_call_context`]. They override variables of the same name and
+//! are not subject to the component's environment policy, since they come from the host and
+//! never from tool arguments.
+//!
+//! When the host enforces a time limit on the call, the milliseconds left until it expires
+//! are exposed the same way as [`DEADLINE_ENV`], so a cooperative component can bound its
+//! own work. The limit itself is still enforced by the host, which interrupts the instance
+//! once the deadline passes even if it never yields.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::Arc;
+
+use tokio::time::Instant;
+
+/// Environment variable holding the milliseconds left before the host abandons the call
+pub const DEADLINE_ENV: &str = "WASSETTE_DEADLINE_MS";
+
+tokio::task_local! {
+    static CALL_CONTEXT: Arc<BTreeMap<String, String>>;
+    static CALL_DEADLINE: Instant;
+    static LOAD_DEADLINE: Instant;
+}
+
+/// Run `call` with `context` exposed to every component instantiated on the current task.
+/// Calls nested inside another context see both, with `context` taking precedence.
+pub async fn with_call_context<F: Future>(context: BTreeMap<String, String>, call: F) -> F::Output {
+    let mut merged = current_call_context()
+        .map(|outer| outer.as_ref().clone())
+        .unwrap_or_default();
+    merged.extend(context);
+    CALL_CONTEXT.scope(Arc::new(merged), call).await
+}
+
+/// Context of the call running on the current task, if any
+pub(crate) fn current_call_context() -> Option<Arc<BTreeMap<String, String>>> {
+    CALL_CONTEXT.try_with(Arc::clone).ok()
+}
+
+/// Run `call` with `deadline` exposed to every component instantiated on the current task.
+/// An earlier deadline of an enclosing call is kept.
+pub async fn with_call_deadline<F: Future>(deadline: Instant, call: F) -> F::Output {
+    let deadline = CALL_DEADLINE
+        .try_with(|outer| deadline.min(*outer))
+        .unwrap_or(deadline);
+    CALL_DEADLINE.scope(deadline, call).await
+}
+
+/// Deadline of the call running on the current task, if any
+pub(crate) fn current_call_deadline() -> Option<Instant> {
+    CALL_DEADLINE.try_with(|deadline| *deadline).ok()
+}
+
+/// Run `load` so that component code it runs, such as an `init` hook, is interrupted at
+/// `deadline`. Unlike a call deadline it is not exposed to the component.
+pub(crate) async fn with_load_deadline<F: Future>(deadline: Instant, load: F) -> F::Output {
+    LOAD_DEADLINE.scope(deadline, load).await
+}
+
+/// When component code running on the current task is interrupted: the earlier of the call
+/// and load deadlines, if any
+pub(crate) fn current_interrupt_deadline() -> Option<Instant> {
+    let load = LOAD_DEADLINE.try_with(|deadline| *deadline).ok();
+    match (current_call_deadline(), load) {
+        (Some(call), Some(load)) => Some(call.min(load)),
+        (call, load) => call.or(load),
+    }
+}
+
+/// Variables to add to the environment of a component instantiated on the current task
+pub(crate) fn current_call_environment() -> Vec<(String, String)> {
+    let mut environment: Vec<(String, String)> = current_call_context()
+        .map(|context| {
+            context
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect()
+        })
+        .unwrap_or_default();
+    if let Some(deadline) = current_call_deadline() {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        environment.push((DEADLINE_ENV.to_string(), remaining.as_millis().to_string()));
+    }
+    environment
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn context(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_nested_context_overrides_outer_values() {
+        assert!(current_call_context().is_none());
+        let seen = with_call_context(
+            context(&[("TENANT_ID", "acme"), ("REGION", "eu")]),
+            with_call_context(context(&[("REGION", "us")]), async {
+                current_call_context().unwrap()
+            }),
+        )
+        .await;
+        assert_eq!(*seen, context(&[("REGION", "us"), ("TENANT_ID", "acme")]));
+        assert!(current_call_context().is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_deadline_reports_remaining_time_of_earliest_limit() {
+        assert!(current_call_environment().is_empty());
+        let now = Instant::now();
+        let environment = with_call_deadline(
+            now + Duration::from_secs(2),
+            with_call_deadline(now + Duration::from_secs(5), async {
+                tokio::time::advance(Duration::from_millis(500)).await;
+                current_call_environment()
+            }),
+        )
+        .await;
+        assert_eq!(
+            environment,
+            [(DEADLINE_ENV.to_string(), "1500".to_string())]
+        );
+    }
+}
@@ -0,0 +1,347 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Bounded buffers of the stdout and stderr lines components write while handling calls.
+//!
+//! Each component keeps its most recent lines in memory, and an optional budget bounds the
+//! bytes held across all components by evicting the oldest lines first, whichever component
+//! wrote them. The lines are also appended to
+//! `logs/<component_id>.log` in the component directory as JSON lines so that another
+//! process, such as `wassette component logs`, can read and follow them.
+
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+
+/// Number of lines kept for each component
+pub const DEFAULT_LOG_CAPACITY: usize = 1000;
+
+/// Size at which a log file is compacted down to the lines held in memory
+const MAX_LOG_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Output stream a captured line was written to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+impl std::fmt::Display for LogStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LogStream::Stdout => f.write_str("stdout"),
+            LogStream::Stderr => f.write_str("stderr"),
+        }
+    }
+}
+
+/// A single line of component output
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogLine {
+    /// Stream the line was written to
+    pub stream: LogStream,
+    /// Line content without the trailing newline
+    pub line: String,
+}
+
+impl LogLine {
+    /// Parse one line of a component log file
+    pub fn parse(raw: &str) -> Option<Self> {
+        serde_json::from_str(raw).ok()
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct ComponentLogs {
+    log_dir: PathBuf,
+    capacity: usize,
+    max_total_bytes: Option<usize>,
+    buffers: Arc<Mutex<LogBuffers>>,
+}
+
+/// Retained lines of every component, each tagged with the order it was recorded in
+#[derive(Default)]
+struct LogBuffers {
+    components: HashMap<String, VecDeque<(u64, LogLine)>>,
+    next_sequence: u64,
+    total_bytes: usize,
+}
+
+impl LogBuffers {
+    fn push(&mut self, component_id: &str, lines: impl IntoIterator<Item = LogLine>) {
+        let buffer = self.components.entry(component_id.to_string()).or_default();
+        for line in lines {
+            self.total_bytes += line.line.len();
+            buffer.push_back((self.next_sequence, line));
+            self.next_sequence += 1;
+        }
+    }
+
+    /// Drop the oldest lines of the component beyond `capacity`
+    fn truncate(&mut self, component_id: &str, capacity: usize) {
+        if let Some(buffer) = self.components.get_mut(component_id) {
+            let excess = buffer.len().saturating_sub(capacity);
+            for (_, line) in buffer.drain(..excess) {
+                self.total_bytes -= line.line.len();
+            }
+        }
+    }
+
+    /// Drop the oldest lines across all components until at most `max_bytes` are retained
+    fn evict_to(&mut self, max_bytes: usize) {
+        while self.total_bytes > max_bytes {
+            let oldest = self
+                .components
+                .values_mut()
+                .filter_map(|buffer| Some((buffer.front()?.0, buffer)))
+                .min_by_key(|(seq, _)| *seq);
+            let Some((_, buffer)) = oldest else {
+                break;
+            };
+            if let Some((_, line)) = buffer.pop_front() {
+                self.total_bytes -= line.line.len();
+            }
+        }
+    }
+
+    fn remove(&mut self, component_id: &str) {
+        if let Some(buffer) = self.components.remove(component_id) {
+            self.total_bytes -= buffer.iter().map(|(_, l)| l.line.len()).sum::<usize>();
+        }
+    }
+}
+
+impl ComponentLogs {
+    pub(crate) fn new(log_dir: PathBuf, capacity: usize) -> Self {
+        Self {
+            log_dir,
+            capacity: capacity.max(1),
+            max_total_bytes: None,
+            buffers: Arc::default(),
+        }
+    }
+
+    /// Bound the bytes of output retained in memory across all components
+    pub(crate) fn with_max_total_bytes(mut self, max_total_bytes: Option<usize>) -> Self {
+        self.max_total_bytes = max_total_bytes;
+        self
+    }
+
+    /// Bytes of output currently retained in memory across all components
+    pub(crate) fn total_bytes(&self) -> usize {
+        self.buffers
+            .lock()
+            .expect("log buffer lock poisoned")
+            .total_bytes
+    }
+
+    fn enforce_limits(&self, buffers: &mut LogBuffers, component_id: &str) {
+        buffers.truncate(component_id, self.capacity);
+        if let Some(max) = self.max_total_bytes {
+            buffers.evict_to(max);
+        }
+    }
+
+    pub(crate) fn log_path(&self, component_id: &str) -> PathBuf {
+        self.log_dir.join(format!("{component_id}.log"))
+    }
+
+    /// Split captured output into lines and record them for the component
+    pub(crate) async fn record(
+        &self,
+        component_id: &str,
+        stream: LogStream,
+        output: &[u8],
+    ) -> Result<()> {
+        let lines: Vec<LogLine> = String::from_utf8_lossy(output)
+            .lines()
+            .map(|line| LogLine {
+                stream,
+                line: line.to_string(),
+            })
+            .collect();
+        if lines.is_empty() {
+            return Ok(());
+        }
+
+        let path = self.log_path(component_id);
+        self.ensure_buffer(component_id, &path).await;
+        let retained = {
+            let mut buffers = self.buffers.lock().expect("log buffer lock poisoned");
+            buffers.push(component_id, lines.iter().cloned());
+            self.enforce_limits(&mut buffers, component_id);
+            buffers.components[component_id]
+                .iter()
+                .map(|(_, line)| line.clone())
+                .collect::<Vec<_>>()
+        };
+
+        tokio::fs::create_dir_all(&self.log_dir)
+            .await
+            .with_context(|| {
+                format!("Failed to create log directory {}", self.log_dir.display())
+            })?;
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await
+            .with_context(|| format!("Failed to open component log {}", path.display()))?;
+        file.write_all(serialize_lines(&lines)?.as_bytes()).await?;
+        file.flush().await?;
+
+        if file.metadata().await?.len() > MAX_LOG_FILE_BYTES {
+            tokio::fs::write(&path, serialize_lines(&retained)?).await?;
+        }
+        Ok(())
+    }
+
+    /// The last `tail` lines of the component, or every retained line when `tail` is `None`
+    pub(crate) async fn tail(&self, component_id: &str, tail: Option<usize>) -> Vec<LogLine> {
+        self.ensure_buffer(component_id, &self.log_path(component_id))
+            .await;
+        let buffers = self.buffers.lock().expect("log buffer lock poisoned");
+        let Some(buffer) = buffers.components.get(component_id) else {
+            return Vec::new();
+        };
+        let skip = tail.map_or(0, |n| buffer.len().saturating_sub(n));
+        buffer
+            .iter()
+            .skip(skip)
+            .map(|(_, line)| line.clone())
+            .collect()
+    }
+
+    /// Forget the component's lines and delete its log file
+    pub(crate) async fn clear(&self, component_id: &str) -> Result<()> {
+        self.buffers
+            .lock()
+            .expect("log buffer lock poisoned")
+            .remove(component_id);
+        match tokio::fs::remove_file(self.log_path(component_id)).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Keep the component's log file under its new id. The lines retained in memory are
+    /// dropped and read back from the file on the next access.
+    pub(crate) async fn rename(&self, from: &str, to: &str) -> Result<()> {
+        {
+            let mut buffers = self.buffers.lock().expect("log buffer lock poisoned");
+            buffers.remove(from);
+            buffers.remove(to);
+        }
+        match tokio::fs::rename(self.log_path(from), self.log_path(to)).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Seed the in-memory buffer from the log file written by an earlier process
+    async fn ensure_buffer(&self, component_id: &str, path: &Path) {
+        if self
+            .buffers
+            .lock()
+            .expect("log buffer lock poisoned")
+            .components
+            .contains_key(component_id)
+        {
+            return;
+        }
+
+        let persisted = tokio::fs::read_to_string(path).await.unwrap_or_default();
+        let lines: Vec<LogLine> = persisted.lines().filter_map(LogLine::parse).collect();
+
+        let mut buffers = self.buffers.lock().expect("log buffer lock poisoned");
+        if buffers.components.contains_key(component_id) {
+            return;
+        }
+        buffers.push(component_id, lines);
+        self.enforce_limits(&mut buffers, component_id);
+    }
+}
+
+fn serialize_lines(lines: &[LogLine]) -> Result<String> {
+    let mut out = String::new();
+    for line in lines {
+        out.push_str(&serde_json::to_string(line)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(logs: &[LogLine]) -> Vec<&str> {
+        logs.iter().map(|l| l.line.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_record_keeps_most_recent_lines() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let logs = ComponentLogs::new(dir.path().to_path_buf(), 3);
+
+        logs.record("comp", LogStream::Stdout, b"one\ntwo\n")
+            .await?;
+        logs.record("comp", LogStream::Stderr, b"three\nfour\nfive")
+            .await?;
+
+        assert_eq!(
+            lines(&logs.tail("comp", None).await),
+            ["three", "four", "five"]
+        );
+        let last = logs.tail("comp", Some(2)).await;
+        assert_eq!(lines(&last), ["four", "five"]);
+        assert_eq!(last[0].stream, LogStream::Stderr);
+        assert!(logs.tail("other", None).await.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_total_budget_evicts_oldest_lines_across_components() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let logs = ComponentLogs::new(dir.path().to_path_buf(), 100).with_max_total_bytes(Some(12));
+
+        logs.record("a", LogStream::Stdout, b"aaaa\n").await?;
+        logs.record("b", LogStream::Stdout, b"bbbb\n").await?;
+        logs.record("a", LogStream::Stdout, b"cccc\n").await?;
+        assert_eq!(logs.total_bytes(), 12);
+
+        // The oldest line overall goes first, even though another component wrote it
+        logs.record("b", LogStream::Stdout, b"dddd\n").await?;
+        assert_eq!(logs.total_bytes(), 12);
+        assert_eq!(lines(&logs.tail("a", None).await), ["cccc"]);
+        assert_eq!(lines(&logs.tail("b", None).await), ["bbbb", "dddd"]);
+
+        logs.clear("b").await?;
+        assert_eq!(logs.total_bytes(), 4);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_logs_are_read_back_from_disk() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        ComponentLogs::new(dir.path().to_path_buf(), 10)
+            .record("comp", LogStream::Stdout, b"hello\nworld\n")
+            .await?;
+
+        let reopened = ComponentLogs::new(dir.path().to_path_buf(), 10);
+        assert_eq!(lines(&reopened.tail("comp", Some(1)).await), ["world"]);
+
+        reopened.clear("comp").await?;
+        assert!(reopened.tail("comp", None).await.is_empty());
+        assert!(!reopened.log_path("comp").exists());
+
+        Ok(())
+    }
+}
@@ -4,18 +4,19 @@
 //! Filesystem helpers that manage component artifacts, metadata, and cache
 //! layout for the lifecycle manager.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::io::BufReader;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use tokio::sync::{Mutex as AsyncMutex, OwnedSemaphorePermit, Semaphore};
 use tokio::task::spawn_blocking;
 
 use crate::loader::DownloadedResource;
-use crate::{ComponentMetadata, ValidationStamp};
+use crate::{CompilationStrategy, ComponentMetadata, DeclaredPermission, ValidationStamp};
 
 /// Handles filesystem layout and metadata persistence for components.
 #[derive(Clone)]
@@ -26,6 +27,12 @@ pub struct ComponentStorage {
     component_locks: Arc<std::sync::Mutex<HashMap<String, Arc<AsyncMutex<()>>>>>,
 }
 
+/// On-disk record of where a component was loaded from.
+#[derive(Serialize, Deserialize)]
+struct ComponentSource {
+    uri: String,
+}
+
 impl ComponentStorage {
     /// Create a new storage manager rooted at the component directory.
     pub async fn new(root: impl Into<PathBuf>, max_concurrent_downloads: usize) -> Result<Self> {
@@ -53,6 +60,56 @@ impl ComponentStorage {
         })
     }
 
+    /// Warn when users other than the owner can write to the component directory, since any
+    /// component they drop into it is loaded on the next start. With `strict` the directory
+    /// is refused instead.
+    #[cfg(unix)]
+    pub async fn check_permissions(&self, strict: bool) -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        use anyhow::bail;
+        use tracing::warn;
+
+        let mode = tokio::fs::metadata(&self.root)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to read permissions of component directory {}",
+                    self.root.display()
+                )
+            })?
+            .permissions()
+            .mode();
+        if mode & 0o022 == 0 {
+            return Ok(());
+        }
+
+        let writable_by = if mode & 0o002 != 0 {
+            "all users"
+        } else {
+            "its group"
+        };
+        if strict {
+            bail!(
+                "Component directory {} is writable by {writable_by} (mode {:o}). Restrict it with `chmod go-w {}` or run without --strict-security",
+                self.root.display(),
+                mode & 0o777,
+                self.root.display()
+            );
+        }
+        warn!(
+            component_dir = %self.root.display(),
+            mode = format_args!("{:o}", mode & 0o777),
+            "SECURITY WARNING: the component directory is writable by {writable_by}, who can plant components that wassette loads on the next start. Restrict it with `chmod go-w`"
+        );
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    pub async fn check_permissions(&self, _strict: bool) -> Result<()> {
+        Ok(())
+    }
+
     fn lock_for(&self, component_id: &str) -> Arc<AsyncMutex<()>> {
         let mut locks = self.component_locks.lock().expect("component lock poisoned");
         locks
@@ -66,8 +123,26 @@ impl ComponentStorage {
         &self.root
     }
 
+    /// Create an empty scratch directory for a single call of `component_id`. The
+    /// directory and its contents are deleted when the returned handle is dropped.
+    pub async fn create_scratch_dir(&self, component_id: &str) -> Result<tempfile::TempDir> {
+        let scratch_root = self.root.join(crate::SCRATCH_DIR);
+        tokio::fs::create_dir_all(&scratch_root)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to create scratch directory at {}",
+                    scratch_root.display()
+                )
+            })?;
+
+        tempfile::Builder::new()
+            .prefix(&format!("{component_id}-"))
+            .tempdir_in(&scratch_root)
+            .with_context(|| format!("Failed to create scratch directory for {component_id}"))
+    }
+
     /// Directory used for staging downloaded artifacts.
-    #[allow(dead_code)]
     pub fn downloads_dir(&self) -> &Path {
         &self.downloads_dir
     }
@@ -102,11 +177,146 @@ impl ComponentStorage {
             .join(format!("{component_id}.{}", crate::PRECOMPILED_EXT))
     }
 
+    /// Absolute path to the file recording the compilation strategy of the precompiled cache.
+    pub fn precompiled_strategy_path(&self, component_id: &str) -> PathBuf {
+        self.root.join(format!(
+            "{component_id}.{}.strategy",
+            crate::PRECOMPILED_EXT
+        ))
+    }
+
     /// Absolute path to the policy metadata JSON for a component.
     pub fn policy_metadata_path(&self, component_id: &str) -> PathBuf {
         self.root.join(format!("{component_id}.policy.meta.json"))
     }
 
+    /// Absolute path to the component-scoped environment variables JSON.
+    pub fn component_env_path(&self, component_id: &str) -> PathBuf {
+        self.root.join(format!("{component_id}.env.json"))
+    }
+
+    /// Absolute path to the record of declared permissions already granted automatically.
+    pub fn auto_granted_path(&self, component_id: &str) -> PathBuf {
+        self.root.join(format!("{component_id}.auto-granted.json"))
+    }
+
+    /// Absolute path to the file recording the URI a component was loaded from.
+    pub fn component_source_path(&self, component_id: &str) -> PathBuf {
+        self.root.join(format!("{component_id}.source.json"))
+    }
+
+    /// The URI the component was last loaded from, if it was recorded.
+    pub async fn read_component_source(&self, component_id: &str) -> Result<Option<String>> {
+        let path = self.component_source_path(component_id);
+        match tokio::fs::read_to_string(&path).await {
+            Ok(content) => {
+                let record: ComponentSource =
+                    serde_json::from_str(&content).with_context(|| {
+                        format!("Failed to parse component source at {}", path.display())
+                    })?;
+                Ok(Some(record.uri))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(anyhow!(
+                "Failed to read component source at {}: {}",
+                path.display(),
+                e
+            )),
+        }
+    }
+
+    /// Record the URI a component was loaded from.
+    pub async fn write_component_source(&self, component_id: &str, uri: &str) -> Result<()> {
+        let path = self.component_source_path(component_id);
+        let json = serde_json::to_string_pretty(&ComponentSource {
+            uri: uri.to_string(),
+        })
+        .context("Failed to serialize component source")?;
+        tokio::fs::write(&path, json)
+            .await
+            .with_context(|| format!("Failed to write component source to {}", path.display()))
+    }
+
+    /// Load the environment variables configured for a component, if any.
+    pub async fn read_component_env(&self, component_id: &str) -> Result<BTreeMap<String, String>> {
+        let path = self.component_env_path(component_id);
+        match tokio::fs::read_to_string(&path).await {
+            Ok(content) => serde_json::from_str(&content).with_context(|| {
+                format!(
+                    "Failed to parse component environment at {}",
+                    path.display()
+                )
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+            Err(e) => Err(anyhow!(
+                "Failed to read component environment at {}: {}",
+                path.display(),
+                e
+            )),
+        }
+    }
+
+    /// Persist the environment variables configured for a component, removing the
+    /// file when no variables remain.
+    pub async fn write_component_env(
+        &self,
+        component_id: &str,
+        env: &BTreeMap<String, String>,
+    ) -> Result<()> {
+        let path = self.component_env_path(component_id);
+        if env.is_empty() {
+            return self
+                .remove_if_exists(&path, "component environment file", component_id)
+                .await;
+        }
+
+        let json = serde_json::to_string_pretty(env)
+            .context("Failed to serialize component environment")?;
+        tokio::fs::write(&path, json).await.with_context(|| {
+            format!(
+                "Failed to write component environment to {}",
+                path.display()
+            )
+        })
+    }
+
+    /// Declared permissions granted automatically at some earlier load of the component.
+    pub async fn read_auto_granted(&self, component_id: &str) -> Result<Vec<DeclaredPermission>> {
+        let path = self.auto_granted_path(component_id);
+        match tokio::fs::read_to_string(&path).await {
+            Ok(content) => serde_json::from_str(&content).with_context(|| {
+                format!(
+                    "Failed to parse auto-granted permissions at {}",
+                    path.display()
+                )
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(anyhow!(
+                "Failed to read auto-granted permissions at {}: {}",
+                path.display(),
+                e
+            )),
+        }
+    }
+
+    /// Record the declared permissions granted automatically, so that none of them is granted
+    /// again after the operator revokes it.
+    pub async fn write_auto_granted(
+        &self,
+        component_id: &str,
+        granted: &[DeclaredPermission],
+    ) -> Result<()> {
+        let path = self.auto_granted_path(component_id);
+        let json = serde_json::to_string_pretty(granted)
+            .context("Failed to serialize auto-granted permissions")?;
+        tokio::fs::write(&path, json).await.with_context(|| {
+            format!(
+                "Failed to write auto-granted permissions to {}",
+                path.display()
+            )
+        })
+    }
+
     /// Stage a downloaded component artifact into storage, replacing any existing files.
     pub async fn install_component_artifact(
         &self,
@@ -119,12 +329,15 @@ impl ComponentStorage {
 
         self.remove_component_artifacts_inner(component_id).await?;
 
-        resource.copy_to(self.root()).await.with_context(|| {
-            format!(
-                "Failed to copy component to destination: {}",
-                self.root.display()
-            )
-        })?;
+        resource
+            .copy_to(self.root(), component_id)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to copy component to destination: {}",
+                    self.root.display()
+                )
+            })?;
 
         Ok(self.component_path(component_id))
     }
@@ -155,9 +368,92 @@ impl ComponentStorage {
             component_id,
         )
         .await?;
+        self.remove_if_exists(
+            &self.precompiled_strategy_path(component_id),
+            "precompiled component strategy file",
+            component_id,
+        )
+        .await?;
+        self.remove_if_exists(
+            &self.component_source_path(component_id),
+            "component source file",
+            component_id,
+        )
+        .await?;
         Ok(())
     }
 
+    /// Move every persisted file of `from` (wasm, policy, metadata, precompiled cache,
+    /// environment, source, auto-granted permissions) to the names used for `to`, rewriting the id recorded in the
+    /// metadata. Fails without touching anything if a component named `to` is installed,
+    /// and moves the files back if any of them cannot be moved.
+    pub async fn rename_component_files(&self, from: &str, to: &str) -> Result<()> {
+        // Lock in a fixed order so concurrent renames in opposite directions cannot deadlock
+        let (first, second) = if from < to { (from, to) } else { (to, from) };
+        let first_lock = self.lock_for(first);
+        let _first_guard = first_lock.lock().await;
+        let second_lock = self.lock_for(second);
+        let _second_guard = second_lock.lock().await;
+
+        if self.component_path(to).exists() {
+            return Err(anyhow!("Component '{to}' already exists"));
+        }
+
+        let paths: [fn(&Self, &str) -> PathBuf; 9] = [
+            Self::component_path,
+            Self::policy_path,
+            Self::policy_metadata_path,
+            Self::metadata_path,
+            Self::precompiled_path,
+            Self::precompiled_strategy_path,
+            Self::component_env_path,
+            Self::component_source_path,
+            Self::auto_granted_path,
+        ];
+        let mut moved = Vec::new();
+        let mut outcome = Ok(());
+        for path in paths {
+            let (source, target) = (path(self, from), path(self, to));
+            match tokio::fs::rename(&source, &target).await {
+                Ok(()) => moved.push((source, target)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    outcome = Err(anyhow!(
+                        "Failed to move {} to {}: {}",
+                        source.display(),
+                        target.display(),
+                        e
+                    ));
+                    break;
+                }
+            }
+        }
+
+        if outcome.is_ok() {
+            outcome = match self.read_metadata(to).await {
+                Ok(Some(mut metadata)) => {
+                    metadata.component_id = to.to_string();
+                    self.write_metadata(&metadata).await
+                }
+                Ok(None) => Ok(()),
+                Err(e) => Err(e),
+            };
+        }
+        if outcome.is_err() {
+            for (source, target) in moved.iter().rev() {
+                if let Err(e) = tokio::fs::rename(target, source).await {
+                    warn!(
+                        component_id = from,
+                        path = %source.display(),
+                        error = %e,
+                        "Failed to move component file back after a failed rename"
+                    );
+                }
+            }
+        }
+        outcome
+    }
+
     /// Persist component metadata to disk.
     pub async fn write_metadata(&self, metadata: &ComponentMetadata) -> Result<()> {
         let path = self.metadata_path(&metadata.component_id);
@@ -189,15 +485,40 @@ impl ComponentStorage {
         Ok(Some(metadata))
     }
 
-    /// Write precompiled component bytes to disk.
-    pub async fn write_precompiled(&self, component_id: &str, bytes: &[u8]) -> Result<()> {
+    /// Write precompiled component bytes to disk along with the strategy they were built with.
+    pub async fn write_precompiled(
+        &self,
+        component_id: &str,
+        bytes: &[u8],
+        strategy: CompilationStrategy,
+    ) -> Result<()> {
         let path = self.precompiled_path(component_id);
         tokio::fs::write(&path, bytes).await.with_context(|| {
             format!(
                 "Failed to write precompiled component to {}",
                 path.display()
             )
-        })
+        })?;
+
+        let strategy_path = self.precompiled_strategy_path(component_id);
+        tokio::fs::write(&strategy_path, strategy.as_str())
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to write precompiled component strategy to {}",
+                    strategy_path.display()
+                )
+            })
+    }
+
+    /// Compilation strategy of the precompiled cache. Caches written before strategies were
+    /// recorded were always optimized.
+    pub async fn read_precompiled_strategy(&self, component_id: &str) -> CompilationStrategy {
+        tokio::fs::read_to_string(self.precompiled_strategy_path(component_id))
+            .await
+            .ok()
+            .and_then(|s| s.trim().parse().ok())
+            .unwrap_or_default()
     }
 
     /// Remove a file if it exists, translating IO errors into `anyhow`.
@@ -263,25 +584,32 @@ impl ComponentStorage {
         })
     }
 
-    /// Check if the validation stamp matches the current file on disk.
-    pub async fn validate_stamp(path: &Path, stamp: &ValidationStamp) -> bool {
-        let metadata = match tokio::fs::metadata(path).await {
-            Ok(metadata) => metadata,
-            Err(_) => return false,
+    /// Why the component file at `path` no longer matches `stamp`, or `None` if it still does.
+    ///
+    /// The size is compared first. Stamps that record a content hash are then checked by
+    /// hash alone, since copies and checkouts change modification times of unchanged files;
+    /// other stamps fall back to the modification time.
+    pub async fn stale_reason(path: &Path, stamp: &ValidationStamp) -> Option<StaleReason> {
+        let Ok(metadata) = tokio::fs::metadata(path).await else {
+            return Some(StaleReason::Missing);
         };
 
         if metadata.len() != stamp.file_size {
-            return false;
+            return Some(StaleReason::SizeMismatch {
+                expected: stamp.file_size,
+                actual: metadata.len(),
+            });
         }
 
         if let Some(expected_hash) = &stamp.content_hash {
-            match compute_file_hash(path).await {
-                Ok(actual_hash) => return actual_hash == *expected_hash,
-                Err(_) => return false,
-            }
+            return match compute_file_hash(path).await {
+                Ok(actual_hash) if actual_hash == *expected_hash => None,
+                Ok(_) => Some(StaleReason::HashMismatch),
+                Err(_) => Some(StaleReason::Missing),
+            };
         }
 
-        let mtime = match metadata
+        let Ok(mtime) = metadata
             .modified()
             .map_err(|_| std::io::Error::from(std::io::ErrorKind::Other))
             .and_then(|t| {
@@ -289,20 +617,46 @@ impl ComponentStorage {
                     .map_err(|_| std::io::Error::from(std::io::ErrorKind::Other))
             })
             .map(|d| d.as_secs())
-        {
-            Ok(mtime) => mtime,
-            Err(_) => return false,
+        else {
+            return Some(StaleReason::Missing);
         };
 
-        if mtime != stamp.mtime {
-            return false;
-        }
+        (mtime != stamp.mtime).then_some(StaleReason::MtimeMismatch {
+            expected: stamp.mtime,
+            actual: mtime,
+        })
+    }
+}
 
-        true
+/// Why cached component metadata no longer describes the file on disk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum StaleReason {
+    /// The file is gone or could not be read
+    Missing,
+    /// The file size differs from the recorded one
+    SizeMismatch { expected: u64, actual: u64 },
+    /// The file has the recorded size but different contents
+    HashMismatch,
+    /// The file was modified after the metadata was recorded
+    MtimeMismatch { expected: u64, actual: u64 },
+}
+
+impl std::fmt::Display for StaleReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing => write!(f, "component file is missing or unreadable"),
+            Self::SizeMismatch { expected, actual } => {
+                write!(f, "size changed from {expected} to {actual} bytes")
+            }
+            Self::HashMismatch => write!(f, "content hash changed"),
+            Self::MtimeMismatch { expected, actual } => {
+                write!(f, "modification time changed from {expected} to {actual}")
+            }
+        }
     }
 }
 
-async fn compute_file_hash(path: &Path) -> Result<String> {
+pub(crate) async fn compute_file_hash(path: &Path) -> Result<String> {
     let file = tokio::fs::File::open(path)
         .await
         .with_context(|| format!("Failed to open {} for hashing", path.display()))?;
@@ -321,3 +675,96 @@ async fn compute_file_hash(path: &Path) -> Result<String> {
     .await?
     .with_context(|| format!("Failed to hash file {}", path.display()))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::*;
+
+    async fn stamped_file(
+        include_hash: bool,
+    ) -> Result<(tempfile::TempDir, PathBuf, ValidationStamp)> {
+        let dir = tempfile::tempdir()?;
+        let storage = ComponentStorage::new(dir.path(), 1).await?;
+        let path = dir.path().join("component.wasm");
+        std::fs::write(&path, b"original")?;
+        let stamp = storage.create_validation_stamp(&path, include_hash).await?;
+        Ok((dir, path, stamp))
+    }
+
+    fn set_mtime(path: &Path, secs: u64) -> Result<()> {
+        std::fs::File::options()
+            .write(true)
+            .open(path)?
+            .set_modified(UNIX_EPOCH + Duration::from_secs(secs))?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_failed_rename_moves_files_back() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let storage = ComponentStorage::new(dir.path(), 1).await?;
+        std::fs::write(storage.component_path("old"), b"wasm")?;
+        std::fs::write(storage.policy_path("old"), b"policy")?;
+        // A directory in the way of the policy file makes the second move fail
+        std::fs::create_dir_all(storage.policy_path("new").join("occupied"))?;
+
+        let err = storage
+            .rename_component_files("old", "new")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().starts_with("Failed to move"), "{err}");
+        assert_eq!(std::fs::read(storage.component_path("old"))?, b"wasm");
+        assert_eq!(std::fs::read(storage.policy_path("old"))?, b"policy");
+        assert!(!storage.component_path("new").exists());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stale_reason_reports_each_mutation() -> Result<()> {
+        let (_dir, path, stamp) = stamped_file(false).await?;
+        assert_eq!(ComponentStorage::stale_reason(&path, &stamp).await, None);
+
+        set_mtime(&path, stamp.mtime + 60)?;
+        assert_eq!(
+            ComponentStorage::stale_reason(&path, &stamp).await,
+            Some(StaleReason::MtimeMismatch {
+                expected: stamp.mtime,
+                actual: stamp.mtime + 60,
+            })
+        );
+
+        std::fs::write(&path, b"longer contents")?;
+        assert_eq!(
+            ComponentStorage::stale_reason(&path, &stamp).await,
+            Some(StaleReason::SizeMismatch {
+                expected: 8,
+                actual: 15,
+            })
+        );
+
+        std::fs::remove_file(&path)?;
+        assert_eq!(
+            ComponentStorage::stale_reason(&path, &stamp).await,
+            Some(StaleReason::Missing)
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stale_reason_uses_hash_over_mtime_when_recorded() -> Result<()> {
+        let (_dir, path, stamp) = stamped_file(true).await?;
+
+        // Same contents with a new timestamp, as after a copy, is still fresh
+        set_mtime(&path, stamp.mtime + 60)?;
+        assert_eq!(ComponentStorage::stale_reason(&path, &stamp).await, None);
+
+        std::fs::write(&path, b"modified")?;
+        assert_eq!(
+            ComponentStorage::stale_reason(&path, &stamp).await,
+            Some(StaleReason::HashMismatch)
+        );
+        Ok(())
+    }
+}
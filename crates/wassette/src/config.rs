@@ -9,20 +9,45 @@ use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use anyhow::{Context, Result};
+use policy::PolicyDocument;
 
+use crate::http::OutboundHttpConfig;
+use crate::proxy::ProxyConfig;
+use crate::runtime_context::{CompilationStrategy, PoolingAllocatorConfig};
 use crate::{
-    get_default_secrets_dir, LifecycleManager, DEFAULT_HTTP_TIMEOUT_SECS, DEFAULT_OCI_TIMEOUT_SECS,
+    get_default_secrets_dir, DuplicateToolPolicy, LifecycleManager, OciComponentIds,
+    DEFAULT_HTTP_TIMEOUT_SECS, DEFAULT_OCI_TIMEOUT_SECS,
 };
 
 /// Fully-specified configuration for constructing a [`LifecycleManager`].
 #[derive(Clone)]
 pub struct LifecycleConfig {
-    component_dir: PathBuf,
-    secrets_dir: PathBuf,
-    environment_vars: HashMap<String, String>,
-    http_client: reqwest::Client,
-    oci_client: oci_client::Client,
-    eager_load: bool,
+    pub(crate) component_dir: PathBuf,
+    pub(crate) secrets_dir: PathBuf,
+    pub(crate) environment_vars: HashMap<String, String>,
+    pub(crate) http_client: reqwest::Client,
+    pub(crate) oci_client: oci_client::Client,
+    pub(crate) eager_load: bool,
+    pub(crate) load_timeout: Option<Duration>,
+    pub(crate) strict_policy: bool,
+    pub(crate) per_call_scratch: bool,
+    pub(crate) max_components: Option<usize>,
+    pub(crate) max_loaded_memory: Option<u64>,
+    pub(crate) outbound_http: OutboundHttpConfig,
+    pub(crate) compilation_strategy: CompilationStrategy,
+    pub(crate) auto_grant_declared: bool,
+    pub(crate) idle_unload_ttl: Option<Duration>,
+    pub(crate) default_policy: Option<PolicyDocument>,
+    pub(crate) pooling_allocator: Option<PoolingAllocatorConfig>,
+    pub(crate) safe_mode: bool,
+    pub(crate) create_component_dir: bool,
+    pub(crate) max_total_log_bytes: Option<usize>,
+    pub(crate) egress_byte_limit: Option<u64>,
+    pub(crate) duplicate_tool_policy: DuplicateToolPolicy,
+    pub(crate) stderr_tracing: bool,
+    pub(crate) redact_secrets: bool,
+    pub(crate) strict_security: bool,
+    pub(crate) oci_component_ids: OciComponentIds,
 }
 
 impl LifecycleConfig {
@@ -56,24 +81,104 @@ impl LifecycleConfig {
         self.eager_load
     }
 
-    pub(crate) fn into_parts(
-        self,
-    ) -> (
-        PathBuf,
-        PathBuf,
-        HashMap<String, String>,
-        reqwest::Client,
-        oci_client::Client,
-        bool,
-    ) {
-        (
-            self.component_dir,
-            self.secrets_dir,
-            self.environment_vars,
-            self.http_client,
-            self.oci_client,
-            self.eager_load,
-        )
+    /// Maximum time allowed for loading a single component, if any.
+    pub fn load_timeout(&self) -> Option<Duration> {
+        self.load_timeout
+    }
+
+    /// Whether policies with unknown permission types are rejected.
+    pub fn strict_policy(&self) -> bool {
+        self.strict_policy
+    }
+
+    /// Whether each tool call gets its own ephemeral scratch directory.
+    pub fn per_call_scratch(&self) -> bool {
+        self.per_call_scratch
+    }
+
+    /// Maximum number of components that may be loaded at once, if any.
+    pub fn max_components(&self) -> Option<usize> {
+        self.max_components
+    }
+
+    /// Bytes of memory that the declared limits of all loaded components may add up to, if any.
+    pub fn max_loaded_memory(&self) -> Option<u64> {
+        self.max_loaded_memory
+    }
+
+    /// User-Agent and timeout applied to outbound HTTP requests made by components.
+    pub fn outbound_http(&self) -> &OutboundHttpConfig {
+        &self.outbound_http
+    }
+
+    /// How components are compiled.
+    pub fn compilation_strategy(&self) -> CompilationStrategy {
+        self.compilation_strategy
+    }
+
+    /// Whether permissions declared by a component are granted when it loads.
+    pub fn auto_grant_declared(&self) -> bool {
+        self.auto_grant_declared
+    }
+
+    /// How long a component may go without calls before it is unloaded from the engine.
+    pub fn idle_unload_ttl(&self) -> Option<Duration> {
+        self.idle_unload_ttl
+    }
+
+    /// Policy whose permissions every component gets in addition to its own.
+    pub fn default_policy(&self) -> Option<&PolicyDocument> {
+        self.default_policy.as_ref()
+    }
+
+    /// Limits of the pooling instance allocator, if components are instantiated from a pool.
+    pub fn pooling_allocator(&self) -> Option<&PoolingAllocatorConfig> {
+        self.pooling_allocator.as_ref()
+    }
+
+    /// Whether every component runs without the capabilities its policy grants.
+    pub fn safe_mode(&self) -> bool {
+        self.safe_mode
+    }
+
+    /// Whether a missing component directory is created rather than reported as an error.
+    pub fn create_component_dir(&self) -> bool {
+        self.create_component_dir
+    }
+
+    /// Bytes of captured component output kept in memory across all components, if bounded.
+    pub fn max_total_log_bytes(&self) -> Option<usize> {
+        self.max_total_log_bytes
+    }
+
+    /// Bytes of HTTP responses a single tool call may receive, if limited.
+    pub fn egress_byte_limit(&self) -> Option<u64> {
+        self.egress_byte_limit
+    }
+
+    /// How a tool name exported by several components is resolved.
+    pub fn duplicate_tool_policy(&self) -> DuplicateToolPolicy {
+        self.duplicate_tool_policy
+    }
+
+    /// Whether lines components write to stderr are also emitted as `tracing` events.
+    pub fn stderr_tracing(&self) -> bool {
+        self.stderr_tracing
+    }
+
+    /// Whether a component's secret values are scrubbed from the errors and output of its calls.
+    pub fn redact_secrets(&self) -> bool {
+        self.redact_secrets
+    }
+
+    /// Whether an insecure component directory is refused instead of only warned about.
+    pub fn strict_security(&self) -> bool {
+        self.strict_security
+    }
+
+    /// How ids are derived for components loaded from OCI references.
+    pub fn oci_component_ids(&self) -> OciComponentIds {
+        self.oci_component_ids
     }
 }
 
@@ -86,6 +191,27 @@ pub struct LifecycleBuilder {
     http_client: Option<reqwest::Client>,
     oci_client: Option<oci_client::Client>,
     eager_load: bool,
+    load_timeout: Option<Duration>,
+    strict_policy: bool,
+    per_call_scratch: bool,
+    max_components: Option<usize>,
+    max_loaded_memory: Option<u64>,
+    proxy: Option<ProxyConfig>,
+    outbound_http: OutboundHttpConfig,
+    compilation_strategy: CompilationStrategy,
+    auto_grant_declared: bool,
+    idle_unload_ttl: Option<Duration>,
+    default_policy: Option<PolicyDocument>,
+    pooling_allocator: Option<PoolingAllocatorConfig>,
+    safe_mode: bool,
+    create_component_dir: bool,
+    max_total_log_bytes: Option<usize>,
+    egress_byte_limit: Option<u64>,
+    duplicate_tool_policy: DuplicateToolPolicy,
+    stderr_tracing: bool,
+    redact_secrets: bool,
+    strict_security: bool,
+    oci_component_ids: OciComponentIds,
 }
 
 impl LifecycleBuilder {
@@ -99,6 +225,27 @@ impl LifecycleBuilder {
             http_client: None,
             oci_client: None,
             eager_load: true,
+            load_timeout: None,
+            strict_policy: false,
+            per_call_scratch: false,
+            max_components: None,
+            max_loaded_memory: None,
+            proxy: ProxyConfig::from_env(),
+            outbound_http: OutboundHttpConfig::default(),
+            compilation_strategy: CompilationStrategy::default(),
+            auto_grant_declared: false,
+            idle_unload_ttl: None,
+            default_policy: None,
+            pooling_allocator: None,
+            safe_mode: false,
+            create_component_dir: true,
+            max_total_log_bytes: None,
+            egress_byte_limit: None,
+            duplicate_tool_policy: DuplicateToolPolicy::default(),
+            stderr_tracing: false,
+            redact_secrets: true,
+            strict_security: false,
+            oci_component_ids: OciComponentIds::default(),
         }
     }
 
@@ -125,6 +272,8 @@ impl LifecycleBuilder {
     }
 
     /// Override the HTTP client.
+    ///
+    /// A custom client is used as-is; configure any proxy on it before passing it in.
     pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
         self.http_client = Some(client);
         self
@@ -136,12 +285,215 @@ impl LifecycleBuilder {
         self
     }
 
+    /// Route downloads made by the default HTTP and OCI clients through `proxy`.
+    ///
+    /// Defaults to [`ProxyConfig::from_env`]. Pass `None` to connect directly.
+    pub fn with_proxy(mut self, proxy: Option<ProxyConfig>) -> Self {
+        self.proxy = proxy;
+        self
+    }
+
     /// Control whether the manager eagerly loads components during build.
     pub fn with_eager_loading(mut self, eager: bool) -> Self {
         self.eager_load = eager;
         self
     }
 
+    /// Interrupt the component code a single load runs, such as an `init` hook, once the
+    /// load has taken `timeout`.
+    ///
+    /// A component that exceeds the limit is reported as a load error and the
+    /// loader moves on to the remaining components. No limit is applied by default.
+    pub fn with_load_timeout(mut self, timeout: Duration) -> Self {
+        self.load_timeout = Some(timeout);
+        self
+    }
+
+    /// Reject policies that declare permission types this version does not recognize.
+    ///
+    /// By default such permissions are logged and ignored while the recognized ones
+    /// still apply.
+    pub fn with_strict_policy(mut self, strict: bool) -> Self {
+        self.strict_policy = strict;
+        self
+    }
+
+    /// Give every tool call a fresh, writable directory preopened at
+    /// [`SCRATCH_GUEST_PATH`](crate::SCRATCH_GUEST_PATH).
+    ///
+    /// The directory is created just before the call and deleted once it returns, so
+    /// nothing written there is visible to later calls. Use granted storage for data
+    /// that has to persist. Disabled by default.
+    pub fn with_per_call_scratch(mut self, enabled: bool) -> Self {
+        self.per_call_scratch = enabled;
+        self
+    }
+
+    /// Refuse to load a new component once `max` components are loaded.
+    ///
+    /// Reloading a component that is already loaded is always allowed. Passing `None`
+    /// removes the limit, which is the default.
+    pub fn with_max_components(mut self, max: Option<usize>) -> Self {
+        self.max_components = max;
+        self
+    }
+
+    /// Refuse to load a component whose declared memory limit would take the sum of the
+    /// limits of all loaded components past `bytes`.
+    ///
+    /// A component's declared limit is the `resources.limits.memory` of its policy, or of the
+    /// default policy when it has none. Components without a declared limit are refused while
+    /// a budget is set, and granting a loaded component a larger limit is checked as well.
+    /// Passing `None` removes the budget, which is the default.
+    pub fn with_max_loaded_memory(mut self, bytes: Option<u64>) -> Self {
+        self.max_loaded_memory = bytes;
+        self
+    }
+
+    /// Set the User-Agent and timeout applied to outbound HTTP requests made by components.
+    ///
+    /// By default requests keep the component's own User-Agent and Wasmtime's timeouts.
+    pub fn with_outbound_http(mut self, outbound: OutboundHttpConfig) -> Self {
+        self.outbound_http = outbound;
+        self
+    }
+
+    /// Choose between quick compiles and optimized code for loaded components.
+    ///
+    /// [`CompilationStrategy::Fast`] cuts cold-load time for large components, which suits
+    /// development; the default [`CompilationStrategy::Optimized`] runs tools faster.
+    /// Precompiled artifacts built with a different strategy are recompiled on load.
+    pub fn with_compilation_strategy(mut self, strategy: CompilationStrategy) -> Self {
+        self.compilation_strategy = strategy;
+        self
+    }
+
+    /// Grant the permissions a component declares in its
+    /// [`PERMISSIONS_SECTION`](crate::PERMISSIONS_SECTION) custom section when it loads.
+    ///
+    /// Declared permissions are always reported in the load outcome; this only decides
+    /// whether they are also added to the component's policy. Disabled by default.
+    pub fn with_auto_grant_declared(mut self, enabled: bool) -> Self {
+        self.auto_grant_declared = enabled;
+        self
+    }
+
+    /// Unload components from the engine once they have not been called for `ttl`.
+    ///
+    /// Their files and registered tools are kept, so an idle component still shows up in
+    /// tool listings and is compiled again the next time one of its tools is called. Unlike
+    /// [`with_max_components`](Self::with_max_components), which bounds how many components
+    /// are loaded, this frees the memory of components nobody is using. Components exporting
+    /// `wassette:lifecycle/hooks` have their shutdown hook run when they are unloaded.
+    /// Disabled by default.
+    pub fn with_idle_unload_ttl(mut self, ttl: Duration) -> Self {
+        self.idle_unload_ttl = Some(ttl);
+        self
+    }
+
+    /// Apply the permissions of `policy` to every component on top of its own policy.
+    ///
+    /// Rules are merged as described in [`policy::merge`]: the allow and deny lists of both
+    /// policies are combined, and the component's runtime and resource settings win over
+    /// the default's. Components without a policy file run with `policy` alone. Passing
+    /// `None`, the default, leaves each component with only its own policy.
+    pub fn with_default_policy(mut self, policy: Option<PolicyDocument>) -> Self {
+        self.default_policy = policy;
+        self
+    }
+
+    /// Instantiate components from Wasmtime's pooling allocator with the given limits.
+    ///
+    /// Suits servers handling many short tool calls, where instantiation dominates the
+    /// cost of a call. Calls made while every pooled instance is in use fail with an error
+    /// rather than waiting. The on-demand allocator is used by default.
+    pub fn with_pooling_allocator(mut self, config: PoolingAllocatorConfig) -> Self {
+        self.pooling_allocator = Some(config);
+        self
+    }
+
+    /// Run every component fully sandboxed, whatever its policy grants.
+    ///
+    /// Network, storage, environment and IPC permissions from component policies and the
+    /// default policy are ignored, as are secrets and configured environment variables, and
+    /// granting permissions fails. Resource limits still apply.
+    pub fn with_safe_mode(mut self, safe_mode: bool) -> Self {
+        self.safe_mode = safe_mode;
+        self
+    }
+
+    /// Create the component directory if it does not exist, the default.
+    ///
+    /// Pass `false` when the directory is expected to be provisioned ahead of time, for
+    /// example mounted into a container, so that a missing or mistyped path fails the
+    /// build instead of silently starting with an empty directory.
+    pub fn with_create_component_dir(mut self, create: bool) -> Self {
+        self.create_component_dir = create;
+        self
+    }
+
+    /// Keep at most `max` bytes of captured stdout and stderr in memory across all components.
+    ///
+    /// Each component already retains at most [`DEFAULT_LOG_CAPACITY`](crate::DEFAULT_LOG_CAPACITY)
+    /// lines; this budget also bounds the total when many components are loaded. Once it is
+    /// exceeded the oldest lines are dropped first, whichever component wrote them. Log files
+    /// on disk are not affected. Unbounded when `None`, the default.
+    pub fn with_max_total_log_bytes(mut self, max: Option<usize>) -> Self {
+        self.max_total_log_bytes = max;
+        self
+    }
+
+    /// Abort a tool call once the HTTP response bodies it has received exceed `limit` bytes.
+    ///
+    /// The response being read fails with `HTTP-response-body-size` and the call returns an
+    /// error, whether or not the component handles the failed read. Bytes received are
+    /// reported by [`LifecycleManager::get_component_stats`] either way. Unlimited when
+    /// `None`, the default.
+    pub fn with_egress_byte_limit(mut self, limit: Option<u64>) -> Self {
+        self.egress_byte_limit = limit;
+        self
+    }
+
+    /// Choose how a call naming a tool that several components export is resolved.
+    /// Defaults to [`DuplicateToolPolicy::Error`].
+    pub fn with_duplicate_tool_policy(mut self, policy: DuplicateToolPolicy) -> Self {
+        self.duplicate_tool_policy = policy;
+        self
+    }
+
+    /// Also emit every line a component writes to stderr as a `tracing` event carrying the
+    /// component id and the function called, so component diagnostics reach the host's log
+    /// pipeline. The lines are captured for [`LifecycleManager::get_component_logs`]
+    /// either way. Off by default.
+    pub fn with_stderr_tracing(mut self, enabled: bool) -> Self {
+        self.stderr_tracing = enabled;
+        self
+    }
+
+    /// Replace a component's secret values with `***` in the errors its calls return, both
+    /// failed calls and error values returned by the component, and in its captured output,
+    /// so that a component echoing a credential does not leak it to clients or logs. On by
+    /// default.
+    pub fn with_secret_redaction(mut self, enabled: bool) -> Self {
+        self.redact_secrets = enabled;
+        self
+    }
+
+    /// Refuse to start when the component directory is writable by other users, who could
+    /// otherwise plant components that are loaded on the next start. Without it the
+    /// directory's permissions are only warned about. Has no effect on Windows.
+    pub fn with_strict_security(mut self, strict: bool) -> Self {
+        self.strict_security = strict;
+        self
+    }
+
+    /// Choose how the id of a component loaded from an OCI reference without an explicit id
+    /// is derived. Defaults to [`OciComponentIds::Flatten`], which earlier versions used.
+    pub fn with_oci_component_ids(mut self, ids: OciComponentIds) -> Self {
+        self.oci_component_ids = ids;
+        self
+    }
+
     /// Produce a validated [`LifecycleConfig`] without constructing a manager.
     pub fn build_config(self) -> Result<LifecycleConfig> {
         let component_dir = match self.component_dir.canonicalize() {
@@ -153,12 +505,12 @@ impl LifecycleBuilder {
 
         let http_client = match self.http_client {
             Some(client) => client,
-            None => default_http_client()?,
+            None => default_http_client(self.proxy.as_ref())?,
         };
 
         let oci_client = match self.oci_client {
             Some(client) => client,
-            None => default_oci_client()?,
+            None => default_oci_client(self.proxy.as_ref()),
         };
 
         Ok(LifecycleConfig {
@@ -168,6 +520,26 @@ impl LifecycleBuilder {
             http_client,
             oci_client,
             eager_load: self.eager_load,
+            load_timeout: self.load_timeout,
+            strict_policy: self.strict_policy,
+            per_call_scratch: self.per_call_scratch,
+            max_components: self.max_components,
+            max_loaded_memory: self.max_loaded_memory,
+            outbound_http: self.outbound_http,
+            compilation_strategy: self.compilation_strategy,
+            auto_grant_declared: self.auto_grant_declared,
+            idle_unload_ttl: self.idle_unload_ttl,
+            default_policy: self.default_policy,
+            pooling_allocator: self.pooling_allocator,
+            safe_mode: self.safe_mode,
+            create_component_dir: self.create_component_dir,
+            max_total_log_bytes: self.max_total_log_bytes,
+            egress_byte_limit: self.egress_byte_limit,
+            duplicate_tool_policy: self.duplicate_tool_policy,
+            stderr_tracing: self.stderr_tracing,
+            redact_secrets: self.redact_secrets,
+            strict_security: self.strict_security,
+            oci_component_ids: self.oci_component_ids,
         })
     }
 
@@ -189,27 +561,34 @@ impl LifecycleBuilder {
 }
 
 /// Create the default HTTP client used when none is supplied.
-fn default_http_client() -> Result<reqwest::Client> {
+fn default_http_client(proxy: Option<&ProxyConfig>) -> Result<reqwest::Client> {
     let http_timeout = std::env::var("HTTP_TIMEOUT_SECS")
         .ok()
         .and_then(|s| s.parse().ok())
         .unwrap_or(DEFAULT_HTTP_TIMEOUT_SECS);
 
-    reqwest::Client::builder()
-        .timeout(Duration::from_secs(http_timeout))
+    let mut builder = reqwest::Client::builder().timeout(Duration::from_secs(http_timeout));
+    if let Some(proxy) = proxy {
+        builder = proxy.apply_to_http(builder)?;
+    }
+    builder
         .build()
         .context("Failed to create default HTTP client")
 }
 
 /// Create the default OCI client used when none is supplied.
-fn default_oci_client() -> Result<oci_client::Client> {
+fn default_oci_client(proxy: Option<&ProxyConfig>) -> oci_client::Client {
     let oci_timeout = std::env::var("OCI_TIMEOUT_SECS")
         .ok()
         .and_then(|s| s.parse().ok())
         .unwrap_or(DEFAULT_OCI_TIMEOUT_SECS);
 
-    Ok(oci_client::Client::new(oci_client::client::ClientConfig {
+    let config = oci_client::client::ClientConfig {
         read_timeout: Some(Duration::from_secs(oci_timeout)),
         ..Default::default()
-    }))
+    };
+    oci_client::Client::new(match proxy {
+        Some(proxy) => proxy.apply_to_oci(config),
+        None => config,
+    })
 }
@@ -0,0 +1,97 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Permissions a component says it needs, read from a custom section of its binary.
+//!
+//! The [`PERMISSIONS_SECTION`] section holds a JSON object mapping a permission type to the
+//! rules it needs, each written like the details of a permission grant:
+//!
+//! ```json
+//! {
+//!   "network": [{ "host": "api.example.com" }],
+//!   "storage": [{ "uri": "fs:///data", "access": ["read"] }],
+//!   "environment": [{ "key": "API_KEY" }]
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use wasmparser::{Parser, Payload};
+
+/// Name of the custom section in which a component declares the permissions it needs
+pub const PERMISSIONS_SECTION: &str = "wassette:permissions";
+
+/// A permission a component declares it needs, in the form accepted by
+/// [`LifecycleManager::grant_permission`](crate::LifecycleManager::grant_permission)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeclaredPermission {
+    /// Permission type, e.g. `network`
+    pub permission_type: String,
+    /// Rule details, e.g. `{"host": "api.example.com"}`
+    pub details: Value,
+}
+
+/// Read the permissions declared in `wasm_bytes`. Components without the section declare none.
+pub(crate) fn declared_permissions(wasm_bytes: &[u8]) -> Result<Vec<DeclaredPermission>> {
+    let Some(section) = find_section(wasm_bytes) else {
+        return Ok(Vec::new());
+    };
+
+    let by_type: BTreeMap<String, Vec<Value>> = serde_json::from_slice(section)
+        .with_context(|| format!("Invalid '{PERMISSIONS_SECTION}' custom section"))?;
+    Ok(by_type
+        .into_iter()
+        .flat_map(|(permission_type, rules)| {
+            rules.into_iter().map(move |details| DeclaredPermission {
+                permission_type: permission_type.clone(),
+                details,
+            })
+        })
+        .collect())
+}
+
+fn find_section(wasm_bytes: &[u8]) -> Option<&[u8]> {
+    Parser::new(0)
+        .parse_all(wasm_bytes)
+        .map_while(Result::ok)
+        .find_map(|payload| match payload {
+            Payload::CustomSection(reader) if reader.name() == PERMISSIONS_SECTION => {
+                Some(reader.data())
+            }
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_declared_permissions_from_custom_section() {
+        let wasm = wat::parse_str(
+            r#"(component
+                (@custom "wassette:permissions" "{\"network\": [{\"host\": \"api.example.com\"}], \"environment\": [{\"key\": \"API_KEY\"}]}")
+            )"#,
+        )
+        .unwrap();
+        assert_eq!(
+            declared_permissions(&wasm).unwrap(),
+            vec![
+                DeclaredPermission {
+                    permission_type: "environment".to_string(),
+                    details: serde_json::json!({ "key": "API_KEY" }),
+                },
+                DeclaredPermission {
+                    permission_type: "network".to_string(),
+                    details: serde_json::json!({ "host": "api.example.com" }),
+                },
+            ]
+        );
+
+        let undeclared = wat::parse_str("(component)").unwrap();
+        assert!(declared_permissions(&undeclared).unwrap().is_empty());
+    }
+}
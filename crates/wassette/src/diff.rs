@@ -0,0 +1,187 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Comparison of two versions of a component's tools and capability requirements
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use component2json::ToolMetadata;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use wasmtime::component::Component;
+use wasmtime::Engine;
+
+/// Differences between the installed version of a component and a candidate artifact
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComponentDiff {
+    /// Identifier of the component being compared
+    pub component_id: String,
+    /// Tools only exported by the candidate
+    pub added_tools: Vec<String>,
+    /// Tools only exported by the installed version
+    pub removed_tools: Vec<String>,
+    /// Tools exported by both whose input or output schema differs
+    pub changed_tools: Vec<String>,
+    /// Capabilities the candidate imports that the installed version does not
+    pub new_capabilities: Vec<String>,
+    /// Capabilities the installed version imports that the candidate no longer does
+    pub dropped_capabilities: Vec<String>,
+}
+
+impl ComponentDiff {
+    /// Compare tool schemas (keyed by tool name) and capability sets of two versions
+    pub fn between(
+        component_id: impl Into<String>,
+        current_tools: &BTreeMap<String, Value>,
+        candidate_tools: &BTreeMap<String, Value>,
+        current_capabilities: &BTreeSet<String>,
+        candidate_capabilities: &BTreeSet<String>,
+    ) -> Self {
+        let added_tools = candidate_tools
+            .keys()
+            .filter(|name| !current_tools.contains_key(*name))
+            .cloned()
+            .collect();
+        let removed_tools = current_tools
+            .keys()
+            .filter(|name| !candidate_tools.contains_key(*name))
+            .cloned()
+            .collect();
+        let changed_tools = current_tools
+            .iter()
+            .filter_map(|(name, schema)| {
+                let candidate = candidate_tools.get(name)?;
+                let changed = ["inputSchema", "outputSchema"]
+                    .iter()
+                    .any(|key| schema.get(key) != candidate.get(key));
+                changed.then(|| name.clone())
+            })
+            .collect();
+
+        Self {
+            component_id: component_id.into(),
+            added_tools,
+            removed_tools,
+            changed_tools,
+            new_capabilities: candidate_capabilities
+                .difference(current_capabilities)
+                .cloned()
+                .collect(),
+            dropped_capabilities: current_capabilities
+                .difference(candidate_capabilities)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Whether the two versions expose identical tools and capability requirements
+    pub fn is_empty(&self) -> bool {
+        self.added_tools.is_empty()
+            && self.removed_tools.is_empty()
+            && self.changed_tools.is_empty()
+            && self.new_capabilities.is_empty()
+            && self.dropped_capabilities.is_empty()
+    }
+}
+
+/// Index tool schemas by their normalized tool name
+pub(crate) fn tools_by_name(tools: &[ToolMetadata]) -> BTreeMap<String, Value> {
+    tools
+        .iter()
+        .map(|tool| (tool.normalized_name.clone(), tool.schema.clone()))
+        .collect()
+}
+
+/// Policy permission categories a component may need granted, inferred from its imports
+pub(crate) fn required_capabilities(component: &Component, engine: &Engine) -> BTreeSet<String> {
+    component
+        .component_type()
+        .imports(engine)
+        .filter_map(|(name, _)| capability_for_import(name))
+        .map(str::to_string)
+        .collect()
+}
+
+fn capability_for_import(import: &str) -> Option<&'static str> {
+    if import.starts_with("wasi:http/") || import.starts_with("wasi:sockets/") {
+        Some("network")
+    } else if import.starts_with("wasi:filesystem/") {
+        Some("storage")
+    } else if import.starts_with("wasi:cli/environment") || import.starts_with("wasi:config/") {
+        Some("environment")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn tool(input: Value) -> Value {
+        json!({ "inputSchema": input, "outputSchema": { "type": "string" } })
+    }
+
+    #[test]
+    fn test_diff_reports_added_removed_and_changed_tools() {
+        let current = BTreeMap::from([
+            ("fetch".to_string(), tool(json!({ "url": "string" }))),
+            ("get-time".to_string(), tool(json!({}))),
+            ("legacy".to_string(), tool(json!({}))),
+        ]);
+        let candidate = BTreeMap::from([
+            (
+                "fetch".to_string(),
+                tool(json!({ "url": "string", "headers": "object" })),
+            ),
+            ("get-time".to_string(), tool(json!({}))),
+            ("render".to_string(), tool(json!({}))),
+        ]);
+        let current_caps = BTreeSet::from(["network".to_string()]);
+        let candidate_caps = BTreeSet::from(["network".to_string(), "storage".to_string()]);
+
+        let diff = ComponentDiff::between(
+            "fetch_rs",
+            &current,
+            &candidate,
+            &current_caps,
+            &candidate_caps,
+        );
+
+        assert_eq!(diff.component_id, "fetch_rs");
+        assert_eq!(diff.added_tools, vec!["render"]);
+        assert_eq!(diff.removed_tools, vec!["legacy"]);
+        assert_eq!(diff.changed_tools, vec!["fetch"]);
+        assert_eq!(diff.new_capabilities, vec!["storage"]);
+        assert!(diff.dropped_capabilities.is_empty());
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn test_diff_of_identical_versions_is_empty() {
+        let tools = BTreeMap::from([("fetch".to_string(), tool(json!({})))]);
+        let caps = BTreeSet::from(["network".to_string()]);
+
+        let diff = ComponentDiff::between("fetch_rs", &tools, &tools, &caps, &caps);
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn test_capability_for_import() {
+        assert_eq!(
+            capability_for_import("wasi:http/outgoing-handler@0.2.0"),
+            Some("network")
+        );
+        assert_eq!(
+            capability_for_import("wasi:filesystem/preopens@0.2.0"),
+            Some("storage")
+        );
+        assert_eq!(
+            capability_for_import("wasi:cli/environment@0.2.0"),
+            Some("environment")
+        );
+        assert_eq!(capability_for_import("wasi:clocks/wall-clock@0.2.0"), None);
+    }
+}
@@ -0,0 +1,51 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Component lifecycle notifications published by the lifecycle manager
+
+use serde::{Deserialize, Serialize};
+
+/// Number of events buffered per subscriber before the slowest receivers start lagging
+pub const LIFECYCLE_EVENT_CAPACITY: usize = 64;
+
+/// A change to the set of loaded components, emitted once the change has been applied
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum LifecycleEvent {
+    /// A component that was not previously loaded is now available
+    Loaded {
+        /// Identifier of the loaded component
+        id: String,
+        /// URI or path the component was loaded from
+        source: String,
+    },
+    /// A component was removed along with its files on disk
+    Unloaded {
+        /// Identifier of the unloaded component
+        id: String,
+    },
+    /// A component was given a new id, keeping its files, policy, and tools
+    Renamed {
+        /// Identifier the component now has
+        id: String,
+        /// Identifier the component had before
+        previous_id: String,
+    },
+    /// An already loaded component was replaced by a new artifact
+    Reloaded {
+        /// Identifier of the reloaded component
+        id: String,
+    },
+}
+
+impl LifecycleEvent {
+    /// Identifier of the component the event refers to
+    pub fn component_id(&self) -> &str {
+        match self {
+            LifecycleEvent::Loaded { id, .. }
+            | LifecycleEvent::Unloaded { id }
+            | LifecycleEvent::Renamed { id, .. }
+            | LifecycleEvent::Reloaded { id } => id,
+        }
+    }
+}
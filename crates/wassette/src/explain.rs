@@ -0,0 +1,329 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Predicting whether a component's policy allows a given network, storage or environment
+//! access, using the same matching rules that are enforced at call time
+
+use std::path::Path;
+
+use anyhow::Result;
+use policy::{AccessType, PolicyDocument};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::http::AllowedHost;
+use crate::wasistate::normalize_path;
+
+/// An access a component might attempt
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum PermissionQuery {
+    /// An outbound HTTP request to `host`, optionally with a scheme (`https://api.example.com`)
+    Network { host: String },
+    /// Access to a file or directory, given as an `fs://` URI or a plain path
+    Storage {
+        uri: String,
+        access: Vec<AccessType>,
+    },
+    /// Reading the environment variable `key`
+    EnvironmentVariable { key: String },
+}
+
+/// Whether a component's effective policy allows a [`PermissionQuery`], and which rule decides it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PermissionExplanation {
+    /// Identifier of the component whose policy was consulted
+    pub component_id: String,
+    /// The access that was checked
+    pub query: PermissionQuery,
+    /// Whether the access would be allowed
+    pub allowed: bool,
+    /// The policy rule that allows the access, as written in the policy
+    pub matched_rule: Option<Value>,
+    /// Why the access is allowed or denied
+    pub reason: String,
+}
+
+/// Check `query` against `policy`. In safe mode nothing is allowed, but the rule that would
+/// allow the access is still reported.
+pub(crate) fn explain(
+    component_id: &str,
+    policy: &PolicyDocument,
+    query: &PermissionQuery,
+    safe_mode: bool,
+) -> Result<PermissionExplanation> {
+    let (matched_rule, mut reason) = match query {
+        PermissionQuery::Network { host } => explain_network(policy, host)?,
+        PermissionQuery::Storage { uri, access } => explain_storage(policy, uri, access),
+        PermissionQuery::EnvironmentVariable { key } => explain_environment(policy, key),
+    };
+    let allowed = matched_rule.is_some() && !safe_mode;
+    if safe_mode {
+        reason = format!(
+            "Denied because safe mode is on; components get none of the capabilities their policies grant ({reason})"
+        );
+    }
+
+    Ok(PermissionExplanation {
+        component_id: component_id.to_string(),
+        query: query.clone(),
+        allowed,
+        matched_rule,
+        reason,
+    })
+}
+
+/// Deny a network access the policy allows when its host resolves to an internal address,
+/// since outbound requests refuse those unless internal addresses are allowed
+pub(crate) async fn apply_internal_address_block(explanation: &mut PermissionExplanation) {
+    let PermissionQuery::Network { host } = &explanation.query else {
+        return;
+    };
+    if !explanation.allowed {
+        return;
+    }
+    let Ok(target) = AllowedHost::from_str(host) else {
+        return;
+    };
+    if let Some(address) = crate::http::internal_address_of(&target.host).await {
+        explanation.allowed = false;
+        explanation.reason = format!(
+            "Denied: '{}' resolves to the internal address {address}, which outbound requests are refused for unless internal addresses are allowed ({})",
+            target.host, explanation.reason
+        );
+    }
+}
+
+fn explain_network(policy: &PolicyDocument, host: &str) -> Result<(Option<Value>, String)> {
+    let target = AllowedHost::from_str(host)?;
+    let request_host = target.host.to_ascii_lowercase();
+    let rules = policy
+        .permissions
+        .network
+        .as_ref()
+        .and_then(|network| network.allow.as_ref());
+
+    for rule in rules.into_iter().flatten() {
+        let rule = serde_json::to_value(rule)?;
+        let Some(allowed) = rule.get("host").and_then(Value::as_str) else {
+            continue;
+        };
+        if AllowedHost::from_str(allowed)
+            .is_ok_and(|allowed| allowed.matches(&request_host, target.scheme.as_deref()))
+        {
+            let reason = format!("Allowed by network rule for host '{allowed}'");
+            return Ok((Some(rule), reason));
+        }
+    }
+    Ok((
+        None,
+        format!("Denied: no network rule allows host '{request_host}'"),
+    ))
+}
+
+fn explain_storage(
+    policy: &PolicyDocument,
+    uri: &str,
+    access: &[AccessType],
+) -> (Option<Value>, String) {
+    let access = if access.is_empty() {
+        &[AccessType::Read][..]
+    } else {
+        access
+    };
+    let target = normalize_path(Path::new(uri.strip_prefix("fs://").unwrap_or(uri)));
+    let rules = policy
+        .permissions
+        .storage
+        .as_ref()
+        .and_then(|storage| storage.allow.as_ref());
+
+    let mut insufficient = None;
+    for rule in rules.into_iter().flatten() {
+        let Some(granted) = rule.uri.strip_prefix("fs://") else {
+            continue;
+        };
+        if !target.starts_with(normalize_path(Path::new(granted))) {
+            continue;
+        }
+        if access.iter().all(|wanted| rule.access.contains(wanted)) {
+            let reason = format!(
+                "Allowed by storage rule '{}' granting {}",
+                rule.uri,
+                access_list(&rule.access)
+            );
+            return (serde_json::to_value(rule).ok(), reason);
+        }
+        insufficient.get_or_insert(rule);
+    }
+
+    let reason = match insufficient {
+        Some(rule) => format!(
+            "Denied: storage rule '{}' covers {} but grants only {}, not {}",
+            rule.uri,
+            target.display(),
+            access_list(&rule.access),
+            access_list(access)
+        ),
+        None => format!("Denied: no storage rule covers {}", target.display()),
+    };
+    (None, reason)
+}
+
+fn explain_environment(policy: &PolicyDocument, key: &str) -> (Option<Value>, String) {
+    let rule = policy
+        .permissions
+        .environment
+        .as_ref()
+        .and_then(|environment| environment.allow.as_ref())
+        .and_then(|rules| rules.iter().find(|rule| rule.matches(key)));
+
+    match rule {
+        Some(rule) => (
+            serde_json::to_value(rule).ok(),
+            format!(
+                "Allowed by environment rule '{}'; the component sees the variable when it is set for the server or the component",
+                rule.key
+            ),
+        ),
+        None => (
+            None,
+            format!("Denied: no environment rule allows '{key}'"),
+        ),
+    }
+}
+
+fn access_list(access: &[AccessType]) -> String {
+    access
+        .iter()
+        .map(|access| match access {
+            AccessType::Read => "read",
+            AccessType::Write => "write",
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn policy() -> PolicyDocument {
+        serde_yaml::from_str(
+            r#"
+version: "1.0"
+permissions:
+  network:
+    allow:
+      - host: "api.example.com"
+      - host: "https://secure.example.com"
+  storage:
+    allow:
+      - uri: "fs:///tmp/cache"
+        access: ["read"]
+  environment:
+    allow:
+      - key: "AWS_*"
+"#,
+        )
+        .unwrap()
+    }
+
+    fn network(host: &str) -> PermissionQuery {
+        PermissionQuery::Network {
+            host: host.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_explain_allowed_host_reports_matching_rule() {
+        let explanation = explain("fetch", &policy(), &network("API.example.com"), false).unwrap();
+        assert!(explanation.allowed);
+        assert_eq!(
+            explanation.matched_rule,
+            Some(json!({ "host": "api.example.com" }))
+        );
+        assert!(explanation.reason.contains("api.example.com"));
+
+        // Scheme-qualified rules only match that scheme
+        let explanation = explain(
+            "fetch",
+            &policy(),
+            &network("https://secure.example.com"),
+            false,
+        )
+        .unwrap();
+        assert!(explanation.allowed);
+        assert!(
+            !explain(
+                "fetch",
+                &policy(),
+                &network("http://secure.example.com"),
+                false
+            )
+            .unwrap()
+            .allowed
+        );
+
+        // Safe mode denies but still names the rule
+        let explanation = explain("fetch", &policy(), &network("api.example.com"), true).unwrap();
+        assert!(!explanation.allowed);
+        assert!(explanation.matched_rule.is_some());
+        assert!(explanation.reason.contains("safe mode"));
+    }
+
+    #[test]
+    fn test_explain_denied_host_reports_no_match() {
+        let explanation = explain("fetch", &policy(), &network("evil.example.com"), false).unwrap();
+        assert!(!explanation.allowed);
+        assert_eq!(explanation.matched_rule, None);
+        assert_eq!(
+            explanation.reason,
+            "Denied: no network rule allows host 'evil.example.com'"
+        );
+    }
+
+    #[test]
+    fn test_explain_storage_and_environment() {
+        let storage = |uri: &str, access: Vec<AccessType>| {
+            explain(
+                "fetch",
+                &policy(),
+                &PermissionQuery::Storage {
+                    uri: uri.to_string(),
+                    access,
+                },
+                false,
+            )
+            .unwrap()
+        };
+        assert!(storage("fs:///tmp/cache/data.json", vec![]).allowed);
+        assert!(!storage("fs:///tmp/cache/../other", vec![]).allowed);
+        let write = storage("/tmp/cache/data.json", vec![AccessType::Write]);
+        assert!(!write.allowed);
+        assert!(
+            write.reason.contains("grants only read"),
+            "{}",
+            write.reason
+        );
+
+        let env = |key: &str| {
+            explain(
+                "fetch",
+                &policy(),
+                &PermissionQuery::EnvironmentVariable {
+                    key: key.to_string(),
+                },
+                false,
+            )
+            .unwrap()
+        };
+        assert_eq!(
+            env("AWS_REGION").matched_rule,
+            Some(json!({ "key": "AWS_*" }))
+        );
+        assert!(!env("HOME").allowed);
+    }
+}
@@ -0,0 +1,223 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Fetching artifacts out of Git repositories for `git+` references such as
+//! `git+https://github.com/org/repo//path/to/tool.wasm@v1.2.0`.
+//!
+//! The repository URL and the path inside it are separated by `//`, and the optional `@ref`
+//! names a branch, tag or commit (the remote's default branch otherwise). Fetching shells out
+//! to the `git` executable so that the user's credential helpers and SSH setup apply.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use tokio::process::Command;
+use tracing::debug;
+
+/// Scheme prefix marking a Git reference, followed by the transport (`https`, `ssh`, `file`)
+pub(crate) const GIT_SCHEME_PREFIX: &str = "git+";
+
+/// How long a single git command may run before it is killed, so an unresponsive remote
+/// cannot hold a load forever
+const GIT_COMMAND_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// A parsed `git+<repo>//<path>[@ref]` reference
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct GitReference {
+    /// Repository URL handed to `git fetch`
+    pub repository: String,
+    /// Path of the artifact relative to the repository root
+    pub path: String,
+    /// Branch, tag or commit to fetch; `None` fetches the remote's `HEAD`
+    pub rev: Option<String>,
+}
+
+impl GitReference {
+    pub fn parse(uri: &str) -> Result<Self> {
+        let rest = uri
+            .trim()
+            .strip_prefix(GIT_SCHEME_PREFIX)
+            .context("Git references must start with 'git+'")?;
+        let (transport, after_scheme) = rest.split_once("://").with_context(|| {
+            format!("Invalid Git reference '{uri}': expected git+<scheme>://<repository>//<path>")
+        })?;
+        if !matches!(transport, "https" | "http" | "ssh" | "file") {
+            bail!("Unsupported Git transport '{transport}' in '{uri}'. Use git+https, git+ssh or git+file");
+        }
+        let (repository, path) = after_scheme.split_once("//").with_context(|| {
+            format!(
+                "Git reference '{uri}' does not name an artifact. Separate the repository from the path inside it with '//', e.g. git+https://github.com/org/repo//tool.wasm"
+            )
+        })?;
+        let (path, rev) = match path.rsplit_once('@') {
+            Some((path, rev)) => (path, Some(rev)),
+            None => (path, None),
+        };
+
+        if repository.is_empty() || path.is_empty() {
+            bail!(
+                "Invalid Git reference '{uri}': the repository and artifact path must not be empty"
+            );
+        }
+        if Path::new(path)
+            .components()
+            .any(|c| !matches!(c, std::path::Component::Normal(_)))
+        {
+            bail!("Invalid Git reference '{uri}': the artifact path must be relative and must not contain '..'");
+        }
+        if rev.is_some_and(|rev| rev.is_empty() || rev.starts_with('-')) {
+            bail!("Invalid Git reference '{uri}': '@' must be followed by a branch, tag or commit");
+        }
+
+        Ok(Self {
+            repository: format!("{transport}://{repository}"),
+            path: path.to_string(),
+            rev: rev.map(str::to_string),
+        })
+    }
+
+    /// The reference without its `@ref`, identifying the artifact across revisions
+    pub fn source(&self) -> String {
+        format!("{GIT_SCHEME_PREFIX}{}//{}", self.repository, self.path)
+    }
+
+    /// Shallowly fetch the requested revision into `checkout` and return the artifact's path
+    /// inside it. Artifacts that resolve outside the checkout through a symbolic link are
+    /// refused.
+    pub async fn fetch_into(&self, checkout: &Path) -> Result<PathBuf> {
+        let rev = self.rev.as_deref().unwrap_or("HEAD");
+        debug!(repository = %self.repository, rev, "Fetching Git reference");
+
+        run_git(checkout, &["init", "--quiet"]).await?;
+        run_git(
+            checkout,
+            &[
+                "fetch",
+                "--quiet",
+                "--depth",
+                "1",
+                "--",
+                &self.repository,
+                rev,
+            ],
+        )
+        .await
+        .with_context(|| format!("Failed to fetch '{rev}' from {}", self.repository))?;
+        run_git(checkout, &["checkout", "--quiet", "FETCH_HEAD"]).await?;
+
+        let artifact = checkout.join(&self.path);
+        let resolved = match tokio::fs::canonicalize(&artifact).await {
+            Ok(resolved) => resolved,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => bail!(
+                "'{}' does not exist at '{rev}' in {}",
+                self.path,
+                self.repository
+            ),
+            Err(e) => return Err(e).with_context(|| format!("Failed to resolve '{}'", self.path)),
+        };
+        let root = tokio::fs::canonicalize(checkout).await?;
+        if !resolved.starts_with(&root) {
+            bail!(
+                "'{}' at '{rev}' in {} is a symbolic link pointing outside the repository",
+                self.path,
+                self.repository
+            );
+        }
+        Ok(artifact)
+    }
+}
+
+/// The source a `git+` URI identifies regardless of revision, or `None` for other URIs
+pub(crate) fn source_identity(uri: &str) -> Option<String> {
+    GitReference::parse(uri)
+        .ok()
+        .map(|reference| reference.source())
+}
+
+async fn run_git(dir: &Path, args: &[&str]) -> Result<()> {
+    run_git_with_timeout(dir, args, GIT_COMMAND_TIMEOUT).await
+}
+
+async fn run_git_with_timeout(dir: &Path, args: &[&str], timeout: Duration) -> Result<()> {
+    let command = args.first().copied().unwrap_or_default();
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        // Fail instead of waiting for a password on a terminal nobody is watching
+        .env("GIT_TERMINAL_PROMPT", "0")
+        // Dropping the output future on timeout kills git instead of leaving it running
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(timeout, output)
+        .await
+        .map_err(|_| anyhow::anyhow!("git {command} timed out after {timeout:?}"))?
+        .context("Failed to run git. Loading git+ references requires git to be installed")?;
+    if !output.status.success() {
+        bail!(
+            "git {command} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_git_reference() {
+        let reference =
+            GitReference::parse("git+https://github.com/org/repo//path/to/tool.wasm@v1.2.0")
+                .unwrap();
+        assert_eq!(
+            reference,
+            GitReference {
+                repository: "https://github.com/org/repo".to_string(),
+                path: "path/to/tool.wasm".to_string(),
+                rev: Some("v1.2.0".to_string()),
+            }
+        );
+        assert_eq!(
+            reference.source(),
+            "git+https://github.com/org/repo//path/to/tool.wasm"
+        );
+
+        let reference = GitReference::parse("git+file:///srv/git/tools.git//tool.wasm").unwrap();
+        assert_eq!(reference.repository, "file:///srv/git/tools.git");
+        assert_eq!(reference.path, "tool.wasm");
+        assert_eq!(reference.rev, None);
+
+        assert!(GitReference::parse("git+https://github.com/org/repo").is_err());
+        assert!(GitReference::parse("git+ext::sh -c true://x//tool.wasm").is_err());
+        assert!(GitReference::parse("git+https://github.com/org/repo//../etc/passwd").is_err());
+        assert!(GitReference::parse("git+https://github.com/org/repo//tool.wasm@").is_err());
+        assert!(
+            GitReference::parse("git+https://github.com/org/repo//tool.wasm@--upload-pack=x")
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_git_fetch_times_out_on_unresponsive_remote() {
+        // A listener that never answers: connections are queued but nothing is ever sent
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let remote = format!("http://{}/repo.git", listener.local_addr().unwrap());
+        let checkout = tempfile::tempdir().unwrap();
+        run_git(checkout.path(), &["init", "--quiet"])
+            .await
+            .unwrap();
+
+        let start = std::time::Instant::now();
+        let err = run_git_with_timeout(
+            checkout.path(),
+            &["fetch", "--quiet", "--depth", "1", "--", &remote, "HEAD"],
+            Duration::from_millis(500),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("git fetch timed out"), "{err:#}");
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,159 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Readiness probes through an optional `wassette:health/check` export.
+//!
+//! The interface has a single `check` function returning a `result`, optionally with a
+//! string error payload. `ok` means healthy; `err`, any other return value, a trap, or not
+//! answering within [`HEALTH_CHECK_TIMEOUT`] means unhealthy. Components that do not export
+//! the interface report an unknown status.
+
+use std::time::Duration;
+
+use serde::Serialize;
+use wasmtime::component::{Component, Val};
+use wasmtime::Engine;
+
+/// Name of the interface a component exports to answer health checks
+pub const HEALTH_INTERFACE: &str = "wassette:health/check";
+
+/// Function of [`HEALTH_INTERFACE`] invoked by a health check
+pub(crate) const CHECK_FUNCTION: &str = "check";
+
+/// How long a component's `check` may run before it is interrupted and reported unhealthy
+pub const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Outcome of a component health check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    /// The component's `check` returned `ok`
+    Healthy,
+    /// The component's `check` returned `err` or an unexpected value, trapped, or timed out
+    Unhealthy,
+    /// The component does not export a health check
+    Unknown,
+}
+
+/// Result of [`LifecycleManager::check_component_health`](crate::LifecycleManager::check_component_health)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ComponentHealth {
+    /// Health status reported by the component
+    pub status: HealthStatus,
+    /// Error message returned by the check, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl ComponentHealth {
+    pub(crate) fn healthy() -> Self {
+        Self {
+            status: HealthStatus::Healthy,
+            message: None,
+        }
+    }
+
+    pub(crate) fn unhealthy(message: impl Into<String>) -> Self {
+        Self {
+            status: HealthStatus::Unhealthy,
+            message: Some(message.into()),
+        }
+    }
+
+    pub(crate) fn unknown() -> Self {
+        Self {
+            status: HealthStatus::Unknown,
+            message: None,
+        }
+    }
+}
+
+/// Whether `name` is the health interface, with or without a version suffix
+pub(crate) fn is_health_interface(name: &str) -> bool {
+    name.strip_prefix(HEALTH_INTERFACE)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('@'))
+}
+
+/// Interpret the values returned by `check`. Only `ok` counts as healthy; a return value that
+/// is not a `result` with an optional string error is reported as unhealthy.
+pub(crate) fn check_outcome(results: &[Val]) -> ComponentHealth {
+    match results {
+        [Val::Result(Ok(None))] => ComponentHealth::healthy(),
+        [Val::Result(Err(payload))] => match payload.as_deref() {
+            Some(Val::String(message)) => ComponentHealth::unhealthy(message.clone()),
+            None => ComponentHealth::unhealthy("health check returned an error"),
+            Some(other) => ComponentHealth::unhealthy(format!(
+                "health check returned an error of unexpected type: {other:?}"
+            )),
+        },
+        other => ComponentHealth::unhealthy(format!(
+            "health check returned {other:?}, expected a result"
+        )),
+    }
+}
+
+/// Export name of the health interface if the component implements it
+pub(crate) fn health_export(component: &Component, engine: &Engine) -> Option<String> {
+    component
+        .component_type()
+        .exports(engine)
+        .map(|(name, _)| name)
+        .find(|name| is_health_interface(name))
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_health_interface() {
+        assert!(is_health_interface("wassette:health/check"));
+        assert!(is_health_interface("wassette:health/check@0.1.0"));
+        assert!(!is_health_interface("wassette:health/checks"));
+        assert!(!is_health_interface("wassette:lifecycle/hooks"));
+    }
+
+    #[test]
+    fn test_check_outcome() {
+        assert_eq!(
+            check_outcome(&[Val::Result(Ok(None))]).status,
+            HealthStatus::Healthy
+        );
+        assert_eq!(
+            check_outcome(&[Val::Result(Err(Some(Box::new(Val::String(
+                "db down".to_string()
+            )))))]),
+            ComponentHealth::unhealthy("db down")
+        );
+        assert_eq!(
+            check_outcome(&[Val::Result(Err(None))]),
+            ComponentHealth::unhealthy("health check returned an error")
+        );
+        // Anything but a bare `result` is not a passing check
+        for results in [
+            vec![],
+            vec![Val::Bool(true)],
+            vec![Val::Result(Ok(Some(Box::new(Val::U32(1)))))],
+            vec![Val::Result(Ok(None)), Val::Bool(true)],
+        ] {
+            assert_eq!(
+                check_outcome(&results).status,
+                HealthStatus::Unhealthy,
+                "{results:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_health_serialization() {
+        assert_eq!(
+            serde_json::to_value(ComponentHealth::unhealthy("db down")).unwrap(),
+            serde_json::json!({ "status": "unhealthy", "message": "db down" })
+        );
+        assert_eq!(
+            serde_json::to_value(ComponentHealth::unknown()).unwrap(),
+            serde_json::json!({ "status": "unknown" })
+        );
+    }
+}
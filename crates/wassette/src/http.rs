@@ -2,26 +2,44 @@
 // Licensed under the MIT license.
 
 use std::collections::HashSet;
+use std::future::Future;
+use std::net::{IpAddr, SocketAddr};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::task::{Context, Poll};
+use std::time::Duration;
 
 use anyhow::Result;
+use bytes::Bytes;
+use http_body::{Body, Frame, SizeHint};
+use http_body_util::BodyExt;
+use hyper_util::rt::TokioExecutor;
+use tokio_rustls::rustls;
 use tracing::{debug, warn};
 use url::Url;
 use wasmtime::component::{Resource, ResourceTable};
+use wasmtime_wasi::runtime::AbortOnDropJoinHandle;
 use wasmtime_wasi::{WasiCtxView, WasiView};
 use wasmtime_wasi_http::bindings::http::types;
-use wasmtime_wasi_http::types::{HostFutureIncomingResponse, OutgoingRequestConfig};
-use wasmtime_wasi_http::{HttpResult, WasiHttpView};
+use wasmtime_wasi_http::body::{HyperIncomingBody, HyperOutgoingBody};
+use wasmtime_wasi_http::io::TokioIo;
+use wasmtime_wasi_http::types::{
+    default_send_request_handler, HostFutureIncomingResponse, IncomingResponse,
+    OutgoingRequestConfig,
+};
+use wasmtime_wasi_http::{hyper_request_error, hyper_response_error, HttpResult, WasiHttpView};
 
 use crate::wasistate::PermissionError;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-struct AllowedHost {
-    scheme: Option<String>,
-    host: String,
+pub(crate) struct AllowedHost {
+    pub(crate) scheme: Option<String>,
+    pub(crate) host: String,
 }
 
 impl AllowedHost {
-    fn from_str(host_str: &str) -> Result<Self> {
+    pub(crate) fn from_str(host_str: &str) -> Result<Self> {
         if let Ok(url) = Url::parse(host_str) {
             Ok(AllowedHost {
                 scheme: Some(url.scheme().to_string()),
@@ -37,7 +55,7 @@ impl AllowedHost {
         }
     }
 
-    fn matches(&self, request_host: &str, request_scheme: Option<&str>) -> bool {
+    pub(crate) fn matches(&self, request_host: &str, request_scheme: Option<&str>) -> bool {
         if self.host != request_host {
             return false;
         }
@@ -49,6 +67,145 @@ impl AllowedHost {
     }
 }
 
+/// Settings applied to every outbound HTTP request a component makes
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OutboundHttpConfig {
+    /// `User-Agent` sent on every request, replacing the one set by the component
+    pub user_agent: Option<String>,
+    /// Upper bound for connecting, waiting for the first response byte and waiting between
+    /// body chunks. Tighter limits requested by the component still apply.
+    pub request_timeout: Option<Duration>,
+    /// Let granted hostnames resolve to loopback, private and link-local addresses. Off by
+    /// default, so that changing the DNS record of a granted name cannot point a component
+    /// at services inside the host's network.
+    pub allow_internal_addresses: bool,
+}
+
+impl OutboundHttpConfig {
+    fn apply<B>(&self, request: &mut hyper::Request<B>, config: &mut OutgoingRequestConfig) {
+        if let Some(user_agent) = &self.user_agent {
+            match hyper::header::HeaderValue::from_str(user_agent) {
+                Ok(value) => {
+                    request
+                        .headers_mut()
+                        .insert(hyper::header::USER_AGENT, value);
+                }
+                Err(e) => warn!(user_agent, error = %e, "Ignoring invalid outbound User-Agent"),
+            }
+        }
+        if let Some(timeout) = self.request_timeout {
+            config.connect_timeout = config.connect_timeout.min(timeout);
+            config.first_byte_timeout = config.first_byte_timeout.min(timeout);
+            config.between_bytes_timeout = config.between_bytes_timeout.min(timeout);
+        }
+    }
+}
+
+/// Bytes of HTTP response bodies a component received during one call, with an optional cap
+#[derive(Debug, Default)]
+pub(crate) struct EgressMeter {
+    bytes: AtomicU64,
+    limit: Option<u64>,
+    exceeded: AtomicBool,
+}
+
+impl EgressMeter {
+    pub(crate) fn new(limit: Option<u64>) -> Self {
+        Self {
+            limit,
+            ..Default::default()
+        }
+    }
+
+    /// Count `len` more bytes, returning `false` once the total is over the limit
+    fn record(&self, len: u64) -> bool {
+        let total = self.bytes.fetch_add(len, Ordering::Relaxed) + len;
+        match self.limit {
+            Some(limit) if total > limit => {
+                self.exceeded.store(true, Ordering::Relaxed);
+                false
+            }
+            _ => true,
+        }
+    }
+
+    pub(crate) fn bytes(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    /// The limit, if the component received more than it allows
+    pub(crate) fn exceeded_limit(&self) -> Option<u64> {
+        self.limit.filter(|_| self.exceeded.load(Ordering::Relaxed))
+    }
+
+    /// Count the body of `response` as it is read
+    fn meter(self: &Arc<Self>, response: IncomingResponse) -> IncomingResponse {
+        let meter = Arc::clone(self);
+        IncomingResponse {
+            resp: response
+                .resp
+                .map(|body| MeteredBody { body, meter }.boxed()),
+            ..response
+        }
+    }
+
+    fn meter_future(
+        self: &Arc<Self>,
+        future: HostFutureIncomingResponse,
+    ) -> HostFutureIncomingResponse {
+        match future {
+            HostFutureIncomingResponse::Pending(handle) => {
+                let meter = Arc::clone(self);
+                HostFutureIncomingResponse::pending(wasmtime_wasi::runtime::spawn(async move {
+                    handle
+                        .await
+                        .map(|response| response.map(|response| meter.meter(response)))
+                }))
+            }
+            HostFutureIncomingResponse::Ready(result) => HostFutureIncomingResponse::Ready(
+                result.map(|response| response.map(|response| self.meter(response))),
+            ),
+            consumed => consumed,
+        }
+    }
+}
+
+/// Response body that counts the bytes passing through and fails once over the meter's limit
+struct MeteredBody {
+    body: HyperIncomingBody,
+    meter: Arc<EgressMeter>,
+}
+
+impl Body for MeteredBody {
+    type Data = Bytes;
+    type Error = types::ErrorCode;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, types::ErrorCode>>> {
+        let frame = std::task::ready!(Pin::new(&mut self.body).poll_frame(cx));
+        if let Some(Ok(frame)) = &frame {
+            let len = frame.data_ref().map_or(0, |data| data.len() as u64);
+            if !self.meter.record(len) {
+                warn!(limit = ?self.meter.limit, "HTTP response body exceeds the egress limit");
+                return Poll::Ready(Some(Err(types::ErrorCode::HttpResponseBodySize(
+                    self.meter.limit,
+                ))));
+            }
+        }
+        Poll::Ready(frame)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.body.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.body.size_hint()
+    }
+}
+
 /// WassetteWasiState is a wrapper around a WASI state that enforces network policies by filtering
 /// outgoing HTTP requests based on a list of allowed hosts from the component's policy document.
 pub struct WassetteWasiState<T> {
@@ -60,6 +217,12 @@ pub struct WassetteWasiState<T> {
 
     /// Last permission error (for tracking network denials)
     last_network_denial: std::sync::Arc<std::sync::Mutex<Option<(String, String)>>>,
+
+    /// User-Agent and timeout applied to allowed requests
+    outbound: OutboundHttpConfig,
+
+    /// Response bytes received by the call this state belongs to
+    egress: Arc<EgressMeter>,
 }
 
 impl<T> WassetteWasiState<T> {
@@ -83,9 +246,28 @@ impl<T> WassetteWasiState<T> {
             inner,
             allowed_hosts: parsed_hosts,
             last_network_denial: std::sync::Arc::new(std::sync::Mutex::new(None)),
+            outbound: OutboundHttpConfig::default(),
+            egress: Arc::default(),
         })
     }
 
+    /// Apply `outbound` to every request the component is allowed to send
+    pub fn with_outbound_config(mut self, outbound: OutboundHttpConfig) -> Self {
+        self.outbound = outbound;
+        self
+    }
+
+    /// Fail response bodies once the call has received more than `limit` bytes in total
+    pub(crate) fn with_egress_limit(mut self, limit: Option<u64>) -> Self {
+        self.egress = Arc::new(EgressMeter::new(limit));
+        self
+    }
+
+    /// Response bytes the component has received so far
+    pub(crate) fn egress(&self) -> &EgressMeter {
+        &self.egress
+    }
+
     /// Check if a host is allowed by the policy
     fn is_host_allowed(&self, uri: &hyper::Uri) -> bool {
         let request_host = if let Some(host) = uri.host() {
@@ -107,8 +289,237 @@ impl<T> WassetteWasiState<T> {
     }
 }
 
+/// Whether `ip` belongs to the host itself or a private network rather than the internet
+fn is_internal_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [first, second, ..] = ip.octets();
+            ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                // 100.64.0.0/10, the shared address space of carrier-grade NAT
+                || (first == 100 && second & 0xc0 == 64)
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_internal_address(IpAddr::V4(ip)),
+            None => {
+                let first = ip.segments()[0];
+                ip.is_loopback()
+                    || ip.is_unspecified()
+                    // fc00::/7 unique local and fe80::/10 link-local addresses
+                    || first & 0xfe00 == 0xfc00
+                    || first & 0xffc0 == 0xfe80
+            }
+        },
+    }
+}
+
+/// The first internal address `host` resolves to, if any. Hosts given as an IP address are
+/// not looked up, since requests naming an address are not checked either.
+pub(crate) async fn internal_address_of(host: &str) -> Option<IpAddr> {
+    let bare = host.trim_start_matches('[').trim_end_matches(']');
+    if bare.parse::<IpAddr>().is_ok() {
+        return None;
+    }
+    let addrs = tokio::net::lookup_host((bare, 0)).await.ok()?;
+    addrs
+        .map(|addr| addr.ip())
+        .find(|ip| is_internal_address(*ip))
+}
+
+/// Choose the address to connect to for a granted hostname from what it resolved to.
+///
+/// Every resolved address is checked rather than just the chosen one, so a name that mixes
+/// public and internal records is refused outright.
+fn pin_resolved_address(
+    host: &str,
+    addrs: impl IntoIterator<Item = SocketAddr>,
+) -> Result<SocketAddr, types::ErrorCode> {
+    let mut pinned = None;
+    for addr in addrs {
+        if is_internal_address(addr.ip()) {
+            warn!(
+                host,
+                address = %addr.ip(),
+                "HTTP request blocked: granted host resolved to an internal address"
+            );
+            return Err(types::ErrorCode::DestinationIpProhibited);
+        }
+        pinned.get_or_insert(addr);
+    }
+    pinned.ok_or_else(|| dns_error(format!("{host} did not resolve to any address")))
+}
+
+fn dns_error(rcode: String) -> types::ErrorCode {
+    types::ErrorCode::DnsError(types::DnsErrorPayload {
+        rcode: Some(rcode),
+        info_code: None,
+    })
+}
+
+/// Resolve the request's host, check the addresses and send the request to the checked one,
+/// so the name cannot be re-resolved to a different address in between.
+async fn send_to_checked_address(
+    request: hyper::Request<HyperOutgoingBody>,
+    config: OutgoingRequestConfig,
+) -> Result<IncomingResponse, types::ErrorCode> {
+    let host = request.uri().host().unwrap_or_default().to_string();
+    let port = request
+        .uri()
+        .port_u16()
+        .unwrap_or(if config.use_tls { 443 } else { 80 });
+
+    let addrs = tokio::net::lookup_host((host.as_str(), port))
+        .await
+        .map_err(|e| dns_error(e.to_string()))?;
+    let addr = pin_resolved_address(&host, addrs)?;
+    send_to_address(request, config, addr).await
+}
+
+/// Send the request over a connection to `addr`. The Host header keeps naming the granted
+/// host, and HTTPS connections send it for SNI and verify the certificate against it.
+async fn send_to_address(
+    mut request: hyper::Request<HyperOutgoingBody>,
+    config: OutgoingRequestConfig,
+    addr: SocketAddr,
+) -> Result<IncomingResponse, types::ErrorCode> {
+    let host = request.uri().host().unwrap_or_default().to_string();
+    let authority = request
+        .uri()
+        .authority()
+        .map(|a| a.to_string())
+        .unwrap_or_default();
+    if let Ok(value) = hyper::header::HeaderValue::from_str(&authority) {
+        request
+            .headers_mut()
+            .entry(hyper::header::HOST)
+            .or_insert(value);
+    }
+
+    if !config.use_tls {
+        let mut parts = request.uri().clone().into_parts();
+        parts.authority = Some(
+            addr.to_string()
+                .parse()
+                .map_err(|_| types::ErrorCode::HttpRequestUriInvalid)?,
+        );
+        *request.uri_mut() =
+            hyper::Uri::from_parts(parts).map_err(|_| types::ErrorCode::HttpRequestUriInvalid)?;
+        return default_send_request_handler(request, config).await;
+    }
+
+    let OutgoingRequestConfig {
+        connect_timeout,
+        first_byte_timeout,
+        between_bytes_timeout,
+        ..
+    } = config;
+    let tcp_stream = tokio::time::timeout(connect_timeout, tokio::net::TcpStream::connect(addr))
+        .await
+        .map_err(|_| types::ErrorCode::ConnectionTimeout)?
+        .map_err(|_| types::ErrorCode::ConnectionRefused)?;
+    let server_name = rustls::pki_types::ServerName::try_from(host.trim_matches(['[', ']']))
+        .map_err(|_| dns_error(format!("{host} is not a valid server name")))?
+        .to_owned();
+    let tls_stream = tokio::time::timeout(
+        connect_timeout,
+        tls_connector().connect(server_name, tcp_stream),
+    )
+    .await
+    .map_err(|_| types::ErrorCode::ConnectionTimeout)?
+    .map_err(|error| {
+        warn!(host, %error, "TLS handshake failed");
+        types::ErrorCode::TlsProtocolError
+    })?;
+    let negotiated_http2 = tls_stream.get_ref().1.alpn_protocol() == Some(&b"h2"[..]);
+    let io = TokioIo::new(tls_stream);
+
+    let (response, worker) = if negotiated_http2 {
+        let (mut sender, connection) = tokio::time::timeout(
+            connect_timeout,
+            hyper::client::conn::http2::handshake(TokioExecutor::new(), io),
+        )
+        .await
+        .map_err(|_| types::ErrorCode::ConnectionTimeout)?
+        .map_err(hyper_request_error)?;
+        let worker = drive_connection(connection);
+        // HTTP/2 carries the scheme and authority as pseudo-headers, so the URI stays absolute
+        let response = tokio::time::timeout(first_byte_timeout, sender.send_request(request)).await;
+        (response, worker)
+    } else {
+        let (mut sender, connection) =
+            tokio::time::timeout(connect_timeout, hyper::client::conn::http1::handshake(io))
+                .await
+                .map_err(|_| types::ErrorCode::ConnectionTimeout)?
+                .map_err(hyper_request_error)?;
+        let worker = drive_connection(connection);
+        // Only a proxy is sent the scheme and authority
+        let path = request
+            .uri()
+            .path_and_query()
+            .map_or("/", |path| path.as_str())
+            .to_string();
+        *request.uri_mut() = path
+            .parse()
+            .map_err(|_| types::ErrorCode::HttpRequestUriInvalid)?;
+        let response = tokio::time::timeout(first_byte_timeout, sender.send_request(request)).await;
+        (response, worker)
+    };
+    let response = response
+        .map_err(|_| types::ErrorCode::ConnectionReadTimeout)?
+        .map_err(hyper_request_error)?
+        .map(|body| body.map_err(hyper_response_error).boxed());
+    Ok(IncomingResponse {
+        resp: response,
+        worker: Some(worker),
+        between_bytes_timeout,
+    })
+}
+
+/// Run an HTTPS connection in the background for as long as its response is in use
+fn drive_connection(
+    connection: impl Future<Output = Result<(), hyper::Error>> + Send + 'static,
+) -> AbortOnDropJoinHandle<()> {
+    wasmtime_wasi::runtime::spawn(async move {
+        if let Err(error) = connection.await {
+            debug!(%error, "HTTPS connection failed");
+        }
+    })
+}
+
+/// TLS client trusting the same web PKI roots as the default request handler, offering
+/// HTTP/2 and HTTP/1.1 through ALPN
+fn tls_connector() -> tokio_rustls::TlsConnector {
+    static CONFIG: OnceLock<Arc<rustls::ClientConfig>> = OnceLock::new();
+    let config = CONFIG.get_or_init(|| {
+        let roots = rustls::RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.into(),
+        };
+        let mut config = rustls::ClientConfig::builder()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+        Arc::new(config)
+    });
+    tokio_rustls::TlsConnector::from(Arc::clone(config))
+}
+
 // Add helper methods specifically for WassetteWasiState<crate::wasistate::WasiState>
 impl WassetteWasiState<crate::wasistate::WasiState> {
+    /// Forget the egress and permission denials of the previous call, for stores that serve
+    /// more than one call
+    pub(crate) fn start_call(&mut self) {
+        self.egress = Arc::new(EgressMeter::new(self.egress.limit));
+        if let Ok(mut denial) = self.last_network_denial.lock() {
+            *denial = None;
+        }
+        if let Ok(mut error) = self.inner.last_permission_error.lock() {
+            *error = None;
+        }
+    }
+
     /// Get the last permission error if any occurred (checks both sources)
     pub fn get_last_permission_error(&self) -> Option<PermissionError> {
         // First check if there was a network denial recorded
@@ -156,8 +567,8 @@ impl<T: WasiHttpView> WasiHttpView for WassetteWasiState<T> {
 
     fn send_request(
         &mut self,
-        request: hyper::Request<wasmtime_wasi_http::body::HyperOutgoingBody>,
-        config: OutgoingRequestConfig,
+        mut request: hyper::Request<wasmtime_wasi_http::body::HyperOutgoingBody>,
+        mut config: OutgoingRequestConfig,
     ) -> HttpResult<HostFutureIncomingResponse> {
         let uri = request.uri();
 
@@ -186,7 +597,39 @@ impl<T: WasiHttpView> WasiHttpView for WassetteWasiState<T> {
 
         debug!(uri = %uri, "HTTP request allowed by network policy");
 
-        self.inner.send_request(request, config)
+        // Grants naming an IP address need no lookup; grants naming a host are checked
+        // against what the name resolves to when the request is sent
+        let host = uri.host().unwrap_or_default().to_string();
+        let names_address = host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+            .is_ok();
+        self.outbound.apply(&mut request, &mut config);
+        if names_address || self.outbound.allow_internal_addresses {
+            let response = self.inner.send_request(request, config)?;
+            return Ok(self.egress.meter_future(response));
+        }
+
+        let denial = (
+            std::sync::Arc::clone(&self.last_network_denial),
+            host,
+            request.uri().to_string(),
+        );
+        Ok(self
+            .egress
+            .meter_future(HostFutureIncomingResponse::pending(
+                wasmtime_wasi::runtime::spawn(async move {
+                    let response = send_to_checked_address(request, config).await;
+                    if let Err(types::ErrorCode::DestinationIpProhibited) = &response {
+                        let (last_denial, host, uri) = denial;
+                        if let Ok(mut last_denial) = last_denial.lock() {
+                            *last_denial = Some((host, uri));
+                        }
+                    }
+                    Ok(response)
+                }),
+            )))
     }
 }
 
@@ -313,6 +756,169 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_outbound_config_sets_user_agent_and_caps_timeouts() {
+        let outbound = OutboundHttpConfig {
+            user_agent: Some("wassette-test/1.0".to_string()),
+            request_timeout: Some(Duration::from_secs(5)),
+            ..Default::default()
+        };
+        let mut request = hyper::Request::builder()
+            .uri("http://api.example.com")
+            .header(hyper::header::USER_AGENT, "component/0.1")
+            .body(())
+            .unwrap();
+        let mut config = OutgoingRequestConfig {
+            use_tls: false,
+            connect_timeout: Duration::from_secs(600),
+            first_byte_timeout: Duration::from_secs(2),
+            between_bytes_timeout: Duration::from_secs(600),
+        };
+
+        outbound.apply(&mut request, &mut config);
+
+        assert_eq!(
+            request.headers()[hyper::header::USER_AGENT],
+            "wassette-test/1.0"
+        );
+        assert_eq!(config.connect_timeout, Duration::from_secs(5));
+        assert_eq!(config.first_byte_timeout, Duration::from_secs(2));
+        assert_eq!(config.between_bytes_timeout, Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_granted_host_resolving_to_internal_address_is_blocked() {
+        let addr = |s: &str| s.parse::<SocketAddr>().unwrap();
+
+        for internal in [
+            "127.0.0.1:80",
+            "10.1.2.3:80",
+            "192.168.0.10:80",
+            "169.254.169.254:80",
+            "100.64.0.1:80",
+            "[::1]:80",
+            "[fd00::1]:80",
+            "[fe80::1]:80",
+            "[::ffff:127.0.0.1]:80",
+        ] {
+            assert!(
+                matches!(
+                    pin_resolved_address("api.example.com", [addr(internal)]),
+                    Err(types::ErrorCode::DestinationIpProhibited)
+                ),
+                "{internal} should be blocked"
+            );
+        }
+
+        // A rebinding record next to a public one is refused too
+        assert!(matches!(
+            pin_resolved_address(
+                "api.example.com",
+                [addr("93.184.216.34:443"), addr("127.0.0.1:443")]
+            ),
+            Err(types::ErrorCode::DestinationIpProhibited)
+        ));
+
+        assert_eq!(
+            pin_resolved_address(
+                "api.example.com",
+                [addr("93.184.216.34:443"), addr("[2606:2800:220:1::1]:443")]
+            )
+            .ok(),
+            Some(addr("93.184.216.34:443"))
+        );
+        assert!(matches!(
+            pin_resolved_address("api.example.com", []),
+            Err(types::ErrorCode::DnsError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_https_connects_to_checked_address_with_granted_host_for_sni() {
+        use tokio::io::AsyncReadExt;
+
+        // Stands in for the address the granted name resolved to
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut client_hello = vec![0; 4096];
+            let read = socket.read(&mut client_hello).await.unwrap();
+            client_hello.truncate(read);
+            client_hello
+        });
+
+        let request = hyper::Request::builder()
+            .uri("https://api.example.com/v1/items")
+            .body(
+                http_body_util::Empty::<Bytes>::new()
+                    .map_err(|never| match never {})
+                    .boxed(),
+            )
+            .unwrap();
+        let config = OutgoingRequestConfig {
+            use_tls: true,
+            connect_timeout: Duration::from_secs(5),
+            first_byte_timeout: Duration::from_secs(5),
+            between_bytes_timeout: Duration::from_secs(5),
+        };
+        let response = send_to_address(request, config, addr).await;
+
+        // The listener hangs up without answering the handshake
+        assert!(matches!(response, Err(types::ErrorCode::TlsProtocolError)));
+        let client_hello = server.await.unwrap();
+        assert!(
+            client_hello
+                .windows(b"api.example.com".len())
+                .any(|window| window == b"api.example.com"),
+            "the ClientHello should carry the granted host for SNI"
+        );
+        assert!(
+            client_hello
+                .windows(b"\x02h2".len())
+                .any(|window| window == b"\x02h2"),
+            "the ClientHello should offer HTTP/2 through ALPN"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_https_handshake_is_bounded_by_connect_timeout() {
+        use tokio::io::AsyncReadExt;
+
+        // Accepts the connection but never answers the ClientHello
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0; 4096];
+            while socket.read(&mut buf).await.unwrap_or(0) > 0 {}
+        });
+
+        let request = hyper::Request::builder()
+            .uri("https://api.example.com/v1/items")
+            .body(
+                http_body_util::Empty::<Bytes>::new()
+                    .map_err(|never| match never {})
+                    .boxed(),
+            )
+            .unwrap();
+        let config = OutgoingRequestConfig {
+            use_tls: true,
+            connect_timeout: Duration::from_millis(200),
+            first_byte_timeout: Duration::from_secs(5),
+            between_bytes_timeout: Duration::from_secs(5),
+        };
+        let response = tokio::time::timeout(
+            Duration::from_secs(5),
+            send_to_address(request, config, addr),
+        )
+        .await
+        .expect("the stalled handshake should time out on its own");
+
+        assert!(matches!(response, Err(types::ErrorCode::ConnectionTimeout)));
+        server.abort();
+    }
+
     #[test]
     fn test_host_matching_is_case_insensitive() {
         let mut allowed_hosts = HashSet::new();
@@ -5,11 +5,14 @@
 
 #![warn(missing_docs)]
 
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::future::Future;
 use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
-use std::time::Instant;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, PoisonError};
+use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, bail, Context, Result};
 use component2json::{
@@ -21,45 +24,115 @@ use etcetera::BaseStrategy;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tokio::fs::DirEntry;
-use tokio::sync::{RwLock, Semaphore};
+use tokio::sync::{broadcast, Mutex as AsyncMutex, RwLock, Semaphore};
 use tracing::{debug, info, instrument, warn};
 use wasmtime::component::{Component, InstancePre};
 use wasmtime::Store;
 
+mod audit;
+mod bundle;
+mod call_context;
+mod component_logs;
 mod component_storage;
 mod config;
+mod declared_permissions;
+mod diff;
+mod events;
+mod explain;
+mod git;
+mod health;
 mod http;
+mod lifecycle_hooks;
 mod loader;
+mod memory_budget;
+mod oci_cache;
 pub mod oci_multi_layer;
 mod policy_internal;
+mod proxy;
+mod redact;
 mod runtime_context;
 pub mod schema;
 mod secrets;
+mod startup;
+mod stats;
+mod trap;
+mod verify;
 mod wasistate;
 
+pub use audit::PermissionAudit;
+pub use call_context::{with_call_context, with_call_deadline, DEADLINE_ENV};
+use component_logs::ComponentLogs;
+pub use component_logs::{LogLine, LogStream, DEFAULT_LOG_CAPACITY};
 use component_storage::ComponentStorage;
 pub use config::{LifecycleBuilder, LifecycleConfig};
-pub use http::WassetteWasiState;
+pub use declared_permissions::{DeclaredPermission, PERMISSIONS_SECTION};
+pub use diff::ComponentDiff;
+pub use events::{LifecycleEvent, LIFECYCLE_EVENT_CAPACITY};
+pub use explain::{PermissionExplanation, PermissionQuery};
+pub use health::{ComponentHealth, HealthStatus, HEALTH_CHECK_TIMEOUT, HEALTH_INTERFACE};
+pub use http::{OutboundHttpConfig, WassetteWasiState};
+pub use lifecycle_hooks::LIFECYCLE_INTERFACE;
+use lifecycle_hooks::{LifecycleHook, LiveInstance, Resident};
 use loader::{ComponentResource, DownloadedResource};
-use policy_internal::PolicyManager;
-pub use policy_internal::{PermissionGrantRequest, PermissionRule, PolicyInfo};
+pub use memory_budget::InsufficientMemoryBudget;
+pub use policy_internal::{PermissionGrantRequest, PermissionRule, PolicyInfo, PolicySource};
+use policy_internal::{PolicyManager, PolicySettings};
+pub use proxy::{ProxyConfig, HTTP_PROXY_ENV};
+use redact::Redactor;
 use runtime_context::RuntimeContext;
-pub use secrets::SecretsManager;
-use wasistate::WasiState;
+pub use runtime_context::{CompilationStrategy, PoolingAllocatorConfig};
+pub use secrets::{SecretsBundle, SecretsManager, SECRETS_BUNDLE_VERSION};
+pub use startup::{ComponentLoadStatus, StartupReport};
+pub use stats::ComponentStats;
+pub use trap::{ComponentTrap, MAX_TRAP_FRAMES};
+pub use verify::{ComponentVerification, UnsatisfiedImport};
 pub use wasistate::{
     create_wasi_state_template_from_policy, CustomResourceLimiter, PermissionError,
     WasiStateTemplate,
 };
+use wasistate::{PreopenedDir, WasiState};
 
 const DOWNLOADS_DIR: &str = "downloads";
+const SCRATCH_DIR: &str = "scratch";
+const LOGS_DIR: &str = "logs";
+const OCI_CACHE_DIR: &str = "oci-cache";
 const PRECOMPILED_EXT: &str = "cwasm";
 const METADATA_EXT: &str = "metadata.json";
 
+/// Guest path of the per-call scratch directory enabled with
+/// [`LifecycleBuilder::with_per_call_scratch`]
+pub const SCRATCH_GUEST_PATH: &str = "/scratch";
+
 // Default timeout configurations
 pub(crate) const DEFAULT_OCI_TIMEOUT_SECS: u64 = 30;
 pub(crate) const DEFAULT_HTTP_TIMEOUT_SECS: u64 = 30;
 pub(crate) const DEFAULT_DOWNLOAD_CONCURRENCY: usize = 8;
 
+/// Per-id locks, created on first use
+pub(crate) type KeyedLocks = std::sync::Mutex<HashMap<String, Arc<AsyncMutex<()>>>>;
+
+/// The lock for `key` in `locks`. Locks no caller holds any more are dropped from the map
+/// first, so it does not grow with every id ever seen.
+pub(crate) fn keyed_lock(locks: &KeyedLocks, key: &str) -> Arc<AsyncMutex<()>> {
+    let mut locks = locks.lock().unwrap_or_else(PoisonError::into_inner);
+    locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+    locks
+        .entry(key.to_string())
+        .or_insert_with(|| Arc::new(AsyncMutex::new(())))
+        .clone()
+}
+
+/// Drop the lock for `key` from `locks`, unless a caller still holds it
+pub(crate) fn forget_keyed_lock(locks: &KeyedLocks, key: &str) {
+    let mut locks = locks.lock().unwrap_or_else(PoisonError::into_inner);
+    if locks
+        .get(key)
+        .is_some_and(|lock| Arc::strong_count(lock) == 1)
+    {
+        locks.remove(key);
+    }
+}
+
 /// Get the default secrets directory path based on the OS
 pub(crate) fn get_default_secrets_dir() -> PathBuf {
     let dir_strategy = etcetera::choose_base_strategy();
@@ -107,6 +180,11 @@ pub struct ValidationStamp {
     pub content_hash: Option<String>,
 }
 
+/// Loaded components and their tools.
+///
+/// Every clone of a [`LifecycleManager`] shares this registry, and the `RwLock` is the only
+/// synchronization point for the loaded-component map and tool index. Writers update all
+/// three maps under one write guard so readers never see a component without its tools.
 #[derive(Clone, Default)]
 struct ComponentRegistry {
     state: Arc<RwLock<ComponentRegistryState>>,
@@ -136,6 +214,128 @@ pub enum LoadResult {
     Replaced,
     /// Indicates that the component did not exist and is now loaded
     New,
+    /// Indicates that the same component was loaded again from the same source
+    Unchanged,
+}
+
+/// The returned status when unloading a component
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum UnloadResult {
+    /// Indicates that the component was loaded or installed and has been removed
+    Unloaded,
+    /// Indicates that no component with the given id was loaded or installed
+    NotFound,
+}
+
+/// What startup loading does when a component in the component directory fails to load
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupLoadPolicy {
+    /// Log the failure and keep loading the remaining components
+    #[default]
+    Continue,
+    /// Stop at the first failure, skipping the components not yet loaded, and report it as
+    /// an error
+    FailFast,
+}
+
+impl std::str::FromStr for StartupLoadPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "continue" => Ok(Self::Continue),
+            "fail_fast" | "fail-fast" => Ok(Self::FailFast),
+            other => {
+                bail!("Unknown startup load policy '{other}', expected 'continue' or 'fail_fast'")
+            }
+        }
+    }
+}
+
+/// How a call naming a tool that several components export picks the component to run
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateToolPolicy {
+    /// Reject the call and list the components exporting the tool
+    #[default]
+    Error,
+    /// Run the tool of the component that was loaded first
+    FirstLoaded,
+    /// Run the tool of the component that was loaded most recently
+    LastLoaded,
+    /// Reject the call and require the tool to be named with its component, as
+    /// `<component>__<tool>`; shared tools are only listed under such names
+    RequireNamespace,
+}
+
+/// How the id of a component loaded from an OCI reference is derived from the reference's
+/// repository when no id is given
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OciComponentIds {
+    /// Replace each `/` with `_`: `acme/tools/fetch` becomes `acme_tools_fetch`. Different
+    /// repositories can get the same id, such as `acme/tool` and `acme_tool`.
+    #[default]
+    Flatten,
+    /// Join the path segments with `..`, which repository names cannot contain:
+    /// `acme/tools/fetch` becomes `acme..tools..fetch`, so every repository gets its own id
+    FullPath,
+}
+
+impl OciComponentIds {
+    /// Separator between repository path segments with [`OciComponentIds::FullPath`]. OCI
+    /// only allows single dots between alphanumerics, so it never occurs inside a segment.
+    pub const FULL_PATH_SEPARATOR: &'static str = "..";
+
+    /// Component id for an OCI `repository` such as `acme/tools/fetch`
+    pub fn component_id(self, repository: &str) -> String {
+        match self {
+            Self::Flatten => repository.replace('/', "_"),
+            Self::FullPath => repository.replace('/', Self::FULL_PATH_SEPARATOR),
+        }
+    }
+}
+
+impl std::str::FromStr for OciComponentIds {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.replace('-', "_").as_str() {
+            "flatten" => Ok(Self::Flatten),
+            "full_path" => Ok(Self::FullPath),
+            _ => {
+                bail!("Unknown OCI component id strategy '{s}', expected 'flatten' or 'full_path'")
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for DuplicateToolPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.replace('-', "_").as_str() {
+            "error" => Ok(Self::Error),
+            "first_loaded" => Ok(Self::FirstLoaded),
+            "last_loaded" => Ok(Self::LastLoaded),
+            "require_namespace" => Ok(Self::RequireNamespace),
+            _ => bail!(
+                "Unknown duplicate tool policy '{s}', expected 'error', 'first_loaded', 'last_loaded' or 'require_namespace'"
+            ),
+        }
+    }
+}
+
+/// Options for [`LifecycleManager::load_component_with`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadOptions {
+    /// Install the component under this id instead of one derived from its file name
+    pub component_id: Option<String>,
+    /// Replace a component with the same id that was installed from a different source
+    pub force: bool,
+    /// Ignore the precompiled cache: compile the component from its `.wasm` and rewrite it
+    pub force_recompile: bool,
 }
 
 /// Detailed outcome for a component load operation.
@@ -147,6 +347,41 @@ pub struct ComponentLoadOutcome {
     pub status: LoadResult,
     /// Normalized tool names exposed by the component after registration.
     pub tool_names: Vec<String>,
+    /// Permissions the component declares it needs, so callers know what to grant.
+    pub declared_permissions: Vec<DeclaredPermission>,
+    /// Declared permissions added to the component's policy because auto-granting is enabled.
+    pub auto_granted: Vec<DeclaredPermission>,
+    /// Tools that were not exposed before this load.
+    pub new_tools: Vec<String>,
+    /// The policy attached to the component after loading, if any.
+    pub policy: Option<PolicyInfo>,
+}
+
+impl ComponentLoadOutcome {
+    /// Number of tools the component exposes.
+    pub fn tool_count(&self) -> usize {
+        self.tool_names.len()
+    }
+
+    /// Warning for a component that loaded but exposes no tools, which usually means it does
+    /// not export the interface its author intended.
+    pub fn warning(&self) -> Option<String> {
+        self.tool_names.is_empty().then(|| {
+            format!(
+                "Component '{}' loaded but exports no functions that can be called as tools",
+                self.component_id
+            )
+        })
+    }
+}
+
+/// Components picked up or dropped by [`LifecycleManager::rescan_components`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RescanOutcome {
+    /// Components whose files appeared in the component directory and were loaded
+    pub loaded: Vec<String>,
+    /// Components whose files disappeared and were removed from the registry
+    pub removed: Vec<String>,
 }
 
 impl ComponentRegistry {
@@ -154,6 +389,20 @@ impl ComponentRegistry {
         Self::default()
     }
 
+    /// Ids of components that are loaded or have tools registered
+    async fn registered_ids(&self) -> Vec<String> {
+        let state = self.state.read().await;
+        let mut ids: Vec<String> = state
+            .components
+            .keys()
+            .chain(state.component_map.keys())
+            .cloned()
+            .collect();
+        ids.sort();
+        ids.dedup();
+        ids
+    }
+
     async fn upsert_component(
         &self,
         component_id: String,
@@ -174,6 +423,17 @@ impl ComponentRegistry {
         state.components.get(component_id).cloned()
     }
 
+    /// Names of the tools currently registered for `component_id`
+    async fn component_tool_names(&self, component_id: &str) -> Vec<String> {
+        self.state
+            .read()
+            .await
+            .component_map
+            .get(component_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
     async fn contains_component(&self, component_id: &str) -> bool {
         self.state
             .read()
@@ -197,6 +457,12 @@ impl ComponentRegistry {
             .and_then(|infos| infos.first().map(|info| info.identifier.clone()))
     }
 
+    /// Components exposing `tool_name`, looked up in the tool index without cloning schemas
+    async fn component_ids_for_tool(&self, tool_name: &str) -> Option<Vec<String>> {
+        let state = self.state.read().await;
+        state.component_ids_for_tool(tool_name)
+    }
+
     async fn tool_infos(&self, tool_name: &str) -> Option<Vec<ToolInfo>> {
         let state = self.state.read().await;
         state.tool_map.get(tool_name).cloned()
@@ -211,6 +477,11 @@ impl ComponentRegistry {
             .collect()
     }
 
+    async fn rename_component(&self, from: &str, to: &str) {
+        let mut state = self.state.write().await;
+        state.rename_component(from, to);
+    }
+
     async fn register_metadata_if_absent(
         &self,
         component_id: &str,
@@ -248,6 +519,32 @@ impl ComponentRegistryState {
         })
     }
 
+    fn component_ids_for_tool(&self, tool_name: &str) -> Option<Vec<String>> {
+        self.tool_map
+            .get(tool_name)
+            .map(|infos| infos.iter().map(|info| info.component_id.clone()).collect())
+    }
+
+    /// Drop the instances of components last used before `cutoff`, keeping their tools
+    /// registered so the next call loads them again. Returns the dropped instances, whose
+    /// resident instances still have to be shut down.
+    fn evict_idle(
+        &mut self,
+        last_used: &std::sync::Mutex<HashMap<String, tokio::time::Instant>>,
+        cutoff: tokio::time::Instant,
+    ) -> Vec<(String, ComponentInstance)> {
+        let last_used = last_used.lock().unwrap_or_else(PoisonError::into_inner);
+        let idle: Vec<String> = self
+            .components
+            .keys()
+            .filter(|id| last_used.get(*id).is_none_or(|used| *used < cutoff))
+            .cloned()
+            .collect();
+        idle.into_iter()
+            .filter_map(|id| self.components.remove(&id).map(|instance| (id, instance)))
+            .collect()
+    }
+
     fn unregister_component(&mut self, component_id: &str) -> Option<ComponentInstance> {
         self.unregister_tools(component_id);
         self.components.remove(component_id)
@@ -266,6 +563,23 @@ impl ComponentRegistryState {
         }
     }
 
+    /// Move the instance and tools of `from` to `to`, keeping the tools' order in the index
+    fn rename_component(&mut self, from: &str, to: &str) {
+        if let Some(instance) = self.components.remove(from) {
+            self.components.insert(to.to_string(), instance);
+        }
+        if let Some(tools) = self.component_map.remove(from) {
+            for tool_name in &tools {
+                for info in self.tool_map.get_mut(tool_name).into_iter().flatten() {
+                    if info.component_id == from {
+                        info.component_id = to.to_string();
+                    }
+                }
+            }
+            self.component_map.insert(to.to_string(), tools);
+        }
+    }
+
     fn register_tools_only(&mut self, component_id: &str, tools: Vec<ToolMetadata>) {
         let mut tool_names = Vec::new();
 
@@ -302,8 +616,41 @@ pub struct LifecycleManager {
     storage: ComponentStorage,
     policy_manager: PolicyManager,
     oci_client: Arc<oci_wasm::WasmClient>,
+    oci_cache: oci_cache::OciCache,
     http_client: reqwest::Client,
     secrets_manager: Arc<SecretsManager>,
+    load_timeout: Option<Duration>,
+    per_call_scratch: bool,
+    max_components: Option<usize>,
+    /// Budget for the declared memory limits of all loaded components
+    max_loaded_memory: Option<u64>,
+    outbound_http: OutboundHttpConfig,
+    auto_grant_declared: bool,
+    logs: ComponentLogs,
+    events: broadcast::Sender<LifecycleEvent>,
+    /// Per-component locks serializing concurrent loads of the same id across clones
+    load_locks: Arc<KeyedLocks>,
+    /// Serializes admission checks, so that loads of different components cannot all pass
+    /// the component limit and memory budget before any of them is registered
+    admission_lock: Arc<AsyncMutex<()>>,
+    /// Components admitted that are still being loaded, with the memory limit each reserved
+    pending_admissions: Arc<std::sync::Mutex<HashMap<String, u64>>>,
+    /// When each component was last loaded or called, consulted by the idle unloader
+    last_used: Arc<std::sync::Mutex<HashMap<String, tokio::time::Instant>>>,
+    /// Stops the idle unloader once the last clone of the manager outside it is dropped
+    idle_unloader: Option<Arc<tokio_util::sync::DropGuard>>,
+    /// Calls and network usage of each component since it was loaded
+    stats: Arc<std::sync::Mutex<HashMap<String, ComponentStats>>>,
+    /// Outcome of loading the installed components in the background
+    startup_report: Arc<std::sync::Mutex<StartupReport>>,
+    egress_byte_limit: Option<u64>,
+    duplicate_tool_policy: DuplicateToolPolicy,
+    /// Whether component stderr lines are also emitted as tracing events
+    stderr_tracing: bool,
+    /// Whether secret values are scrubbed from the errors of component calls
+    redact_secrets: bool,
+    /// How ids are derived for components loaded from OCI references
+    oci_component_ids: OciComponentIds,
 }
 
 /// A representation of a loaded component instance. It contains both the base component info and a
@@ -313,6 +660,8 @@ pub struct ComponentInstance {
     component: Arc<Component>,
     instance_pre: Arc<InstancePre<WassetteWasiState<WasiState>>>,
     package_docs: Option<Value>,
+    /// The instance every call runs in, for components exporting the lifecycle interface
+    resident: Option<Arc<Resident>>,
 }
 
 impl LifecycleManager {
@@ -340,13 +689,52 @@ impl LifecycleManager {
     /// Construct a lifecycle manager from an explicit configuration without loading components.
     #[instrument(skip_all, fields(component_dir = %config.component_dir().display()))]
     pub async fn from_config(config: LifecycleConfig) -> Result<Self> {
-        let (component_dir, secrets_dir, environment_vars, http_client, oci_client, _) =
-            config.into_parts();
+        let LifecycleConfig {
+            component_dir,
+            secrets_dir,
+            environment_vars,
+            http_client,
+            oci_client,
+            eager_load: _,
+            load_timeout,
+            strict_policy,
+            per_call_scratch,
+            max_components,
+            max_loaded_memory,
+            outbound_http,
+            compilation_strategy,
+            auto_grant_declared,
+            idle_unload_ttl,
+            default_policy,
+            pooling_allocator,
+            safe_mode,
+            create_component_dir,
+            max_total_log_bytes,
+            egress_byte_limit,
+            duplicate_tool_policy,
+            stderr_tracing,
+            redact_secrets,
+            strict_security,
+            oci_component_ids,
+        } = config;
+
+        if !create_component_dir && !tokio::fs::try_exists(&component_dir).await? {
+            bail!(
+                "Component directory {} does not exist. Create it, fix the configured path, or allow wassette to create it",
+                component_dir.display()
+            );
+        }
 
         let storage =
             ComponentStorage::new(component_dir.clone(), DEFAULT_DOWNLOAD_CONCURRENCY).await?;
+        storage.check_permissions(strict_security).await?;
+        let logs = ComponentLogs::new(component_dir.join(LOGS_DIR), DEFAULT_LOG_CAPACITY)
+            .with_max_total_bytes(max_total_log_bytes);
 
-        let runtime = Arc::new(RuntimeContext::initialize()?);
+        let runtime = Arc::new(RuntimeContext::initialize(
+            compilation_strategy,
+            pooling_allocator.as_ref(),
+        )?);
 
         let secrets_manager = Arc::new(SecretsManager::new(secrets_dir.clone()));
         secrets_manager.ensure_secrets_dir().await?;
@@ -360,28 +748,131 @@ impl LifecycleManager {
             Arc::clone(&environment_vars),
             Arc::clone(&oci_client),
             http_client.clone(),
+            PolicySettings {
+                strict_policy,
+                default_policy: default_policy.map(Arc::new),
+                safe_mode,
+            },
         );
 
-        Ok(Self {
+        let oci_cache = oci_cache::OciCache::new(storage.downloads_dir().join(OCI_CACHE_DIR));
+
+        let mut manager = Self {
             runtime,
             registry: ComponentRegistry::new(),
             storage,
             policy_manager,
             oci_client,
+            oci_cache,
             http_client,
             secrets_manager,
-        })
+            load_timeout,
+            per_call_scratch,
+            max_components,
+            max_loaded_memory,
+            outbound_http,
+            auto_grant_declared,
+            logs,
+            events: broadcast::channel(LIFECYCLE_EVENT_CAPACITY).0,
+            load_locks: Arc::default(),
+            admission_lock: Arc::default(),
+            pending_admissions: Arc::default(),
+            last_used: Arc::default(),
+            idle_unloader: None,
+            stats: Arc::default(),
+            startup_report: Arc::default(),
+            egress_byte_limit,
+            duplicate_tool_policy,
+            stderr_tracing,
+            redact_secrets,
+            oci_component_ids,
+        };
+        if let Some(ttl) = idle_unload_ttl {
+            manager.idle_unloader = Some(Arc::new(manager.spawn_idle_unloader(ttl)));
+        }
+        Ok(manager)
+    }
+
+    /// Periodically unload components that have not been called within `ttl`, running the
+    /// shutdown hook of their resident instances. The task runs until the returned guard is
+    /// dropped, which the manager does once its last clone outside the task is gone.
+    fn spawn_idle_unloader(&self, ttl: Duration) -> tokio_util::sync::DropGuard {
+        let stopped = tokio_util::sync::CancellationToken::new();
+        let manager = self.clone();
+        let stop = stopped.clone();
+        tokio::spawn(async move {
+            let mut sweeps = tokio::time::interval((ttl / 2).max(Duration::from_millis(1)));
+            sweeps.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                tokio::select! {
+                    _ = stop.cancelled() => break,
+                    _ = sweeps.tick() => {}
+                }
+                let Some(cutoff) = tokio::time::Instant::now().checked_sub(ttl) else {
+                    continue;
+                };
+                // Holding the registry lock while reading the timestamps means a call that
+                // marked its component as used either keeps it loaded or reloads it afterwards
+                let unloaded = manager
+                    .registry
+                    .state
+                    .write()
+                    .await
+                    .evict_idle(&manager.last_used, cutoff);
+                for (id, component) in unloaded {
+                    manager.stop_resident(&id, &component).await;
+                    info!(component_id = %id, ttl_ms = %ttl.as_millis(), "Unloaded idle component");
+                }
+            }
+        });
+        stopped.drop_guard()
+    }
+
+    /// Record that `component_id` was just loaded or called
+    fn mark_used(&self, component_id: &str) {
+        self.last_used
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(component_id.to_string(), tokio::time::Instant::now());
+    }
+
+    /// Subscribe to [`LifecycleEvent`]s for components loaded, reloaded, or unloaded after
+    /// this call. Receivers that fall more than [`LIFECYCLE_EVENT_CAPACITY`] events behind
+    /// skip the oldest events and observe [`broadcast::error::RecvError::Lagged`].
+    pub fn subscribe(&self) -> broadcast::Receiver<LifecycleEvent> {
+        self.events.subscribe()
+    }
+
+    /// Lock serializing loads and unloads of one component. A request that arrives while
+    /// another is in flight waits for it instead of racing on the component's files.
+    fn load_lock(&self, component_id: &str) -> Arc<AsyncMutex<()>> {
+        keyed_lock(&self.load_locks, component_id)
+    }
+
+    fn emit_event(&self, event: LifecycleEvent) {
+        // Sending only fails when nobody is subscribed, which is not an error
+        let _ = self.events.send(event);
     }
 
     /// Load every component present in the component directory, updating the registry and cache.
+    /// Past the component limit, the components whose ids sort last are skipped.
     #[instrument(skip(self))]
     pub async fn load_all_components(&self) -> Result<()> {
-        let loaded_components =
-            load_components_parallel(self.storage.root(), Arc::clone(&self.runtime)).await?;
+        let mut loaded_components = load_components_parallel(
+            self.storage.root(),
+            Arc::clone(&self.runtime),
+            self.load_timeout,
+        )
+        .await?;
+        loaded_components.sort_by(|(_, a), (_, b)| a.cmp(b));
 
         let mut registered_ids = Vec::new();
 
         for (component_instance, name) in loaded_components {
+            if let Err(error) = self.check_component_limit(&name).await {
+                warn!(%name, %error, "Skipping component past the component limit");
+                continue;
+            }
             let tool_metadata = if let Some(ref package_docs) = component_instance.package_docs {
                 component_exports_to_tools_with_docs(
                     &component_instance.component,
@@ -396,6 +887,7 @@ impl LifecycleManager {
                     true,
                 )
             };
+            let tool_metadata = lifecycle_hooks::without_lifecycle_tools(tool_metadata);
 
             if let Err(error) = self
                 .registry
@@ -405,14 +897,32 @@ impl LifecycleManager {
                 warn!(%name, %error, "Failed to register component in registry");
                 continue;
             }
+            self.mark_used(&name);
 
             registered_ids.push(name);
         }
 
         for component_id in registered_ids {
             if let Err(error) = self.restore_policy_attachment(&component_id).await {
+                if self.policy_manager.is_strict() {
+                    warn!(%component_id, %error, "Skipping component with an invalid policy");
+                    self.registry.remove_component(&component_id).await;
+                    continue;
+                }
                 warn!(%component_id, %error, "Failed to restore policy attachment");
             }
+            if let Some(instance) = self.registry.get_component(&component_id).await {
+                if let Err(error) = self.start_resident(&component_id, &instance).await {
+                    warn!(%component_id, %error, "Skipping component whose init hook failed");
+                    self.registry.remove_component(&component_id).await;
+                    continue;
+                }
+            }
+            let source = self.storage.component_path(&component_id);
+            self.emit_event(LifecycleEvent::Loaded {
+                source: format!("file://{}", source.display()),
+                id: component_id,
+            });
         }
 
         info!("LifecycleManager finished loading components");
@@ -427,6 +937,19 @@ impl LifecycleManager {
         // Show progress when running in CLI mode (stderr is a TTY)
         let show_progress = std::io::stderr().is_terminal();
 
+        if let Some(reference) = uri.trim().strip_prefix("oci://") {
+            let repository = reference
+                .parse::<oci_client::Reference>()
+                .context("Failed to parse OCI reference")?
+                .repository()
+                .to_string();
+            let resource = self
+                .oci_cache
+                .pull(reference, &self.oci_client, show_progress)
+                .await?;
+            return Ok((self.oci_component_ids.component_id(&repository), resource));
+        }
+
         let resource = loader::load_resource_with_progress::<ComponentResource>(
             uri,
             &self.oci_client,
@@ -438,6 +961,38 @@ impl LifecycleManager {
         Ok((id, resource))
     }
 
+    /// Refuse to replace a component that was installed from a different source
+    async fn check_component_source(&self, component_id: &str, uri: &str) -> Result<()> {
+        let Some(existing) = self.storage.read_component_source(component_id).await? else {
+            return Ok(());
+        };
+        if source_identity(&existing) != source_identity(uri) {
+            bail!(
+                "Component '{component_id}' is already loaded from {existing}. Use --force to replace it or choose a different id with --id"
+            );
+        }
+        Ok(())
+    }
+
+    /// Hash of the artifact installed for a loaded component, if `uri` is the source it was
+    /// installed from, so that loading the same bytes again can be reported as unchanged.
+    async fn installed_artifact_hash(&self, component_id: &str, uri: &str) -> Option<String> {
+        if !self.registry.contains_component(component_id).await {
+            return None;
+        }
+        let source = self
+            .storage
+            .read_component_source(component_id)
+            .await
+            .ok()??;
+        if source_identity(&source) != source_identity(uri) {
+            return None;
+        }
+        component_storage::compute_file_hash(&self.component_path(component_id))
+            .await
+            .ok()
+    }
+
     async fn stage_component_artifact(
         &self,
         component_id: &str,
@@ -458,10 +1013,45 @@ impl LifecycleManager {
         &self,
         component_id: &str,
         wasm_path: &Path,
+        skip_precompiled: bool,
     ) -> Result<ComponentLoadOutcome> {
         let (component, wasm_bytes) = self
-            .load_component_optimized(wasm_path, component_id)
+            .load_component_optimized(wasm_path, component_id, skip_precompiled)
             .await?;
+        let declared_permissions = declared_permissions::declared_permissions(&wasm_bytes)
+            .unwrap_or_else(|error| {
+                warn!(%component_id, error = %format!("{error:#}"), "Ignoring declared permissions");
+                Vec::new()
+            });
+        // Declared permissions are granted once. Ones granted at an earlier load are not
+        // granted again, so that a permission the operator revoked stays revoked.
+        let to_grant: Vec<DeclaredPermission> = if self.auto_grant_declared && !self.is_safe_mode()
+        {
+            let already_granted = self
+                .storage
+                .read_auto_granted(component_id)
+                .await
+                .unwrap_or_else(|error| {
+                    warn!(%component_id, error = %format!("{error:#}"), "Ignoring auto-grant record");
+                    Vec::new()
+                });
+            declared_permissions
+                .iter()
+                .filter(|permission| !already_granted.contains(permission))
+                .cloned()
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        // Admitted before its metadata, policy or grants are written, so that a refused
+        // component leaves none of them behind. Held until the component is registered, so
+        // that it counts against the limit and budget meanwhile.
+        let memory_limit = self
+            .policy_manager
+            .prospective_memory_limit(component_id, &to_grant)
+            .await;
+        let _admission = self.admit_component(component_id, memory_limit).await?;
 
         let instance_pre = self
             .runtime
@@ -472,6 +1062,7 @@ impl LifecycleManager {
         let package_docs = extract_package_docs(&wasm_bytes);
 
         let component_instance = ComponentInstance {
+            resident: Resident::for_component(&component, self.runtime.as_ref()),
             component: Arc::new(component),
             instance_pre: Arc::new(instance_pre),
             package_docs: package_docs.clone(),
@@ -488,37 +1079,138 @@ impl LifecycleManager {
         } else {
             component_exports_to_tools(&component_instance.component, self.runtime.as_ref(), true)
         };
+        let tool_metadata = lifecycle_hooks::without_lifecycle_tools(tool_metadata);
 
         let tool_names: Vec<String> = tool_metadata
             .iter()
             .map(|tool| tool.normalized_name.clone())
             .collect();
 
-        if let Ok(validation_stamp) = self.storage.create_validation_stamp(wasm_path, false).await {
-            if let Err(e) = self
-                .save_component_metadata(component_id, &tool_metadata, validation_stamp)
-                .await
+        // A load failing from here on puts the files it wrote back as they were, so that a
+        // failed reload leaves the installed component untouched
+        let snapshot = FileSnapshot::take([
+            self.storage.metadata_path(component_id),
+            self.storage.policy_path(component_id),
+            self.storage.policy_metadata_path(component_id),
+            self.storage.auto_granted_path(component_id),
+        ])
+        .await;
+        let prepared = async {
+            if let Ok(validation_stamp) =
+                self.storage.create_validation_stamp(wasm_path, false).await
             {
-                warn!(%component_id, error = %e, "Failed to save component metadata");
+                if let Err(e) = self
+                    .save_component_metadata(component_id, &tool_metadata, validation_stamp)
+                    .await
+                {
+                    warn!(%component_id, error = %e, "Failed to save component metadata");
+                }
+            }
+
+            // In strict mode an invalid co-located policy fails the load before the component
+            // becomes callable.
+            if let Err(error) = self.policy_manager.restore_from_disk(component_id).await {
+                if self.policy_manager.is_strict() {
+                    return Err(error);
+                }
+                warn!(%component_id, %error, "Failed to restore policy attachment");
             }
+
+            let auto_granted = self
+                .grant_declared_permissions(component_id, &to_grant)
+                .await;
+
+            self.start_resident(component_id, &component_instance)
+                .await?;
+            Ok::<_, anyhow::Error>(auto_granted)
         }
+        .await;
+        let auto_granted = match prepared {
+            Ok(auto_granted) => auto_granted,
+            Err(error) => {
+                if let Err(restore_error) = snapshot.restore().await {
+                    warn!(%component_id, error = %format!("{restore_error:#}"), "Failed to roll back a failed load");
+                }
+                self.policy_manager.cleanup(component_id).await;
+                if let Err(policy_error) = self.policy_manager.restore_from_disk(component_id).await
+                {
+                    warn!(%component_id, error = %policy_error, "Failed to restore policy attachment");
+                }
+                return Err(error);
+            }
+        };
 
+        let previous = self.registry.get_component(component_id).await;
+        let previous_tools = self.registry.component_tool_names(component_id).await;
+        let new_tools = tool_names
+            .iter()
+            .filter(|name| !previous_tools.contains(name))
+            .cloned()
+            .collect();
         let load_result = self
             .registry
             .upsert_component(component_id.to_string(), component_instance, tool_metadata)
             .await?;
-
-        if let Err(error) = self.policy_manager.restore_from_disk(component_id).await {
-            warn!(%component_id, %error, "Failed to restore policy attachment");
+        self.mark_used(component_id);
+        if let Some(previous) = previous {
+            self.stop_resident(component_id, &previous).await;
         }
 
         Ok(ComponentLoadOutcome {
             component_id: component_id.to_string(),
             status: load_result,
             tool_names,
+            declared_permissions,
+            auto_granted,
+            new_tools,
+            policy: self.policy_manager.get_policy_info(component_id).await,
         })
     }
 
+    /// Add each declared permission to the component's policy, returning those granted, and
+    /// record them as granted. A permission that cannot be granted is logged and skipped
+    /// rather than failing the load.
+    async fn grant_declared_permissions(
+        &self,
+        component_id: &str,
+        declared: &[DeclaredPermission],
+    ) -> Vec<DeclaredPermission> {
+        if declared.is_empty() {
+            return Vec::new();
+        }
+        let mut granted = Vec::new();
+        for permission in declared {
+            match self
+                .policy_manager
+                .grant_permission(
+                    component_id,
+                    &permission.permission_type,
+                    &permission.details,
+                )
+                .await
+            {
+                Ok(()) => granted.push(permission.clone()),
+                Err(error) => warn!(
+                    %component_id,
+                    permission_type = %permission.permission_type,
+                    %error,
+                    "Failed to grant declared permission"
+                ),
+            }
+        }
+
+        let mut record = self
+            .storage
+            .read_auto_granted(component_id)
+            .await
+            .unwrap_or_default();
+        record.extend(granted.iter().cloned());
+        if let Err(error) = self.storage.write_auto_granted(component_id, &record).await {
+            warn!(%component_id, error = %format!("{error:#}"), "Failed to record auto-granted permissions");
+        }
+        granted
+    }
+
     /// Loads a new component from the given URI. This URI can be a file path, an OCI reference, or a URL.
     ///
     /// If a component with the given id already exists, it will be updated with the new component.
@@ -526,20 +1218,69 @@ impl LifecycleManager {
     /// component and whether it replaced an existing instance.
     #[instrument(skip(self))]
     pub async fn load_component(&self, uri: &str) -> Result<ComponentLoadOutcome> {
+        self.load_component_with(uri, LoadOptions::default()).await
+    }
+
+    /// Loads a component like [`Self::load_component`], with the given [`LoadOptions`].
+    ///
+    /// Without an explicit id the id is derived from the file name. Loading fails when the id
+    /// already belongs to a component installed from a different source, unless `force` is set.
+    #[instrument(skip(self))]
+    pub async fn load_component_with(
+        &self,
+        uri: &str,
+        options: LoadOptions,
+    ) -> Result<ComponentLoadOutcome> {
         debug!(uri, "Loading component");
-        let (component_id, resource) = self.resolve_component_resource(uri).await?;
+        let LoadOptions {
+            component_id,
+            force,
+            force_recompile,
+        } = options;
+        if let Some(id) = &component_id {
+            validate_component_id(id)?;
+        }
+        let (derived_id, resource) = self.resolve_component_resource(uri).await?;
+        let component_id = component_id.unwrap_or(derived_id);
+        loader::ensure_component_file(resource.as_ref()).await?;
+        let load_lock = self.load_lock(&component_id);
+        let _load_guard = load_lock.lock().await;
+        self.check_component_limit(&component_id).await?;
+        if !force {
+            self.check_component_source(&component_id, uri).await?;
+        }
+        let previous_hash = self.installed_artifact_hash(&component_id, uri).await;
+        let bundled_policy = resource.has_colocated_policy();
         let staged_path = self
             .stage_component_artifact(&component_id, resource)
             .await?;
-        let outcome = self
-            .compile_and_register_component(&component_id, &staged_path)
+        self.storage
+            .write_component_source(&component_id, uri)
+            .await?;
+        if bundled_policy {
+            self.policy_manager
+                .record_policy_source(&component_id, uri)
+                .await?;
+        }
+        let mut outcome = self
+            .compile_and_register_component(&component_id, &staged_path, force_recompile)
             .await
-            .with_context(|| {
-                format!(
-                    "Failed to compile component from path: {}. Please ensure the file is a valid WebAssembly component.",
-                    staged_path.display()
-                )
+            .map_err(|error| {
+                with_load_context(error, || {
+                    format!(
+                        "Failed to compile component from path: {}. Please ensure the file is a valid WebAssembly component.",
+                        staged_path.display()
+                    )
+                })
             })?;
+        if outcome.status == LoadResult::Replaced && previous_hash.is_some() {
+            let staged_hash = component_storage::compute_file_hash(&staged_path)
+                .await
+                .ok();
+            if staged_hash == previous_hash {
+                outcome.status = LoadResult::Unchanged;
+            }
+        }
 
         info!(
             component_id = %outcome.component_id,
@@ -547,14 +1288,40 @@ impl LifecycleManager {
             tools = ?outcome.tool_names,
             "Successfully loaded component"
         );
+        if let Some(warning) = outcome.warning() {
+            warn!(component_id = %outcome.component_id, "{warning}");
+        }
+        self.emit_event(match outcome.status {
+            LoadResult::New => LifecycleEvent::Loaded {
+                id: outcome.component_id.clone(),
+                source: uri.to_string(),
+            },
+            LoadResult::Replaced | LoadResult::Unchanged => LifecycleEvent::Reloaded {
+                id: outcome.component_id.clone(),
+            },
+        });
         Ok(outcome)
     }
 
     /// Unloads the component with the specified id. This removes the component from the runtime
     /// and removes all associated files from disk, making it the reverse operation of load_component.
     /// This function fails if any files cannot be removed (except when they don't exist).
+    /// Unloading a component that is neither loaded nor installed is not an error and
+    /// returns [`UnloadResult::NotFound`], so repeated unloads are safe.
     #[instrument(skip(self))]
-    pub async fn unload_component(&self, id: &str) -> Result<()> {
+    pub async fn unload_component(&self, id: &str) -> Result<UnloadResult> {
+        let load_lock = self.load_lock(id);
+        let _load_guard = load_lock.lock().await;
+
+        if !self.registry.contains_component(id).await && !self.component_path(id).exists() {
+            debug!(component_id = %id, "Component not found, nothing to unload");
+            return Ok(UnloadResult::NotFound);
+        }
+
+        if let Some(instance) = self.registry.get_component(id).await {
+            self.stop_resident(id, &instance).await;
+        }
+
         debug!("Unloading component and removing files from disk");
 
         // Remove files first, then clean up memory on success
@@ -570,48 +1337,337 @@ impl LifecycleManager {
             .remove_if_exists(&metadata_path, "policy metadata file", id)
             .await?;
 
+        let env_path = self.storage.component_env_path(id);
+        self.storage
+            .remove_if_exists(&env_path, "component environment file", id)
+            .await?;
+
+        let auto_granted_path = self.storage.auto_granted_path(id);
+        self.storage
+            .remove_if_exists(&auto_granted_path, "auto-granted permissions file", id)
+            .await?;
+
+        self.logs.clear(id).await?;
+        self.stats
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(id);
+
         // Only cleanup memory after all files are successfully removed
         self.registry.remove_component(id).await;
+        self.last_used
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(id);
         self.policy_manager.cleanup(id).await;
 
         info!(component_id = %id, "Component unloaded successfully");
-        Ok(())
+        self.emit_event(LifecycleEvent::Unloaded { id: id.to_string() });
+        Ok(UnloadResult::Unloaded)
     }
 
-    /// Returns the component ID for a given tool name.
-    /// If there are multiple components with the same tool name, returns an error.
+    /// Give an installed component a new id without reloading it. Its files, compiled
+    /// code, policy and granted permissions, environment, secrets, and logs move to the new
+    /// id. Fails if a component named `new_id` is already loaded or installed, and moves
+    /// everything back to `id` if any of it cannot be moved.
     #[instrument(skip(self))]
-    pub async fn get_component_id_for_tool(&self, tool_name: &str) -> Result<String> {
-        let tool_infos = self
-            .registry
-            .tool_infos(tool_name)
-            .await
-            .context("Tool not found")?;
+    pub async fn rename_component(&self, id: &str, new_id: &str) -> Result<()> {
+        validate_component_id(new_id)?;
+        if id == new_id {
+            bail!("Component '{id}' already has that id");
+        }
 
-        if tool_infos.len() > 1 {
-            bail!(
-                "Multiple components found for tool '{}': {}",
-                tool_name,
-                tool_infos
-                    .iter()
-                    .map(|info| info.component_id.as_str())
-                    .collect::<Vec<_>>()
-                    .join(", ")
-            );
+        // Lock in a fixed order so two renames in opposite directions cannot deadlock
+        let (first, second) = if id < new_id {
+            (id, new_id)
+        } else {
+            (new_id, id)
+        };
+        let first_lock = self.load_lock(first);
+        let _first_guard = first_lock.lock().await;
+        let second_lock = self.load_lock(second);
+        let _second_guard = second_lock.lock().await;
+
+        if !self.component_path(id).exists() {
+            bail!("Component not found: {id}");
+        }
+        if self.registry.contains_component(new_id).await || self.component_path(new_id).exists() {
+            bail!("Component '{new_id}' already exists");
+        }
+
+        // Each step that fails moves what the earlier ones moved back under the old id
+        self.storage.rename_component_files(id, new_id).await?;
+        let mut moved_secrets = false;
+        let mut moved_logs = false;
+        let outcome = async {
+            self.secrets_manager
+                .rename_component_secrets(id, new_id)
+                .await?;
+            moved_secrets = true;
+            self.logs
+                .rename(id, new_id)
+                .await
+                .context("Failed to move component logs")?;
+            moved_logs = true;
+            self.restore_policy_attachment(new_id).await
+        }
+        .await;
+        if let Err(error) = outcome {
+            self.policy_manager.cleanup(new_id).await;
+            if moved_logs {
+                if let Err(e) = self.logs.rename(new_id, id).await {
+                    warn!(component_id = %id, error = %e, "Failed to move component logs back after a failed rename");
+                }
+            }
+            if moved_secrets {
+                if let Err(e) = self
+                    .secrets_manager
+                    .rename_component_secrets(new_id, id)
+                    .await
+                {
+                    warn!(component_id = %id, error = %e, "Failed to move component secrets back after a failed rename");
+                }
+            }
+            if let Err(e) = self.storage.rename_component_files(new_id, id).await {
+                warn!(component_id = %id, error = %e, "Failed to move component files back after a failed rename");
+            }
+            return Err(error);
+        }
+
+        self.registry.rename_component(id, new_id).await;
+        {
+            let mut last_used = self
+                .last_used
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            if let Some(used) = last_used.remove(id) {
+                last_used.insert(new_id.to_string(), used);
+            }
+        }
+        {
+            let mut stats = self.stats.lock().unwrap_or_else(PoisonError::into_inner);
+            if let Some(component_stats) = stats.remove(id) {
+                stats.insert(new_id.to_string(), component_stats);
+            }
         }
+        self.policy_manager.cleanup(id).await;
 
-        Ok(tool_infos[0].component_id.clone())
+        info!(component_id = %new_id, previous_id = %id, "Component renamed");
+        self.emit_event(LifecycleEvent::Renamed {
+            id: new_id.to_string(),
+            previous_id: id.to_string(),
+        });
+        Ok(())
     }
 
-    /// Lists all available tools across all components
+    /// Bring the registry in line with the component directory: load components whose
+    /// `.wasm` files were added since the last scan and drop those whose files were
+    /// removed. Only in-memory state is dropped; leftover policy and metadata files of a
+    /// removed component stay where they are.
     #[instrument(skip(self))]
-    pub async fn list_tools(&self) -> Vec<Value> {
-        self.registry.list_tools().await
+    pub async fn rescan_components(&self) -> Result<RescanOutcome> {
+        let mut on_disk = Vec::new();
+        let mut entries = tokio::fs::read_dir(self.storage.root()).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "wasm") {
+                if let Some(id) = path.file_stem().and_then(|s| s.to_str()) {
+                    on_disk.push(id.to_string());
+                }
+            }
+        }
+        on_disk.sort();
+        let registered = self.registry.registered_ids().await;
+
+        let mut outcome = RescanOutcome::default();
+        for id in on_disk.iter().filter(|id| !registered.contains(id)) {
+            match self.ensure_component_loaded(id).await {
+                Ok(()) => {
+                    self.emit_event(LifecycleEvent::Loaded {
+                        id: id.clone(),
+                        source: format!("file://{}", self.component_path(id).display()),
+                    });
+                    outcome.loaded.push(id.clone());
+                }
+                Err(e) => warn!(component_id = %id, error = %e, "Failed to load new component"),
+            }
+        }
+
+        for id in registered.iter().filter(|id| !on_disk.contains(id)) {
+            let load_lock = self.load_lock(id);
+            let _load_guard = load_lock.lock().await;
+            if self.component_path(id).exists() {
+                continue;
+            }
+            self.registry.remove_component(id).await;
+            self.last_used
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .remove(id);
+            self.policy_manager.cleanup(id).await;
+            self.emit_event(LifecycleEvent::Unloaded { id: id.clone() });
+            outcome.removed.push(id.clone());
+        }
+
+        info!(
+            loaded = outcome.loaded.len(),
+            removed = outcome.removed.len(),
+            "Rescanned component directory"
+        );
+        Ok(outcome)
     }
 
-    /// Returns the schema for a specific tool owned by a component, if available
+    /// Compare the installed version of a component with the artifact at `uri` without
+    /// installing it. Reports added, removed, and changed tools along with capabilities the
+    /// new version would need that the installed one does not.
     #[instrument(skip(self))]
-    pub async fn get_tool_schema_for_component(
+    pub async fn diff_component(&self, component_id: &str, uri: &str) -> Result<ComponentDiff> {
+        let current_path = self.component_path(component_id);
+        if !current_path.exists() {
+            bail!("Component not found: {component_id}");
+        }
+
+        let (current, current_bytes) = self
+            .compile_component(&current_path)
+            .await
+            .context("Failed to compile installed component")?;
+
+        let (_, resource) = self.resolve_component_resource(uri).await?;
+        let (candidate, candidate_bytes) = self
+            .compile_component(resource.as_ref())
+            .await
+            .with_context(|| format!("Failed to compile component from {uri}"))?;
+
+        let engine: &wasmtime::Engine = self.runtime.as_ref();
+        Ok(ComponentDiff::between(
+            component_id,
+            &diff::tools_by_name(&self.tool_metadata_for(&current, &current_bytes)),
+            &diff::tools_by_name(&self.tool_metadata_for(&candidate, &candidate_bytes)),
+            &diff::required_capabilities(&current, engine),
+            &diff::required_capabilities(&candidate, engine),
+        ))
+    }
+
+    /// Fetch and compile the component at `uri` and check that the host provides every
+    /// interface it imports, without installing it.
+    #[instrument(skip(self))]
+    pub async fn verify_component(&self, uri: &str) -> Result<ComponentVerification> {
+        let (_, resource) = self.resolve_component_resource(uri).await?;
+        let (component, _) = self
+            .compile_component(resource.as_ref())
+            .await
+            .with_context(|| format!("Failed to compile component from {uri}"))?;
+
+        verify::verify(uri, &component, self.runtime.as_ref())
+    }
+
+    /// Compare the capabilities an installed component imports with the permissions its
+    /// effective policy grants, including those of the default policy, reporting imports with
+    /// no grant and grants that are never used. Rules
+    /// the component declares for the missing capabilities are suggested as grants.
+    #[instrument(skip(self))]
+    pub async fn audit_component_permissions(&self, component_id: &str) -> Result<PermissionAudit> {
+        let path = self.component_path(component_id);
+        if !path.exists() {
+            bail!("Component not found: {component_id}");
+        }
+        let declared = declared_permissions::declared_permissions(&tokio::fs::read(&path).await?)
+            .unwrap_or_else(|error| {
+                warn!(%component_id, error = %format!("{error:#}"), "Ignoring declared permissions");
+                Vec::new()
+            });
+
+        let (component, _) = self
+            .compile_component(&path)
+            .await
+            .context("Failed to compile installed component")?;
+        let required = diff::required_capabilities(&component, self.runtime.as_ref());
+        let policy = self.get_effective_policy(component_id).await?;
+
+        Ok(PermissionAudit::between(
+            component_id,
+            &required,
+            &audit::granted_capabilities(&policy),
+        )
+        .with_suggestions(declared))
+    }
+
+    /// Package an installed component and its current policy into a gzipped tarball at `out`.
+    ///
+    /// Loading the bundle with [`Self::load_component`] installs both, so a working setup
+    /// can be reproduced on another machine.
+    #[instrument(skip(self))]
+    pub async fn export_component(&self, component_id: &str, out: &Path) -> Result<()> {
+        let wasm = self.component_path(component_id);
+        if !wasm.exists() {
+            bail!("Component not found: {component_id}");
+        }
+        let policy = self.get_component_policy_path(component_id);
+        let policy = policy.exists().then_some(policy);
+
+        let component_id = component_id.to_string();
+        let out = out.to_path_buf();
+        tokio::task::spawn_blocking(move || {
+            bundle::write_bundle(&out, &component_id, &wasm, policy.as_deref())
+        })
+        .await?
+    }
+
+    /// Derive tool metadata for a compiled component, using embedded package docs if present.
+    fn tool_metadata_for(&self, component: &Component, wasm_bytes: &[u8]) -> Vec<ToolMetadata> {
+        let tools = match extract_package_docs(wasm_bytes) {
+            Some(docs) => {
+                component_exports_to_tools_with_docs(component, self.runtime.as_ref(), true, &docs)
+            }
+            None => component_exports_to_tools(component, self.runtime.as_ref(), true),
+        };
+        lifecycle_hooks::without_lifecycle_tools(tools)
+    }
+
+    /// Returns the component ID for a given tool name.
+    /// If several components export the tool, the configured [`DuplicateToolPolicy`] picks
+    /// one of them or rejects the lookup.
+    #[instrument(skip(self))]
+    pub async fn get_component_id_for_tool(&self, tool_name: &str) -> Result<String> {
+        // Ordered by when each component registered the tool
+        let mut component_ids = self
+            .registry
+            .component_ids_for_tool(tool_name)
+            .await
+            .context("Tool not found")?;
+
+        if component_ids.len() > 1 {
+            match self.duplicate_tool_policy {
+                DuplicateToolPolicy::FirstLoaded => {}
+                DuplicateToolPolicy::LastLoaded => component_ids.reverse(),
+                DuplicateToolPolicy::Error | DuplicateToolPolicy::RequireNamespace => bail!(
+                    "Multiple components found for tool '{}': {}. Call it with its component \
+                     prefix instead, e.g. '{}__{}'",
+                    tool_name,
+                    component_ids.join(", "),
+                    component_ids[0],
+                    tool_name
+                ),
+            }
+        }
+
+        Ok(component_ids.remove(0))
+    }
+
+    /// How a tool name exported by several components is resolved
+    pub fn duplicate_tool_policy(&self) -> DuplicateToolPolicy {
+        self.duplicate_tool_policy
+    }
+
+    /// Lists all available tools across all components
+    #[instrument(skip(self))]
+    pub async fn list_tools(&self) -> Vec<Value> {
+        self.registry.list_tools().await
+    }
+
+    /// Returns the schema for a specific tool owned by a component, if available
+    #[instrument(skip(self))]
+    pub async fn get_tool_schema_for_component(
         &self,
         component_id: &str,
         tool_name: &str,
@@ -635,6 +1691,110 @@ impl LifecycleManager {
         self.registry.list_components().await
     }
 
+    /// Maximum number of components that may be loaded at once, if limited
+    pub fn max_components(&self) -> Option<usize> {
+        self.max_components
+    }
+
+    /// Fail if loading `component_id` would take the number of loaded components past
+    /// the configured limit. Reloading a component that is already loaded is allowed.
+    ///
+    /// Components admitted by [`Self::admit_component`] count as loaded. Loads call this
+    /// before staging their artifact to refuse early, and are admitted again under the
+    /// admission lock before they are registered.
+    async fn check_component_limit(&self, component_id: &str) -> Result<()> {
+        let Some(max) = self.max_components else {
+            return Ok(());
+        };
+        if self.registry.contains_component(component_id).await {
+            return Ok(());
+        }
+        let mut counted: HashSet<String> =
+            self.registry.list_components().await.into_iter().collect();
+        counted.extend(
+            self.pending_admissions
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .keys()
+                .cloned(),
+        );
+        let loaded = counted.len();
+        if loaded >= max {
+            bail!(
+                "Component limit reached: {loaded} of {max} allowed components are loaded. Unload a component before loading '{component_id}'"
+            );
+        }
+        Ok(())
+    }
+
+    /// Check the component limit and memory budget, and reserve a place and `memory_limit`
+    /// for `component_id` until the returned [`Admission`] is dropped. Checks are
+    /// serialized, so concurrent loads of different components cannot all pass before any
+    /// of them is registered.
+    async fn admit_component(
+        &self,
+        component_id: &str,
+        memory_limit: Option<u64>,
+    ) -> Result<Admission> {
+        let _admission_guard = self.admission_lock.lock().await;
+        self.check_component_limit(component_id).await?;
+        self.check_memory_budget(component_id, memory_limit).await?;
+        self.pending_admissions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(component_id.to_string(), memory_limit.unwrap_or(0));
+        Ok(Admission {
+            component_id: component_id.to_string(),
+            pending: Arc::clone(&self.pending_admissions),
+        })
+    }
+
+    /// Budget for the declared memory limits of all loaded components, if any
+    pub fn max_loaded_memory(&self) -> Option<u64> {
+        self.max_loaded_memory
+    }
+
+    /// Fail if `requested`, the memory limit `component_id` will run with, would take the
+    /// sum of the limits of loaded components past the memory budget. A reloaded component
+    /// is only counted once, with its new limit, and admitted components still being loaded
+    /// count with the limit they reserved. A component without a limit could use any amount
+    /// of memory, so it is refused while a budget is set.
+    async fn check_memory_budget(&self, component_id: &str, requested: Option<u64>) -> Result<()> {
+        let Some(budget) = self.max_loaded_memory else {
+            return Ok(());
+        };
+        let Some(requested) = requested else {
+            bail!(
+                "Component '{component_id}' declares no memory limit, but loaded components must fit in a memory budget of {budget} bytes. Set resources.limits.memory in its policy or in the default policy"
+            );
+        };
+        let pending = self
+            .pending_admissions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        let mut reserved = pending
+            .iter()
+            .filter(|(id, _)| id.as_str() != component_id)
+            .fold(0u64, |sum, (_, limit)| sum.saturating_add(*limit));
+        for id in self.registry.list_components().await {
+            if id != component_id && !pending.contains_key(&id) {
+                let limit = self.declared_memory_limit(&id).await.unwrap_or(0);
+                reserved = reserved.saturating_add(limit);
+            }
+        }
+        InsufficientMemoryBudget::check(component_id, requested, reserved, budget)?;
+        Ok(())
+    }
+
+    /// Memory limit set by the policy enforced for `component_id`, in bytes
+    async fn declared_memory_limit(&self, component_id: &str) -> Option<u64> {
+        self.policy_manager
+            .template_for_component(component_id)
+            .await
+            .memory_limit
+    }
+
     /// Lists all known components by ID (union of loaded components and any
     /// `*.wasm` files present in the component directory). Does not compile components.
     #[instrument(skip(self))]
@@ -736,7 +1896,7 @@ impl LifecycleManager {
 
     /// Attach a policy to a component by URI.
     pub async fn attach_policy(&self, component_id: &str, policy_uri: &str) -> Result<()> {
-        if !self.registry.contains_component(component_id).await {
+        if !self.is_installed(component_id).await {
             return Err(anyhow!("Component not found: {}", component_id));
         }
         self.policy_manager
@@ -754,6 +1914,51 @@ impl LifecycleManager {
         self.policy_manager.get_policy_info(component_id).await
     }
 
+    /// The policy a component actually runs with: its own policy, or an empty one if it
+    /// has none, merged with the default policy configured with
+    /// [`LifecycleBuilder::with_default_policy`].
+    pub async fn get_effective_policy(&self, component_id: &str) -> Result<policy::PolicyDocument> {
+        let policy = self
+            .policy_manager
+            .load_or_create_component_policy(component_id)
+            .await?;
+        Ok(self.policy_manager.effective_policy(&policy))
+    }
+
+    /// Predict whether a component may perform `query` under its effective policy, naming the
+    /// rule that allows it. Nothing is granted or changed, and the component does not need to
+    /// be loaded. A granted host that resolves to an internal address is reported as denied
+    /// unless internal addresses are allowed, as requests to it would be refused.
+    #[instrument(skip(self))]
+    pub async fn explain_permission(
+        &self,
+        component_id: &str,
+        query: &PermissionQuery,
+    ) -> Result<PermissionExplanation> {
+        if !self.is_installed(component_id).await {
+            bail!("Component not found: {component_id}");
+        }
+        let policy = self.get_effective_policy(component_id).await?;
+        let mut explanation = explain::explain(component_id, &policy, query, self.is_safe_mode())?;
+        if !self.outbound_http.allow_internal_addresses {
+            explain::apply_internal_address_block(&mut explanation).await;
+        }
+        Ok(explanation)
+    }
+
+    /// Whether the component is loaded or installed in the component directory, whether or
+    /// not it currently has an instance
+    async fn is_installed(&self, component_id: &str) -> bool {
+        self.registry.contains_component(component_id).await
+            || self.component_path(component_id).exists()
+    }
+
+    /// Whether the manager runs in safe mode, where components get none of the capabilities
+    /// their policies grant and granting permissions is refused.
+    pub fn is_safe_mode(&self) -> bool {
+        self.policy_manager.is_safe_mode()
+    }
+
     /// Grant a specific permission rule to a component.
     #[instrument(skip(self))]
     pub async fn grant_permission(
@@ -762,9 +1967,28 @@ impl LifecycleManager {
         permission_type: &str,
         details: &serde_json::Value,
     ) -> Result<()> {
-        if !self.registry.contains_component(component_id).await {
+        if !self.is_installed(component_id).await {
             return Err(anyhow!("Component not found: {}", component_id));
         }
+        // A loaded component's new memory limit has to fit in the budget like a load would.
+        // Held until the grant is saved, so a concurrent load cannot take the room meanwhile.
+        let _admission_guard = if self.max_loaded_memory.is_some()
+            && self.registry.contains_component(component_id).await
+        {
+            let guard = self.admission_lock.lock().await;
+            let grant = DeclaredPermission {
+                permission_type: permission_type.to_string(),
+                details: details.clone(),
+            };
+            let memory_limit = self
+                .policy_manager
+                .prospective_memory_limit(component_id, std::slice::from_ref(&grant))
+                .await;
+            self.check_memory_budget(component_id, memory_limit).await?;
+            Some(guard)
+        } else {
+            None
+        };
         self.policy_manager
             .grant_permission(component_id, permission_type, details)
             .await
@@ -778,7 +2002,7 @@ impl LifecycleManager {
         permission_type: &str,
         details: &serde_json::Value,
     ) -> Result<()> {
-        if !self.registry.contains_component(component_id).await {
+        if !self.is_installed(component_id).await {
             return Err(anyhow!("Component not found: {}", component_id));
         }
         self.policy_manager
@@ -789,7 +2013,7 @@ impl LifecycleManager {
     /// Reset all permissions for a component to defaults.
     #[instrument(skip(self))]
     pub async fn reset_permission(&self, component_id: &str) -> Result<()> {
-        if !self.registry.contains_component(component_id).await {
+        if !self.is_installed(component_id).await {
             return Err(anyhow!("Component not found: {}", component_id));
         }
         self.policy_manager.reset_permission(component_id).await
@@ -802,7 +2026,7 @@ impl LifecycleManager {
         component_id: &str,
         uri: &str,
     ) -> Result<()> {
-        if !self.registry.contains_component(component_id).await {
+        if !self.is_installed(component_id).await {
             return Err(anyhow!("Component not found: {}", component_id));
         }
         self.policy_manager
@@ -824,18 +2048,28 @@ impl LifecycleManager {
             return Ok(());
         }
 
+        // Another caller may have loaded the component while we waited for the lock
+        let load_lock = self.load_lock(component_id);
+        let _load_guard = load_lock.lock().await;
+        if self.registry.contains_component(component_id).await {
+            return Ok(());
+        }
+
         let entry_path = self.component_path(component_id);
         if !entry_path.exists() {
             bail!("Component not found: {}", component_id);
         }
+        self.check_component_limit(component_id).await?;
 
-        self.compile_and_register_component(component_id, &entry_path)
+        self.compile_and_register_component(component_id, &entry_path, false)
             .await
-            .with_context(|| {
-                format!(
-                    "Failed to compile component from path: {}",
-                    entry_path.display()
-                )
+            .map_err(|error| {
+                with_load_context(error, || {
+                    format!(
+                        "Failed to compile component from path: {}",
+                        entry_path.display()
+                    )
+                })
             })?;
 
         Ok(())
@@ -889,23 +2123,55 @@ impl LifecycleManager {
             .context("Failed to precompile component")?;
 
         self.storage
-            .write_precompiled(component_id, &precompiled_data)
+            .write_precompiled(
+                component_id,
+                &precompiled_data,
+                self.runtime.compilation_strategy(),
+            )
             .await?;
 
         info!(component_id = %component_id, "Saved precompiled component");
         Ok(())
     }
 
+    /// Compile a component artifact without registering it or touching the precompiled cache.
+    async fn compile_component(&self, wasm_path: &Path) -> Result<(Component, Vec<u8>)> {
+        let wasm_bytes = tokio::fs::read(wasm_path)
+            .await
+            .context("Failed to read wasm file")?;
+        loader::ensure_component_binary(&wasm_bytes)?;
+
+        // Compile on the blocking pool so that the async runtime keeps serving other work
+        let runtime = Arc::clone(&self.runtime);
+        tokio::task::spawn_blocking(move || {
+            Component::new(runtime.as_ref(), &wasm_bytes).map(|component| (component, wasm_bytes))
+        })
+        .await?
+        .context("Failed to compile component")
+    }
+
     /// Load component from precompiled cache or compile fresh
     async fn load_component_optimized(
         &self,
         wasm_path: &Path,
         component_id: &str,
+        skip_precompiled: bool,
     ) -> Result<(Component, Vec<u8>)> {
         let precompiled_path = self.component_precompiled_path(component_id);
 
-        // Try to load from precompiled cache first
-        if precompiled_path.exists() {
+        // Try to load from precompiled cache first, unless it was built with another strategy
+        let strategy = self.runtime.compilation_strategy();
+        let cached_strategy = self.storage.read_precompiled_strategy(component_id).await;
+        if skip_precompiled {
+            debug!(%component_id, "Skipping precompiled cache, recompiling");
+        } else if precompiled_path.exists() && cached_strategy != strategy {
+            debug!(
+                %component_id,
+                cached = cached_strategy.as_str(),
+                current = strategy.as_str(),
+                "Precompiled component uses a different compilation strategy, recompiling"
+            );
+        } else if precompiled_path.exists() {
             match unsafe { Component::deserialize_file(self.runtime.as_ref(), &precompiled_path) } {
                 Ok(component) => {
                     debug!(component_id = %component_id, "Loaded component from precompiled cache");
@@ -922,12 +2188,7 @@ impl LifecycleManager {
         }
 
         // Fall back to compilation
-        let wasm_bytes = tokio::fs::read(wasm_path)
-            .await
-            .context("Failed to read wasm file")?;
-
-        let component = Component::new(self.runtime.as_ref(), &wasm_bytes)
-            .context("Failed to compile component")?;
+        let (component, wasm_bytes) = self.compile_component(wasm_path).await?;
 
         // Save precompiled version for next time (async, don't block on this)
         if let Err(e) = self
@@ -941,47 +2202,74 @@ impl LifecycleManager {
         Ok((component, wasm_bytes))
     }
 
-    async fn get_wasi_state_for_component(
-        &self,
-        component_id: &str,
-    ) -> Result<(WassetteWasiState<WasiState>, Option<CustomResourceLimiter>)> {
+    /// Resolve the WASI template for a component, layering in its configured
+    /// environment variables beneath secrets and policy-granted variables.
+    async fn component_wasi_template(&self, component_id: &str) -> Result<Arc<WasiStateTemplate>> {
         let policy_template = self
             .policy_manager
             .template_for_component(component_id)
             .await;
 
+        let component_env = self.storage.read_component_env(component_id).await?;
+        if component_env.is_empty() {
+            return Ok(policy_template);
+        }
+
+        let mut template = policy_template.as_ref().clone();
+        for (key, value) in component_env {
+            template.config_vars.entry(key).or_insert(value);
+        }
+        Ok(Arc::new(template))
+    }
+
+    async fn get_wasi_state_for_component(
+        &self,
+        component_id: &str,
+        scratch_dir: Option<&Path>,
+    ) -> Result<(WassetteWasiState<WasiState>, Option<CustomResourceLimiter>)> {
+        let mut policy_template = self.component_wasi_template(component_id).await?;
+        if let Some(scratch_dir) = scratch_dir {
+            Arc::make_mut(&mut policy_template)
+                .preopened_dirs
+                .push(PreopenedDir {
+                    host_path: scratch_dir.to_path_buf(),
+                    guest_path: SCRATCH_GUEST_PATH.to_string(),
+                    dir_perms: wasmtime_wasi::DirPerms::all(),
+                    file_perms: wasmtime_wasi::FilePerms::all(),
+                });
+        }
+        let call_environment = call_context::current_call_environment();
+        if !call_environment.is_empty() {
+            Arc::make_mut(&mut policy_template)
+                .config_vars
+                .extend(call_environment);
+        }
+
         let wasi_state = policy_template.build()?;
         let allowed_hosts = policy_template.allowed_hosts.clone();
         let resource_limiter = wasi_state.resource_limiter.clone();
 
-        let wassette_wasi_state = WassetteWasiState::new(wasi_state, allowed_hosts)?;
+        let wassette_wasi_state = WassetteWasiState::new(wasi_state, allowed_hosts)?
+            .with_outbound_config(self.outbound_http.clone())
+            .with_egress_limit(self.egress_byte_limit);
         Ok((wassette_wasi_state, resource_limiter))
     }
 
-    /// Executes a function call on a WebAssembly component
-    #[instrument(skip(self))]
-    pub async fn execute_component_call(
+    /// Create a store carrying the component's WASI state and policy memory limits, with
+    /// `scratch_dir` preopened at [`SCRATCH_GUEST_PATH`] when given
+    async fn new_component_store(
         &self,
         component_id: &str,
-        function_name: &str,
-        parameters: &str,
-    ) -> Result<String> {
-        let start_time = Instant::now();
-
-        debug!(
-            component_id = %component_id,
-            function_name = %function_name,
-            "Starting WebAssembly component execution"
-        );
-
-        let component = self
-            .get_component(component_id)
-            .await
-            .ok_or_else(|| anyhow!("Component not found: {}", component_id))?;
-
-        let (state, resource_limiter) = self.get_wasi_state_for_component(component_id).await?;
+        scratch_dir: Option<&Path>,
+    ) -> Result<Store<WassetteWasiState<WasiState>>> {
+        let (state, resource_limiter) = self
+            .get_wasi_state_for_component(component_id, scratch_dir)
+            .await?;
 
         let mut store = Store::new(self.runtime.as_ref(), state);
+        store.set_epoch_deadline(RuntimeContext::epoch_deadline(
+            call_context::current_interrupt_deadline(),
+        ));
 
         // Apply memory limits if configured in the policy by setting up a limiter closure
         // that extracts the resource limiter from the WasiState
@@ -996,74 +2284,517 @@ impl LifecycleManager {
             });
         }
 
-        let instantiation_start = Instant::now();
-        let instance = component.instance_pre.instantiate_async(&mut store).await?;
-        let instantiation_duration = instantiation_start.elapsed();
+        Ok(store)
+    }
 
-        debug!(
-            component_id = %component_id,
-            instantiation_ms = %instantiation_duration.as_millis(),
-            "Component instance created"
+    /// Start the resident instance of a component exporting the `wassette:lifecycle/hooks`
+    /// interface by running its `init` hook in it. Components without the interface are
+    /// skipped.
+    async fn start_resident(
+        &self,
+        component_id: &str,
+        component: &ComponentInstance,
+    ) -> Result<()> {
+        let Some(resident) = &component.resident else {
+            return Ok(());
+        };
+        let mut live = resident.live.lock().await;
+        *live = Some(
+            self.init_resident(component_id, component, &resident.interface)
+                .await?,
         );
+        Ok(())
+    }
 
-        // Use the new function identifier lookup instead of dot-splitting
-        let function_id = self
-            .registry
-            .tool_identifier(function_name)
-            .await
-            .ok_or_else(|| anyhow!("Unknown tool name: {}", function_name))?;
-
-        let (interface_name, func_name) = (
-            function_id.interface_name.as_deref().unwrap_or(""),
-            &function_id.function_name,
-        );
+    /// Create an instance of the component with the permissions its policy grants now and
+    /// run its `init` hook
+    async fn init_resident(
+        &self,
+        component_id: &str,
+        component: &ComponentInstance,
+        interface: &str,
+    ) -> Result<LiveInstance> {
+        let policy = self.policy_manager.registered_template(component_id).await;
+        let mut store = self.new_component_store(component_id, None).await?;
+        let instance = instantiate(component, &mut store).await?;
+        let mut live = LiveInstance {
+            store,
+            instance,
+            policy,
+        };
+        self.run_lifecycle_hook(component_id, &mut live, interface, LifecycleHook::Init)
+            .await?;
+        Ok(live)
+    }
 
-        let func = if !interface_name.is_empty() {
-            let interface_index = instance
-                .get_export_index(&mut store, None, interface_name)
-                .ok_or_else(|| anyhow!("Interface not found: {}", interface_name))?;
+    /// Run the `shutdown` hook in the resident instance of the component, if it has one
+    /// running, and drop the instance. A failing hook is logged, as it cannot stop the
+    /// component from going away.
+    async fn stop_resident(&self, component_id: &str, component: &ComponentInstance) {
+        let Some(resident) = &component.resident else {
+            return;
+        };
+        let Some(mut live) = resident.live.lock().await.take() else {
+            return;
+        };
+        if let Err(error) = self
+            .run_lifecycle_hook(
+                component_id,
+                &mut live,
+                &resident.interface,
+                LifecycleHook::Shutdown,
+            )
+            .await
+        {
+            warn!(%component_id, %error, "Component shutdown hook failed");
+        }
+    }
 
-            let function_index = instance
-                .get_export_index(&mut store, Some(&interface_index), func_name)
-                .ok_or_else(|| {
-                    anyhow!(
-                        "Function not found in interface: {}.{}",
-                        interface_name,
-                        func_name
-                    )
-                })?;
+    /// Shut down the resident instance of a loaded component, so that its next call starts
+    /// a new one that sees changed secrets or environment variables
+    async fn restart_resident(&self, component_id: &str) {
+        if let Some(component) = self.registry.get_component(component_id).await {
+            self.stop_resident(component_id, &component).await;
+        }
+    }
 
-            instance
-                .get_func(&mut store, function_index)
-                .ok_or_else(|| {
-                    anyhow!(
-                        "Function not found in interface: {}.{}",
-                        interface_name,
-                        func_name
-                    )
-                })?
-        } else {
-            let func_index = instance
-                .get_export_index(&mut store, None, func_name)
-                .ok_or_else(|| anyhow!("Function not found: {}", func_name))?;
-            instance
-                .get_func(&mut store, func_index)
-                .ok_or_else(|| anyhow!("Function not found: {}", func_name))?
+    /// Call a function of the component's `wassette:lifecycle/hooks` export in `live`.
+    /// Components that leave out this particular hook are skipped.
+    async fn run_lifecycle_hook(
+        &self,
+        component_id: &str,
+        live: &mut LiveInstance,
+        interface: &str,
+        hook: LifecycleHook,
+    ) -> Result<()> {
+        let hook_name = hook.function_name();
+
+        debug!(%component_id, hook = hook_name, "Running component lifecycle hook");
+        live.store.data_mut().start_call();
+        live.store
+            .set_epoch_deadline(RuntimeContext::epoch_deadline(
+                call_context::current_interrupt_deadline(),
+            ));
+        let Some(results) = self
+            .call_export(
+                component_id,
+                &mut live.store,
+                &live.instance,
+                interface,
+                hook_name,
+            )
+            .await
+            .with_context(|| format!("Component {hook_name} hook trapped"))?
+        else {
+            return Ok(());
         };
 
-        let params: serde_json::Value = serde_json::from_str(parameters)?;
-        let argument_vals = json_to_vals(&params, &func.params(&store))?;
+        lifecycle_hooks::hook_outcome(&results)
+            .map_err(|message| anyhow!("Component {hook_name} hook failed: {message}"))
+    }
 
-        let mut results = create_placeholder_results(&func.results(&store));
+    /// Run the component's `wassette:health/check` export, loading the component first if
+    /// needed. Components without the export report [`HealthStatus::Unknown`]; a check still
+    /// running after [`HEALTH_CHECK_TIMEOUT`] is interrupted and reported unhealthy.
+    #[instrument(skip(self))]
+    pub async fn check_component_health(&self, component_id: &str) -> Result<ComponentHealth> {
+        self.check_component_health_within(component_id, HEALTH_CHECK_TIMEOUT)
+            .await
+    }
 
-        let execution_start = Instant::now();
+    async fn check_component_health_within(
+        &self,
+        component_id: &str,
+        timeout: Duration,
+    ) -> Result<ComponentHealth> {
+        self.ensure_component_loaded(component_id).await?;
+        let component = self
+            .registry
+            .get_component(component_id)
+            .await
+            .with_context(|| format!("Component not found: {component_id}"))?;
+        let Some(interface) = health::health_export(&component.component, self.runtime.as_ref())
+        else {
+            return Ok(ComponentHealth::unknown());
+        };
 
-        // Execute the WASM function and capture any errors
-        let call_result = func
-            .call_async(&mut store, &argument_vals, &mut results)
+        let deadline = tokio::time::Instant::now() + timeout;
+        let outcome = call_context::with_call_deadline(
+            deadline,
+            self.call_interface_function(
+                component_id,
+                &component,
+                &interface,
+                health::CHECK_FUNCTION,
+            ),
+        )
+        .await;
+        Ok(match outcome {
+            Ok(Some(results)) => health::check_outcome(&results),
+            Ok(None) => ComponentHealth::unknown(),
+            Err(e) if e.downcast_ref::<wasmtime::Trap>() == Some(&wasmtime::Trap::Interrupt) => {
+                ComponentHealth::unhealthy(format!("Health check timed out after {timeout:?}"))
+            }
+            Err(e) => ComponentHealth::unhealthy(format!("Health check trapped: {e:#}")),
+        })
+    }
+
+    /// Call the argument-less `function` of the component's exported `interface`, in its
+    /// resident instance if it has one running and in a new instance otherwise. Returns
+    /// `None` when the interface has no such function.
+    async fn call_interface_function(
+        &self,
+        component_id: &str,
+        component: &ComponentInstance,
+        interface: &str,
+        function: &str,
+    ) -> Result<Option<Vec<wasmtime::component::Val>>> {
+        if let Some(resident) = &component.resident {
+            let mut live = resident.live.lock().await;
+            if let Some(live) = live.as_mut() {
+                live.store.data_mut().start_call();
+                live.store
+                    .set_epoch_deadline(RuntimeContext::epoch_deadline(
+                        call_context::current_interrupt_deadline(),
+                    ));
+                return self
+                    .call_export(
+                        component_id,
+                        &mut live.store,
+                        &live.instance,
+                        interface,
+                        function,
+                    )
+                    .await;
+            }
+        }
+
+        let mut store = self.new_component_store(component_id, None).await?;
+        let instance = instantiate(component, &mut store).await?;
+        self.call_export(component_id, &mut store, &instance, interface, function)
+            .await
+    }
+
+    /// Call the argument-less `function` of the exported `interface` of `instance`
+    async fn call_export(
+        &self,
+        component_id: &str,
+        store: &mut Store<WassetteWasiState<WasiState>>,
+        instance: &wasmtime::component::Instance,
+        interface: &str,
+        function: &str,
+    ) -> Result<Option<Vec<wasmtime::component::Val>>> {
+        let Some(interface_index) = instance.get_export_index(&mut *store, None, interface) else {
+            return Ok(None);
+        };
+        let Some(func) = instance
+            .get_export_index(&mut *store, Some(&interface_index), function)
+            .and_then(|func_index| instance.get_func(&mut *store, func_index))
+        else {
+            return Ok(None);
+        };
+
+        let mut results = create_placeholder_results(&func.results(&*store));
+        let call_result = func.call_async(&mut *store, &[], &mut results).await;
+        let redactor = self.secret_redactor(component_id).await;
+        self.record_output(component_id, function, store, redactor.as_ref())
+            .await;
+        call_result?;
+        Ok(Some(results))
+    }
+
+    /// Append whatever the component wrote to stdout and stderr during a call of `function`
+    /// to its logs, with the component's secret values scrubbed when `redactor` is given
+    async fn record_output(
+        &self,
+        component_id: &str,
+        function: &str,
+        store: &Store<WassetteWasiState<WasiState>>,
+        redactor: Option<&Redactor>,
+    ) {
+        let output = store.data().inner.captured_output.clone();
+        for (stream, pipe) in [
+            (LogStream::Stdout, output.stdout),
+            (LogStream::Stderr, output.stderr),
+        ] {
+            let mut contents = String::from_utf8_lossy(&pipe.take()).into_owned();
+            if let Some(redactor) = redactor.filter(|redactor| redactor.matches(&contents)) {
+                contents = redactor.redact(&contents);
+            }
+            if self.stderr_tracing && stream == LogStream::Stderr {
+                for line in contents.lines() {
+                    info!(%component_id, function, "{line}");
+                }
+            }
+            if let Err(error) = self
+                .logs
+                .record(component_id, stream, contents.as_bytes())
+                .await
+            {
+                warn!(%component_id, %stream, %error, "Failed to record component output");
+            }
+        }
+    }
+
+    /// Most recent stdout and stderr lines written by a component, oldest first. At most
+    /// `tail` lines are returned when given, and never more than [`DEFAULT_LOG_CAPACITY`].
+    pub async fn get_component_logs(
+        &self,
+        component_id: &str,
+        tail: Option<usize>,
+    ) -> Vec<LogLine> {
+        self.logs.tail(component_id, tail).await
+    }
+
+    /// Calls made to a component and the network traffic they received since it was loaded,
+    /// or `None` if it has not been called
+    pub fn get_component_stats(&self, component_id: &str) -> Option<ComponentStats> {
+        self.stats
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(component_id)
+            .copied()
+    }
+
+    /// [`ComponentStats`] of every component that has been called, by component id
+    pub fn all_component_stats(&self) -> BTreeMap<String, ComponentStats> {
+        self.stats
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|(id, stats)| (id.clone(), *stats))
+            .collect()
+    }
+
+    /// File the component's output is appended to as JSON lines, one [`LogLine`] each
+    pub fn component_log_path(&self, component_id: &str) -> PathBuf {
+        self.logs.log_path(component_id)
+    }
+
+    /// Executes a function call on a WebAssembly component
+    #[instrument(skip(self))]
+    pub async fn execute_component_call(
+        &self,
+        component_id: &str,
+        function_name: &str,
+        parameters: &str,
+    ) -> Result<String> {
+        let redactor = self.secret_redactor(component_id).await;
+        let outcome = self
+            .run_component_call(component_id, function_name, parameters, redactor.as_ref())
+            .await;
+        let Some(redactor) = redactor else {
+            return outcome;
+        };
+        match outcome {
+            Ok(result) if redactor.matches(&result) => {
+                let Ok(mut value) = serde_json::from_str::<Value>(&result) else {
+                    return Ok(result);
+                };
+                redactor.redact_result_errors(&mut value);
+                Ok(serde_json::to_string(&value)?)
+            }
+            Ok(result) => Ok(result),
+            Err(e) => Err(redact_error(&redactor, e)),
+        }
+    }
+
+    /// Scrubs the component's secret values, or `None` when redaction is disabled or the
+    /// component has no secrets
+    async fn secret_redactor(&self, component_id: &str) -> Option<Redactor> {
+        if !self.redact_secrets {
+            return None;
+        }
+        let secrets = self
+            .secrets_manager
+            .load_component_secrets(component_id)
+            .await
+            .unwrap_or_default();
+        (!secrets.is_empty()).then(|| Redactor::new(secrets.into_values()))
+    }
+
+    async fn run_component_call(
+        &self,
+        component_id: &str,
+        function_name: &str,
+        parameters: &str,
+        redactor: Option<&Redactor>,
+    ) -> Result<String> {
+        debug!(
+            component_id = %component_id,
+            function_name = %function_name,
+            "Starting WebAssembly component execution"
+        );
+
+        // Marked before loading so the idle unloader cannot evict it again in between
+        self.mark_used(component_id);
+        self.ensure_component_loaded(component_id).await?;
+        let component = self
+            .get_component(component_id)
+            .await
+            .ok_or_else(|| anyhow!("Component not found: {}", component_id))?;
+
+        if let Some(resident) = &component.resident {
+            let mut live = resident.live.lock().await;
+            let policy = self.policy_manager.registered_template(component_id).await;
+            if !live
+                .as_ref()
+                .is_some_and(|live| live.has_policy(policy.as_ref()))
+            {
+                // Not running after a failed restart, or built with permissions that have
+                // changed since: restart it so the call runs with the current ones
+                if let Some(mut stale) = live.take() {
+                    if let Err(error) = self
+                        .run_lifecycle_hook(
+                            component_id,
+                            &mut stale,
+                            &resident.interface,
+                            LifecycleHook::Shutdown,
+                        )
+                        .await
+                    {
+                        warn!(%component_id, %error, "Component shutdown hook failed");
+                    }
+                }
+                *live = Some(
+                    self.init_resident(component_id, &component, &resident.interface)
+                        .await?,
+                );
+            }
+            let live = live.as_mut().expect("resident instance was started above");
+            live.store.data_mut().start_call();
+            live.store
+                .set_epoch_deadline(RuntimeContext::epoch_deadline(
+                    call_context::current_interrupt_deadline(),
+                ));
+            return self
+                .invoke_tool(
+                    component_id,
+                    function_name,
+                    parameters,
+                    redactor,
+                    &mut live.store,
+                    &live.instance,
+                )
+                .await;
+        }
+
+        // Declared before the store so the directory outlives it and is removed on return
+        let scratch_dir = if self.per_call_scratch {
+            Some(self.storage.create_scratch_dir(component_id).await?)
+        } else {
+            None
+        };
+        let mut store = self
+            .new_component_store(component_id, scratch_dir.as_ref().map(|dir| dir.path()))
+            .await?;
+
+        let instantiation_start = Instant::now();
+        let instance = instantiate(&component, &mut store).await?;
+        let instantiation_duration = instantiation_start.elapsed();
+
+        debug!(
+            component_id = %component_id,
+            instantiation_ms = %instantiation_duration.as_millis(),
+            "Component instance created"
+        );
+
+        self.invoke_tool(
+            component_id,
+            function_name,
+            parameters,
+            redactor,
+            &mut store,
+            &instance,
+        )
+        .await
+    }
+
+    /// Call the tool `function_name` of the component in `instance` and convert its results
+    /// to JSON
+    async fn invoke_tool(
+        &self,
+        component_id: &str,
+        function_name: &str,
+        parameters: &str,
+        redactor: Option<&Redactor>,
+        store: &mut Store<WassetteWasiState<WasiState>>,
+        instance: &wasmtime::component::Instance,
+    ) -> Result<String> {
+        // Use the new function identifier lookup instead of dot-splitting
+        let function_id = self
+            .registry
+            .tool_identifier(function_name)
+            .await
+            .ok_or_else(|| anyhow!("Unknown tool name: {}", function_name))?;
+
+        let (interface_name, func_name) = (
+            function_id.interface_name.as_deref().unwrap_or(""),
+            &function_id.function_name,
+        );
+
+        let func = if !interface_name.is_empty() {
+            let interface_index = instance
+                .get_export_index(&mut *store, None, interface_name)
+                .ok_or_else(|| anyhow!("Interface not found: {}", interface_name))?;
+
+            let function_index = instance
+                .get_export_index(&mut *store, Some(&interface_index), func_name)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Function not found in interface: {}.{}",
+                        interface_name,
+                        func_name
+                    )
+                })?;
+
+            instance
+                .get_func(&mut *store, function_index)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Function not found in interface: {}.{}",
+                        interface_name,
+                        func_name
+                    )
+                })?
+        } else {
+            let func_index = instance
+                .get_export_index(&mut *store, None, func_name)
+                .ok_or_else(|| anyhow!("Function not found: {}", func_name))?;
+            instance
+                .get_func(&mut *store, func_index)
+                .ok_or_else(|| anyhow!("Function not found: {}", func_name))?
+        };
+
+        let params: serde_json::Value = serde_json::from_str(parameters)?;
+        let argument_vals = json_to_vals(&params, &func.params(&*store))?;
+
+        let mut results = create_placeholder_results(&func.results(&*store));
+
+        let execution_start = Instant::now();
+
+        // Execute the WASM function and capture any errors
+        let call_result = func
+            .call_async(&mut *store, &argument_vals, &mut results)
             .await;
 
         let execution_duration = execution_start.elapsed();
+        self.record_output(component_id, function_name, store, redactor)
+            .await;
+
+        let egress = store.data().egress();
+        self.stats
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(component_id.to_string())
+            .or_default()
+            .record_call(egress.bytes());
+        if let Some(limit) = egress.exceeded_limit() {
+            warn!(%component_id, %function_name, limit, "Tool call aborted over the egress limit");
+            bail!(
+                "Component '{component_id}' exceeded the egress limit of {limit} bytes while running '{function_name}'"
+            );
+        }
 
         // If the call failed, check if it was due to a permission denial
         if let Err(e) = call_result {
@@ -1072,19 +2803,34 @@ impl LifecycleManager {
                 // Return a more informative error with instructions
                 return Err(anyhow!(perm_error.to_user_message(component_id)));
             }
+            if e.downcast_ref::<wasmtime::Trap>() == Some(&wasmtime::Trap::Interrupt) {
+                warn!(%component_id, %function_name, "Tool call interrupted at its deadline");
+                bail!(
+                    "Component '{component_id}' was interrupted at the call deadline while running '{function_name}'"
+                );
+            }
+            if let Some(trap) = ComponentTrap::from_error(component_id, function_name, &e) {
+                let message = match redactor {
+                    Some(redactor) => redactor.redact(&trap.message),
+                    None => trap.message.clone(),
+                };
+                warn!(
+                    component_id = %component_id,
+                    function_name = %function_name,
+                    trap = %message,
+                    "Component trapped"
+                );
+                return Err(trap.into());
+            }
             // Otherwise, return the original WASM execution error
             return Err(e);
         }
 
-        let result_json = vals_to_json(&results);
-
-        let total_duration = start_time.elapsed();
+        let result_json = vals_to_json(&results, &func.results(&*store));
 
         debug!(
             component_id = %component_id,
             function_name = %function_name,
-            total_duration_ms = %total_duration.as_millis(),
-            instantiation_ms = %instantiation_duration.as_millis(),
             execution_ms = %execution_duration.as_millis(),
             "WebAssembly component execution completed"
         );
@@ -1098,11 +2844,15 @@ impl LifecycleManager {
 
     /// Load existing components from component directory in the background with bounded parallelism
     /// Default concurrency is min(num_cpus, 4) if not specified
+    ///
+    /// With [`StartupLoadPolicy::FailFast`] the first failure stops loading: loads already
+    /// running finish, the remaining components are skipped and the failure is returned.
     #[instrument(skip(self, notify_fn))]
     pub async fn load_existing_components_async<F>(
         &self,
         concurrency: Option<usize>,
         notify_fn: Option<F>,
+        policy: StartupLoadPolicy,
     ) -> Result<()>
     where
         F: Fn() + Send + Sync + 'static,
@@ -1118,18 +2868,51 @@ impl LifecycleManager {
         );
 
         let semaphore = Arc::new(Semaphore::new(concurrency));
+        let stop = Arc::new(AtomicBool::new(false));
         let mut entries = tokio::fs::read_dir(self.storage.root()).await?;
         let mut load_futures = Vec::new();
+        *self.lock_startup_report() = StartupReport::default();
 
         while let Some(entry) = entries.next_entry().await? {
             let self_clone = self.clone();
             let semaphore = semaphore.clone();
+            let stop = stop.clone();
             let notify_fn = notify_fn.as_ref().map(std::sync::Arc::new);
+            let load_timeout = self.load_timeout;
 
             let future = async move {
                 let _permit = semaphore.acquire().await.unwrap();
+                if stop.load(Ordering::SeqCst) {
+                    return None;
+                }
+                let entry_path = entry.path();
+                let component_id = entry_path
+                    .extension()
+                    .is_some_and(|ext| ext == "wasm")
+                    .then(|| entry_path.file_stem().and_then(|s| s.to_str()))
+                    .flatten()
+                    .map(String::from);
+
+                let result = run_with_load_timeout(
+                    load_timeout,
+                    &entry_path,
+                    self_clone.load_component_from_entry_optimized(entry),
+                )
+                .await;
+                let status = match &result {
+                    Ok(_) => ComponentLoadStatus::Loaded,
+                    Err(e) => ComponentLoadStatus::Failed {
+                        reason: format!("{e:#}"),
+                    },
+                };
+                if let Some(component_id) = component_id {
+                    self_clone
+                        .lock_startup_report()
+                        .components
+                        .insert(component_id, status);
+                }
 
-                match self_clone.load_component_from_entry_optimized(entry).await {
+                match result {
                     Ok(true) => {
                         // Component was loaded, notify if callback provided
                         if let Some(notify) = notify_fn {
@@ -1137,18 +2920,89 @@ impl LifecycleManager {
                         }
                     }
                     Ok(false) => {} // No component to load (not a .wasm file)
-                    Err(e) => warn!("Failed to load component: {}", e),
+                    Err(e) => {
+                        warn!("Failed to load component: {}", e);
+                        if policy == StartupLoadPolicy::FailFast {
+                            stop.store(true, Ordering::SeqCst);
+                        }
+                        return Some(format!("{}: {e:#}", entry_path.display()));
+                    }
                 }
+                None
             };
             load_futures.push(future);
         }
 
-        // Wait for all components to load
-        futures::future::join_all(load_futures).await;
+        // Wait for all components to load, or to be skipped after a fail-fast failure
+        let failures: Vec<String> = futures::future::join_all(load_futures)
+            .await
+            .into_iter()
+            .flatten()
+            .collect();
+        self.lock_startup_report().complete = true;
         info!("Background component loading completed");
+
+        if policy == StartupLoadPolicy::FailFast {
+            if let Some(failure) = failures.into_iter().next() {
+                bail!("Stopped loading components at the first failure: {failure}");
+            }
+        }
         Ok(())
     }
 
+    /// Which installed components [`Self::load_existing_components_async`] loaded and which
+    /// failed, with the error for each failure.
+    pub fn get_startup_report(&self) -> StartupReport {
+        self.lock_startup_report().clone()
+    }
+
+    /// Compile every installed component and read its tools without loading it, reporting
+    /// the ones that fail. Unlike [`Self::load_existing_components_async`] nothing is
+    /// registered or written: no lifecycle hooks run, no permissions are granted and the
+    /// precompiled cache is left alone.
+    pub async fn check_installed_components(&self) -> Result<StartupReport> {
+        let mut report = StartupReport::default();
+        let mut entries = tokio::fs::read_dir(self.storage.root()).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let entry_path = entry.path();
+            if !entry_path.extension().is_some_and(|ext| ext == "wasm") {
+                continue;
+            }
+            let Some(component_id) = entry_path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+
+            let checked = async {
+                let (component, _) = self.compile_component(&entry_path).await?;
+                self.runtime
+                    .instantiate_pre(&component)
+                    .context("failed to instantiate component")?;
+                let tools = component_exports_to_tools(&component, self.runtime.as_ref(), true);
+                debug!(%component_id, tools = tools.len(), "Component compiles");
+                Ok::<_, anyhow::Error>(())
+            }
+            .await;
+            let status = match checked {
+                Ok(()) => ComponentLoadStatus::Loaded,
+                Err(e) => ComponentLoadStatus::Failed {
+                    reason: format!(
+                        "Failed to compile component from path: {}: {e:#}",
+                        entry_path.display()
+                    ),
+                },
+            };
+            report.components.insert(component_id.to_string(), status);
+        }
+        report.complete = true;
+        Ok(report)
+    }
+
+    fn lock_startup_report(&self) -> std::sync::MutexGuard<'_, StartupReport> {
+        self.startup_report
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
     /// Populate tool registry from cached metadata without compiling components
     async fn populate_registry_from_metadata(&self) -> Result<()> {
         let mut entries = tokio::fs::read_dir(self.storage.root()).await?;
@@ -1172,7 +3026,12 @@ impl LifecycleManager {
             // Try to load cached metadata
             if let Ok(Some(metadata)) = self.load_component_metadata(component_id).await {
                 // Validate that the component file hasn't changed
-                if ComponentStorage::validate_stamp(&entry_path, &metadata.validation_stamp).await {
+                let stale =
+                    ComponentStorage::stale_reason(&entry_path, &metadata.validation_stamp).await;
+                if let Some(reason) = &stale {
+                    info!(%component_id, %reason, "Cached metadata is stale, component will be recompiled");
+                }
+                if stale.is_none() {
                     let tool_metadata: Vec<ToolMetadata> = metadata
                         .function_identifiers
                         .into_iter()
@@ -1245,31 +3104,207 @@ impl LifecycleManager {
             .map(String::from)
             .context("wasm file didn't have a valid file name")?;
 
+        let load_lock = self.load_lock(&component_id);
+        let _load_guard = load_lock.lock().await;
         if self.registry.contains_component(&component_id).await {
             debug!(component_id = %component_id, "Component already loaded in memory");
             return Ok(false);
         }
+        self.check_component_limit(&component_id).await?;
 
         let start_time = Instant::now();
-        self.compile_and_register_component(&component_id, &entry_path)
+        self.compile_and_register_component(&component_id, &entry_path, false)
             .await
-            .with_context(|| {
-                format!(
-                    "Failed to compile component from path: {}",
-                    entry_path.display()
-                )
+            .map_err(|error| {
+                with_load_context(error, || {
+                    format!(
+                        "Failed to compile component from path: {}",
+                        entry_path.display()
+                    )
+                })
             })?;
 
         info!(component_id = %component_id, elapsed = ?start_time.elapsed(), "component loaded");
+        self.emit_event(LifecycleEvent::Loaded {
+            id: component_id,
+            source: format!("file://{}", entry_path.display()),
+        });
         Ok(true)
     }
 
     // Granular permission system methods
 }
+
+/// A component admitted past the component limit and memory budget, counted as loaded
+/// until dropped
+struct Admission {
+    component_id: String,
+    pending: Arc<std::sync::Mutex<HashMap<String, u64>>>,
+}
+
+impl Drop for Admission {
+    fn drop(&mut self) {
+        self.pending
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&self.component_id);
+    }
+}
+
+/// Contents of files as they were before a load wrote them, to put back if the load fails
+struct FileSnapshot(Vec<(PathBuf, Option<Vec<u8>>)>);
+
+impl FileSnapshot {
+    async fn take(paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        let mut files = Vec::new();
+        for path in paths {
+            let contents = tokio::fs::read(&path).await.ok();
+            files.push((path, contents));
+        }
+        Self(files)
+    }
+
+    /// Write every file back as it was, removing those that did not exist
+    async fn restore(self) -> Result<()> {
+        for (path, contents) in self.0 {
+            match contents {
+                Some(contents) => tokio::fs::write(&path, contents)
+                    .await
+                    .with_context(|| format!("Failed to restore {}", path.display()))?,
+                None => match tokio::fs::remove_file(&path).await {
+                    Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                        return Err(anyhow::Error::from(e)
+                            .context(format!("Failed to remove {}", path.display())));
+                    }
+                    _ => {}
+                },
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Add `context` to the error of a failed load, unless the memory budget refused the
+/// component, which already explains itself.
+fn with_load_context(error: anyhow::Error, context: impl FnOnce() -> String) -> anyhow::Error {
+    if error.is::<InsufficientMemoryBudget>() {
+        error
+    } else {
+        error.context(context())
+    }
+}
+
+/// Instantiate `component` in `store`, explaining failures caused by a full instance pool.
+async fn instantiate(
+    component: &ComponentInstance,
+    store: &mut Store<WassetteWasiState<WasiState>>,
+) -> Result<wasmtime::component::Instance> {
+    component
+        .instance_pre
+        .instantiate_async(store)
+        .await
+        .map_err(|e| {
+            if e.chain()
+                .any(|cause| cause.is::<wasmtime::PoolConcurrencyLimitError>())
+            {
+                e.context(
+                    "Every pooled component instance is in use; retry the call once others \
+                     finish or raise the pooling allocator's instance limits",
+                )
+            } else {
+                e
+            }
+        })
+}
+
+/// Scrub secret values from a failed call's error. Errors without a secret are returned
+/// untouched, and traps stay [`ComponentTrap`]s so callers can still downcast them.
+fn redact_error(redactor: &Redactor, error: anyhow::Error) -> anyhow::Error {
+    if let Some(trap) = error.downcast_ref::<ComponentTrap>() {
+        if !redactor.matches(&trap.message) && !trap.backtrace.iter().any(|f| redactor.matches(f)) {
+            return error;
+        }
+        let mut trap = trap.clone();
+        trap.message = redactor.redact(&trap.message);
+        trap.backtrace = trap.backtrace.iter().map(|f| redactor.redact(f)).collect();
+        return trap.into();
+    }
+    let text = format!("{error:#}");
+    if redactor.matches(&text) {
+        anyhow!(redactor.redact(&text))
+    } else {
+        error
+    }
+}
+
+/// Explicit component ids become file names, so they are limited to a portable character set.
+fn validate_component_id(id: &str) -> Result<()> {
+    let valid = id.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+    if !valid {
+        bail!(
+            "Invalid component id '{id}': ids must start with a letter or digit and contain only letters, digits, '.', '_' or '-'"
+        );
+    }
+    Ok(())
+}
+
+/// The part of a load URI that identifies where a component comes from. OCI references ignore
+/// the tag or digest and Git references their `@ref`, so upgrading a component in place is not
+/// treated as a conflict.
+fn source_identity(uri: &str) -> Cow<'_, str> {
+    if let Some(source) = git::source_identity(uri) {
+        return Cow::Owned(source);
+    }
+    let Some(reference) = uri.strip_prefix("oci://") else {
+        return Cow::Borrowed(uri);
+    };
+    let reference = reference
+        .split_once('@')
+        .map_or(reference, |(name, _)| name);
+    match reference.rfind(':') {
+        Some(colon) if !reference[colon..].contains('/') => Cow::Borrowed(&reference[..colon]),
+        _ => Cow::Borrowed(reference),
+    }
+}
+
+/// Run a single component load, interrupting the component code it runs, such as an `init`
+/// hook, once `timeout` elapses. The load is not abandoned: compilation runs no component
+/// code and always finishes, and an interrupted instance is dropped with the failed load.
+async fn run_with_load_timeout<T>(
+    timeout: Option<Duration>,
+    path: &Path,
+    load: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    let Some(limit) = timeout else {
+        return load.await;
+    };
+
+    let deadline = tokio::time::Instant::now() + limit;
+    call_context::with_load_deadline(deadline, load)
+        .await
+        .map_err(|error| {
+            let interrupted =
+                error.downcast_ref::<wasmtime::Trap>() == Some(&wasmtime::Trap::Interrupt);
+            if interrupted || tokio::time::Instant::now() >= deadline {
+                error.context(format!(
+                    "Timed out after {:?} while loading component from {}",
+                    limit,
+                    path.display()
+                ))
+            } else {
+                error
+            }
+        })
+}
+
 // Load components in parallel for improved startup performance
 async fn load_components_parallel(
     component_dir: &Path,
     runtime: Arc<RuntimeContext>,
+    load_timeout: Option<Duration>,
 ) -> Result<Vec<(ComponentInstance, String)>> {
     let mut entries = tokio::fs::read_dir(component_dir).await?;
     let mut load_futures = Vec::new();
@@ -1277,7 +3312,9 @@ async fn load_components_parallel(
     while let Some(entry) = entries.next_entry().await? {
         let runtime_clone = Arc::clone(&runtime);
         let future = async move {
-            match load_component_from_entry(runtime_clone, entry).await {
+            let entry_path = entry.path();
+            let load = load_component_from_entry(runtime_clone, entry);
+            match run_with_load_timeout(load_timeout, &entry_path, load).await {
                 Ok(Some(result)) => Some(Ok(result)),
                 Ok(None) => None,
                 Err(e) => Some(Err(e)),
@@ -1330,7 +3367,9 @@ impl LifecycleManager {
 
         self.secrets_manager
             .set_component_secrets(component_id, secrets)
-            .await
+            .await?;
+        self.restart_resident(component_id).await;
+        Ok(())
     }
 
     /// Delete secrets for a component
@@ -1341,9 +3380,78 @@ impl LifecycleManager {
     ) -> Result<()> {
         self.secrets_manager
             .delete_component_secrets(component_id, keys)
+            .await?;
+        self.restart_resident(component_id).await;
+        Ok(())
+    }
+
+    /// Export all secrets for a component as a portable bundle
+    pub async fn export_component_secrets(&self, component_id: &str) -> Result<SecretsBundle> {
+        self.secrets_manager
+            .export_component_secrets(component_id)
             .await
     }
 
+    /// Import secrets from a bundle into the component it was exported from
+    pub async fn import_component_secrets(&self, bundle: &SecretsBundle) -> Result<usize> {
+        // Check if component exists in the component directory
+        let component_path = self.component_path(&bundle.component_id);
+        if !component_path.exists() {
+            bail!("Component not found: {}", bundle.component_id);
+        }
+
+        let imported = self
+            .secrets_manager
+            .import_component_secrets(bundle)
+            .await?;
+        self.restart_resident(&bundle.component_id).await;
+        Ok(imported)
+    }
+
+    /// Get the non-secret environment variables configured for a component
+    pub async fn get_component_env(&self, component_id: &str) -> Result<BTreeMap<String, String>> {
+        self.storage.read_component_env(component_id).await
+    }
+
+    /// Set non-secret environment variables for a component.
+    ///
+    /// These are injected into the component's WASI environment on every call,
+    /// independently of the secrets store and environment permissions.
+    pub async fn set_component_env(
+        &self,
+        component_id: &str,
+        vars: &[(String, String)],
+    ) -> Result<()> {
+        let component_path = self.component_path(component_id);
+        if !component_path.exists() {
+            bail!("Component not found: {}", component_id);
+        }
+
+        let mut env = self.storage.read_component_env(component_id).await?;
+        env.extend(vars.iter().cloned());
+        self.storage.write_component_env(component_id, &env).await?;
+        self.restart_resident(component_id).await;
+
+        info!(component_id = %component_id, "Updated component environment");
+        Ok(())
+    }
+
+    /// Remove non-secret environment variables from a component
+    pub async fn unset_component_env(&self, component_id: &str, keys: &[String]) -> Result<()> {
+        let mut env = self.storage.read_component_env(component_id).await?;
+        for key in keys {
+            if env.remove(key).is_none() {
+                warn!(
+                    "Environment variable '{}' not set for component: {}",
+                    key, component_id
+                );
+            }
+        }
+        self.storage.write_component_env(component_id, &env).await?;
+        self.restart_resident(component_id).await;
+        Ok(())
+    }
+
     /// Load secrets for a component as environment variables
     pub async fn load_component_secrets(
         &self,
@@ -1398,6 +3506,7 @@ async fn load_component_from_entry(
     let instance_pre = runtime.instantiate_pre(&component)?;
     Ok(Some((
         ComponentInstance {
+            resident: Resident::for_component(&component, runtime.as_ref()),
             component: Arc::new(component),
             instance_pre: Arc::new(instance_pre),
             package_docs,
@@ -1441,89 +3550,2363 @@ mod tests {
     impl Deref for TestLifecycleManager {
         type Target = LifecycleManager;
 
-        fn deref(&self) -> &Self::Target {
-            &self.manager
-        }
-    }
+        fn deref(&self) -> &Self::Target {
+            &self.manager
+        }
+    }
+
+    pub(crate) async fn create_test_manager() -> Result<TestLifecycleManager> {
+        let tempdir = tempfile::tempdir()?;
+        let manager = LifecycleManager::new(&tempdir).await?;
+        Ok(TestLifecycleManager {
+            manager,
+            _tempdir: tempdir,
+        })
+    }
+
+    pub(crate) async fn build_example_component() -> Result<PathBuf> {
+        let cwd = std::env::current_dir()?;
+        println!("CWD: {}", cwd.display());
+        let component_path =
+            cwd.join("../../examples/fetch-rs/target/wasm32-wasip2/release/fetch_rs.wasm");
+
+        if !component_path.exists() {
+            let status = Command::new("cargo")
+                .current_dir(cwd.join("../../examples/fetch-rs"))
+                .args(["build", "--release", "--target", "wasm32-wasip2"])
+                .status()
+                .context("Failed to execute cargo component build")?;
+
+            if !status.success() {
+                anyhow::bail!("Failed to compile fetch-rs component");
+            }
+        }
+
+        if !component_path.exists() {
+            anyhow::bail!(
+                "Component file not found after build: {}",
+                component_path.display()
+            );
+        }
+
+        Ok(component_path)
+    }
+
+    #[test(tokio::test)]
+    async fn test_lifecycle_manager_tool_registry() -> Result<()> {
+        let manager = create_test_manager().await?;
+
+        let temp_dir = tempfile::tempdir()?;
+        let component_path = temp_dir.path().join("mock_component.wasm");
+        std::fs::write(&component_path, b"mock wasm bytes")?;
+
+        let load_result = manager
+            .load_component(component_path.to_str().unwrap())
+            .await;
+        assert!(load_result.is_err()); // Expected since we're using invalid WASM
+
+        let lookup_result = manager.get_component_id_for_tool("non-existent").await;
+        assert!(lookup_result.is_err());
+
+        Ok(())
+    }
+
+    fn fake_tool(name: &str) -> ToolMetadata {
+        ToolMetadata {
+            identifier: FunctionIdentifier {
+                package_name: None,
+                interface_name: None,
+                function_name: name.to_string(),
+            },
+            normalized_name: name.to_string(),
+            schema: serde_json::json!({ "name": name }),
+        }
+    }
+
+    #[test]
+    fn test_tool_index_tracks_loads_and_unloads() {
+        let mut state = ComponentRegistryState::default();
+        state.register_tools_only("a", vec![fake_tool("get"), fake_tool("put")]);
+        state.register_tools_only("b", vec![fake_tool("list")]);
+
+        assert_eq!(state.component_ids_for_tool("get"), Some(vec!["a".into()]));
+        assert_eq!(state.component_ids_for_tool("list"), Some(vec!["b".into()]));
+
+        state.register_tools_only("c", vec![fake_tool("get")]);
+        assert_eq!(
+            state.component_ids_for_tool("get"),
+            Some(vec!["a".to_string(), "c".to_string()])
+        );
+
+        state.unregister_component("a");
+        assert_eq!(state.component_ids_for_tool("get"), Some(vec!["c".into()]));
+        assert_eq!(state.component_ids_for_tool("put"), None);
+        assert!(!state.tool_map.contains_key("put"));
+
+        state.unregister_component("c");
+        assert_eq!(state.component_ids_for_tool("get"), None);
+        assert_eq!(state.component_ids_for_tool("list"), Some(vec!["b".into()]));
+    }
+
+    #[test(tokio::test)]
+    async fn test_tool_routing_indexes_many_components() -> Result<()> {
+        const COMPONENTS: usize = 500;
+        const TOOLS_PER_COMPONENT: usize = 20;
+
+        let manager = create_test_manager().await?;
+        {
+            let mut state = manager.registry.state.write().await;
+            for c in 0..COMPONENTS {
+                let tools = (0..TOOLS_PER_COMPONENT)
+                    .map(|t| fake_tool(&format!("c{c}-t{t}")))
+                    .collect();
+                state.register_tools_only(&format!("component-{c}"), tools);
+            }
+            state.register_tools_only("shadow", vec![fake_tool("c7-t3")]);
+
+            // Lookups go through one index entry per tool name holding only the components
+            // providing it, rather than a scan over every registered tool
+            assert_eq!(state.tool_map.len(), COMPONENTS * TOOLS_PER_COMPONENT);
+            for (tool_name, infos) in &state.tool_map {
+                let expected = if tool_name == "c7-t3" { 2 } else { 1 };
+                assert_eq!(infos.len(), expected, "{tool_name}");
+            }
+            assert_eq!(state.component_map["shadow"], ["c7-t3"]);
+        }
+
+        for c in 0..COMPONENTS {
+            let id = manager
+                .get_component_id_for_tool(&format!("c{c}-t{}", c % TOOLS_PER_COMPONENT))
+                .await?;
+            assert_eq!(id, format!("component-{c}"));
+        }
+
+        let err = manager
+            .get_component_id_for_tool("c7-t3")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("component-7, shadow"));
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_new_manager() -> Result<()> {
+        let _manager = create_test_manager().await?;
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_load_and_unload_component() -> Result<()> {
+        let manager = create_test_manager().await?;
+
+        let load_result = manager.load_component("/path/to/nonexistent").await;
+        assert!(load_result.is_err());
+
+        manager.load_test_component().await?;
+
+        let loaded_components = manager.list_components().await;
+        assert_eq!(loaded_components.len(), 1);
+
+        assert_eq!(
+            manager.unload_component(TEST_COMPONENT_ID).await?,
+            UnloadResult::Unloaded
+        );
+
+        let loaded_components = manager.list_components().await;
+        assert!(loaded_components.is_empty());
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_concurrent_loads_of_same_component_are_serialized() -> Result<()> {
+        let manager = create_test_manager().await?;
+        let component_path = build_example_component().await?;
+        let uri = format!("file://{}", component_path.to_str().unwrap());
+
+        let (first, second) =
+            tokio::join!(manager.load_component(&uri), manager.load_component(&uri));
+        let mut statuses = vec![first?.status, second?.status];
+        statuses.sort_by_key(|status| matches!(status, LoadResult::Unchanged));
+        assert_eq!(statuses, vec![LoadResult::New, LoadResult::Unchanged]);
+
+        assert_eq!(manager.list_components().await, vec![TEST_COMPONENT_ID]);
+        let artifacts: Vec<_> = std::fs::read_dir(manager.component_root())?
+            .flatten()
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "wasm"))
+            .collect();
+        assert_eq!(artifacts.len(), 1);
+
+        let result = manager
+            .execute_component_call(
+                TEST_COMPONENT_ID,
+                "fetch",
+                r#"{"url": "https://example.com"}"#,
+            )
+            .await;
+        if let Err(e) = result {
+            assert!(!e.to_string().contains("Component not found"));
+        }
+
+        Ok(())
+    }
+
+    /// A component whose `log` function writes three lines to stdout through
+    /// `wasi:cli/stdout` and returns how many it wrote.
+    fn logging_component(dir: &Path) -> Result<PathBuf> {
+        logging_component_on(dir, "stdout")
+    }
+
+    /// [`logging_component`] writing to `stream`, `stdout` or `stderr`
+    fn logging_component_on(dir: &Path, stream: &str) -> Result<PathBuf> {
+        let wat = r#"(component
+                (import "wasi:io/error@0.2.0" (instance $error
+                    (export "error" (type (sub resource)))
+                ))
+                (alias export $error "error" (type $error-type))
+                (import "wasi:io/streams@0.2.0" (instance $streams
+                    (export "output-stream" (type $os (sub resource)))
+                    (alias outer 1 $error-type (type $err))
+                    (export "error" (type $err-e (eq $err)))
+                    (type $stream-error (variant (case "last-operation-failed" (own $err-e)) (case "closed")))
+                    (export "stream-error" (type $se (eq $stream-error)))
+                    (export "[method]output-stream.blocking-write-and-flush"
+                        (func (param "self" (borrow $os)) (param "contents" (list u8)) (result (result (error $se)))))
+                ))
+                (alias export $streams "output-stream" (type $output-stream))
+                (import "wasi:cli/stdout@0.2.0" (instance $stdout
+                    (alias outer 1 $output-stream (type $os))
+                    (export "output-stream" (type $os-e (eq $os)))
+                    (export "get-stdout" (func (result (own $os-e))))
+                ))
+                (core module $memory (memory (export "memory") 1))
+                (core instance $memory (instantiate $memory))
+                (alias core export $memory "memory" (core memory $mem))
+                (core func $get-stdout (canon lower (func $stdout "get-stdout")))
+                (core func $write
+                    (canon lower (func $streams "[method]output-stream.blocking-write-and-flush") (memory $mem)))
+                (core func $drop (canon resource.drop $output-stream))
+                (core module $m
+                    (import "env" "memory" (memory 1))
+                    (import "host" "get-stdout" (func $get-stdout (result i32)))
+                    (import "host" "write" (func $write (param i32 i32 i32 i32)))
+                    (import "host" "drop" (func $drop (param i32)))
+                    (data (i32.const 16) "first\nsecond\nthird\n")
+                    (func (export "log") (result i32)
+                        (local $out i32)
+                        (local.set $out (call $get-stdout))
+                        (call $write (local.get $out) (i32.const 16) (i32.const 19) (i32.const 64))
+                        (call $drop (local.get $out))
+                        (i32.const 3))
+                )
+                (core instance $i (instantiate $m
+                    (with "env" (instance $memory))
+                    (with "host" (instance
+                        (export "get-stdout" (func $get-stdout))
+                        (export "write" (func $write))
+                        (export "drop" (func $drop))
+                    ))
+                ))
+                (func $log (result u32) (canon lift (core func $i "log")))
+                (export "log" (func $log))
+            )"#
+        .replace("stdout", stream);
+        let path = dir.join("logger.wasm");
+        std::fs::write(&path, wat::parse_str(wat)?)?;
+        Ok(path)
+    }
+
+    /// Component exposing a `boom` tool that traps two frames deep
+    fn trapping_component(dir: &Path) -> Result<PathBuf> {
+        let wat = r#"(component
+                (core module $m
+                    (func $inner unreachable)
+                    (func $boom (export "boom") (result i32)
+                        (call $inner)
+                        (i32.const 0))
+                )
+                (core instance $i (instantiate $m))
+                (func $boom (result u32) (canon lift (core func $i "boom")))
+                (export "boom" (func $boom))
+            )"#;
+        let path = dir.join("crashy.wasm");
+        std::fs::write(&path, wat::parse_str(wat)?)?;
+        Ok(path)
+    }
+
+    #[test(tokio::test)]
+    async fn test_component_trap_is_classified_with_backtrace() -> Result<()> {
+        let manager = create_test_manager().await?;
+        let source_dir = tempfile::tempdir()?;
+        let path = trapping_component(source_dir.path())?;
+        manager
+            .load_component(&format!("file://{}", path.display()))
+            .await?;
+
+        let err = manager
+            .execute_component_call("crashy", "boom", "{}")
+            .await
+            .unwrap_err();
+        let trap = err
+            .downcast_ref::<ComponentTrap>()
+            .expect("a trapping call should be classified as a trap");
+        assert_eq!(trap.component_id, "crashy");
+        assert_eq!(trap.tool, "boom");
+        assert!(trap.message.contains("unreachable"), "{trap}");
+        assert!(
+            trap.backtrace.iter().any(|frame| frame.contains("inner")),
+            "{trap}"
+        );
+        assert!(err.to_string().contains("wasm backtrace:"));
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_large_integer_arguments_reach_component_exactly() -> Result<()> {
+        let manager = create_test_manager().await?;
+        let source_dir = tempfile::tempdir()?;
+        let wat = r#"(component
+                (core module $m (func (export "echo") (param i64) (result i64) (local.get 0)))
+                (core instance $i (instantiate $m))
+                (func $echo (param "id" u64) (result u64) (canon lift (core func $i "echo")))
+                (export "echo" (func $echo))
+            )"#;
+        let path = source_dir.path().join("echo.wasm");
+        std::fs::write(&path, wat::parse_str(wat)?)?;
+        manager
+            .load_component(&format!("file://{}", path.display()))
+            .await?;
+
+        // 2^53 + 1 would come back as 9007199254740992 after a round trip through f64
+        let result = manager
+            .execute_component_call("echo", "echo", r#"{"id": 9007199254740993}"#)
+            .await?;
+        assert_eq!(result, r#"{"result":9007199254740993}"#);
+
+        let result = manager
+            .execute_component_call("echo", "echo", r#"{"id": 18446744073709551615}"#)
+            .await?;
+        assert_eq!(result, r#"{"result":18446744073709551615}"#);
+
+        // Clients limited to doubles send it as a string, which must not be rounded either
+        let result = manager
+            .execute_component_call("echo", "echo", r#"{"id": "9007199254740993"}"#)
+            .await?;
+        assert_eq!(result, r#"{"result":9007199254740993}"#);
+
+        // A float is refused rather than truncated or rounded
+        assert!(manager
+            .execute_component_call("echo", "echo", r#"{"id": 1e3}"#)
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_load_component_from_git_reference() -> Result<()> {
+        let git = |dir: &Path, args: &[&str]| -> Result<()> {
+            let status = Command::new("git")
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .current_dir(dir)
+                .status()?;
+            anyhow::ensure!(status.success(), "git {args:?} failed");
+            Ok(())
+        };
+
+        // Commit a component to a work tree and push it to a bare repository
+        let root = tempfile::tempdir()?;
+        let work = root.path().join("work");
+        let bare = root.path().join("tools.git");
+        std::fs::create_dir_all(work.join("tools"))?;
+        trapping_component(&work.join("tools"))?;
+        // A committed link to a file outside the repository must not be followed
+        #[cfg(unix)]
+        {
+            trapping_component(root.path())?;
+            std::os::unix::fs::symlink(
+                root.path().join("crashy.wasm"),
+                work.join("tools/escape.wasm"),
+            )?;
+        }
+        git(
+            root.path(),
+            &["init", "--quiet", "--bare", bare.to_str().unwrap()],
+        )?;
+        git(&work, &["init", "--quiet"])?;
+        git(&work, &["add", "."])?;
+        git(&work, &["commit", "--quiet", "-m", "Add crashy"])?;
+        git(&work, &["tag", "v1"])?;
+        git(
+            &work,
+            &[
+                "push",
+                "--quiet",
+                bare.to_str().unwrap(),
+                "HEAD:refs/heads/main",
+                "v1",
+            ],
+        )?;
+
+        let manager = create_test_manager().await?;
+        let uri = format!("git+file://{}//tools/crashy.wasm@v1", bare.display());
+        let outcome = manager.load_component(&uri).await?;
+        assert_eq!(outcome.component_id, "crashy");
+        assert_eq!(outcome.tool_names, ["boom"]);
+
+        // The same artifact at another ref is an upgrade of the same source
+        let uri = format!("git+file://{}//tools/crashy.wasm@main", bare.display());
+        manager.load_component(&uri).await?;
+
+        let missing = format!("git+file://{}//tools/missing.wasm@v1", bare.display());
+        let err = manager.load_component(&missing).await.unwrap_err();
+        assert!(format!("{err:#}").contains("does not exist"), "{err:#}");
+
+        #[cfg(unix)]
+        {
+            let escape = format!("git+file://{}//tools/escape.wasm@v1", bare.display());
+            let err = manager.load_component(&escape).await.unwrap_err();
+            assert!(
+                format!("{err:#}").contains("pointing outside the repository"),
+                "{err:#}"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_declared_permissions_reported_and_auto_granted() -> Result<()> {
+        let source_dir = tempfile::tempdir()?;
+        let wat = r#"(component
+                (@custom "wassette:permissions" "{\"network\": [{\"host\": \"api.example.com\"}]}")
+                (core module $m (func (export "ping") (result i32) (i32.const 1)))
+                (core instance $i (instantiate $m))
+                (func $ping (result u32) (canon lift (core func $i "ping")))
+                (export "ping" (func $ping))
+            )"#;
+        let path = source_dir.path().join("pinger.wasm");
+        std::fs::write(&path, wat::parse_str(wat)?)?;
+        let uri = format!("file://{}", path.display());
+        let expected = vec![DeclaredPermission {
+            permission_type: "network".to_string(),
+            details: serde_json::json!({ "host": "api.example.com" }),
+        }];
+
+        // Declared permissions are reported but not granted by default
+        let manager = create_test_manager().await?;
+        let outcome = manager.load_component(&uri).await?;
+        assert_eq!(outcome.declared_permissions, expected);
+        assert!(outcome.auto_granted.is_empty());
+        let policy_path = manager.get_component_policy_path("pinger");
+        let policy = tokio::fs::read_to_string(&policy_path)
+            .await
+            .unwrap_or_default();
+        assert!(!policy.contains("api.example.com"));
+
+        let component_dir = tempfile::tempdir()?;
+        let manager = LifecycleManager::builder(component_dir.path())
+            .with_auto_grant_declared(true)
+            .build()
+            .await?;
+        let outcome = manager.load_component(&uri).await?;
+        assert_eq!(outcome.declared_permissions, expected);
+        assert_eq!(outcome.auto_granted, expected);
+        let policy = tokio::fs::read_to_string(manager.get_component_policy_path("pinger")).await?;
+        assert!(policy.contains("api.example.com"));
+
+        // A revoked declared permission stays revoked across reloads and replacements
+        manager
+            .revoke_permission(
+                "pinger",
+                "network",
+                &serde_json::json!({ "host": "api.example.com" }),
+            )
+            .await?;
+        let outcome = manager.load_component(&uri).await?;
+        assert!(outcome.auto_granted.is_empty());
+        let outcome = manager
+            .load_component_with(
+                &uri,
+                LoadOptions {
+                    component_id: Some("pinger".to_string()),
+                    force: true,
+                    force_recompile: true,
+                },
+            )
+            .await?;
+        assert!(outcome.auto_granted.is_empty());
+        let manager = LifecycleManager::builder(component_dir.path())
+            .with_auto_grant_declared(true)
+            .build()
+            .await?;
+        assert!(manager
+            .list_components()
+            .await
+            .contains(&"pinger".to_string()));
+        let policy = tokio::fs::read_to_string(manager.get_component_policy_path("pinger")).await?;
+        assert!(!policy.contains("api.example.com"), "{policy}");
+
+        // Uninstalling forgets the record, so a fresh install is granted again
+        manager.unload_component("pinger").await?;
+        let outcome = manager.load_component(&uri).await?;
+        assert_eq!(outcome.auto_granted, expected);
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_explain_permission_of_installed_component() -> Result<()> {
+        let source_dir = tempfile::tempdir()?;
+        let component_dir = tempfile::tempdir()?;
+        let uri = format!("file://{}", logging_component(source_dir.path())?.display());
+        let manager = LifecycleManager::new(component_dir.path()).await?;
+        manager.load_component(&uri).await?;
+        for host in ["localhost", "203.0.113.7"] {
+            manager
+                .grant_permission("logger", "network", &serde_json::json!({ "host": host }))
+                .await?;
+        }
+        let network = |host: &str| PermissionQuery::Network {
+            host: host.to_string(),
+        };
+
+        // Built the way `wassette permission explain` builds it, with nothing loaded
+        let manager = LifecycleManager::new_unloaded(component_dir.path()).await?;
+        assert!(!manager.registry.contains_component("logger").await);
+        let explanation = manager
+            .explain_permission("logger", &network("203.0.113.7"))
+            .await?;
+        assert!(explanation.allowed, "{}", explanation.reason);
+
+        // Granted, but refused at request time because the name resolves to loopback
+        let explanation = manager
+            .explain_permission("logger", &network("localhost"))
+            .await?;
+        assert!(!explanation.allowed);
+        assert!(explanation.matched_rule.is_some());
+        assert!(
+            explanation.reason.contains("internal address"),
+            "{}",
+            explanation.reason
+        );
+        assert!(manager
+            .explain_permission("missing", &network("localhost"))
+            .await
+            .is_err());
+
+        let manager = LifecycleManager::builder(component_dir.path())
+            .with_eager_loading(false)
+            .with_outbound_http(OutboundHttpConfig {
+                allow_internal_addresses: true,
+                ..Default::default()
+            })
+            .build()
+            .await?;
+        let explanation = manager
+            .explain_permission("logger", &network("localhost"))
+            .await?;
+        assert!(explanation.allowed, "{}", explanation.reason);
+        Ok(())
+    }
+
+    /// Write `writer.wasm`, whose `write` tool creates `probe.txt` in its first preopened
+    /// directory and returns 0, or 1 plus the `wasi:filesystem` error code when that fails
+    fn file_writer_component(dir: &Path) -> Result<PathBuf> {
+        let wat = r#"(component
+                (import "wasi:filesystem/types@0.2.0" (instance $types
+                    (export "descriptor" (type $d (sub resource)))
+                    (type $df (flags "read" "write" "file-integrity-sync" "data-integrity-sync" "requested-write-sync" "mutate-directory"))
+                    (export "descriptor-flags" (type $df-e (eq $df)))
+                    (type $pf (flags "symlink-follow"))
+                    (export "path-flags" (type $pf-e (eq $pf)))
+                    (type $of (flags "create" "directory" "exclusive" "truncate"))
+                    (export "open-flags" (type $of-e (eq $of)))
+                    (type $ec (enum "access" "would-block" "already" "bad-descriptor" "busy" "deadlock" "quota" "exist" "file-too-large" "illegal-byte-sequence" "in-progress" "interrupted" "invalid" "io" "is-directory" "loop" "too-many-links" "message-size" "name-too-long" "no-device" "no-entry" "no-lock" "insufficient-memory" "insufficient-space" "not-directory" "not-empty" "not-recoverable" "unsupported" "no-tty" "no-such-device" "overflow" "not-permitted" "pipe" "read-only" "invalid-seek" "text-file-busy" "cross-device"))
+                    (export "error-code" (type $ec-e (eq $ec)))
+                    (export "[method]descriptor.open-at"
+                        (func (param "self" (borrow $d)) (param "path-flags" $pf-e) (param "path" string)
+                            (param "open-flags" $of-e) (param "flags" $df-e) (result (result (own $d) (error $ec-e)))))
+                ))
+                (alias export $types "descriptor" (type $descriptor))
+                (import "wasi:filesystem/preopens@0.2.0" (instance $preopens
+                    (alias outer 1 $descriptor (type $d))
+                    (export "descriptor" (type $d-e (eq $d)))
+                    (export "get-directories" (func (result (list (tuple (own $d-e) string)))))
+                ))
+                (core module $memory
+                    (memory (export "memory") 1)
+                    (global $next (mut i32) (i32.const 1024))
+                    (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+                        (local $ptr i32)
+                        (local.set $ptr (global.get $next))
+                        (global.set $next
+                            (i32.and (i32.add (i32.add (local.get $ptr) (local.get 3)) (i32.const 7)) (i32.const -8)))
+                        (local.get $ptr))
+                )
+                (core instance $memory (instantiate $memory))
+                (alias core export $memory "memory" (core memory $mem))
+                (alias core export $memory "realloc" (core func $realloc))
+                (core func $get-directories
+                    (canon lower (func $preopens "get-directories") (memory $mem) (realloc $realloc)))
+                (core func $open-at
+                    (canon lower (func $types "[method]descriptor.open-at") (memory $mem)))
+                (core module $m
+                    (import "env" "memory" (memory 1))
+                    (import "host" "get-directories" (func $get-directories (param i32)))
+                    (import "host" "open-at" (func $open-at (param i32 i32 i32 i32 i32 i32 i32)))
+                    (data (i32.const 256) "probe.txt")
+                    ;; Create probe.txt in the first preopened directory: 0 on success, else 1 + error code
+                    (func (export "write") (result i32)
+                        (call $get-directories (i32.const 0))
+                        (call $open-at
+                            (i32.load (i32.load (i32.const 0)))
+                            (i32.const 0)
+                            (i32.const 256) (i32.const 9)
+                            (i32.const 1)
+                            (i32.const 2)
+                            (i32.const 16))
+                        (if (result i32) (i32.load8_u (i32.const 16))
+                            (then (i32.add (i32.load8_u (i32.const 20)) (i32.const 1)))
+                            (else (i32.const 0))))
+                )
+                (core instance $i (instantiate $m
+                    (with "env" (instance $memory))
+                    (with "host" (instance
+                        (export "get-directories" (func $get-directories))
+                        (export "open-at" (func $open-at))
+                    ))
+                ))
+                (func $write (result u32) (canon lift (core func $i "write")))
+                (export "write" (func $write))
+            )"#;
+        let path = dir.join("writer.wasm");
+        std::fs::write(&path, wat::parse_str(wat)?)?;
+        Ok(path)
+    }
+
+    #[test(tokio::test)]
+    async fn test_read_only_storage_grant_denies_writes() -> Result<()> {
+        let manager = create_test_manager().await?;
+        let source_dir = tempfile::tempdir()?;
+        let path = file_writer_component(source_dir.path())?;
+        manager
+            .load_component(&format!("file://{}", path.display()))
+            .await?;
+        let data_dir = tempfile::tempdir()?;
+        let uri = format!("fs://{}", data_dir.path().display());
+
+        manager
+            .grant_permission(
+                "writer",
+                "storage",
+                &serde_json::json!({ "uri": uri, "access": ["read"] }),
+            )
+            .await?;
+        let result = manager
+            .execute_component_call("writer", "write", "{}")
+            .await?;
+        assert_ne!(
+            result, r#"{"result":0}"#,
+            "a read-only grant must not allow creating files"
+        );
+        assert!(!data_dir.path().join("probe.txt").exists());
+
+        manager
+            .grant_permission(
+                "writer",
+                "storage",
+                &serde_json::json!({ "uri": uri, "access": ["write"] }),
+            )
+            .await?;
+        let result = manager
+            .execute_component_call("writer", "write", "{}")
+            .await?;
+        assert_eq!(result, r#"{"result":0}"#);
+        assert!(data_dir.path().join("probe.txt").exists());
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_diff_component_compares_compiled_versions() -> Result<()> {
+        let manager = create_test_manager().await?;
+        let installed = r#"(component
+                (import "wasi:cli/environment@0.2.0" (instance))
+                (core module $m
+                    (func (export "ping") (result i32) (i32.const 1))
+                    (func (export "legacy"))
+                )
+                (core instance $i (instantiate $m))
+                (func $ping (result u32) (canon lift (core func $i "ping")))
+                (func $legacy (canon lift (core func $i "legacy")))
+                (export "ping" (func $ping))
+                (export "legacy" (func $legacy))
+            )"#;
+        std::fs::write(manager.component_path("pinger"), wat::parse_str(installed)?)?;
+
+        // Takes an argument in `ping`, replaces `legacy` with `pong` and needs storage
+        let candidate = r#"(component
+                (import "wasi:cli/environment@0.2.0" (instance))
+                (import "wasi:filesystem/preopens@0.2.0" (instance))
+                (core module $m
+                    (func (export "ping") (param i32) (result i32) (local.get 0))
+                    (func (export "pong"))
+                )
+                (core instance $i (instantiate $m))
+                (func $ping (param "times" u32) (result u32) (canon lift (core func $i "ping")))
+                (func $pong (canon lift (core func $i "pong")))
+                (export "ping" (func $ping))
+                (export "pong" (func $pong))
+            )"#;
+        let source_dir = tempfile::tempdir()?;
+        let path = source_dir.path().join("pinger.wasm");
+        std::fs::write(&path, wat::parse_str(candidate)?)?;
+
+        let diff = manager
+            .diff_component("pinger", &format!("file://{}", path.display()))
+            .await?;
+        assert_eq!(diff.added_tools, vec!["pong"]);
+        assert_eq!(diff.removed_tools, vec!["legacy"]);
+        assert_eq!(diff.changed_tools, vec!["ping"]);
+        assert_eq!(diff.new_capabilities, vec!["storage"]);
+        assert!(diff.dropped_capabilities.is_empty());
+
+        // Comparing the installed version with itself finds nothing
+        let unchanged = manager
+            .diff_component(
+                "pinger",
+                &format!("file://{}", manager.component_path("pinger").display()),
+            )
+            .await?;
+        assert!(unchanged.is_empty(), "{unchanged:?}");
+        // Nothing was installed
+        assert!(manager.list_components().await.is_empty());
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_verify_component_reports_unsatisfied_imports() -> Result<()> {
+        let manager = create_test_manager().await?;
+
+        let fetch = build_example_component().await?;
+        let verification = manager
+            .verify_component(&format!("file://{}", fetch.display()))
+            .await?;
+        assert!(verification.compatible, "{verification:?}");
+        assert!(verification.unsatisfied_imports.is_empty());
+        assert!(manager.list_components().await.is_empty());
+
+        let source_dir = tempfile::tempdir()?;
+        let wat = r#"(component
+                (import "acme:missing/store@1.0.0" (instance $store
+                    (export "get" (func (param "key" string) (result string)))
+                ))
+                (import "acme:missing/clock@1.0.0" (instance $clock
+                    (export "now" (func (result u64)))
+                ))
+            )"#;
+        let path = source_dir.path().join("unlinkable.wasm");
+        std::fs::write(&path, wat::parse_str(wat)?)?;
+        let verification = manager
+            .verify_component(&format!("file://{}", path.display()))
+            .await?;
+        assert!(!verification.compatible);
+        let missing: Vec<&str> = verification
+            .unsatisfied_imports
+            .iter()
+            .map(|import| import.name.as_str())
+            .collect();
+        assert_eq!(
+            missing,
+            vec!["acme:missing/store@1.0.0", "acme:missing/clock@1.0.0"]
+        );
+        assert!(manager.list_components().await.is_empty());
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_compilation_strategies_share_precompiled_cache() -> Result<()> {
+        let component_dir = tempfile::tempdir()?;
+        logging_component(component_dir.path())?;
+
+        // Switching strategy recompiles instead of reusing the other strategy's artifact
+        for strategy in [CompilationStrategy::Fast, CompilationStrategy::Optimized] {
+            let manager = LifecycleManager::builder(component_dir.path())
+                .with_compilation_strategy(strategy)
+                .build()
+                .await?;
+            assert_eq!(
+                manager
+                    .execute_component_call("logger", "log", "{}")
+                    .await?,
+                r#"{"result":3}"#
+            );
+            assert!(manager.component_precompiled_path("logger").exists());
+            assert_eq!(
+                manager.storage.read_precompiled_strategy("logger").await,
+                strategy
+            );
+        }
+
+        assert_eq!(
+            "fast".parse::<CompilationStrategy>()?,
+            CompilationStrategy::Fast
+        );
+        assert!("speed".parse::<CompilationStrategy>().is_err());
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_force_recompile_replaces_corrupt_precompiled_cache() -> Result<()> {
+        let component_dir = tempfile::tempdir()?;
+        let path = logging_component(component_dir.path())?;
+        let manager = LifecycleManager::builder(component_dir.path())
+            .build()
+            .await?;
+        manager
+            .execute_component_call("logger", "log", "{}")
+            .await?;
+        let precompiled_path = manager.component_precompiled_path("logger");
+        assert!(precompiled_path.exists());
+
+        // A loadable artifact of another component, which only bypassing the cache detects
+        let other_dir = tempfile::tempdir()?;
+        let other = file_writer_component(other_dir.path())?;
+        let corrupt = manager
+            .runtime
+            .precompile_component(&std::fs::read(other)?)?;
+        std::fs::write(&precompiled_path, &corrupt)?;
+
+        // Reloading with force_recompile compiles the installed file and rewrites the cache
+        let uri = format!("file://{}", path.display());
+        manager
+            .load_component_with(
+                &uri,
+                LoadOptions {
+                    force_recompile: true,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        assert_eq!(
+            manager
+                .execute_component_call("logger", "log", "{}")
+                .await?,
+            r#"{"result":3}"#
+        );
+        assert_ne!(std::fs::read(&precompiled_path)?, corrupt);
+        Ok(())
+    }
+
+    #[test(tokio::test(start_paused = true))]
+    async fn test_idle_components_unloaded_and_reloaded_on_call() -> Result<()> {
+        let component_dir = tempfile::tempdir()?;
+        logging_component(component_dir.path())?;
+
+        let manager = LifecycleManager::builder(component_dir.path())
+            .with_idle_unload_ttl(Duration::from_secs(60))
+            .build()
+            .await?;
+        assert_eq!(manager.list_components().await, vec!["logger".to_string()]);
+
+        tokio::time::sleep(Duration::from_secs(45)).await;
+        assert_eq!(manager.list_components().await, vec!["logger".to_string()]);
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        assert!(manager.list_components().await.is_empty());
+        // Only the engine instance is dropped; files and tools stay
+        assert!(manager.component_path("logger").exists());
+        assert!(!manager.list_tools().await.is_empty());
+
+        assert_eq!(
+            manager
+                .execute_component_call("logger", "log", "{}")
+                .await?,
+            r#"{"result":3}"#
+        );
+        assert_eq!(manager.list_components().await, vec!["logger".to_string()]);
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_captured_logs_stay_within_total_budget() -> Result<()> {
+        let component_dir = tempfile::tempdir()?;
+        let source_dir = tempfile::tempdir()?;
+        let logger = logging_component(source_dir.path())?;
+        let manager = LifecycleManager::builder(component_dir.path())
+            .with_max_total_log_bytes(Some(40))
+            .build()
+            .await?;
+
+        let ids = ["logger-a", "logger-b", "logger-c"];
+        for id in ids {
+            let path = source_dir.path().join(format!("{id}.wasm"));
+            std::fs::copy(&logger, &path)?;
+            manager
+                .load_component(&format!("file://{}", path.display()))
+                .await?;
+        }
+        // Each call writes 16 bytes, so the components write 96 bytes in total
+        for _ in 0..2 {
+            for id in ids {
+                manager.execute_component_call(id, "log", "{}").await?;
+            }
+        }
+
+        let mut total = 0;
+        for id in ids {
+            total += manager
+                .get_component_logs(id, None)
+                .await
+                .iter()
+                .map(|line| line.line.len())
+                .sum::<usize>();
+        }
+        assert!(total <= 40, "{total} bytes of logs are buffered");
+        assert_eq!(total, manager.logs.total_bytes());
+
+        // The most recent output is what remains
+        let last: Vec<String> = manager
+            .get_component_logs("logger-c", None)
+            .await
+            .into_iter()
+            .map(|line| line.line)
+            .collect();
+        assert_eq!(last, vec!["first", "second", "third"]);
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_pooling_allocator_runs_components_and_reports_full_pool() -> Result<()> {
+        let component_dir = tempfile::tempdir()?;
+        logging_component(component_dir.path())?;
+        let manager = LifecycleManager::builder(component_dir.path())
+            .with_pooling_allocator(PoolingAllocatorConfig {
+                max_component_instances: 1,
+                max_core_instances: 16,
+                max_memories: 4,
+                max_tables: 16,
+                max_memory_size: 64 << 20,
+            })
+            .build()
+            .await?;
+        // Policy memory limits apply alongside the pool's own
+        manager
+            .grant_permission(
+                "logger",
+                "resource",
+                &serde_json::json!({"limits": {"memory": "32Mi"}}),
+            )
+            .await?;
+
+        // Slots are returned to the pool after each call
+        for _ in 0..3 {
+            assert_eq!(
+                manager
+                    .execute_component_call("logger", "log", "{}")
+                    .await?,
+                r#"{"result":3}"#
+            );
+        }
+
+        // Hold the only slot while another call needs one
+        let component = manager.get_component("logger").await.unwrap();
+        let mut store = manager.new_component_store("logger", None).await?;
+        let _held = instantiate(&component, &mut store).await?;
+        let err = manager
+            .execute_component_call("logger", "log", "{}")
+            .await
+            .unwrap_err();
+        assert!(
+            format!("{err:#}").contains("Every pooled component instance is in use"),
+            "unexpected error: {err:#}"
+        );
+
+        drop(store);
+        assert_eq!(
+            manager
+                .execute_component_call("logger", "log", "{}")
+                .await?,
+            r#"{"result":3}"#
+        );
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_missing_component_dir_is_created_unless_disabled() -> Result<()> {
+        let parent = tempfile::tempdir()?;
+        let missing = parent.path().join("missing-components");
+
+        let err = LifecycleManager::builder(&missing)
+            .with_create_component_dir(false)
+            .build()
+            .await
+            .err()
+            .expect("a missing component directory should be rejected");
+        assert!(
+            err.to_string().contains("does not exist"),
+            "unexpected error: {err:#}"
+        );
+        assert!(!missing.exists(), "the directory must not be created");
+
+        LifecycleManager::builder(&missing).build().await?;
+        assert!(missing.is_dir());
+
+        // An existing directory is accepted either way
+        LifecycleManager::builder(&missing)
+            .with_create_component_dir(false)
+            .build()
+            .await?;
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_rescan_loads_added_and_drops_removed_components() -> Result<()> {
+        let component_dir = tempfile::tempdir()?;
+        let logger = logging_component(component_dir.path())?;
+        let manager = LifecycleManager::new(&component_dir).await?;
+        assert_eq!(manager.rescan_components().await?, RescanOutcome::default());
+
+        std::fs::copy(&logger, component_dir.path().join("second.wasm"))?;
+        let outcome = manager.rescan_components().await?;
+        assert_eq!(outcome.loaded, vec!["second".to_string()]);
+        assert!(outcome.removed.is_empty());
+        assert_eq!(
+            manager
+                .execute_component_call("second", "log", "{}")
+                .await?,
+            r#"{"result":3}"#
+        );
+
+        std::fs::remove_file(&logger)?;
+        let outcome = manager.rescan_components().await?;
+        assert!(outcome.loaded.is_empty());
+        assert_eq!(outcome.removed, vec!["logger".to_string()]);
+        assert_eq!(manager.list_components().await, vec!["second".to_string()]);
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_startup_load_policy() -> Result<()> {
+        let component_dir = tempfile::tempdir()?;
+        logging_component(component_dir.path())?;
+        std::fs::write(component_dir.path().join("broken.wasm"), b"not a component")?;
+
+        let manager = LifecycleManager::new_unloaded(&component_dir).await?;
+        manager
+            .load_existing_components_async(None, None::<fn()>, StartupLoadPolicy::Continue)
+            .await?;
+        assert_eq!(manager.list_components().await, vec!["logger".to_string()]);
+
+        let manager = LifecycleManager::new_unloaded(&component_dir).await?;
+        let err = manager
+            .load_existing_components_async(None, None::<fn()>, StartupLoadPolicy::FailFast)
+            .await
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Stopped loading components at the first failure"));
+        assert!(err.to_string().contains("broken.wasm"));
+
+        // Loading one component at a time, nothing is attempted after the first failure
+        std::fs::write(
+            component_dir.path().join("broken-too.wasm"),
+            b"not a component",
+        )?;
+        let manager = LifecycleManager::new_unloaded(&component_dir).await?;
+        manager
+            .load_existing_components_async(Some(1), None::<fn()>, StartupLoadPolicy::FailFast)
+            .await
+            .unwrap_err();
+        let report = manager.get_startup_report();
+        assert_eq!(report.failed().count(), 1);
+        assert!(report.components.len() <= 2);
+
+        assert_eq!(
+            "fail-fast".parse::<StartupLoadPolicy>()?,
+            StartupLoadPolicy::FailFast
+        );
+        assert!("abort".parse::<StartupLoadPolicy>().is_err());
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_startup_report_lists_failed_components() -> Result<()> {
+        let component_dir = tempfile::tempdir()?;
+        logging_component(component_dir.path())?;
+        std::fs::write(component_dir.path().join("broken.wasm"), b"not a component")?;
+
+        let manager = LifecycleManager::new_unloaded(&component_dir).await?;
+        assert!(!manager.get_startup_report().complete);
+        manager
+            .load_existing_components_async(None, None::<fn()>, StartupLoadPolicy::Continue)
+            .await?;
+
+        let report = manager.get_startup_report();
+        assert!(report.complete);
+        assert_eq!(
+            report.components.get("logger"),
+            Some(&ComponentLoadStatus::Loaded)
+        );
+        let failed: Vec<_> = report.failed().collect();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].0, "broken");
+        assert!(
+            failed[0].1.contains("Failed to compile component"),
+            "{}",
+            failed[0].1
+        );
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_check_installed_components_runs_no_hooks() -> Result<()> {
+        let component_dir = tempfile::tempdir()?;
+        // Its init hook fails, which fails a real load but not the check
+        hooked_component(component_dir.path(), 1)?;
+        std::fs::write(component_dir.path().join("broken.wasm"), b"not a component")?;
+
+        let manager = LifecycleManager::new_unloaded(&component_dir).await?;
+        let report = manager.check_installed_components().await?;
+        assert!(report.complete);
+        assert_eq!(
+            report.components.get("hooked"),
+            Some(&ComponentLoadStatus::Loaded)
+        );
+        let failed: Vec<_> = report.failed().collect();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].0, "broken");
+        assert!(
+            failed[0].1.contains("Failed to compile component"),
+            "{}",
+            failed[0].1
+        );
+
+        // Nothing was registered, cached or written next to the components
+        assert!(manager.list_components().await.is_empty());
+        assert!(!manager.get_startup_report().complete);
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(component_dir.path())? {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                files.push(entry.file_name().into_string().unwrap());
+            }
+        }
+        files.sort();
+        assert_eq!(files, vec!["broken.wasm", "hooked.wasm"]);
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_component_output_is_captured_in_logs() -> Result<()> {
+        let manager = create_test_manager().await?;
+        let source_dir = tempfile::tempdir()?;
+        let path = logging_component(source_dir.path())?;
+        manager
+            .load_component(&format!("file://{}", path.display()))
+            .await?;
+
+        for _ in 0..2 {
+            let result = manager
+                .execute_component_call("logger", "log", "{}")
+                .await?;
+            assert_eq!(result, r#"{"result":3}"#);
+        }
+
+        let all = manager.get_component_logs("logger", None).await;
+        assert_eq!(all.len(), 6);
+        assert!(all.iter().all(|line| line.stream == LogStream::Stdout));
+
+        let tail: Vec<String> = manager
+            .get_component_logs("logger", Some(2))
+            .await
+            .into_iter()
+            .map(|line| line.line)
+            .collect();
+        assert_eq!(tail, vec!["second", "third"]);
+        assert!(manager.component_log_path("logger").exists());
+
+        manager.unload_component("logger").await?;
+        assert!(manager.get_component_logs("logger", None).await.is_empty());
+        assert!(!manager.component_log_path("logger").exists());
+
+        Ok(())
+    }
+
+    /// Collects formatted tracing output in memory
+    #[derive(Clone, Default)]
+    struct CapturedTracing(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedTracing {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stderr_lines_are_traced_with_component_id() -> Result<()> {
+        let captured = CapturedTracing::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        // The test runs on a single thread, so the default covers every await below
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let component_dir = tempfile::tempdir()?;
+        let manager = LifecycleManager::builder(component_dir.path())
+            .with_stderr_tracing(true)
+            .build()
+            .await?;
+        let source_dir = tempfile::tempdir()?;
+        let path = logging_component_on(source_dir.path(), "stderr")?;
+        manager
+            .load_component(&format!("file://{}", path.display()))
+            .await?;
+
+        manager
+            .execute_component_call("logger", "log", "{}")
+            .await?;
+        let output = String::from_utf8(captured.0.lock().unwrap().clone())?;
+        let traced = output
+            .lines()
+            .find(|line| line.contains(" second "))
+            .unwrap_or_else(|| panic!("stderr line not traced:\n{output}"));
+        assert!(traced.contains("component_id=logger"), "{traced}");
+        assert!(traced.contains("function=\"log\""), "{traced}");
+        // Captured for `component logs` as before
+        let lines = manager.get_component_logs("logger", None).await;
+        assert_eq!(lines.len(), 3);
+        assert!(lines.iter().all(|line| line.stream == LogStream::Stderr));
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_world_writable_component_dir_is_warned_about() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let captured = CapturedTracing::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let component_dir = tempfile::tempdir()?;
+        std::fs::set_permissions(component_dir.path(), std::fs::Permissions::from_mode(0o777))?;
+
+        LifecycleManager::builder(component_dir.path())
+            .build()
+            .await?;
+        let output = String::from_utf8(captured.0.lock().unwrap().clone())?;
+        assert!(
+            output.contains("SECURITY WARNING") && output.contains("writable by all users"),
+            "{output}"
+        );
+
+        let err = LifecycleManager::builder(component_dir.path())
+            .with_strict_security(true)
+            .build()
+            .await
+            .err()
+            .expect("strict security must refuse a world-writable component directory");
+        assert!(err.to_string().contains("writable by all users"), "{err}");
+
+        std::fs::set_permissions(component_dir.path(), std::fs::Permissions::from_mode(0o700))?;
+        LifecycleManager::builder(component_dir.path())
+            .with_strict_security(true)
+            .build()
+            .await?;
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_secrets_are_redacted_from_component_errors() -> Result<()> {
+        let manager = create_test_manager().await?;
+        let source_dir = tempfile::tempdir()?;
+        let path = source_dir.path().join("login.wasm");
+        // `login` returns `err("auth failed with key s3cr3t-token")`
+        let wat = r#"(component
+                (core module $m
+                    (memory (export "memory") 1)
+                    (data (i32.const 0) "\01\00\00\00\20\00\00\00\21\00\00\00")
+                    (data (i32.const 32) "auth failed with key s3cr3t-token")
+                    (func (export "login") (result i32) (i32.const 0))
+                )
+                (core instance $i (instantiate $m))
+                (alias core export $i "memory" (core memory $mem))
+                (func $login (result (result u32 (error string)))
+                    (canon lift (core func $i "login") (memory $mem)))
+                (export "login" (func $login))
+            )"#;
+        std::fs::write(&path, wat::parse_str(wat)?)?;
+        manager
+            .load_component(&format!("file://{}", path.display()))
+            .await?;
+
+        let unredacted = manager
+            .execute_component_call("login", "login", "{}")
+            .await?;
+        assert!(unredacted.contains("s3cr3t-token"), "{unredacted}");
+
+        manager
+            .set_component_secrets("login", &[("API_KEY".into(), "s3cr3t-token".into())])
+            .await?;
+        let result = manager
+            .execute_component_call("login", "login", "{}")
+            .await?;
+        assert!(!result.contains("s3cr3t-token"), "{result}");
+        assert!(result.contains("auth failed with key ***"), "{result}");
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_secrets_are_redacted_from_component_logs() -> Result<()> {
+        let manager = create_test_manager().await?;
+        let source_dir = tempfile::tempdir()?;
+        let path = logging_component(source_dir.path())?;
+        manager
+            .load_component(&format!("file://{}", path.display()))
+            .await?;
+        manager
+            .set_component_secrets("logger", &[("TOKEN".into(), "second".into())])
+            .await?;
+
+        manager
+            .execute_component_call("logger", "log", "{}")
+            .await?;
+
+        let lines: Vec<String> = manager
+            .get_component_logs("logger", None)
+            .await
+            .into_iter()
+            .map(|line| line.line)
+            .collect();
+        assert_eq!(lines, vec!["first", "***", "third"]);
+        let on_disk = std::fs::read_to_string(manager.component_log_path("logger"))?;
+        assert!(!on_disk.contains("second"), "{on_disk}");
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_loading_core_module_explains_it_is_not_a_component() -> Result<()> {
+        let manager = create_test_manager().await?;
+        let source_dir = tempfile::tempdir()?;
+        let path = source_dir.path().join("core.wasm");
+        std::fs::write(&path, wat::parse_str(r#"(module (func (export "run")))"#)?)?;
+
+        let err = manager
+            .load_component(&format!("file://{}", path.display()))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "This file is a core WebAssembly module, not a component; Wassette only runs components. Consider `wasm-tools component new`."
+        );
+        assert!(manager.list_components().await.is_empty());
+        assert!(!manager.component_path("core").exists());
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_load_component_with_explicit_id() -> Result<()> {
+        let manager = create_test_manager().await?;
+        let source_dir = tempfile::tempdir()?;
+        let path = logging_component(source_dir.path())?;
+
+        let outcome = manager
+            .load_component_with(
+                &format!("file://{}", path.display()),
+                LoadOptions {
+                    component_id: Some("myfetch".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        assert_eq!(outcome.component_id, "myfetch");
+        assert!(manager.component_path("myfetch").exists());
+        assert!(!manager.component_path("logger").exists());
+        assert_eq!(
+            manager
+                .execute_component_call("myfetch", "log", "{}")
+                .await?,
+            r#"{"result":3}"#
+        );
+
+        let err = manager
+            .load_component_with(
+                &format!("file://{}", path.display()),
+                LoadOptions {
+                    component_id: Some("../x".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Invalid component id"));
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_duplicate_tool_policy_picks_component() -> Result<()> {
+        let source_dir = tempfile::tempdir()?;
+        let uri = format!("file://{}", logging_component(source_dir.path())?.display());
+
+        for (policy, expected) in [
+            (DuplicateToolPolicy::Error, None),
+            (DuplicateToolPolicy::FirstLoaded, Some("first")),
+            (DuplicateToolPolicy::LastLoaded, Some("second")),
+            (DuplicateToolPolicy::RequireNamespace, None),
+        ] {
+            let component_dir = tempfile::tempdir()?;
+            let manager = LifecycleManager::builder(component_dir.path())
+                .with_duplicate_tool_policy(policy)
+                .build()
+                .await?;
+            // Both components export `log`
+            for id in ["first", "second"] {
+                manager
+                    .load_component_with(
+                        &uri,
+                        LoadOptions {
+                            component_id: Some(id.to_string()),
+                            ..Default::default()
+                        },
+                    )
+                    .await?;
+            }
+
+            match expected {
+                Some(id) => assert_eq!(manager.get_component_id_for_tool("log").await?, id),
+                None => {
+                    let err = manager.get_component_id_for_tool("log").await.unwrap_err();
+                    assert!(err.to_string().contains("first__log"), "{policy:?}: {err}");
+                }
+            }
+        }
+
+        assert_eq!(
+            "last-loaded".parse::<DuplicateToolPolicy>()?,
+            DuplicateToolPolicy::LastLoaded
+        );
+        assert!("newest".parse::<DuplicateToolPolicy>().is_err());
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_conflicting_implicit_id_requires_force() -> Result<()> {
+        let manager = create_test_manager().await?;
+        let first_dir = tempfile::tempdir()?;
+        let second_dir = tempfile::tempdir()?;
+        let first = format!("file://{}", logging_component(first_dir.path())?.display());
+        let second = format!("file://{}", logging_component(second_dir.path())?.display());
+
+        manager.load_component(&first).await?;
+        // Reloading from the same source is not a conflict
+        assert_eq!(
+            manager.load_component(&first).await?.status,
+            LoadResult::Unchanged
+        );
+
+        let err = manager.load_component(&second).await.unwrap_err();
+        assert!(err.to_string().contains("already loaded from"));
+        assert!(err.to_string().contains("--force"));
+
+        let outcome = manager
+            .load_component_with(
+                &second,
+                LoadOptions {
+                    force: true,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        assert_eq!(outcome.status, LoadResult::Replaced);
+        assert_eq!(
+            manager.storage.read_component_source("logger").await?,
+            Some(second)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_source_identity_ignores_oci_tags() {
+        assert_eq!(
+            source_identity("oci://ghcr.io/org/fetch:v1"),
+            source_identity("oci://ghcr.io/org/fetch@sha256:abc")
+        );
+        assert_eq!(
+            source_identity("oci://localhost:5000/fetch"),
+            "localhost:5000/fetch"
+        );
+        assert_eq!(
+            source_identity("file:///a/fetch.wasm"),
+            "file:///a/fetch.wasm"
+        );
+    }
+
+    #[test]
+    fn test_full_path_oci_ids_keep_colliding_repositories_apart() {
+        let id = |ids: OciComponentIds, reference: &str| {
+            let reference: oci_client::Reference = reference.parse().unwrap();
+            ids.component_id(reference.repository())
+        };
+
+        // Each pair gets the same id when flattened
+        let collisions = [
+            ("ghcr.io/acme/tool:1.0", "ghcr.io/acme_tool:1.0"),
+            ("ghcr.io/a/b_c:latest", "ghcr.io/a_b/c:latest"),
+            ("ghcr.io/a/b__c", "ghcr.io/a_b__c"),
+            ("localhost:5000/team/fetch", "localhost:5000/team_fetch"),
+        ];
+        for (a, b) in collisions {
+            assert_eq!(
+                id(OciComponentIds::Flatten, a),
+                id(OciComponentIds::Flatten, b)
+            );
+            let (full_a, full_b) = (
+                id(OciComponentIds::FullPath, a),
+                id(OciComponentIds::FullPath, b),
+            );
+            assert_ne!(full_a, full_b, "{a} and {b}");
+            validate_component_id(&full_a).unwrap();
+            validate_component_id(&full_b).unwrap();
+        }
+
+        assert_eq!(
+            id(OciComponentIds::Flatten, "ghcr.io/acme/tools/fetch:v1"),
+            "acme_tools_fetch"
+        );
+        assert_eq!(
+            id(OciComponentIds::FullPath, "ghcr.io/acme/tools/fetch:v1"),
+            "acme..tools..fetch"
+        );
+        assert_eq!(
+            "full-path".parse::<OciComponentIds>().unwrap(),
+            OciComponentIds::FullPath
+        );
+        assert!("nested".parse::<OciComponentIds>().is_err());
+    }
+
+    #[test(tokio::test)]
+    async fn test_export_round_trip_preserves_tools_and_policy() -> Result<()> {
+        let manager = create_test_manager().await?;
+        let source_dir = tempfile::tempdir()?;
+        let path = logging_component(source_dir.path())?;
+        manager
+            .load_component(&format!("file://{}", path.display()))
+            .await?;
+        manager
+            .grant_permission(
+                "logger",
+                "network",
+                &serde_json::json!({"host": "api.example.com"}),
+            )
+            .await?;
+
+        let bundle = source_dir.path().join("logger.tar.gz");
+        manager.export_component("logger", &bundle).await?;
+
+        let fresh = create_test_manager().await?;
+        let outcome = fresh
+            .load_component(&format!("file://{}", bundle.display()))
+            .await?;
+        assert_eq!(outcome.component_id, "logger");
+        assert_eq!(outcome.tool_names, vec!["log"]);
+        assert_eq!(
+            fresh.execute_component_call("logger", "log", "{}").await?,
+            r#"{"result":3}"#
+        );
+
+        let policy = std::fs::read_to_string(fresh.get_component_policy_path("logger"))?;
+        assert!(policy.contains("api.example.com"));
+
+        let err = manager
+            .export_component("missing", &source_dir.path().join("missing.tar.gz"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Component not found"));
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_rename_keeps_tools_and_policy_under_new_id() -> Result<()> {
+        let manager = create_test_manager().await?;
+        let source_dir = tempfile::tempdir()?;
+        let path = logging_component(source_dir.path())?;
+        manager
+            .load_component(&format!("file://{}", path.display()))
+            .await?;
+        manager
+            .grant_permission(
+                "logger",
+                "network",
+                &serde_json::json!({"host": "api.example.com"}),
+            )
+            .await?;
+        let mut events = manager.subscribe();
+
+        manager.rename_component("logger", "audit-log").await?;
+
+        assert_eq!(manager.list_components().await, vec!["audit-log"]);
+        assert_eq!(manager.get_component_id_for_tool("log").await?, "audit-log");
+        assert_eq!(
+            manager
+                .execute_component_call("audit-log", "log", "{}")
+                .await?,
+            r#"{"result":3}"#
+        );
+        let policy = manager.get_effective_policy("audit-log").await?;
+        assert!(serde_yaml::to_string(&policy)?.contains("api.example.com"));
+        assert!(!manager.component_path("logger").exists());
+        assert!(!manager.get_component_policy_path("logger").exists());
+        assert_eq!(
+            events.recv().await?,
+            LifecycleEvent::Renamed {
+                id: "audit-log".to_string(),
+                previous_id: "logger".to_string(),
+            }
+        );
+
+        // The new id must be free
+        let second_dir = source_dir.path().join("second");
+        std::fs::create_dir(&second_dir)?;
+        let second = logging_component(&second_dir)?;
+        manager
+            .load_component(&format!("file://{}", second.display()))
+            .await?;
+        let err = manager
+            .rename_component("logger", "audit-log")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+        assert_eq!(manager.list_components().await, vec!["audit-log", "logger"]);
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_failed_rename_moves_secrets_and_files_back() -> Result<()> {
+        let manager = create_test_manager().await?;
+        let source_dir = tempfile::tempdir()?;
+        let path = logging_component(source_dir.path())?;
+        manager
+            .load_component(&format!("file://{}", path.display()))
+            .await?;
+        manager
+            .set_component_secrets("logger", &[("TOKEN".to_string(), "s3cret".to_string())])
+            .await?;
+        manager
+            .execute_component_call("logger", "log", "{}")
+            .await?;
+        // A directory in the way of the log file makes the rename fail after the component
+        // files and secrets have moved
+        std::fs::create_dir_all(manager.component_log_path("audit-log").join("occupied"))?;
+
+        let err = manager
+            .rename_component("logger", "audit-log")
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("Failed to move component logs"),
+            "{err}"
+        );
+        assert!(manager.component_path("logger").exists());
+        assert!(!manager.component_path("audit-log").exists());
+        assert!(manager
+            .secrets_manager
+            .get_component_secrets_path("logger")
+            .exists());
+        assert!(!manager
+            .secrets_manager
+            .get_component_secrets_path("audit-log")
+            .exists());
+        assert!(manager.component_log_path("logger").exists());
+        assert_eq!(manager.list_components().await, vec!["logger"]);
+        assert_eq!(
+            manager
+                .execute_component_call("logger", "log", "{}")
+                .await?,
+            r#"{"result":3}"#
+        );
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_downloads_are_routed_through_proxy() -> Result<()> {
+        use std::io::{BufRead, BufReader, Write};
+
+        // A proxy that records the first request line and refuses to tunnel
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+        let proxy_url = format!("http://{}", listener.local_addr()?);
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let _ = reader
+                .get_mut()
+                .write_all(b"HTTP/1.1 502 Bad Gateway\r\nContent-Length: 0\r\n\r\n");
+            tx.send(request_line).unwrap();
+        });
+
+        let tempdir = tempfile::tempdir()?;
+        let manager = LifecycleManager::builder(tempdir.path())
+            .with_eager_loading(false)
+            .with_proxy(Some(ProxyConfig {
+                url: proxy_url,
+                no_proxy: None,
+            }))
+            .build()
+            .await?;
+
+        assert!(manager
+            .load_component("https://components.example.invalid/fetch.wasm")
+            .await
+            .is_err());
+        let request_line = rx.recv_timeout(Duration::from_secs(10))?;
+        assert!(
+            request_line.starts_with("CONNECT components.example.invalid:443"),
+            "unexpected proxy request: {request_line}"
+        );
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_max_components_rejects_loads_past_the_limit() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let manager = LifecycleManager::builder(tempdir.path())
+            .with_max_components(Some(1))
+            .build()
+            .await?;
+        let source_dir = tempfile::tempdir()?;
+        let first = logging_component(source_dir.path())?;
+        let second = source_dir.path().join("second-logger.wasm");
+        std::fs::copy(&first, &second)?;
+        let first_uri = format!("file://{}", first.display());
+        let second_uri = format!("file://{}", second.display());
+
+        manager.load_component(&first_uri).await?;
+        // Replacing a loaded component does not count as a new one
+        manager.load_component(&first_uri).await?;
+
+        let err = manager.load_component(&second_uri).await.unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Component limit reached: 1 of 1 allowed components are loaded"),
+            "unexpected error: {err}"
+        );
+        assert_eq!(manager.list_components().await, vec!["logger"]);
+
+        manager.unload_component("logger").await?;
+        manager.load_component(&second_uri).await?;
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_max_components_applies_to_startup_loading() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let source_dir = tempfile::tempdir()?;
+        let logger = logging_component(source_dir.path())?;
+        for id in ["c", "a", "b"] {
+            std::fs::copy(&logger, tempdir.path().join(format!("{id}.wasm")))?;
+        }
+
+        let manager = LifecycleManager::builder(tempdir.path())
+            .with_max_components(Some(2))
+            .build()
+            .await?;
+        assert_eq!(manager.list_components().await, vec!["a", "b"]);
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_load_locks_are_dropped_once_released() -> Result<()> {
+        let manager = create_test_manager().await?;
+        let source_dir = tempfile::tempdir()?;
+        let logger = logging_component(source_dir.path())?;
+        for i in 0..5 {
+            let path = source_dir.path().join(format!("logger-{i}.wasm"));
+            std::fs::copy(&logger, &path)?;
+            let outcome = manager
+                .load_component(&format!("file://{}", path.display()))
+                .await?;
+            manager.unload_component(&outcome.component_id).await?;
+        }
+        // Only the lock taken last is left until the next one is requested
+        assert!(manager.load_locks.lock().unwrap().len() <= 1);
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_policy_lock_is_dropped_on_unload() -> Result<()> {
+        let manager = create_test_manager().await?;
+        let source_dir = tempfile::tempdir()?;
+        let logger = logging_component(source_dir.path())?;
+        manager
+            .load_component(&format!("file://{}", logger.display()))
+            .await?;
+        manager
+            .grant_permission(
+                "logger",
+                "network",
+                &serde_json::json!({ "host": "api.example.com" }),
+            )
+            .await?;
+        assert!(manager
+            .policy_manager
+            .policy_locks
+            .lock()
+            .unwrap()
+            .contains_key("logger"));
+
+        manager.unload_component("logger").await?;
+        assert!(!manager
+            .policy_manager
+            .policy_locks
+            .lock()
+            .unwrap()
+            .contains_key("logger"));
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_max_components_holds_for_concurrent_loads() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let manager = LifecycleManager::builder(tempdir.path())
+            .with_max_components(Some(2))
+            .build()
+            .await?;
+        let source_dir = tempfile::tempdir()?;
+        let logger = logging_component(source_dir.path())?;
+        let mut uris = Vec::new();
+        for i in 0..6 {
+            let path = source_dir.path().join(format!("logger-{i}.wasm"));
+            std::fs::copy(&logger, &path)?;
+            uris.push(format!("file://{}", path.display()));
+        }
+
+        let outcomes = futures::future::join_all(uris.iter().map(|uri| {
+            let manager = manager.clone();
+            let uri = uri.clone();
+            tokio::spawn(async move { manager.load_component(&uri).await })
+        }))
+        .await;
+        let loaded = outcomes
+            .into_iter()
+            .filter(|outcome| matches!(outcome, Ok(Ok(_))))
+            .count();
+        assert_eq!(loaded, 2);
+        assert_eq!(manager.list_components().await.len(), 2);
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_memory_budget_refuses_loads_past_the_budget() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        // Every component declares 32Mi through the default policy
+        let default_policy = PolicyParser::parse_str(
+            r#"
+version: "1.0"
+permissions:
+  resources:
+    limits:
+      memory: "32Mi"
+"#,
+        )?;
+        let manager = LifecycleManager::builder(tempdir.path())
+            .with_default_policy(Some(default_policy))
+            .with_max_loaded_memory(Some(80 << 20))
+            .build()
+            .await?;
+        let source_dir = tempfile::tempdir()?;
+        let logger = logging_component(source_dir.path())?;
+        let mut uris = Vec::new();
+        for id in ["first", "second", "third"] {
+            let path = source_dir.path().join(format!("{id}.wasm"));
+            std::fs::copy(&logger, &path)?;
+            uris.push(format!("file://{}", path.display()));
+        }
+
+        manager.load_component(&uris[0]).await?;
+        manager.load_component(&uris[1]).await?;
+        // Replacing a loaded component only counts its limit once
+        manager.load_component(&uris[1]).await?;
+
+        let err = manager.load_component(&uris[2]).await.unwrap_err();
+        assert!(
+            err.to_string().starts_with(
+                "Insufficient memory budget: 'third' declares a memory limit of 33554432 bytes, \
+                 but loaded components already reserve 67108864 of the 83886080 bytes allowed"
+            ),
+            "unexpected error: {err:#}"
+        );
+        assert_eq!(
+            err.downcast_ref::<InsufficientMemoryBudget>()
+                .map(|refusal| refusal.reserved),
+            Some(64 << 20)
+        );
+        let mut loaded = manager.list_components().await;
+        loaded.sort();
+        assert_eq!(loaded, vec!["first", "second"]);
+        // Refused before anything about the component was written
+        assert!(!manager.storage.metadata_path("third").exists());
+
+        manager.unload_component("first").await?;
+        manager.load_component(&uris[2]).await?;
+
+        // Raising a loaded component's limit is checked against the budget too
+        let err = manager
+            .grant_permission("third", "resource", &serde_json::json!({"memory": "64Mi"}))
+            .await
+            .unwrap_err();
+        assert!(
+            err.is::<InsufficientMemoryBudget>(),
+            "unexpected error: {err:#}"
+        );
+        manager
+            .grant_permission("third", "resource", &serde_json::json!({"memory": "16Mi"}))
+            .await?;
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_memory_budget_refuses_components_without_limit() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let manager = LifecycleManager::builder(tempdir.path())
+            .with_max_loaded_memory(Some(80 << 20))
+            .build()
+            .await?;
+        let source_dir = tempfile::tempdir()?;
+        let logger = logging_component(source_dir.path())?;
+
+        let err = manager
+            .load_component(&format!("file://{}", logger.display()))
+            .await
+            .unwrap_err();
+        assert!(
+            format!("{err:#}").contains("'logger' declares no memory limit"),
+            "unexpected error: {err:#}"
+        );
+        assert!(manager.list_components().await.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_memory_budget_holds_for_concurrent_loads() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let default_policy = PolicyParser::parse_str(
+            r#"
+version: "1.0"
+permissions:
+  resources:
+    limits:
+      memory: "32Mi"
+"#,
+        )?;
+        let manager = LifecycleManager::builder(tempdir.path())
+            .with_default_policy(Some(default_policy))
+            .with_max_loaded_memory(Some(80 << 20))
+            .build()
+            .await?;
+        let source_dir = tempfile::tempdir()?;
+        let logger = logging_component(source_dir.path())?;
+        let mut uris = Vec::new();
+        for i in 0..6 {
+            let path = source_dir.path().join(format!("logger-{i}.wasm"));
+            std::fs::copy(&logger, &path)?;
+            uris.push(format!("file://{}", path.display()));
+        }
+
+        let outcomes = futures::future::join_all(uris.iter().map(|uri| {
+            let manager = manager.clone();
+            let uri = uri.clone();
+            tokio::spawn(async move { manager.load_component(&uri).await })
+        }))
+        .await;
+        let loaded = outcomes
+            .into_iter()
+            .filter(|outcome| matches!(outcome, Ok(Ok(_))))
+            .count();
+        assert_eq!(loaded, 2);
+        assert_eq!(manager.list_components().await.len(), 2);
+        Ok(())
+    }
+
+    /// A component exporting `wassette:lifecycle/hooks` whose `init` sets a counter to 41 and
+    /// returns `ok` when `init_status` is 0 and `err` otherwise, whose `shutdown` traps, and
+    /// which exposes a single `ping` tool returning the incremented counter.
+    fn hooked_component(dir: &Path, init_status: u32) -> Result<PathBuf> {
+        let wat = format!(
+            r#"(component
+                (core module $m
+                    (global $counter (mut i32) (i32.const 0))
+                    (func (export "init") (result i32)
+                        (global.set $counter (i32.const 41))
+                        (i32.const {init_status}))
+                    (func (export "shutdown") unreachable)
+                    (func (export "ping") (result i32)
+                        (global.set $counter (i32.add (global.get $counter) (i32.const 1)))
+                        (global.get $counter))
+                )
+                (core instance $i (instantiate $m))
+                (type $status (result))
+                (func $init (result $status) (canon lift (core func $i "init")))
+                (func $shutdown (canon lift (core func $i "shutdown")))
+                (func $ping (result u32) (canon lift (core func $i "ping")))
+                (instance $hooks
+                    (export "init" (func $init))
+                    (export "shutdown" (func $shutdown))
+                )
+                (export "wassette:lifecycle/hooks" (instance $hooks))
+                (export "ping" (func $ping))
+            )"#
+        );
+        let path = dir.join("hooked.wasm");
+        std::fs::write(&path, wat::parse_str(wat)?)?;
+        Ok(path)
+    }
+
+    /// A component exporting `wassette:health/check` whose `check` returns `ok` when it can
+    /// see at least one environment variable and `err` otherwise, plus a `ping` tool.
+    fn health_component(dir: &Path) -> Result<PathBuf> {
+        let wat = r#"(component
+                (import "wasi:cli/environment@0.2.0" (instance $environment
+                    (export "get-environment" (func (result (list (tuple string string)))))
+                ))
+                (core module $libc
+                    (memory (export "memory") 1)
+                    (global $next (mut i32) (i32.const 1024))
+                    (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+                        (local $ptr i32)
+                        (local.set $ptr
+                            (i32.and
+                                (i32.add (global.get $next) (i32.sub (local.get 2) (i32.const 1)))
+                                (i32.sub (i32.const 0) (local.get 2))))
+                        (global.set $next (i32.add (local.get $ptr) (local.get 3)))
+                        (local.get $ptr))
+                )
+                (core instance $libc (instantiate $libc))
+                (alias core export $libc "memory" (core memory $mem))
+                (alias core export $libc "realloc" (core func $realloc))
+                (core func $get-environment
+                    (canon lower (func $environment "get-environment") (memory $mem) (realloc $realloc)))
+                (core module $m
+                    (import "host" "get-environment" (func $get-environment (param i32)))
+                    (import "host" "memory" (memory 1))
+                    (func (export "check") (result i32)
+                        (call $get-environment (i32.const 0))
+                        (if (result i32) (i32.load (i32.const 4))
+                            (then (i32.const 0))
+                            (else (i32.const 1))))
+                    (func (export "ping") (result i32) (i32.const 1))
+                )
+                (core instance $host
+                    (export "get-environment" (func $get-environment))
+                    (export "memory" (memory $mem))
+                )
+                (core instance $i (instantiate $m (with "host" (instance $host))))
+                (type $status (result))
+                (func $check (result $status) (canon lift (core func $i "check")))
+                (func $ping (result u32) (canon lift (core func $i "ping")))
+                (instance $health (export "check" (func $check)))
+                (export "wassette:health/check" (instance $health))
+                (export "ping" (func $ping))
+            )"#;
+        let path = dir.join("probe.wasm");
+        std::fs::write(&path, wat::parse_str(wat)?)?;
+        Ok(path)
+    }
+
+    #[test(tokio::test)]
+    async fn test_component_health_check() -> Result<()> {
+        let source_dir = tempfile::tempdir()?;
+        let probe_uri = format!("file://{}", health_component(source_dir.path())?.display());
+        let logger_uri = format!("file://{}", logging_component(source_dir.path())?.display());
+
+        let manager = create_test_manager().await?;
+        let outcome = manager.load_component(&probe_uri).await?;
+        // The health check is not exposed as a tool
+        assert_eq!(outcome.tool_names, vec!["ping"]);
+        assert_eq!(
+            manager.check_component_health("probe").await?,
+            ComponentHealth::unhealthy("health check returned an error")
+        );
+
+        manager.load_component(&logger_uri).await?;
+        assert_eq!(
+            manager.check_component_health("logger").await?.status,
+            HealthStatus::Unknown
+        );
+        assert!(manager.check_component_health("missing").await.is_err());
+
+        let component_dir = tempfile::tempdir()?;
+        let manager = LifecycleManager::builder(component_dir.path())
+            .with_environment_vars(HashMap::from([(
+                "UPSTREAM_READY".to_string(),
+                "1".to_string(),
+            )]))
+            .build()
+            .await?;
+        manager.load_component(&probe_uri).await?;
+        assert_eq!(
+            manager.check_component_health("probe").await?,
+            ComponentHealth::healthy()
+        );
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_stuck_health_check_times_out() -> Result<()> {
+        let source_dir = tempfile::tempdir()?;
+        // A health check that never returns and never yields to the async runtime
+        let wat = r#"(component
+                (core module $m
+                    (func (export "check") (result i32)
+                        (loop $spin (br $spin))
+                        unreachable)
+                    (func (export "ping") (result i32) (i32.const 1))
+                )
+                (core instance $i (instantiate $m))
+                (type $status (result))
+                (func $check (result $status) (canon lift (core func $i "check")))
+                (func $ping (result u32) (canon lift (core func $i "ping")))
+                (instance $health (export "check" (func $check)))
+                (export "wassette:health/check" (instance $health))
+                (export "ping" (func $ping))
+            )"#;
+        let path = source_dir.path().join("stuck.wasm");
+        std::fs::write(&path, wat::parse_str(wat)?)?;
+
+        let manager = create_test_manager().await?;
+        manager
+            .load_component(&format!("file://{}", path.display()))
+            .await?;
+        let health = manager
+            .check_component_health_within("stuck", Duration::from_millis(200))
+            .await?;
+        assert_eq!(health.status, HealthStatus::Unhealthy);
+        assert!(
+            health.message.as_deref().unwrap().contains("timed out"),
+            "{health:?}"
+        );
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_lifecycle_hooks_run_on_load_and_unload() -> Result<()> {
+        let manager = create_test_manager().await?;
+        let source_dir = tempfile::tempdir()?;
+        let path = hooked_component(source_dir.path(), 0)?;
+
+        let outcome = manager
+            .load_component(&format!("file://{}", path.display()))
+            .await?;
+        assert_eq!(outcome.component_id, "hooked");
+        // Hook functions are never exposed as tools
+        assert_eq!(outcome.tool_names, vec!["ping"]);
+
+        let result = manager
+            .execute_component_call("hooked", "ping", "{}")
+            .await?;
+        assert_eq!(result, r#"{"result":42}"#);
+
+        // A failing shutdown hook is reported but does not block the unload
+        assert_eq!(
+            manager.unload_component("hooked").await?,
+            UnloadResult::Unloaded
+        );
+        assert!(manager.list_components().await.is_empty());
 
-    pub(crate) async fn create_test_manager() -> Result<TestLifecycleManager> {
-        let tempdir = tempfile::tempdir()?;
-        let manager = LifecycleManager::new(&tempdir).await?;
-        Ok(TestLifecycleManager {
-            manager,
-            _tempdir: tempdir,
-        })
+        Ok(())
     }
 
-    pub(crate) async fn build_example_component() -> Result<PathBuf> {
-        let cwd = std::env::current_dir()?;
-        println!("CWD: {}", cwd.display());
-        let component_path =
-            cwd.join("../../examples/fetch-rs/target/wasm32-wasip2/release/fetch_rs.wasm");
-
-        if !component_path.exists() {
-            let status = Command::new("cargo")
-                .current_dir(cwd.join("../../examples/fetch-rs"))
-                .args(["build", "--release", "--target", "wasm32-wasip2"])
-                .status()
-                .context("Failed to execute cargo component build")?;
+    #[test(tokio::test(start_paused = true))]
+    async fn test_idle_unload_shuts_down_resident_instance() -> Result<()> {
+        let component_dir = tempfile::tempdir()?;
+        let manager = LifecycleManager::builder(component_dir.path())
+            .with_idle_unload_ttl(Duration::from_secs(60))
+            .build()
+            .await?;
+        let source_dir = tempfile::tempdir()?;
+        let path = hooked_component(source_dir.path(), 0)?;
+        manager
+            .load_component(&format!("file://{}", path.display()))
+            .await?;
+        manager
+            .execute_component_call("hooked", "ping", "{}")
+            .await?;
+        let component = manager.get_component("hooked").await.unwrap();
 
-            if !status.success() {
-                anyhow::bail!("Failed to compile fetch-rs component");
-            }
-        }
+        tokio::time::sleep(Duration::from_secs(120)).await;
+        assert!(manager.list_components().await.is_empty());
+        // The shutdown hook ran on the instance, even though it failed
+        let resident = component.resident.as_ref().unwrap();
+        assert!(resident.live.lock().await.is_none());
 
-        if !component_path.exists() {
-            anyhow::bail!(
-                "Component file not found after build: {}",
-                component_path.display()
-            );
-        }
+        // An unloaded component can still be managed, and its next call starts it again
+        manager
+            .grant_permission(
+                "hooked",
+                "network",
+                &serde_json::json!({"host": "api.example.com"}),
+            )
+            .await?;
+        assert_eq!(
+            manager
+                .execute_component_call("hooked", "ping", "{}")
+                .await?,
+            r#"{"result":42}"#
+        );
 
-        Ok(component_path)
+        Ok(())
     }
 
     #[test(tokio::test)]
-    async fn test_lifecycle_manager_tool_registry() -> Result<()> {
+    async fn test_failing_init_hook_aborts_load() -> Result<()> {
         let manager = create_test_manager().await?;
+        let source_dir = tempfile::tempdir()?;
+        let path = hooked_component(source_dir.path(), 1)?;
 
-        let temp_dir = tempfile::tempdir()?;
-        let component_path = temp_dir.path().join("mock_component.wasm");
-        std::fs::write(&component_path, b"mock wasm bytes")?;
-
-        let load_result = manager
-            .load_component(component_path.to_str().unwrap())
-            .await;
-        assert!(load_result.is_err()); // Expected since we're using invalid WASM
-
-        let lookup_result = manager.get_component_id_for_tool("non-existent").await;
-        assert!(lookup_result.is_err());
+        let err = manager
+            .load_component(&format!("file://{}", path.display()))
+            .await
+            .unwrap_err();
+        assert!(
+            format!("{err:#}").contains("Component init hook failed"),
+            "unexpected error: {err:#}"
+        );
+        assert!(manager.list_components().await.is_empty());
+        // The metadata written before init ran is removed again
+        assert!(!manager.storage.metadata_path("hooked").exists());
+        assert!(!manager.get_component_policy_path("hooked").exists());
 
         Ok(())
     }
 
     #[test(tokio::test)]
-    async fn test_new_manager() -> Result<()> {
-        let _manager = create_test_manager().await?;
+    async fn test_calls_see_state_set_up_by_init() -> Result<()> {
+        let manager = create_test_manager().await?;
+        let source_dir = tempfile::tempdir()?;
+        let path = hooked_component(source_dir.path(), 0)?;
+        manager
+            .load_component(&format!("file://{}", path.display()))
+            .await?;
+
+        // Calls run in the instance init ran in, one after another
+        for expected in [42, 43, 44] {
+            let result = manager
+                .execute_component_call("hooked", "ping", "{}")
+                .await?;
+            assert_eq!(result, format!(r#"{{"result":{expected}}}"#));
+        }
+
+        // A policy change restarts the instance, running init again
+        manager
+            .grant_permission(
+                "hooked",
+                "network",
+                &serde_json::json!({"host": "api.example.com"}),
+            )
+            .await?;
+        let result = manager
+            .execute_component_call("hooked", "ping", "{}")
+            .await?;
+        assert_eq!(result, r#"{"result":42}"#);
+
         Ok(())
     }
 
     #[test(tokio::test)]
-    async fn test_load_and_unload_component() -> Result<()> {
+    async fn test_unload_missing_component_is_not_found() -> Result<()> {
         let manager = create_test_manager().await?;
+        let mut events = manager.subscribe();
 
-        let load_result = manager.load_component("/path/to/nonexistent").await;
-        assert!(load_result.is_err());
+        assert_eq!(
+            manager.unload_component("does-not-exist").await?,
+            UnloadResult::NotFound
+        );
 
         manager.load_test_component().await?;
+        let _ = events.recv().await?;
+        assert_eq!(
+            manager.unload_component(TEST_COMPONENT_ID).await?,
+            UnloadResult::Unloaded
+        );
+        assert_eq!(
+            manager.unload_component(TEST_COMPONENT_ID).await?,
+            UnloadResult::NotFound
+        );
 
-        let loaded_components = manager.list_components().await;
-        assert_eq!(loaded_components.len(), 1);
+        // Only the real unload is announced
+        assert_eq!(
+            events.recv().await?,
+            LifecycleEvent::Unloaded {
+                id: TEST_COMPONENT_ID.to_string()
+            }
+        );
+        assert!(events.try_recv().is_err());
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_lifecycle_events_emitted_in_order() -> Result<()> {
+        let manager = create_test_manager().await?;
+        let mut events = manager.subscribe();
+        let component_path = build_example_component().await?;
+        let uri = format!("file://{}", component_path.to_str().unwrap());
 
+        manager.load_component(&uri).await?;
+        manager.load_component(&uri).await?;
         manager.unload_component(TEST_COMPONENT_ID).await?;
 
-        let loaded_components = manager.list_components().await;
-        assert!(loaded_components.is_empty());
+        assert_eq!(
+            events.recv().await?,
+            LifecycleEvent::Loaded {
+                id: TEST_COMPONENT_ID.to_string(),
+                source: uri,
+            }
+        );
+        assert_eq!(
+            events.recv().await?,
+            LifecycleEvent::Reloaded {
+                id: TEST_COMPONENT_ID.to_string(),
+            }
+        );
+        assert_eq!(
+            events.recv().await?,
+            LifecycleEvent::Unloaded {
+                id: TEST_COMPONENT_ID.to_string(),
+            }
+        );
+        assert!(events.try_recv().is_err());
 
         Ok(())
     }
@@ -1562,6 +5945,71 @@ mod tests {
         Ok(())
     }
 
+    const POLICY_WITH_UNKNOWN_PERMISSION: &str = r#"
+version: "1.0"
+permissions:
+  gpu:
+    allow: ["cuda"]
+  network:
+    allow:
+    - host: "api.example.com"
+"#;
+
+    #[test(tokio::test)]
+    async fn test_unknown_policy_permission_ignored_when_lenient() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let manager = LifecycleManager::new_unloaded(&tempdir).await?;
+        let component_path = build_example_component().await?;
+
+        tokio::fs::write(
+            manager.get_component_policy_path(TEST_COMPONENT_ID),
+            POLICY_WITH_UNKNOWN_PERMISSION,
+        )
+        .await?;
+
+        manager
+            .load_component(&format!("file://{}", component_path.display()))
+            .await?;
+        assert_eq!(manager.list_components().await, vec![TEST_COMPONENT_ID]);
+
+        let policy = manager
+            .policy_manager
+            .load_or_create_component_policy(TEST_COMPONENT_ID)
+            .await?;
+        assert!(policy.permissions.network.is_some());
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_unknown_policy_permission_rejected_when_strict() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let manager = LifecycleManager::builder(&tempdir)
+            .with_eager_loading(false)
+            .with_strict_policy(true)
+            .build()
+            .await?;
+        let component_path = build_example_component().await?;
+
+        tokio::fs::write(
+            manager.get_component_policy_path(TEST_COMPONENT_ID),
+            POLICY_WITH_UNKNOWN_PERMISSION,
+        )
+        .await?;
+
+        let err = manager
+            .load_component(&format!("file://{}", component_path.display()))
+            .await
+            .unwrap_err();
+        assert!(
+            format!("{err:#}").contains("Unknown permission type(s) in policy: gpu"),
+            "unexpected error: {err:#}"
+        );
+        assert!(manager.list_components().await.is_empty());
+
+        Ok(())
+    }
+
     #[test(tokio::test)]
     async fn test_component_reload() -> Result<()> {
         let manager = create_test_manager().await?;
@@ -1620,7 +6068,7 @@ permissions:
 
         // Test getting WASI state for component with attached policy
         let _wasi_state = manager
-            .get_wasi_state_for_component(TEST_COMPONENT_ID)
+            .get_wasi_state_for_component(TEST_COMPONENT_ID, None)
             .await?;
 
         Ok(())
@@ -1753,6 +6201,67 @@ permissions:
         Ok(())
     }
 
+    #[test(tokio::test)]
+    async fn test_audit_component_permissions_reports_network_gap() -> Result<()> {
+        let manager = create_test_manager().await?;
+        manager.load_test_component().await?;
+
+        let details = serde_json::json!({"host": "api.example.com"});
+        manager
+            .grant_permission(TEST_COMPONENT_ID, "network", &details)
+            .await?;
+        let audit = manager
+            .audit_component_permissions(TEST_COMPONENT_ID)
+            .await?;
+        assert!(audit.required.contains(&"network".to_string()));
+        assert_eq!(audit.granted, vec!["network"]);
+        assert!(!audit.gaps.contains(&"network".to_string()));
+        assert!(audit.over_grants.is_empty());
+
+        manager
+            .revoke_permission(TEST_COMPONENT_ID, "network", &details)
+            .await?;
+        let audit = manager
+            .audit_component_permissions(TEST_COMPONENT_ID)
+            .await?;
+        assert!(audit.granted.is_empty());
+        assert!(audit.gaps.contains(&"network".to_string()));
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_audit_counts_default_policy_grants() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let default_policy = PolicyParser::parse_str(
+            r#"
+version: "1.0"
+permissions:
+  network:
+    allow:
+      - host: "api.example.com"
+"#,
+        )?;
+        let manager = LifecycleManager::builder(tempdir.path())
+            .with_default_policy(Some(default_policy))
+            .build()
+            .await?;
+        let component_path = build_example_component().await?;
+        manager
+            .load_component(&format!("file://{}", component_path.display()))
+            .await?;
+
+        // The component's own policy grants nothing, but the default policy covers network
+        let audit = manager
+            .audit_component_permissions(TEST_COMPONENT_ID)
+            .await?;
+        assert!(audit.required.contains(&"network".to_string()));
+        assert_eq!(audit.granted, vec!["network"]);
+        assert!(audit.gaps.is_empty(), "{audit:?}");
+
+        Ok(())
+    }
+
     // Revoke permission system tests
 
     #[test(tokio::test)]
@@ -1965,4 +6474,213 @@ permissions:
 
         Ok(())
     }
+
+    #[test(tokio::test)]
+    async fn test_load_timeout_interrupts_stuck_component() -> Result<()> {
+        let component_dir = tempfile::tempdir()?;
+        logging_component(component_dir.path())?;
+        // A component whose init hook never returns and never yields to the async runtime
+        let wat = r#"(component
+                (core module $m
+                    (func (export "init") (result i32)
+                        (loop $spin (br $spin))
+                        unreachable)
+                )
+                (core instance $i (instantiate $m))
+                (type $status (result))
+                (func $init (result $status) (canon lift (core func $i "init")))
+                (instance $hooks (export "init" (func $init)))
+                (export "wassette:lifecycle/hooks" (instance $hooks))
+            )"#;
+        std::fs::write(
+            component_dir.path().join("stuck.wasm"),
+            wat::parse_str(wat)?,
+        )?;
+
+        let manager = LifecycleManager::builder(component_dir.path())
+            .with_eager_loading(false)
+            .with_load_timeout(Duration::from_millis(200))
+            .build()
+            .await?;
+        manager
+            .load_existing_components_async(None, None::<fn()>, StartupLoadPolicy::Continue)
+            .await?;
+
+        let report = manager.get_startup_report();
+        assert_eq!(
+            report.components.get("logger"),
+            Some(&ComponentLoadStatus::Loaded)
+        );
+        let failed: Vec<_> = report.failed().collect();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].0, "stuck");
+        assert!(failed[0].1.contains("Timed out"), "{}", failed[0].1);
+        assert!(failed[0].1.contains("stuck.wasm"), "{}", failed[0].1);
+        assert!(manager.get_component("stuck").await.is_none());
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_load_timeout_configured_from_builder() -> Result<()> {
+        let tempdir = tempfile::tempdir()?;
+        let config = LifecycleManager::builder(&tempdir)
+            .with_load_timeout(Duration::from_secs(10))
+            .build_config()?;
+        assert_eq!(config.load_timeout(), Some(Duration::from_secs(10)));
+
+        let manager = LifecycleManager::from_config(config).await?;
+        assert_eq!(manager.load_timeout, Some(Duration::from_secs(10)));
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_component_env_injected_into_wasi_template() -> Result<()> {
+        let manager = create_test_manager().await?;
+        manager.load_test_component().await?;
+
+        manager
+            .set_component_env(
+                TEST_COMPONENT_ID,
+                &[
+                    ("LOG_LEVEL".to_string(), "debug".to_string()),
+                    ("SHARED".to_string(), "from-config".to_string()),
+                ],
+            )
+            .await?;
+        manager
+            .set_component_secrets(
+                TEST_COMPONENT_ID,
+                &[("SHARED".to_string(), "from-secret".to_string())],
+            )
+            .await?;
+
+        let env = manager.get_component_env(TEST_COMPONENT_ID).await?;
+        assert_eq!(env.get("LOG_LEVEL"), Some(&"debug".to_string()));
+
+        // Component env is not stored alongside secrets
+        let secrets = manager.load_component_secrets(TEST_COMPONENT_ID).await?;
+        assert!(!secrets.contains_key("LOG_LEVEL"));
+
+        let template = manager.component_wasi_template(TEST_COMPONENT_ID).await?;
+        assert_eq!(
+            template.config_vars.get("LOG_LEVEL"),
+            Some(&"debug".to_string())
+        );
+        // Secrets take precedence over component env on key conflicts
+        assert_eq!(
+            template.config_vars.get("SHARED"),
+            Some(&"from-secret".to_string())
+        );
+
+        manager
+            .unset_component_env(TEST_COMPONENT_ID, &["LOG_LEVEL".to_string()])
+            .await?;
+        let template = manager.component_wasi_template(TEST_COMPONENT_ID).await?;
+        assert!(!template.config_vars.contains_key("LOG_LEVEL"));
+
+        Ok(())
+    }
+
+    /// A component whose `env` tool returns the WASI environment it was started with
+    fn env_echo_component(dir: &Path) -> Result<PathBuf> {
+        let wat = r#"(component
+                (import "wasi:cli/environment@0.2.0" (instance $environment
+                    (export "get-environment" (func (result (list (tuple string string)))))
+                ))
+                (core module $libc
+                    (memory (export "memory") 1)
+                    (global $next (mut i32) (i32.const 1024))
+                    (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+                        (local $ptr i32)
+                        (local.set $ptr
+                            (i32.and
+                                (i32.add (global.get $next) (i32.sub (local.get 2) (i32.const 1)))
+                                (i32.sub (i32.const 0) (local.get 2))))
+                        (global.set $next (i32.add (local.get $ptr) (local.get 3)))
+                        (local.get $ptr))
+                )
+                (core instance $libc (instantiate $libc))
+                (alias core export $libc "memory" (core memory $mem))
+                (alias core export $libc "realloc" (core func $realloc))
+                (core func $get-environment
+                    (canon lower (func $environment "get-environment") (memory $mem) (realloc $realloc)))
+                (core module $m
+                    (import "host" "get-environment" (func $get-environment (param i32)))
+                    (func (export "env") (result i32)
+                        (call $get-environment (i32.const 8))
+                        (i32.const 8))
+                )
+                (core instance $host (export "get-environment" (func $get-environment)))
+                (core instance $i (instantiate $m (with "host" (instance $host))))
+                (func $env (result (list (tuple string string)))
+                    (canon lift (core func $i "env") (memory $mem)))
+                (export "env" (func $env))
+            )"#;
+        let path = dir.join("env-echo.wasm");
+        std::fs::write(&path, wat::parse_str(wat)?)?;
+        Ok(path)
+    }
+
+    #[test(tokio::test)]
+    async fn test_component_sees_its_env() -> Result<()> {
+        let source_dir = tempfile::tempdir()?;
+        let uri = format!(
+            "file://{}",
+            env_echo_component(source_dir.path())?.display()
+        );
+        let manager = create_test_manager().await?;
+        manager.load_component(&uri).await?;
+        let env = |result: String| -> Result<Value> {
+            Ok(serde_json::from_str::<Value>(&result)?["result"].clone())
+        };
+
+        assert_eq!(
+            env(manager
+                .execute_component_call("env-echo", "env", "{}")
+                .await?)?,
+            serde_json::json!([])
+        );
+
+        manager
+            .set_component_env(
+                "env-echo",
+                &[("LOG_LEVEL".to_string(), "debug".to_string())],
+            )
+            .await?;
+        assert_eq!(
+            env(manager
+                .execute_component_call("env-echo", "env", "{}")
+                .await?)?,
+            serde_json::json!([["LOG_LEVEL", "debug"]])
+        );
+
+        manager
+            .unset_component_env("env-echo", &["LOG_LEVEL".to_string()])
+            .await?;
+        assert_eq!(
+            env(manager
+                .execute_component_call("env-echo", "env", "{}")
+                .await?)?,
+            serde_json::json!([])
+        );
+
+        Ok(())
+    }
+
+    #[test(tokio::test)]
+    async fn test_set_component_env_component_not_found() -> Result<()> {
+        let manager = create_test_manager().await?;
+
+        let result = manager
+            .set_component_env("missing", &[("KEY".to_string(), "value".to_string())])
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Component not found"));
+
+        Ok(())
+    }
 }
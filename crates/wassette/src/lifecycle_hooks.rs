@@ -0,0 +1,179 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Optional setup and teardown functions exported through the `wassette:lifecycle/hooks`
+//! interface.
+//!
+//! `init` runs once before a component becomes callable and an error result aborts the load.
+//! `shutdown` runs when the component is unloaded or replaced. Unlike other components, which
+//! get a fresh instance for every tool call, a component exporting the interface keeps a
+//! single [`Resident`] instance from `init` to `shutdown` and its calls run in it one at a
+//! time, so state `init` keeps in memory is there for them.
+
+use std::sync::Arc;
+
+use component2json::ToolMetadata;
+use tokio::sync::Mutex as AsyncMutex;
+use wasmtime::component::{Component, Instance, Val};
+use wasmtime::{Engine, Store};
+
+use crate::health::is_health_interface;
+use crate::wasistate::{WasiState, WasiStateTemplate};
+use crate::WassetteWasiState;
+
+/// Name of the interface a component exports to receive lifecycle hooks
+pub const LIFECYCLE_INTERFACE: &str = "wassette:lifecycle/hooks";
+
+/// A function of the lifecycle interface
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LifecycleHook {
+    Init,
+    Shutdown,
+}
+
+impl LifecycleHook {
+    pub(crate) fn function_name(self) -> &'static str {
+        match self {
+            LifecycleHook::Init => "init",
+            LifecycleHook::Shutdown => "shutdown",
+        }
+    }
+}
+
+/// The instance kept for a component exporting the lifecycle interface
+pub(crate) struct Resident {
+    /// Export name of the lifecycle interface
+    pub(crate) interface: String,
+    /// The running instance, absent before `init` succeeds and after `shutdown`
+    pub(crate) live: AsyncMutex<Option<LiveInstance>>,
+}
+
+impl Resident {
+    /// A resident slot for `component` if it exports the lifecycle interface
+    pub(crate) fn for_component(component: &Component, engine: &Engine) -> Option<Arc<Self>> {
+        lifecycle_export(component, engine).map(|interface| {
+            Arc::new(Self {
+                interface,
+                live: AsyncMutex::new(None),
+            })
+        })
+    }
+}
+
+/// A resident instance together with its store
+pub(crate) struct LiveInstance {
+    pub(crate) store: Store<WassetteWasiState<WasiState>>,
+    pub(crate) instance: Instance,
+    /// Policy template registered for the component when the store was built, if any. A
+    /// different one means the policy changed and the instance has to be restarted.
+    pub(crate) policy: Option<Arc<WasiStateTemplate>>,
+}
+
+impl LiveInstance {
+    /// Whether the instance was built from `policy`, the template now registered
+    pub(crate) fn has_policy(&self, policy: Option<&Arc<WasiStateTemplate>>) -> bool {
+        match (&self.policy, policy) {
+            (Some(built), Some(current)) => Arc::ptr_eq(built, current),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Whether `name` is the lifecycle interface, with or without a version suffix
+fn is_lifecycle_interface(name: &str) -> bool {
+    name.strip_prefix(LIFECYCLE_INTERFACE)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('@'))
+}
+
+/// Export name of the lifecycle interface if the component implements it
+pub(crate) fn lifecycle_export(component: &Component, engine: &Engine) -> Option<String> {
+    component
+        .component_type()
+        .exports(engine)
+        .map(|(name, _)| name)
+        .find(|name| is_lifecycle_interface(name))
+        .map(str::to_string)
+}
+
+/// Drop hook and health check functions from a component's tool list so they are never
+/// exposed to clients
+pub(crate) fn without_lifecycle_tools(tools: Vec<ToolMetadata>) -> Vec<ToolMetadata> {
+    tools
+        .into_iter()
+        .filter(|tool| {
+            !tool
+                .identifier
+                .interface_name
+                .as_deref()
+                .is_some_and(|name| is_lifecycle_interface(name) || is_health_interface(name))
+        })
+        .collect()
+}
+
+/// Interpret the values returned by a hook. Only an `err` result counts as a failure.
+pub(crate) fn hook_outcome(results: &[Val]) -> Result<(), String> {
+    match results.first() {
+        Some(Val::Result(Err(payload))) => Err(match payload.as_deref() {
+            Some(Val::String(message)) => message.clone(),
+            Some(other) => format!("{other:?}"),
+            None => "hook returned an error".to_string(),
+        }),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use component2json::FunctionIdentifier;
+    use serde_json::json;
+
+    use super::*;
+
+    fn tool(interface_name: Option<&str>, function_name: &str) -> ToolMetadata {
+        ToolMetadata {
+            identifier: FunctionIdentifier {
+                package_name: None,
+                interface_name: interface_name.map(str::to_string),
+                function_name: function_name.to_string(),
+            },
+            normalized_name: function_name.to_string(),
+            schema: json!({}),
+        }
+    }
+
+    #[test]
+    fn test_is_lifecycle_interface() {
+        assert!(is_lifecycle_interface("wassette:lifecycle/hooks"));
+        assert!(is_lifecycle_interface("wassette:lifecycle/hooks@0.1.0"));
+        assert!(!is_lifecycle_interface("wassette:lifecycle/hooks-extra"));
+        assert!(!is_lifecycle_interface("wassette:test/hooks"));
+    }
+
+    #[test]
+    fn test_without_lifecycle_tools() {
+        let tools = without_lifecycle_tools(vec![
+            tool(Some("wassette:lifecycle/hooks@0.1.0"), "init"),
+            tool(Some("wassette:lifecycle/hooks@0.1.0"), "shutdown"),
+            tool(Some("wassette:health/check"), "check"),
+            tool(Some("component:fetch/fetch"), "fetch"),
+            tool(None, "ping"),
+        ]);
+
+        let names: Vec<_> = tools.iter().map(|t| t.normalized_name.as_str()).collect();
+        assert_eq!(names, vec!["fetch", "ping"]);
+    }
+
+    #[test]
+    fn test_hook_outcome() {
+        assert!(hook_outcome(&[]).is_ok());
+        assert!(hook_outcome(&[Val::Result(Ok(None))]).is_ok());
+        assert_eq!(
+            hook_outcome(&[Val::Result(Err(Some(Box::new(Val::String(
+                "database unavailable".to_string()
+            )))))]),
+            Err("database unavailable".to_string())
+        );
+        assert!(hook_outcome(&[Val::Result(Err(None))]).is_err());
+    }
+}
@@ -7,9 +7,12 @@ use std::path::{Path, PathBuf};
 use anyhow::{bail, Context, Result};
 use futures::TryStreamExt;
 use tokio::fs::metadata;
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tracing::{debug, info, warn};
 
+/// Binary encoding version of the component model that Wasmtime accepts
+const COMPONENT_ENCODING_VERSION: u16 = 0x0d;
+
 /// Represents a downloaded resource, either from a local file or a temporary one.
 pub enum DownloadedResource {
     Local(PathBuf),
@@ -69,7 +72,27 @@ impl DownloadedResource {
             .ok_or_else(|| anyhow::anyhow!("Failed to extract resource ID from path"))
     }
 
-    pub async fn copy_to(self, dest: impl AsRef<Path>) -> Result<()> {
+    /// Whether a policy file was downloaded alongside the resource, as with multi-layer OCI
+    /// artifacts. Such a policy is installed together with the component by [`Self::copy_to`].
+    pub fn has_colocated_policy(&self) -> bool {
+        self.colocated_policy_path().is_some()
+    }
+
+    /// Path of the policy file downloaded alongside the resource, if there is one.
+    pub fn colocated_policy_path(&self) -> Option<PathBuf> {
+        match self {
+            DownloadedResource::Local(_) => None,
+            DownloadedResource::Temp((tempdir, file)) => file
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .map(|stem| tempdir.path().join(format!("{stem}.policy.yaml")))
+                .filter(|path| path.exists()),
+        }
+    }
+
+    /// Copy the resource into `dest` as `{component_id}.wasm`, together with any co-located
+    /// policy file.
+    pub async fn copy_to(self, dest: impl AsRef<Path>, component_id: &str) -> Result<()> {
         let meta = tokio::fs::metadata(&dest).await?;
         if !meta.is_dir() {
             bail!(
@@ -79,10 +102,7 @@ impl DownloadedResource {
         }
         match self {
             DownloadedResource::Local(path) => {
-                let dest = dest.as_ref().join(
-                    path.file_name()
-                        .context("Path to copy is missing filename")?,
-                );
+                let dest = dest.as_ref().join(format!("{component_id}.wasm"));
                 tokio::fs::copy(path, dest).await?;
             }
             DownloadedResource::Temp((tempdir, file)) => {
@@ -93,7 +113,7 @@ impl DownloadedResource {
                 let policy_path = tempdir.path().join(format!("{wasm_stem}.policy.yaml"));
 
                 if policy_path.exists() {
-                    let policy_dest = dest_dir.join(format!("{wasm_stem}.policy.yaml"));
+                    let policy_dest = dest_dir.join(format!("{component_id}.policy.yaml"));
                     debug!(
                         "Copying co-located policy file from {:?} to {:?}",
                         policy_path, policy_dest
@@ -104,10 +124,7 @@ impl DownloadedResource {
                 }
 
                 // Copy the main file (WASM)
-                let dest_file = dest_dir.join(
-                    file.file_name()
-                        .context("Path to copy is missing filename")?,
-                );
+                let dest_file = dest_dir.join(format!("{component_id}.wasm"));
 
                 // Copy the main WASM file
                 match tokio::fs::rename(&file, &dest_file).await {
@@ -159,6 +176,7 @@ pub trait Loadable: Sized {
         show_progress: bool,
     ) -> Result<DownloadedResource>;
     async fn from_url(url: &str, http_client: &reqwest::Client) -> Result<DownloadedResource>;
+    async fn from_git_reference(uri: &str) -> Result<DownloadedResource>;
 }
 
 /// Loadable implementation for WebAssembly components
@@ -177,9 +195,13 @@ impl Loadable for ComponentResource {
             bail!("Component path does not exist: {}. Please provide a valid path to a WebAssembly component file.", path.display());
         }
 
+        if crate::bundle::is_bundle(path) {
+            return crate::bundle::open_bundle(path).await;
+        }
+
         if path.extension().unwrap_or_default() != Self::FILE_EXTENSION {
             bail!(
-                "Invalid file extension for component: {}. Component file must have .{} extension.",
+                "Invalid file extension for component: {}. Component file must have .{} extension or be a .tar.gz bundle.",
                 path.display(),
                 Self::FILE_EXTENSION
             );
@@ -311,6 +333,38 @@ impl Loadable for ComponentResource {
         drop(file);
         Ok(downloaded_resource)
     }
+
+    async fn from_git_reference(uri: &str) -> Result<DownloadedResource> {
+        let reference = crate::git::GitReference::parse(uri)?;
+        let checkout = tokio::task::spawn_blocking(tempfile::tempdir).await??;
+        let artifact = reference.fetch_into(checkout.path()).await?;
+
+        if crate::bundle::is_bundle(&artifact) {
+            return crate::bundle::open_bundle(&artifact).await;
+        }
+        if artifact.extension().unwrap_or_default() != Self::FILE_EXTENSION {
+            bail!(
+                "Invalid file extension for component: {}. Component file must have .{} extension or be a .tar.gz bundle.",
+                reference.path,
+                Self::FILE_EXTENSION
+            );
+        }
+
+        let name = artifact
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .context("Failed to discover name from Git reference")?;
+        let (downloaded_resource, mut file) =
+            DownloadedResource::new_temp_file(name, Self::FILE_EXTENSION).await?;
+        let mut source = tokio::fs::File::open(&artifact).await?;
+        tokio::io::copy(&mut source, &mut file)
+            .await
+            .context("Failed to copy component out of the Git checkout")?;
+        file.flush().await?;
+        file.sync_all().await?;
+        drop(file);
+        Ok(downloaded_resource)
+    }
 }
 
 /// Loadable implementation for policies
@@ -372,6 +426,10 @@ impl Loadable for PolicyResource {
 
         Ok(downloaded_resource)
     }
+
+    async fn from_git_reference(_uri: &str) -> Result<DownloadedResource> {
+        bail!("Git references are not supported for policy resources. Use 'file://' or 'https://' schemes instead.")
+    }
 }
 
 /// Generic resource loading function
@@ -401,14 +459,67 @@ pub(crate) async fn load_resource_with_progress<T: Loadable>(
         "file" => T::from_local_file(Path::new(reference)).await,
         "oci" => T::from_oci_reference_with_progress(reference, oci_client, show_progress).await,
         "https" => T::from_url(uri, http_client).await,
+        git if git.starts_with(crate::git::GIT_SCHEME_PREFIX) => T::from_git_reference(uri).await,
         _ => bail!("Unsupported {} scheme: {}", T::RESOURCE_TYPE, scheme),
     }
 }
 
+/// Reject binaries that are not components with an explanation instead of Wasmtime's
+/// decoding error. Bytes without the WebAssembly magic number are left for the compiler
+/// to report.
+pub(crate) fn ensure_component_binary(bytes: &[u8]) -> Result<()> {
+    let Some(header) = bytes.get(..8) else {
+        return Ok(());
+    };
+    if header[..4] != *b"\0asm" {
+        return Ok(());
+    }
+
+    let version = u16::from_le_bytes([header[4], header[5]]);
+    let layer = u16::from_le_bytes([header[6], header[7]]);
+    match layer {
+        0 => bail!(
+            "This file is a core WebAssembly module, not a component; Wassette only runs components. Consider `wasm-tools component new`."
+        ),
+        1 if version != COMPONENT_ENCODING_VERSION => bail!(
+            "This component is encoded with component-model binary version {version:#x}, but Wassette supports version {COMPONENT_ENCODING_VERSION:#x}. Rebuild it with a toolchain that targets the current component model."
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// Check the header of the file at `path` with [`ensure_component_binary`]
+pub(crate) async fn ensure_component_file(path: &Path) -> Result<()> {
+    let file = tokio::fs::File::open(path)
+        .await
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut header = Vec::with_capacity(8);
+    file.take(8).read_to_end(&mut header).await?;
+    ensure_component_binary(&header)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_ensure_component_binary() {
+        let core = [0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+        let err = ensure_component_binary(&core).unwrap_err();
+        assert!(err.to_string().contains("core WebAssembly module"));
+
+        let component = [0x00, 0x61, 0x73, 0x6d, 0x0d, 0x00, 0x01, 0x00];
+        assert!(ensure_component_binary(&component).is_ok());
+
+        let old_component = [0x00, 0x61, 0x73, 0x6d, 0x0a, 0x00, 0x01, 0x00];
+        let err = ensure_component_binary(&old_component).unwrap_err();
+        assert!(err.to_string().contains("binary version 0xa"));
+
+        // Text and truncated input are left for the compiler to diagnose
+        assert!(ensure_component_binary(b"(component)").is_ok());
+        assert!(ensure_component_binary(b"\0asm").is_ok());
+    }
+
     #[test]
     fn test_load_resource_with_progress_api_exists() {
         // Compile-time test to verify the progress-aware API exists
@@ -0,0 +1,57 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Admission control against a host-wide memory budget.
+//!
+//! Each component may declare a memory limit in its policy. With a budget set through
+//! [`LifecycleBuilder::with_max_loaded_memory`](crate::LifecycleBuilder::with_max_loaded_memory),
+//! a component is only loaded while the declared limits of all loaded components, its own
+//! included, fit in the budget. Components that declare no limit are refused.
+
+use std::fmt;
+
+/// Error returned when loading a component would take the declared memory limits of the
+/// loaded components past the memory budget
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InsufficientMemoryBudget {
+    /// Component that was refused
+    pub component_id: String,
+    /// Memory limit the component declares, in bytes
+    pub requested: u64,
+    /// Sum of the limits declared by the other loaded components, in bytes
+    pub reserved: u64,
+    /// The memory budget, in bytes
+    pub budget: u64,
+}
+
+impl InsufficientMemoryBudget {
+    /// Refusal of `component_id` when `requested` does not fit next to `reserved`
+    pub(crate) fn check(
+        component_id: &str,
+        requested: u64,
+        reserved: u64,
+        budget: u64,
+    ) -> Result<(), Self> {
+        if reserved.saturating_add(requested) <= budget {
+            return Ok(());
+        }
+        Err(Self {
+            component_id: component_id.to_string(),
+            requested,
+            reserved,
+            budget,
+        })
+    }
+}
+
+impl fmt::Display for InsufficientMemoryBudget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Insufficient memory budget: '{}' declares a memory limit of {} bytes, but loaded components already reserve {} of the {} bytes allowed. Unload a component or lower its memory limit",
+            self.component_id, self.requested, self.reserved, self.budget
+        )
+    }
+}
+
+impl std::error::Error for InsufficientMemoryBudget {}
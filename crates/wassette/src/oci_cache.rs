@@ -0,0 +1,245 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! On-disk cache of components pulled from OCI registries.
+//!
+//! An index maps each reference to the manifest digest it last resolved to, and the artifact
+//! pulled for a digest is kept in a directory named after it. Loading a reference whose
+//! manifest digest is unchanged then costs a single manifest `HEAD` request instead of a pull
+//! of every blob, which keeps restarts fast for pinned or rarely updated components.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use oci_client::secrets::RegistryAuth;
+use oci_client::Reference;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex as AsyncMutex;
+use tracing::{debug, info, warn};
+
+use crate::loader::{ComponentResource, DownloadedResource, Loadable};
+
+const INDEX_FILE: &str = "index.json";
+const ARTIFACT_FILE: &str = "component.wasm";
+const POLICY_FILE: &str = "component.policy.yaml";
+
+/// Components pulled from OCI registries, keyed by manifest digest.
+#[derive(Clone)]
+pub(crate) struct OciCache {
+    dir: PathBuf,
+    /// Serializes updates of the index and of cached artifacts
+    write_lock: Arc<AsyncMutex<()>>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct CacheIndex {
+    /// Manifest digest each reference resolved to when it was last pulled
+    references: BTreeMap<String, String>,
+}
+
+impl OciCache {
+    pub(crate) fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            write_lock: Arc::default(),
+        }
+    }
+
+    /// Pull the component at `reference` (without the `oci://` scheme), reusing the cached
+    /// artifact when the registry still reports the digest it was cached under.
+    ///
+    /// Registries that cannot answer the digest query are pulled from directly, and failing
+    /// to update the cache never fails the pull.
+    pub(crate) async fn pull(
+        &self,
+        reference: &str,
+        client: &oci_client::Client,
+        show_progress: bool,
+    ) -> Result<DownloadedResource> {
+        let parsed: Reference = reference.parse().context("Failed to parse OCI reference")?;
+        let digest = match client
+            .fetch_manifest_digest(&parsed, &RegistryAuth::Anonymous)
+            .await
+        {
+            Ok(digest) => digest,
+            Err(error) => {
+                debug!(reference, %error, "Could not resolve manifest digest, pulling uncached");
+                return ComponentResource::from_oci_reference_with_progress(
+                    reference,
+                    client,
+                    show_progress,
+                )
+                .await;
+            }
+        };
+
+        match self.lookup(reference, &parsed, &digest).await {
+            Ok(Some(resource)) => {
+                info!(
+                    reference,
+                    digest, "Manifest digest unchanged, using cached component"
+                );
+                if show_progress {
+                    eprintln!("✓ {reference} is unchanged, using the cached download");
+                }
+                return Ok(resource);
+            }
+            Ok(None) => {}
+            Err(error) => {
+                warn!(reference, error = %format!("{error:#}"), "Ignoring OCI cache entry")
+            }
+        }
+
+        let resource =
+            ComponentResource::from_oci_reference_with_progress(reference, client, show_progress)
+                .await?;
+        if let Err(error) = self.store(reference, &digest, &resource).await {
+            warn!(reference, error = %format!("{error:#}"), "Failed to cache OCI component");
+        }
+        Ok(resource)
+    }
+
+    /// A copy of the artifact cached for `reference`, if it was last pulled at `digest`
+    async fn lookup(
+        &self,
+        reference: &str,
+        parsed: &Reference,
+        digest: &str,
+    ) -> Result<Option<DownloadedResource>> {
+        let index = self.read_index().await?;
+        if index.references.get(reference).map(String::as_str) != Some(digest) {
+            return Ok(None);
+        }
+        let Some(entry) = self.entry_dir(digest) else {
+            return Ok(None);
+        };
+        let artifact = entry.join(ARTIFACT_FILE);
+        if !tokio::fs::try_exists(&artifact).await? {
+            return Ok(None);
+        }
+
+        // Hand out a copy named like a fresh pull so that installing the component neither
+        // consumes the cached artifact nor changes the derived component id
+        let name = parsed.repository().replace('/', "_");
+        let (resource, file) = DownloadedResource::new_temp_file(&name, "wasm").await?;
+        drop(file);
+        tokio::fs::copy(&artifact, resource.as_ref()).await?;
+        let policy = entry.join(POLICY_FILE);
+        if tokio::fs::try_exists(&policy).await? {
+            if let DownloadedResource::Temp((tempdir, _)) = &resource {
+                tokio::fs::copy(&policy, tempdir.path().join(format!("{name}.policy.yaml")))
+                    .await?;
+            }
+        }
+        Ok(Some(resource))
+    }
+
+    /// Record that `reference` resolved to `digest` and keep a copy of what was pulled
+    async fn store(
+        &self,
+        reference: &str,
+        digest: &str,
+        resource: &DownloadedResource,
+    ) -> Result<()> {
+        let Some(entry) = self.entry_dir(digest) else {
+            debug!(
+                reference,
+                digest, "Not caching an artifact with an unexpected digest"
+            );
+            return Ok(());
+        };
+        let _guard = self.write_lock.lock().await;
+
+        tokio::fs::create_dir_all(&entry)
+            .await
+            .with_context(|| format!("Failed to create {}", entry.display()))?;
+        copy_atomic(resource.as_ref(), &entry.join(ARTIFACT_FILE)).await?;
+        match resource.colocated_policy_path() {
+            Some(policy) => copy_atomic(&policy, &entry.join(POLICY_FILE)).await?,
+            None => remove_if_present(&entry.join(POLICY_FILE)).await?,
+        }
+
+        let mut index = self.read_index().await?;
+        let previous = index
+            .references
+            .insert(reference.to_string(), digest.to_string());
+        let json = serde_json::to_vec_pretty(&index)?;
+        write_atomic(&self.dir.join(INDEX_FILE), &json).await?;
+
+        // Drop the artifact the reference pointed at before, unless another reference shares it
+        if let Some(previous) = previous.filter(|previous| previous != digest) {
+            if !index.references.values().any(|d| *d == previous) {
+                if let Some(stale) = self.entry_dir(&previous) {
+                    if let Err(error) = tokio::fs::remove_dir_all(&stale).await {
+                        debug!(path = %stale.display(), %error, "Failed to remove stale OCI cache entry");
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn read_index(&self) -> Result<CacheIndex> {
+        match tokio::fs::read(self.dir.join(INDEX_FILE)).await {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes).unwrap_or_else(|error| {
+                warn!(%error, "Discarding unreadable OCI cache index");
+                CacheIndex::default()
+            })),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(CacheIndex::default()),
+            Err(e) => Err(e).context("Failed to read the OCI cache index"),
+        }
+    }
+
+    /// Directory holding the artifact for `digest`, or `None` if the digest is not of the
+    /// `algorithm:hex` form and so cannot safely be used as a file name
+    fn entry_dir(&self, digest: &str) -> Option<PathBuf> {
+        let (algorithm, hex) = digest.split_once(':')?;
+        let valid =
+            |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric());
+        (valid(algorithm) && valid(hex)).then(|| self.dir.join(format!("{algorithm}-{hex}")))
+    }
+}
+
+async fn copy_atomic(from: &Path, to: &Path) -> Result<()> {
+    let partial = to.with_extension("partial");
+    tokio::fs::copy(from, &partial)
+        .await
+        .with_context(|| format!("Failed to copy {} to {}", from.display(), partial.display()))?;
+    tokio::fs::rename(&partial, to).await?;
+    Ok(())
+}
+
+async fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let partial = path.with_extension("partial");
+    tokio::fs::write(&partial, contents)
+        .await
+        .with_context(|| format!("Failed to write {}", partial.display()))?;
+    tokio::fs::rename(&partial, path).await?;
+    Ok(())
+}
+
+async fn remove_if_present(path: &Path) -> Result<()> {
+    match tokio::fs::remove_file(path).await {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_dir_rejects_unsafe_digests() {
+        let cache = OciCache::new(PathBuf::from("/cache"));
+        assert_eq!(
+            cache.entry_dir("sha256:abc123"),
+            Some(PathBuf::from("/cache/sha256-abc123"))
+        );
+        assert_eq!(cache.entry_dir("sha256:../../etc"), None);
+        assert_eq!(cache.entry_dir("abc123"), None);
+        assert_eq!(cache.entry_dir("sha256:"), None);
+    }
+}
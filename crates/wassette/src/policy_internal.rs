@@ -4,10 +4,11 @@
 //! Policy management structures and types
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
 use oci_wasm::WasmClient;
 use policy::{
     AccessType, EnvironmentPermission, NetworkHostPermission, NetworkPermission, PolicyDocument,
@@ -15,12 +16,15 @@ use policy::{
 };
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use tokio::sync::RwLock;
-use tracing::{info, instrument, warn};
+use tokio::sync::{Mutex as AsyncMutex, RwLock};
+use tracing::{debug, info, instrument, warn};
 
 use crate::component_storage::ComponentStorage;
 use crate::loader::{self, PolicyResource};
-use crate::{SecretsManager, WasiStateTemplate};
+use crate::{
+    forget_keyed_lock, keyed_lock, DeclaredPermission, KeyedLocks, SecretsManager,
+    WasiStateTemplate,
+};
 
 /// Granular permission rule types
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,6 +61,17 @@ pub(crate) struct PolicyRegistry {
     pub(crate) component_policies: HashMap<String, Arc<WasiStateTemplate>>,
 }
 
+/// How the policy manager interprets policies, taken from the lifecycle configuration
+#[derive(Clone)]
+pub(crate) struct PolicySettings {
+    /// Reject policies declaring unknown permission types instead of ignoring them
+    pub(crate) strict_policy: bool,
+    /// Permissions every component gets on top of its own policy
+    pub(crate) default_policy: Option<Arc<PolicyDocument>>,
+    /// Ignore every capability policies grant and refuse new grants
+    pub(crate) safe_mode: bool,
+}
+
 #[derive(Clone)]
 pub(crate) struct PolicyManager {
     registry: Arc<RwLock<PolicyRegistry>>,
@@ -65,6 +80,48 @@ pub(crate) struct PolicyManager {
     environment_vars: Arc<HashMap<String, String>>,
     oci_client: Arc<WasmClient>,
     http_client: Client,
+    strict_policy: bool,
+    /// Permissions every component gets on top of its own policy
+    default_policy: Option<Arc<PolicyDocument>>,
+    /// Ignore every capability policies grant and refuse new grants
+    safe_mode: bool,
+    /// Per-component locks serializing read-modify-write updates of the policy file, so
+    /// concurrent grants and revokes do not lose each other's changes
+    policy_locks: Arc<KeyedLocks>,
+}
+
+/// Where a component's policy came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolicySource {
+    /// Written next to the component without a recorded origin, e.g. by permission grants
+    Inline,
+    /// Attached from a local file or an HTTP(S) URI
+    Attached,
+    /// Attached from an OCI registry or bundled with an OCI component
+    Oci,
+    /// No policy file exists and the component runs with the deny-by-default policy
+    Default,
+}
+
+impl PolicySource {
+    /// Classify a policy from the source URI recorded when it was attached, if any
+    fn from_recorded_uri(source_uri: Option<&str>) -> Self {
+        match source_uri {
+            None => PolicySource::Inline,
+            Some(uri) if uri.starts_with("oci://") => PolicySource::Oci,
+            Some(_) => PolicySource::Attached,
+        }
+    }
+
+    /// Lowercase name used in tool and CLI output
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PolicySource::Inline => "inline",
+            PolicySource::Attached => "attached",
+            PolicySource::Oci => "oci",
+            PolicySource::Default => "default",
+        }
+    }
 }
 
 /// Information about a policy attached to a component
@@ -72,6 +129,8 @@ pub(crate) struct PolicyManager {
 pub struct PolicyInfo {
     /// Unique identifier for the policy
     pub policy_id: String,
+    /// How the policy was provided
+    pub source: PolicySource,
     /// The original URI where the policy was loaded from
     pub source_uri: String,
     /// Local filesystem path where the policy is stored
@@ -80,6 +139,8 @@ pub struct PolicyInfo {
     pub component_id: String,
     /// Timestamp when the policy was created/attached
     pub created_at: std::time::SystemTime,
+    /// Timestamp when the policy file was last written
+    pub modified_at: std::time::SystemTime,
 }
 
 impl PolicyManager {
@@ -89,7 +150,13 @@ impl PolicyManager {
         environment_vars: Arc<HashMap<String, String>>,
         oci_client: Arc<WasmClient>,
         http_client: Client,
+        settings: PolicySettings,
     ) -> Self {
+        let PolicySettings {
+            strict_policy,
+            default_policy,
+            safe_mode,
+        } = settings;
         Self {
             registry: Arc::new(RwLock::new(PolicyRegistry::default())),
             storage,
@@ -97,9 +164,93 @@ impl PolicyManager {
             environment_vars,
             oci_client,
             http_client,
+            strict_policy,
+            default_policy,
+            safe_mode,
+            policy_locks: Arc::default(),
         }
     }
 
+    fn policy_lock(&self, component_id: &str) -> Arc<AsyncMutex<()>> {
+        keyed_lock(&self.policy_locks, component_id)
+    }
+
+    pub(crate) fn is_safe_mode(&self) -> bool {
+        self.safe_mode
+    }
+
+    /// Whether policies declaring unknown permission types are rejected.
+    pub(crate) fn is_strict(&self) -> bool {
+        self.strict_policy
+    }
+
+    /// Parse a component policy. Unknown permission types are rejected in strict mode and
+    /// otherwise logged and ignored so the recognized permissions still apply.
+    fn parse_policy(&self, component_id: &str, content: &str) -> Result<PolicyDocument> {
+        if self.strict_policy {
+            return PolicyParser::parse_str_strict(content);
+        }
+
+        let parsed = PolicyParser::parse_str_checked(content)?;
+        if let Some(version) = &parsed.migrated_from {
+            debug!(
+                component_id,
+                from = %version,
+                to = policy::CURRENT_POLICY_VERSION,
+                "Migrated policy to the current format"
+            );
+        }
+        for permission_type in &parsed.unknown_permissions {
+            warn!(
+                component_id,
+                permission_type = %permission_type,
+                "Ignoring unknown permission type in policy"
+            );
+        }
+        Ok(parsed.document)
+    }
+
+    /// The policy a component runs with: its own policy merged with the default policy
+    pub(crate) fn effective_policy(&self, policy: &PolicyDocument) -> PolicyDocument {
+        let merged = match &self.default_policy {
+            Some(default) => policy::merge(default, policy),
+            None => policy.clone(),
+        };
+        if !self.safe_mode {
+            return merged;
+        }
+        // Safe mode keeps only the restrictions
+        PolicyDocument {
+            permissions: policy::Permissions {
+                resources: merged.permissions.resources,
+                ..Default::default()
+            },
+            ..merged
+        }
+    }
+
+    /// Build the WASI template enforcing `policy` together with the default policy
+    fn template_from_policy(
+        &self,
+        policy: &PolicyDocument,
+        secrets: Option<&HashMap<String, String>>,
+    ) -> Result<WasiStateTemplate> {
+        if self.safe_mode {
+            return crate::create_wasi_state_template_from_policy(
+                &self.effective_policy(policy),
+                self.storage.root(),
+                &HashMap::new(),
+                None,
+            );
+        }
+        crate::create_wasi_state_template_from_policy(
+            &self.effective_policy(policy),
+            self.storage.root(),
+            self.environment_vars.as_ref(),
+            secrets,
+        )
+    }
+
     pub(crate) fn policy_path(&self, component_id: &str) -> PathBuf {
         self.storage.policy_path(component_id)
     }
@@ -114,6 +265,7 @@ impl PolicyManager {
             .await
             .component_policies
             .remove(component_id);
+        forget_keyed_lock(&self.policy_locks, component_id);
     }
 
     pub(crate) async fn store_template(
@@ -146,9 +298,34 @@ impl PolicyManager {
         self.build_default_template(component_id).await
     }
 
+    /// The template registered from the component's policy, or `None` when it runs with
+    /// the default one
+    pub(crate) async fn registered_template(
+        &self,
+        component_id: &str,
+    ) -> Option<Arc<WasiStateTemplate>> {
+        self.registry
+            .read()
+            .await
+            .component_policies
+            .get(component_id)
+            .cloned()
+    }
+
     /// Construct a default WASI template enriched with configured environment
-    /// variables and any stored secrets for the component.
+    /// variables and any stored secrets for the component. With a default policy
+    /// configured, the template enforces that policy instead.
     async fn build_default_template(&self, component_id: &str) -> Arc<WasiStateTemplate> {
+        if self.default_policy.is_some() || self.safe_mode {
+            let secrets = self.secrets.load_component_secrets(component_id).await.ok();
+            match self.template_from_policy(&PolicyDocument::default(), secrets.as_ref()) {
+                Ok(template) => return Arc::new(template),
+                Err(e) => {
+                    warn!(component_id, error = %e, "Failed to apply the default policy");
+                }
+            }
+        }
+
         let mut config_vars = self.environment_vars.as_ref().clone();
 
         if let Ok(secrets) = self.secrets.load_component_secrets(component_id).await {
@@ -174,13 +351,33 @@ impl PolicyManager {
         )
         .await?;
 
-        let policy = PolicyParser::parse_file(downloaded_policy.as_ref())?;
+        let policy_content = tokio::fs::read_to_string(downloaded_policy.as_ref()).await?;
+        let policy = self.parse_policy(component_id, &policy_content)?;
 
-        let policy_path = self.policy_path(component_id);
-        tokio::fs::copy(downloaded_policy.as_ref(), &policy_path).await?;
+        let policy_lock = self.policy_lock(component_id);
+        let _policy_guard = policy_lock.lock().await;
+        write_atomically(&self.policy_path(component_id), policy_content.into_bytes()).await?;
+        self.record_policy_source(component_id, policy_uri).await?;
+
+        let secrets = self.secrets.load_component_secrets(component_id).await.ok();
+
+        let wasi_template = self.template_from_policy(&policy, secrets.as_ref())?;
+
+        self.store_template(component_id, Arc::new(wasi_template))
+            .await;
+
+        info!(component_id, policy_uri, "Policy attached successfully");
+        Ok(())
+    }
 
+    /// Record where the component's current policy file came from
+    pub(crate) async fn record_policy_source(
+        &self,
+        component_id: &str,
+        source_uri: &str,
+    ) -> Result<()> {
         let metadata = serde_json::json!({
-            "source_uri": policy_uri,
+            "source_uri": source_uri,
             "attached_at": std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap()
@@ -188,20 +385,6 @@ impl PolicyManager {
         });
         let metadata_path = self.metadata_path(component_id);
         tokio::fs::write(&metadata_path, serde_json::to_string_pretty(&metadata)?).await?;
-
-        let secrets = self.secrets.load_component_secrets(component_id).await.ok();
-
-        let wasi_template = crate::create_wasi_state_template_from_policy(
-            &policy,
-            self.storage.root(),
-            self.environment_vars.as_ref(),
-            secrets.as_ref(),
-        )?;
-
-        self.store_template(component_id, Arc::new(wasi_template))
-            .await;
-
-        info!(component_id, policy_uri, "Policy attached successfully");
         Ok(())
     }
 
@@ -231,32 +414,35 @@ impl PolicyManager {
         }
 
         let metadata_path = self.metadata_path(component_id);
-        let source_uri =
-            if let Ok(metadata_content) = tokio::fs::read_to_string(&metadata_path).await {
-                if let Ok(metadata) = serde_json::from_str::<serde_json::Value>(&metadata_content) {
-                    metadata
-                        .get("source_uri")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("unknown")
-                        .to_string()
-                } else {
-                    format!("file://{}", policy_path.display())
-                }
-            } else {
-                format!("file://{}", policy_path.display())
-            };
+        let recorded = tokio::fs::read_to_string(&metadata_path)
+            .await
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok());
+        let recorded_uri = recorded.as_ref().map(|metadata| {
+            metadata
+                .get("source_uri")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown")
+        });
+        let source = PolicySource::from_recorded_uri(recorded_uri);
+        let source_uri = recorded_uri
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("file://{}", policy_path.display()));
 
         let metadata = tokio::fs::metadata(&policy_path).await.ok()?;
         let created_at = metadata
             .created()
             .unwrap_or_else(|_| std::time::SystemTime::now());
+        let modified_at = metadata.modified().unwrap_or(created_at);
 
         Some(PolicyInfo {
             policy_id: format!("{component_id}-policy"),
+            source,
             source_uri,
             local_path: policy_path,
             component_id: component_id.to_string(),
             created_at,
+            modified_at,
         })
     }
 
@@ -267,18 +453,42 @@ impl PolicyManager {
     ) -> Result<()> {
         let secrets = self.secrets.load_component_secrets(component_id).await.ok();
 
-        let wasi_template = crate::create_wasi_state_template_from_policy(
-            policy,
-            self.storage.root(),
-            self.environment_vars.as_ref(),
-            secrets.as_ref(),
-        )?;
+        let wasi_template = self.template_from_policy(policy, secrets.as_ref())?;
 
         self.store_template(component_id, Arc::new(wasi_template))
             .await;
         Ok(())
     }
 
+    /// Memory limit `component_id` will run with once its co-located policy file is restored
+    /// and each of `grants` is added to it, worked out without attaching or saving anything
+    pub(crate) async fn prospective_memory_limit(
+        &self,
+        component_id: &str,
+        grants: &[DeclaredPermission],
+    ) -> Option<u64> {
+        if !self.policy_path(component_id).exists() && grants.is_empty() {
+            return self.template_for_component(component_id).await.memory_limit;
+        }
+        // A policy file that cannot be parsed is not restored, so the current one stays
+        let Ok(mut policy) = self.load_or_create_component_policy(component_id).await else {
+            return self.template_for_component(component_id).await.memory_limit;
+        };
+        for grant in grants {
+            // Grants that would be refused are skipped, as they are when loading
+            let Ok(rule) = self.parse_permission_rule(&grant.permission_type, &grant.details)
+            else {
+                continue;
+            };
+            if self.validate_permission_rule(&rule).is_ok() {
+                let _ = self.add_permission_rule_to_policy(&mut policy, rule);
+            }
+        }
+        crate::wasistate::extract_memory_limit(&self.effective_policy(&policy))
+            .ok()
+            .flatten()
+    }
+
     /// Rehydrate policy templates from a co-located policy file on disk, if
     /// one exists for the component.
     pub(crate) async fn restore_from_disk(&self, component_id: &str) -> Result<()> {
@@ -290,13 +500,8 @@ impl PolicyManager {
         let secrets = self.secrets.load_component_secrets(component_id).await.ok();
 
         match tokio::fs::read_to_string(&policy_path).await {
-            Ok(policy_content) => match PolicyParser::parse_str(&policy_content) {
-                Ok(policy) => match crate::create_wasi_state_template_from_policy(
-                    &policy,
-                    self.storage.root(),
-                    self.environment_vars.as_ref(),
-                    secrets.as_ref(),
-                ) {
+            Ok(policy_content) => match self.parse_policy(component_id, &policy_content) {
+                Ok(policy) => match self.template_from_policy(&policy, secrets.as_ref()) {
                     Ok(wasi_template) => {
                         self.store_template(component_id, Arc::new(wasi_template))
                             .await;
@@ -306,6 +511,12 @@ impl PolicyManager {
                         warn!(component_id = %component_id, error = %e, "Failed to create WASI template from policy");
                     }
                 },
+                Err(e) if self.strict_policy => {
+                    return Err(e.context(format!(
+                        "Failed to parse co-located policy file: {}",
+                        policy_path.display()
+                    )));
+                }
                 Err(e) => {
                     warn!(component_id = %component_id, error = %e, "Failed to parse co-located policy file");
                 }
@@ -326,6 +537,8 @@ impl PolicyManager {
         if uri.is_empty() {
             return Err(anyhow!("Storage URI cannot be empty"));
         }
+        let policy_lock = self.policy_lock(component_id);
+        let _policy_guard = policy_lock.lock().await;
         let mut policy = self.load_or_create_component_policy(component_id).await?;
         self.remove_storage_permission_by_uri_from_policy(&mut policy, uri)?;
         self.save_component_policy(component_id, &policy).await?;
@@ -341,12 +554,22 @@ impl PolicyManager {
         permission_type: &str,
         details: &serde_json::Value,
     ) -> Result<()> {
+        if self.safe_mode {
+            return Err(anyhow!("Grants disabled in safe mode"));
+        }
         info!(
             component_id,
             permission_type, "Granting permission to component"
         );
         let permission_rule = self.parse_permission_rule(permission_type, details)?;
         self.validate_permission_rule(&permission_rule)?;
+        if let PermissionRule::Storage(storage) = &permission_rule {
+            if let Some(path) = storage.uri.strip_prefix("fs://") {
+                crate::wasistate::storage_host_path(Path::new(path), self.storage.root())?;
+            }
+        }
+        let policy_lock = self.policy_lock(component_id);
+        let _policy_guard = policy_lock.lock().await;
         let mut policy = self.load_or_create_component_policy(component_id).await?;
         self.add_permission_rule_to_policy(&mut policy, permission_rule)?;
         self.save_component_policy(component_id, &policy).await?;
@@ -475,11 +698,11 @@ impl PolicyManager {
 
         if policy_path.exists() {
             let policy_content = tokio::fs::read_to_string(&policy_path).await?;
-            Ok(PolicyParser::parse_str(&policy_content)?)
+            self.parse_policy(component_id, &policy_content)
         } else {
             // Create minimal policy document
             Ok(policy::PolicyDocument {
-                version: "1.0".to_string(),
+                version: policy::CURRENT_POLICY_VERSION.to_string(),
                 description: Some(format!(
                     "Auto-generated policy for component: {component_id}"
                 )),
@@ -659,8 +882,7 @@ impl PolicyManager {
     ) -> Result<()> {
         let policy_path = self.policy_path(component_id);
         let policy_yaml = serde_yaml::to_string(policy)?;
-        tokio::fs::write(&policy_path, policy_yaml).await?;
-        Ok(())
+        write_atomically(&policy_path, policy_yaml.into_bytes()).await
     }
 
     /// Validate permission rule
@@ -683,6 +905,7 @@ impl PolicyManager {
                 if env.key.is_empty() {
                     return Err(anyhow!("Environment variable key cannot be empty"));
                 }
+                policy::Permissions::validate_environment_key(&env.key)?;
             }
             _ => {}
         }
@@ -703,6 +926,8 @@ impl PolicyManager {
         );
         let permission_rule = self.parse_permission_rule(permission_type, details)?;
         self.validate_permission_rule(&permission_rule)?;
+        let policy_lock = self.policy_lock(component_id);
+        let _policy_guard = policy_lock.lock().await;
         let mut policy = self.load_or_create_component_policy(component_id).await?;
         self.remove_permission_rule_from_policy(&mut policy, permission_rule)?;
         self.save_component_policy(component_id, &policy).await?;
@@ -719,6 +944,8 @@ impl PolicyManager {
     #[instrument(skip(self))]
     pub async fn reset_permission(&self, component_id: &str) -> Result<()> {
         info!(component_id, "Resetting all permissions for component");
+        let policy_lock = self.policy_lock(component_id);
+        let _policy_guard = policy_lock.lock().await;
         // Remove policy files
         let policy_path = self.policy_path(component_id);
         self.storage
@@ -845,6 +1072,29 @@ impl PolicyManager {
     }
 }
 
+/// Replace the file at `path` with `contents` by writing a temporary file next to it and
+/// renaming it into place. Readers, and a restart after a crash, see either the old or the
+/// new policy but never a partially written one.
+async fn write_atomically(path: &Path, contents: Vec<u8>) -> Result<()> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let dir = path
+            .parent()
+            .context("Policy path has no parent directory")?;
+        let mut file = tempfile::Builder::new()
+            .prefix(".policy-")
+            .suffix(".tmp")
+            .tempfile_in(dir)
+            .with_context(|| format!("Failed to create temporary file in {}", dir.display()))?;
+        file.write_all(&contents)?;
+        file.as_file().sync_all()?;
+        file.persist(&path)
+            .with_context(|| format!("Failed to replace {}", path.display()))?;
+        Ok(())
+    })
+    .await?
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -883,6 +1133,7 @@ permissions:
         let info = policy_info.unwrap();
         assert_eq!(info.component_id, TEST_COMPONENT_ID);
         assert_eq!(info.source_uri, policy_uri);
+        assert_eq!(info.source, PolicySource::Attached);
 
         // Verify co-located policy file exists
         let co_located_path = manager.get_component_policy_path(TEST_COMPONENT_ID);
@@ -945,6 +1196,10 @@ permissions: {}
         assert!(policy_content.contains("api.example.com"));
         assert!(policy_content.contains("network"));
 
+        let info = manager.get_policy_info(TEST_COMPONENT_ID).await.unwrap();
+        assert_eq!(info.source, PolicySource::Inline);
+        assert_eq!(info.local_path, policy_path);
+
         Ok(())
     }
 
@@ -1236,7 +1491,7 @@ permissions: {}
 
         // Verify policy registry was updated by attempting to get WASI state
         let _wasi_state = manager
-            .get_wasi_state_for_component(TEST_COMPONENT_ID)
+            .get_wasi_state_for_component(TEST_COMPONENT_ID, None)
             .await?;
 
         // If we get here without error, the policy registry was updated successfully
@@ -1281,6 +1536,95 @@ permissions:
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_policy_writes_replace_the_file_atomically() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("component.policy.yaml");
+        let old_policy = "version: \"1.0\"\npermissions: {}\n";
+        tokio::fs::write(&path, old_policy).await?;
+
+        // Rewrite the policy while a reader keeps parsing it: every read sees a whole document
+        let writer = async {
+            for i in 0..50 {
+                let hosts: String = (0..=i)
+                    .map(|n| format!("      - host: \"host-{n}.example.com\"\n"))
+                    .collect();
+                let policy =
+                    format!("version: \"1.0\"\npermissions:\n  network:\n    allow:\n{hosts}");
+                write_atomically(&path, policy.into_bytes()).await?;
+            }
+            anyhow::Ok(())
+        };
+        let reader = async {
+            for _ in 0..50 {
+                let content = tokio::fs::read_to_string(&path).await?;
+                PolicyParser::parse_str(&content)?;
+                tokio::task::yield_now().await;
+            }
+            anyhow::Ok(())
+        };
+        let (written, read) = tokio::join!(writer, reader);
+        written?;
+        read?;
+
+        let policy = PolicyParser::parse_str(&tokio::fs::read_to_string(&path).await?)?;
+        let network = policy.permissions.network.unwrap().allow.unwrap();
+        assert_eq!(network.len(), 50);
+        // Only the policy is left behind, no temporary files
+        assert_eq!(std::fs::read_dir(dir.path())?.count(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_grants_and_revokes_keep_policy_complete() -> Result<()> {
+        let manager = create_test_manager().await?;
+        manager.load_test_component().await?;
+        manager
+            .grant_permission(
+                TEST_COMPONENT_ID,
+                "network",
+                &serde_json::json!({"host": "revoked.example.com"}),
+            )
+            .await?;
+
+        let grants = (0..16).map(|i| {
+            let details = serde_json::json!({"host": format!("host-{i}.example.com")});
+            let manager = &manager;
+            async move {
+                manager
+                    .grant_permission(TEST_COMPONENT_ID, "network", &details)
+                    .await
+            }
+        });
+        let revoke = manager.revoke_permission(
+            TEST_COMPONENT_ID,
+            "network",
+            &serde_json::json!({"host": "revoked.example.com"}),
+        );
+        let (granted, revoked) = tokio::join!(futures::future::join_all(grants), revoke);
+        granted.into_iter().collect::<Result<Vec<_>>>()?;
+        revoked?;
+
+        let content =
+            tokio::fs::read_to_string(manager.get_component_policy_path(TEST_COMPONENT_ID)).await?;
+        let policy = PolicyParser::parse_str(&content)?;
+        let hosts: Vec<_> = policy
+            .permissions
+            .network
+            .unwrap()
+            .allow
+            .unwrap()
+            .into_iter()
+            .map(|permission| match permission {
+                NetworkPermission::Host(host) => host.host,
+                other => panic!("unexpected network permission {other:?}"),
+            })
+            .collect();
+        assert_eq!(hosts.len(), 16, "no grant may be lost: {hosts:?}");
+        assert!(!hosts.contains(&"revoked.example.com".to_string()));
+        Ok(())
+    }
+
     #[test]
     fn test_permission_rule_serialization() -> Result<()> {
         // Test serialization of PermissionRule
@@ -0,0 +1,131 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Outbound proxy settings for component and policy downloads.
+
+use anyhow::{Context, Result};
+
+/// Environment variable that overrides the standard proxy variables for Wassette only
+pub const HTTP_PROXY_ENV: &str = "WASSETTE_HTTP_PROXY";
+
+/// Proxy that `https://` and `oci://` downloads are routed through
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyConfig {
+    /// Proxy URL, for example `http://proxy.internal:3128`
+    pub url: String,
+    /// Comma-separated hosts that bypass the proxy, in `NO_PROXY` syntax
+    pub no_proxy: Option<String>,
+}
+
+impl ProxyConfig {
+    /// Read the proxy from `WASSETTE_HTTP_PROXY`, falling back to `HTTPS_PROXY` and
+    /// `HTTP_PROXY` (upper or lower case). `NO_PROXY` lists hosts to reach directly.
+    pub fn from_env() -> Option<Self> {
+        let var = |names: &[&str]| {
+            names
+                .iter()
+                .filter_map(|name| std::env::var(name).ok())
+                .map(|value| value.trim().to_string())
+                .find(|value| !value.is_empty())
+        };
+
+        let url = var(&[
+            HTTP_PROXY_ENV,
+            "HTTPS_PROXY",
+            "https_proxy",
+            "HTTP_PROXY",
+            "http_proxy",
+        ])?;
+        Some(Self {
+            url,
+            no_proxy: var(&["NO_PROXY", "no_proxy"]),
+        })
+    }
+
+    /// Route every request of `builder` through this proxy
+    pub fn apply_to_http(&self, builder: reqwest::ClientBuilder) -> Result<reqwest::ClientBuilder> {
+        let proxy = reqwest::Proxy::all(&self.url)
+            .with_context(|| format!("Invalid proxy URL: {}", self.url))?
+            .no_proxy(
+                self.no_proxy
+                    .as_deref()
+                    .and_then(reqwest::NoProxy::from_string),
+            );
+        Ok(builder.proxy(proxy))
+    }
+
+    /// Route registry traffic of an OCI client through this proxy
+    pub fn apply_to_oci(
+        &self,
+        config: oci_client::client::ClientConfig,
+    ) -> oci_client::client::ClientConfig {
+        oci_client::client::ClientConfig {
+            https_proxy: Some(self.url.clone()),
+            http_proxy: Some(self.url.clone()),
+            no_proxy: self.no_proxy.clone(),
+            ..config
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROXY_VARS: [&str; 7] = [
+        HTTP_PROXY_ENV,
+        "HTTPS_PROXY",
+        "https_proxy",
+        "HTTP_PROXY",
+        "http_proxy",
+        "NO_PROXY",
+        "no_proxy",
+    ];
+
+    fn with_proxy_env<R>(vars: &[(&str, &str)], f: impl FnOnce() -> R) -> R {
+        let env: Vec<(&str, Option<&str>)> = PROXY_VARS
+            .iter()
+            .map(|name| {
+                let value = vars.iter().find(|(k, _)| k == name).map(|(_, v)| *v);
+                (*name, value)
+            })
+            .collect();
+        temp_env::with_vars(env, f)
+    }
+
+    #[test]
+    fn test_proxy_from_env() {
+        with_proxy_env(&[], || assert_eq!(ProxyConfig::from_env(), None));
+
+        with_proxy_env(
+            &[
+                ("HTTPS_PROXY", "http://corp:3128"),
+                ("NO_PROXY", "localhost,.internal"),
+            ],
+            || {
+                assert_eq!(
+                    ProxyConfig::from_env(),
+                    Some(ProxyConfig {
+                        url: "http://corp:3128".to_string(),
+                        no_proxy: Some("localhost,.internal".to_string()),
+                    })
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn test_wassette_proxy_overrides_standard_variables() {
+        with_proxy_env(
+            &[
+                ("HTTPS_PROXY", "http://corp:3128"),
+                (HTTP_PROXY_ENV, "http://wassette-only:8080"),
+            ],
+            || {
+                let proxy = ProxyConfig::from_env().unwrap();
+                assert_eq!(proxy.url, "http://wassette-only:8080");
+                assert_eq!(proxy.no_proxy, None);
+            },
+        );
+    }
+}
@@ -0,0 +1,114 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Scrubbing a component's secret values out of the errors and output its calls produce, so
+//! that a component which echoes a credential does not leak it to MCP clients, its captured
+//! logs or the server logs.
+
+use serde_json::Value;
+
+/// Text that replaces every occurrence of a secret value
+pub(crate) const REDACTED: &str = "***";
+
+/// Values shorter than this are left alone; replacing every `1` or `on` in an error would
+/// make it unreadable without hiding anything worth hiding
+const MIN_SECRET_LEN: usize = 4;
+
+/// Replaces known secret values with [`REDACTED`]
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Redactor {
+    /// Longest first, so a secret containing another is replaced as a whole
+    values: Vec<String>,
+}
+
+impl Redactor {
+    pub fn new(values: impl IntoIterator<Item = String>) -> Self {
+        let mut values: Vec<String> = values
+            .into_iter()
+            .filter(|value| value.len() >= MIN_SECRET_LEN)
+            .collect();
+        values.sort_unstable_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        values.dedup();
+        Self { values }
+    }
+
+    /// Whether `text` contains any of the secret values
+    pub fn matches(&self, text: &str) -> bool {
+        self.values
+            .iter()
+            .any(|value| text.contains(value.as_str()))
+    }
+
+    pub fn redact(&self, text: &str) -> String {
+        self.values.iter().fold(text.to_string(), |text, value| {
+            text.replace(value.as_str(), REDACTED)
+        })
+    }
+
+    /// Redact every string found under an `err` key of a call result, which is how the
+    /// error case of a `result` returned by a component is encoded
+    pub fn redact_result_errors(&self, value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, item) in map.iter_mut() {
+                    if key == "err" {
+                        self.redact_strings(item);
+                    } else {
+                        self.redact_result_errors(item);
+                    }
+                }
+            }
+            Value::Array(items) => items
+                .iter_mut()
+                .for_each(|item| self.redact_result_errors(item)),
+            _ => {}
+        }
+    }
+
+    fn redact_strings(&self, value: &mut Value) {
+        match value {
+            Value::String(text) => {
+                if self.matches(text) {
+                    *text = self.redact(text);
+                }
+            }
+            Value::Object(map) => map.values_mut().for_each(|item| self.redact_strings(item)),
+            Value::Array(items) => items.iter_mut().for_each(|item| self.redact_strings(item)),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_redacts_longest_secret_first_and_skips_short_values() {
+        let redactor = Redactor::new(["token".to_string(), "token-123".to_string(), "ab".into()]);
+        assert_eq!(
+            redactor.redact("bad token-123, not token, ab"),
+            "bad ***, not ***, ab"
+        );
+        assert!(!redactor.matches("nothing to see"));
+    }
+
+    #[test]
+    fn test_only_error_values_of_results_are_redacted() {
+        let redactor = Redactor::new(["hunter22".to_string()]);
+        let mut result = json!({
+            "result": { "err": { "message": "login as admin:hunter22 failed" } },
+            "echo": "hunter22",
+        });
+        redactor.redact_result_errors(&mut result);
+        assert_eq!(
+            result,
+            json!({
+                "result": { "err": { "message": "login as admin:*** failed" } },
+                "echo": "hunter22",
+            })
+        );
+    }
+}
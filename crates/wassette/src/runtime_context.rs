@@ -5,44 +5,176 @@
 //! manager instances.
 
 use std::sync::Arc;
+use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
 use wasmtime::component::{Component, InstancePre, Linker};
-use wasmtime::Engine;
+use wasmtime::{Engine, InstanceAllocationStrategy, OptLevel, PoolingAllocationConfig};
 use wasmtime_wasi_config::WasiConfig;
 
 use crate::{WasiState, WassetteWasiState};
 
+/// How often the engine's epoch advances. Call deadlines interrupt running code at this
+/// granularity.
+const EPOCH_TICK: Duration = Duration::from_millis(10);
+
+/// Epoch deadline of calls without a time limit, far enough away never to be reached
+const NO_DEADLINE_TICKS: u64 = u64::MAX / 2;
+
+/// How much effort Cranelift spends optimizing compiled components
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompilationStrategy {
+    /// Skip optimizations for the quickest compiles, at the cost of slower tool calls
+    Fast,
+    /// Optimize generated code for speed
+    #[default]
+    Optimized,
+}
+
+impl CompilationStrategy {
+    /// Name of the strategy as used in configuration
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Fast => "fast",
+            Self::Optimized => "optimized",
+        }
+    }
+
+    fn opt_level(self) -> OptLevel {
+        match self {
+            Self::Fast => OptLevel::None,
+            Self::Optimized => OptLevel::Speed,
+        }
+    }
+}
+
+impl std::str::FromStr for CompilationStrategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "fast" => Ok(Self::Fast),
+            "optimized" => Ok(Self::Optimized),
+            other => {
+                bail!("Unknown compilation strategy '{other}', expected 'fast' or 'optimized'")
+            }
+        }
+    }
+}
+
+/// Limits of Wasmtime's pooling instance allocator.
+///
+/// The pool reserves memory for every slot up front so that instantiating a component only
+/// has to claim a free slot, which makes short tool calls cheaper at the cost of a larger
+/// memory reservation. A call that needs a slot while all of them are in use fails instead
+/// of waiting. Memory limits from component policies still apply, but no memory can grow
+/// beyond [`max_memory_size`](Self::max_memory_size).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolingAllocatorConfig {
+    /// Component instances that may be alive at once, one per running tool call
+    pub max_component_instances: u32,
+    /// Core module instances that may be alive at once across all component instances
+    pub max_core_instances: u32,
+    /// Linear memories that may be alive at once
+    pub max_memories: u32,
+    /// Tables that may be alive at once
+    pub max_tables: u32,
+    /// Largest size a single linear memory may grow to, in bytes
+    pub max_memory_size: usize,
+}
+
+impl Default for PoolingAllocatorConfig {
+    fn default() -> Self {
+        Self {
+            max_component_instances: 100,
+            max_core_instances: 1_000,
+            max_memories: 200,
+            max_tables: 1_000,
+            max_memory_size: 1 << 30,
+        }
+    }
+}
+
+impl PoolingAllocatorConfig {
+    fn strategy(&self) -> InstanceAllocationStrategy {
+        let mut pool = PoolingAllocationConfig::default();
+        pool.total_component_instances(self.max_component_instances)
+            .total_core_instances(self.max_core_instances)
+            .total_memories(self.max_memories)
+            .total_tables(self.max_tables)
+            // Every running call needs its own async stack
+            .total_stacks(self.max_component_instances)
+            .max_memory_size(self.max_memory_size);
+        InstanceAllocationStrategy::Pooling(pool)
+    }
+}
+
+/// Build a linker providing every interface the host implements for components
+pub(crate) fn host_linker(engine: &Engine) -> Result<Linker<WassetteWasiState<WasiState>>> {
+    let mut linker = Linker::new(engine);
+    wasmtime_wasi::p2::add_to_linker_async(&mut linker)?;
+    wasmtime_wasi_http::add_only_http_to_linker_async(&mut linker)?;
+    wasmtime_wasi_config::add_to_linker(&mut linker, |h: &mut WassetteWasiState<WasiState>| {
+        WasiConfig::from(&h.inner.wasi_config_vars)
+    })?;
+    Ok(linker)
+}
+
 /// Encapsulates Wasmtime engine and linker setup for reuse across the lifecycle manager.
 #[derive(Clone)]
 pub struct RuntimeContext {
     engine: Arc<Engine>,
     linker: Arc<Linker<WassetteWasiState<WasiState>>>,
+    strategy: CompilationStrategy,
 }
 
 impl RuntimeContext {
-    /// Build a runtime context with the standard configuration used by Wassette.
-    pub fn initialize() -> Result<Self> {
+    /// Build a runtime context with the standard configuration used by Wassette, compiling
+    /// components with `strategy` and instantiating them from `pooling` when given.
+    pub fn initialize(
+        strategy: CompilationStrategy,
+        pooling: Option<&PoolingAllocatorConfig>,
+    ) -> Result<Self> {
         let mut config = wasmtime::Config::new();
         config.wasm_component_model(true);
         config.async_support(true);
+        // Lets a call's deadline interrupt wasm that never yields to the async runtime
+        config.epoch_interruption(true);
+        config.cranelift_opt_level(strategy.opt_level());
+        if let Some(pooling) = pooling {
+            config.allocation_strategy(pooling.strategy());
+        }
 
         let engine = Arc::new(Engine::new(&config)?);
+        spawn_epoch_ticker(engine.as_ref())?;
 
-        let mut linker = Linker::new(engine.as_ref());
-        wasmtime_wasi::p2::add_to_linker_async(&mut linker)?;
-        wasmtime_wasi_http::add_only_http_to_linker_async(&mut linker)?;
-        wasmtime_wasi_config::add_to_linker(
-            &mut linker,
-            |h: &mut WassetteWasiState<WasiState>| WasiConfig::from(&h.inner.wasi_config_vars),
-        )?;
+        let linker = host_linker(engine.as_ref())?;
 
         Ok(Self {
             engine,
             linker: Arc::new(linker),
+            strategy,
         })
     }
 
+    /// Strategy components are compiled with
+    pub fn compilation_strategy(&self) -> CompilationStrategy {
+        self.strategy
+    }
+
+    /// Epoch ticks a store may run for before a call ending at `deadline` is interrupted,
+    /// or a deadline that is never reached when there is none
+    pub(crate) fn epoch_deadline(deadline: Option<tokio::time::Instant>) -> u64 {
+        let Some(deadline) = deadline else {
+            return NO_DEADLINE_TICKS;
+        };
+        let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+        let ticks = remaining.as_millis().div_ceil(EPOCH_TICK.as_millis());
+        u64::try_from(ticks).unwrap_or(NO_DEADLINE_TICKS).max(1)
+    }
+
     /// Produce a cached `InstancePre` handle for the provided component using
     /// the shared linker configuration.
     pub fn instantiate_pre(
@@ -53,6 +185,21 @@ impl RuntimeContext {
     }
 }
 
+/// Advance the engine's epoch every [`EPOCH_TICK`] until the engine is dropped
+fn spawn_epoch_ticker(engine: &Engine) -> Result<()> {
+    let engine = engine.weak();
+    std::thread::Builder::new()
+        .name("wassette-epoch".to_string())
+        .spawn(move || {
+            while let Some(engine) = engine.upgrade() {
+                engine.increment_epoch();
+                drop(engine);
+                std::thread::sleep(EPOCH_TICK);
+            }
+        })?;
+    Ok(())
+}
+
 impl AsRef<Engine> for RuntimeContext {
     fn as_ref(&self) -> &Engine {
         self.engine.as_ref()
@@ -3,6 +3,7 @@
 
 //! Helpers for canonicalizing tool output schemas and aligning structured results.
 
+pub use component2json::is_binary_schema;
 use serde_json::{Map, Value};
 
 /// Canonicalize a tool output schema so that it always represents structured
@@ -9,13 +9,14 @@
 //! - Easy to edit and audit via CLI
 //! - Integrated with component environment variable system
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
@@ -28,6 +29,47 @@ pub struct SecretCache {
     pub last_mtime: SystemTime,
 }
 
+/// Version of the portable secrets bundle format produced by export
+pub const SECRETS_BUNDLE_VERSION: u32 = 1;
+
+/// Portable bundle of a component's secrets, used to migrate them between hosts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SecretsBundle {
+    /// Bundle format version
+    pub version: u32,
+    /// Component the secrets belong to
+    pub component_id: String,
+    /// Secret key/value pairs, sorted by key
+    pub secrets: BTreeMap<String, String>,
+}
+
+impl SecretsBundle {
+    /// Read a bundle from a YAML file, rejecting unsupported format versions
+    pub async fn read_from_file(path: &Path) -> Result<Self> {
+        let content = tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("Failed to read secrets bundle: {}", path.display()))?;
+        let bundle: SecretsBundle = serde_yaml::from_str(&content)
+            .with_context(|| format!("Failed to parse secrets bundle: {}", path.display()))?;
+
+        if bundle.version != SECRETS_BUNDLE_VERSION {
+            bail!(
+                "Unsupported secrets bundle version {} (expected {})",
+                bundle.version,
+                SECRETS_BUNDLE_VERSION
+            );
+        }
+
+        Ok(bundle)
+    }
+
+    /// Write the bundle to a YAML file readable only by the current user
+    pub async fn write_to_file(&self, path: &Path) -> Result<()> {
+        let content = serde_yaml::to_string(self).context("Failed to serialize secrets bundle")?;
+        write_private_file(path, &content).await
+    }
+}
+
 /// Secrets manager for components
 #[derive(Debug)]
 pub struct SecretsManager {
@@ -286,6 +328,64 @@ impl SecretsManager {
         Ok(())
     }
 
+    /// Move the secrets of component `from` to component `to`, if it has any
+    pub async fn rename_component_secrets(&self, from: &str, to: &str) -> Result<()> {
+        let (source, target) = (
+            self.get_component_secrets_path(from),
+            self.get_component_secrets_path(to),
+        );
+        match tokio::fs::rename(&source, &target).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                return Err(e)
+                    .with_context(|| format!("Failed to move secrets file: {}", source.display()));
+            }
+            _ => {}
+        }
+
+        let mut cache = self.cache.write().await;
+        cache.remove(from);
+        cache.remove(to);
+        Ok(())
+    }
+
+    /// Export all secrets for a component as a portable bundle
+    pub async fn export_component_secrets(&self, component_id: &str) -> Result<SecretsBundle> {
+        if !self.get_component_secrets_path(component_id).exists() {
+            bail!("No secrets file found for component: {}", component_id);
+        }
+
+        let secrets = self.load_component_secrets(component_id).await?;
+
+        Ok(SecretsBundle {
+            version: SECRETS_BUNDLE_VERSION,
+            component_id: component_id.to_string(),
+            secrets: secrets.into_iter().collect(),
+        })
+    }
+
+    /// Restore secrets from a bundle, merging them into any existing secrets.
+    ///
+    /// Returns the number of secrets imported.
+    pub async fn import_component_secrets(&self, bundle: &SecretsBundle) -> Result<usize> {
+        if bundle.version != SECRETS_BUNDLE_VERSION {
+            bail!(
+                "Unsupported secrets bundle version {} (expected {})",
+                bundle.version,
+                SECRETS_BUNDLE_VERSION
+            );
+        }
+
+        let secrets: Vec<(String, String)> = bundle
+            .secrets
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        self.set_component_secrets(&bundle.component_id, &secrets)
+            .await?;
+
+        Ok(secrets.len())
+    }
+
     /// Write secrets to file atomically with proper permissions
     async fn write_secrets_file(
         &self,
@@ -294,51 +394,53 @@ impl SecretsManager {
     ) -> Result<()> {
         let content =
             serde_yaml::to_string(secrets).context("Failed to serialize secrets to YAML")?;
+        write_private_file(secrets_path, &content).await
+    }
+}
 
-        // Write to temporary file first
-        let temp_path = secrets_path.with_extension("tmp");
-        tokio::fs::write(&temp_path, &content)
+/// Write a file atomically with permissions restricted to the current user
+async fn write_private_file(path: &Path, content: &str) -> Result<()> {
+    // Write to temporary file first
+    let temp_path = path.with_extension("tmp");
+    tokio::fs::write(&temp_path, content)
+        .await
+        .with_context(|| {
+            format!(
+                "Failed to write temporary secrets file: {}",
+                temp_path.display()
+            )
+        })?;
+
+    // Set file permissions to 0600 (user read/write only)
+    #[cfg(unix)]
+    {
+        let metadata = tokio::fs::metadata(&temp_path).await.with_context(|| {
+            format!(
+                "Failed to get metadata for temporary secrets file: {}",
+                temp_path.display()
+            )
+        })?;
+        let mut perms = metadata.permissions();
+        perms.set_mode(0o600);
+        tokio::fs::set_permissions(&temp_path, perms)
             .await
             .with_context(|| {
                 format!(
-                    "Failed to write temporary secrets file: {}",
-                    temp_path.display()
-                )
-            })?;
-
-        // Set file permissions to 0600 (user read/write only)
-        #[cfg(unix)]
-        {
-            let metadata = tokio::fs::metadata(&temp_path).await.with_context(|| {
-                format!(
-                    "Failed to get metadata for temporary secrets file: {}",
+                    "Failed to set permissions for temporary secrets file: {}",
                     temp_path.display()
                 )
             })?;
-            let mut perms = metadata.permissions();
-            perms.set_mode(0o600);
-            tokio::fs::set_permissions(&temp_path, perms)
-                .await
-                .with_context(|| {
-                    format!(
-                        "Failed to set permissions for temporary secrets file: {}",
-                        temp_path.display()
-                    )
-                })?;
-        }
+    }
 
-        // Atomic rename
-        tokio::fs::rename(&temp_path, secrets_path)
-            .await
-            .with_context(|| {
-                format!(
-                    "Failed to rename temporary secrets file to: {}",
-                    secrets_path.display()
-                )
-            })?;
+    // Atomic rename
+    tokio::fs::rename(&temp_path, path).await.with_context(|| {
+        format!(
+            "Failed to rename temporary secrets file to: {}",
+            path.display()
+        )
+    })?;
 
-        Ok(())
-    }
+    Ok(())
 }
 
 /// Sanitize component ID for use as filename
@@ -461,6 +563,89 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_secrets_export_import_round_trip() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let manager = SecretsManager::new(temp_dir.path().join("secrets"));
+
+        let secrets = vec![
+            ("API_KEY".to_string(), "secret123".to_string()),
+            ("REGION".to_string(), "us-west-2".to_string()),
+        ];
+        manager
+            .set_component_secrets("test-component", &secrets)
+            .await?;
+
+        // Export to a bundle file
+        let bundle = manager.export_component_secrets("test-component").await?;
+        assert_eq!(bundle.version, SECRETS_BUNDLE_VERSION);
+        assert_eq!(bundle.secrets.len(), 2);
+        let bundle_path = temp_dir.path().join("bundle.yaml");
+        bundle.write_to_file(&bundle_path).await?;
+
+        #[cfg(unix)]
+        {
+            let mode = std::fs::metadata(&bundle_path)?.permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        // Remove every secret, then restore from the bundle
+        manager
+            .delete_component_secrets(
+                "test-component",
+                &["API_KEY".to_string(), "REGION".to_string()],
+            )
+            .await?;
+        assert!(manager
+            .load_component_secrets("test-component")
+            .await?
+            .is_empty());
+
+        let restored_bundle = SecretsBundle::read_from_file(&bundle_path).await?;
+        assert_eq!(restored_bundle, bundle);
+        let imported = manager.import_component_secrets(&restored_bundle).await?;
+        assert_eq!(imported, 2);
+
+        let restored = manager.load_component_secrets("test-component").await?;
+        assert_eq!(restored.get("API_KEY"), Some(&"secret123".to_string()));
+        assert_eq!(restored.get("REGION"), Some(&"us-west-2".to_string()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_secrets_export_without_secrets_fails() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let manager = SecretsManager::new(temp_dir.path().join("secrets"));
+
+        let result = manager.export_component_secrets("missing").await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("No secrets file found"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_secrets_bundle_rejects_unknown_version() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let bundle_path = temp_dir.path().join("bundle.yaml");
+        tokio::fs::write(
+            &bundle_path,
+            "version: 99\ncomponent_id: test\nsecrets:\n  KEY: value\n",
+        )
+        .await?;
+
+        let result = SecretsBundle::read_from_file(&bundle_path).await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Unsupported secrets bundle version 99"));
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_cache_invalidation() -> Result<()> {
         let temp_dir = TempDir::new()?;
@@ -0,0 +1,41 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Per-component outcome of loading the installed components when the manager starts
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// How loading one installed component went
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ComponentLoadStatus {
+    Loaded,
+    Failed {
+        /// The full error chain
+        reason: String,
+    },
+}
+
+/// Outcome of the most recent run of
+/// [`LifecycleManager::load_existing_components_async`](crate::LifecycleManager::load_existing_components_async)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StartupReport {
+    /// Whether loading has finished; components still being loaded are not listed yet
+    pub complete: bool,
+    /// Status of every component file found, keyed by component id
+    pub components: BTreeMap<String, ComponentLoadStatus>,
+}
+
+impl StartupReport {
+    /// Components that failed to load, with the reason
+    pub fn failed(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.components
+            .iter()
+            .filter_map(|(id, status)| match status {
+                ComponentLoadStatus::Failed { reason } => Some((id.as_str(), reason.as_str())),
+                ComponentLoadStatus::Loaded => None,
+            })
+    }
+}
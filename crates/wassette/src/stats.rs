@@ -0,0 +1,26 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Per-component counters of tool calls and of the network traffic they caused.
+
+use serde::Serialize;
+
+/// Usage of a component since it was loaded, as returned by
+/// [`LifecycleManager::get_component_stats`](crate::LifecycleManager::get_component_stats)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ComponentStats {
+    /// Tool calls that ran, whether or not they succeeded
+    pub calls: u64,
+    /// Bytes of HTTP response bodies received across all calls
+    pub egress_bytes: u64,
+    /// Bytes of HTTP response bodies received by the most recent call
+    pub last_call_egress_bytes: u64,
+}
+
+impl ComponentStats {
+    pub(crate) fn record_call(&mut self, egress_bytes: u64) {
+        self.calls += 1;
+        self.egress_bytes += egress_bytes;
+        self.last_call_egress_bytes = egress_bytes;
+    }
+}
@@ -0,0 +1,135 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Readable errors for components that trap during a tool call.
+//!
+//! A trap (a Rust `panic!`, an `unreachable` instruction, an out-of-bounds access, ...) aborts
+//! the call. Wasmtime reports it with a terse message and attaches a [`WasmBacktrace`]; this
+//! module turns both into a [`ComponentTrap`] so callers can tell a crashed component apart
+//! from a tool that returned an error value.
+
+use std::fmt;
+
+use serde::Serialize;
+use wasmtime::{Trap, WasmBacktrace};
+
+/// Frames kept in a [`ComponentTrap`] backtrace; deeper frames are summarized
+pub const MAX_TRAP_FRAMES: usize = 16;
+
+/// Error returned when a component traps while executing a tool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ComponentTrap {
+    /// Component whose code trapped
+    pub component_id: String,
+    /// Tool that was being executed
+    pub tool: String,
+    /// Wasmtime's description of the trap, e.g. `wasm 'unreachable' instruction executed`
+    pub message: String,
+    /// Innermost frames first. Frames carry source locations when the component was built
+    /// with debug info and `WASMTIME_BACKTRACE_DETAILS=1` is set.
+    pub backtrace: Vec<String>,
+    /// Number of frames dropped past [`MAX_TRAP_FRAMES`]
+    #[serde(skip_serializing_if = "is_zero")]
+    pub truncated_frames: usize,
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+impl ComponentTrap {
+    /// Build a trap report from a failed call, or `None` if the error is not a trap
+    pub(crate) fn from_error(
+        component_id: &str,
+        tool: &str,
+        error: &anyhow::Error,
+    ) -> Option<Self> {
+        let trap = error.downcast_ref::<Trap>()?;
+        let frames: Vec<String> = error
+            .downcast_ref::<WasmBacktrace>()
+            .map(|backtrace| backtrace.frames().iter().map(format_frame).collect())
+            .unwrap_or_default();
+
+        let truncated_frames = frames.len().saturating_sub(MAX_TRAP_FRAMES);
+        let mut backtrace = frames;
+        backtrace.truncate(MAX_TRAP_FRAMES);
+
+        Some(Self {
+            component_id: component_id.to_string(),
+            tool: tool.to_string(),
+            message: trap.to_string(),
+            backtrace,
+            truncated_frames,
+        })
+    }
+}
+
+/// Render a frame as `module!function (at file:line:column)`, falling back to indices
+fn format_frame(frame: &wasmtime::FrameInfo) -> String {
+    let module = frame.module().name().unwrap_or("<module>");
+    let mut line = match frame.func_name() {
+        Some(name) => format!("{module}!{name}"),
+        None => format!("{module}!<wasm function {}>", frame.func_index()),
+    };
+    if let Some(symbol) = frame.symbols().first() {
+        if let Some(file) = symbol.file() {
+            line.push_str(&format!(" at {file}"));
+            if let Some(l) = symbol.line() {
+                line.push_str(&format!(":{l}"));
+                if let Some(column) = symbol.column() {
+                    line.push_str(&format!(":{column}"));
+                }
+            }
+        }
+    }
+    line
+}
+
+impl fmt::Display for ComponentTrap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Component '{}' trapped while executing '{}': {}",
+            self.component_id, self.tool, self.message
+        )?;
+        if !self.backtrace.is_empty() {
+            write!(f, "\nwasm backtrace:")?;
+            for (i, frame) in self.backtrace.iter().enumerate() {
+                write!(f, "\n  {i:>2}: {frame}")?;
+            }
+            if self.truncated_frames > 0 {
+                write!(f, "\n  ... {} more frames", self.truncated_frames)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ComponentTrap {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_non_trap_errors_are_not_classified() {
+        let error = anyhow::anyhow!("Unknown tool name: missing");
+        assert_eq!(ComponentTrap::from_error("c", "missing", &error), None);
+    }
+
+    #[test]
+    fn test_trap_display_truncates_backtrace() {
+        let trap = ComponentTrap {
+            component_id: "crashy".to_string(),
+            tool: "boom".to_string(),
+            message: "wasm 'unreachable' instruction executed".to_string(),
+            backtrace: vec!["m!inner".to_string(), "m!outer".to_string()],
+            truncated_frames: 3,
+        };
+        assert_eq!(
+            trap.to_string(),
+            "Component 'crashy' trapped while executing 'boom': wasm 'unreachable' instruction executed\n\
+             wasm backtrace:\n   0: m!inner\n   1: m!outer\n  ... 3 more frames"
+        );
+    }
+}
@@ -0,0 +1,126 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Compatibility check of a component against the interfaces this host provides
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+use wasmtime::component::types::ComponentItem;
+use wasmtime::component::{Component, Linker, ResourceType};
+use wasmtime::Engine;
+
+use crate::runtime_context::host_linker;
+
+/// Result of [`LifecycleManager::verify_component`](crate::LifecycleManager::verify_component)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ComponentVerification {
+    /// URI the component was fetched from
+    pub uri: String,
+    /// Whether the host satisfies every import, so the component can be loaded
+    pub compatible: bool,
+    /// Top-level imports the component declares
+    pub imports: Vec<String>,
+    /// Imports the host cannot satisfy, in the order the linker reported them
+    pub unsatisfied_imports: Vec<UnsatisfiedImport>,
+}
+
+/// An import of a verified component that the host does not provide
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnsatisfiedImport {
+    /// Import name, e.g. `wasi:keyvalue/store@0.2.0`
+    pub name: String,
+    /// Why the linker rejected it
+    pub reason: String,
+}
+
+/// Stand-in for resources of imports the host does not provide
+enum UnsatisfiedResource {}
+
+/// Check `component`'s imports against the host linker.
+///
+/// The linker only reports the first import it cannot satisfy, so each one found is stubbed
+/// out and the check repeated until instantiation type-checks or no further progress is made.
+pub(crate) fn verify(
+    uri: &str,
+    component: &Component,
+    engine: &Engine,
+) -> Result<ComponentVerification> {
+    let imports: Vec<(String, ComponentItem)> = component
+        .component_type()
+        .imports(engine)
+        .map(|(name, item)| (name.to_string(), item))
+        .collect();
+
+    let mut linker = host_linker(engine)?;
+    linker.allow_shadowing(true);
+
+    let mut unsatisfied: Vec<UnsatisfiedImport> = Vec::new();
+    while let Err(error) = linker.instantiate_pre(component) {
+        let reason = format!("{error:#}");
+        let culprit = imports.iter().find(|(name, _)| {
+            reason.contains(&format!("`{name}`"))
+                && !unsatisfied.iter().any(|import| &import.name == name)
+        });
+        let Some((name, item)) = culprit else {
+            // Not attributable to a single import (or already stubbed); report as-is
+            unsatisfied.push(UnsatisfiedImport {
+                name: "<unknown>".to_string(),
+                reason,
+            });
+            break;
+        };
+
+        unsatisfied.push(UnsatisfiedImport {
+            name: name.clone(),
+            reason,
+        });
+        if stub_import(&mut linker, name, item, engine).is_err() {
+            break;
+        }
+    }
+
+    Ok(ComponentVerification {
+        uri: uri.to_string(),
+        compatible: unsatisfied.is_empty(),
+        imports: imports.into_iter().map(|(name, _)| name).collect(),
+        unsatisfied_imports: unsatisfied,
+    })
+}
+
+/// Define `name` in the linker with functions that trap and placeholder resources
+fn stub_import<T: 'static>(
+    linker: &mut Linker<T>,
+    name: &str,
+    item: &ComponentItem,
+    engine: &Engine,
+) -> Result<()> {
+    let mut root = linker.root();
+    match item {
+        ComponentItem::ComponentInstance(instance) => {
+            let mut stub = root.instance(name)?;
+            for (export, item) in instance.exports(engine) {
+                match item {
+                    ComponentItem::ComponentFunc(_) => {
+                        stub.func_new(export, |_, _, _, _| bail!("import is not provided"))?
+                    }
+                    ComponentItem::Resource(_) => stub.resource(
+                        export,
+                        ResourceType::host::<UnsatisfiedResource>(),
+                        |_, _| Ok(()),
+                    )?,
+                    _ => {}
+                }
+            }
+        }
+        ComponentItem::ComponentFunc(_) => {
+            root.func_new(name, |_, _, _, _| bail!("import is not provided"))?
+        }
+        ComponentItem::Resource(_) => {
+            root.resource(name, ResourceType::host::<UnsatisfiedResource>(), |_, _| {
+                Ok(())
+            })?
+        }
+        _ => bail!("Cannot stub import '{name}'"),
+    }
+    Ok(())
+}
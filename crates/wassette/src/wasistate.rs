@@ -3,10 +3,16 @@
 
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
 
+use bytes::{Bytes, BytesMut};
 use policy::{AccessType, PolicyDocument};
+use tokio::io::AsyncWrite;
 use wasmtime::component::ResourceTable;
+use wasmtime_wasi::cli::{IsTerminal, StdoutStream};
+use wasmtime_wasi::p2::{OutputStream, Pollable, StreamError};
 use wasmtime_wasi::{WasiCtx, WasiCtxBuilder, WasiCtxView};
 use wasmtime_wasi_config::WasiConfigVariables;
 use wasmtime_wasi_http::{WasiHttpCtx, WasiHttpView};
@@ -87,6 +93,121 @@ impl wasmtime::ResourceLimiter for CustomResourceLimiter {
     }
 }
 
+/// Largest amount of output captured from a single stream during one call
+const MAX_CAPTURED_OUTPUT_BYTES: usize = 16 * 1024 * 1024;
+
+/// In-memory pipes receiving a component's stdout and stderr
+#[derive(Clone)]
+pub struct CapturedOutput {
+    pub stdout: OutputCapture,
+    pub stderr: OutputCapture,
+}
+
+impl CapturedOutput {
+    fn new() -> Self {
+        Self {
+            stdout: OutputCapture::new(MAX_CAPTURED_OUTPUT_BYTES),
+            stderr: OutputCapture::new(MAX_CAPTURED_OUTPUT_BYTES),
+        }
+    }
+}
+
+/// In-memory output stream of a component, like wasmtime's `MemoryOutputPipe` except that
+/// what was written can be taken out. An instance kept across calls then has the full
+/// capacity available again for every call.
+#[derive(Debug, Clone)]
+pub struct OutputCapture {
+    capacity: usize,
+    buffer: Arc<Mutex<BytesMut>>,
+}
+
+impl OutputCapture {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            buffer: Arc::default(),
+        }
+    }
+
+    fn buffer(&self) -> std::sync::MutexGuard<'_, BytesMut> {
+        self.buffer.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Remove and return everything written since the last call
+    pub fn take(&self) -> Bytes {
+        self.buffer().split().freeze()
+    }
+}
+
+impl IsTerminal for OutputCapture {
+    fn is_terminal(&self) -> bool {
+        false
+    }
+}
+
+impl StdoutStream for OutputCapture {
+    fn p2_stream(&self) -> Box<dyn OutputStream> {
+        Box::new(self.clone())
+    }
+
+    fn async_stream(&self) -> Box<dyn AsyncWrite + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+#[wasmtime_wasi::async_trait]
+impl OutputStream for OutputCapture {
+    fn write(&mut self, bytes: Bytes) -> Result<(), StreamError> {
+        let mut buffer = self.buffer();
+        if bytes.len() > self.capacity - buffer.len() {
+            return Err(StreamError::Trap(anyhow::anyhow!(
+                "write beyond the capacity of the captured output"
+            )));
+        }
+        buffer.extend_from_slice(&bytes);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), StreamError> {
+        Ok(())
+    }
+
+    fn check_write(&mut self) -> Result<usize, StreamError> {
+        let used = self.buffer().len();
+        if used < self.capacity {
+            Ok(self.capacity - used)
+        } else {
+            Err(StreamError::Closed)
+        }
+    }
+}
+
+#[wasmtime_wasi::async_trait]
+impl Pollable for OutputCapture {
+    async fn ready(&mut self) {}
+}
+
+impl AsyncWrite for OutputCapture {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let mut buffer = self.buffer();
+        let len = buf.len().min(self.capacity - buffer.len());
+        buffer.extend_from_slice(&buf[..len]);
+        Poll::Ready(Ok(len))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
 pub struct WasiState {
     pub ctx: WasiCtx,
     pub table: wasmtime_wasi::ResourceTable,
@@ -95,6 +216,8 @@ pub struct WasiState {
     pub resource_limiter: Option<CustomResourceLimiter>,
     /// Tracks the last permission error that occurred during execution
     pub last_permission_error: Arc<Mutex<Option<PermissionError>>>,
+    /// Output the component wrote to stdout and stderr
+    pub captured_output: CapturedOutput,
 }
 
 impl wasmtime_wasi::WasiView for WasiState {
@@ -120,11 +243,14 @@ impl WasiStateTemplate {
     /// Creates a new `WasiState` from the template.
     pub fn build(&self) -> anyhow::Result<WasiState> {
         let mut ctx_builder = WasiCtxBuilder::new();
+        // Output is captured rather than inherited so it never interleaves with the
+        // server's own stdout, which carries the protocol in stdio mode
+        let captured_output = CapturedOutput::new();
         if self.allow_stdout {
-            ctx_builder.inherit_stdout();
+            ctx_builder.stdout(captured_output.stdout.clone());
         }
         if self.allow_stderr {
-            ctx_builder.inherit_stderr();
+            ctx_builder.stderr(captured_output.stderr.clone());
         }
         ctx_builder.inherit_args();
         if self.allow_args {
@@ -166,6 +292,7 @@ impl WasiStateTemplate {
                 .as_ref()
                 .map(|limits| CustomResourceLimiter::new(limits.clone())),
             last_permission_error: Arc::new(Mutex::new(None)),
+            captured_output,
         })
     }
 }
@@ -191,9 +318,9 @@ pub struct NetworkPermissions {
 /// this includes the wasmtime_wasi, wasmtime_wasi_config and wasmtime_wasi_http states
 #[derive(Clone)]
 pub struct WasiStateTemplate {
-    /// Whether to allow stdout access
+    /// Whether to capture stdout; otherwise writes are discarded
     pub allow_stdout: bool,
-    /// Whether to allow stderr access
+    /// Whether to capture stderr; otherwise writes are discarded
     pub allow_stderr: bool,
     /// Whether to allow command line arguments access
     pub allow_args: bool,
@@ -281,7 +408,15 @@ pub(crate) fn extract_env_vars(
     if let Some(env_perms) = &policy.permissions.environment {
         if let Some(env_allow_vec) = &env_perms.allow {
             for env_allow in env_allow_vec {
-                if let Some(value) = environment_vars.get(&env_allow.key) {
+                if env_allow.prefix().is_some() {
+                    // Wildcard rules expose every host variable sharing the prefix
+                    env_vars.extend(
+                        environment_vars
+                            .iter()
+                            .filter(|(name, _)| env_allow.matches(name))
+                            .map(|(name, value)| (name.clone(), value.clone())),
+                    );
+                } else if let Some(value) = environment_vars.get(&env_allow.key) {
                     env_vars.insert(env_allow.key.clone(), value.clone());
                 }
             }
@@ -338,7 +473,19 @@ pub(crate) fn extract_storage_permissions(
                     let path = Path::new(uri);
                     let (file_perms, dir_perms) = calculate_permissions(&storage_permission.access);
                     let guest_path = path.to_string_lossy().to_string();
-                    let host_path = component_dir.join(path);
+                    // Several rules for one path become a single preopen with the union of
+                    // their access. Separate preopens would leave it to the guest which one
+                    // it resolves the path against, so a read-only copy could shadow a write
+                    // grant or the other way around.
+                    if let Some(existing) = preopened_dirs
+                        .iter_mut()
+                        .find(|dir| dir.guest_path == guest_path)
+                    {
+                        existing.file_perms |= file_perms;
+                        existing.dir_perms |= dir_perms;
+                        continue;
+                    }
+                    let host_path = storage_host_path(path, component_dir)?;
                     preopened_dirs.push(PreopenedDir {
                         host_path,
                         guest_path,
@@ -352,6 +499,45 @@ pub(crate) fn extract_storage_permissions(
     Ok(preopened_dirs)
 }
 
+/// Resolve the host directory preopened for an `fs://` grant.
+///
+/// Relative paths resolve against the component directory. A grant is refused when it would
+/// expose the component directory's own files, i.e. it names that directory, one of its
+/// ancestors, or the downloads, scratch or log directories shared by all components.
+pub(crate) fn storage_host_path(path: &Path, component_dir: &Path) -> anyhow::Result<PathBuf> {
+    let host_path = normalize_path(&component_dir.join(path));
+    let canonical = |p: &Path| std::fs::canonicalize(p).unwrap_or_else(|_| normalize_path(p));
+    let resolved = canonical(&host_path);
+    let component_dir = canonical(component_dir);
+
+    let shared_dir = [crate::DOWNLOADS_DIR, crate::SCRATCH_DIR, crate::LOGS_DIR]
+        .iter()
+        .any(|dir| resolved.starts_with(component_dir.join(dir)));
+    if component_dir.starts_with(&resolved) || shared_dir {
+        anyhow::bail!(
+            "Storage permission for '{}' would expose the component directory {}; grant a dedicated directory instead",
+            path.display(),
+            component_dir.display()
+        );
+    }
+    Ok(host_path)
+}
+
+/// Lexically resolve `.` and `..` components
+pub(crate) fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
 pub(crate) fn calculate_permissions(
     access_types: &[AccessType],
 ) -> (wasmtime_wasi::FilePerms, wasmtime_wasi::DirPerms) {
@@ -579,6 +765,37 @@ permissions:
         assert!(extracted_vars.is_empty());
     }
 
+    #[test]
+    fn test_extract_environment_variables_with_prefix_wildcard() {
+        let yaml_content = r#"
+version: "1.0"
+permissions:
+  environment:
+    allow:
+      - key: "AWS_*"
+      - key: "HOME"
+"#;
+        let policy = PolicyParser::parse_str(yaml_content).unwrap();
+        let env_vars = HashMap::from([
+            ("AWS_REGION".to_string(), "eu-west-1".to_string()),
+            ("AWS_PROFILE".to_string(), "dev".to_string()),
+            ("AWSOME".to_string(), "no".to_string()),
+            ("MY_AWS_KEY".to_string(), "no".to_string()),
+            ("HOME".to_string(), "/home/user".to_string()),
+        ]);
+
+        let extracted_vars = extract_env_vars(&policy, &env_vars, None).unwrap();
+        assert_eq!(extracted_vars.len(), 3);
+        assert_eq!(
+            extracted_vars.get("AWS_REGION"),
+            Some(&"eu-west-1".to_string())
+        );
+        assert_eq!(extracted_vars.get("AWS_PROFILE"), Some(&"dev".to_string()));
+        assert_eq!(extracted_vars.get("HOME"), Some(&"/home/user".to_string()));
+        assert!(!extracted_vars.contains_key("AWSOME"));
+        assert!(!extracted_vars.contains_key("MY_AWS_KEY"));
+    }
+
     #[test]
     fn test_extract_network_permissions_with_allow() {
         let policy = create_test_policy();
@@ -652,6 +869,38 @@ permissions:
         );
     }
 
+    #[test]
+    fn test_storage_grants_cannot_expose_component_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let component_dir = temp_dir.path();
+
+        for path in [
+            ".",
+            "",
+            "data/..",
+            "downloads",
+            "scratch/other-call",
+            "logs",
+        ] {
+            assert!(
+                storage_host_path(Path::new(path), component_dir).is_err(),
+                "grant for '{path}' should be refused"
+            );
+        }
+        assert!(storage_host_path(component_dir, component_dir).is_err());
+        assert!(storage_host_path(component_dir.parent().unwrap(), component_dir).is_err());
+
+        assert_eq!(
+            storage_host_path(Path::new("data/./cache"), component_dir).unwrap(),
+            component_dir.join("data/cache")
+        );
+        let elsewhere = TempDir::new().unwrap();
+        assert_eq!(
+            storage_host_path(elsewhere.path(), component_dir).unwrap(),
+            elsewhere.path()
+        );
+    }
+
     #[test]
     fn test_extract_storage_permissions_skips_non_fs_uri() {
         let temp_dir = TempDir::new().unwrap();
@@ -719,6 +968,37 @@ permissions:
         );
     }
 
+    #[test]
+    fn test_rules_for_the_same_path_share_one_preopen() {
+        let temp_dir = TempDir::new().unwrap();
+        let yaml_content = r#"
+version: "1.0"
+permissions:
+  storage:
+    allow:
+      - uri: "fs://data"
+        access: ["read"]
+      - uri: "fs://cache"
+        access: ["read"]
+      - uri: "fs://data"
+        access: ["write"]
+"#;
+        let policy = PolicyParser::parse_str(yaml_content).unwrap();
+        let preopened_dirs = extract_storage_permissions(&policy, temp_dir.path()).unwrap();
+
+        assert_eq!(preopened_dirs.len(), 2);
+        assert_eq!(preopened_dirs[0].guest_path, "data");
+        assert_eq!(
+            preopened_dirs[0].file_perms,
+            wasmtime_wasi::FilePerms::READ | wasmtime_wasi::FilePerms::WRITE
+        );
+        assert_eq!(
+            preopened_dirs[0].dir_perms,
+            wasmtime_wasi::DirPerms::READ | wasmtime_wasi::DirPerms::MUTATE
+        );
+        assert_eq!(preopened_dirs[1].dir_perms, wasmtime_wasi::DirPerms::READ);
+    }
+
     #[test]
     fn test_create_wasi_state_template_from_policy() {
         let temp_dir = TempDir::new().unwrap();
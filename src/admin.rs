@@ -0,0 +1,199 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Plain HTTP admin endpoints under `/admin` for operations tooling.
+//!
+//! Served next to the streamable HTTP transport when an admin token is configured. The
+//! endpoints call the same handlers as the CLI and MCP tools, and every request must carry
+//! the token as `Authorization: Bearer <token>`:
+//!
+//! - `GET /admin/components` lists components with their tool counts and schemas
+//! - `GET /admin/components/{id}/policy` returns a component's policy
+//! - `POST /admin/components` loads the component at `{"uri": ...}`
+//! - `POST /admin/components/{id}/rename` gives a component the id in `{"id": ...}`
+//! - `GET /admin/startup` reports which installed components loaded at startup and which failed
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use axum::extract::{Path, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use mcp_server::components::{
+    handle_list_components, handle_load_component_cli, handle_rename_component,
+};
+use mcp_server::tools::handle_get_policy;
+use mcp_server::{LifecycleManager, McpServer};
+use rmcp::model::{CallToolRequestParam, CallToolResult};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+#[derive(Clone)]
+struct AdminState {
+    lifecycle_manager: LifecycleManager,
+    server: McpServer,
+}
+
+/// Body of `POST /admin/components`
+#[derive(Debug, Deserialize)]
+struct LoadRequest {
+    uri: String,
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    force: bool,
+}
+
+/// Body of `POST /admin/components/{id}/rename`
+#[derive(Debug, Deserialize)]
+struct RenameRequest {
+    id: String,
+}
+
+/// Router serving the admin endpoints, rejecting requests without `token`
+pub fn router(lifecycle_manager: LifecycleManager, server: McpServer, token: String) -> Router {
+    let state = AdminState {
+        lifecycle_manager,
+        server,
+    };
+    Router::new()
+        .route(
+            "/admin/components",
+            get(list_components).post(load_component),
+        )
+        .route("/admin/components/{id}/policy", get(component_policy))
+        .route("/admin/components/{id}/rename", post(rename_component))
+        .route("/admin/startup", get(startup_report))
+        .layer(axum::middleware::from_fn_with_state(
+            Arc::<str>::from(token),
+            require_token,
+        ))
+        .with_state(state)
+}
+
+async fn require_token(State(token): State<Arc<str>>, request: Request, next: Next) -> Response {
+    let presented = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    match presented {
+        Some(presented) if tokens_match(presented, &token) => next.run(request).await,
+        _ => (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            Json(json!({ "error": "missing or invalid admin token" })),
+        )
+            .into_response(),
+    }
+}
+
+/// Compare without short-circuiting so response timing does not reveal the token
+fn tokens_match(presented: &str, expected: &str) -> bool {
+    presented.len() == expected.len()
+        && presented
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+async fn list_components(State(state): State<AdminState>) -> Response {
+    respond(
+        handle_list_components(&state.lifecycle_manager).await,
+        StatusCode::INTERNAL_SERVER_ERROR,
+    )
+}
+
+async fn startup_report(State(state): State<AdminState>) -> Response {
+    Json(state.lifecycle_manager.get_startup_report()).into_response()
+}
+
+async fn component_policy(State(state): State<AdminState>, Path(id): Path<String>) -> Response {
+    let req = tool_request("get-policy", json!({ "component_id": id }));
+    respond(
+        handle_get_policy(&req, &state.lifecycle_manager).await,
+        StatusCode::NOT_FOUND,
+    )
+}
+
+async fn load_component(
+    State(state): State<AdminState>,
+    Json(body): Json<LoadRequest>,
+) -> Response {
+    let req = tool_request(
+        "load-component",
+        json!({ "path": body.uri, "id": body.id, "force": body.force }),
+    );
+    let result = handle_load_component_cli(&req, &state.lifecycle_manager).await;
+    if result.is_ok() {
+        // MCP clients connected to this server should pick up the new tools
+        if let Some(peer) = state.server.get_peer() {
+            if let Err(e) = peer.notify_tool_list_changed().await {
+                tracing::warn!("Failed to notify tool list changed: {}", e);
+            }
+        }
+    }
+    respond(result, StatusCode::BAD_REQUEST)
+}
+
+async fn rename_component(
+    State(state): State<AdminState>,
+    Path(id): Path<String>,
+    Json(body): Json<RenameRequest>,
+) -> Response {
+    respond(
+        handle_rename_component(
+            &state.lifecycle_manager,
+            &id,
+            &body.id,
+            state.server.get_peer(),
+        )
+        .await,
+        StatusCode::BAD_REQUEST,
+    )
+}
+
+fn tool_request(name: &'static str, arguments: Value) -> CallToolRequestParam {
+    CallToolRequestParam {
+        name: name.into(),
+        arguments: arguments.as_object().cloned(),
+    }
+}
+
+/// Turn a handler's JSON text result into a response, using `error_status` for failures
+fn respond(result: Result<CallToolResult>, error_status: StatusCode) -> Response {
+    match result.and_then(|result| result_json(&result)) {
+        Ok(body) => Json(body).into_response(),
+        Err(e) => (error_status, Json(json!({ "error": format!("{e:#}") }))).into_response(),
+    }
+}
+
+fn result_json(result: &CallToolResult) -> Result<Value> {
+    let text = result
+        .content
+        .first()
+        .and_then(|content| content.as_text())
+        .context("Handler returned no content")?;
+    let value: Value = serde_json::from_str(&text.text).context("Handler returned invalid JSON")?;
+    if result.is_error.unwrap_or(false) {
+        anyhow::bail!("{}", value.get("error").unwrap_or(&value));
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_match() {
+        assert!(tokens_match("s3cret", "s3cret"));
+        assert!(!tokens_match("s3cret", "s3cre"));
+        assert!(!tokens_match("s3creT", "s3cret"));
+        assert!(!tokens_match("", "s3cret"));
+    }
+}
@@ -0,0 +1,393 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Recording component tool calls with `--record-calls` and replaying them with
+//! `wassette replay`.
+//!
+//! Every component tool call that returns a result is written to its own JSON file in the
+//! recording directory, numbered in the order the calls completed. Replaying runs each
+//! recorded call against the components installed now and compares the results, so a
+//! session with known-good output doubles as a regression test for those components.
+//! Replayed calls are handled the way the server handles them, with the tool timeouts,
+//! output limit and tool prefixes from the configuration.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use mcp_server::tools::{handle_component_tools_call, is_builtin_tool};
+use mcp_server::{
+    LifecycleManager, ServerHooks, ToolCallContext, ToolCallOptions, ToolResultContext,
+    ToolTimeouts,
+};
+use rmcp::model::{CallToolRequestParam, CallToolResult, ErrorData};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use tokio::io::AsyncWriteExt;
+
+use crate::config::Config;
+
+/// Metadata entry carrying a call's arguments from `before_tool_call` to `after_tool_call`
+const ARGUMENTS_KEY: &str = "record_calls.arguments";
+
+/// A tool call as written to the recording directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedCall {
+    pub tool: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<Map<String, Value>>,
+    pub result: CallToolResult,
+}
+
+/// Hook writing every component tool call and its result to a directory. Built-in tools
+/// are not recorded, since replaying them would change the server's state.
+pub struct CallRecorder {
+    dir: PathBuf,
+    /// Number of the last recording written
+    last: AtomicUsize,
+}
+
+impl CallRecorder {
+    /// Record into `dir`, creating it if needed. Numbering continues after any recordings
+    /// already in it.
+    pub fn open(dir: &Path) -> Result<Self> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create recording directory {}", dir.display()))?;
+        let last = recording_files(dir)?
+            .iter()
+            .filter_map(|path| sequence_number(path))
+            .max()
+            .unwrap_or(0);
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            last: AtomicUsize::new(last),
+        })
+    }
+
+    async fn write(&self, call: &RecordedCall) -> Result<()> {
+        let number = self.last.fetch_add(1, Ordering::SeqCst) + 1;
+        let path = self
+            .dir
+            .join(format!("{number:06}-{}.json", file_name_safe(&call.tool)));
+        // Recorded arguments and results can hold secrets, so only the owner may read them
+        let mut options = tokio::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        let mut file = options
+            .open(&path)
+            .await
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        file.write_all(&serde_json::to_vec_pretty(call)?)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+#[async_trait]
+impl ServerHooks for CallRecorder {
+    async fn before_tool_call(&self, ctx: &mut ToolCallContext<'_>) -> Result<(), ErrorData> {
+        if !is_builtin_tool(&ctx.tool_name) {
+            let arguments = ctx.arguments().cloned().map_or(Value::Null, Value::Object);
+            ctx.metadata.insert(ARGUMENTS_KEY.to_string(), arguments);
+        }
+        Ok(())
+    }
+
+    async fn after_tool_call(&self, ctx: &mut ToolResultContext) -> Result<(), ErrorData> {
+        let Some(arguments) = ctx.metadata.get(ARGUMENTS_KEY) else {
+            return Ok(());
+        };
+        let call = RecordedCall {
+            tool: ctx.tool_name.clone(),
+            arguments: arguments.as_object().cloned(),
+            result: ctx.result.clone(),
+        };
+        // A failed recording must not fail the call itself
+        if let Err(error) = self.write(&call).await {
+            tracing::warn!(
+                tool = %ctx.tool_name,
+                error = %format!("{error:#}"),
+                "Failed to record tool call"
+            );
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "record-calls"
+    }
+}
+
+/// Outcome of replaying every recorded call, in recording order
+#[derive(Debug, Default, Serialize)]
+pub struct ReplayReport {
+    pub passed: bool,
+    pub calls: Vec<ReplayOutcome>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReplayOutcome {
+    pub file: String,
+    pub tool: String,
+    pub matched: bool,
+    /// Recorded result, included when the replayed one differs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected: Option<Value>,
+    /// Result of the replay, or the error it failed with, when it differs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual: Option<Value>,
+}
+
+/// Call options matching those `wassette serve` applies with `config`
+pub fn replay_options(config: &Config) -> ToolCallOptions {
+    ToolCallOptions {
+        timeouts: ToolTimeouts::from_millis(config.tool_timeout_ms, &config.tool_timeouts),
+        max_output_bytes: config.max_tool_output_bytes,
+        prefix_component_tools: config.prefix_component_tools,
+        ..Default::default()
+    }
+}
+
+/// Re-run every call recorded in `dir` with `options` and compare the results with the
+/// recorded ones. Results are compared without their `_meta`.
+pub async fn replay(
+    dir: &Path,
+    lifecycle_manager: &LifecycleManager,
+    options: &ToolCallOptions,
+) -> Result<ReplayReport> {
+    let files = recording_files(dir)?;
+    if files.is_empty() {
+        bail!("No recorded calls found in {}", dir.display());
+    }
+
+    let mut report = ReplayReport {
+        passed: true,
+        calls: Vec::new(),
+    };
+    for path in files {
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let call: RecordedCall = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid recorded call {}", path.display()))?;
+
+        let req = CallToolRequestParam {
+            name: call.tool.clone().into(),
+            arguments: call.arguments,
+        };
+        let actual = match handle_component_tools_call(req, lifecycle_manager, options).await {
+            Ok(result) => comparable(&serde_json::from_value(result)?)?,
+            Err(e) => json!({ "error": format!("{e:#}") }),
+        };
+        let expected = comparable(&call.result)?;
+        let matched = actual == expected;
+        report.passed &= matched;
+        report.calls.push(ReplayOutcome {
+            file: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            tool: call.tool,
+            matched,
+            expected: (!matched).then_some(expected),
+            actual: (!matched).then_some(actual),
+        });
+    }
+    Ok(report)
+}
+
+/// Human-readable form of a replay report
+pub fn render_report(report: &ReplayReport) -> String {
+    let mut out = String::new();
+    for call in &report.calls {
+        let mark = if call.matched { "✓" } else { "✗" };
+        out.push_str(&format!("{mark} {} ({})\n", call.file, call.tool));
+        if let (Some(expected), Some(actual)) = (&call.expected, &call.actual) {
+            out.push_str(&format!("    expected: {expected}\n"));
+            out.push_str(&format!("    actual:   {actual}\n"));
+        }
+    }
+    let matched = report.calls.iter().filter(|call| call.matched).count();
+    out.push_str(&format!(
+        "{matched} of {} recorded calls matched\n",
+        report.calls.len()
+    ));
+    out
+}
+
+fn comparable(result: &CallToolResult) -> Result<Value> {
+    let mut value = serde_json::to_value(result)?;
+    if let Some(object) = value.as_object_mut() {
+        object.remove("_meta");
+    }
+    Ok(value)
+}
+
+/// Recordings in `dir`, in recording order
+fn recording_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let entries = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read recording directory {}", dir.display()))?;
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+fn sequence_number(path: &Path) -> Option<usize> {
+    let name = path.file_name()?.to_str()?;
+    name.split_once('-')?.0.parse().ok()
+}
+
+fn file_name_safe(tool: &str) -> String {
+    tool.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use mcp_server::McpServer;
+    use rmcp::ServiceExt;
+    use wassette::LoadOptions;
+
+    use super::*;
+    use crate::test_support::answer_component;
+
+    /// Call each of `tools` through a server recording with `recorder` and handling calls
+    /// with `options`
+    async fn recorded_calls(
+        recorder: CallRecorder,
+        lifecycle_manager: &LifecycleManager,
+        options: &ToolCallOptions,
+        tools: &[&str],
+    ) {
+        let server = McpServer::builder(lifecycle_manager.clone())
+            .with_hooks(recorder)
+            .with_tool_timeouts(options.timeouts.clone())
+            .with_max_tool_output_bytes(options.max_output_bytes)
+            .with_component_tool_prefixes(options.prefix_component_tools)
+            .build();
+        let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+        tokio::spawn(async move {
+            if let Ok(running) = server.serve(tokio::io::split(server_io)).await {
+                let _ = running.waiting().await;
+            }
+        });
+        let client = ().serve(tokio::io::split(client_io)).await.unwrap();
+        for tool in tools {
+            let result = client
+                .call_tool(CallToolRequestParam {
+                    name: tool.to_string().into(),
+                    arguments: Some(Map::new()),
+                })
+                .await
+                .unwrap();
+            assert_ne!(result.is_error, Some(true), "{tool} failed: {result:?}");
+        }
+        client.cancel().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_replay_matches_recorded_calls_and_flags_changed_output() {
+        let component_dir = tempfile::tempdir().unwrap();
+        let source_dir = tempfile::tempdir().unwrap();
+        let recording_dir = tempfile::tempdir().unwrap();
+        let lifecycle_manager = LifecycleManager::new(component_dir.path()).await.unwrap();
+        let original = answer_component(source_dir.path(), "answer-42", 42, Some(1));
+        lifecycle_manager
+            .load_component_with(
+                &format!("file://{}", original.display()),
+                LoadOptions {
+                    component_id: Some("answer".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        // The server cuts `42` down to `4`, so replay must apply the same limit to match
+        let options = ToolCallOptions {
+            max_output_bytes: Some(1),
+            ..Default::default()
+        };
+        let recorder = CallRecorder::open(recording_dir.path()).unwrap();
+        recorded_calls(
+            recorder,
+            &lifecycle_manager,
+            &options,
+            &["answer", "version"],
+        )
+        .await;
+        let files = recording_files(recording_dir.path()).unwrap();
+        let names: Vec<_> = files
+            .iter()
+            .map(|path| path.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(names, ["000001-answer.json", "000002-version.json"]);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&files[0]).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let report = replay(recording_dir.path(), &lifecycle_manager, &options)
+            .await
+            .unwrap();
+        assert!(report.passed, "{}", render_report(&report));
+        assert_eq!(report.calls.len(), 2);
+
+        // The same component now answers differently
+        let changed = answer_component(source_dir.path(), "answer-72", 72, Some(1));
+        lifecycle_manager
+            .load_component_with(
+                &format!("file://{}", changed.display()),
+                LoadOptions {
+                    component_id: Some("answer".to_string()),
+                    force: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let report = replay(recording_dir.path(), &lifecycle_manager, &options)
+            .await
+            .unwrap();
+        assert!(!report.passed);
+        let outcomes: Vec<(&str, bool)> = report
+            .calls
+            .iter()
+            .map(|call| (call.tool.as_str(), call.matched))
+            .collect();
+        assert_eq!(outcomes, [("answer", false), ("version", true)]);
+        let flagged = &report.calls[0];
+        assert!(flagged
+            .expected
+            .as_ref()
+            .unwrap()
+            .to_string()
+            .contains("4...[truncated 1 bytes]"));
+        assert!(flagged
+            .actual
+            .as_ref()
+            .unwrap()
+            .to_string()
+            .contains("7...[truncated 1 bytes]"));
+        assert!(render_report(&report).ends_with("1 of 2 recorded calls matched\n"));
+
+        // A new recorder continues the numbering
+        let recorder = CallRecorder::open(recording_dir.path()).unwrap();
+        recorded_calls(recorder, &lifecycle_manager, &options, &["version"]).await;
+        assert!(recording_dir.path().join("000003-version.json").exists());
+    }
+}
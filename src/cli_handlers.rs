@@ -3,7 +3,9 @@
 
 //! CLI command handlers for wassette
 
-use std::path::PathBuf;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use anyhow::{Context, Result};
 use mcp_server::components::{
@@ -16,13 +18,16 @@ use mcp_server::tools::{
     handle_revoke_environment_variable_permission, handle_revoke_network_permission,
     handle_revoke_storage_permission,
 };
-use mcp_server::LifecycleManager;
+use mcp_server::{handle_tools_list, LifecycleManager, ToolListOptions};
 use rmcp::model::CallToolRequestParam;
-use serde_json::{Map, Value};
+use serde_json::{json, Map, Value};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use wassette::{DeclaredPermission, LogLine, PermissionAudit};
 
 use crate::config;
 use crate::format::{print_result, OutputFormat};
 use crate::tools::ToolName;
+use crate::utils::{download_clients, load_default_policy};
 
 /// Handle CLI tool commands by creating appropriate tool call requests
 pub async fn handle_tool_cli_command(
@@ -78,6 +83,33 @@ pub async fn handle_tool_cli_command(
     Ok(())
 }
 
+/// Load the configuration `wassette serve` would run with when given no options
+pub fn load_config() -> Result<config::Config> {
+    config::Config::from_serve(&crate::commands::Serve {
+        component_dir: None,
+        transport: Default::default(),
+        env_vars: vec![],
+        env_file: None,
+        disable_builtin_tools: false,
+        strict_policy: false,
+        strict_security: false,
+        safe_mode: false,
+        bind_address: None,
+        manifest: None,
+        manifest_dir: None,
+        tls_cert: None,
+        tls_key: None,
+        startup_load_policy: None,
+        max_sessions: None,
+        session_idle_timeout_secs: None,
+        max_loaded_memory: None,
+        protocol_log: None,
+        record_calls: None,
+        reload_on_sighup: false,
+    })
+    .context("Failed to load configuration")
+}
+
 /// Create LifecycleManager from component directory
 ///
 /// For CLI responsiveness, we create an unloaded lifecycle manager which
@@ -91,36 +123,393 @@ pub async fn create_lifecycle_manager(component_dir: Option<PathBuf>) -> Result<
                 eprintln!("WARN: Unable to determine default secrets directory, using `secrets` directory in the current working directory");
                 PathBuf::from("./secrets")
             }),
+            create_component_dir: true,
             environment_vars: std::collections::HashMap::new(),
             bind_address: "127.0.0.1:9001".to_string(),
+            tool_timeout_ms: None,
+            tool_timeouts: Default::default(),
+            max_tool_output_bytes: None,
+            max_components: None,
+            max_loaded_memory: None,
+            max_sessions: None,
+            session_idle_timeout_secs: None,
+            tls_cert: None,
+            tls_key: None,
+            outbound_user_agent: None,
+            outbound_request_timeout_ms: None,
+            outbound_allow_internal_addresses: false,
+            startup_load_policy: Default::default(),
+            compile_opt: Default::default(),
+            max_total_log_bytes: None,
+            egress_byte_limit: None,
+            duplicate_tool_policy: Default::default(),
+            oci_component_ids: Default::default(),
+            call_queue_depth: None,
+            call_queue_reject_when_full: false,
+            call_queue_max_wait_ms: None,
+            instructions_append: None,
+            admin_token: None,
+            default_policy: None,
+            prefix_component_tools: false,
+            group_component_tools: false,
+            trace_component_stderr: false,
+            redact_component_secrets: true,
         }
     } else {
-        config::Config::from_serve(&crate::commands::Serve {
-            component_dir: None,
-            transport: Default::default(),
-            env_vars: vec![],
-            env_file: None,
-            disable_builtin_tools: false,
-            bind_address: None,
-            manifest: None,
-        })
-        .context("Failed to load configuration")?
+        load_config()?
     };
 
     // Use unloaded manager for fast CLI startup, but preserve custom secrets dir
     let config::Config {
         component_dir,
+        create_component_dir,
         secrets_dir,
         environment_vars,
-        bind_address: _,
+        compile_opt,
+        default_policy,
+        duplicate_tool_policy,
+        oci_component_ids,
+        ..
     } = config;
 
+    let (oci_client, http_client) = download_clients()?;
     LifecycleManager::builder(component_dir)
         .with_environment_vars(environment_vars)
         .with_secrets_dir(secrets_dir)
-        .with_oci_client(oci_client::Client::default())
-        .with_http_client(reqwest::Client::default())
+        .with_oci_client(oci_client)
+        .with_http_client(http_client)
         .with_eager_loading(false)
+        .with_create_component_dir(create_component_dir)
+        .with_compilation_strategy(compile_opt)
+        .with_duplicate_tool_policy(duplicate_tool_policy)
+        .with_oci_component_ids(oci_component_ids)
+        .with_default_policy(load_default_policy(default_policy.as_deref())?)
         .build()
         .await
 }
+
+/// Grant the permissions `audit` found missing. For each gap the rules the component
+/// declares are granted; a gap without any is asked about. `prompt` is shown each question
+/// and returns the answer, and without it (`--yes`) declared rules are granted without asking
+/// and undeclared gaps are skipped.
+pub async fn fix_permission_gaps(
+    lifecycle_manager: &LifecycleManager,
+    audit: &PermissionAudit,
+    mut prompt: Option<&mut dyn FnMut(&str) -> Result<String>>,
+) -> Result<Vec<DeclaredPermission>> {
+    let component_id = &audit.component_id;
+    lifecycle_manager
+        .ensure_component_loaded(component_id)
+        .await?;
+
+    let mut granted = Vec::new();
+    for gap in &audit.gaps {
+        let declared: Vec<&DeclaredPermission> = audit
+            .suggested_grants
+            .iter()
+            .filter(|permission| &permission.permission_type == gap)
+            .collect();
+
+        let mut grants = Vec::new();
+        if declared.is_empty() {
+            let Some((question, details)) = manual_grant(gap) else {
+                continue;
+            };
+            let Some(ask) = prompt.as_mut() else {
+                eprintln!(
+                    "Skipping {gap}: '{component_id}' declares no {gap} rule. Grant one with `wassette permission grant {gap}`"
+                );
+                continue;
+            };
+            let answer = ask(&format!(
+                "'{component_id}' needs {gap} access but declares no rule. {question} (empty to skip): "
+            ))?;
+            let answer = answer.trim();
+            if !answer.is_empty() {
+                grants.push(DeclaredPermission {
+                    permission_type: gap.clone(),
+                    details: details(answer),
+                });
+            }
+        }
+        for permission in declared {
+            let accepted = match prompt.as_mut() {
+                Some(ask) => ask(&format!(
+                    "Grant {gap} permission {} to '{component_id}'? [y/N]: ",
+                    permission.details
+                ))?
+                .trim()
+                .eq_ignore_ascii_case("y"),
+                None => true,
+            };
+            if accepted {
+                grants.push(permission.clone());
+            }
+        }
+
+        for permission in grants {
+            lifecycle_manager
+                .grant_permission(
+                    component_id,
+                    &permission.permission_type,
+                    &permission.details,
+                )
+                .await?;
+            granted.push(permission);
+        }
+    }
+    Ok(granted)
+}
+
+/// Question to ask for a gap the component declares no rule for, and the grant details
+/// built from the answer
+fn manual_grant(gap: &str) -> Option<(&'static str, fn(&str) -> Value)> {
+    match gap {
+        "network" => Some((
+            "Host to allow, e.g. api.example.com",
+            |host| json!({ "host": host }),
+        )),
+        "storage" => Some(("Path to allow reading, e.g. /data", |path| {
+            json!({
+                "uri": format!("fs://{}", path.trim_start_matches("fs://")),
+                "access": ["read"],
+            })
+        })),
+        "environment" => Some((
+            "Environment variable to expose, e.g. API_KEY",
+            |key| json!({ "key": key }),
+        )),
+        _ => None,
+    }
+}
+
+/// How often `component logs --follow` checks the log file for new lines
+const LOG_FOLLOW_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Print a captured component output line
+pub fn print_log_line(line: &LogLine) {
+    println!("[{}] {}", line.stream, line.line);
+}
+
+/// Print lines appended to a component log file until Ctrl-C is pressed
+pub async fn follow_component_logs(path: &Path) -> Result<()> {
+    let mut offset = tokio::fs::metadata(path).await.map_or(0, |m| m.len());
+    let mut pending = String::new();
+    let mut interval = tokio::time::interval(LOG_FOLLOW_INTERVAL);
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+
+    loop {
+        tokio::select! {
+            result = &mut ctrl_c => return result.context("Failed to listen for Ctrl-C"),
+            _ = interval.tick() => {}
+        }
+
+        let len = tokio::fs::metadata(path).await.map_or(0, |m| m.len());
+        if len < offset {
+            // The log was compacted or removed, so continue from its new end
+            offset = len;
+            pending.clear();
+        }
+        if len == offset {
+            continue;
+        }
+
+        let mut file = tokio::fs::File::open(path).await?;
+        file.seek(SeekFrom::Start(offset)).await?;
+        let mut chunk = Vec::new();
+        offset += file.read_to_end(&mut chunk).await? as u64;
+        pending.push_str(&String::from_utf8_lossy(&chunk));
+
+        while let Some(end) = pending.find('\n') {
+            let raw: String = pending.drain(..=end).collect();
+            if let Some(line) = LogLine::parse(raw.trim_end()) {
+                print_log_line(&line);
+            }
+        }
+    }
+}
+
+/// Tool list options matching those `wassette serve` applies with `config`
+pub fn tool_list_options(config: &config::Config) -> ToolListOptions {
+    ToolListOptions {
+        prefix_component_tools: config.prefix_component_tools,
+        group_component_tools: config.group_component_tools,
+        ..Default::default()
+    }
+}
+
+/// The `tools/list` response a client of `wassette serve` listing tools with `options` would
+/// receive, as pretty-printed JSON, for `component list --output-format mcp`
+pub async fn mcp_tools_list(
+    lifecycle_manager: &LifecycleManager,
+    options: ToolListOptions,
+) -> Result<String> {
+    let result = handle_tools_list(lifecycle_manager, options).await?;
+    Ok(serde_json::to_string_pretty(&result)?)
+}
+
+/// Compile every installed component without loading it, for `doctor` and
+/// `component list --show-failed`: how many were checked and the ones that failed with the
+/// error for each
+pub async fn check_components(lifecycle_manager: &LifecycleManager) -> Result<Value> {
+    let report = lifecycle_manager.check_installed_components().await?;
+    let failed: Vec<Value> = report
+        .failed()
+        .map(|(id, reason)| json!({ "id": id, "reason": reason }))
+        .collect();
+    Ok(json!({ "checked": report.components.len(), "failed": failed }))
+}
+
+/// Report of the calls made by `tool invoke --repeat --timing`: one entry per call plus the
+/// min, max, mean and 95th percentile durations in milliseconds. `calls` holds each call's
+/// duration and whether it succeeded.
+pub fn timing_report(tool: &str, calls: &[(Duration, bool)]) -> Value {
+    let millis = |d: &Duration| d.as_secs_f64() * 1000.0;
+    let mut sorted: Vec<f64> = calls.iter().map(|(d, _)| millis(d)).collect();
+    sorted.sort_by(f64::total_cmp);
+
+    let summary = if sorted.is_empty() {
+        Value::Null
+    } else {
+        // Nearest-rank percentile
+        let p95_rank = ((sorted.len() as f64) * 0.95).ceil() as usize;
+        json!({
+            "min_ms": sorted[0],
+            "max_ms": sorted[sorted.len() - 1],
+            "mean_ms": sorted.iter().sum::<f64>() / sorted.len() as f64,
+            "p95_ms": sorted[p95_rank.max(1) - 1],
+        })
+    };
+
+    json!({
+        "tool": tool,
+        "iterations": calls.len(),
+        "errors": calls.iter().filter(|(_, ok)| !ok).count(),
+        "calls": calls
+            .iter()
+            .enumerate()
+            .map(|(i, (duration, ok))| json!({
+                "iteration": i + 1,
+                "duration_ms": millis(duration),
+                "ok": ok,
+            }))
+            .collect::<Vec<_>>(),
+        "summary": summary,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::answer_component;
+
+    #[tokio::test]
+    async fn test_mcp_tools_list_is_a_list_tools_result() {
+        let component_dir = tempfile::tempdir().unwrap();
+        let source_dir = tempfile::tempdir().unwrap();
+        let wasm = answer_component(source_dir.path(), "answer", 42, None);
+        let lifecycle_manager = LifecycleManager::new(component_dir.path()).await.unwrap();
+        lifecycle_manager
+            .load_component(&format!("file://{}", wasm.display()))
+            .await
+            .unwrap();
+
+        let output = mcp_tools_list(&lifecycle_manager, ToolListOptions::default())
+            .await
+            .unwrap();
+        let result: rmcp::model::ListToolsResult = serde_json::from_str(&output).unwrap();
+        let names: Vec<&str> = result.tools.iter().map(|tool| tool.name.as_ref()).collect();
+        assert!(names.contains(&"load-component"), "{names:?}");
+        assert!(names.contains(&"answer"), "{names:?}");
+
+        // The configured prefix applies as it does for `wassette serve`
+        let options = ToolListOptions {
+            prefix_component_tools: true,
+            ..Default::default()
+        };
+        let output = mcp_tools_list(&lifecycle_manager, options).await.unwrap();
+        let result: rmcp::model::ListToolsResult = serde_json::from_str(&output).unwrap();
+        let names: Vec<&str> = result.tools.iter().map(|tool| tool.name.as_ref()).collect();
+        assert!(names.contains(&"answer__answer"), "{names:?}");
+    }
+
+    #[tokio::test]
+    async fn test_audit_fix_grants_declared_network_permission() {
+        let component_dir = tempfile::tempdir().unwrap();
+        let source_dir = tempfile::tempdir().unwrap();
+        let wasm = source_dir.path().join("fetcher.wasm");
+        let wat = r#"(component
+                (@custom "wassette:permissions" "{\"network\": [{\"host\": \"api.example.com\"}]}")
+                (import "wasi:http/types@0.2.0" (instance))
+                (core module $m (func (export "fetch") (result i32) (i32.const 200)))
+                (core instance $i (instantiate $m))
+                (func $fetch (result u32) (canon lift (core func $i "fetch")))
+                (export "fetch" (func $fetch))
+            )"#;
+        std::fs::write(&wasm, wat::parse_str(wat).unwrap()).unwrap();
+        let lifecycle_manager = LifecycleManager::new(component_dir.path()).await.unwrap();
+        lifecycle_manager
+            .load_component(&format!("file://{}", wasm.display()))
+            .await
+            .unwrap();
+        let network = wassette::PermissionQuery::Network {
+            host: "api.example.com".to_string(),
+        };
+
+        let audit = lifecycle_manager
+            .audit_component_permissions("fetcher")
+            .await
+            .unwrap();
+        assert_eq!(audit.gaps, vec!["network"]);
+
+        // Declining the prompt grants nothing
+        let mut questions = Vec::new();
+        let mut decline = |question: &str| -> Result<String> {
+            questions.push(question.to_string());
+            Ok("n\n".to_string())
+        };
+        let granted = fix_permission_gaps(&lifecycle_manager, &audit, Some(&mut decline))
+            .await
+            .unwrap();
+        assert!(granted.is_empty());
+        assert_eq!(questions.len(), 1);
+        assert!(questions[0].contains("api.example.com"), "{questions:?}");
+
+        // --yes grants the declared rule without asking
+        let granted = fix_permission_gaps(&lifecycle_manager, &audit, None)
+            .await
+            .unwrap();
+        assert_eq!(granted, audit.suggested_grants);
+        let audit = lifecycle_manager
+            .audit_component_permissions("fetcher")
+            .await
+            .unwrap();
+        assert!(audit.gaps.is_empty(), "{audit:?}");
+        let explanation = lifecycle_manager
+            .explain_permission("fetcher", &network)
+            .await
+            .unwrap();
+        assert!(explanation.allowed, "{}", explanation.reason);
+    }
+
+    #[test]
+    fn test_timing_report() {
+        let calls: Vec<(Duration, bool)> = (1..=20)
+            .map(|ms| (Duration::from_millis(ms), ms != 7))
+            .collect();
+        let report = timing_report("ping", &calls);
+
+        assert_eq!(report["tool"], "ping");
+        assert_eq!(report["iterations"], 20);
+        assert_eq!(report["errors"], 1);
+        assert_eq!(report["calls"].as_array().unwrap().len(), 20);
+        assert_eq!(report["calls"][6]["ok"], false);
+        assert_eq!(report["summary"]["min_ms"], 1.0);
+        assert_eq!(report["summary"]["max_ms"], 20.0);
+        assert_eq!(report["summary"]["mean_ms"], 10.5);
+        assert_eq!(report["summary"]["p95_ms"], 19.0);
+
+        assert_eq!(timing_report("ping", &[])["summary"], Value::Null);
+    }
+}
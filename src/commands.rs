@@ -21,6 +21,7 @@ pub enum Shell {
     /// Fish shell
     Fish,
     /// PowerShell
+    #[value(alias = "powershell")]
     PowerShell,
     /// Elvish shell
     Elvish,
@@ -78,6 +79,9 @@ pub enum Commands {
         /// Directory where components are stored. Defaults to $XDG_DATA_HOME/wassette/components
         #[arg(long)]
         component_dir: Option<PathBuf>,
+        /// Inline `$ref`s into `definitions`/`$defs` so the full shape of each schema is shown
+        #[arg(long)]
+        deref: bool,
     },
     /// Manage tools (list, read, invoke).
     Tool {
@@ -89,7 +93,40 @@ pub enum Commands {
         #[command(subcommand)]
         command: RegistryCommands,
     },
+    /// Work with provisioning manifests.
+    Manifest {
+        #[command(subcommand)]
+        command: ManifestCommands,
+    },
+    /// Re-run the tool calls recorded with --record-calls and compare their results.
+    Replay {
+        /// Directory holding the recorded calls
+        dir: PathBuf,
+        /// Directory where components are stored. Defaults to $XDG_DATA_HOME/wassette/components
+        #[arg(long)]
+        component_dir: Option<PathBuf>,
+        /// Print the report in this format instead of as text
+        #[arg(short = 'o', long = "output-format")]
+        output_format: Option<OutputFormat>,
+    },
+    /// Load, call, and unload a bundled component to check that the runtime works here.
+    Selftest {
+        /// Print the report in this format instead of as text
+        #[arg(short = 'o', long = "output-format")]
+        output_format: Option<OutputFormat>,
+    },
+    /// Compile every installed component without loading it and report the ones that fail.
+    /// Exits non-zero when any component fails.
+    Doctor {
+        /// Directory where components are stored. Defaults to $XDG_DATA_HOME/wassette/components
+        #[arg(long)]
+        component_dir: Option<PathBuf>,
+        /// Output format
+        #[arg(short = 'o', long = "output-format", default_value = "json")]
+        output_format: OutputFormat,
+    },
     /// Generate shell completion scripts.
+    #[command(visible_alias = "completions")]
     Autocomplete {
         /// Shell type to generate completions for
         #[arg(value_enum)]
@@ -119,6 +156,41 @@ pub struct Run {
     #[arg(long)]
     #[serde(default)]
     pub disable_builtin_tools: bool,
+
+    /// Fail component loads whose policy declares unknown permission types instead of ignoring them
+    #[arg(long)]
+    #[serde(default)]
+    pub strict_policy: bool,
+
+    /// Refuse to start when the component directory is writable by its group or by all
+    /// users, instead of only warning about it
+    #[arg(long)]
+    #[serde(default)]
+    pub strict_security: bool,
+
+    /// Run every component fully sandboxed: ignore the network, storage and environment
+    /// permissions of all policies and refuse permission grants
+    #[arg(long)]
+    #[serde(default)]
+    pub safe_mode: bool,
+
+    /// What to do when a component in the component directory fails to load at startup:
+    /// `continue` (default) or `fail-fast`, which stops at the first failure and exits with
+    /// an error instead of serving
+    #[arg(long, value_name = "POLICY")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub startup_load_policy: Option<wassette::StartupLoadPolicy>,
+
+    /// Record every JSON-RPC message exchanged with the client to this file (JSON lines)
+    #[arg(long = "protocol-log", alias = "json-rpc-log", value_name = "PATH")]
+    #[serde(skip)]
+    pub protocol_log: Option<PathBuf>,
+
+    /// Write every component tool call and its result to a file in this directory, for
+    /// `wassette replay`
+    #[arg(long, value_name = "DIR")]
+    #[serde(skip)]
+    pub record_calls: Option<PathBuf>,
 }
 
 /// Configuration for serving remotely over HTTP transports
@@ -147,6 +219,23 @@ pub struct Serve {
     #[serde(default)]
     pub disable_builtin_tools: bool,
 
+    /// Fail component loads whose policy declares unknown permission types instead of ignoring them
+    #[arg(long)]
+    #[serde(default)]
+    pub strict_policy: bool,
+
+    /// Refuse to start when the component directory is writable by its group or by all
+    /// users, instead of only warning about it
+    #[arg(long)]
+    #[serde(default)]
+    pub strict_security: bool,
+
+    /// Run every component fully sandboxed: ignore the network, storage and environment
+    /// permissions of all policies and refuse permission grants
+    #[arg(long)]
+    #[serde(default)]
+    pub safe_mode: bool,
+
     /// Bind address for HTTP-based transports (SSE and StreamableHttp). Defaults to 127.0.0.1:9001
     #[arg(long)]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -156,6 +245,64 @@ pub struct Serve {
     #[arg(long)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub manifest: Option<PathBuf>,
+
+    /// Directory of provisioning manifests (*.yaml, *.yml) to merge for headless deployment mode
+    #[arg(long, conflicts_with = "manifest")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manifest_dir: Option<PathBuf>,
+
+    /// PEM certificate chain to serve the HTTP transports over TLS. Requires --tls-key
+    #[arg(long, requires = "tls_key")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls_cert: Option<PathBuf>,
+
+    /// PEM private key matching --tls-cert
+    #[arg(long, requires = "tls_cert")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls_key: Option<PathBuf>,
+
+    /// What to do when a component in the component directory fails to load at startup:
+    /// `continue` (default) or `fail-fast`, which stops at the first failure and exits with
+    /// an error instead of serving
+    #[arg(long, value_name = "POLICY")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub startup_load_policy: Option<wassette::StartupLoadPolicy>,
+
+    /// Maximum number of concurrent streamable HTTP sessions; further sessions are rejected
+    /// with 503 until one is closed
+    #[arg(long, value_name = "N")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_sessions: Option<usize>,
+
+    /// Close a streamable HTTP session once it has gone this many seconds without a request
+    /// (default 1800)
+    #[arg(long = "session-idle-timeout", value_name = "SECS")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_idle_timeout_secs: Option<u64>,
+
+    /// Refuse to load a component once the memory limits declared by the loaded components
+    /// would exceed this budget, e.g. `4Gi`
+    #[arg(long, value_name = "SIZE")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_loaded_memory: Option<String>,
+
+    /// Record every JSON-RPC message exchanged with the client to this file (JSON lines)
+    #[arg(long = "protocol-log", alias = "json-rpc-log", value_name = "PATH")]
+    #[serde(skip)]
+    pub protocol_log: Option<PathBuf>,
+
+    /// Write every component tool call and its result to a file in this directory, for
+    /// `wassette replay`
+    #[arg(long, value_name = "DIR")]
+    #[serde(skip)]
+    pub record_calls: Option<PathBuf>,
+
+    /// On SIGHUP, re-read the configuration file and rescan the component directory,
+    /// loading new components and dropping removed ones. Tool timeouts and the output
+    /// limit are applied from the re-read configuration.
+    #[arg(long)]
+    #[serde(skip)]
+    pub reload_on_sighup: bool,
 }
 
 /// HTTP transport options for the Serve command
@@ -193,8 +340,17 @@ impl From<&HttpTransportFlags> for Transport {
 pub enum ComponentCommands {
     /// Load a WebAssembly component from a file path or OCI registry.
     Load {
-        /// Path to the component (file:// or oci://)
+        /// Path to the component (file:// or oci://) or its name in the component registry
         path: String,
+        /// Load the component under this id instead of one derived from its file name
+        #[arg(long)]
+        id: Option<String>,
+        /// Replace a component with the same id that was loaded from a different source
+        #[arg(long)]
+        force: bool,
+        /// Compile the component instead of using the precompiled cache, and rewrite the cache
+        #[arg(long)]
+        no_cache: bool,
         /// Directory where components are stored. Defaults to $XDG_DATA_HOME/wassette/components
         #[arg(long)]
         component_dir: Option<PathBuf>,
@@ -203,12 +359,159 @@ pub enum ComponentCommands {
     Unload {
         /// Component ID to unload
         id: String,
+        /// Exit successfully when the component is not loaded
+        #[arg(long)]
+        ignore_missing: bool,
+        /// Directory where components are stored. Defaults to $XDG_DATA_HOME/wassette/components
+        #[arg(long)]
+        component_dir: Option<PathBuf>,
+    },
+    /// Give an installed component a new id, keeping its policy, permissions, and cache.
+    Rename {
+        /// Current component ID
+        id: String,
+        /// New component ID
+        new_id: String,
         /// Directory where components are stored. Defaults to $XDG_DATA_HOME/wassette/components
         #[arg(long)]
         component_dir: Option<PathBuf>,
     },
     /// List all loaded components.
     List {
+        /// Directory where components are stored. Defaults to $XDG_DATA_HOME/wassette/components
+        #[arg(long)]
+        component_dir: Option<PathBuf>,
+        /// Compile every installed component, without running its hooks or granting its
+        /// permissions, and list the ones that fail with the error for each
+        #[arg(long)]
+        show_failed: bool,
+        /// Output format
+        #[arg(short = 'o', long = "output-format", default_value = "json")]
+        output_format: OutputFormat,
+    },
+    /// Show every component with its tools and granted permissions nested under it.
+    Tree {
+        /// Directory where components are stored. Defaults to $XDG_DATA_HOME/wassette/components
+        #[arg(long)]
+        component_dir: Option<PathBuf>,
+        /// Print the tree as structured data in this format instead of as indented text
+        #[arg(short = 'o', long = "output-format")]
+        output_format: Option<OutputFormat>,
+    },
+    /// Run a component's health check. Exits non-zero when the component reports unhealthy.
+    Health {
+        /// Component ID to check
+        id: String,
+        /// Directory where components are stored. Defaults to $XDG_DATA_HOME/wassette/components
+        #[arg(long)]
+        component_dir: Option<PathBuf>,
+        /// Output format
+        #[arg(short = 'o', long = "output-format", default_value = "json")]
+        output_format: OutputFormat,
+    },
+    /// Show stdout and stderr output recently captured from a component.
+    Logs {
+        /// Component ID to show output for
+        id: String,
+        /// Only show the last N lines
+        #[arg(long)]
+        tail: Option<usize>,
+        /// Keep printing new lines as the component writes them, until interrupted
+        #[arg(short = 'f', long)]
+        follow: bool,
+        /// Directory where components are stored. Defaults to $XDG_DATA_HOME/wassette/components
+        #[arg(long)]
+        component_dir: Option<PathBuf>,
+    },
+    /// Manage non-secret environment variables injected into a component.
+    Env {
+        #[command(subcommand)]
+        command: ComponentEnvCommands,
+    },
+    /// Compare an installed component with a new version without installing it.
+    Diff {
+        /// Component ID of the installed version
+        component_id: String,
+        /// URI of the new version (file://, oci://, or https://)
+        new_uri: String,
+        /// Directory where components are stored. Defaults to $XDG_DATA_HOME/wassette/components
+        #[arg(long)]
+        component_dir: Option<PathBuf>,
+        /// Output format
+        #[arg(short = 'o', long = "output-format", default_value = "json")]
+        output_format: OutputFormat,
+    },
+    /// Check that a component compiles and that the host provides every interface it imports,
+    /// without installing it. Exits non-zero when an import is unsatisfied.
+    Verify {
+        /// URI of the component (file://, oci://, or https://)
+        uri: String,
+        /// Directory where components are stored. Defaults to $XDG_DATA_HOME/wassette/components
+        #[arg(long)]
+        component_dir: Option<PathBuf>,
+        /// Output format
+        #[arg(short = 'o', long = "output-format", default_value = "json")]
+        output_format: OutputFormat,
+    },
+    /// Check a component's granted permissions against the capabilities it imports.
+    Audit {
+        /// Component ID to audit
+        id: String,
+        /// Grant the permissions the component is missing, asking before each grant, then
+        /// report the audit again
+        #[arg(long)]
+        fix: bool,
+        /// With --fix, grant the rules the component declares without asking and skip
+        /// missing capabilities it declares no rule for
+        #[arg(long, requires = "fix")]
+        yes: bool,
+        /// Directory where components are stored. Defaults to $XDG_DATA_HOME/wassette/components
+        #[arg(long)]
+        component_dir: Option<PathBuf>,
+        /// Output format
+        #[arg(short = 'o', long = "output-format", default_value = "json")]
+        output_format: OutputFormat,
+    },
+    /// Bundle an installed component and its policy into a .tar.gz that `component load` accepts.
+    Export {
+        /// Component ID to export
+        id: String,
+        /// Path of the bundle to write, e.g. fetch.tar.gz
+        output: PathBuf,
+        /// Directory where components are stored. Defaults to $XDG_DATA_HOME/wassette/components
+        #[arg(long)]
+        component_dir: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ComponentEnvCommands {
+    /// Set environment variables for a component.
+    Set {
+        /// Component ID to set environment variables for
+        component_id: String,
+        /// Variables in KEY=VALUE format. Can be specified multiple times.
+        #[arg(value_parser = crate::parse_env_var, required = true)]
+        vars: Vec<(String, String)>,
+        /// Directory where components are stored. Defaults to $XDG_DATA_HOME/wassette/components
+        #[arg(long)]
+        component_dir: Option<PathBuf>,
+    },
+    /// Remove environment variables from a component.
+    Unset {
+        /// Component ID to remove environment variables from
+        component_id: String,
+        /// Variable names to remove
+        #[arg(required = true)]
+        keys: Vec<String>,
+        /// Directory where components are stored. Defaults to $XDG_DATA_HOME/wassette/components
+        #[arg(long)]
+        component_dir: Option<PathBuf>,
+    },
+    /// List environment variables configured for a component.
+    List {
+        /// Component ID to list environment variables for
+        component_id: String,
         /// Directory where components are stored. Defaults to $XDG_DATA_HOME/wassette/components
         #[arg(long)]
         component_dir: Option<PathBuf>,
@@ -224,6 +527,10 @@ pub enum PolicyCommands {
     Get {
         /// Component ID to get policy for
         component_id: String,
+        /// Also print the effective policy: the component's policy merged with the
+        /// configured default policy
+        #[arg(long, alias = "print-effective-policy")]
+        effective: bool,
         /// Directory where components are stored. Defaults to $XDG_DATA_HOME/wassette/components
         #[arg(long)]
         component_dir: Option<PathBuf>,
@@ -253,6 +560,56 @@ pub enum PermissionCommands {
         #[arg(long)]
         component_dir: Option<PathBuf>,
     },
+    /// Show whether a component's policy would allow an access, and which rule decides it.
+    #[command(after_help = "EXAMPLES:
+    # Would a request to api.example.com be allowed?
+    wassette permission explain my-component network api.example.com
+
+    # Could the component write below /tmp/output?
+    wassette permission explain my-component storage fs:///tmp/output/report.json --access write
+
+    # Would the component see AWS_REGION?
+    wassette permission explain my-component environment-variable AWS_REGION")]
+    Explain {
+        /// Component ID whose policy to consult
+        component_id: String,
+        #[command(subcommand)]
+        target: ExplainPermissionCommands,
+        /// Directory where components are stored. Defaults to $XDG_DATA_HOME/wassette/components
+        #[arg(long, global = true)]
+        component_dir: Option<PathBuf>,
+        /// Output format
+        #[arg(
+            short = 'o',
+            long = "output-format",
+            default_value = "json",
+            global = true
+        )]
+        output_format: OutputFormat,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ExplainPermissionCommands {
+    /// Explain an outbound request to a host.
+    Network {
+        /// Host, optionally with a scheme (e.g., api.example.com or https://api.example.com)
+        host: String,
+    },
+    /// Explain access to a file or directory.
+    Storage {
+        /// URI of the storage resource (e.g., fs:///path/to/file)
+        uri: String,
+        /// Access to check (read, write, or read,write). Defaults to read
+        #[arg(long, value_delimiter = ',')]
+        access: Vec<String>,
+    },
+    /// Explain reading an environment variable.
+    #[command(name = "environment-variable")]
+    EnvironmentVariable {
+        /// Environment variable key
+        key: String,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -309,12 +666,15 @@ pub enum GrantPermissionCommands {
     wassette permission grant environment-variable my-component CONFIG_URL
 
     # Grant access to a database connection string
-    wassette permission grant environment-variable my-component DATABASE_URL"
+    wassette permission grant environment-variable my-component DATABASE_URL
+
+    # Grant access to every variable starting with AWS_
+    wassette permission grant environment-variable my-component 'AWS_*'"
     )]
     EnvironmentVariable {
         /// Component ID to grant permission to
         component_id: String,
-        /// Environment variable key
+        /// Environment variable key, or a prefix ending in `*` to match a family (e.g. AWS_*)
         key: String,
         /// Directory where components are stored. Defaults to $XDG_DATA_HOME/wassette/components
         #[arg(long)]
@@ -402,6 +762,12 @@ pub enum SecretCommands {
         /// Secrets in KEY=VALUE format. Can be specified multiple times.
         #[arg(value_parser = crate::parse_env_var)]
         secrets: Vec<(String, String)>,
+        /// Read secrets from a file (supports .env format)
+        #[arg(long = "from-file")]
+        from_file: Option<PathBuf>,
+        /// Read the value of KEY from the current environment. Can be specified multiple times.
+        #[arg(long = "from-env", value_name = "KEY")]
+        from_env: Vec<String>,
         /// Directory where components are stored. Defaults to $XDG_DATA_HOME/wassette/components
         #[arg(long)]
         component_dir: Option<PathBuf>,
@@ -416,6 +782,31 @@ pub enum SecretCommands {
         #[arg(long)]
         component_dir: Option<PathBuf>,
     },
+    /// Export a component's secrets to a portable bundle (reveals values, prompts for confirmation).
+    Export {
+        /// Component ID to export secrets for
+        component_id: String,
+        /// File to write the bundle to. Prints the bundle to stdout when omitted.
+        #[arg(long, short = 'f')]
+        output: Option<PathBuf>,
+        /// Skip confirmation prompt
+        #[arg(long)]
+        yes: bool,
+        /// Directory where components are stored. Defaults to $XDG_DATA_HOME/wassette/components
+        #[arg(long)]
+        component_dir: Option<PathBuf>,
+    },
+    /// Import secrets from a bundle created by `secret export`.
+    Import {
+        /// Path to the secrets bundle file
+        file: PathBuf,
+        /// Import into this component instead of the one recorded in the bundle
+        #[arg(long)]
+        component_id: Option<String>,
+        /// Directory where components are stored. Defaults to $XDG_DATA_HOME/wassette/components
+        #[arg(long)]
+        component_dir: Option<PathBuf>,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -436,6 +827,9 @@ pub enum ToolCommands {
         /// Directory where components are stored. Defaults to $XDG_DATA_HOME/wassette/components
         #[arg(long)]
         component_dir: Option<PathBuf>,
+        /// Inline `$ref`s into `definitions`/`$defs` so the full shape of each schema is shown
+        #[arg(long)]
+        deref: bool,
         /// Output format
         #[arg(short = 'o', long = "output-format", default_value = "json")]
         output_format: OutputFormat,
@@ -447,6 +841,15 @@ pub enum ToolCommands {
         /// Arguments in JSON format (e.g., '{"key": "value"}')
         #[arg(long)]
         args: Option<String>,
+        /// Invoke a component tool this many times in a row
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+        repeat: u32,
+        /// Report per-call and aggregate durations instead of the tool output
+        #[arg(long)]
+        timing: bool,
+        /// Stop repeating after the first call that fails
+        #[arg(long)]
+        stop_on_error: bool,
         /// Directory where components are stored. Defaults to $XDG_DATA_HOME/wassette/components
         #[arg(long)]
         component_dir: Option<PathBuf>,
@@ -456,6 +859,20 @@ pub enum ToolCommands {
     },
 }
 
+#[derive(Subcommand, Debug)]
+pub enum ManifestCommands {
+    /// Print the JSON Schema for provisioning manifests (for editor and CI validation).
+    Schema,
+    /// Validate a provisioning manifest and report every problem with the path of its field.
+    Validate {
+        /// Path to the manifest file
+        manifest: PathBuf,
+        /// Output format. `json` and `yaml` list each issue with a JSON pointer to its field.
+        #[arg(short = 'o', long = "output-format", default_value = "table")]
+        output_format: OutputFormat,
+    },
+}
+
 #[derive(Subcommand, Debug)]
 pub enum RegistryCommands {
     /// Search for components in the registry.
@@ -465,6 +882,10 @@ pub enum RegistryCommands {
         /// Output format
         #[arg(short = 'o', long = "output-format", default_value = "json")]
         output_format: OutputFormat,
+        /// Read the registry index from this URL or file instead of the bundled one. The index
+        /// is cached locally after the first fetch; use `registry refresh` to update it.
+        #[arg(long)]
+        registry_url: Option<String>,
     },
     /// Fetch and load a component from the registry.
     Get {
@@ -473,5 +894,15 @@ pub enum RegistryCommands {
         /// Directory where plugins are stored. Defaults to $XDG_DATA_HOME/wassette/components
         #[arg(long)]
         plugin_dir: Option<PathBuf>,
+        /// Read the registry index from this URL or file instead of the bundled one. The index
+        /// is cached locally after the first fetch; use `registry refresh` to update it.
+        #[arg(long)]
+        registry_url: Option<String>,
+    },
+    /// Fetch the registry index again, replacing the locally cached copy.
+    Refresh {
+        /// URL or file to fetch the registry index from
+        #[arg(long)]
+        registry_url: String,
     },
 }
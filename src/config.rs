@@ -23,6 +23,12 @@ pub fn get_secrets_dir() -> Result<PathBuf, anyhow::Error> {
     Ok(dir_strategy.config_dir().join("wassette").join("secrets"))
 }
 
+/// Get the directory caching the registry index fetched from `--registry-url`
+pub fn get_registry_cache_dir() -> Result<PathBuf, anyhow::Error> {
+    let dir_strategy = etcetera::choose_base_strategy().context("Unable to get home directory")?;
+    Ok(dir_strategy.cache_dir().join("wassette"))
+}
+
 fn default_component_dir() -> PathBuf {
     get_component_dir().unwrap_or_else(|_| {
         eprintln!("WARN: Unable to determine default component directory, using `components` directory in the current working directory");
@@ -37,6 +43,14 @@ fn default_secrets_dir() -> PathBuf {
     })
 }
 
+fn default_create_component_dir() -> bool {
+    true
+}
+
+fn default_redact_component_secrets() -> bool {
+    true
+}
+
 fn default_bind_address() -> String {
     // Default bind address using PORT and BIND_HOST environment variables (twelve-factor app compliance).
     // This is only used when bind_address is not set via CLI, config file, or other higher-precedence sources.
@@ -52,6 +66,12 @@ pub struct Config {
     #[serde(default = "default_component_dir")]
     pub component_dir: PathBuf,
 
+    /// Create `component_dir` when it does not exist. Turn off when the directory is mounted
+    /// or provisioned ahead of time, so that a wrong path fails startup instead of serving
+    /// from a new empty directory.
+    #[serde(default = "default_create_component_dir")]
+    pub create_component_dir: bool,
+
     /// Directory where secrets are stored
     #[serde(default = "default_secrets_dir")]
     pub secrets_dir: PathBuf,
@@ -64,6 +84,143 @@ pub struct Config {
     /// Configured via PORT and BIND_HOST environment variables or CLI/config file
     #[serde(default = "default_bind_address", rename = "bind_address")]
     pub bind_address: String,
+    /// Default time limit for tool calls, in milliseconds. Calls are not limited when unset.
+    #[serde(default)]
+    pub tool_timeout_ms: Option<u64>,
+
+    /// Per-tool time limits in milliseconds, keyed by tool name. Overrides `tool_timeout_ms`.
+    #[serde(default)]
+    pub tool_timeouts: HashMap<String, u64>,
+
+    /// Maximum size of the text returned by a single tool call, in bytes. Longer output is
+    /// truncated with a marker. Output is not limited when unset.
+    #[serde(default)]
+    pub max_tool_output_bytes: Option<usize>,
+
+    /// Maximum number of components that may be loaded at once. Loading another component
+    /// fails once the limit is reached. Unlimited when unset.
+    #[serde(default)]
+    pub max_components: Option<usize>,
+
+    /// Budget for the memory limits declared by all loaded components, as a number of bytes
+    /// or with a `Ki`, `Mi`, `Gi` or `Ti` suffix. Loading a component whose declared limit
+    /// would exceed it fails. Unlimited when unset.
+    #[serde(default)]
+    pub max_loaded_memory: Option<String>,
+
+    /// Maximum number of concurrent streamable HTTP sessions. Requests that would open
+    /// another session are rejected with 503 until one is closed. Unlimited when unset.
+    #[serde(default)]
+    pub max_sessions: Option<usize>,
+
+    /// Seconds a streamable HTTP session may go without a request before it is closed and
+    /// its slot freed. 30 minutes when unset.
+    #[serde(default)]
+    pub session_idle_timeout_secs: Option<u64>,
+
+    /// PEM certificate chain for serving the HTTP transports over TLS. Requires `tls_key`.
+    #[serde(default)]
+    pub tls_cert: Option<PathBuf>,
+
+    /// PEM private key matching `tls_cert`
+    #[serde(default)]
+    pub tls_key: Option<PathBuf>,
+
+    /// `User-Agent` sent on outbound HTTP requests made by components, replacing their own
+    #[serde(default)]
+    pub outbound_user_agent: Option<String>,
+
+    /// Time limit for outbound HTTP requests made by components to connect, start responding
+    /// or deliver the next chunk, in milliseconds. Wasmtime's defaults apply when unset.
+    #[serde(default)]
+    pub outbound_request_timeout_ms: Option<u64>,
+
+    /// Let hosts granted to components by name resolve to loopback, private and link-local
+    /// addresses. Such requests are refused by default to stop DNS rebinding.
+    #[serde(default)]
+    pub outbound_allow_internal_addresses: bool,
+
+    /// Whether a component that fails to load at startup is skipped (`continue`) or stops
+    /// loading and the server from starting (`fail_fast`)
+    #[serde(default)]
+    pub startup_load_policy: wassette::StartupLoadPolicy,
+
+    /// Whether components are compiled quickly (`fast`) or into optimized code (`optimized`).
+    /// Set with `WASSETTE_COMPILE_OPT` in the environment.
+    #[serde(default)]
+    pub compile_opt: wassette::CompilationStrategy,
+
+    /// Bytes of captured component stdout and stderr kept in memory across all components.
+    /// The oldest lines are dropped first once the budget is exceeded. Unlimited when unset.
+    #[serde(default)]
+    pub max_total_log_bytes: Option<usize>,
+
+    /// Bytes of HTTP responses a single tool call may receive before it is aborted.
+    /// Unlimited when unset.
+    #[serde(default)]
+    pub egress_byte_limit: Option<u64>,
+
+    /// Which component runs a tool that several components export: `error`, `first_loaded`,
+    /// `last_loaded`, or `require_namespace` to only accept `<component>__<tool>` names
+    #[serde(default)]
+    pub duplicate_tool_policy: wassette::DuplicateToolPolicy,
+
+    /// How ids are derived for components loaded from OCI references: `flatten`
+    /// (`acme/tool` becomes `acme_tool`) or `full_path` (`acme..tool`), which keeps
+    /// repositories such as `acme/tool` and `acme_tool` apart
+    #[serde(default)]
+    pub oci_component_ids: wassette::OciComponentIds,
+
+    /// Maximum number of tool calls that run at once. Unlimited when unset.
+    #[serde(default)]
+    pub call_queue_depth: Option<usize>,
+
+    /// Reject tool calls with a "server busy" error instead of queueing them once
+    /// `call_queue_depth` calls are running
+    #[serde(default)]
+    pub call_queue_reject_when_full: bool,
+
+    /// Reject a tool call with a "server busy" error once it has waited this many
+    /// milliseconds for a free slot. Defaults to 60 seconds.
+    #[serde(default)]
+    pub call_queue_max_wait_ms: Option<u64>,
+
+    /// Text added after the default server instructions sent to MCP clients. Set with
+    /// `WASSETTE_INSTRUCTIONS_APPEND` in the environment.
+    #[serde(default)]
+    pub instructions_append: Option<String>,
+
+    /// Bearer token required by the `/admin` HTTP endpoints, which are only served when it
+    /// is set. Set with `WASSETTE_ADMIN_TOKEN` in the environment.
+    #[serde(default)]
+    pub admin_token: Option<String>,
+
+    /// Policy file whose permissions every component gets on top of its own policy. Set
+    /// with `WASSETTE_DEFAULT_POLICY` in the environment.
+    #[serde(default)]
+    pub default_policy: Option<PathBuf>,
+
+    /// Advertise component tools as `<component id>__<tool>` so that clients serving several
+    /// tenants can tell which component each tool belongs to. Set with
+    /// `WASSETTE_PREFIX_COMPONENT_TOOLS` in the environment.
+    #[serde(default)]
+    pub prefix_component_tools: bool,
+
+    /// Tag every component tool in `tools/list` with its component id as the `group` entry of
+    /// the tool's `_meta`, for clients that group tools in their UI. Set with
+    /// `WASSETTE_GROUP_COMPONENT_TOOLS` in the environment.
+    #[serde(default)]
+    pub group_component_tools: bool,
+
+    /// Also log every line components write to stderr, tagged with the component id and the
+    /// function called. Set with `WASSETTE_TRACE_COMPONENT_STDERR` in the environment.
+    #[serde(default)]
+    pub trace_component_stderr: bool,
+
+    /// Replace a component's secret values with `***` in the errors and output of its tool calls.
+    /// On by default; set `WASSETTE_REDACT_COMPONENT_SECRETS=false` to turn it off.
+    #[serde(default = "default_redact_component_secrets")]
+    pub redact_component_secrets: bool,
 }
 
 impl Config {
@@ -168,6 +325,17 @@ impl Config {
 
         Ok(config)
     }
+
+    /// Outbound HTTP settings for components, as passed to the lifecycle manager
+    pub fn outbound_http(&self) -> wassette::OutboundHttpConfig {
+        wassette::OutboundHttpConfig {
+            user_agent: self.outbound_user_agent.clone(),
+            request_timeout: self
+                .outbound_request_timeout_ms
+                .map(std::time::Duration::from_millis),
+            allow_internal_addresses: self.outbound_allow_internal_addresses,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -186,6 +354,12 @@ mod tests {
             env_vars: vec![],
             env_file: None,
             disable_builtin_tools: false,
+            strict_policy: false,
+            strict_security: false,
+            safe_mode: false,
+            startup_load_policy: None,
+            protocol_log: None,
+            record_calls: None,
         }
     }
 
@@ -196,6 +370,12 @@ mod tests {
             env_vars: vec![],
             env_file: None,
             disable_builtin_tools: false,
+            strict_policy: false,
+            strict_security: false,
+            safe_mode: false,
+            startup_load_policy: None,
+            protocol_log: None,
+            record_calls: None,
         }
     }
 
@@ -206,8 +386,21 @@ mod tests {
             env_vars: vec![],
             env_file: None,
             disable_builtin_tools: false,
+            strict_policy: false,
+            strict_security: false,
+            safe_mode: false,
             bind_address: None,
             manifest: None,
+            manifest_dir: None,
+            tls_cert: None,
+            tls_key: None,
+            startup_load_policy: None,
+            max_sessions: None,
+            session_idle_timeout_secs: None,
+            max_loaded_memory: None,
+            protocol_log: None,
+            record_calls: None,
+            reload_on_sighup: false,
         }
     }
 
@@ -218,8 +411,21 @@ mod tests {
             env_vars: vec![],
             env_file: None,
             disable_builtin_tools: false,
+            strict_policy: false,
+            strict_security: false,
+            safe_mode: false,
             bind_address: None,
             manifest: None,
+            manifest_dir: None,
+            tls_cert: None,
+            tls_key: None,
+            startup_load_policy: None,
+            max_sessions: None,
+            session_idle_timeout_secs: None,
+            max_loaded_memory: None,
+            protocol_log: None,
+            record_calls: None,
+            reload_on_sighup: false,
         }
     }
 
@@ -326,6 +532,173 @@ component_dir = "/config/component/dir"
         assert_eq!(config.component_dir, PathBuf::from("/config/component/dir"));
     }
 
+    #[test]
+    fn test_max_tool_output_bytes_from_config_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_file = temp_dir.path().join("config.toml");
+
+        fs::write(&config_file, "max_tool_output_bytes = 65536\n").unwrap();
+
+        let config = Config::new_from_path(&empty_test_cli_config(), &config_file)
+            .expect("Failed to create config");
+        assert_eq!(config.max_tool_output_bytes, Some(65536));
+
+        let config = Config::new_from_path(&empty_test_cli_config(), temp_dir.path().join("none"))
+            .expect("Failed to create config");
+        assert_eq!(config.max_tool_output_bytes, None);
+    }
+
+    #[test]
+    fn test_outbound_http_from_config_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_file = temp_dir.path().join("config.toml");
+
+        fs::write(
+            &config_file,
+            "outbound_user_agent = \"acme-agent/2.0\"\noutbound_request_timeout_ms = 1500\n\
+             outbound_allow_internal_addresses = true\n",
+        )
+        .unwrap();
+
+        let config = Config::new_from_path(&empty_test_cli_config(), &config_file)
+            .expect("Failed to create config");
+        let outbound = config.outbound_http();
+        assert_eq!(outbound.user_agent.as_deref(), Some("acme-agent/2.0"));
+        assert_eq!(
+            outbound.request_timeout,
+            Some(std::time::Duration::from_millis(1500))
+        );
+        assert!(outbound.allow_internal_addresses);
+    }
+
+    #[test]
+    fn test_max_components_from_config_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_file = temp_dir.path().join("config.toml");
+
+        fs::write(&config_file, "max_components = 5\n").unwrap();
+
+        let config = Config::new_from_path(&empty_test_cli_config(), &config_file)
+            .expect("Failed to create config");
+        assert_eq!(config.max_components, Some(5));
+
+        let config = Config::new_from_path(&empty_test_cli_config(), temp_dir.path().join("none"))
+            .expect("Failed to create config");
+        assert_eq!(config.max_components, None);
+    }
+
+    #[test]
+    fn test_startup_load_policy_from_config_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_file = temp_dir.path().join("config.toml");
+
+        fs::write(&config_file, "startup_load_policy = \"fail_fast\"\n").unwrap();
+
+        let config = Config::new_from_path(&empty_test_cli_config(), &config_file)
+            .expect("Failed to create config");
+        assert_eq!(
+            config.startup_load_policy,
+            wassette::StartupLoadPolicy::FailFast
+        );
+
+        let mut cli_config = empty_test_cli_config();
+        cli_config.startup_load_policy = Some(wassette::StartupLoadPolicy::Continue);
+        let config =
+            Config::new_from_path(&cli_config, &config_file).expect("Failed to create config");
+        assert_eq!(
+            config.startup_load_policy,
+            wassette::StartupLoadPolicy::Continue
+        );
+    }
+
+    #[test]
+    fn test_call_queue_from_config_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_file = temp_dir.path().join("config.toml");
+
+        fs::write(
+            &config_file,
+            "call_queue_depth = 8\ncall_queue_reject_when_full = true\ncall_queue_max_wait_ms = 500\n",
+        )
+        .unwrap();
+
+        let config = Config::new_from_path(&empty_test_cli_config(), &config_file)
+            .expect("Failed to create config");
+        assert_eq!(config.call_queue_depth, Some(8));
+        assert!(config.call_queue_reject_when_full);
+        assert_eq!(config.call_queue_max_wait_ms, Some(500));
+    }
+
+    #[test]
+    fn test_create_component_dir_from_config_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_file = temp_dir.path().join("config.toml");
+
+        let config = Config::new_from_path(&empty_test_cli_config(), &config_file)
+            .expect("Failed to create config");
+        assert!(config.create_component_dir);
+
+        fs::write(&config_file, "create_component_dir = false\n").unwrap();
+        let config = Config::new_from_path(&empty_test_cli_config(), &config_file)
+            .expect("Failed to create config");
+        assert!(!config.create_component_dir);
+    }
+
+    #[test]
+    fn test_instructions_append_from_env() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_file = temp_dir.path().join("config.toml");
+
+        temp_env::with_var(
+            "WASSETTE_INSTRUCTIONS_APPEND",
+            Some("Ask before granting network access."),
+            || {
+                let config = Config::new_from_path(&empty_test_cli_config(), &config_file)
+                    .expect("Failed to create config");
+                assert_eq!(
+                    config.instructions_append.as_deref(),
+                    Some("Ask before granting network access.")
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn test_compile_opt_from_config_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_file = temp_dir.path().join("config.toml");
+
+        let config = Config::new_from_path(&empty_test_cli_config(), &config_file)
+            .expect("Failed to create config");
+        assert_eq!(config.compile_opt, wassette::CompilationStrategy::Optimized);
+
+        fs::write(&config_file, "compile_opt = \"fast\"\n").unwrap();
+        let config = Config::new_from_path(&empty_test_cli_config(), &config_file)
+            .expect("Failed to create config");
+        assert_eq!(config.compile_opt, wassette::CompilationStrategy::Fast);
+    }
+
+    #[test]
+    fn test_tool_timeouts_from_config_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_file = temp_dir.path().join("config.toml");
+
+        let toml_content = r#"
+tool_timeout_ms = 2000
+
+[tool_timeouts]
+render = 60000
+"#;
+        fs::write(&config_file, toml_content).unwrap();
+
+        let config = Config::new_from_path(&empty_test_cli_config(), &config_file)
+            .expect("Failed to create config");
+
+        assert_eq!(config.tool_timeout_ms, Some(2000));
+        assert_eq!(config.tool_timeouts.get("render"), Some(&60000));
+        assert_eq!(config.tool_timeouts.len(), 1);
+    }
+
     #[test]
     fn test_new_method_without_wassette_config_file_env() {
         // This test verifies that new() works when WASSETTE_CONFIG_FILE is not set
@@ -430,8 +803,21 @@ bind_address = "0.0.0.0:8080"
             env_vars: vec![],
             env_file: None,
             disable_builtin_tools: false,
+            strict_policy: false,
+            strict_security: false,
+            safe_mode: false,
             bind_address: Some("192.168.1.100:9090".to_string()),
             manifest: None,
+            manifest_dir: None,
+            tls_cert: None,
+            tls_key: None,
+            startup_load_policy: None,
+            max_sessions: None,
+            session_idle_timeout_secs: None,
+            max_loaded_memory: None,
+            protocol_log: None,
+            record_calls: None,
+            reload_on_sighup: false,
         };
 
         let config =
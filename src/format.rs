@@ -3,7 +3,7 @@
 
 //! Output formatting utilities for CLI commands
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::ValueEnum;
 use rmcp::model::CallToolResult;
 use serde_json::{Map, Value};
@@ -17,6 +17,12 @@ pub enum OutputFormat {
     Yaml,
     /// Table format
     Table,
+    /// Comma-separated values (listing commands only)
+    Csv,
+    /// Tab-separated values (listing commands only)
+    Tsv,
+    /// The exact `tools/list` response an MCP client would receive (component list only)
+    Mcp,
 }
 
 impl Default for OutputFormat {
@@ -83,6 +89,83 @@ pub fn format_as_table(value: &Value) -> Result<String> {
     Ok(table)
 }
 
+/// Extract the header and rows of a listing that can be rendered as delimited text.
+///
+/// Only the component, registry, tool, and secret listings have a tabular shape;
+/// any other value returns `None`.
+fn tabular_rows(value: &Value) -> Option<(Vec<&'static str>, Vec<Vec<String>>)> {
+    let obj = value.as_object()?;
+
+    let (columns, items) = if let Some(items) = obj.get("components").and_then(|v| v.as_array()) {
+        // Registry search results share the `components` key but describe remote entries
+        if items.iter().any(|item| item.get("uri").is_some()) {
+            (vec!["name", "description", "uri"], items)
+        } else {
+            (vec!["id", "tools_count"], items)
+        }
+    } else if let Some(items) = obj.get("tools").and_then(|v| v.as_array()) {
+        (vec!["name", "description"], items)
+    } else if let Some(items) = obj.get("secrets").and_then(|v| v.as_array()) {
+        if items.iter().any(|item| item.get("value").is_some()) {
+            (vec!["key", "value"], items)
+        } else {
+            (vec!["key"], items)
+        }
+    } else {
+        return None;
+    };
+
+    let rows = items
+        .iter()
+        .map(|item| {
+            columns
+                .iter()
+                .map(|column| match item.get(*column) {
+                    None | Some(Value::Null) => String::new(),
+                    Some(Value::String(s)) => s.clone(),
+                    Some(other) => other.to_string(),
+                })
+                .collect()
+        })
+        .collect();
+
+    Some((columns, rows))
+}
+
+/// Format a listing as delimited text with a header row followed by one row per item.
+///
+/// Fields are quoted per RFC 4180 for CSV; for TSV, tabs and newlines inside a
+/// field are replaced with spaces.
+pub fn format_as_delimited(value: &Value, delimiter: char) -> Result<String> {
+    let Some((columns, rows)) = tabular_rows(value) else {
+        bail!(
+            "{} output is only supported for listing commands (component list, tool list, secret list, registry search)",
+            if delimiter == '\t' { "TSV" } else { "CSV" }
+        );
+    };
+
+    let escape = |field: &str| -> String {
+        if delimiter == '\t' {
+            field.replace(['\t', '\n', '\r'], " ")
+        } else if field.contains([delimiter, '"', '\n', '\r']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_string()
+        }
+    };
+
+    let separator = delimiter.to_string();
+    let mut output = columns.join(&separator);
+    output.push('\n');
+    for row in rows {
+        let fields: Vec<String> = row.iter().map(|field| escape(field.as_str())).collect();
+        output.push_str(&fields.join(&separator));
+        output.push('\n');
+    }
+
+    Ok(output)
+}
+
 /// Print the result of a tool call with the specified format
 pub fn print_result(result: &CallToolResult, output_format: OutputFormat) -> Result<()> {
     for content in &result.content {
@@ -103,6 +186,15 @@ pub fn print_result(result: &CallToolResult, output_format: OutputFormat) -> Res
                         // Format as table
                         println!("{}", format_as_table(&json_value)?);
                     }
+                    OutputFormat::Csv => {
+                        print!("{}", format_as_delimited(&json_value, ',')?);
+                    }
+                    OutputFormat::Tsv => {
+                        print!("{}", format_as_delimited(&json_value, '\t')?);
+                    }
+                    OutputFormat::Mcp => {
+                        bail!("MCP output is only supported by component list");
+                    }
                 }
             } else {
                 // If it's not JSON, just print the text
@@ -116,3 +208,105 @@ pub fn print_result(result: &CallToolResult, output_format: OutputFormat) -> Res
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_format_as_yaml_tool_listing_round_trips() {
+        let listing = json!({
+            "tools": [
+                {
+                    "name": "fetch",
+                    "description": "Fetch a URL",
+                    "input_schema": {
+                        "type": "object",
+                        "properties": {"url": {"type": "string"}},
+                        "required": ["url"]
+                    },
+                    "output_schema": null
+                },
+                {
+                    "name": "load-component",
+                    "description": "Dynamically loads a new tool or component",
+                    "input_schema": {"type": "object"},
+                    "output_schema": null
+                }
+            ]
+        });
+
+        let yaml = format_as_yaml(&listing).unwrap();
+        assert!(yaml.starts_with("tools:"));
+
+        let parsed: Value = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(parsed, listing);
+    }
+
+    #[test]
+    fn test_format_as_csv_component_listing() {
+        let listing = json!({
+            "components": [
+                {"id": "fetch-rs", "tools_count": 1, "schema": {"tools": []}},
+                {"id": "time-server-js", "tools_count": 2, "schema": {"tools": []}}
+            ],
+            "total": 2
+        });
+
+        let csv = format_as_delimited(&listing, ',').unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines,
+            vec!["id,tools_count", "fetch-rs,1", "time-server-js,2"]
+        );
+    }
+
+    #[test]
+    fn test_format_as_delimited_escapes_fields() {
+        let listing = json!({
+            "tools": [
+                {"name": "fetch", "description": "Fetch a URL, return \"body\""},
+                {"name": "echo", "description": "Echo\tinput"}
+            ]
+        });
+
+        let csv = format_as_delimited(&listing, ',').unwrap();
+        assert_eq!(
+            csv,
+            "name,description\nfetch,\"Fetch a URL, return \"\"body\"\"\"\necho,Echo\tinput\n"
+        );
+
+        let tsv = format_as_delimited(&listing, '\t').unwrap();
+        assert_eq!(
+            tsv,
+            "name\tdescription\nfetch\tFetch a URL, return \"body\"\necho\tEcho input\n"
+        );
+    }
+
+    #[test]
+    fn test_format_as_csv_secret_listing_columns() {
+        let keys_only = json!({"component_id": "c", "secrets": [{"key": "API_KEY"}]});
+        assert_eq!(
+            format_as_delimited(&keys_only, ',').unwrap(),
+            "key\nAPI_KEY\n"
+        );
+
+        let with_values = json!({
+            "component_id": "c",
+            "secrets": [{"key": "API_KEY", "value": "abc"}]
+        });
+        assert_eq!(
+            format_as_delimited(&with_values, ',').unwrap(),
+            "key,value\nAPI_KEY,abc\n"
+        );
+    }
+
+    #[test]
+    fn test_format_as_csv_rejects_non_tabular_output() {
+        let policy = json!({"component_id": "c", "policy_info": {"policy_id": "c-policy"}});
+        let error = format_as_delimited(&policy, ',').unwrap_err().to_string();
+        assert!(error.contains("CSV output is only supported for listing commands"));
+    }
+}
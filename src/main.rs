@@ -5,38 +5,67 @@
 
 #![warn(missing_docs)]
 
+use std::sync::Arc;
+use std::time::Duration;
+
 use anyhow::{bail, Context, Result};
 use clap::{CommandFactory, Parser};
 use clap_complete::{generate, shells};
-use mcp_server::{handle_tools_list, LifecycleManager};
+use mcp_server::{handle_tools_list, LifecycleManager, ToolListOptions};
 use rmcp::service::serve_server;
-use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp::transport::sse_server::SseServerConfig;
 use rmcp::transport::streamable_http_server::StreamableHttpService;
 use rmcp::transport::{stdio as stdio_transport, SseServer};
-use serde_json::{json, Map};
+use serde_json::{json, Map, Value};
+use tokio_util::sync::CancellationToken;
+use tracing::level_filters::LevelFilter;
 use tracing_subscriber::layer::SubscriberExt as _;
 use tracing_subscriber::util::SubscriberInitExt as _;
+use tracing_subscriber::EnvFilter;
+use wassette::StartupLoadPolicy;
 
+mod admin;
+mod call_recording;
 mod cli_handlers;
 mod commands;
 mod config;
 mod format;
 mod manifest;
 mod permission_synthesis;
+mod protocol_log;
 mod provisioning_controller;
 mod registry;
+#[cfg(unix)]
+mod reload;
+mod schema;
+mod selftest;
+mod session_limit;
+mod telemetry;
+#[cfg(test)]
+#[path = "../tests/common/answer.rs"]
+mod test_support;
+mod tls;
 mod tools;
+mod tree;
 mod utils;
 
-use cli_handlers::{create_lifecycle_manager, handle_tool_cli_command};
+use cli_handlers::{
+    check_components, create_lifecycle_manager, fix_permission_gaps, follow_component_logs,
+    handle_tool_cli_command, mcp_tools_list, print_log_line, timing_report, tool_list_options,
+};
 use commands::{
-    Cli, Commands, ComponentCommands, GrantPermissionCommands, PermissionCommands, PolicyCommands,
+    Cli, Commands, ComponentCommands, ComponentEnvCommands, ExplainPermissionCommands,
+    GrantPermissionCommands, ManifestCommands, PermissionCommands, PolicyCommands,
     RegistryCommands, RevokePermissionCommands, SecretCommands, Shell, ToolCommands, Transport,
 };
 use format::{print_result, OutputFormat};
-use mcp_server::McpServer;
+use mcp_server::components::handle_rename_component;
+use mcp_server::{LogLevelControl, McpServer, ToolTimeouts, DEFAULT_QUEUE_MAX_WAIT};
 use tools::ToolName;
-use utils::{format_build_info, load_component_registry, parse_env_var};
+use utils::{
+    collect_secrets, download_clients, format_build_info, load_component_registry,
+    load_default_policy, parse_env_var, parse_memory_budget,
+};
 
 // Health and info endpoint handlers
 mod endpoints {
@@ -64,6 +93,43 @@ mod endpoints {
             "build_info": build_info
         }))
     }
+
+    /// Metrics endpoint - returns tool call queue gauges and per-component network counters
+    /// in the Prometheus text format
+    pub async fn metrics(server: mcp_server::McpServer) -> String {
+        let stats = server.call_queue_stats();
+        let mut body = format!(
+            "# HELP wassette_call_queue_depth Tool calls currently running\n\
+             # TYPE wassette_call_queue_depth gauge\n\
+             wassette_call_queue_depth {}\n\
+             # HELP wassette_call_queue_waiting Tool calls waiting for a free slot\n\
+             # TYPE wassette_call_queue_waiting gauge\n\
+             wassette_call_queue_waiting {}\n",
+            stats.depth, stats.waiting
+        );
+        if let Some(capacity) = stats.capacity {
+            body.push_str(&format!(
+                "# HELP wassette_call_queue_capacity Maximum number of concurrent tool calls\n\
+                 # TYPE wassette_call_queue_capacity gauge\n\
+                 wassette_call_queue_capacity {capacity}\n"
+            ));
+        }
+
+        let component_stats = server.lifecycle_manager().all_component_stats();
+        if !component_stats.is_empty() {
+            body.push_str(
+                "# HELP wassette_component_egress_bytes_total HTTP response bytes received by component tool calls\n\
+                 # TYPE wassette_component_egress_bytes_total counter\n",
+            );
+            for (id, stats) in component_stats {
+                body.push_str(&format!(
+                    "wassette_component_egress_bytes_total{{component=\"{id}\"}} {}\n",
+                    stats.egress_bytes
+                ));
+            }
+        }
+        body
+    }
 }
 
 #[tokio::main]
@@ -80,15 +146,18 @@ async fn main() -> Result<()> {
         Some(command) => match command {
             Commands::Run(cfg) => {
                 // Configure logging - use stderr for stdio transport to avoid interfering with MCP protocol
-                let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| {
-                    "info,cranelift_codegen=warn,cranelift_entity=warn,cranelift_bforest=warn,cranelift_frontend=warn"
-                    .to_string()
-                    .into()
-                });
+                let env_filter = EnvFilter::try_from_default_env()
+                    .unwrap_or_else(|_| log_filter(LevelFilter::INFO));
+                let (env_filter, log_level_handle) =
+                    tracing_subscriber::reload::Layer::new(env_filter);
+
+                let (otel_layer, _telemetry_guard) = telemetry::otlp_layer()
+                    .context("Failed to configure OpenTelemetry export")?
+                    .unzip();
 
                 tracing_subscriber::registry()
                     .with(env_filter)
+                    .with(otel_layer)
                     .with(
                         tracing_subscriber::fmt::layer()
                             .with_writer(std::io::stderr)
@@ -98,54 +167,141 @@ async fn main() -> Result<()> {
 
                 let config =
                     config::Config::from_run(cfg).context("Failed to load configuration")?;
+                let outbound_http = config.outbound_http();
 
                 // Build the lifecycle manager without eagerly loading components so the
                 // background loader is the single source of tool registration.
                 let config::Config {
                     component_dir,
+                    create_component_dir,
                     secrets_dir,
                     environment_vars,
                     bind_address: _,
+                    tool_timeout_ms,
+                    tool_timeouts,
+                    max_tool_output_bytes,
+                    max_components,
+                    max_loaded_memory,
+                    max_sessions: _,
+                    session_idle_timeout_secs: _,
+                    tls_cert: _,
+                    tls_key: _,
+                    outbound_user_agent: _,
+                    outbound_request_timeout_ms: _,
+                    outbound_allow_internal_addresses: _,
+                    startup_load_policy,
+                    compile_opt,
+                    max_total_log_bytes,
+                    egress_byte_limit,
+                    duplicate_tool_policy,
+                    oci_component_ids,
+                    call_queue_depth,
+                    call_queue_reject_when_full,
+                    call_queue_max_wait_ms,
+                    instructions_append,
+                    admin_token: _,
+                    default_policy,
+                    prefix_component_tools,
+                    group_component_tools,
+                    trace_component_stderr,
+                    redact_component_secrets,
                 } = config;
 
+                let (oci_client, http_client) = download_clients()?;
                 let lifecycle_manager = LifecycleManager::builder(component_dir)
                     .with_environment_vars(environment_vars)
                     .with_secrets_dir(secrets_dir)
-                    .with_oci_client(oci_client::Client::default())
-                    .with_http_client(reqwest::Client::default())
+                    .with_oci_client(oci_client)
+                    .with_http_client(http_client)
                     .with_eager_loading(false)
+                    .with_create_component_dir(create_component_dir)
+                    .with_max_total_log_bytes(max_total_log_bytes)
+                    .with_egress_byte_limit(egress_byte_limit)
+                    .with_duplicate_tool_policy(duplicate_tool_policy)
+                    .with_oci_component_ids(oci_component_ids)
+                    .with_stderr_tracing(trace_component_stderr)
+                    .with_secret_redaction(redact_component_secrets)
+                    .with_strict_policy(cfg.strict_policy)
+                    .with_strict_security(cfg.strict_security)
+                    .with_safe_mode(cfg.safe_mode)
+                    .with_max_components(max_components)
+                    .with_max_loaded_memory(parse_memory_budget(max_loaded_memory.as_deref())?)
+                    .with_outbound_http(outbound_http)
+                    .with_compilation_strategy(compile_opt)
+                    .with_default_policy(load_default_policy(default_policy.as_deref())?)
                     .build()
                     .await?;
 
-                let server = McpServer::new(lifecycle_manager.clone(), cfg.disable_builtin_tools);
-
-                // Start background component loading
-                let server_clone = server.clone();
-                let lifecycle_manager_clone = lifecycle_manager.clone();
-                tokio::spawn(async move {
-                    let notify_fn = move || {
-                        // Notify clients when a new component is loaded (if peer is available)
-                        if let Some(peer) = server_clone.get_peer() {
-                            let peer_clone = peer.clone();
-                            tokio::spawn(async move {
-                                if let Err(e) = peer_clone.notify_tool_list_changed().await {
-                                    tracing::warn!("Failed to notify tool list changed: {}", e);
-                                }
-                            });
-                        }
-                    };
+                let mut server_builder = McpServer::builder(lifecycle_manager.clone())
+                    .with_transport(mcp_server::Transport::Stdio)
+                    .with_builtin_tools_disabled(cfg.disable_builtin_tools)
+                    .with_tool_timeouts(ToolTimeouts::from_millis(tool_timeout_ms, &tool_timeouts))
+                    .with_max_tool_output_bytes(max_tool_output_bytes)
+                    .with_call_queue_depth(call_queue_depth)
+                    .with_reject_when_full(call_queue_reject_when_full)
+                    .with_call_queue_max_wait(
+                        call_queue_max_wait_ms
+                            .map_or(DEFAULT_QUEUE_MAX_WAIT, Duration::from_millis),
+                    )
+                    .with_component_tool_prefixes(prefix_component_tools)
+                    .with_component_tool_groups(group_component_tools)
+                    .with_log_level_control(log_level_control(log_level_handle));
+                if let Some(extra) = instructions_append {
+                    server_builder = server_builder.with_instructions_appended(extra);
+                }
+                if let Some(dir) = &cfg.record_calls {
+                    let recorder = call_recording::CallRecorder::open(dir)?;
+                    tracing::info!("Recording component tool calls to {}", dir.display());
+                    server_builder = server_builder.with_hooks(recorder);
+                }
+                let server = server_builder.build();
 
-                    if let Err(e) = lifecycle_manager_clone
-                        .load_existing_components_async(None, Some(notify_fn))
+                if startup_load_policy == StartupLoadPolicy::FailFast {
+                    // Refuse to serve a partial tool set: load everything before starting.
+                    lifecycle_manager
+                        .load_existing_components_async(None, None::<fn()>, startup_load_policy)
                         .await
-                    {
-                        tracing::error!("Background component loading failed: {}", e);
-                    }
-                });
+                        .context("Startup component loading failed")?;
+                } else {
+                    // Start background component loading
+                    let server_clone = server.clone();
+                    let lifecycle_manager_clone = lifecycle_manager.clone();
+                    tokio::spawn(async move {
+                        let notify_fn = move || {
+                            // Notify clients when a new component is loaded (if peer is available)
+                            if let Some(peer) = server_clone.get_peer() {
+                                let peer_clone = peer.clone();
+                                tokio::spawn(async move {
+                                    if let Err(e) = peer_clone.notify_tool_list_changed().await {
+                                        tracing::warn!("Failed to notify tool list changed: {}", e);
+                                    }
+                                });
+                            }
+                        };
+
+                        if let Err(e) = lifecycle_manager_clone
+                            .load_existing_components_async(
+                                None,
+                                Some(notify_fn),
+                                startup_load_policy,
+                            )
+                            .await
+                        {
+                            tracing::error!("Background component loading failed: {}", e);
+                        }
+                    });
+                }
 
                 tracing::info!("Starting MCP server with stdio transport. Components will load in the background.");
-                let transport = stdio_transport();
-                let running_service = serve_server(server, transport).await?;
+                let running_service = match &cfg.protocol_log {
+                    Some(path) => {
+                        let log = protocol_log::ProtocolLog::open(path)?;
+                        tracing::info!("Recording MCP protocol traffic to {}", path.display());
+                        serve_server(server, protocol_log::record_stdio(&log, stdio_transport()))
+                            .await?
+                    }
+                    None => serve_server(server, stdio_transport()).await?,
+                };
 
                 tokio::signal::ctrl_c().await?;
                 let _ = running_service.cancel().await;
@@ -154,25 +310,39 @@ async fn main() -> Result<()> {
             }
             Commands::Serve(cfg) => {
                 // Configure logging for HTTP-based transports
-                let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| {
-                    "info,cranelift_codegen=warn,cranelift_entity=warn,cranelift_bforest=warn,cranelift_frontend=warn"
-                    .to_string()
-                    .into()
-                });
+                let env_filter = EnvFilter::try_from_default_env()
+                    .unwrap_or_else(|_| log_filter(LevelFilter::INFO));
+                let (env_filter, log_level_handle) =
+                    tracing_subscriber::reload::Layer::new(env_filter);
+
+                let (otel_layer, _telemetry_guard) = telemetry::otlp_layer()
+                    .context("Failed to configure OpenTelemetry export")?
+                    .unzip();
 
                 tracing_subscriber::registry()
                     .with(env_filter)
+                    .with(otel_layer)
                     .with(tracing_subscriber::fmt::layer())
                     .init();
 
                 let config =
                     config::Config::from_serve(cfg).context("Failed to load configuration")?;
+                let outbound_http = config.outbound_http();
 
                 // Parse and validate manifest if provided
-                let manifest = if let Some(manifest_path) = &cfg.manifest {
-                    let m = manifest::ProvisioningManifest::from_file(manifest_path)
-                        .context("Failed to parse provisioning manifest")?;
+                let manifest_source = match (&cfg.manifest, &cfg.manifest_dir) {
+                    (Some(manifest_path), _) => Some((
+                        manifest::ProvisioningManifest::from_file(manifest_path),
+                        manifest_path,
+                    )),
+                    (None, Some(manifest_dir)) => Some((
+                        manifest::ProvisioningManifest::from_dir(manifest_dir),
+                        manifest_dir,
+                    )),
+                    (None, None) => None,
+                };
+                let manifest = if let Some((parsed, manifest_path)) = manifest_source {
+                    let m = parsed.context("Failed to parse provisioning manifest")?;
 
                     tracing::info!(
                         "Validating provisioning manifest from: {}",
@@ -193,20 +363,73 @@ async fn main() -> Result<()> {
                 // background loader is the single source of tool registration.
                 let config::Config {
                     component_dir,
+                    create_component_dir,
                     secrets_dir,
                     environment_vars,
                     bind_address,
+                    tool_timeout_ms,
+                    tool_timeouts,
+                    max_tool_output_bytes,
+                    max_components,
+                    max_loaded_memory,
+                    max_sessions,
+                    session_idle_timeout_secs,
+                    tls_cert,
+                    tls_key,
+                    outbound_user_agent: _,
+                    outbound_request_timeout_ms: _,
+                    outbound_allow_internal_addresses: _,
+                    startup_load_policy,
+                    compile_opt,
+                    max_total_log_bytes,
+                    egress_byte_limit,
+                    duplicate_tool_policy,
+                    oci_component_ids,
+                    call_queue_depth,
+                    call_queue_reject_when_full,
+                    call_queue_max_wait_ms,
+                    instructions_append,
+                    admin_token,
+                    default_policy,
+                    prefix_component_tools,
+                    group_component_tools,
+                    trace_component_stderr,
+                    redact_component_secrets,
                 } = config;
 
+                let tls_config = tls::server_config(tls_cert.as_deref(), tls_key.as_deref())
+                    .context("Failed to configure TLS")?;
+                let scheme = if tls_config.is_some() {
+                    "https"
+                } else {
+                    "http"
+                };
+
                 // Keep a clone of component_dir for provisioning
                 let component_dir_path = component_dir.clone();
 
+                let (oci_client, http_client) = download_clients()?;
                 let lifecycle_manager = LifecycleManager::builder(component_dir)
                     .with_environment_vars(environment_vars)
                     .with_secrets_dir(secrets_dir)
-                    .with_oci_client(oci_client::Client::default())
-                    .with_http_client(reqwest::Client::default())
+                    .with_oci_client(oci_client)
+                    .with_http_client(http_client)
                     .with_eager_loading(false)
+                    .with_create_component_dir(create_component_dir)
+                    .with_max_total_log_bytes(max_total_log_bytes)
+                    .with_egress_byte_limit(egress_byte_limit)
+                    .with_duplicate_tool_policy(duplicate_tool_policy)
+                    .with_oci_component_ids(oci_component_ids)
+                    .with_stderr_tracing(trace_component_stderr)
+                    .with_secret_redaction(redact_component_secrets)
+                    .with_strict_policy(cfg.strict_policy)
+                    .with_strict_security(cfg.strict_security)
+                    .with_safe_mode(cfg.safe_mode)
+                    .with_max_components(max_components)
+                    .with_max_loaded_memory(parse_memory_budget(max_loaded_memory.as_deref())?)
+                    .with_outbound_http(outbound_http)
+                    .with_compilation_strategy(compile_opt)
+                    .with_default_policy(load_default_policy(default_policy.as_deref())?)
                     .build()
                     .await?;
 
@@ -229,31 +452,77 @@ async fn main() -> Result<()> {
                     tracing::info!("All components provisioned successfully");
                 }
 
-                let server = McpServer::new(lifecycle_manager.clone(), cfg.disable_builtin_tools);
-
-                // Start background component loading
-                let server_clone = server.clone();
-                let lifecycle_manager_clone = lifecycle_manager.clone();
-                tokio::spawn(async move {
-                    let notify_fn = move || {
-                        // Notify clients when a new component is loaded (if peer is available)
-                        if let Some(peer) = server_clone.get_peer() {
-                            let peer_clone = peer.clone();
-                            tokio::spawn(async move {
-                                if let Err(e) = peer_clone.notify_tool_list_changed().await {
-                                    tracing::warn!("Failed to notify tool list changed: {}", e);
-                                }
-                            });
-                        }
-                    };
+                let mut server_builder = McpServer::builder(lifecycle_manager.clone())
+                    .with_transport(mcp_server::Transport::Http)
+                    .with_builtin_tools_disabled(cfg.disable_builtin_tools)
+                    .with_tool_timeouts(ToolTimeouts::from_millis(tool_timeout_ms, &tool_timeouts))
+                    .with_max_tool_output_bytes(max_tool_output_bytes)
+                    .with_call_queue_depth(call_queue_depth)
+                    .with_reject_when_full(call_queue_reject_when_full)
+                    .with_call_queue_max_wait(
+                        call_queue_max_wait_ms
+                            .map_or(DEFAULT_QUEUE_MAX_WAIT, Duration::from_millis),
+                    )
+                    .with_component_tool_prefixes(prefix_component_tools)
+                    .with_component_tool_groups(group_component_tools)
+                    .with_log_level_control(log_level_control(log_level_handle));
+                if let Some(extra) = instructions_append {
+                    server_builder = server_builder.with_instructions_appended(extra);
+                }
+                if let Some(dir) = &cfg.record_calls {
+                    let recorder = call_recording::CallRecorder::open(dir)?;
+                    tracing::info!("Recording component tool calls to {}", dir.display());
+                    server_builder = server_builder.with_hooks(recorder);
+                }
+                let server = server_builder.build();
 
-                    if let Err(e) = lifecycle_manager_clone
-                        .load_existing_components_async(None, Some(notify_fn))
+                if startup_load_policy == StartupLoadPolicy::FailFast {
+                    // Refuse to serve a partial tool set: load everything before starting.
+                    lifecycle_manager
+                        .load_existing_components_async(None, None::<fn()>, startup_load_policy)
                         .await
-                    {
-                        tracing::error!("Background component loading failed: {}", e);
-                    }
-                });
+                        .context("Startup component loading failed")?;
+                } else {
+                    // Start background component loading
+                    let server_clone = server.clone();
+                    let lifecycle_manager_clone = lifecycle_manager.clone();
+                    tokio::spawn(async move {
+                        let notify_fn = move || {
+                            // Notify clients when a new component is loaded (if peer is available)
+                            if let Some(peer) = server_clone.get_peer() {
+                                let peer_clone = peer.clone();
+                                tokio::spawn(async move {
+                                    if let Err(e) = peer_clone.notify_tool_list_changed().await {
+                                        tracing::warn!("Failed to notify tool list changed: {}", e);
+                                    }
+                                });
+                            }
+                        };
+
+                        if let Err(e) = lifecycle_manager_clone
+                            .load_existing_components_async(
+                                None,
+                                Some(notify_fn),
+                                startup_load_policy,
+                            )
+                            .await
+                        {
+                            tracing::error!("Background component loading failed: {}", e);
+                        }
+                    });
+                }
+
+                if cfg.reload_on_sighup {
+                    #[cfg(unix)]
+                    reload::spawn(
+                        cfg.clone(),
+                        component_dir_path.clone(),
+                        lifecycle_manager.clone(),
+                        server.clone(),
+                    )?;
+                    #[cfg(not(unix))]
+                    tracing::warn!("--reload-on-sighup is only supported on Unix; ignoring it");
+                }
 
                 let transport: Transport = (&cfg.transport).into();
                 match transport {
@@ -262,38 +531,98 @@ async fn main() -> Result<()> {
                         "Starting MCP server on {} with streamable HTTP transport. Components will load in the background.",
                         bind_address
                     );
+                        let metrics_server = server.clone();
+                        let admin_server = server.clone();
+                        let idle_timeout = session_idle_timeout_secs
+                            .map(Duration::from_secs)
+                            .unwrap_or(session_limit::DEFAULT_IDLE_TIMEOUT);
                         let service = StreamableHttpService::new(
                             move || Ok(server.clone()),
-                            LocalSessionManager::default().into(),
+                            Arc::new(session_limit::Sessions::new(max_sessions, idle_timeout)),
                             Default::default(),
                         );
 
-                        let router = axum::Router::new()
-                            .nest_service("/mcp", service)
+                        let mut router = axum::Router::new().nest_service("/mcp", service);
+                        if let Some(max_sessions) = max_sessions {
+                            tracing::info!("Allowing at most {max_sessions} concurrent sessions");
+                            router = router
+                                .layer(axum::middleware::from_fn(session_limit::refuse_when_full));
+                        }
+                        if let Some(path) = &cfg.protocol_log {
+                            let log = protocol_log::ProtocolLog::open(path)?;
+                            tracing::info!("Recording MCP protocol traffic to {}", path.display());
+                            router = router.layer(axum::middleware::from_fn_with_state(
+                                log,
+                                protocol_log::record_http_traffic,
+                            ));
+                        }
+                        let router = router
                             .route("/health", axum::routing::get(endpoints::health))
+                            .route("/healthz", axum::routing::get(endpoints::health))
                             .route("/ready", axum::routing::get(endpoints::ready))
-                            .route("/info", axum::routing::get(endpoints::info));
+                            .route("/info", axum::routing::get(endpoints::info))
+                            .route(
+                                "/metrics",
+                                axum::routing::get(move || endpoints::metrics(metrics_server)),
+                            );
+                        let admin_enabled = admin_token.is_some();
+                        let router = match admin_token {
+                            Some(token) => router.merge(admin::router(
+                                lifecycle_manager.clone(),
+                                admin_server,
+                                token,
+                            )),
+                            None => router,
+                        };
                         let tcp_listener = tokio::net::TcpListener::bind(&bind_address).await?;
 
                         // Spawn the server in a background task
                         let server_handle = tokio::spawn(async move {
-                            axum::serve(tcp_listener, router)
-                                .with_graceful_shutdown(async {
-                                    tokio::signal::ctrl_c().await.unwrap()
-                                })
-                                .await
+                            let shutdown = async { tokio::signal::ctrl_c().await.unwrap() };
+                            match tls_config {
+                                Some(tls_config) => {
+                                    tls::serve(tcp_listener, router, tls_config, shutdown).await
+                                }
+                                None => {
+                                    axum::serve(tcp_listener, router)
+                                        .with_graceful_shutdown(shutdown)
+                                        .await
+                                }
+                            }
                         });
 
                         tracing::info!(
-                            "MCP server is ready and listening on http://{}/mcp",
+                            "MCP server is ready and listening on {}://{}/mcp",
+                            scheme,
                             bind_address
                         );
-                        tracing::info!("Health check available at http://{}/health", bind_address);
                         tracing::info!(
-                            "Readiness check available at http://{}/ready",
+                            "Health check available at {}://{}/health",
+                            scheme,
                             bind_address
                         );
-                        tracing::info!("Build info available at http://{}/info", bind_address);
+                        tracing::info!(
+                            "Readiness check available at {}://{}/ready",
+                            scheme,
+                            bind_address
+                        );
+                        tracing::info!(
+                            "Build info available at {}://{}/info",
+                            scheme,
+                            bind_address
+                        );
+                        tracing::info!(
+                            "Metrics available at {}://{}/metrics",
+                            scheme,
+                            bind_address
+                        );
+                        if admin_enabled {
+                            tracing::info!(
+                                "Admin API available at {}://{}/admin/components",
+                                scheme,
+                                bind_address
+                            );
+                        }
 
                         // Wait for the server task to complete
                         let _ = server_handle.await;
@@ -304,16 +633,55 @@ async fn main() -> Result<()> {
                         bind_address
                     );
 
-                        let ct = SseServer::serve(bind_address.parse().unwrap())
-                            .await?
-                            .with_service(move || server.clone());
+                        if cfg.protocol_log.is_some() {
+                            bail!(
+                                "--protocol-log is not supported with the SSE transport; use --streamable-http to record protocol traffic"
+                            );
+                        }
+                        if admin_token.is_some() {
+                            tracing::warn!(
+                                "The admin API is not served with the SSE transport; use --streamable-http to enable it"
+                            );
+                        }
+                        if max_sessions.is_some() {
+                            bail!(
+                                "--max-sessions is not supported with the SSE transport; use --streamable-http to limit sessions"
+                            );
+                        }
+
+                        let ct = match tls_config {
+                            Some(tls_config) => {
+                                let bind = bind_address.parse()?;
+                                let (sse_server, router) = SseServer::new(SseServerConfig {
+                                    bind,
+                                    sse_path: "/sse".to_string(),
+                                    post_path: "/message".to_string(),
+                                    ct: CancellationToken::new(),
+                                    sse_keep_alive: None,
+                                });
+                                let tcp_listener = tokio::net::TcpListener::bind(bind).await?;
+                                let ct = sse_server.with_service(move || server.clone());
+                                let shutdown = ct.clone();
+                                tokio::spawn(tls::serve(
+                                    tcp_listener,
+                                    router,
+                                    tls_config,
+                                    async move { shutdown.cancelled().await },
+                                ));
+                                ct
+                            }
+                            None => SseServer::serve(bind_address.parse().unwrap())
+                                .await?
+                                .with_service(move || server.clone()),
+                        };
 
                         tracing::info!(
-                            "MCP server is ready and listening on http://{}/sse",
+                            "MCP server is ready and listening on {}://{}/sse",
+                            scheme,
                             bind_address
                         );
                         tracing::info!(
-                            "Note: Health endpoints (/health, /ready, /info) are only available with --streamable-http transport. \
+                            "Note: Health endpoints (/health, /healthz, /ready, /info, /metrics) are only available with --streamable-http transport. \
                             SSE transport is designed solely for event streaming and does not provide a general HTTP request/response interface."
                         );
 
@@ -327,12 +695,20 @@ async fn main() -> Result<()> {
             Commands::Component { command } => match command {
                 ComponentCommands::Load {
                     path,
+                    id,
+                    force,
+                    no_cache,
                     component_dir,
                 } => {
                     let component_dir = component_dir.clone().or_else(|| cli.component_dir.clone());
                     let lifecycle_manager = create_lifecycle_manager(component_dir).await?;
                     let mut args = Map::new();
                     args.insert("path".to_string(), json!(path));
+                    if let Some(id) = id {
+                        args.insert("id".to_string(), json!(id));
+                    }
+                    args.insert("force".to_string(), json!(force));
+                    args.insert("force_recompile".to_string(), json!(no_cache));
                     handle_tool_cli_command(
                         &lifecycle_manager,
                         "load-component",
@@ -341,11 +717,16 @@ async fn main() -> Result<()> {
                     )
                     .await?;
                 }
-                ComponentCommands::Unload { id, component_dir } => {
+                ComponentCommands::Unload {
+                    id,
+                    ignore_missing,
+                    component_dir,
+                } => {
                     let component_dir = component_dir.clone().or_else(|| cli.component_dir.clone());
                     let lifecycle_manager = create_lifecycle_manager(component_dir).await?;
                     let mut args = Map::new();
                     args.insert("id".to_string(), json!(id));
+                    args.insert("ignore_missing".to_string(), json!(ignore_missing));
                     handle_tool_cli_command(
                         &lifecycle_manager,
                         "unload-component",
@@ -354,25 +735,320 @@ async fn main() -> Result<()> {
                     )
                     .await?;
                 }
+                ComponentCommands::Rename {
+                    id,
+                    new_id,
+                    component_dir,
+                } => {
+                    let component_dir = component_dir.clone().or_else(|| cli.component_dir.clone());
+                    let lifecycle_manager = create_lifecycle_manager(component_dir).await?;
+                    let result =
+                        handle_rename_component(&lifecycle_manager, id, new_id, None).await?;
+                    print_result(&result, OutputFormat::Json)?;
+                    if result.is_error.unwrap_or(false) {
+                        std::process::exit(1);
+                    }
+                }
                 ComponentCommands::List {
                     component_dir,
+                    show_failed: true,
                     output_format,
                 } => {
                     let component_dir = component_dir.clone().or_else(|| cli.component_dir.clone());
                     let lifecycle_manager = create_lifecycle_manager(component_dir).await?;
-                    let args = Map::new();
-                    handle_tool_cli_command(
-                        &lifecycle_manager,
-                        "list-components",
-                        args,
+                    let failed = check_components(&lifecycle_manager).await?["failed"].take();
+
+                    print_result(
+                        &rmcp::model::CallToolResult {
+                            content: vec![rmcp::model::Content::text(
+                                serde_json::to_string_pretty(&json!({ "failed": failed }))?,
+                            )],
+                            structured_content: None,
+                            is_error: None,
+                            meta: None,
+                        },
                         *output_format,
-                    )
-                    .await?;
+                    )?;
+                }
+                ComponentCommands::List {
+                    component_dir,
+                    show_failed: false,
+                    output_format,
+                } => {
+                    let component_dir = component_dir.clone().or_else(|| cli.component_dir.clone());
+                    let lifecycle_manager = create_lifecycle_manager(component_dir).await?;
+                    if *output_format == OutputFormat::Mcp {
+                        let options = tool_list_options(&cli_handlers::load_config()?);
+                        println!("{}", mcp_tools_list(&lifecycle_manager, options).await?);
+                    } else {
+                        let args = Map::new();
+                        handle_tool_cli_command(
+                            &lifecycle_manager,
+                            "list-components",
+                            args,
+                            *output_format,
+                        )
+                        .await?;
+                    }
+                }
+                ComponentCommands::Tree {
+                    component_dir,
+                    output_format,
+                } => {
+                    let component_dir = component_dir.clone().or_else(|| cli.component_dir.clone());
+                    let lifecycle_manager = create_lifecycle_manager(component_dir).await?;
+                    let tree = tree::component_tree(&lifecycle_manager).await?;
+                    match output_format {
+                        Some(format) => print_result(
+                            &rmcp::model::CallToolResult {
+                                content: vec![rmcp::model::Content::text(
+                                    serde_json::to_string_pretty(&tree)?,
+                                )],
+                                structured_content: None,
+                                is_error: None,
+                                meta: None,
+                            },
+                            *format,
+                        )?,
+                        None => print!("{}", tree::render_tree(&tree)),
+                    }
+                }
+                ComponentCommands::Health {
+                    id,
+                    component_dir,
+                    output_format,
+                } => {
+                    let component_dir = component_dir.clone().or_else(|| cli.component_dir.clone());
+                    let lifecycle_manager = create_lifecycle_manager(component_dir).await?;
+                    let health = lifecycle_manager.check_component_health(id).await?;
+                    let mut report = serde_json::to_value(&health)?;
+                    report["component_id"] = json!(id);
+
+                    print_result(
+                        &rmcp::model::CallToolResult {
+                            content: vec![rmcp::model::Content::text(
+                                serde_json::to_string_pretty(&report)?,
+                            )],
+                            structured_content: None,
+                            is_error: None,
+                            meta: None,
+                        },
+                        *output_format,
+                    )?;
+                    if health.status == wassette::HealthStatus::Unhealthy {
+                        std::process::exit(1);
+                    }
+                }
+                ComponentCommands::Logs {
+                    id,
+                    tail,
+                    follow,
+                    component_dir,
+                } => {
+                    let component_dir = component_dir.clone().or_else(|| cli.component_dir.clone());
+                    let lifecycle_manager = create_lifecycle_manager(component_dir).await?;
+                    for line in lifecycle_manager.get_component_logs(id, *tail).await {
+                        print_log_line(&line);
+                    }
+                    if *follow {
+                        follow_component_logs(&lifecycle_manager.component_log_path(id)).await?;
+                    }
+                }
+                ComponentCommands::Env { command } => match command {
+                    ComponentEnvCommands::Set {
+                        component_id,
+                        vars,
+                        component_dir,
+                    } => {
+                        let component_dir =
+                            component_dir.clone().or_else(|| cli.component_dir.clone());
+                        let lifecycle_manager = create_lifecycle_manager(component_dir).await?;
+                        lifecycle_manager
+                            .set_component_env(component_id, vars)
+                            .await?;
+
+                        let result = json!({
+                            "status": "success",
+                            "component_id": component_id,
+                            "message": format!("Set {} environment variable(s) for component", vars.len())
+                        });
+
+                        print_result(
+                            &rmcp::model::CallToolResult {
+                                content: vec![rmcp::model::Content::text(
+                                    serde_json::to_string_pretty(&result)?,
+                                )],
+                                structured_content: None,
+                                is_error: None,
+                                meta: None,
+                            },
+                            OutputFormat::Json,
+                        )?;
+                    }
+                    ComponentEnvCommands::Unset {
+                        component_id,
+                        keys,
+                        component_dir,
+                    } => {
+                        let component_dir =
+                            component_dir.clone().or_else(|| cli.component_dir.clone());
+                        let lifecycle_manager = create_lifecycle_manager(component_dir).await?;
+                        lifecycle_manager
+                            .unset_component_env(component_id, keys)
+                            .await?;
+
+                        let result = json!({
+                            "status": "success",
+                            "component_id": component_id,
+                            "message": format!("Removed {} environment variable(s) from component", keys.len())
+                        });
+
+                        print_result(
+                            &rmcp::model::CallToolResult {
+                                content: vec![rmcp::model::Content::text(
+                                    serde_json::to_string_pretty(&result)?,
+                                )],
+                                structured_content: None,
+                                is_error: None,
+                                meta: None,
+                            },
+                            OutputFormat::Json,
+                        )?;
+                    }
+                    ComponentEnvCommands::List {
+                        component_id,
+                        component_dir,
+                        output_format,
+                    } => {
+                        let component_dir =
+                            component_dir.clone().or_else(|| cli.component_dir.clone());
+                        let lifecycle_manager = create_lifecycle_manager(component_dir).await?;
+                        let env = lifecycle_manager.get_component_env(component_id).await?;
+
+                        print_result(
+                            &rmcp::model::CallToolResult {
+                                content: vec![rmcp::model::Content::text(
+                                    serde_json::to_string_pretty(&json!({
+                                        "component_id": component_id,
+                                        "env": env
+                                    }))?,
+                                )],
+                                structured_content: None,
+                                is_error: None,
+                                meta: None,
+                            },
+                            *output_format,
+                        )?;
+                    }
+                },
+                ComponentCommands::Diff {
+                    component_id,
+                    new_uri,
+                    component_dir,
+                    output_format,
+                } => {
+                    let component_dir = component_dir.clone().or_else(|| cli.component_dir.clone());
+                    let lifecycle_manager = create_lifecycle_manager(component_dir).await?;
+                    let diff = lifecycle_manager
+                        .diff_component(component_id, new_uri)
+                        .await?;
+
+                    print_result(
+                        &rmcp::model::CallToolResult {
+                            content: vec![rmcp::model::Content::text(
+                                serde_json::to_string_pretty(&diff)?,
+                            )],
+                            structured_content: None,
+                            is_error: None,
+                            meta: None,
+                        },
+                        *output_format,
+                    )?;
+                }
+                ComponentCommands::Verify {
+                    uri,
+                    component_dir,
+                    output_format,
+                } => {
+                    let component_dir = component_dir.clone().or_else(|| cli.component_dir.clone());
+                    let lifecycle_manager = create_lifecycle_manager(component_dir).await?;
+                    let verification = lifecycle_manager.verify_component(uri).await?;
+
+                    print_result(
+                        &rmcp::model::CallToolResult {
+                            content: vec![rmcp::model::Content::text(
+                                serde_json::to_string_pretty(&verification)?,
+                            )],
+                            structured_content: None,
+                            is_error: None,
+                            meta: None,
+                        },
+                        *output_format,
+                    )?;
+                    if !verification.compatible {
+                        std::process::exit(1);
+                    }
+                }
+                ComponentCommands::Audit {
+                    id,
+                    fix,
+                    yes,
+                    component_dir,
+                    output_format,
+                } => {
+                    let component_dir = component_dir.clone().or_else(|| cli.component_dir.clone());
+                    let lifecycle_manager = create_lifecycle_manager(component_dir).await?;
+                    let mut audit = lifecycle_manager.audit_component_permissions(id).await?;
+
+                    if *fix {
+                        // Questions go to stderr so the report on stdout stays parseable
+                        let mut ask = |question: &str| -> Result<String> {
+                            eprint!("{question}");
+                            std::io::Write::flush(&mut std::io::stderr())?;
+                            let mut input = String::new();
+                            std::io::stdin().read_line(&mut input)?;
+                            Ok(input)
+                        };
+                        let prompt: Option<&mut dyn FnMut(&str) -> Result<String>> =
+                            if *yes { None } else { Some(&mut ask) };
+                        for permission in
+                            fix_permission_gaps(&lifecycle_manager, &audit, prompt).await?
+                        {
+                            eprintln!(
+                                "Granted {} permission {}",
+                                permission.permission_type, permission.details
+                            );
+                        }
+                        audit = lifecycle_manager.audit_component_permissions(id).await?;
+                    }
+
+                    print_result(
+                        &rmcp::model::CallToolResult {
+                            content: vec![rmcp::model::Content::text(
+                                serde_json::to_string_pretty(&audit)?,
+                            )],
+                            structured_content: None,
+                            is_error: None,
+                            meta: None,
+                        },
+                        *output_format,
+                    )?;
+                }
+                ComponentCommands::Export {
+                    id,
+                    output,
+                    component_dir,
+                } => {
+                    let component_dir = component_dir.clone().or_else(|| cli.component_dir.clone());
+                    let lifecycle_manager = create_lifecycle_manager(component_dir).await?;
+                    lifecycle_manager.export_component(id, output).await?;
+                    println!("Exported component '{id}' to {}", output.display());
                 }
             },
             Commands::Policy { command } => match command {
                 PolicyCommands::Get {
                     component_id,
+                    effective,
                     component_dir,
                     output_format,
                 } => {
@@ -380,6 +1056,7 @@ async fn main() -> Result<()> {
                     let lifecycle_manager = create_lifecycle_manager(component_dir).await?;
                     let mut args = Map::new();
                     args.insert("component_id".to_string(), json!(component_id));
+                    args.insert("effective".to_string(), json!(effective));
                     handle_tool_cli_command(&lifecycle_manager, "get-policy", args, *output_format)
                         .await?;
                 }
@@ -579,6 +1256,46 @@ async fn main() -> Result<()> {
                     )
                     .await?;
                 }
+                PermissionCommands::Explain {
+                    component_id,
+                    target,
+                    component_dir,
+                    output_format,
+                } => {
+                    let query = match target {
+                        ExplainPermissionCommands::Network { host } => {
+                            wassette::PermissionQuery::Network { host: host.clone() }
+                        }
+                        ExplainPermissionCommands::Storage { uri, access } => {
+                            wassette::PermissionQuery::Storage {
+                                uri: uri.clone(),
+                                access: serde_json::from_value(json!(access)).context(
+                                    "Invalid access type. Use read, write, or read,write",
+                                )?,
+                            }
+                        }
+                        ExplainPermissionCommands::EnvironmentVariable { key } => {
+                            wassette::PermissionQuery::EnvironmentVariable { key: key.clone() }
+                        }
+                    };
+                    let component_dir = component_dir.clone().or_else(|| cli.component_dir.clone());
+                    let lifecycle_manager = create_lifecycle_manager(component_dir).await?;
+                    let explanation = lifecycle_manager
+                        .explain_permission(component_id, &query)
+                        .await?;
+
+                    print_result(
+                        &rmcp::model::CallToolResult {
+                            content: vec![rmcp::model::Content::text(
+                                serde_json::to_string_pretty(&explanation)?,
+                            )],
+                            structured_content: None,
+                            is_error: None,
+                            meta: None,
+                        },
+                        *output_format,
+                    )?;
+                }
             },
             Commands::Secret { command } => match command {
                 SecretCommands::List {
@@ -641,11 +1358,14 @@ async fn main() -> Result<()> {
                 SecretCommands::Set {
                     component_id,
                     secrets,
+                    from_file,
+                    from_env,
                     component_dir,
                 } => {
+                    let secrets = collect_secrets(secrets, from_file.as_ref(), from_env)?;
                     let lifecycle_manager = create_lifecycle_manager(component_dir.clone()).await?;
                     lifecycle_manager
-                        .set_component_secrets(component_id, secrets)
+                        .set_component_secrets(component_id, &secrets)
                         .await?;
 
                     let result = json!({
@@ -682,6 +1402,92 @@ async fn main() -> Result<()> {
                         "message": format!("Deleted {} secret(s) from component", keys.len())
                     });
 
+                    print_result(
+                        &rmcp::model::CallToolResult {
+                            content: vec![rmcp::model::Content::text(
+                                serde_json::to_string_pretty(&result)?,
+                            )],
+                            structured_content: None,
+                            is_error: None,
+                            meta: None,
+                        },
+                        OutputFormat::Json,
+                    )?;
+                }
+                SecretCommands::Export {
+                    component_id,
+                    output,
+                    yes,
+                    component_dir,
+                } => {
+                    let lifecycle_manager = create_lifecycle_manager(component_dir.clone()).await?;
+
+                    // Exporting reveals secret values, so confirm like `secret list --show-values`.
+                    // The prompt goes to stderr to keep a bundle printed on stdout intact.
+                    if !*yes {
+                        eprint!("Export secret values? [y/N]: ");
+                        std::io::Write::flush(&mut std::io::stderr())?;
+                        let mut input = String::new();
+                        std::io::stdin().read_line(&mut input)?;
+                        if !input.trim().eq_ignore_ascii_case("y") {
+                            eprintln!("Cancelled.");
+                            return Ok(());
+                        }
+                    }
+
+                    let bundle = lifecycle_manager
+                        .export_component_secrets(component_id)
+                        .await?;
+
+                    match output {
+                        Some(path) => {
+                            bundle.write_to_file(path).await?;
+
+                            let result = json!({
+                                "status": "success",
+                                "component_id": component_id,
+                                "message": format!(
+                                    "Exported {} secret(s) to {}",
+                                    bundle.secrets.len(),
+                                    path.display()
+                                )
+                            });
+
+                            print_result(
+                                &rmcp::model::CallToolResult {
+                                    content: vec![rmcp::model::Content::text(
+                                        serde_json::to_string_pretty(&result)?,
+                                    )],
+                                    structured_content: None,
+                                    is_error: None,
+                                    meta: None,
+                                },
+                                OutputFormat::Json,
+                            )?;
+                        }
+                        None => print!("{}", serde_yaml::to_string(&bundle)?),
+                    }
+                }
+                SecretCommands::Import {
+                    file,
+                    component_id,
+                    component_dir,
+                } => {
+                    let lifecycle_manager = create_lifecycle_manager(component_dir.clone()).await?;
+
+                    let mut bundle = wassette::SecretsBundle::read_from_file(file).await?;
+                    if let Some(component_id) = component_id {
+                        bundle.component_id = component_id.clone();
+                    }
+
+                    let imported = lifecycle_manager.import_component_secrets(&bundle).await?;
+
+                    let result = json!({
+                        "status": "success",
+                        "component_id": bundle.component_id,
+                        "message": format!("Imported {imported} secret(s) for component")
+                    });
+
                     print_result(
                         &rmcp::model::CallToolResult {
                             content: vec![rmcp::model::Content::text(
@@ -703,7 +1509,8 @@ async fn main() -> Result<()> {
                     let component_dir = component_dir.clone().or_else(|| cli.component_dir.clone());
                     let lifecycle_manager = create_lifecycle_manager(component_dir).await?;
 
-                    let result = handle_tools_list(&lifecycle_manager, false).await?;
+                    let result =
+                        handle_tools_list(&lifecycle_manager, ToolListOptions::default()).await?;
 
                     let tools_result: rmcp::model::ListToolsResult =
                         serde_json::from_value(result)?;
@@ -732,12 +1539,14 @@ async fn main() -> Result<()> {
                 ToolCommands::Read {
                     name,
                     component_dir,
+                    deref,
                     output_format,
                 } => {
                     let component_dir = component_dir.clone().or_else(|| cli.component_dir.clone());
                     let lifecycle_manager = create_lifecycle_manager(component_dir).await?;
 
-                    let result = handle_tools_list(&lifecycle_manager, false).await?;
+                    let result =
+                        handle_tools_list(&lifecycle_manager, ToolListOptions::default()).await?;
                     let tools_result: rmcp::model::ListToolsResult =
                         serde_json::from_value(result)?;
 
@@ -747,11 +1556,20 @@ async fn main() -> Result<()> {
                         .find(|t| t.name == name.as_str())
                         .ok_or_else(|| anyhow::anyhow!("Tool not found: {}", name))?;
 
+                    let mut input_schema = Value::Object(tool.input_schema.as_ref().clone());
+                    let mut output_schema = tool
+                        .output_schema
+                        .as_ref()
+                        .map(|schema| Value::Object(schema.as_ref().clone()));
+                    if *deref {
+                        input_schema = schema::dereference(&input_schema);
+                        output_schema = output_schema.as_ref().map(schema::dereference);
+                    }
                     let content = serde_json::to_string_pretty(&json!({
                         "name": tool.name,
                         "description": tool.description,
-                        "input_schema": tool.input_schema,
-                        "output_schema": tool.output_schema,
+                        "input_schema": input_schema,
+                        "output_schema": output_schema,
                     }))?;
 
                     print_result(
@@ -767,6 +1585,9 @@ async fn main() -> Result<()> {
                 ToolCommands::Invoke {
                     name,
                     args,
+                    repeat,
+                    timing,
+                    stop_on_error,
                     component_dir,
                     output_format,
                 } => {
@@ -787,6 +1608,9 @@ async fn main() -> Result<()> {
                     };
 
                     if let Ok(tool_name) = ToolName::try_from(name.as_str()) {
+                        if *repeat > 1 || *timing {
+                            bail!("--repeat and --timing only apply to component tools");
+                        }
                         handle_tool_cli_command(
                             &lifecycle_manager,
                             tool_name.as_str(),
@@ -801,27 +1625,48 @@ async fn main() -> Result<()> {
                         };
 
                         use mcp_server::components::handle_component_call;
-                        let result = handle_component_call(&req, &lifecycle_manager).await;
-
-                        match result {
-                            Ok(tool_result) => {
-                                print_result(&tool_result, *output_format)?;
-
-                                if tool_result.is_error.unwrap_or(false) {
-                                    std::process::exit(1);
+                        let mut calls = Vec::new();
+                        for _ in 0..*repeat {
+                            let started = std::time::Instant::now();
+                            let result = handle_component_call(&req, &lifecycle_manager).await;
+                            let ok = matches!(&result, Ok(r) if !r.is_error.unwrap_or(false));
+                            calls.push((started.elapsed(), ok));
+
+                            if !*timing {
+                                match result {
+                                    Ok(tool_result) => print_result(&tool_result, *output_format)?,
+                                    Err(e) => eprintln!("Error invoking tool '{}': {}", name, e),
                                 }
                             }
-                            Err(e) => {
-                                eprintln!("Error invoking tool '{}': {}", name, e);
-                                std::process::exit(1);
+                            if !ok && *stop_on_error {
+                                break;
                             }
                         }
+
+                        if *timing {
+                            let report = timing_report(name, &calls);
+                            print_result(
+                                &rmcp::model::CallToolResult {
+                                    content: vec![rmcp::model::Content::text(
+                                        serde_json::to_string_pretty(&report)?,
+                                    )],
+                                    structured_content: None,
+                                    is_error: None,
+                                    meta: None,
+                                },
+                                *output_format,
+                            )?;
+                        }
+                        if calls.iter().any(|(_, ok)| !ok) {
+                            std::process::exit(1);
+                        }
                     }
                 }
             },
             Commands::Inspect {
                 component_id,
                 component_dir,
+                deref,
             } => {
                 let component_dir = component_dir.clone().or_else(|| cli.component_dir.clone());
                 let lifecycle_manager = create_lifecycle_manager(component_dir).await?;
@@ -846,8 +1691,12 @@ async fn main() -> Result<()> {
                             .to_string();
                         let description: Option<String> =
                             tool_info["description"].as_str().map(|s| s.to_string());
-                        let input_schema = tool_info["inputSchema"].clone();
-                        let output_schema = tool_info["outputSchema"].clone();
+                        let mut input_schema = tool_info["inputSchema"].clone();
+                        let mut output_schema = tool_info["outputSchema"].clone();
+                        if *deref {
+                            input_schema = schema::dereference(&input_schema);
+                            output_schema = schema::dereference(&output_schema);
+                        }
 
                         println!("{name}, {description:?}");
                         println!(
@@ -867,8 +1716,9 @@ async fn main() -> Result<()> {
                 RegistryCommands::Search {
                     query,
                     output_format,
+                    registry_url,
                 } => {
-                    let components = load_component_registry()?;
+                    let components = load_component_registry(registry_url.as_deref()).await?;
                     let results = registry::search_components(&components, query.as_deref());
 
                     let result = json!({
@@ -892,8 +1742,9 @@ async fn main() -> Result<()> {
                 RegistryCommands::Get {
                     component,
                     plugin_dir,
+                    registry_url,
                 } => {
-                    let components = load_component_registry()?;
+                    let components = load_component_registry(registry_url.as_deref()).await?;
 
                     // Find the component by name or URI
                     let registry_component =
@@ -918,33 +1769,124 @@ async fn main() -> Result<()> {
                     )
                     .await?;
                 }
+                RegistryCommands::Refresh { registry_url } => {
+                    let cache_dir = config::get_registry_cache_dir()?;
+                    let components = registry::refresh_registry(registry_url, &cache_dir).await?;
+                    println!(
+                        "Refreshed registry index from {registry_url}: {} components available",
+                        components.len()
+                    );
+                }
             },
-            Commands::Autocomplete { shell } => {
-                let mut cmd = Cli::command();
-                let bin_name = cmd.get_name().to_string();
-
-                match shell {
-                    Shell::Bash => {
-                        generate(shells::Bash, &mut cmd, &bin_name, &mut std::io::stdout());
-                    }
-                    Shell::Zsh => {
-                        generate(shells::Zsh, &mut cmd, &bin_name, &mut std::io::stdout());
-                    }
-                    Shell::Fish => {
-                        generate(shells::Fish, &mut cmd, &bin_name, &mut std::io::stdout());
-                    }
-                    Shell::PowerShell => {
-                        generate(
-                            shells::PowerShell,
-                            &mut cmd,
-                            &bin_name,
-                            &mut std::io::stdout(),
-                        );
+            Commands::Manifest { command } => match command {
+                ManifestCommands::Schema => {
+                    let schema = manifest::ProvisioningManifest::json_schema()?;
+                    println!("{}", serde_json::to_string_pretty(&schema)?);
+                }
+                ManifestCommands::Validate {
+                    manifest,
+                    output_format,
+                } => {
+                    let issues = manifest::ProvisioningManifest::from_file(manifest)?
+                        .validate_detailed()
+                        .err()
+                        .unwrap_or_default();
+                    let report = json!({ "valid": issues.is_empty(), "issues": issues });
+
+                    match output_format {
+                        OutputFormat::Json => {
+                            println!("{}", serde_json::to_string_pretty(&report)?)
+                        }
+                        OutputFormat::Yaml => println!("{}", format::format_as_yaml(&report)?),
+                        _ if issues.is_empty() => {
+                            println!("Manifest {} is valid", manifest.display())
+                        }
+                        _ => {
+                            for issue in &issues {
+                                println!("{issue}");
+                            }
+                        }
                     }
-                    Shell::Elvish => {
-                        generate(shells::Elvish, &mut cmd, &bin_name, &mut std::io::stdout());
+
+                    if !issues.is_empty() {
+                        std::process::exit(1);
                     }
                 }
+            },
+            Commands::Doctor {
+                component_dir,
+                output_format,
+            } => {
+                let component_dir = component_dir.clone().or_else(|| cli.component_dir.clone());
+                let lifecycle_manager = create_lifecycle_manager(component_dir).await?;
+                let report = check_components(&lifecycle_manager).await?;
+                print_result(
+                    &rmcp::model::CallToolResult {
+                        content: vec![rmcp::model::Content::text(serde_json::to_string_pretty(
+                            &report,
+                        )?)],
+                        structured_content: None,
+                        is_error: None,
+                        meta: None,
+                    },
+                    *output_format,
+                )?;
+                if report["failed"]
+                    .as_array()
+                    .is_some_and(|failed| !failed.is_empty())
+                {
+                    std::process::exit(1);
+                }
+            }
+            Commands::Selftest { output_format } => {
+                let report = selftest::run().await;
+                match output_format {
+                    Some(format) => print_result(
+                        &rmcp::model::CallToolResult {
+                            content: vec![rmcp::model::Content::text(
+                                serde_json::to_string_pretty(&report)?,
+                            )],
+                            structured_content: None,
+                            is_error: None,
+                            meta: None,
+                        },
+                        *format,
+                    )?,
+                    None => print!("{}", selftest::render_report(&report)),
+                }
+                if !report.passed {
+                    std::process::exit(1);
+                }
+            }
+            Commands::Replay {
+                dir,
+                component_dir,
+                output_format,
+            } => {
+                let component_dir = component_dir.clone().or_else(|| cli.component_dir.clone());
+                let lifecycle_manager = create_lifecycle_manager(component_dir).await?;
+                let options = call_recording::replay_options(&cli_handlers::load_config()?);
+                let report = call_recording::replay(dir, &lifecycle_manager, &options).await?;
+                match output_format {
+                    Some(format) => print_result(
+                        &rmcp::model::CallToolResult {
+                            content: vec![rmcp::model::Content::text(
+                                serde_json::to_string_pretty(&report)?,
+                            )],
+                            structured_content: None,
+                            is_error: None,
+                            meta: None,
+                        },
+                        *format,
+                    )?,
+                    None => print!("{}", call_recording::render_report(&report)),
+                }
+                if !report.passed {
+                    std::process::exit(1);
+                }
+            }
+            Commands::Autocomplete { shell } => {
+                write_completions(shell, &mut std::io::stdout());
             }
         },
         None => {
@@ -956,6 +1898,36 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Log filter used when `RUST_LOG` is not set: `level` for everything but Cranelift's
+/// code generation, which is far too chatty below warnings
+fn log_filter(level: LevelFilter) -> EnvFilter {
+    EnvFilter::new(format!(
+        "{level},cranelift_codegen=warn,cranelift_entity=warn,cranelift_bforest=warn,cranelift_frontend=warn"
+    ))
+}
+
+/// Apply the levels clients send with `logging/setLevel` by swapping the log filter,
+/// including one set with `RUST_LOG`
+fn log_level_control<S: 'static>(
+    handle: tracing_subscriber::reload::Handle<EnvFilter, S>,
+) -> LogLevelControl {
+    LogLevelControl::new(move |level| Ok(handle.reload(log_filter(level))?))
+}
+
+/// Write the completion script for `shell` covering the whole command tree
+fn write_completions(shell: &Shell, out: &mut dyn std::io::Write) {
+    let mut cmd = Cli::command();
+    let bin_name = cmd.get_name().to_string();
+
+    match shell {
+        Shell::Bash => generate(shells::Bash, &mut cmd, bin_name, out),
+        Shell::Zsh => generate(shells::Zsh, &mut cmd, bin_name, out),
+        Shell::Fish => generate(shells::Fish, &mut cmd, bin_name, out),
+        Shell::PowerShell => generate(shells::PowerShell, &mut cmd, bin_name, out),
+        Shell::Elvish => generate(shells::Elvish, &mut cmd, bin_name, out),
+    }
+}
+
 #[cfg(test)]
 mod cli_tests {
     use clap::Parser;
@@ -1063,6 +2035,69 @@ mod cli_tests {
         }
     }
 
+    #[test]
+    fn test_permission_explain_parsing() {
+        let args = vec![
+            "wassette",
+            "permission",
+            "explain",
+            "test-component",
+            "storage",
+            "fs:///tmp/test",
+            "--access",
+            "read,write",
+            "-o",
+            "yaml",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        if let Some(Commands::Permission {
+            command:
+                PermissionCommands::Explain {
+                    component_id,
+                    target: ExplainPermissionCommands::Storage { uri, access },
+                    output_format,
+                    ..
+                },
+        }) = cli.command
+        {
+            assert_eq!(component_id, "test-component");
+            assert_eq!(uri, "fs:///tmp/test");
+            assert_eq!(access, vec!["read", "write"]);
+            assert!(matches!(output_format, OutputFormat::Yaml));
+        } else {
+            panic!("Expected storage explain command");
+        }
+    }
+
+    #[test]
+    fn test_completions_alias_generates_bash_script() {
+        let cli = Cli::try_parse_from(["wassette", "completions", "bash"]).unwrap();
+        let Some(Commands::Autocomplete { shell }) = cli.command else {
+            panic!("Expected completions to map to the autocomplete command");
+        };
+
+        let mut script = Vec::new();
+        write_completions(&shell, &mut script);
+        let script = String::from_utf8(script).unwrap();
+
+        assert!(script.contains("complete"));
+        assert!(
+            script.lines().any(|line| line.contains("permission")
+                && line.contains("grant")
+                && line.contains("storage")),
+            "bash completions do not cover `permission grant storage`"
+        );
+
+        let cli = Cli::try_parse_from(["wassette", "completions", "powershell"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Autocomplete {
+                shell: Shell::PowerShell
+            })
+        ));
+    }
+
     #[test]
     fn test_autocomplete_parsing() {
         // Test autocomplete bash
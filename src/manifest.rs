@@ -1,14 +1,15 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT license.
 
-use std::collections::HashSet;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
-use anyhow::{bail, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Provisioning manifest for headless deployment mode
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ProvisioningManifest {
     /// Manifest schema version
     pub version: u32,
@@ -18,7 +19,7 @@ pub struct ProvisioningManifest {
 }
 
 /// Component declaration in manifest
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ComponentDeclaration {
     /// Component URI (file://, oci://, https://)
     pub uri: String,
@@ -31,7 +32,7 @@ pub struct ComponentDeclaration {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub digest: Option<String>,
 
-    /// Permissions configuration (inline only in MVP)
+    /// Permissions configuration, declared inline and/or loaded with `from_file`
     pub permissions: InlinePermissions,
 
     /// Optional retry policy (deferred to post-MVP)
@@ -39,9 +40,14 @@ pub struct ComponentDeclaration {
     pub retry_policy: Option<RetryPolicy>,
 }
 
-/// Inline permission declarations (only mode supported in MVP)
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+/// Inline permission declarations
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct InlinePermissions {
+    /// YAML file holding more permissions in this same format, merged with the inline ones.
+    /// Relative paths are resolved against the directory of the manifest.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_file: Option<PathBuf>,
+
     /// Network permissions
     #[serde(skip_serializing_if = "Option::is_none")]
     pub network: Option<NetworkPermissions>,
@@ -54,34 +60,34 @@ pub struct InlinePermissions {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub environment: Option<EnvironmentPermissions>,
 
-    /// Memory and resource limits (deferred to post-MVP)
+    /// Memory and resource limits. `memory_bytes` is written to the synthesized policy.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub resources: Option<ResourceLimits>,
 }
 
 /// Network access permissions
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct NetworkPermissions {
     /// List of allowed hosts
     pub allow: Vec<NetworkRule>,
 }
 
 /// Network access rule
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct NetworkRule {
     /// Host to allow (e.g., "api.example.com")
     pub host: String,
 }
 
 /// Storage access permissions
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct StoragePermissions {
     /// List of allowed filesystem paths
     pub allow: Vec<StorageRule>,
 }
 
 /// Storage access rule
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct StorageRule {
     /// URI of storage resource (e.g., "fs:///tmp/workspace")
     pub uri: String,
@@ -91,7 +97,7 @@ pub struct StorageRule {
 }
 
 /// Storage access type
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum AccessType {
     Read,
@@ -99,37 +105,44 @@ pub enum AccessType {
 }
 
 /// Environment variable permissions
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct EnvironmentPermissions {
     /// List of allowed environment variables
     pub allow: Vec<EnvironmentRule>,
 }
 
 /// Environment variable rule
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct EnvironmentRule {
     /// Environment variable key
     pub key: String,
 
-    /// Optional source hint (e.g., for GitHub Actions secrets)
+    /// Optional source hint (e.g., for GitHub Actions secrets). May contain `${VAR}`
+    /// references, which are expanded before the variable is read.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub value_from: Option<String>,
+
+    /// Literal value, instead of reading one from the environment. `${VAR}` references are
+    /// replaced with the value of `VAR` in the process environment at provisioning time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
 }
 
-/// Resource limits (deferred to post-MVP)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Resource limits
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ResourceLimits {
     /// Memory limit in bytes
     #[serde(skip_serializing_if = "Option::is_none")]
     pub memory_bytes: Option<u64>,
 
-    /// CPU time limit in milliseconds
+    /// CPU time limit in milliseconds. Not supported: validation rejects a manifest that sets
+    /// it rather than accept a limit that would never be enforced.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cpu_time_ms: Option<u64>,
 }
 
 /// Retry policy (deferred to post-MVP)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RetryPolicy {
     /// Number of retry attempts
     pub attempts: u32,
@@ -139,7 +152,7 @@ pub struct RetryPolicy {
 }
 
 /// Backoff strategy for retries
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum BackoffStrategy {
     Exponential { base_ms: u64 },
@@ -147,182 +160,588 @@ pub enum BackoffStrategy {
 }
 
 impl ProvisioningManifest {
-    /// Parse manifest from a YAML file
+    /// JSON Schema describing the manifest format, for editors and CI validators
+    pub fn json_schema() -> Result<serde_json::Value> {
+        serde_json::to_value(schemars::schema_for!(ProvisioningManifest))
+            .context("Failed to serialize manifest schema")
+    }
+
+    /// Parse manifest from a YAML file. Permission files it references are resolved against
+    /// the manifest's directory.
     pub fn from_file(path: &Path) -> Result<Self> {
-        let content = std::fs::read_to_string(path)
+        let content = read_yaml_file(path)
             .with_context(|| format!("Failed to read manifest file: {}", path.display()))?;
 
-        Self::from_yaml(&content)
+        let base_dir = path.parent().unwrap_or(Path::new(""));
+        Self::parse(&content, base_dir)
             .with_context(|| format!("Failed to parse manifest file: {}", path.display()))
     }
 
-    /// Parse manifest from YAML string
+    /// Parse several manifest files and merge them into a single manifest.
+    ///
+    /// Components are concatenated in the order the files are given. All files must declare
+    /// the same schema version, and a component URI may only be declared once across all of them.
+    pub fn from_files<P: AsRef<Path>>(paths: &[P]) -> Result<Self> {
+        let Some((first, rest)) = paths.split_first() else {
+            bail!("No manifest files provided");
+        };
+
+        let mut merged = Self::from_file(first.as_ref())?;
+        let mut declared_in: HashMap<String, &Path> = HashMap::new();
+        for component in &merged.components {
+            declared_in
+                .entry(component.uri.clone())
+                .or_insert(first.as_ref());
+        }
+
+        for path in rest {
+            let path = path.as_ref();
+            let manifest = Self::from_file(path)?;
+            if manifest.version != merged.version {
+                bail!(
+                    "Manifest {} declares version {} but {} declares version {}",
+                    path.display(),
+                    manifest.version,
+                    first.as_ref().display(),
+                    merged.version
+                );
+            }
+
+            for component in manifest.components {
+                if let Some(previous) = declared_in.get(&component.uri) {
+                    bail!(
+                        "Duplicate component URI '{}' declared in {} and {}",
+                        component.uri,
+                        previous.display(),
+                        path.display()
+                    );
+                }
+                declared_in.insert(component.uri.clone(), path);
+                merged.components.push(component);
+            }
+        }
+
+        Ok(merged)
+    }
+
+    /// Parse and merge every `.yaml`/`.yml` manifest in a directory, in file name order
+    pub fn from_dir(dir: &Path) -> Result<Self> {
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+            .with_context(|| format!("Failed to read manifest directory: {}", dir.display()))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.is_file()
+                    && path
+                        .extension()
+                        .is_some_and(|ext| ext == "yaml" || ext == "yml")
+            })
+            .collect();
+        paths.sort();
+
+        if paths.is_empty() {
+            bail!(
+                "No manifest files (*.yaml, *.yml) found in {}",
+                dir.display()
+            );
+        }
+
+        Self::from_files(&paths)
+    }
+
+    /// Parse manifest from YAML string. Permission files it references are resolved against
+    /// the current directory.
     pub fn from_yaml(content: &str) -> Result<Self> {
-        serde_yaml::from_str(content).context("Failed to deserialize manifest YAML")
+        Self::parse(content, Path::new(""))
+    }
+
+    fn parse(content: &str, base_dir: &Path) -> Result<Self> {
+        let mut manifest: Self =
+            parse_yaml(content).context("Failed to deserialize manifest YAML")?;
+        for component in &mut manifest.components {
+            component
+                .permissions
+                .load_from_file(base_dir)
+                .with_context(|| format!("Component '{}'", component.uri))?;
+        }
+        Ok(manifest)
     }
 
     /// Validate the manifest
     pub fn validate(&self) -> Result<()> {
+        self.validate_detailed().map_err(issues_to_error)
+    }
+
+    /// Validate the manifest, reporting every problem found along with the JSON pointer of the
+    /// field it concerns (for example `/components/2/permissions/storage/allow/0/uri`).
+    pub fn validate_detailed(&self) -> Result<(), Vec<ValidationIssue>> {
+        let mut issues = Vec::new();
+
         // Check version
         if self.version != 1 {
-            bail!(
-                "Unsupported manifest version: {}. Only version 1 is supported.",
-                self.version
-            );
+            issues.push(ValidationIssue::new(
+                "/version",
+                format!(
+                    "Unsupported manifest version: {}. Only version 1 is supported.",
+                    self.version
+                ),
+            ));
         }
 
         // Check for components
         if self.components.is_empty() {
-            bail!("Manifest must declare at least one component");
+            issues.push(ValidationIssue::new(
+                "/components",
+                "Manifest must declare at least one component",
+            ));
         }
 
         // Check for duplicate URIs
         let mut seen_uris = HashSet::new();
-        let mut duplicate_uris = Vec::new();
-
-        for component in &self.components {
+        for (idx, component) in self.components.iter().enumerate() {
             if !seen_uris.insert(&component.uri) {
-                duplicate_uris.push(&component.uri);
+                issues.push(ValidationIssue::new(
+                    format!("/components/{idx}/uri"),
+                    format!("Duplicate component URI: {}", component.uri),
+                ));
             }
         }
 
-        if !duplicate_uris.is_empty() {
-            bail!(
-                "Duplicate component URIs found: {}",
-                duplicate_uris
-                    .iter()
-                    .map(|s| s.as_str())
-                    .collect::<Vec<_>>()
-                    .join(", ")
-            );
-        }
-
         // Validate each component
         for (idx, component) in self.components.iter().enumerate() {
-            component
-                .validate()
-                .with_context(|| format!("Invalid component at index {}", idx))?;
+            component.collect_issues(&format!("/components/{idx}"), &mut issues);
         }
 
-        Ok(())
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
+        }
+    }
+}
+
+/// A single manifest validation problem
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ValidationIssue {
+    /// JSON pointer to the offending field, relative to the manifest root
+    pub path: String,
+    /// Human-readable description of the problem
+    pub message: String,
+}
+
+impl ValidationIssue {
+    fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
     }
 }
 
+fn issues_to_error(issues: Vec<ValidationIssue>) -> anyhow::Error {
+    anyhow!(issues
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; "))
+}
+
 impl ComponentDeclaration {
     /// Validate the component declaration
     pub fn validate(&self) -> Result<()> {
-        // Validate URI
-        if self.uri.is_empty() {
-            bail!("Component URI cannot be empty");
+        let mut issues = Vec::new();
+        self.collect_issues("", &mut issues);
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues_to_error(issues))
         }
+    }
+
+    fn collect_issues(&self, pointer: &str, issues: &mut Vec<ValidationIssue>) {
+        let uri_pointer = format!("{pointer}/uri");
 
-        // Validate URI scheme
+        // Validate URI
         let valid_schemes = ["file://", "oci://", "https://", "http://"];
-        if !valid_schemes
+        if self.uri.is_empty() {
+            issues.push(ValidationIssue::new(
+                &uri_pointer,
+                "Component URI cannot be empty",
+            ));
+        } else if !valid_schemes
             .iter()
             .any(|scheme| self.uri.starts_with(scheme))
         {
-            bail!(
-                "Component URI must start with one of: {}. Got: {}",
-                valid_schemes.join(", "),
-                self.uri
-            );
+            // Validate URI scheme
+            issues.push(ValidationIssue::new(
+                &uri_pointer,
+                format!(
+                    "Component URI must start with one of: {}. Got: {}",
+                    valid_schemes.join(", "),
+                    self.uri
+                ),
+            ));
         }
 
         // Validate digest format if present
         if let Some(digest) = &self.digest {
-            if !digest.starts_with("sha256:") {
-                bail!("Digest must be in format 'sha256:<hex>'. Got: {}", digest);
-            }
-
-            let hex_part = &digest[7..]; // Skip "sha256:"
-            if hex_part.len() != 64 {
-                bail!(
-                    "SHA-256 digest must be 64 hex characters. Got: {} characters",
-                    hex_part.len()
-                );
-            }
-
-            if !hex_part.chars().all(|c| c.is_ascii_hexdigit()) {
-                bail!("SHA-256 digest must contain only hex characters");
+            let digest_pointer = format!("{pointer}/digest");
+            match digest.strip_prefix("sha256:") {
+                None => issues.push(ValidationIssue::new(
+                    digest_pointer,
+                    format!("Digest must be in format 'sha256:<hex>'. Got: {digest}"),
+                )),
+                Some(hex_part) if hex_part.len() != 64 => issues.push(ValidationIssue::new(
+                    digest_pointer,
+                    format!(
+                        "SHA-256 digest must be 64 hex characters. Got: {} characters",
+                        hex_part.len()
+                    ),
+                )),
+                Some(hex_part) if !hex_part.chars().all(|c| c.is_ascii_hexdigit()) => {
+                    issues.push(ValidationIssue::new(
+                        digest_pointer,
+                        "SHA-256 digest must contain only hex characters",
+                    ))
+                }
+                Some(_) => {}
             }
         }
 
         // Validate permissions
         self.permissions
-            .validate()
-            .context("Invalid permissions configuration")?;
-
-        Ok(())
+            .collect_issues(&format!("{pointer}/permissions"), issues);
     }
 }
 
 impl InlinePermissions {
+    /// Merge the permissions referenced by `from_file`, if any, into these ones. Allow rules
+    /// from the file are appended after the inline rules; inline resource limits take
+    /// precedence over the file's.
+    pub fn load_from_file(&mut self, base_dir: &Path) -> Result<()> {
+        let Some(from_file) = self.from_file.take() else {
+            return Ok(());
+        };
+
+        let path = base_dir.join(&from_file);
+        let content = read_yaml_file(&path)
+            .with_context(|| format!("Failed to read permissions file: {}", path.display()))?;
+        let loaded: InlinePermissions = parse_yaml(&content)
+            .with_context(|| format!("Failed to parse permissions file: {}", path.display()))?;
+        if loaded.from_file.is_some() {
+            bail!(
+                "Permissions file {} cannot itself use from_file",
+                path.display()
+            );
+        }
+
+        self.merge(loaded);
+        Ok(())
+    }
+
+    fn merge(&mut self, other: InlinePermissions) {
+        fn append<T>(target: &mut Option<T>, other: Option<T>, rules: impl FnOnce(&mut T, T)) {
+            match (target.as_mut(), other) {
+                (Some(existing), Some(more)) => rules(existing, more),
+                (None, more) => *target = more,
+                (Some(_), None) => {}
+            }
+        }
+
+        append(&mut self.network, other.network, |a, b| {
+            a.allow.extend(b.allow)
+        });
+        append(&mut self.storage, other.storage, |a, b| {
+            a.allow.extend(b.allow)
+        });
+        append(&mut self.environment, other.environment, |a, b| {
+            a.allow.extend(b.allow)
+        });
+        append(&mut self.resources, other.resources, |a, b| {
+            a.memory_bytes = a.memory_bytes.or(b.memory_bytes);
+            a.cpu_time_ms = a.cpu_time_ms.or(b.cpu_time_ms);
+        });
+    }
+
     /// Validate inline permissions
     pub fn validate(&self) -> Result<()> {
+        let mut issues = Vec::new();
+        self.collect_issues("", &mut issues);
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues_to_error(issues))
+        }
+    }
+
+    fn collect_issues(&self, pointer: &str, issues: &mut Vec<ValidationIssue>) {
         // At least one permission type should be specified
         if self.network.is_none()
             && self.storage.is_none()
             && self.environment.is_none()
             && self.resources.is_none()
         {
-            bail!("Inline permissions must specify at least one permission type (network, storage, environment, or resources)");
+            issues.push(ValidationIssue::new(
+                pointer,
+                "Inline permissions must specify at least one permission type (network, storage, environment, or resources)",
+            ));
         }
 
         // Validate network permissions
         if let Some(network) = &self.network {
+            let allow_pointer = format!("{pointer}/network/allow");
             if network.allow.is_empty() {
-                bail!("Network permissions 'allow' list cannot be empty");
+                issues.push(ValidationIssue::new(
+                    &allow_pointer,
+                    "Network permissions 'allow' list cannot be empty",
+                ));
             }
 
-            for rule in &network.allow {
+            for (idx, rule) in network.allow.iter().enumerate() {
                 if rule.host.is_empty() {
-                    bail!("Network rule host cannot be empty");
+                    issues.push(ValidationIssue::new(
+                        format!("{allow_pointer}/{idx}/host"),
+                        "Network rule host cannot be empty",
+                    ));
                 }
             }
         }
 
         // Validate storage permissions
         if let Some(storage) = &self.storage {
+            let allow_pointer = format!("{pointer}/storage/allow");
             if storage.allow.is_empty() {
-                bail!("Storage permissions 'allow' list cannot be empty");
+                issues.push(ValidationIssue::new(
+                    &allow_pointer,
+                    "Storage permissions 'allow' list cannot be empty",
+                ));
             }
 
-            for rule in &storage.allow {
+            for (idx, rule) in storage.allow.iter().enumerate() {
+                let uri_pointer = format!("{allow_pointer}/{idx}/uri");
                 if rule.uri.is_empty() {
-                    bail!("Storage rule URI cannot be empty");
-                }
-
-                if !rule.uri.starts_with("fs://") {
-                    bail!("Storage URI must start with 'fs://'. Got: {}", rule.uri);
+                    issues.push(ValidationIssue::new(
+                        uri_pointer,
+                        "Storage rule URI cannot be empty",
+                    ));
+                } else if !rule.uri.starts_with("fs://") {
+                    issues.push(ValidationIssue::new(
+                        uri_pointer,
+                        format!("Storage URI must start with 'fs://'. Got: {}", rule.uri),
+                    ));
                 }
 
                 if rule.access.is_empty() {
-                    bail!("Storage rule must specify at least one access type (read or write)");
+                    issues.push(ValidationIssue::new(
+                        format!("{allow_pointer}/{idx}/access"),
+                        "Storage rule must specify at least one access type (read or write)",
+                    ));
                 }
             }
         }
 
         // Validate environment permissions
         if let Some(env) = &self.environment {
+            let allow_pointer = format!("{pointer}/environment/allow");
             if env.allow.is_empty() {
-                bail!("Environment permissions 'allow' list cannot be empty");
+                issues.push(ValidationIssue::new(
+                    &allow_pointer,
+                    "Environment permissions 'allow' list cannot be empty",
+                ));
             }
 
             let mut seen_keys = HashSet::new();
-            for rule in &env.allow {
+            for (idx, rule) in env.allow.iter().enumerate() {
+                let key_pointer = format!("{allow_pointer}/{idx}/key");
                 if rule.key.is_empty() {
-                    bail!("Environment variable key cannot be empty");
+                    issues.push(ValidationIssue::new(
+                        key_pointer,
+                        "Environment variable key cannot be empty",
+                    ));
+                    continue;
+                }
+
+                if let Err(e) = policy::Permissions::validate_environment_key(&rule.key) {
+                    issues.push(ValidationIssue::new(&key_pointer, format!("{e:#}")));
+                }
+                if rule.key.ends_with('*') && rule.value_from.is_some() {
+                    issues.push(ValidationIssue::new(
+                        format!("{allow_pointer}/{idx}/value_from"),
+                        format!(
+                            "Environment rule '{}' uses a wildcard and cannot set value_from",
+                            rule.key
+                        ),
+                    ));
+                }
+                if rule.key.ends_with('*') && rule.value.is_some() {
+                    issues.push(ValidationIssue::new(
+                        format!("{allow_pointer}/{idx}/value"),
+                        format!(
+                            "Environment rule '{}' uses a wildcard and cannot set value",
+                            rule.key
+                        ),
+                    ));
+                }
+                if rule.value.is_some() && rule.value_from.is_some() {
+                    issues.push(ValidationIssue::new(
+                        format!("{allow_pointer}/{idx}/value"),
+                        format!(
+                            "Environment rule '{}' cannot set both value and value_from",
+                            rule.key
+                        ),
+                    ));
+                }
+                for (field, template) in [("value_from", &rule.value_from), ("value", &rule.value)]
+                {
+                    let Some(template) = template else { continue };
+                    // Only the syntax can be checked here; variables are read when provisioning
+                    if let Err(e) = expand_env_template(template, |_| Some(String::new())) {
+                        issues.push(ValidationIssue::new(
+                            format!("{allow_pointer}/{idx}/{field}"),
+                            format!("{e:#}"),
+                        ));
+                    }
                 }
 
                 if !seen_keys.insert(&rule.key) {
-                    bail!("Duplicate environment variable key: {}", rule.key);
+                    issues.push(ValidationIssue::new(
+                        key_pointer,
+                        format!("Duplicate environment variable key: {}", rule.key),
+                    ));
                 }
             }
         }
 
-        Ok(())
+        // Validate resource limits
+        if let Some(resources) = &self.resources {
+            if resources.cpu_time_ms.is_some() {
+                issues.push(ValidationIssue::new(
+                    format!("{pointer}/resources/cpu_time_ms"),
+                    "cpu_time_ms is not supported; use a tool timeout to bound how long calls run",
+                ));
+            }
+        }
+    }
+}
+
+/// Replace every `${VAR}` in `template` with the value `lookup` returns for `VAR`. `$$`
+/// stands for a literal `$`; any other `$` is kept as is. Fails on a reference that is not
+/// closed or has an empty name, and on a variable `lookup` does not know.
+pub fn expand_env_template(
+    template: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<String> {
+    let mut expanded = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        if let Some(after) = after.strip_prefix('$') {
+            expanded.push('$');
+            rest = after;
+        } else if let Some(reference) = after.strip_prefix('{') {
+            let Some(end) = reference.find('}') else {
+                bail!("Unterminated variable reference in '{template}'");
+            };
+            let name = &reference[..end];
+            if name.is_empty() {
+                bail!("Empty variable reference in '{template}'");
+            }
+            let Some(value) = lookup(name) else {
+                bail!("Environment variable {name} referenced by '{template}' is not set");
+            };
+            expanded.push_str(&value);
+            rest = &reference[end + 1..];
+        } else {
+            expanded.push('$');
+            rest = after;
+        }
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// Largest manifest or permissions file accepted, far above what any real deployment needs
+pub const MAX_MANIFEST_BYTES: usize = 1024 * 1024;
+
+/// Read a YAML file, refusing files larger than [`MAX_MANIFEST_BYTES`] without reading them
+/// into memory in full
+fn read_yaml_file(path: &Path) -> Result<String> {
+    use std::io::Read;
+
+    let mut content = String::new();
+    std::fs::File::open(path)?
+        .take(MAX_MANIFEST_BYTES as u64 + 1)
+        .read_to_string(&mut content)?;
+    ensure_within_size_limit(&content)?;
+    Ok(content)
+}
+
+fn ensure_within_size_limit(content: &str) -> Result<()> {
+    if content.len() > MAX_MANIFEST_BYTES {
+        bail!("Manifest YAML is larger than the {MAX_MANIFEST_BYTES} byte limit");
+    }
+    Ok(())
+}
+
+/// Deserialize manifest YAML, which may come from a less trusted source. Documents over
+/// [`MAX_MANIFEST_BYTES`] and documents using aliases are rejected before parsing, since a
+/// handful of nested aliases can expand into billions of nodes. serde_yaml additionally
+/// bounds nesting depth.
+fn parse_yaml<T: serde::de::DeserializeOwned>(content: &str) -> Result<T> {
+    ensure_within_size_limit(content)?;
+    if let Some(line) = find_alias(content) {
+        bail!("Line {line} uses a YAML alias ('*name'); aliases are not allowed in manifests");
+    }
+    Ok(serde_yaml::from_str(content)?)
+}
+
+/// 1-based line of the first alias in `content`. Aliases can only start a node, where a
+/// plain scalar may not begin with `*`, so quoted text, comments and `*` inside plain
+/// scalars such as `a*b` are not mistaken for one.
+fn find_alias(content: &str) -> Option<usize> {
+    for (index, line) in content.lines().enumerate() {
+        let mut node_start = true;
+        let mut quote = None;
+        let mut escaped = false;
+        let mut chars = line.chars().peekable();
+        let mut previous = ' ';
+        while let Some(c) = chars.next() {
+            let next_is_space = chars.peek().is_none_or(|next| next.is_whitespace());
+            if let Some(open) = quote {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' && open == '"' {
+                    escaped = true;
+                } else if c == open {
+                    quote = None;
+                    node_start = false;
+                }
+            } else {
+                match c {
+                    c if c.is_whitespace() => {}
+                    '#' if previous.is_whitespace() => break,
+                    '*' if node_start => return Some(index + 1),
+                    '"' | '\'' if node_start => quote = Some(c),
+                    '[' | '{' | ',' => node_start = true,
+                    ':' => node_start = next_is_space,
+                    '-' | '?' if node_start => node_start = next_is_space,
+                    _ => node_start = false,
+                }
+            }
+            previous = c;
+        }
     }
+    None
 }
 
 #[cfg(test)]
@@ -485,6 +904,49 @@ components:
         assert!(manifest.validate().is_err());
     }
 
+    #[test]
+    fn test_validate_detailed_reports_json_pointers() {
+        let yaml = r#"
+version: 1
+components:
+  - uri: oci://example.com/first:latest
+    permissions:
+      network:
+        allow:
+          - host: api.example.com
+  - uri: oci://example.com/second:latest
+    digest: sha256:abc
+    permissions:
+      storage:
+        allow:
+          - uri: fs:///tmp/ok
+            access: [read]
+          - uri: /tmp/data
+            access: []
+"#;
+
+        let manifest = ProvisioningManifest::from_yaml(yaml).unwrap();
+        let issues = manifest.validate_detailed().unwrap_err();
+        let paths: Vec<&str> = issues.iter().map(|issue| issue.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "/components/1/digest",
+                "/components/1/permissions/storage/allow/1/uri",
+                "/components/1/permissions/storage/allow/1/access",
+            ]
+        );
+        assert_eq!(
+            issues[1].message,
+            "Storage URI must start with 'fs://'. Got: /tmp/data"
+        );
+
+        let err = manifest.validate().unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("/components/1/permissions/storage/allow/1/uri: Storage URI"));
+    }
+
     #[test]
     fn test_duplicate_env_keys() {
         let yaml = r#"
@@ -501,4 +963,352 @@ components:
         let manifest = ProvisioningManifest::from_yaml(yaml).unwrap();
         assert!(manifest.validate().is_err());
     }
+
+    #[test]
+    fn test_wildcard_env_keys() {
+        let yaml = r#"
+version: 1
+components:
+  - uri: oci://example.com/component:latest
+    permissions:
+      environment:
+        allow:
+          - key: AWS_*
+          - key: AWS_REGION
+"#;
+        let manifest = ProvisioningManifest::from_yaml(yaml).unwrap();
+        manifest.validate().unwrap();
+
+        let duplicate = r#"
+version: 1
+components:
+  - uri: oci://example.com/component:latest
+    permissions:
+      environment:
+        allow:
+          - key: AWS_*
+          - key: AWS_*
+"#;
+        let manifest = ProvisioningManifest::from_yaml(duplicate).unwrap();
+        let err = manifest.validate().unwrap_err();
+        assert!(format!("{err:#}").contains("Duplicate environment variable key: AWS_*"));
+
+        let misplaced = r#"
+version: 1
+components:
+  - uri: oci://example.com/component:latest
+    permissions:
+      environment:
+        allow:
+          - key: "*_TOKEN"
+"#;
+        let manifest = ProvisioningManifest::from_yaml(misplaced).unwrap();
+        assert!(manifest.validate().is_err());
+    }
+
+    #[test]
+    fn test_expand_env_template() {
+        let lookup = |name: &str| match name {
+            "HOST" => Some("db.internal".to_string()),
+            "PORT" => Some("5432".to_string()),
+            _ => None,
+        };
+        assert_eq!(
+            expand_env_template("postgres://${HOST}:${PORT}/app", lookup).unwrap(),
+            "postgres://db.internal:5432/app"
+        );
+        assert_eq!(
+            expand_env_template("cost: $$5, $PORT", lookup).unwrap(),
+            "cost: $5, $PORT"
+        );
+        assert_eq!(
+            expand_env_template("${MISSING}", lookup)
+                .unwrap_err()
+                .to_string(),
+            "Environment variable MISSING referenced by '${MISSING}' is not set"
+        );
+        assert!(expand_env_template("${HOST", lookup).is_err());
+        assert!(expand_env_template("${}", lookup).is_err());
+    }
+
+    #[test]
+    fn test_env_value_rules_are_validated() {
+        let yaml = r#"
+version: 1
+components:
+  - uri: oci://example.com/component:latest
+    permissions:
+      environment:
+        allow:
+          - key: DATABASE_URL
+            value: "postgres://${DB_HOST}/${DB_NAME"
+          - key: API_KEY
+            value: literal
+            value_from: API_KEY_SOURCE
+          - key: AWS_*
+            value: x
+"#;
+        let manifest = ProvisioningManifest::from_yaml(yaml).unwrap();
+        let issues = manifest.validate_detailed().unwrap_err();
+        let pointers: Vec<_> = issues.iter().map(|issue| issue.path.as_str()).collect();
+        assert_eq!(
+            pointers,
+            [
+                "/components/0/permissions/environment/allow/0/value",
+                "/components/0/permissions/environment/allow/1/value",
+                "/components/0/permissions/environment/allow/2/value",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cpu_time_limit_is_rejected() {
+        let yaml = r#"
+version: 1
+components:
+  - uri: oci://example.com/component:latest
+    permissions:
+      resources:
+        memory_bytes: 1048576
+        cpu_time_ms: 500
+"#;
+        let manifest = ProvisioningManifest::from_yaml(yaml).unwrap();
+        let issues = manifest.validate_detailed().unwrap_err();
+        let pointers: Vec<_> = issues.iter().map(|issue| issue.path.as_str()).collect();
+        assert_eq!(
+            pointers,
+            ["/components/0/permissions/resources/cpu_time_ms"]
+        );
+    }
+
+    fn write_manifest(dir: &Path, file_name: &str, uri: &str) -> PathBuf {
+        let path = dir.join(file_name);
+        let yaml = format!(
+            r#"
+version: 1
+components:
+  - uri: {uri}
+    permissions:
+      network:
+        allow:
+          - host: api.example.com
+"#
+        );
+        std::fs::write(&path, yaml).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_from_files_merges_components() {
+        let dir = tempfile::tempdir().unwrap();
+        let team_a = write_manifest(dir.path(), "team-a.yaml", "oci://example.com/a:1");
+        let team_b = write_manifest(dir.path(), "team-b.yaml", "oci://example.com/b:1");
+
+        let manifest = ProvisioningManifest::from_files(&[team_a, team_b]).unwrap();
+        let uris: Vec<&str> = manifest.components.iter().map(|c| c.uri.as_str()).collect();
+        assert_eq!(uris, vec!["oci://example.com/a:1", "oci://example.com/b:1"]);
+        manifest.validate().unwrap();
+    }
+
+    #[test]
+    fn test_from_files_detects_duplicate_uris_across_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let team_a = write_manifest(dir.path(), "team-a.yaml", "oci://example.com/shared:1");
+        let team_b = write_manifest(dir.path(), "team-b.yaml", "oci://example.com/shared:1");
+
+        let err = ProvisioningManifest::from_files(&[&team_a, &team_b]).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("Duplicate component URI 'oci://example.com/shared:1'"));
+        assert!(message.contains("team-a.yaml"));
+        assert!(message.contains("team-b.yaml"));
+    }
+
+    #[test]
+    fn test_from_dir_reads_yaml_files_in_name_order() {
+        let dir = tempfile::tempdir().unwrap();
+        write_manifest(dir.path(), "20-second.yml", "oci://example.com/second:1");
+        write_manifest(dir.path(), "10-first.yaml", "oci://example.com/first:1");
+        std::fs::write(dir.path().join("README.md"), "not a manifest").unwrap();
+
+        let manifest = ProvisioningManifest::from_dir(dir.path()).unwrap();
+        assert_eq!(manifest.components.len(), 2);
+        assert_eq!(manifest.components[0].uri, "oci://example.com/first:1");
+
+        let empty = tempfile::tempdir().unwrap();
+        assert!(ProvisioningManifest::from_dir(empty.path()).is_err());
+    }
+
+    #[test]
+    fn test_permissions_from_file_are_merged_with_inline() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("policies")).unwrap();
+        std::fs::write(
+            dir.path().join("policies/weather.yaml"),
+            r#"
+network:
+  allow:
+    - host: api.openweathermap.com
+environment:
+  allow:
+    - key: OPENWEATHER_API_KEY
+resources:
+  memory_bytes: 1048576
+"#,
+        )
+        .unwrap();
+        let manifest_path = dir.path().join("manifest.yaml");
+        std::fs::write(
+            &manifest_path,
+            r#"
+version: 1
+components:
+  - uri: oci://ghcr.io/microsoft/get-weather-js:latest
+    name: weather-service
+    permissions:
+      from_file: policies/weather.yaml
+      network:
+        allow:
+          - host: api.example.com
+"#,
+        )
+        .unwrap();
+
+        let manifest = ProvisioningManifest::from_file(&manifest_path).unwrap();
+        manifest.validate().unwrap();
+        let permissions = &manifest.components[0].permissions;
+        assert_eq!(permissions.from_file, None);
+
+        let policy = crate::permission_synthesis::synthesize_policy_from_inline(
+            permissions,
+            Some("weather-service"),
+        )
+        .unwrap();
+        let hosts: Vec<String> = policy
+            .permissions
+            .network
+            .unwrap()
+            .allow
+            .unwrap()
+            .into_iter()
+            .map(|rule| match rule {
+                policy::NetworkPermission::Host(host) => host.host,
+                other => panic!("unexpected network rule: {other:?}"),
+            })
+            .collect();
+        assert_eq!(hosts, vec!["api.example.com", "api.openweathermap.com"]);
+        let env_keys: Vec<String> = policy
+            .permissions
+            .environment
+            .unwrap()
+            .allow
+            .unwrap()
+            .into_iter()
+            .map(|rule| rule.key)
+            .collect();
+        assert_eq!(env_keys, vec!["OPENWEATHER_API_KEY"]);
+        assert!(policy.permissions.resources.is_some());
+
+        // A missing permissions file fails the whole manifest
+        std::fs::remove_file(dir.path().join("policies/weather.yaml")).unwrap();
+        let err = ProvisioningManifest::from_file(&manifest_path).unwrap_err();
+        assert!(format!("{err:#}").contains("Failed to read permissions file"));
+    }
+
+    #[test]
+    fn test_json_schema_accepts_example_manifests() {
+        let schema = ProvisioningManifest::json_schema().unwrap();
+        assert!(schema["$schema"]
+            .as_str()
+            .unwrap()
+            .starts_with("https://json-schema.org/"));
+        assert_eq!(schema["title"], "ProvisioningManifest");
+        assert_eq!(schema["type"], "object");
+
+        let validator = jsonschema::validator_for(&schema).unwrap();
+        for example in [
+            include_str!("../examples/manifests/production.yaml"),
+            include_str!("../examples/manifests/multi-component.yaml"),
+        ] {
+            let manifest: serde_json::Value = serde_yaml::from_str(example).unwrap();
+            let errors: Vec<String> = validator
+                .iter_errors(&manifest)
+                .map(|error| error.to_string())
+                .collect();
+            assert!(errors.is_empty(), "{manifest}: {errors:?}");
+        }
+    }
+
+    #[test]
+    fn test_json_schema_rejects_invalid_manifests() {
+        let schema = ProvisioningManifest::json_schema().unwrap();
+        let validator = jsonschema::validator_for(&schema).unwrap();
+
+        let missing_uri = serde_json::json!({
+            "version": 1,
+            "components": [{ "permissions": {} }]
+        });
+        assert!(!validator.is_valid(&missing_uri));
+
+        let bad_access = serde_json::json!({
+            "version": 1,
+            "components": [{
+                "uri": "file:///tmp/tool.wasm",
+                "permissions": {
+                    "storage": { "allow": [{ "uri": "fs:///tmp", "access": ["execute"] }] }
+                }
+            }]
+        });
+        assert!(!validator.is_valid(&bad_access));
+    }
+
+    #[test]
+    fn test_alias_bomb_is_rejected_before_expansion() {
+        let bomb = r#"
+version: 1
+a: &a ["lol", "lol", "lol", "lol", "lol", "lol", "lol", "lol", "lol"]
+b: &b [*a, *a, *a, *a, *a, *a, *a, *a, *a]
+c: &c [*b, *b, *b, *b, *b, *b, *b, *b, *b]
+d: &d [*c, *c, *c, *c, *c, *c, *c, *c, *c]
+e: &e [*d, *d, *d, *d, *d, *d, *d, *d, *d]
+f: &f [*e, *e, *e, *e, *e, *e, *e, *e, *e]
+g: &g [*f, *f, *f, *f, *f, *f, *f, *f, *f]
+h: &h [*g, *g, *g, *g, *g, *g, *g, *g, *g]
+components: *h
+"#;
+        let started = std::time::Instant::now();
+        let err = ProvisioningManifest::from_yaml(bomb).unwrap_err();
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+        assert!(
+            format!("{err:#}").contains("Line 4 uses a YAML alias"),
+            "{err:#}"
+        );
+
+        let oversized = format!(
+            "version: 1\ncomponents: []\n#{}",
+            "x".repeat(MAX_MANIFEST_BYTES)
+        );
+        let err = ProvisioningManifest::from_yaml(&oversized).unwrap_err();
+        assert!(format!("{err:#}").contains("byte limit"), "{err:#}");
+    }
+
+    #[test]
+    fn test_asterisks_outside_aliases_are_accepted() {
+        let yaml = r#"
+version: 1 # *not an alias
+components:
+  - uri: "*weird*.wasm"
+    name: a*b
+    permissions:
+      network:
+        allow:
+          - host: '*.example.com'
+      environment:
+        allow:
+          - key: AWS_*
+"#;
+        let manifest = ProvisioningManifest::from_yaml(yaml).unwrap();
+        assert_eq!(manifest.components[0].uri, "*weird*.wasm");
+        assert_eq!(find_alias("components: [ *x ]"), Some(1));
+        assert_eq!(find_alias("a:\n  - *x"), Some(2));
+    }
 }
@@ -3,8 +3,9 @@
 
 use anyhow::{Context, Result};
 use policy::{
-    AccessType as PolicyAccessType, EnvironmentPermission, EnvironmentPermissions,
-    NetworkHostPermission, NetworkPermission, PermissionList, PolicyDocument, StoragePermission,
+    AccessType as PolicyAccessType, EnvironmentPermission, EnvironmentPermissions, MemoryLimit,
+    NetworkHostPermission, NetworkPermission, PermissionList, PolicyDocument, ResourceLimitValues,
+    ResourceLimits, StoragePermission,
 };
 
 use crate::manifest::{AccessType, InlinePermissions};
@@ -76,6 +77,20 @@ pub fn synthesize_policy_from_inline(
         });
     }
 
+    // Convert resource limits. Manifest validation rejects cpu_time_ms, which has no policy
+    // equivalent: the policy's CPU limit is a core count, not a time budget.
+    if let Some(resources) = &inline.resources {
+        if let Some(memory_bytes) = resources.memory_bytes {
+            policy.permissions.resources = Some(ResourceLimits {
+                limits: Some(ResourceLimitValues::new(
+                    None,
+                    Some(MemoryLimit::String(memory_bytes.to_string())),
+                )),
+                ..Default::default()
+            });
+        }
+    }
+
     // Validate the generated policy
     policy
         .validate()
@@ -104,12 +119,45 @@ mod tests {
     use crate::manifest::{
         EnvironmentPermissions as ManifestEnvPerms, EnvironmentRule,
         NetworkPermissions as ManifestNetPerms, NetworkRule,
-        StoragePermissions as ManifestStoragePerms, StorageRule,
+        ResourceLimits as ManifestResourceLimits, StoragePermissions as ManifestStoragePerms,
+        StorageRule,
     };
 
+    #[test]
+    fn test_synthesize_memory_limit() {
+        let inline = InlinePermissions {
+            from_file: None,
+            network: None,
+            storage: None,
+            environment: None,
+            resources: Some(ManifestResourceLimits {
+                memory_bytes: Some(64 * 1024 * 1024),
+                cpu_time_ms: None,
+            }),
+        };
+
+        let policy = synthesize_policy_from_inline(&inline, Some("test-component")).unwrap();
+        let limits = policy
+            .permissions
+            .resources
+            .as_ref()
+            .and_then(|resources| resources.limits.as_ref())
+            .expect("memory limit should be synthesized");
+        assert_eq!(limits.memory_bytes().unwrap(), Some(64 * 1024 * 1024));
+        assert!(limits.cpu.is_none());
+
+        let yaml = synthesize_policy_yaml(&inline, Some("test-component")).unwrap();
+        assert!(yaml.contains("resources:"));
+        assert!(yaml.contains("67108864"));
+        let parsed = policy::PolicyParser::parse_str(&yaml).unwrap();
+        let parsed_limits = parsed.permissions.resources.unwrap().limits.unwrap();
+        assert_eq!(parsed_limits.memory, limits.memory);
+    }
+
     #[test]
     fn test_synthesize_network_only() {
         let inline = InlinePermissions {
+            from_file: None,
             network: Some(ManifestNetPerms {
                 allow: vec![
                     NetworkRule {
@@ -143,6 +191,7 @@ mod tests {
     #[test]
     fn test_synthesize_storage_only() {
         let inline = InlinePermissions {
+            from_file: None,
             network: None,
             storage: Some(ManifestStoragePerms {
                 allow: vec![StorageRule {
@@ -166,6 +215,7 @@ mod tests {
     #[test]
     fn test_synthesize_environment_only() {
         let inline = InlinePermissions {
+            from_file: None,
             network: None,
             storage: None,
             environment: Some(ManifestEnvPerms {
@@ -173,10 +223,12 @@ mod tests {
                     EnvironmentRule {
                         key: "API_KEY".to_string(),
                         value_from: None,
+                        value: None,
                     },
                     EnvironmentRule {
                         key: "DATABASE_URL".to_string(),
                         value_from: Some("DB_URL".to_string()),
+                        value: None,
                     },
                 ],
             }),
@@ -195,6 +247,7 @@ mod tests {
     #[test]
     fn test_synthesize_all_permissions() {
         let inline = InlinePermissions {
+            from_file: None,
             network: Some(ManifestNetPerms {
                 allow: vec![NetworkRule {
                     host: "api.example.com".to_string(),
@@ -210,6 +263,7 @@ mod tests {
                 allow: vec![EnvironmentRule {
                     key: "API_KEY".to_string(),
                     value_from: None,
+                    value: None,
                 }],
             }),
             resources: None,
@@ -225,6 +279,7 @@ mod tests {
     #[test]
     fn test_synthesize_to_yaml() {
         let inline = InlinePermissions {
+            from_file: None,
             network: Some(ManifestNetPerms {
                 allow: vec![NetworkRule {
                     host: "api.example.com".to_string(),
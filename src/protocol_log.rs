@@ -0,0 +1,369 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Diagnostic recording of raw MCP JSON-RPC traffic for `--protocol-log`.
+//!
+//! Every frame exchanged with the client is appended to the log file as a single
+//! JSON line carrying a timestamp, the direction, and the message itself.
+//!
+//! Over HTTP, request bodies are buffered to be recorded, up to
+//! [`MAX_RECORDED_BODY_BYTES`]. Responses are recorded as they stream to the client, and
+//! each event of a `text/event-stream` response is one entry.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context as TaskContext, Poll};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use futures_util::StreamExt;
+use serde_json::{json, Value};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// Largest request body accepted while recording, and the most of an unfinished frame kept
+/// in memory before it is recorded as is
+pub const MAX_RECORDED_BODY_BYTES: usize = 4 * 1024 * 1024;
+
+/// Direction of a recorded frame relative to the server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Client to server
+    Inbound,
+    /// Server to client
+    Outbound,
+}
+
+impl Direction {
+    fn as_str(self) -> &'static str {
+        match self {
+            Direction::Inbound => "inbound",
+            Direction::Outbound => "outbound",
+        }
+    }
+}
+
+/// Append-only JSON-lines log of protocol frames
+#[derive(Clone)]
+pub struct ProtocolLog {
+    file: Arc<Mutex<File>>,
+}
+
+impl ProtocolLog {
+    /// Open (or create) the log file for appending
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open protocol log: {}", path.display()))?;
+        Ok(Self {
+            file: Arc::new(Mutex::new(file)),
+        })
+    }
+
+    /// Record a single frame. Frames that are not valid JSON are kept as strings.
+    pub fn record(&self, direction: Direction, frame: &str) {
+        let frame = frame.trim();
+        if frame.is_empty() {
+            return;
+        }
+
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        let message = serde_json::from_str::<Value>(frame)
+            .unwrap_or_else(|_| Value::String(frame.to_string()));
+        let entry = json!({
+            "timestamp_ms": timestamp_ms,
+            "direction": direction.as_str(),
+            "message": message,
+        });
+
+        let mut file = self.file.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Err(e) = writeln!(file, "{entry}") {
+            tracing::warn!(error = %e, "Failed to write protocol log entry");
+        }
+    }
+}
+
+/// Splits a byte stream into newline-delimited frames and records each one.
+///
+/// In SSE mode the `data:` lines of each event are joined and recorded as one frame. A
+/// line longer than [`MAX_RECORDED_BODY_BYTES`] is recorded as far as it got, and any
+/// trailing partial frame is recorded when the recorder is dropped.
+struct FrameRecorder {
+    log: ProtocolLog,
+    direction: Direction,
+    sse: bool,
+    buffer: Vec<u8>,
+    /// Data of the SSE event being read
+    event_data: Option<String>,
+}
+
+impl FrameRecorder {
+    fn new(log: ProtocolLog, direction: Direction, sse: bool) -> Self {
+        Self {
+            log,
+            direction,
+            sse,
+            buffer: Vec::new(),
+            event_data: None,
+        }
+    }
+
+    fn feed(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+        while let Some(pos) = self.buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+            self.record_line(&line);
+        }
+        if self.buffer.len() > MAX_RECORDED_BODY_BYTES {
+            let line = std::mem::take(&mut self.buffer);
+            self.record_line(&line);
+        }
+    }
+
+    fn record_line(&mut self, line: &[u8]) {
+        let line = String::from_utf8_lossy(line);
+        if !self.sse {
+            self.log.record(self.direction, &line);
+            return;
+        }
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            self.finish_event();
+        } else if let Some(data) = line.strip_prefix("data:") {
+            let data = data.strip_prefix(' ').unwrap_or(data);
+            match &mut self.event_data {
+                Some(event_data) => {
+                    event_data.push('\n');
+                    event_data.push_str(data);
+                }
+                None => self.event_data = Some(data.to_string()),
+            }
+        }
+    }
+
+    fn finish_event(&mut self) {
+        if let Some(data) = self.event_data.take() {
+            self.log.record(self.direction, &data);
+        }
+    }
+}
+
+impl Drop for FrameRecorder {
+    fn drop(&mut self) {
+        if !self.buffer.is_empty() {
+            let rest = std::mem::take(&mut self.buffer);
+            self.record_line(&rest);
+        }
+        self.finish_event();
+    }
+}
+
+/// Reader that records every inbound frame it yields
+pub struct RecordingReader<R> {
+    inner: R,
+    recorder: FrameRecorder,
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for RecordingReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let already_filled = buf.filled().len();
+        let this = &mut *self;
+        let poll = Pin::new(&mut this.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = &poll {
+            this.recorder.feed(&buf.filled()[already_filled..]);
+        }
+        poll
+    }
+}
+
+/// Writer that records every outbound frame written through it
+pub struct RecordingWriter<W> {
+    inner: W,
+    recorder: FrameRecorder,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for RecordingWriter<W> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = &mut *self;
+        let poll = Pin::new(&mut this.inner).poll_write(cx, buf);
+        if let Poll::Ready(Ok(written)) = &poll {
+            this.recorder.feed(&buf[..*written]);
+        }
+        poll
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(
+        mut self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+/// Wrap a stdio-style `(reader, writer)` transport so both directions are recorded
+pub fn record_stdio<R, W>(
+    log: &ProtocolLog,
+    (reader, writer): (R, W),
+) -> (RecordingReader<R>, RecordingWriter<W>) {
+    (
+        RecordingReader {
+            inner: reader,
+            recorder: FrameRecorder::new(log.clone(), Direction::Inbound, false),
+        },
+        RecordingWriter {
+            inner: writer,
+            recorder: FrameRecorder::new(log.clone(), Direction::Outbound, false),
+        },
+    )
+}
+
+/// Axum middleware recording request bodies and (possibly streamed) response bodies.
+/// Requests with a body over [`MAX_RECORDED_BODY_BYTES`] are refused.
+pub async fn record_http_traffic(
+    State(log): State<ProtocolLog>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let (parts, body) = request.into_parts();
+    let declared_length = parts
+        .headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<usize>().ok());
+    if declared_length.is_some_and(|length| length > MAX_RECORDED_BODY_BYTES) {
+        return (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "Request body too large to record",
+        )
+            .into_response();
+    }
+    let bytes = match axum::body::to_bytes(body, MAX_RECORDED_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to read request body for protocol log");
+            return (StatusCode::BAD_REQUEST, "Failed to read request body").into_response();
+        }
+    };
+    log.record(Direction::Inbound, &String::from_utf8_lossy(&bytes));
+
+    let response = next
+        .run(Request::from_parts(parts, Body::from(bytes)))
+        .await;
+
+    let is_sse = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/event-stream"));
+
+    let (parts, body) = response.into_parts();
+    let mut recorder = FrameRecorder::new(log, Direction::Outbound, is_sse);
+    let stream = body.into_data_stream().map(move |chunk| {
+        if let Ok(bytes) = &chunk {
+            recorder.feed(bytes);
+        }
+        chunk
+    });
+
+    Response::from_parts(parts, Body::from_stream(stream))
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+
+    fn read_entries(path: &Path) -> Vec<Value> {
+        std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_stdio_frames_are_recorded_with_direction() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("protocol.log");
+        let log = ProtocolLog::open(&path).unwrap();
+
+        let input: &[u8] = b"{\"jsonrpc\":\"2.0\",\"method\":\"initialize\",\"id\":1}\n";
+        let (mut reader, mut writer) = record_stdio(&log, (input, Vec::new()));
+
+        let mut received = String::new();
+        reader.read_to_string(&mut received).await.unwrap();
+        // Write the response in two chunks to exercise frame reassembly
+        writer.write_all(b"{\"jsonrpc\":\"2.0\",").await.unwrap();
+        writer
+            .write_all(b"\"id\":1,\"result\":{}}\n")
+            .await
+            .unwrap();
+
+        let entries = read_entries(&path);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["direction"], "inbound");
+        assert_eq!(entries[0]["message"]["method"], "initialize");
+        assert!(entries[0]["timestamp_ms"].as_u64().unwrap() > 0);
+        assert_eq!(entries[1]["direction"], "outbound");
+        assert_eq!(entries[1]["message"]["id"], 1);
+        assert!(entries[1]["message"]["result"].is_object());
+    }
+
+    #[test]
+    fn test_sse_recorder_keeps_only_data_lines() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("protocol.log");
+        let log = ProtocolLog::open(&path).unwrap();
+
+        let mut recorder = FrameRecorder::new(log, Direction::Outbound, true);
+        recorder
+            .feed(b"id: 0\nevent: message\ndata: {\"jsonrpc\":\"2.0\",\"id\":1,\"result\":{}}\n\n");
+        drop(recorder);
+
+        let entries = read_entries(&path);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0]["message"]["id"], 1);
+    }
+
+    #[test]
+    fn test_sse_recorder_joins_data_lines_of_an_event() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("protocol.log");
+        let log = ProtocolLog::open(&path).unwrap();
+
+        let mut recorder = FrameRecorder::new(log, Direction::Outbound, true);
+        recorder.feed(b"event: message\r\ndata: {\"jsonrpc\":\"2.0\",\r\n");
+        recorder.feed(b"data: \"id\":2,\"result\":{}}\r\n\r\ndata: {\"id\":3}\n");
+        drop(recorder);
+
+        let entries = read_entries(&path);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["message"]["id"], 2);
+        assert_eq!(entries[1]["message"]["id"], 3);
+    }
+}
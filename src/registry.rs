@@ -2,6 +2,12 @@
 // Licensed under the MIT license.
 
 //! Registry operations for searching and fetching components from component-registry.json
+//!
+//! Instead of the bundled index, the registry can be read from a URL. The index fetched from
+//! it is cached locally and only fetched again by [`refresh_registry`].
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
@@ -19,6 +25,90 @@ pub fn parse_registry(registry_json: &str) -> Result<Vec<RegistryComponent>> {
     serde_json::from_str(registry_json).context("Failed to parse component registry JSON")
 }
 
+/// Name of the file in the cache directory holding the index fetched from a registry URL
+const CACHE_FILE: &str = "registry-index.json";
+
+/// The cached index, with the URL it was fetched from
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedIndex {
+    url: String,
+    components: Vec<RegistryComponent>,
+}
+
+/// Components listed by the index at `url`. The cached copy in `cache_dir` is used when it was
+/// fetched from the same URL; otherwise the index is fetched and cached.
+pub async fn load_registry(url: &str, cache_dir: &Path) -> Result<Vec<RegistryComponent>> {
+    match read_cache(cache_dir).await {
+        Some(cached) if cached.url == url => Ok(cached.components),
+        _ => refresh_registry(url, cache_dir).await,
+    }
+}
+
+/// Fetch the index at `url` and replace the cached index in `cache_dir` with it
+pub async fn refresh_registry(url: &str, cache_dir: &Path) -> Result<Vec<RegistryComponent>> {
+    let index = fetch_index(url).await?;
+    let components =
+        parse_registry(&index).with_context(|| format!("Invalid registry index at {url}"))?;
+    let cached = CachedIndex {
+        url: url.to_string(),
+        components,
+    };
+    write_cache(cache_dir, &cached)?;
+    Ok(cached.components)
+}
+
+/// Read the index at an `http(s)://` URL, or from a `file://` URL or plain path
+async fn fetch_index(url: &str) -> Result<String> {
+    if url.starts_with("http://") || url.starts_with("https://") {
+        let response = reqwest::get(url)
+            .await
+            .and_then(|response| response.error_for_status())
+            .with_context(|| format!("Failed to fetch registry index from {url}"))?;
+        response
+            .text()
+            .await
+            .with_context(|| format!("Failed to read registry index from {url}"))
+    } else {
+        let path = url.strip_prefix("file://").unwrap_or(url);
+        tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("Failed to read registry index from {path}"))
+    }
+}
+
+fn cache_path(cache_dir: &Path) -> PathBuf {
+    cache_dir.join(CACHE_FILE)
+}
+
+/// The cached index, if there is a readable one
+async fn read_cache(cache_dir: &Path) -> Option<CachedIndex> {
+    let content = tokio::fs::read(cache_path(cache_dir)).await.ok()?;
+    match serde_json::from_slice(&content) {
+        Ok(cached) => Some(cached),
+        Err(e) => {
+            tracing::warn!("Ignoring unreadable registry cache: {e}");
+            None
+        }
+    }
+}
+
+/// Replace the cached index, through a temporary file so readers never see a partial index
+fn write_cache(cache_dir: &Path, cached: &CachedIndex) -> Result<()> {
+    std::fs::create_dir_all(cache_dir).with_context(|| {
+        format!(
+            "Failed to create registry cache directory {}",
+            cache_dir.display()
+        )
+    })?;
+    let mut file = tempfile::NamedTempFile::new_in(cache_dir)
+        .context("Failed to create temporary registry cache file")?;
+    file.write_all(&serde_json::to_vec_pretty(cached)?)
+        .context("Failed to write registry cache")?;
+    file.persist(cache_path(cache_dir))
+        .context("Failed to replace registry cache")?;
+    Ok(())
+}
+
 /// Search for components matching a query string with optimized full-text search
 pub fn search_components(
     components: &[RegistryComponent],
@@ -198,6 +288,47 @@ mod tests {
         assert_eq!(results.len(), 1);
     }
 
+    fn write_index(path: &Path, names: &[&str]) {
+        let components: Vec<_> = names
+            .iter()
+            .map(|name| RegistryComponent {
+                name: name.to_string(),
+                description: format!("{name} component"),
+                uri: format!("oci://example.com/{}", name.to_lowercase()),
+            })
+            .collect();
+        std::fs::write(path, serde_json::to_string(&components).unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_refresh_replaces_cached_index() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let index_path = temp_dir.path().join("index.json");
+        let cache_dir = temp_dir.path().join("cache");
+        let url = format!("file://{}", index_path.display());
+
+        write_index(&index_path, &["Weather"]);
+        let components = load_registry(&url, &cache_dir).await.unwrap();
+        assert_eq!(components.len(), 1);
+
+        // The cache is used until refreshed
+        write_index(&index_path, &["Weather", "Time"]);
+        let components = load_registry(&url, &cache_dir).await.unwrap();
+        assert!(search_components(&components, Some("time")).is_empty());
+
+        let refreshed = refresh_registry(&url, &cache_dir).await.unwrap();
+        assert_eq!(refreshed.len(), 2);
+        let components = load_registry(&url, &cache_dir).await.unwrap();
+        let results = search_components(&components, Some("time"));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].uri, "oci://example.com/time");
+
+        // A broken index leaves the cache as it was
+        std::fs::write(&index_path, "not json").unwrap();
+        assert!(refresh_registry(&url, &cache_dir).await.is_err());
+        assert_eq!(load_registry(&url, &cache_dir).await.unwrap().len(), 2);
+    }
+
     #[test]
     fn test_search_components_empty_query() {
         let components = vec![RegistryComponent {
@@ -0,0 +1,136 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! `serve --reload-on-sighup`: re-read the configuration and rescan the component directory
+//! when the process receives SIGHUP, as is customary for long-running daemons.
+//!
+//! Of the configuration, only `tool_timeout_ms`, `tool_timeouts` and `max_tool_output_bytes`
+//! are applied on reload; every other setting needs a restart.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use mcp_server::{LifecycleManager, McpServer, ToolTimeouts};
+use tokio::signal::unix::{signal, SignalKind};
+
+use crate::commands::Serve;
+use crate::config;
+
+/// Listen for SIGHUP in the background for as long as the server runs
+pub fn spawn(
+    serve: Serve,
+    component_dir: PathBuf,
+    lifecycle_manager: LifecycleManager,
+    server: McpServer,
+) -> Result<()> {
+    let mut hangups =
+        signal(SignalKind::hangup()).context("Failed to install the SIGHUP handler")?;
+    tokio::spawn(async move {
+        while hangups.recv().await.is_some() {
+            tracing::info!("Received SIGHUP, reloading");
+            reload(&serve, &component_dir, &lifecycle_manager, &server).await;
+        }
+    });
+    Ok(())
+}
+
+async fn reload(
+    serve: &Serve,
+    component_dir: &PathBuf,
+    lifecycle_manager: &LifecycleManager,
+    server: &McpServer,
+) {
+    apply_config(config::Config::from_serve(serve), component_dir, server);
+
+    match lifecycle_manager.rescan_components().await {
+        Ok(outcome) if outcome.loaded.is_empty() && outcome.removed.is_empty() => {}
+        Ok(outcome) => {
+            tracing::info!(
+                loaded = ?outcome.loaded,
+                removed = ?outcome.removed,
+                "Component directory rescanned"
+            );
+            if let Some(peer) = server.get_peer() {
+                if let Err(e) = peer.notify_tool_list_changed().await {
+                    tracing::warn!("Failed to notify tool list changed: {}", e);
+                }
+            }
+        }
+        Err(e) => tracing::error!("Failed to rescan the component directory: {e:#}"),
+    }
+}
+
+/// Apply the reloadable settings of a re-read configuration to `server`
+fn apply_config(config: Result<config::Config>, component_dir: &Path, server: &McpServer) {
+    // A broken config file must not take down a running server; keep the current settings
+    let config = match config {
+        Ok(config) => config,
+        Err(e) => {
+            tracing::error!("Failed to reload configuration, keeping the current one: {e:#}");
+            return;
+        }
+    };
+    if config.component_dir != component_dir {
+        tracing::warn!(
+            "The component directory changed to {}; restart wassette to serve it",
+            config.component_dir.display()
+        );
+    }
+    server.set_call_limits(
+        ToolTimeouts::from_millis(config.tool_timeout_ms, &config.tool_timeouts),
+        config.max_tool_output_bytes,
+    );
+    tracing::info!(
+        "Configuration re-read; tool timeouts and the output limit apply now, other settings after a restart"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reload_applies_tool_limits_to_running_server() {
+        let component_dir = tempfile::tempdir().unwrap();
+        let config_dir = tempfile::tempdir().unwrap();
+        let lifecycle_manager = LifecycleManager::new(component_dir.path()).await.unwrap();
+        let server = McpServer::builder(lifecycle_manager)
+            .with_max_tool_output_bytes(Some(1024))
+            .build();
+        // Sessions serve clones of the server
+        let session = server.clone();
+
+        let config_file = config_dir.path().join("config.toml");
+        let toml_content = r#"
+tool_timeout_ms = 2000
+max_tool_output_bytes = 64
+
+[tool_timeouts]
+slow = 9000
+"#;
+        std::fs::write(&config_file, toml_content).unwrap();
+        let config = config::Config::new_from_path(&serde_json::json!({}), &config_file);
+        apply_config(config, component_dir.path(), &server);
+
+        let options = session.call_options();
+        assert_eq!(options.max_output_bytes, Some(64));
+        assert_eq!(
+            options.timeouts.for_tool("fast"),
+            Some(Duration::from_millis(2000))
+        );
+        assert_eq!(
+            options.timeouts.for_tool("slow"),
+            Some(Duration::from_millis(9000))
+        );
+
+        // A configuration that fails to load keeps the current limits
+        apply_config(
+            Err(anyhow::anyhow!("broken")),
+            component_dir.path(),
+            &server,
+        );
+        assert_eq!(session.call_options().max_output_bytes, Some(64));
+    }
+}
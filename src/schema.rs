@@ -0,0 +1,134 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Display helpers for tool JSON schemas.
+
+use serde_json::{Map, Value};
+
+/// Marker left in place of a `$ref` that points back into a definition being expanded
+const CYCLE_MARKER: &str = "$cycle";
+
+/// A copy of `schema` with every local `$ref` (`#/definitions/...`, `#/$defs/...` or any other
+/// JSON pointer into the schema) replaced by the schema it points to.
+///
+/// A reference back into a definition that is already being expanded is kept as
+/// `{"$ref": ..., "$cycle": true}`. The `definitions`/`$defs` blocks are dropped once nothing
+/// refers to them anymore. Unresolvable and remote references are left untouched.
+pub fn dereference(schema: &Value) -> Value {
+    let mut stack = Vec::new();
+    let mut cyclic = false;
+    let mut resolved = resolve(schema, schema, &mut stack, &mut cyclic);
+    if !cyclic {
+        if let Value::Object(map) = &mut resolved {
+            map.remove("definitions");
+            map.remove("$defs");
+        }
+    }
+    resolved
+}
+
+fn resolve(root: &Value, value: &Value, stack: &mut Vec<String>, cyclic: &mut bool) -> Value {
+    match value {
+        Value::Object(map) => {
+            if let Some(reference) = map.get("$ref").and_then(Value::as_str) {
+                if let Some(target) = reference.strip_prefix('#').and_then(|p| root.pointer(p)) {
+                    if stack.iter().any(|seen| seen == reference) {
+                        *cyclic = true;
+                        let mut marker = Map::new();
+                        marker.insert("$ref".to_string(), Value::from(reference));
+                        marker.insert(CYCLE_MARKER.to_string(), Value::Bool(true));
+                        return Value::Object(marker);
+                    }
+
+                    stack.push(reference.to_string());
+                    let mut inlined = resolve(root, target, stack, cyclic);
+                    stack.pop();
+
+                    // Keywords next to the `$ref` refine the referenced schema
+                    if let Value::Object(inlined) = &mut inlined {
+                        for (key, sibling) in map.iter().filter(|(key, _)| *key != "$ref") {
+                            inlined.insert(key.clone(), resolve(root, sibling, stack, cyclic));
+                        }
+                    }
+                    return inlined;
+                }
+            }
+            Value::Object(
+                map.iter()
+                    .map(|(key, child)| (key.clone(), resolve(root, child, stack, cyclic)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| resolve(root, item, stack, cyclic))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_dereference_inlines_definitions() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "origin": {"$ref": "#/definitions/point"},
+                "target": {"$ref": "#/$defs/point", "description": "Where to go"},
+            },
+            "definitions": {
+                "point": {
+                    "type": "object",
+                    "properties": {"x": {"type": "number"}, "y": {"type": "number"}},
+                },
+            },
+            "$defs": {
+                "point": {"$ref": "#/definitions/point"},
+            },
+        });
+
+        let point = json!({
+            "type": "object",
+            "properties": {"x": {"type": "number"}, "y": {"type": "number"}},
+        });
+        let mut described = point.clone();
+        described["description"] = json!("Where to go");
+        assert_eq!(
+            dereference(&schema),
+            json!({
+                "type": "object",
+                "properties": {"origin": point, "target": described},
+            })
+        );
+    }
+
+    #[test]
+    fn test_dereference_marks_recursive_references() {
+        let schema = json!({
+            "$ref": "#/definitions/node",
+            "definitions": {
+                "node": {
+                    "type": "object",
+                    "properties": {
+                        "children": {"type": "array", "items": {"$ref": "#/definitions/node"}},
+                    },
+                },
+            },
+        });
+
+        let resolved = dereference(&schema);
+        assert_eq!(
+            resolved["properties"]["children"]["items"],
+            json!({"$ref": "#/definitions/node", "$cycle": true})
+        );
+        // The marker still points somewhere meaningful
+        assert!(resolved.get("definitions").is_some());
+    }
+}
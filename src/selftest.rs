@@ -0,0 +1,210 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! `wassette selftest`: run a tiny bundled component through load, call, and unload in a
+//! throwaway component directory to catch a broken runtime before serving anything.
+
+use std::future::Future;
+use std::path::Path;
+use std::time::Instant;
+
+use anyhow::{bail, ensure, Context, Result};
+use mcp_server::LifecycleManager;
+use serde::Serialize;
+use wassette::UnloadResult;
+
+/// The bundled component, assembled from `selftest.wat` by the build script. Its `selftest`
+/// tool prints three lines through WASI and returns how many it printed.
+const SELFTEST_WASM: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/selftest.wasm"));
+const SELFTEST_ID: &str = "selftest";
+const STEPS: [&str; 4] = ["start", "load", "call", "unload"];
+
+/// Outcome of every selftest step, in the order they ran
+#[derive(Debug, Default, Serialize)]
+pub struct SelftestReport {
+    pub passed: bool,
+    pub steps: Vec<StepOutcome>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StepOutcome {
+    pub step: &'static str,
+    pub status: StepStatus,
+    pub duration_ms: u64,
+    /// What the step observed, or the full error chain when it failed
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StepStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+impl SelftestReport {
+    async fn step<T>(
+        &mut self,
+        step: &'static str,
+        run: impl Future<Output = Result<(T, String)>>,
+    ) -> Option<T> {
+        let started = Instant::now();
+        let result = run.await;
+        let duration_ms = started.elapsed().as_millis() as u64;
+        let (value, status, detail) = match result {
+            Ok((value, detail)) => (Some(value), StepStatus::Passed, detail),
+            Err(e) => (None, StepStatus::Failed, format!("{e:#}")),
+        };
+        self.steps.push(StepOutcome {
+            step,
+            status,
+            duration_ms,
+            detail,
+        });
+        value
+    }
+
+    /// Mark the steps that did not run because an earlier one failed
+    fn finish(mut self) -> Self {
+        for step in STEPS.into_iter().skip(self.steps.len()) {
+            self.steps.push(StepOutcome {
+                step,
+                status: StepStatus::Skipped,
+                duration_ms: 0,
+                detail: "an earlier step failed".to_string(),
+            });
+        }
+        self.passed = self
+            .steps
+            .iter()
+            .all(|step| step.status == StepStatus::Passed);
+        self
+    }
+}
+
+/// Run every step, stopping at the first failure
+pub async fn run() -> SelftestReport {
+    let mut report = SelftestReport::default();
+    let workdir = match tempfile::tempdir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            report.steps.push(StepOutcome {
+                step: "start",
+                status: StepStatus::Failed,
+                duration_ms: 0,
+                detail: format!("Failed to create a temporary directory: {e}"),
+            });
+            return report.finish();
+        }
+    };
+
+    let Some((manager, uri)) = report.step("start", start(workdir.path())).await else {
+        return report.finish();
+    };
+    if report.step("load", load(&manager, &uri)).await.is_none() {
+        return report.finish();
+    }
+    if report.step("call", call(&manager)).await.is_none() {
+        return report.finish();
+    }
+    report.step("unload", unload(&manager)).await;
+    report.finish()
+}
+
+async fn start(workdir: &Path) -> Result<((LifecycleManager, String), String)> {
+    let path = workdir.join(format!("{SELFTEST_ID}.wasm"));
+    tokio::fs::write(&path, SELFTEST_WASM).await?;
+
+    let manager = LifecycleManager::builder(workdir.join("components"))
+        .with_secrets_dir(workdir.join("secrets"))
+        .with_eager_loading(false)
+        .build()
+        .await
+        .context("Failed to start the component runtime")?;
+    Ok((
+        (manager, format!("file://{}", path.display())),
+        "component runtime started".to_string(),
+    ))
+}
+
+async fn load(manager: &LifecycleManager, uri: &str) -> Result<((), String)> {
+    let outcome = manager.load_component(uri).await?;
+    ensure!(
+        outcome.tool_names == [SELFTEST_ID],
+        "Expected the component to expose the tool '{SELFTEST_ID}', found {:?}",
+        outcome.tool_names
+    );
+    Ok((
+        (),
+        format!("compiled and loaded '{}'", outcome.component_id),
+    ))
+}
+
+async fn call(manager: &LifecycleManager) -> Result<((), String)> {
+    let result = manager
+        .execute_component_call(SELFTEST_ID, SELFTEST_ID, "{}")
+        .await?;
+    ensure!(
+        result == r#"{"result":3}"#,
+        "Expected the call to return 3, got {result}"
+    );
+
+    let lines: Vec<String> = manager
+        .get_component_logs(SELFTEST_ID, None)
+        .await
+        .into_iter()
+        .map(|line| line.line)
+        .collect();
+    ensure!(
+        lines == ["first", "second", "third"],
+        "Expected three lines on the component's stdout, captured {lines:?}"
+    );
+    Ok((
+        (),
+        "called the tool and captured its WASI stdout".to_string(),
+    ))
+}
+
+async fn unload(manager: &LifecycleManager) -> Result<((), String)> {
+    match manager.unload_component(SELFTEST_ID).await? {
+        UnloadResult::Unloaded => Ok(((), "unloaded and removed its files".to_string())),
+        UnloadResult::NotFound => bail!("The component was no longer installed"),
+    }
+}
+
+/// Human-readable report, one line per step
+pub fn render_report(report: &SelftestReport) -> String {
+    let mut out = String::new();
+    for step in &report.steps {
+        let mark = match step.status {
+            StepStatus::Passed => "✓",
+            StepStatus::Failed => "✗",
+            StepStatus::Skipped => "-",
+        };
+        out.push_str(&format!(
+            "{mark} {}: {} ({} ms)\n",
+            step.step, step.detail, step.duration_ms
+        ));
+    }
+    out.push_str(if report.passed {
+        "Selftest passed\n"
+    } else {
+        "Selftest failed\n"
+    });
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_selftest_passes() {
+        let report = run().await;
+        assert!(report.passed, "{}", render_report(&report));
+        let steps: Vec<&str> = report.steps.iter().map(|step| step.step).collect();
+        assert_eq!(steps, STEPS);
+        assert!(render_report(&report).ends_with("Selftest passed\n"));
+    }
+}
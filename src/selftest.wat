@@ -0,0 +1,53 @@
+;; Component run by `wassette selftest`. Its `selftest` function writes three lines to
+;; stdout through `wasi:cli/stdout` and returns how many it wrote.
+(component
+  (import "wasi:io/error@0.2.0" (instance $error
+    (export "error" (type (sub resource)))
+  ))
+  (alias export $error "error" (type $error-type))
+  (import "wasi:io/streams@0.2.0" (instance $streams
+    (export "output-stream" (type $os (sub resource)))
+    (alias outer 1 $error-type (type $err))
+    (export "error" (type $err-e (eq $err)))
+    (type $stream-error (variant (case "last-operation-failed" (own $err-e)) (case "closed")))
+    (export "stream-error" (type $se (eq $stream-error)))
+    (export "[method]output-stream.blocking-write-and-flush"
+      (func (param "self" (borrow $os)) (param "contents" (list u8)) (result (result (error $se)))))
+  ))
+  (alias export $streams "output-stream" (type $output-stream))
+  (import "wasi:cli/stdout@0.2.0" (instance $stdout
+    (alias outer 1 $output-stream (type $os))
+    (export "output-stream" (type $os-e (eq $os)))
+    (export "get-stdout" (func (result (own $os-e))))
+  ))
+  (core module $memory (memory (export "memory") 1))
+  (core instance $memory (instantiate $memory))
+  (alias core export $memory "memory" (core memory $mem))
+  (core func $get-stdout (canon lower (func $stdout "get-stdout")))
+  (core func $write
+    (canon lower (func $streams "[method]output-stream.blocking-write-and-flush") (memory $mem)))
+  (core func $drop (canon resource.drop $output-stream))
+  (core module $m
+    (import "env" "memory" (memory 1))
+    (import "host" "get-stdout" (func $get-stdout (result i32)))
+    (import "host" "write" (func $write (param i32 i32 i32 i32)))
+    (import "host" "drop" (func $drop (param i32)))
+    (data (i32.const 16) "first\nsecond\nthird\n")
+    (func (export "selftest") (result i32)
+      (local $out i32)
+      (local.set $out (call $get-stdout))
+      (call $write (local.get $out) (i32.const 16) (i32.const 19) (i32.const 64))
+      (call $drop (local.get $out))
+      (i32.const 3))
+  )
+  (core instance $i (instantiate $m
+    (with "env" (instance $memory))
+    (with "host" (instance
+      (export "get-stdout" (func $get-stdout))
+      (export "write" (func $write))
+      (export "drop" (func $drop))
+    ))
+  ))
+  (func $selftest (result u32) (canon lift (core func $i "selftest")))
+  (export "selftest" (func $selftest))
+)
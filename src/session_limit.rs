@@ -0,0 +1,268 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Session bookkeeping for the streamable HTTP transport: idle expiry and `--max-sessions`.
+//!
+//! Every session keeps a worker and its buffers alive until the client closes it, so sessions
+//! that have gone without a request for the idle timeout are closed, and a client that keeps
+//! opening sessions could otherwise grow the server's memory without bound. Requests
+//! belonging to an existing session are never affected by the cap.
+
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+use axum::extract::Request;
+use axum::http::{header, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use futures_util::Stream;
+use rmcp::model::{ClientJsonRpcMessage, ServerJsonRpcMessage};
+use rmcp::transport::common::server_side_http::ServerSseMessage;
+use rmcp::transport::streamable_http_server::session::local::{
+    LocalSessionManager, LocalSessionManagerError, LocalSessionWorker, SessionConfig,
+};
+use rmcp::transport::streamable_http_server::{SessionId, SessionManager};
+use rmcp::transport::WorkerTransport;
+use tokio::time::Instant;
+
+/// How long a session may go without a request before it is closed, unless configured
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Seconds a rejected client is asked to wait before trying again
+const RETRY_AFTER_SECS: &str = "5";
+
+tokio::task_local! {
+    /// Set when the request being served was refused a session because the cap was reached
+    static REFUSED: Cell<bool>;
+}
+
+/// Why a session operation failed
+#[derive(Debug)]
+pub enum SessionsError {
+    /// `max_sessions` sessions are already open
+    Full,
+    /// The underlying session manager failed
+    Local(LocalSessionManagerError),
+}
+
+impl fmt::Display for SessionsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Full => f.write_str("session limit reached"),
+            Self::Local(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for SessionsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Full => None,
+            Self::Local(e) => Some(e),
+        }
+    }
+}
+
+impl From<LocalSessionManagerError> for SessionsError {
+    fn from(e: LocalSessionManagerError) -> Self {
+        Self::Local(e)
+    }
+}
+
+/// [`LocalSessionManager`] that closes sessions once they have been idle for `idle_timeout`
+/// and refuses to open more than `max_sessions` at a time
+pub struct Sessions {
+    inner: LocalSessionManager,
+    max_sessions: Option<usize>,
+    idle_timeout: Duration,
+    /// When each open session last received a request
+    last_seen: Mutex<HashMap<SessionId, Instant>>,
+    /// Held while a new session is checked against the cap and registered, so two clients
+    /// cannot both take the last slot
+    opening: tokio::sync::Mutex<()>,
+}
+
+impl Sessions {
+    /// Close sessions idle for `idle_timeout` and, when set, allow at most `max_sessions`
+    pub fn new(max_sessions: Option<usize>, idle_timeout: Duration) -> Self {
+        Self {
+            inner: LocalSessionManager {
+                session_config: SessionConfig {
+                    keep_alive: Some(idle_timeout),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            max_sessions,
+            idle_timeout,
+            last_seen: Mutex::default(),
+            opening: tokio::sync::Mutex::default(),
+        }
+    }
+
+    fn last_seen(&self) -> std::sync::MutexGuard<'_, HashMap<SessionId, Instant>> {
+        self.last_seen
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Record a request for the session `id`
+    fn touch(&self, id: &SessionId) {
+        if let Some(seen) = self.last_seen().get_mut(id) {
+            *seen = Instant::now();
+        }
+    }
+
+    /// Close the sessions that have gone without a request for the idle timeout.
+    ///
+    /// A session whose worker already stopped on its own stays registered with the
+    /// [`LocalSessionManager`] until it is closed here, so this is also what frees its slot.
+    async fn close_idle(&self) {
+        let open: HashSet<SessionId> = self.inner.sessions.read().await.keys().cloned().collect();
+        let idle: Vec<SessionId> = {
+            let mut last_seen = self.last_seen();
+            last_seen.retain(|id, _| open.contains(id));
+            last_seen
+                .iter()
+                .filter(|(_, seen)| seen.elapsed() >= self.idle_timeout)
+                .map(|(id, _)| id.clone())
+                .collect()
+        };
+        for id in idle {
+            tracing::info!(session_id = %id, "Closing idle MCP session");
+            if let Err(e) = self.close_session(&id).await {
+                tracing::debug!(session_id = %id, error = %e, "Idle MCP session was already gone");
+            }
+        }
+    }
+}
+
+impl SessionManager for Sessions {
+    type Error = SessionsError;
+    type Transport = WorkerTransport<LocalSessionWorker>;
+
+    async fn create_session(&self) -> Result<(SessionId, Self::Transport), Self::Error> {
+        let _opening = self.opening.lock().await;
+        self.close_idle().await;
+        if let Some(max_sessions) = self.max_sessions {
+            let open = self.inner.sessions.read().await.len();
+            if open >= max_sessions {
+                tracing::warn!(
+                    open,
+                    max_sessions,
+                    "Rejecting new MCP session: session limit reached"
+                );
+                let _ = REFUSED.try_with(|refused| refused.set(true));
+                return Err(SessionsError::Full);
+            }
+        }
+        let (id, transport) = self.inner.create_session().await?;
+        self.last_seen().insert(id.clone(), Instant::now());
+        Ok((id, transport))
+    }
+
+    async fn initialize_session(
+        &self,
+        id: &SessionId,
+        message: ClientJsonRpcMessage,
+    ) -> Result<ServerJsonRpcMessage, Self::Error> {
+        self.touch(id);
+        Ok(self.inner.initialize_session(id, message).await?)
+    }
+
+    async fn has_session(&self, id: &SessionId) -> Result<bool, Self::Error> {
+        Ok(self.inner.has_session(id).await?)
+    }
+
+    async fn close_session(&self, id: &SessionId) -> Result<(), Self::Error> {
+        self.last_seen().remove(id);
+        Ok(self.inner.close_session(id).await?)
+    }
+
+    async fn create_stream(
+        &self,
+        id: &SessionId,
+        message: ClientJsonRpcMessage,
+    ) -> Result<impl Stream<Item = ServerSseMessage> + Send + Sync + 'static, Self::Error> {
+        self.touch(id);
+        Ok(self.inner.create_stream(id, message).await?)
+    }
+
+    async fn accept_message(
+        &self,
+        id: &SessionId,
+        message: ClientJsonRpcMessage,
+    ) -> Result<(), Self::Error> {
+        self.touch(id);
+        Ok(self.inner.accept_message(id, message).await?)
+    }
+
+    async fn create_standalone_stream(
+        &self,
+        id: &SessionId,
+    ) -> Result<impl Stream<Item = ServerSseMessage> + Send + Sync + 'static, Self::Error> {
+        self.touch(id);
+        Ok(self.inner.create_standalone_stream(id).await?)
+    }
+
+    async fn resume(
+        &self,
+        id: &SessionId,
+        last_event_id: String,
+    ) -> Result<impl Stream<Item = ServerSseMessage> + Send + Sync + 'static, Self::Error> {
+        self.touch(id);
+        Ok(self.inner.resume(id, last_event_id).await?)
+    }
+}
+
+/// Answer a request that [`Sessions`] refused a new session with 503 and `Retry-After`
+/// instead of the transport's generic 500
+pub async fn refuse_when_full(request: Request, next: Next) -> Response {
+    let (refused, response) = REFUSED
+        .scope(Cell::new(false), async {
+            let response = next.run(request).await;
+            (REFUSED.with(Cell::get), response)
+        })
+        .await;
+    if !refused {
+        return response;
+    }
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        [(header::RETRY_AFTER, RETRY_AFTER_SECS)],
+        "Too many concurrent sessions",
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_idle_session_frees_its_slot() {
+        let sessions = Sessions::new(Some(1), Duration::from_secs(60));
+        let (first, _first_transport) = sessions.create_session().await.unwrap();
+        assert!(matches!(
+            sessions.create_session().await,
+            Err(SessionsError::Full)
+        ));
+
+        // A request keeps the session alive past the original deadline
+        tokio::time::advance(Duration::from_secs(45)).await;
+        sessions.touch(&first);
+        tokio::time::advance(Duration::from_secs(45)).await;
+        assert!(matches!(
+            sessions.create_session().await,
+            Err(SessionsError::Full)
+        ));
+
+        tokio::time::advance(Duration::from_secs(16)).await;
+        let (second, _second_transport) = sessions.create_session().await.unwrap();
+        assert!(!sessions.has_session(&first).await.unwrap());
+        assert!(sessions.has_session(&second).await.unwrap());
+    }
+}
@@ -0,0 +1,186 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Opt-in export of tracing spans to an OpenTelemetry collector over OTLP/HTTP.
+//!
+//! Export is enabled by setting `WASSETTE_OTLP_ENDPOINT` to the collector's base URL, for
+//! example `http://localhost:4318`. Without it no exporter is created and logging behaves as
+//! before.
+
+use anyhow::{Context, Result};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::trace::{SdkTracer, SdkTracerProvider};
+use opentelemetry_sdk::Resource;
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Environment variable holding the OTLP collector endpoint
+pub const OTLP_ENDPOINT_ENV: &str = "WASSETTE_OTLP_ENDPOINT";
+
+/// Path of the OTLP/HTTP traces signal, appended to the configured endpoint
+const TRACES_PATH: &str = "/v1/traces";
+
+const SERVICE_NAME: &str = "wassette";
+
+/// Flushes and shuts down the tracer provider when dropped
+pub struct TelemetryGuard {
+    provider: SdkTracerProvider,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.provider.shutdown() {
+            eprintln!("Failed to shut down OpenTelemetry exporter: {e}");
+        }
+    }
+}
+
+/// Build the OpenTelemetry layer if `WASSETTE_OTLP_ENDPOINT` is set.
+///
+/// The returned guard must be kept alive for as long as spans should be exported.
+pub fn otlp_layer<S>() -> Result<Option<(OpenTelemetryLayer<S, SdkTracer>, TelemetryGuard)>>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    match std::env::var(OTLP_ENDPOINT_ENV) {
+        Ok(endpoint) if !endpoint.trim().is_empty() => otlp_layer_for(endpoint.trim()).map(Some),
+        _ => Ok(None),
+    }
+}
+
+fn otlp_layer_for<S>(endpoint: &str) -> Result<(OpenTelemetryLayer<S, SdkTracer>, TelemetryGuard)>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(traces_endpoint(endpoint))
+        .build()
+        .with_context(|| format!("Failed to create OTLP exporter for {endpoint}"))?;
+
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name(SERVICE_NAME).build())
+        .build();
+    let tracer = provider.tracer(SERVICE_NAME);
+
+    Ok((
+        tracing_opentelemetry::layer().with_tracer(tracer),
+        TelemetryGuard { provider },
+    ))
+}
+
+/// The traces URL for a collector endpoint, accepting either the base URL or the full path
+fn traces_endpoint(endpoint: &str) -> String {
+    let endpoint = endpoint.trim_end_matches('/');
+    if endpoint.ends_with(TRACES_PATH) {
+        endpoint.to_string()
+    } else {
+        format!("{endpoint}{TRACES_PATH}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    use tracing_subscriber::layer::SubscriberExt as _;
+
+    use super::*;
+
+    /// Accept a single OTLP export request and report its path and body
+    fn mock_collector() -> (String, mpsc::Receiver<(String, Vec<u8>)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let path = request_line
+                .split_whitespace()
+                .nth(1)
+                .unwrap_or_default()
+                .to_string();
+
+            let mut content_length = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = header.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+
+            reader
+                .get_mut()
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+            tx.send((path, body)).unwrap();
+        });
+
+        (endpoint, rx)
+    }
+
+    #[test]
+    fn test_traces_endpoint() {
+        assert_eq!(
+            traces_endpoint("http://localhost:4318"),
+            "http://localhost:4318/v1/traces"
+        );
+        assert_eq!(
+            traces_endpoint("http://localhost:4318/"),
+            "http://localhost:4318/v1/traces"
+        );
+        assert_eq!(
+            traces_endpoint("http://collector/v1/traces"),
+            "http://collector/v1/traces"
+        );
+    }
+
+    #[test]
+    fn test_otlp_layer_disabled_without_endpoint() {
+        temp_env::with_var_unset(OTLP_ENDPOINT_ENV, || {
+            let layer = otlp_layer::<tracing_subscriber::Registry>().unwrap();
+            assert!(layer.is_none());
+        });
+    }
+
+    #[test]
+    fn test_tool_call_span_is_exported() {
+        let (endpoint, requests) = mock_collector();
+        let (layer, guard) = otlp_layer_for(&endpoint).unwrap();
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("tools/call", tool = "fetch");
+            let _entered = span.enter();
+        });
+        guard.provider.force_flush().unwrap();
+
+        let (path, body) = requests
+            .recv_timeout(Duration::from_secs(10))
+            .expect("collector received no export request");
+        assert_eq!(path, TRACES_PATH);
+        assert!(
+            body.windows(b"tools/call".len())
+                .any(|w| w == b"tools/call"),
+            "exported payload does not contain the span name"
+        );
+    }
+}
@@ -0,0 +1,218 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! `component tree`: every known component with its tools and granted permissions nested
+//! under it.
+
+use anyhow::Result;
+use mcp_server::LifecycleManager;
+use policy::{AccessType, NetworkPermission, PermissionList, PolicyDocument};
+use serde_json::{json, Map, Value};
+
+/// Nested description of every known component: its tools and a summary of the permissions
+/// it effectively runs with.
+pub async fn component_tree(lifecycle_manager: &LifecycleManager) -> Result<Value> {
+    let mut components = Vec::new();
+    for id in lifecycle_manager.list_components_known().await {
+        let tools: Vec<Value> = lifecycle_manager
+            .get_component_schema(&id)
+            .await
+            .and_then(|schema| schema.get("tools").and_then(Value::as_array).cloned())
+            .unwrap_or_default()
+            .iter()
+            .map(|tool| {
+                json!({
+                    "name": tool.get("name").cloned().unwrap_or(Value::Null),
+                    "description": tool.get("description").cloned().unwrap_or(Value::Null),
+                })
+            })
+            .collect();
+        let policy = lifecycle_manager.get_effective_policy(&id).await?;
+        components.push(json!({
+            "id": id,
+            "tools": tools,
+            "permissions": summarize_permissions(&policy),
+        }));
+    }
+    Ok(json!({ "components": components }))
+}
+
+/// One line per granted rule, grouped by permission type. Types without rules are left out.
+fn summarize_permissions(policy: &PolicyDocument) -> Value {
+    let permissions = &policy.permissions;
+    let mut summary = Map::new();
+    let mut add = |kind: &str, rules: Vec<String>| {
+        if !rules.is_empty() {
+            summary.insert(kind.to_string(), json!(rules));
+        }
+    };
+
+    add(
+        "network",
+        rules(&permissions.network, |rule| match rule {
+            NetworkPermission::Host(host) => host.host.clone(),
+            NetworkPermission::Cidr(cidr) => cidr.cidr.clone(),
+        }),
+    );
+    add(
+        "storage",
+        rules(&permissions.storage, |rule| {
+            let access: Vec<&str> = rule
+                .access
+                .iter()
+                .map(|access| match access {
+                    AccessType::Read => "read",
+                    AccessType::Write => "write",
+                })
+                .collect();
+            format!("{} ({})", rule.uri, access.join(", "))
+        }),
+    );
+    add(
+        "environment",
+        permissions
+            .environment
+            .iter()
+            .flat_map(|env| env.allow.iter().flatten())
+            .map(|rule| rule.key.clone())
+            .collect(),
+    );
+    add("ipc", rules(&permissions.ipc, |rule| rule.uri.clone()));
+
+    if let Some(limits) = permissions
+        .resources
+        .as_ref()
+        .and_then(|resources| resources.limits.as_ref())
+    {
+        let mut values = Vec::new();
+        if let Some(memory) = &limits.memory {
+            values.push(format!("memory {}", scalar(memory)));
+        }
+        if let Some(cpu) = &limits.cpu {
+            values.push(format!("cpu {}", scalar(cpu)));
+        }
+        add("resources", values);
+    }
+    Value::Object(summary)
+}
+
+/// Allowed rules followed by denied ones, which are marked as such
+fn rules<T>(list: &Option<PermissionList<T>>, describe: impl Fn(&T) -> String) -> Vec<String> {
+    let Some(list) = list else {
+        return Vec::new();
+    };
+    let allowed = list.allow.iter().flatten().map(&describe);
+    let denied = list
+        .deny
+        .iter()
+        .flatten()
+        .map(|rule| format!("{} (denied)", describe(rule)));
+    allowed.chain(denied).collect()
+}
+
+/// A serializable limit as written in a policy, without JSON quoting
+fn scalar(value: &impl serde::Serialize) -> String {
+    match serde_json::to_value(value) {
+        Ok(Value::String(s)) => s,
+        Ok(other) => other.to_string(),
+        Err(_) => String::new(),
+    }
+}
+
+/// Render the output of [`component_tree`] as an indented tree
+pub fn render_tree(tree: &Value) -> String {
+    let components = tree["components"].as_array().cloned().unwrap_or_default();
+    if components.is_empty() {
+        return "No components installed\n".to_string();
+    }
+
+    let mut out = String::new();
+    for component in &components {
+        out.push_str(component["id"].as_str().unwrap_or_default());
+        out.push('\n');
+
+        let tools: Vec<String> = component["tools"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|tool| tool["name"].as_str().map(String::from))
+            .collect();
+        let permissions: Vec<String> = component["permissions"]
+            .as_object()
+            .into_iter()
+            .flatten()
+            .flat_map(|(kind, rules)| {
+                rules
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_str)
+                    .map(move |rule| format!("{kind}: {rule}"))
+            })
+            .collect();
+
+        push_branch(&mut out, "tools", &tools, false);
+        push_branch(&mut out, "permissions", &permissions, true);
+    }
+    out
+}
+
+fn push_branch(out: &mut String, label: &str, children: &[String], last: bool) {
+    let (branch, indent) = if last {
+        ("└── ", "    ")
+    } else {
+        ("├── ", "│   ")
+    };
+    if children.is_empty() {
+        out.push_str(&format!("{branch}{label}: none\n"));
+        return;
+    }
+    out.push_str(&format!("{branch}{label}\n"));
+    for (i, child) in children.iter().enumerate() {
+        let leaf = if i + 1 == children.len() {
+            "└── "
+        } else {
+            "├── "
+        };
+        out.push_str(&format!("{indent}{leaf}{child}\n"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::answer_component;
+
+    #[tokio::test]
+    async fn test_tree_lists_component_tools_and_permissions() -> Result<()> {
+        let component_dir = tempfile::tempdir()?;
+        let source_dir = tempfile::tempdir()?;
+        let wasm = answer_component(source_dir.path(), "answer", 42, None);
+        let lifecycle_manager = LifecycleManager::new(component_dir.path()).await?;
+        lifecycle_manager
+            .load_component(&format!("file://{}", wasm.display()))
+            .await?;
+        lifecycle_manager
+            .grant_permission("answer", "network", &json!({"host": "api.example.com"}))
+            .await?;
+
+        let tree = component_tree(&lifecycle_manager).await?;
+        let component = &tree["components"][0];
+        assert_eq!(component["id"], "answer");
+        assert_eq!(component["tools"][0]["name"], "answer");
+        assert_eq!(
+            component["permissions"]["network"],
+            json!(["api.example.com"])
+        );
+
+        let expected = [
+            "answer",
+            "├── tools",
+            "│   └── answer",
+            "└── permissions",
+            "    └── network: api.example.com",
+        ];
+        assert_eq!(render_tree(&tree), expected.join("\n") + "\n");
+        Ok(())
+    }
+}
@@ -3,12 +3,12 @@
 
 //! Utility functions for the wassette command
 
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 
 use anyhow::{bail, Context, Result};
 
-use crate::registry;
+use crate::{config, registry};
 
 mod built_info {
     include!(concat!(env!("OUT_DIR"), "/built.rs"));
@@ -79,10 +79,85 @@ pub fn load_env_file(path: &PathBuf) -> Result<HashMap<String, String>, anyhow::
     Ok(env_vars)
 }
 
-/// Load and parse the component registry JSON
-pub fn load_component_registry() -> Result<Vec<registry::RegistryComponent>> {
+/// Gather secrets for `secret set` from a dotenv file, the current environment, and
+/// inline `KEY=VALUE` pairs.
+///
+/// Later sources take precedence: file values are overridden by `--from-env` values,
+/// which are in turn overridden by inline pairs. The result is sorted by key.
+pub fn collect_secrets(
+    inline: &[(String, String)],
+    from_file: Option<&PathBuf>,
+    from_env: &[String],
+) -> Result<Vec<(String, String)>> {
+    let mut secrets = BTreeMap::new();
+
+    if let Some(path) = from_file {
+        secrets.extend(load_env_file(path)?);
+    }
+
+    for key in from_env {
+        let value = std::env::var(key)
+            .with_context(|| format!("Environment variable '{key}' is not set"))?;
+        secrets.insert(key.clone(), value);
+    }
+
+    secrets.extend(inline.iter().cloned());
+
+    if secrets.is_empty() {
+        bail!("No secrets provided. Pass KEY=VALUE pairs, --from-file, or --from-env");
+    }
+
+    Ok(secrets.into_iter().collect())
+}
+
+/// OCI and HTTP clients for component downloads, routed through the proxy configured in the
+/// environment. Unlike the library defaults they set no overall request timeout, so large
+/// components can finish downloading.
+pub fn download_clients() -> Result<(oci_client::Client, reqwest::Client)> {
+    let mut http = reqwest::Client::builder();
+    let mut oci = oci_client::client::ClientConfig::default();
+    if let Some(proxy) = wassette::ProxyConfig::from_env() {
+        http = proxy.apply_to_http(http)?;
+        oci = proxy.apply_to_oci(oci);
+    }
+    let http = http.build().context("Failed to create HTTP client")?;
+    Ok((oci_client::Client::new(oci), http))
+}
+
+/// Read the default policy applied to every component, if one is configured
+pub fn load_default_policy(path: Option<&Path>) -> Result<Option<policy::PolicyDocument>> {
+    let Some(path) = path else {
+        return Ok(None);
+    };
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read default policy {}", path.display()))?;
+    let parsed = policy::PolicyParser::parse_str_checked(&content)
+        .with_context(|| format!("Invalid default policy {}", path.display()))?;
+    Ok(Some(parsed.document))
+}
+
+/// Bytes of the memory budget for loaded components, if one is configured
+pub fn parse_memory_budget(budget: Option<&str>) -> Result<Option<u64>> {
+    budget
+        .map(|budget| {
+            policy::MemoryLimit::String(budget.to_string())
+                .to_bytes()
+                .with_context(|| format!("Invalid max_loaded_memory '{budget}'"))
+        })
+        .transpose()
+}
+
+/// Load and parse the component registry JSON, from `registry_url` (through the local cache)
+/// when given and otherwise the index bundled with wassette
+pub async fn load_component_registry(
+    registry_url: Option<&str>,
+) -> Result<Vec<registry::RegistryComponent>> {
     const COMPONENT_REGISTRY: &str = include_str!("../component-registry.json");
-    registry::parse_registry(COMPONENT_REGISTRY).context("Failed to parse component registry")
+    match registry_url {
+        Some(url) => registry::load_registry(url, &config::get_registry_cache_dir()?).await,
+        None => registry::parse_registry(COMPONENT_REGISTRY)
+            .context("Failed to parse component registry"),
+    }
 }
 
 /// Formats build information similar to agentgateway's version output
@@ -141,6 +216,84 @@ mod tests {
         assert!(version_info.contains("GitRevision"));
     }
 
+    #[test]
+    fn test_collect_secrets_from_file() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().join("secrets.env");
+        std::fs::write(
+            &path,
+            "# comment\nAPI_KEY=\"secret123\"\nREGION=us-west-2\n",
+        )?;
+
+        let secrets = collect_secrets(&[], Some(&path), &[])?;
+        assert_eq!(
+            secrets,
+            vec![
+                ("API_KEY".to_string(), "secret123".to_string()),
+                ("REGION".to_string(), "us-west-2".to_string()),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_collect_secrets_from_env() {
+        temp_env::with_var("WASSETTE_TEST_SECRET_FROM_ENV", Some("from-env"), || {
+            let secrets =
+                collect_secrets(&[], None, &["WASSETTE_TEST_SECRET_FROM_ENV".to_string()]).unwrap();
+            assert_eq!(
+                secrets,
+                vec![(
+                    "WASSETTE_TEST_SECRET_FROM_ENV".to_string(),
+                    "from-env".to_string()
+                )]
+            );
+        });
+    }
+
+    #[test]
+    fn test_collect_secrets_missing_env_var() {
+        temp_env::with_var_unset("WASSETTE_TEST_SECRET_MISSING", || {
+            let error = collect_secrets(&[], None, &["WASSETTE_TEST_SECRET_MISSING".to_string()])
+                .unwrap_err();
+            assert!(error
+                .to_string()
+                .contains("Environment variable 'WASSETTE_TEST_SECRET_MISSING' is not set"));
+        });
+    }
+
+    #[test]
+    fn test_collect_secrets_precedence() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().join("secrets.env");
+        std::fs::write(&path, "SHARED=from-file\nFILE_ONLY=file\n")?;
+
+        temp_env::with_var("SHARED", Some("from-env"), || -> Result<()> {
+            let inline = vec![("INLINE".to_string(), "inline".to_string())];
+            let secrets = collect_secrets(&inline, Some(&path), &["SHARED".to_string()])?;
+            assert_eq!(
+                secrets,
+                vec![
+                    ("FILE_ONLY".to_string(), "file".to_string()),
+                    ("INLINE".to_string(), "inline".to_string()),
+                    ("SHARED".to_string(), "from-env".to_string()),
+                ]
+            );
+
+            let inline = vec![("SHARED".to_string(), "inline".to_string())];
+            let secrets = collect_secrets(&inline, Some(&path), &["SHARED".to_string()])?;
+            assert!(secrets.contains(&("SHARED".to_string(), "inline".to_string())));
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_collect_secrets_requires_input() {
+        let error = collect_secrets(&[], None, &[]).unwrap_err();
+        assert!(error.to_string().contains("No secrets provided"));
+    }
+
     #[test]
     fn test_version_contains_cargo_version() {
         let version_info = format_build_info();
@@ -12,7 +12,7 @@ use test_log::test;
 use tokio::process::Command as AsyncCommand;
 
 mod common;
-use common::build_fetch_component;
+use common::{build_fetch_component, build_filesystem_component};
 
 /// Helper struct for managing the test environment
 struct CliTestContext {
@@ -214,6 +214,7 @@ async fn test_cli_component_load_unload() -> Result<()> {
     let unload_output: Value = ctx.parse_json_output(&stdout)?;
     assert_eq!(unload_output["status"], "component unloaded successfully");
     assert_eq!(unload_output["id"], component_id);
+    assert_eq!(unload_output["unloaded"], true);
 
     // Verify component is no longer listed
     let (stdout, stderr, exit_code) = ctx.run_command(&["component", "list"]).await?;
@@ -249,12 +250,27 @@ async fn test_cli_component_load_invalid_path() -> Result<()> {
 async fn test_cli_component_unload_invalid_id() -> Result<()> {
     let ctx = CliTestContext::new().await?;
 
-    let (_stdout, _stderr, exit_code) = ctx
+    let (stdout, _stderr, exit_code) = ctx
         .run_command(&["component", "unload", "nonexistent-component"])
         .await?;
 
-    assert_eq!(exit_code, 0, "Command should succeed (idempotent behavior)");
-    // Unloading a non-existent component should succeed due to idempotent behavior
+    assert_ne!(exit_code, 0, "Unloading a missing component should fail");
+    assert!(stdout.contains("Component not found"));
+
+    // With --ignore-missing the same unload is a successful no-op
+    let (stdout, stderr, exit_code) = ctx
+        .run_command(&[
+            "component",
+            "unload",
+            "nonexistent-component",
+            "--ignore-missing",
+        ])
+        .await?;
+
+    assert_eq!(exit_code, 0, "Command failed with stderr: {stderr}");
+    let output: Value = ctx.parse_json_output(&stdout)?;
+    assert_eq!(output["unloaded"], false);
+    assert_eq!(output["id"], "nonexistent-component");
 
     Ok(())
 }
@@ -537,6 +553,33 @@ async fn test_cli_output_format_yaml() -> Result<()> {
     Ok(())
 }
 
+#[test(tokio::test)]
+async fn test_cli_tool_list_output_format_yaml() -> Result<()> {
+    let ctx = CliTestContext::new().await?;
+
+    let (stdout, stderr, exit_code) = ctx
+        .run_command(&["tool", "list", "--output-format", "yaml"])
+        .await?;
+
+    assert_eq!(exit_code, 0, "Command failed with stderr: {stderr}");
+
+    // The output must parse as YAML and carry the same structure as the JSON listing
+    let output: Value = serde_yaml::from_str(&stdout).context("Failed to parse YAML output")?;
+    let tools = output["tools"]
+        .as_array()
+        .expect("YAML output should contain a 'tools' sequence");
+    assert!(
+        tools.iter().any(|tool| tool["name"] == "load-component"),
+        "YAML tool listing should include built-in tools"
+    );
+    assert!(
+        serde_json::from_str::<Value>(stdout.trim()).is_err(),
+        "YAML output should not be emitted as JSON"
+    );
+
+    Ok(())
+}
+
 #[test(tokio::test)]
 async fn test_cli_output_format_table() -> Result<()> {
     let ctx = CliTestContext::new().await?;
@@ -900,3 +943,152 @@ async fn test_cli_autocomplete_includes_all_commands() -> Result<()> {
 
     Ok(())
 }
+
+#[test(tokio::test)]
+async fn test_cli_tool_invoke_repeat_timing() -> Result<()> {
+    let ctx = CliTestContext::new().await?;
+    let component_path = build_filesystem_component().await?;
+
+    let (_, stderr, exit_code) = ctx
+        .run_command(&[
+            "component",
+            "load",
+            &format!("file://{}", component_path.display()),
+        ])
+        .await?;
+    assert_eq!(exit_code, 0, "Load command failed with stderr: {stderr}");
+
+    let (stdout, stderr, exit_code) = ctx
+        .run_command(&[
+            "tool",
+            "invoke",
+            "file-exists",
+            "--args",
+            r#"{"path": "/does-not-exist"}"#,
+            "--repeat",
+            "3",
+            "--timing",
+        ])
+        .await?;
+    assert_eq!(exit_code, 0, "Invoke command failed with stderr: {stderr}");
+
+    let report = ctx.parse_json_output(&stdout)?;
+    assert_eq!(report["tool"], "file-exists");
+    assert_eq!(report["iterations"], 3);
+    assert_eq!(report["errors"], 0);
+    assert_eq!(report["calls"].as_array().unwrap().len(), 3);
+    for key in ["min_ms", "max_ms", "mean_ms", "p95_ms"] {
+        assert!(report["summary"][key].is_f64(), "missing summary {key}");
+    }
+
+    Ok(())
+}
+
+/// Append a custom section to a wasm binary, as a component's build tooling would
+fn append_custom_section(wasm: &mut Vec<u8>, name: &str, payload: &[u8]) {
+    fn leb128(out: &mut Vec<u8>, mut value: usize) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                out.push(byte);
+                return;
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    let mut section = Vec::new();
+    leb128(&mut section, name.len());
+    section.extend_from_slice(name.as_bytes());
+    section.extend_from_slice(payload);
+    wasm.push(0);
+    leb128(wasm, section.len());
+    wasm.extend(section);
+}
+
+#[test(tokio::test)]
+async fn test_cli_component_audit_fix_allows_declared_network_call() -> Result<()> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let ctx = CliTestContext::new().await?;
+
+    // A local server standing in for the host the component declares it needs
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("http://{}/", listener.local_addr()?);
+    let (tx, mut requests) = tokio::sync::mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf).await;
+            let _ = tx.send(());
+            let _ = stream
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 16\r\nconnection: close\r\n\r\nhello-from-audit")
+                .await;
+        }
+    });
+
+    // The fetch component, declaring the local server as the host it needs
+    let mut wasm = tokio::fs::read(build_fetch_component().await?).await?;
+    append_custom_section(
+        &mut wasm,
+        "wassette:permissions",
+        br#"{"network": [{"host": "127.0.0.1"}]}"#,
+    );
+    let source_dir = tempfile::tempdir()?;
+    let component_path = source_dir.path().join("fetch_rs.wasm");
+    tokio::fs::write(&component_path, wasm).await?;
+    let (stdout, stderr, exit_code) = ctx
+        .run_command(&[
+            "component",
+            "load",
+            &format!("file://{}", component_path.display()),
+        ])
+        .await?;
+    assert_eq!(exit_code, 0, "Load command failed with stderr: {stderr}");
+    let component_id = ctx.parse_json_output(&stdout)?["id"]
+        .as_str()
+        .context("load output has no id")?
+        .to_string();
+
+    let fetch_args = serde_json::json!({ "url": url }).to_string();
+    let (stdout, _, _) = ctx
+        .run_command(&["tool", "invoke", "fetch", "--args", &fetch_args])
+        .await?;
+    assert!(
+        !stdout.contains("hello-from-audit"),
+        "fetch succeeded before the fix: {stdout}"
+    );
+    assert!(
+        requests.try_recv().is_err(),
+        "denied request reached the server"
+    );
+
+    let (stdout, stderr, exit_code) = ctx
+        .run_command(&["component", "audit", &component_id, "--fix", "--yes"])
+        .await?;
+    assert_eq!(exit_code, 0, "Audit command failed with stderr: {stderr}");
+    let audit = ctx.parse_json_output(&stdout)?;
+    assert!(
+        !audit["gaps"]
+            .as_array()
+            .context("audit output has no gaps")?
+            .contains(&Value::from("network")),
+        "{audit}"
+    );
+
+    let (stdout, stderr, exit_code) = ctx
+        .run_command(&["tool", "invoke", "fetch", "--args", &fetch_args])
+        .await?;
+    assert_eq!(exit_code, 0, "Invoke command failed with stderr: {stderr}");
+    assert!(
+        stdout.contains("hello-from-audit"),
+        "fetch failed after the fix: {stdout}"
+    );
+    requests
+        .recv()
+        .await
+        .context("the server saw no request after the fix")?;
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! The `answer` component used throughout the workspace's tests. Integration tests reach it
+//! through `common`; crates include this file as a module in their unit tests.
+
+use std::path::{Path, PathBuf};
+
+/// Write a component to `dir/<name>.wasm` whose `answer` tool returns `answer` and, when
+/// `version` is given, whose `version` tool returns it
+#[allow(dead_code)]
+pub fn answer_component(dir: &Path, name: &str, answer: u32, version: Option<u32>) -> PathBuf {
+    let version_tool = version
+        .map(|version| {
+            format!(
+                r#"
+            (core module $v (func (export "version") (result i32) (i32.const {version})))
+            (core instance $vi (instantiate $v))
+            (func $version (result u32) (canon lift (core func $vi "version")))
+            (export "version" (func $version))"#
+            )
+        })
+        .unwrap_or_default();
+    let wat = format!(
+        r#"(component
+            (core module $m (func (export "answer") (result i32) (i32.const {answer})))
+            (core instance $i (instantiate $m))
+            (func $answer (result u32) (canon lift (core func $i "answer")))
+            (export "answer" (func $answer)){version_tool}
+        )"#
+    );
+    let wasm = dir.join(format!("{name}.wasm"));
+    std::fs::write(&wasm, wat::parse_str(wat).unwrap()).unwrap();
+    wasm
+}
@@ -6,6 +6,8 @@ use std::sync::Once;
 
 use anyhow::{Context, Result};
 
+pub mod answer;
+
 static FETCH_COMPONENT_BUILD: Once = Once::new();
 static FILESYSTEM_COMPONENT_BUILD: Once = Once::new();
 
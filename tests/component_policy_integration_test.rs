@@ -5,7 +5,9 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::{Context, Result};
-use serde_json::json;
+use mcp_server::tools::handle_get_policy;
+use rmcp::model::CallToolRequestParam;
+use serde_json::{json, Value};
 use tempfile::TempDir;
 use test_log::test;
 use wassette::LifecycleManager;
@@ -306,3 +308,111 @@ async fn test_tools_listing_integration() -> Result<()> {
 
     Ok(())
 }
+
+async fn get_policy_output(manager: &LifecycleManager, component_id: &str) -> Result<Value> {
+    let req = CallToolRequestParam {
+        name: "get-policy".into(),
+        arguments: Some(
+            json!({"component_id": component_id})
+                .as_object()
+                .unwrap()
+                .clone(),
+        ),
+    };
+    let result = handle_get_policy(&req, manager).await?;
+    let text = result.content[0]
+        .as_text()
+        .context("get-policy should return text content")?;
+    Ok(serde_json::from_str(&text.text)?)
+}
+
+#[test(tokio::test)]
+async fn test_get_policy_reports_source_and_path() -> Result<()> {
+    let (manager, temp_dir) = setup_lifecycle_manager().await?;
+    let component_path = build_fetch_component().await?;
+    let component_id = manager
+        .load_component(&format!("file://{}", component_path.display()))
+        .await?
+        .component_id;
+
+    let output = get_policy_output(&manager, &component_id).await?;
+    assert_eq!(output["status"], "no policy found");
+    assert_eq!(output["policy_info"]["source"], "default");
+
+    manager
+        .grant_permission(&component_id, "network", &json!({"host": "example.com"}))
+        .await?;
+    let output = get_policy_output(&manager, &component_id).await?;
+    let policy_path = manager
+        .get_policy_info(&component_id)
+        .await
+        .context("Policy should exist after a grant")?
+        .local_path;
+    assert_eq!(output["policy_info"]["source"], "inline");
+    assert_eq!(
+        output["policy_info"]["local_path"],
+        json!(policy_path.display().to_string())
+    );
+    assert!(output["policy_info"]["modified_at"].is_u64());
+    assert_eq!(
+        output["policy"]["permissions"]["network"]["allow"][0]["host"],
+        "example.com"
+    );
+
+    let attached_policy = temp_dir.path().join("attached.policy.yaml");
+    tokio::fs::write(
+        &attached_policy,
+        "version: \"1.0\"\npermissions:\n  network:\n    allow:\n      - host: \"api.example.com\"\n",
+    )
+    .await?;
+    let attached_uri = format!("file://{}", attached_policy.display());
+    manager.attach_policy(&component_id, &attached_uri).await?;
+
+    let output = get_policy_output(&manager, &component_id).await?;
+    assert_eq!(output["policy_info"]["source"], "attached");
+    assert_eq!(output["policy_info"]["source_uri"], json!(attached_uri));
+    assert_eq!(
+        output["policy_info"]["local_path"],
+        json!(policy_path.display().to_string())
+    );
+    assert_eq!(
+        output["policy"]["permissions"]["network"]["allow"][0]["host"],
+        "api.example.com"
+    );
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_get_policy_returns_unparsable_policy_as_raw_text() -> Result<()> {
+    let (manager, _temp_dir) = setup_lifecycle_manager().await?;
+    let component_path = build_fetch_component().await?;
+    let component_id = manager
+        .load_component(&format!("file://{}", component_path.display()))
+        .await?
+        .component_id;
+    manager
+        .grant_permission(&component_id, "network", &json!({"host": "example.com"}))
+        .await?;
+    let policy_path = manager
+        .get_policy_info(&component_id)
+        .await
+        .context("Policy should exist after a grant")?
+        .local_path;
+
+    let broken = "version: \"1.0\"\npermissions: [network\n";
+    tokio::fs::write(&policy_path, broken).await?;
+
+    let output = get_policy_output(&manager, &component_id).await?;
+    assert_eq!(output["status"], "policy found");
+    assert_eq!(output["policy_info"]["source"], "inline");
+    assert_eq!(output["policy_raw"], broken);
+    assert!(output.get("policy").is_none());
+    let warning = output["warnings"][0].as_str().unwrap();
+    assert!(
+        warning.starts_with("Failed to parse policy file"),
+        "{warning}"
+    );
+
+    Ok(())
+}
@@ -248,3 +248,316 @@ async fn test_fetch_with_scheme_specific_permissions() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_granted_host_resolving_to_loopback_is_blocked() -> Result<()> {
+    let component_path = build_fetch_component().await?;
+    let (base_url, mut requests) = spawn_mock_server().await?;
+    // `localhost` is a granted name whose address is internal, as after a DNS rebinding
+    let url = base_url.replace("127.0.0.1", "localhost");
+
+    for allow_internal_addresses in [false, true] {
+        let tempdir = tempfile::tempdir()?;
+        let manager = LifecycleManager::builder(tempdir.path())
+            .with_outbound_http(wassette::OutboundHttpConfig {
+                allow_internal_addresses,
+                ..Default::default()
+            })
+            .build()
+            .await?;
+        let component_id = manager
+            .load_component(&format!("file://{}", component_path.to_str().unwrap()))
+            .await?
+            .component_id;
+        manager
+            .grant_permission(
+                &component_id,
+                "network",
+                &serde_json::json!({"host": "localhost"}),
+            )
+            .await?;
+
+        let response = manager
+            .execute_component_call(
+                &component_id,
+                "fetch",
+                &serde_json::json!({"url": format!("{url}/ok")}).to_string(),
+            )
+            .await;
+        if allow_internal_addresses {
+            let response = response?;
+            assert!(
+                response.contains("hello-from-mock"),
+                "unexpected response: {response}"
+            );
+            requests
+                .recv()
+                .await
+                .context("mock server saw no request")?;
+        } else {
+            if let Ok(response) = &response {
+                assert!(
+                    !response.contains("hello-from-mock"),
+                    "request to an internal address should be blocked: {response}"
+                );
+            }
+            assert!(
+                requests.try_recv().is_err(),
+                "blocked request reached the server"
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_safe_mode_ignores_network_grants() -> Result<()> {
+    let tempdir = tempfile::tempdir()?;
+    let manager = LifecycleManager::builder(tempdir.path())
+        .with_safe_mode(true)
+        .build()
+        .await?;
+    let component_path = build_fetch_component().await?;
+    let component_id = manager
+        .load_component(&format!("file://{}", component_path.to_str().unwrap()))
+        .await?
+        .component_id;
+
+    let err = manager
+        .grant_permission(
+            &component_id,
+            "network",
+            &serde_json::json!({"host": "127.0.0.1"}),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Grants disabled in safe mode"));
+
+    // A policy attached from a file grants the host, but safe mode ignores it
+    let policy_dir = tempfile::tempdir()?;
+    let policy_path = policy_dir.path().join("policy.yaml");
+    std::fs::write(
+        &policy_path,
+        "version: \"1.0\"\npermissions:\n  network:\n    allow:\n      - host: \"127.0.0.1\"\n",
+    )?;
+    manager
+        .attach_policy(
+            &component_id,
+            &format!("file://{}", policy_path.to_str().unwrap()),
+        )
+        .await?;
+
+    let (base_url, mut requests) = spawn_mock_server().await?;
+    let response = manager
+        .execute_component_call(
+            &component_id,
+            "fetch",
+            &serde_json::json!({"url": format!("{base_url}/ok")}).to_string(),
+        )
+        .await;
+    if let Ok(response) = &response {
+        assert!(
+            !response.contains("hello-from-mock"),
+            "safe mode should block the request: {response}"
+        );
+    }
+    assert!(
+        requests.try_recv().is_err(),
+        "blocked request reached the server"
+    );
+
+    Ok(())
+}
+
+/// Serve `/slow` after a long delay and everything else immediately, reporting each request head
+async fn spawn_mock_server() -> Result<(String, tokio::sync::mpsc::UnboundedReceiver<String>)> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let base_url = format!("http://{}", listener.local_addr()?);
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let mut head = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match stream.read(&mut buf).await {
+                        Ok(0) | Err(_) => return,
+                        Ok(n) => head.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let head = String::from_utf8_lossy(&head).to_string();
+                let slow = head.starts_with("GET /slow ");
+                let _ = tx.send(head);
+                if slow {
+                    tokio::time::sleep(std::time::Duration::from_secs(10)).await;
+                }
+                let _ = stream
+                    .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 15\r\nconnection: close\r\n\r\nhello-from-mock")
+                    .await;
+            });
+        }
+    });
+
+    Ok((base_url, rx))
+}
+
+#[tokio::test]
+async fn test_outbound_user_agent_and_request_timeout() -> Result<()> {
+    let tempdir = tempfile::tempdir()?;
+    let manager = LifecycleManager::builder(tempdir.path())
+        .with_outbound_http(wassette::OutboundHttpConfig {
+            user_agent: Some("acme-agent/2.0".to_string()),
+            request_timeout: Some(std::time::Duration::from_millis(500)),
+            ..Default::default()
+        })
+        .build()
+        .await?;
+    let component_path = build_fetch_component().await?;
+    let component_id = manager
+        .load_component(&format!("file://{}", component_path.to_str().unwrap()))
+        .await?
+        .component_id;
+    manager
+        .grant_permission(
+            &component_id,
+            "network",
+            &serde_json::json!({"host": "127.0.0.1"}),
+        )
+        .await?;
+
+    let (base_url, mut requests) = spawn_mock_server().await?;
+
+    let response = manager
+        .execute_component_call(
+            &component_id,
+            "fetch",
+            &serde_json::json!({"url": format!("{base_url}/ok")}).to_string(),
+        )
+        .await?;
+    assert!(
+        response.contains("hello-from-mock"),
+        "unexpected response: {response}"
+    );
+    let head = requests
+        .recv()
+        .await
+        .context("mock server saw no request")?;
+    assert!(
+        head.to_ascii_lowercase()
+            .contains("user-agent: acme-agent/2.0\r\n"),
+        "configured User-Agent missing from request: {head}"
+    );
+
+    let started = std::time::Instant::now();
+    let slow = manager
+        .execute_component_call(
+            &component_id,
+            "fetch",
+            &serde_json::json!({"url": format!("{base_url}/slow")}).to_string(),
+        )
+        .await?;
+    assert!(
+        started.elapsed() < std::time::Duration::from_secs(5),
+        "slow response was not cut off by the timeout"
+    );
+    // The component sees the timeout as a failed request and returns it as its error
+    let slow: serde_json::Value = serde_json::from_str(&slow)?;
+    let error = slow["result"]["err"]
+        .as_str()
+        .with_context(|| format!("slow request did not fail: {slow}"))?;
+    assert!(
+        error.to_ascii_lowercase().contains("timeout"),
+        "slow request failed with something other than a timeout: {error}"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_egress_bytes_are_accounted_per_component() -> Result<()> {
+    let (manager, _tempdir) = setup_lifecycle_manager().await?;
+    let component_path = build_fetch_component().await?;
+    let component_id = manager
+        .load_component(&format!("file://{}", component_path.to_str().unwrap()))
+        .await?
+        .component_id;
+    manager
+        .grant_permission(
+            &component_id,
+            "network",
+            &serde_json::json!({"host": "127.0.0.1"}),
+        )
+        .await?;
+    assert_eq!(manager.get_component_stats(&component_id), None);
+
+    let (base_url, _requests) = spawn_mock_server().await?;
+    for _ in 0..2 {
+        let response = manager
+            .execute_component_call(
+                &component_id,
+                "fetch",
+                &serde_json::json!({"url": format!("{base_url}/ok")}).to_string(),
+            )
+            .await?;
+        assert!(response.contains("hello-from-mock"));
+    }
+
+    // The mock server answers with a 15-byte body
+    let stats = manager
+        .get_component_stats(&component_id)
+        .context("no stats recorded")?;
+    assert_eq!(stats.calls, 2);
+    assert_eq!(stats.last_call_egress_bytes, 15);
+    assert_eq!(stats.egress_bytes, 30);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_egress_byte_limit_aborts_the_call() -> Result<()> {
+    let tempdir = tempfile::tempdir()?;
+    let manager = LifecycleManager::builder(tempdir.path())
+        .with_egress_byte_limit(Some(10))
+        .build()
+        .await?;
+    let component_path = build_fetch_component().await?;
+    let component_id = manager
+        .load_component(&format!("file://{}", component_path.to_str().unwrap()))
+        .await?
+        .component_id;
+    manager
+        .grant_permission(
+            &component_id,
+            "network",
+            &serde_json::json!({"host": "127.0.0.1"}),
+        )
+        .await?;
+
+    let (base_url, _requests) = spawn_mock_server().await?;
+    let err = manager
+        .execute_component_call(
+            &component_id,
+            "fetch",
+            &serde_json::json!({"url": format!("{base_url}/ok")}).to_string(),
+        )
+        .await
+        .expect_err("a 15-byte body is over the 10-byte limit");
+    assert!(
+        err.to_string()
+            .contains("exceeded the egress limit of 10 bytes"),
+        "unexpected error: {err:#}"
+    );
+    assert_eq!(
+        manager
+            .get_component_stats(&component_id)
+            .map(|stats| stats.calls),
+        Some(1)
+    );
+
+    Ok(())
+}
@@ -458,3 +458,111 @@ async fn test_filesystem_component_lifecycle_manager() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+#[test(tokio::test)]
+async fn test_per_call_scratch_is_cleared_between_calls() -> Result<()> {
+    let tempdir = tempfile::tempdir()?;
+    let manager = LifecycleManager::builder(tempdir.path())
+        .with_per_call_scratch(true)
+        .build()
+        .await?;
+
+    let component_path = build_filesystem_component().await?;
+    let id = manager
+        .load_component(&format!("file://{}", component_path.to_str().unwrap()))
+        .await?
+        .component_id;
+
+    let scratch_file = format!("{}/note.txt", wassette::SCRATCH_GUEST_PATH);
+    let write_result = manager
+        .execute_component_call(
+            &id,
+            "write-file",
+            &serde_json::json!({"path": scratch_file, "content": "temporary"}).to_string(),
+        )
+        .await?;
+    let write_result: serde_json::Value = serde_json::from_str(&write_result)?;
+    assert!(
+        write_result["result"]["ok"].is_string(),
+        "Writing to scratch should succeed: {write_result}"
+    );
+
+    let exists_result = manager
+        .execute_component_call(
+            &id,
+            "file-exists",
+            &serde_json::json!({"path": scratch_file}).to_string(),
+        )
+        .await?;
+    let exists_result: serde_json::Value = serde_json::from_str(&exists_result)?;
+    assert_eq!(
+        exists_result["result"]["ok"],
+        serde_json::Value::Bool(false),
+        "File written during a previous call should be gone: {exists_result}"
+    );
+
+    let leftover = std::fs::read_dir(tempdir.path().join("scratch"))?.count();
+    assert_eq!(
+        leftover, 0,
+        "Scratch directories should be removed after each call"
+    );
+
+    Ok(())
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+#[test(tokio::test)]
+async fn test_component_directory_is_never_preopened() -> Result<()> {
+    let (manager, tempdir) = setup_lifecycle_manager().await?;
+    let component_path = build_filesystem_component().await?;
+    let id = manager
+        .load_component(&format!("file://{}", component_path.to_str().unwrap()))
+        .await?
+        .component_id;
+
+    // A dedicated data directory inside the component directory may still be granted
+    let data_dir = tempdir.path().join("data");
+    std::fs::create_dir_all(&data_dir)?;
+    manager
+        .grant_permission(
+            &id,
+            "storage",
+            &serde_json::json!({"uri": format!("fs://{}", data_dir.display()), "access": ["read"]}),
+        )
+        .await?;
+
+    let grant_component_dir = manager
+        .grant_permission(
+            &id,
+            "storage",
+            &serde_json::json!({"uri": format!("fs://{}", tempdir.path().display()), "access": ["read"]}),
+        )
+        .await;
+    assert!(
+        grant_component_dir.is_err(),
+        "Granting the component directory itself must be refused"
+    );
+
+    for path in [
+        tempdir.path().join(format!("{id}.wasm")),
+        data_dir.join("..").join(format!("{id}.policy.yaml")),
+    ] {
+        let result = manager
+            .execute_component_call(
+                &id,
+                "read-file",
+                &serde_json::json!({"path": path.display().to_string()}).to_string(),
+            )
+            .await;
+        let denied = match result {
+            Ok(response) => serde_json::from_str::<serde_json::Value>(&response)?["result"]
+                .get("err")
+                .is_some(),
+            Err(_) => true,
+        };
+        assert!(denied, "Reading {} should be denied", path.display());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,153 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Reloading an OCI reference whose manifest digest has not changed reuses the cached download
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use anyhow::Result;
+use oci_client::client::{ClientConfig, ClientProtocol};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use wassette::LifecycleManager;
+
+mod common;
+use common::answer::answer_component;
+
+const MANIFEST_MEDIA_TYPE: &str = "application/vnd.oci.image.manifest.v1+json";
+
+fn digest(data: &[u8]) -> String {
+    format!("sha256:{}", hex::encode(Sha256::digest(data)))
+}
+
+/// Serve a single-layer Wasm artifact as `test/answer:v1` over plain HTTP, counting blob
+/// downloads. Returns the registry's `host:port`.
+async fn spawn_mock_registry(wasm: Vec<u8>) -> Result<(String, Arc<AtomicUsize>)> {
+    let layer_digest = digest(&wasm);
+    let config = json!({
+        "created": "2024-09-25T12:00:00Z",
+        "architecture": "wasm",
+        "os": "wasip2",
+        "layerDigests": [layer_digest],
+    })
+    .to_string()
+    .into_bytes();
+    let config_digest = digest(&config);
+    let manifest = json!({
+        "schemaVersion": 2,
+        "mediaType": MANIFEST_MEDIA_TYPE,
+        "config": {
+            "mediaType": "application/vnd.wasm.config.v0+json",
+            "digest": config_digest,
+            "size": config.len(),
+        },
+        "layers": [{
+            "mediaType": "application/wasm",
+            "digest": layer_digest,
+            "size": wasm.len(),
+        }],
+    })
+    .to_string()
+    .into_bytes();
+    let manifest_digest = digest(&manifest);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?.to_string();
+    let blob_pulls = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&blob_pulls);
+
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut head = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                match stream.read(&mut buf).await {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => head.extend_from_slice(&buf[..n]),
+                }
+            }
+            let head = String::from_utf8_lossy(&head).to_string();
+            let mut request_line = head.split_whitespace();
+            let method = request_line.next().unwrap_or_default();
+            let path = request_line.next().unwrap_or_default();
+
+            let (content_type, body): (&str, &[u8]) = if path == "/v2/" {
+                ("application/json", b"{}")
+            } else if path.starts_with("/v2/test/answer/manifests/") {
+                (MANIFEST_MEDIA_TYPE, &manifest)
+            } else if let Some(blob) = path.strip_prefix("/v2/test/answer/blobs/") {
+                counter.fetch_add(1, Ordering::SeqCst);
+                if blob == layer_digest {
+                    ("application/octet-stream", &wasm)
+                } else if blob == config_digest {
+                    ("application/octet-stream", &config)
+                } else {
+                    ("text/plain", b"")
+                }
+            } else {
+                ("text/plain", b"")
+            };
+            let status = if body.is_empty() {
+                "404 Not Found"
+            } else {
+                "200 OK"
+            };
+            let mut response = format!(
+                "HTTP/1.1 {status}\r\ncontent-type: {content_type}\r\ncontent-length: {}\r\ndocker-content-digest: {manifest_digest}\r\nconnection: close\r\n\r\n",
+                body.len()
+            )
+            .into_bytes();
+            if method != "HEAD" {
+                response.extend_from_slice(body);
+            }
+            let _ = stream.write_all(&response).await;
+        }
+    });
+
+    Ok((address, blob_pulls))
+}
+
+fn http_oci_client() -> oci_client::Client {
+    oci_client::Client::new(ClientConfig {
+        protocol: ClientProtocol::Http,
+        ..Default::default()
+    })
+}
+
+#[tokio::test]
+async fn test_unchanged_oci_reference_is_not_downloaded_again() -> Result<()> {
+    let source_dir = tempfile::tempdir()?;
+    let wasm = std::fs::read(answer_component(source_dir.path(), "answer", 42, None))?;
+    let (registry, blob_pulls) = spawn_mock_registry(wasm).await?;
+    let uri = format!("oci://{registry}/test/answer:v1");
+    let component_dir = tempfile::tempdir()?;
+
+    let manager = LifecycleManager::builder(component_dir.path())
+        .with_oci_client(http_oci_client())
+        .with_eager_loading(false)
+        .build()
+        .await?;
+    let first = manager.load_component(&uri).await?;
+    assert_eq!(first.tool_names, vec!["answer"]);
+    let pulled = blob_pulls.load(Ordering::SeqCst);
+    assert!(pulled > 0, "the first load should download the blobs");
+    drop(manager);
+
+    // A restarted server finds the digest unchanged and only checks the manifest
+    let manager = LifecycleManager::builder(component_dir.path())
+        .with_oci_client(http_oci_client())
+        .with_eager_loading(false)
+        .build()
+        .await?;
+    let second = manager.load_component(&uri).await?;
+    assert_eq!(second.component_id, first.component_id);
+    assert_eq!(second.tool_names, vec!["answer"]);
+    assert_eq!(
+        blob_pulls.load(Ordering::SeqCst),
+        pulled,
+        "the second load should not download any blob"
+    );
+    Ok(())
+}
@@ -668,3 +668,45 @@ mod real_registry_digest_tests {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod registry_name_tests {
+    use mcp_server::components::{handle_load_component_cli, resolve_component_reference};
+    use rmcp::model::CallToolRequestParam;
+
+    use super::*;
+
+    /// Loading by registry name resolves the bundled registry entry and loads its URI
+    #[tokio::test]
+    async fn test_load_component_by_registry_name() -> Result<()> {
+        assert_eq!(
+            resolve_component_reference("Fetch")?,
+            "oci://ghcr.io/microsoft/fetch-rs:latest"
+        );
+
+        if !is_registry_operational("https://ghcr.io").await {
+            eprintln!("⚠️  Skipping test: ghcr.io not reachable");
+            return Ok(());
+        }
+
+        let temp_dir = tempfile::tempdir()?;
+        let manager = LifecycleManager::new(temp_dir.path()).await?;
+
+        let req = CallToolRequestParam {
+            name: "load-component".into(),
+            arguments: Some(json!({"path": "Fetch"}).as_object().unwrap().clone()),
+        };
+        let result = handle_load_component_cli(&req, &manager).await?;
+        assert_ne!(result.is_error, Some(true));
+
+        let components = manager.list_components().await;
+        assert_eq!(components.len(), 1);
+        assert!(
+            components[0].ends_with("fetch-rs"),
+            "Unexpected component id: {}",
+            components[0]
+        );
+
+        Ok(())
+    }
+}
@@ -26,7 +26,7 @@ use tokio::time::sleep;
 use wassette::LifecycleManager;
 
 mod common;
-use common::build_fetch_component;
+use common::{build_fetch_component, build_filesystem_component};
 
 const DOCKER_REGISTRY_PORT: u16 = 5000;
 
@@ -879,6 +879,133 @@ async fn test_tool_list_notification() -> Result<()> {
     Ok(())
 }
 
+#[test(tokio::test)]
+async fn test_load_components_batch_sends_single_notification() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let component_dir_arg = format!("--component-dir={}", temp_dir.path().display());
+
+    let binary_path = std::env::current_dir()
+        .context("Failed to get current directory")?
+        .join("target/debug/wassette");
+
+    let mut child = tokio::process::Command::new(&binary_path)
+        .args(["run", &component_dir_arg])
+        .env("RUST_LOG", "off")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to start wassette with stdio transport")?;
+
+    let mut stdin = child.stdin.take().context("Failed to get stdin handle")?;
+    let mut stdout = BufReader::new(child.stdout.take().context("Failed to get stdout handle")?);
+
+    tokio::time::sleep(Duration::from_millis(1000)).await;
+
+    let initialize_request = r#"{"jsonrpc": "2.0", "method": "initialize", "params": {"protocolVersion": "2024-11-05", "capabilities": {}, "clientInfo": {"name": "test-client", "version": "1.0.0"}}, "id": 1}
+{"jsonrpc": "2.0", "method": "notifications/initialized", "params": {}}
+"#;
+    stdin.write_all(initialize_request.as_bytes()).await?;
+    stdin.flush().await?;
+
+    let mut line = String::new();
+    tokio::time::timeout(Duration::from_secs(30), stdout.read_line(&mut line))
+        .await
+        .context("Timeout waiting for initialize response")?
+        .context("Failed to read initialize response")?;
+
+    let fetch_path = build_fetch_component().await?;
+    let filesystem_path = build_filesystem_component().await?;
+    let load_request = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "tools/call",
+        "params": {
+            "name": "load-components",
+            "arguments": {
+                "components": [
+                    format!("file://{}", fetch_path.display()),
+                    { "path": format!("file://{}", filesystem_path.display()) }
+                ]
+            }
+        },
+        "id": 2
+    });
+    stdin
+        .write_all(format!("{load_request}\n").as_bytes())
+        .await?;
+    stdin.flush().await?;
+
+    // Collect everything up to the batch response, counting notifications on the way
+    let mut notifications = 0;
+    let load_response = loop {
+        let mut line = String::new();
+        tokio::time::timeout(Duration::from_secs(60), stdout.read_line(&mut line))
+            .await
+            .context("Timeout waiting for load-components response")?
+            .context("Failed to read load-components response")?;
+        let message: serde_json::Value = serde_json::from_str(&line)?;
+        if message["method"] == "notifications/tools/list_changed" {
+            notifications += 1;
+        } else if message["id"] == 2 {
+            break message;
+        }
+    };
+
+    let text = load_response["result"]["content"][0]["text"]
+        .as_str()
+        .context("load-components response has no text content")?;
+    let report: serde_json::Value = serde_json::from_str(text)?;
+    assert_eq!(report["loaded"], 2, "Unexpected report: {report}");
+    assert_eq!(report["failed"], 0);
+
+    let list_request = r#"{"jsonrpc": "2.0", "method": "tools/list", "params": {}, "id": 3}
+"#;
+    stdin.write_all(list_request.as_bytes()).await?;
+    stdin.flush().await?;
+
+    let list_response = loop {
+        let mut line = String::new();
+        tokio::time::timeout(Duration::from_secs(30), stdout.read_line(&mut line))
+            .await
+            .context("Timeout waiting for tools/list response")?
+            .context("Failed to read tools/list response")?;
+        let message: serde_json::Value = serde_json::from_str(&line)?;
+        if message["method"] == "notifications/tools/list_changed" {
+            notifications += 1;
+        } else if message["id"] == 3 {
+            break message;
+        }
+    };
+    assert_eq!(
+        notifications, 1,
+        "Expected exactly one tool list notification"
+    );
+
+    let tool_names: Vec<&str> = list_response["result"]["tools"]
+        .as_array()
+        .context("tools/list response has no tools")?
+        .iter()
+        .filter_map(|tool| tool["name"].as_str())
+        .collect();
+    for result in report["results"].as_array().unwrap() {
+        let tools = result["tools"].as_array().unwrap();
+        assert!(
+            !tools.is_empty(),
+            "Component loaded without tools: {result}"
+        );
+        for tool in tools {
+            assert!(
+                tool_names.contains(&tool.as_str().unwrap()),
+                "Tool {tool} missing from tools/list"
+            );
+        }
+    }
+
+    child.kill().await.ok();
+
+    Ok(())
+}
+
 #[test(tokio::test)]
 async fn test_http_transport() -> Result<()> {
     // Use a random available port to avoid conflicts
@@ -944,6 +1071,337 @@ async fn test_http_transport() -> Result<()> {
     Ok(())
 }
 
+#[test(tokio::test)]
+async fn test_streamable_http_transport_over_tls() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let component_dir = temp_dir.path().join("components");
+    let cert = rcgen::generate_simple_self_signed(vec!["localhost".into(), "127.0.0.1".into()])?;
+    let cert_path = temp_dir.path().join("cert.pem");
+    let key_path = temp_dir.path().join("key.pem");
+    std::fs::write(&cert_path, cert.cert.pem())?;
+    std::fs::write(&key_path, cert.signing_key.serialize_pem())?;
+
+    let port = find_open_port().await?;
+    let binary_path = std::env::current_dir()
+        .context("Failed to get current directory")?
+        .join("target/debug/wassette");
+
+    let mut child = tokio::process::Command::new(&binary_path)
+        .arg("serve")
+        .arg("--streamable-http")
+        .arg(format!("--bind-address=127.0.0.1:{port}"))
+        .arg(format!("--component-dir={}", component_dir.display()))
+        .arg("--tls-cert")
+        .arg(&cert_path)
+        .arg("--tls-key")
+        .arg(&key_path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .context("Failed to start wassette with TLS")?;
+
+    let client = reqwest::Client::builder()
+        .danger_accept_invalid_certs(true)
+        .build()?;
+    let url = format!("https://127.0.0.1:{port}/healthz");
+
+    let mut response = None;
+    for _ in 0..50 {
+        if let Ok(resp) = client.get(&url).send().await {
+            response = Some(resp);
+            break;
+        }
+        sleep(Duration::from_millis(200)).await;
+    }
+    let response = response.context("Server did not accept HTTPS connections")?;
+    assert!(response.status().is_success());
+
+    // Plaintext requests are not served on the TLS port
+    let plaintext = reqwest::Client::new()
+        .get(format!("http://127.0.0.1:{port}/healthz"))
+        .send()
+        .await;
+    if let Ok(resp) = plaintext {
+        assert!(!resp.status().is_success());
+    }
+
+    child.kill().await.ok();
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_admin_api_lists_and_loads_components() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let component_path = build_fetch_component().await?;
+
+    let port = find_open_port().await?;
+    let binary_path = std::env::current_dir()
+        .context("Failed to get current directory")?
+        .join("target/debug/wassette");
+
+    let mut child = tokio::process::Command::new(&binary_path)
+        .arg("serve")
+        .arg("--streamable-http")
+        .arg(format!("--bind-address=127.0.0.1:{port}"))
+        .arg(format!("--component-dir={}", temp_dir.path().display()))
+        .env("WASSETTE_ADMIN_TOKEN", "admin-secret")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .context("Failed to start wassette with the admin API")?;
+
+    let client = reqwest::Client::new();
+    let components_url = format!("http://127.0.0.1:{port}/admin/components");
+
+    let mut unauthorized = None;
+    for _ in 0..50 {
+        if let Ok(resp) = client.get(&components_url).send().await {
+            unauthorized = Some(resp);
+            break;
+        }
+        sleep(Duration::from_millis(200)).await;
+    }
+    let unauthorized = unauthorized.context("Server did not accept HTTP connections")?;
+    assert_eq!(unauthorized.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let wrong_token = client
+        .get(&components_url)
+        .bearer_auth("not-the-token")
+        .send()
+        .await?;
+    assert_eq!(wrong_token.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let listed: serde_json::Value = client
+        .get(&components_url)
+        .bearer_auth("admin-secret")
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    assert_eq!(listed["total"], 0);
+
+    let loaded: serde_json::Value = client
+        .post(&components_url)
+        .bearer_auth("admin-secret")
+        .json(&serde_json::json!({ "uri": format!("file://{}", component_path.display()) }))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    let id = loaded["id"].as_str().context("load response has no id")?;
+    assert!(!loaded["tools"].as_array().unwrap().is_empty());
+
+    let listed: serde_json::Value = client
+        .get(&components_url)
+        .bearer_auth("admin-secret")
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    assert_eq!(listed["total"], 1);
+    assert_eq!(listed["components"][0]["id"], id);
+
+    let policy: serde_json::Value = client
+        .get(format!("{components_url}/{id}/policy"))
+        .bearer_auth("admin-secret")
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    assert_eq!(policy["component_id"], id);
+
+    let missing = client
+        .get(format!("{components_url}/does-not-exist/policy"))
+        .bearer_auth("admin-secret")
+        .send()
+        .await?;
+    assert_eq!(missing.status(), reqwest::StatusCode::NOT_FOUND);
+
+    let bad_load = client
+        .post(&components_url)
+        .bearer_auth("admin-secret")
+        .json(&serde_json::json!({ "uri": "file:///does/not/exist.wasm" }))
+        .send()
+        .await?;
+    assert_eq!(bad_load.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    child.kill().await.ok();
+
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_streamable_http_rejects_sessions_over_the_limit() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let port = find_open_port().await?;
+    let binary_path = std::env::current_dir()
+        .context("Failed to get current directory")?
+        .join("target/debug/wassette");
+
+    let mut child = tokio::process::Command::new(&binary_path)
+        .arg("serve")
+        .arg("--streamable-http")
+        .arg(format!("--bind-address=127.0.0.1:{port}"))
+        .arg(format!("--component-dir={}", temp_dir.path().display()))
+        .arg("--max-sessions=2")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .context("Failed to start wassette with a session limit")?;
+
+    let client = reqwest::Client::new();
+    let health_url = format!("http://127.0.0.1:{port}/health");
+    let mut ready = false;
+    for _ in 0..50 {
+        if client.get(&health_url).send().await.is_ok() {
+            ready = true;
+            break;
+        }
+        sleep(Duration::from_millis(200)).await;
+    }
+    assert!(ready, "Server did not accept HTTP connections");
+
+    let mcp_url = format!("http://127.0.0.1:{port}/mcp");
+    let initialize = || {
+        client
+            .post(&mcp_url)
+            .header("Accept", "application/json, text/event-stream")
+            .json(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": {
+                    "protocolVersion": "2024-11-05",
+                    "capabilities": {},
+                    "clientInfo": { "name": "test-client", "version": "1.0.0" }
+                }
+            }))
+            .send()
+    };
+
+    let mut session_ids = Vec::new();
+    for _ in 0..2 {
+        let response = initialize().await?.error_for_status()?;
+        let session_id = response
+            .headers()
+            .get("mcp-session-id")
+            .context("initialize response has no session id")?
+            .to_str()?
+            .to_string();
+        session_ids.push(session_id);
+    }
+
+    let rejected = initialize().await?;
+    assert_eq!(rejected.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+    assert!(rejected.headers().contains_key("retry-after"));
+
+    // Closing a session frees its slot
+    client
+        .delete(&mcp_url)
+        .header("mcp-session-id", &session_ids[0])
+        .send()
+        .await?
+        .error_for_status()?;
+    let accepted = initialize().await?;
+    assert!(
+        accepted.status().is_success(),
+        "expected a free slot after closing a session, got {}",
+        accepted.status()
+    );
+
+    child.kill().await.ok();
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test(tokio::test)]
+async fn test_sighup_loads_components_added_to_the_directory() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let component_path = build_fetch_component().await?;
+
+    let port = find_open_port().await?;
+    let binary_path = std::env::current_dir()
+        .context("Failed to get current directory")?
+        .join("target/debug/wassette");
+
+    let mut child = tokio::process::Command::new(&binary_path)
+        .arg("serve")
+        .arg("--streamable-http")
+        .arg("--reload-on-sighup")
+        .arg(format!("--bind-address=127.0.0.1:{port}"))
+        .arg(format!("--component-dir={}", temp_dir.path().display()))
+        .env("WASSETTE_ADMIN_TOKEN", "admin-secret")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .context("Failed to start wassette with --reload-on-sighup")?;
+
+    let client = reqwest::Client::new();
+    let components_url = format!("http://127.0.0.1:{port}/admin/components");
+    let list_components = || async {
+        let listed: serde_json::Value = client
+            .get(&components_url)
+            .bearer_auth("admin-secret")
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        anyhow::Ok(listed)
+    };
+
+    let mut listed = None;
+    for _ in 0..50 {
+        if let Ok(result) = list_components().await {
+            listed = Some(result);
+            break;
+        }
+        sleep(Duration::from_millis(200)).await;
+    }
+    let listed = listed.context("Server did not accept HTTP connections")?;
+    assert_eq!(listed["total"], 0);
+
+    std::fs::copy(&component_path, temp_dir.path().join("fetch.wasm"))?;
+    let pid = child.id().context("wassette exited early")?;
+    let status = std::process::Command::new("kill")
+        .args(["-HUP", &pid.to_string()])
+        .status()?;
+    assert!(status.success(), "Failed to send SIGHUP");
+
+    let mut loaded = false;
+    for _ in 0..50 {
+        let listed = list_components().await?;
+        if listed["total"] == 1 {
+            assert_eq!(listed["components"][0]["id"], "fetch");
+            loaded = true;
+            break;
+        }
+        sleep(Duration::from_millis(200)).await;
+    }
+    assert!(
+        loaded,
+        "Component added to the directory was not loaded after SIGHUP"
+    );
+
+    child.kill().await.ok();
+
+    Ok(())
+}
+
 #[test(tokio::test)]
 async fn test_default_stdio_transport() -> Result<()> {
     // Create a temporary directory for this test to avoid loading existing components
@@ -1013,6 +1471,71 @@ async fn test_default_stdio_transport() -> Result<()> {
     Ok(())
 }
 
+#[test(tokio::test)]
+async fn test_stdio_protocol_log_records_initialize() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let component_dir_arg = format!("--component-dir={}", temp_dir.path().display());
+    let log_path = temp_dir.path().join("wire.log");
+
+    let binary_path = std::env::current_dir()
+        .context("Failed to get current directory")?
+        .join("target/debug/wassette");
+
+    let mut child = tokio::process::Command::new(&binary_path)
+        .args(["run", &component_dir_arg, "--protocol-log"])
+        .arg(&log_path)
+        .env("RUST_LOG", "off")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to start wassette with --protocol-log")?;
+
+    let mut stdin = child.stdin.take().context("Failed to get stdin handle")?;
+    let mut stdout = BufReader::new(child.stdout.take().context("Failed to get stdout handle")?);
+
+    tokio::time::sleep(Duration::from_millis(1000)).await;
+
+    let initialize_request = r#"{"jsonrpc": "2.0", "method": "initialize", "params": {"protocolVersion": "2024-11-05", "capabilities": {}, "clientInfo": {"name": "test-client", "version": "1.0.0"}}, "id": 1}
+"#;
+    stdin.write_all(initialize_request.as_bytes()).await?;
+    stdin.flush().await?;
+
+    let mut response_line = String::new();
+    tokio::time::timeout(
+        Duration::from_secs(10),
+        stdout.read_line(&mut response_line),
+    )
+    .await
+    .context("Timeout waiting for initialize response")?
+    .context("Failed to read initialize response")?;
+
+    child.kill().await.ok();
+
+    let entries: Vec<serde_json::Value> = std::fs::read_to_string(&log_path)
+        .context("Protocol log was not written")?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()
+        .context("Protocol log contains invalid JSON lines")?;
+
+    let request = entries
+        .iter()
+        .find(|entry| entry["direction"] == "inbound")
+        .context("No inbound entry in protocol log")?;
+    assert_eq!(request["message"]["method"], "initialize");
+    assert!(request["timestamp_ms"].is_u64());
+
+    let response = entries
+        .iter()
+        .find(|entry| entry["direction"] == "outbound")
+        .context("No outbound entry in protocol log")?;
+    assert_eq!(response["message"]["id"], 1);
+    assert!(response["message"]["result"].is_object());
+
+    Ok(())
+}
+
 #[cfg(any(target_os = "linux", target_os = "macos"))]
 #[test(tokio::test)]
 async fn test_grant_permission_network_basic() -> Result<()> {